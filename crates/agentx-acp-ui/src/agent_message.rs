@@ -1,24 +1,56 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Range;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use agent_client_protocol::{ContentBlock, ContentChunk, SessionId};
 use gpui::{
-    App, AppContext, Context, ElementId, Entity, IntoElement, ParentElement, Render, RenderOnce,
-    SharedString, Styled, Window, div, prelude::FluentBuilder as _, px,
+    AnyElement, App, AppContext, ClipboardItem, Context, ElementId, Entity, InteractiveElement,
+    IntoElement, ParentElement, Render, RenderOnce, SharedString, Styled, Window, div,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    text::TextView,
+    v_flex,
 };
-use gpui_component::{ActiveTheme, Icon, IconName, h_flex, text::TextView, v_flex};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// How long the copy button shows "Copied" after a click
+const COPIED_RESET_DELAY: Duration = Duration::from_secs(2);
+
+/// `_meta` key a turn's token usage and cost are reported under, on agent
+/// message chunks or the prompt response, shaped like ACP usage:
+/// `{"inputTokens": 1200, "outputTokens": 350, "cost": {"amount": 0.02, "currency": "USD"}}`
+pub const USAGE_META_KEY: &str = "usage";
 
 pub type AgentIconProvider = Arc<dyn Fn(&str) -> Icon + Send + Sync>;
 
+/// Formats a message's arrival time for display, e.g. as "5 minutes ago".
+/// Supplied by the host app so it can localize the text.
+pub type TimestampFormatter = Arc<dyn Fn(SystemTime) -> String + Send + Sync>;
+
 #[derive(Clone)]
 pub struct AgentMessageOptions {
     pub icon_provider: AgentIconProvider,
+    /// Show when the message arrived, if it carries a timestamp
+    pub show_timestamp: bool,
+    /// How to display the arrival time; without one no time is shown
+    pub timestamp_formatter: Option<TimestampFormatter>,
+    /// Show token usage and cost, if the agent reported them
+    pub show_usage: bool,
 }
 
 impl Default for AgentMessageOptions {
     fn default() -> Self {
         Self {
             icon_provider: Arc::new(|_| Icon::new(IconName::Bot)),
+            show_timestamp: true,
+            timestamp_formatter: None,
+            show_usage: true,
         }
     }
 }
@@ -33,6 +65,84 @@ pub struct AgentMessageMeta {
     /// Whether the message is complete
     #[serde(default)]
     pub is_complete: bool,
+    /// When the message arrived (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<SystemTime>,
+    /// Prompt tokens consumed by this turn (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u64>,
+    /// Completion tokens produced by this turn (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u64>,
+    /// Estimated cost of this turn in US dollars (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl AgentMessageMeta {
+    /// Take the token usage and cost reported in a `_meta` object under
+    /// [`USAGE_META_KEY`]. Fields it leaves out keep their value; a cost in
+    /// another currency than US dollars is ignored.
+    pub fn apply_usage_meta(&mut self, meta: &Map<String, Value>) {
+        let Some(usage) = meta.get(USAGE_META_KEY).and_then(Value::as_object) else {
+            return;
+        };
+        let tokens = |keys: [&str; 2]| keys.into_iter().find_map(|key| usage.get(key)?.as_u64());
+
+        if let Some(tokens) = tokens(["inputTokens", "input_tokens"]) {
+            self.input_tokens = Some(tokens);
+        }
+        if let Some(tokens) = tokens(["outputTokens", "output_tokens"]) {
+            self.output_tokens = Some(tokens);
+        }
+        let cost_usd = match usage.get("cost") {
+            Some(Value::Object(cost)) => cost
+                .get("currency")
+                .and_then(Value::as_str)
+                .is_none_or(|currency| currency.eq_ignore_ascii_case("USD"))
+                .then(|| cost.get("amount").and_then(Value::as_f64))
+                .flatten(),
+            Some(cost) => cost.as_f64(),
+            None => None,
+        };
+        if let Some(cost_usd) = cost_usd {
+            self.cost_usd = Some(cost_usd);
+        }
+    }
+
+    /// Footer text summarizing token usage and cost, e.g.
+    /// "1,200 in · 350 out · $0.02". `None` when nothing was reported.
+    pub fn usage_summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(tokens) = self.input_tokens {
+            parts.push(format!("{} in", format_count(tokens)));
+        }
+        if let Some(tokens) = self.output_tokens {
+            parts.push(format!("{} out", format_count(tokens)));
+        }
+        if let Some(cost) = self.cost_usd {
+            parts.push(format!("${:.2}", cost));
+        }
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" · "))
+        }
+    }
+}
+
+/// Format a count with thousands separators
+fn format_count(value: u64) -> String {
+    let digits = value.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, ch) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
 }
 
 /// Agent message data structure based on ACP's ContentChunk
@@ -63,15 +173,34 @@ impl AgentMessageData {
         self
     }
 
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.meta.timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn with_usage(
+        mut self,
+        input_tokens: Option<u64>,
+        output_tokens: Option<u64>,
+        cost_usd: Option<f64>,
+    ) -> Self {
+        self.meta.input_tokens = input_tokens;
+        self.meta.output_tokens = output_tokens;
+        self.meta.cost_usd = cost_usd;
+        self
+    }
+
     pub fn with_chunks(mut self, chunks: Vec<ContentChunk>) -> Self {
+        for chunk in &chunks {
+            self.apply_chunk_meta(chunk);
+        }
         self.chunks = chunks;
         self.update_cache();
         self
     }
 
     pub fn add_chunk(mut self, chunk: ContentChunk) -> Self {
-        self.chunks.push(chunk);
-        self.update_cache();
+        self.push_chunk(chunk);
         self
     }
 
@@ -85,10 +214,18 @@ impl AgentMessageData {
 
     /// Append a chunk in place and update cache
     pub fn push_chunk(&mut self, chunk: ContentChunk) {
+        self.apply_chunk_meta(&chunk);
         self.chunks.push(chunk);
         self.update_cache();
     }
 
+    /// Pick up the usage an agent reported in a chunk's `_meta`
+    fn apply_chunk_meta(&mut self, chunk: &ContentChunk) {
+        if let Some(meta) = &chunk.meta {
+            self.meta.apply_usage_meta(meta);
+        }
+    }
+
     /// Append text in place and update cache
     pub fn push_text(&mut self, text: &str) {
         match self.chunks.last_mut().map(|chunk| &mut chunk.content) {
@@ -142,6 +279,63 @@ impl AgentMessageData {
     pub fn agent_name(&self) -> Option<&str> {
         self.meta.agent_name.as_deref()
     }
+
+    /// Get the arrival time
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        self.meta.timestamp
+    }
+}
+
+/// A top-level code block found in a markdown string
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// Language tag from the info string (e.g. `rust` in "```rust")
+    pub language: Option<String>,
+    /// Raw code between the fences
+    pub code: String,
+    /// Byte range of the whole block, fences included
+    pub range: Range<usize>,
+}
+
+/// Find the top-level code blocks in `markdown`, using the markdown parser so
+/// fences nested in lists or quotes stay with the block around them.
+///
+/// A block left open at the end of the text (e.g. while streaming) runs to
+/// the end of the text.
+pub fn extract_code_blocks(markdown: &str) -> Vec<CodeBlock> {
+    use markdown::mdast::Node;
+
+    let Ok(root) = markdown::to_mdast(markdown, &markdown::ParseOptions::gfm()) else {
+        return Vec::new();
+    };
+    let Some(children) = root.children() else {
+        return Vec::new();
+    };
+
+    children
+        .iter()
+        .filter_map(|node| match node {
+            Node::Code(code) => {
+                let position = code.position.as_ref()?;
+                Some(CodeBlock {
+                    language: code.lang.clone(),
+                    code: code.value.clone(),
+                    range: position.start.offset..position.end.offset,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Element key for the markdown block starting at `start`: its offset plus a
+/// hash of its first line, so a block keeps its state while it streams in but
+/// not when a different block ends up at the same offset
+fn block_key(markdown: &str, start: usize) -> String {
+    let first_line = markdown[start..].lines().next().unwrap_or_default();
+    let mut hasher = DefaultHasher::new();
+    first_line.hash(&mut hasher);
+    format!("{}-{:x}", start, hasher.finish())
 }
 
 /// Agent message component
@@ -173,13 +367,177 @@ impl AgentMessage {
         self.options.icon_provider = icon_provider;
         self
     }
+
+    pub fn show_timestamp(mut self, show: bool) -> Self {
+        self.options.show_timestamp = show;
+        self
+    }
+
+    pub fn timestamp_formatter(mut self, formatter: Option<TimestampFormatter>) -> Self {
+        self.options.timestamp_formatter = formatter;
+        self
+    }
+
+    pub fn show_usage(mut self, show: bool) -> Self {
+        self.options.show_usage = show;
+        self
+    }
+}
+
+/// Muted footer line for a message: relative time and/or usage summary
+fn footer_text(data: &AgentMessageData, options: &AgentMessageOptions) -> Option<String> {
+    let timestamp = data
+        .timestamp()
+        .filter(|_| options.show_timestamp)
+        .zip(options.timestamp_formatter.as_ref())
+        .map(|(time, format)| format(time));
+    let usage = data.meta.usage_summary().filter(|_| options.show_usage);
+
+    match (timestamp, usage) {
+        (Some(timestamp), Some(usage)) => Some(format!("{} · {}", timestamp, usage)),
+        (timestamp, usage) => timestamp.or(usage),
+    }
+}
+
+fn render_markdown(
+    id: SharedString,
+    text: SharedString,
+    window: &mut Window,
+    cx: &mut App,
+) -> AnyElement {
+    TextView::markdown(id, text, window, cx)
+        .text_sm()
+        .text_color(cx.theme().foreground)
+        .selectable(true)
+        .pr_3()
+        .into_any_element()
+}
+
+/// Render the message markdown, splitting out top-level code blocks so each
+/// one gets its own copy button
+fn render_markdown_segments(
+    id: &ElementId,
+    full_text: SharedString,
+    window: &mut Window,
+    cx: &mut App,
+) -> Vec<AnyElement> {
+    let blocks = extract_code_blocks(&full_text);
+    if blocks.is_empty() {
+        let markdown_id = SharedString::from(format!("{}-markdown", id));
+        return vec![render_markdown(markdown_id, full_text, window, cx)];
+    }
+
+    let mut segments = Vec::new();
+    let mut cursor = 0;
+    for block in blocks {
+        let prose = &full_text[cursor..block.range.start];
+        if !prose.trim().is_empty() {
+            let markdown_id =
+                SharedString::from(format!("{}-markdown-{}", id, block_key(&full_text, cursor)));
+            segments.push(render_markdown(
+                markdown_id,
+                prose.to_string().into(),
+                window,
+                cx,
+            ));
+        }
+
+        let block_id = SharedString::from(format!(
+            "{}-code-{}",
+            id,
+            block_key(&full_text, block.range.start)
+        ));
+        let source = full_text[block.range.clone()].to_string();
+        cursor = block.range.end;
+        segments.push(render_code_block(block_id, source, block.code, window, cx));
+    }
+
+    let tail = &full_text[cursor..];
+    if !tail.trim().is_empty() {
+        let markdown_id =
+            SharedString::from(format!("{}-markdown-{}", id, block_key(&full_text, cursor)));
+        segments.push(render_markdown(
+            markdown_id,
+            tail.to_string().into(),
+            window,
+            cx,
+        ));
+    }
+
+    segments
+}
+
+/// A code block with a copy button that appears on hover
+fn render_code_block(
+    id: SharedString,
+    source: String,
+    code: String,
+    window: &mut Window,
+    cx: &mut App,
+) -> AnyElement {
+    let copied =
+        window.use_keyed_state(SharedString::from(format!("{}-copied", id)), cx, |_, _| {
+            false
+        });
+    let is_copied = *copied.read(cx);
+
+    div()
+        .relative()
+        .w_full()
+        .group(id.clone())
+        .child(render_markdown(
+            SharedString::from(format!("{}-markdown", id)),
+            source.into(),
+            window,
+            cx,
+        ))
+        .child(
+            div()
+                .absolute()
+                .top_1()
+                .right_4()
+                .when(!is_copied, |this| {
+                    this.opacity(0.)
+                        .group_hover(id.clone(), |style| style.opacity(1.))
+                })
+                .child(
+                    Button::new(SharedString::from(format!("{}-copy", id)))
+                        .map(|button| {
+                            if is_copied {
+                                button.icon(IconName::Check).label("Copied")
+                            } else {
+                                button.icon(IconName::Copy)
+                            }
+                        })
+                        .ghost()
+                        .xsmall()
+                        .on_click(move |_, _window, cx| {
+                            cx.write_to_clipboard(ClipboardItem::new_string(code.clone()));
+                            copied.update(cx, |copied, cx| {
+                                *copied = true;
+                                cx.notify();
+                            });
+
+                            let copied = copied.clone();
+                            cx.spawn(async move |cx| {
+                                cx.background_executor().timer(COPIED_RESET_DELAY).await;
+                                let _ = copied.update(cx, |copied, cx| {
+                                    *copied = false;
+                                    cx.notify();
+                                });
+                            })
+                            .detach();
+                        }),
+                ),
+        )
+        .into_any_element()
 }
 
 impl RenderOnce for AgentMessage {
     fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
         let agent_name = self.data.agent_name().unwrap_or("Agent");
         let full_text = self.data.full_text();
-        let markdown_id = SharedString::from(format!("{}-markdown", self.id));
+        let footer = footer_text(&self.data, &self.options);
 
         // Get icon based on agent name
         let icon = (self.options.icon_provider)(agent_name);
@@ -195,18 +553,21 @@ impl RenderOnce for AgentMessage {
                     .child(icon.size(px(16.)).mt_1().text_color(cx.theme().foreground))
                     // Message content with markdown rendering
                     .child(
-                        div()
+                        v_flex()
                             .w_full()
-                            .child(
-                                TextView::markdown(markdown_id, full_text, window, cx)
-                                    .text_sm()
-                                    .text_color(cx.theme().foreground)
-                                    .selectable(true)
-                                    .pr_3(),
-                            )
+                            .children(render_markdown_segments(&self.id, full_text, window, cx))
                             .pr_3(),
                     ),
             )
+            .when_some(footer, |this, footer| {
+                this.child(
+                    div()
+                        .pl_6()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(footer),
+                )
+            })
     }
 }
 
@@ -289,6 +650,45 @@ impl AgentMessageView {
         self.data.read(cx).is_complete()
     }
 
+    /// Set the arrival time
+    pub fn set_timestamp(&mut self, timestamp: SystemTime, cx: &mut Context<Self>) {
+        self.update_message(cx, |d| d.meta.timestamp = Some(timestamp));
+    }
+
+    pub fn set_show_timestamp(&mut self, show: bool, cx: &mut Context<Self>) {
+        self.options.show_timestamp = show;
+        cx.notify();
+    }
+
+    pub fn set_timestamp_formatter(
+        &mut self,
+        formatter: Option<TimestampFormatter>,
+        cx: &mut Context<Self>,
+    ) {
+        self.options.timestamp_formatter = formatter;
+        cx.notify();
+    }
+
+    /// Record token usage and cost reported for this turn
+    pub fn set_usage(
+        &mut self,
+        input_tokens: Option<u64>,
+        output_tokens: Option<u64>,
+        cost_usd: Option<f64>,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_message(cx, |d| {
+            d.meta.input_tokens = input_tokens;
+            d.meta.output_tokens = output_tokens;
+            d.meta.cost_usd = cost_usd;
+        });
+    }
+
+    pub fn set_show_usage(&mut self, show: bool, cx: &mut Context<Self>) {
+        self.options.show_usage = show;
+        cx.notify();
+    }
+
     pub fn set_icon_provider(&mut self, provider: AgentIconProvider, cx: &mut Context<Self>) {
         self.options.icon_provider = provider;
         cx.notify();
@@ -321,4 +721,178 @@ mod tests {
         data.push_chunk(ContentChunk::new(ContentBlock::from("Text")));
         assert_eq!(data.full_text().as_ref(), "Text");
     }
+
+    #[test]
+    fn agent_message_meta_round_trips_timestamp() {
+        let legacy: AgentMessageMeta =
+            serde_json::from_str(r#"{"agentName":"Claude","isComplete":true}"#).unwrap();
+        assert_eq!(legacy.timestamp, None);
+        let json = serde_json::to_value(&legacy).unwrap();
+        assert!(json.get("timestamp").is_none());
+
+        let timestamp = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        let data = AgentMessageData::new("session-1").with_timestamp(timestamp);
+        let json = serde_json::to_string(&data.meta).unwrap();
+        let meta: AgentMessageMeta = serde_json::from_str(&json).unwrap();
+        assert_eq!(meta.timestamp, Some(timestamp));
+    }
+
+    #[test]
+    fn usage_footer_only_when_reported() {
+        let options = AgentMessageOptions {
+            show_timestamp: false,
+            ..Default::default()
+        };
+
+        let data = AgentMessageData::new("session-1").add_text("Hi");
+        assert_eq!(footer_text(&data, &options), None);
+
+        let data = data.with_usage(Some(12_345), Some(678), Some(0.0349));
+        assert_eq!(
+            footer_text(&data, &options).as_deref(),
+            Some("12,345 in · 678 out · $0.03")
+        );
+
+        let data = AgentMessageData::new("session-1").with_usage(None, None, Some(1.5));
+        assert_eq!(data.meta.usage_summary().as_deref(), Some("$1.50"));
+
+        let hidden = AgentMessageOptions {
+            show_usage: false,
+            ..options
+        };
+        assert_eq!(footer_text(&data, &hidden), None);
+    }
+
+    #[test]
+    fn timestamp_footer_uses_the_host_formatter() {
+        let timestamp = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        let data = AgentMessageData::new("session-1").with_timestamp(timestamp);
+        assert_eq!(footer_text(&data, &AgentMessageOptions::default()), None);
+
+        let options = AgentMessageOptions {
+            timestamp_formatter: Some(Arc::new(|_| "5 minutes ago".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            footer_text(&data, &options).as_deref(),
+            Some("5 minutes ago")
+        );
+
+        let data = data.with_usage(Some(10), Some(2), None);
+        assert_eq!(
+            footer_text(&data, &options).as_deref(),
+            Some("5 minutes ago · 10 in · 2 out")
+        );
+    }
+
+    fn chunk_with_meta(text: &str, meta: Value) -> ContentChunk {
+        let mut chunk = ContentChunk::new(ContentBlock::from(text));
+        chunk.meta = meta.as_object().cloned();
+        chunk
+    }
+
+    #[test]
+    fn usage_reported_in_chunk_meta_reaches_the_footer() {
+        let options = AgentMessageOptions {
+            show_timestamp: false,
+            ..Default::default()
+        };
+
+        // Tokens arrive on a streamed chunk, the cost with the prompt response
+        let mut data = AgentMessageData::new("session-1").add_chunk(chunk_with_meta(
+            "Done.",
+            serde_json::json!({ "usage": { "inputTokens": 1200, "outputTokens": 350 } }),
+        ));
+        assert_eq!(
+            footer_text(&data, &options).as_deref(),
+            Some("1,200 in · 350 out")
+        );
+
+        data.push_chunk(chunk_with_meta(
+            "",
+            serde_json::json!({
+                "usage": { "cost": { "amount": 0.0213, "currency": "USD" } }
+            }),
+        ));
+        assert_eq!(data.full_text().as_ref(), "Done.");
+        assert_eq!(
+            footer_text(&data, &options).as_deref(),
+            Some("1,200 in · 350 out · $0.02")
+        );
+
+        // Other currencies can't be shown as dollars
+        let data = AgentMessageData::new("session-1").with_chunks(vec![chunk_with_meta(
+            "Hi",
+            serde_json::json!({
+                "usage": { "output_tokens": 5, "cost": { "amount": 3, "currency": "EUR" } }
+            }),
+        )]);
+        assert_eq!(footer_text(&data, &options).as_deref(), Some("5 out"));
+    }
+
+    #[test]
+    fn extract_code_blocks_finds_fences_and_languages() {
+        let markdown = "Intro\n\
+\n\
+```rust\n\
+fn main() {}\n\
+```\n\
+\n\
+Between\n\
+\n\
+~~~\n\
+plain\n\
+~~~\n\
+\n\
+````python extra\n\
+print(\"```\")\n\
+````\n\
+\n\
+```sh\n\
+echo open";
+
+        let blocks = extract_code_blocks(markdown);
+        assert_eq!(blocks.len(), 4);
+
+        assert_eq!(blocks[0].language.as_deref(), Some("rust"));
+        assert_eq!(blocks[0].code, "fn main() {}");
+        assert_eq!(
+            &markdown[blocks[0].range.clone()],
+            "```rust\nfn main() {}\n```"
+        );
+
+        assert_eq!(blocks[1].language, None);
+        assert_eq!(blocks[1].code, "plain");
+
+        assert_eq!(blocks[2].language.as_deref(), Some("python"));
+        assert_eq!(blocks[2].code, "print(\"```\")");
+
+        // Unterminated fence runs to the end
+        assert_eq!(blocks[3].language.as_deref(), Some("sh"));
+        assert_eq!(blocks[3].code, "echo open");
+        assert_eq!(blocks[3].range.end, markdown.len());
+    }
+
+    #[test]
+    fn extract_code_blocks_leaves_nested_fences_in_their_block() {
+        let markdown =
+            "1. Run this:\n\n   ```sh\n   cargo build\n   ```\n\n> ```\n> quoted\n> ```\n";
+        assert!(extract_code_blocks(markdown).is_empty());
+    }
+
+    #[test]
+    fn block_key_is_stable_while_the_block_streams() {
+        let partial = "Intro\n\n```rust\nfn main";
+        let complete = "Intro\n\n```rust\nfn main() {}\n```\n";
+        assert_eq!(block_key(partial, 7), block_key(complete, 7));
+        assert_ne!(
+            block_key(complete, 7),
+            block_key("Intro\n\n```sh\necho\n```", 7)
+        );
+    }
+
+    #[test]
+    fn extract_code_blocks_ignores_inline_code() {
+        assert!(extract_code_blocks("Use `cargo build` or ``a``").is_empty());
+    }
 }
@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 use gpui::{Context, IntoElement, ParentElement, Render, Styled, Window, div, prelude::*, px};
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
@@ -6,31 +8,68 @@ use gpui_component::{
     h_flex, v_flex,
 };
 
-/// Agent thought item for streaming "thinking" output.
+/// Agent thought item for streaming "thinking" output, collapsed by default.
 pub struct AgentThoughtItem {
     text: String,
     open: bool,
+    started_at: Instant,
+    updated_at: Instant,
 }
 
 impl AgentThoughtItem {
     pub fn new(text: impl Into<String>) -> Self {
+        let now = Instant::now();
         Self {
             text: text.into(),
             open: false,
+            started_at: now,
+            updated_at: now,
         }
     }
 
     /// Append more text to the thought (for streaming updates)
     pub fn append_text(&mut self, text: impl Into<String>, cx: &mut Context<Self>) {
         self.text.push_str(&text.into());
+        self.updated_at = Instant::now();
         cx.notify();
     }
 
+    /// Whether the full thought text is shown
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Time between the first and the latest chunk of the thought
+    pub fn duration(&self) -> Duration {
+        self.updated_at.duration_since(self.started_at)
+    }
+
+    /// The thought text accumulated so far
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
     /// Toggle open/close state
     pub fn toggle(&mut self, cx: &mut Context<Self>) {
-        self.open = !self.open;
+        self.toggle_open();
         cx.notify();
     }
+
+    fn toggle_open(&mut self) {
+        self.open = !self.open;
+    }
+}
+
+/// Summary shown next to the "Thinking" label, e.g. `~120 tokens · 4s`.
+/// Tokens are estimated at four characters each.
+pub fn thought_summary(text: &str, duration: Duration) -> String {
+    let tokens = text.chars().count().div_ceil(4);
+    let seconds = duration.as_secs();
+    if seconds == 0 {
+        format!("~{} tokens", tokens)
+    } else {
+        format!("~{} tokens · {}s", tokens, seconds)
+    }
 }
 
 impl Render for AgentThoughtItem {
@@ -58,10 +97,18 @@ impl Render for AgentThoughtItem {
                                 )
                                 .child(
                                     div()
-                                        .flex_1()
                                         .text_sm()
                                         .text_color(cx.theme().muted_foreground)
-                                        .child("Thinking..."),
+                                        .child("Thinking"),
+                                )
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground.opacity(0.7))
+                                        .when(has_content, |this| {
+                                            this.child(thought_summary(&self.text, self.duration()))
+                                        }),
                                 )
                                 .when(has_content, |this| {
                                     this.child(
@@ -95,3 +142,29 @@ impl Render for AgentThoughtItem {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thought_starts_collapsed_and_toggles() {
+        let mut thought = AgentThoughtItem::new("Let me look at the tests first.");
+        assert!(!thought.is_open());
+
+        thought.toggle_open();
+        assert!(thought.is_open());
+
+        thought.toggle_open();
+        assert!(!thought.is_open());
+    }
+
+    #[test]
+    fn summary_estimates_tokens_and_duration() {
+        assert_eq!(thought_summary("abcdefgh", Duration::ZERO), "~2 tokens");
+        assert_eq!(
+            thought_summary("abcdefghi", Duration::from_millis(4_200)),
+            "~3 tokens · 4s"
+        );
+    }
+}
@@ -1,6 +1,10 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
 use gpui::{
     App, AppContext, Context, ElementId, Entity, InteractiveElement, IntoElement, ParentElement,
-    Render, RenderOnce, SharedString, Styled, Window, div, px,
+    Render, RenderOnce, SharedString, StatefulInteractiveElement, Styled, Window, div,
+    prelude::FluentBuilder as _, px,
 };
 
 use agent_client_protocol::{Plan, PlanEntry, PlanEntryPriority, PlanEntryStatus};
@@ -16,20 +20,91 @@ pub struct PlanMeta {
     pub title: Option<String>,
 }
 
+/// Called with the full entry list after the user reorders entries or
+/// changes a status, so the app can inform the agent
+pub type TodoListChangeHandler = Arc<dyn Fn(Vec<PlanEntry>, &mut Window, &mut App) + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub struct AgentTodoListOptions {
+    /// Allow drag-to-reorder and clicking the status icon to change status
+    pub editable: bool,
+    pub on_change: Option<TodoListChangeHandler>,
+}
+
+/// Move the entry at `from` to position `to`, shifting the entries between
+pub fn reorder_entries(entries: &mut Vec<PlanEntry>, from: usize, to: usize) {
+    if from >= entries.len() || from == to {
+        return;
+    }
+    let entry = entries.remove(from);
+    let to = to.min(entries.len());
+    entries.insert(to, entry);
+}
+
+/// Status after the user clicks an entry's status icon: completed entries go
+/// back to pending, anything else is marked done
+pub fn toggle_status(status: &PlanEntryStatus) -> PlanEntryStatus {
+    match status {
+        PlanEntryStatus::Completed => PlanEntryStatus::Pending,
+        _ => PlanEntryStatus::Completed,
+    }
+}
+
+type EntryToggleHandler = Rc<dyn Fn(usize, &mut Window, &mut App)>;
+type EntryMoveHandler = Rc<dyn Fn(usize, usize, &mut Window, &mut App)>;
+
+/// Drag payload and preview for reordering plan entries
+#[derive(Clone)]
+struct DraggedPlanEntry {
+    index: usize,
+    content: SharedString,
+}
+
+impl Render for DraggedPlanEntry {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_2()
+            .py_1()
+            .rounded(cx.theme().radius)
+            .bg(cx.theme().secondary)
+            .border_1()
+            .border_color(cx.theme().border)
+            .text_size(px(14.))
+            .text_color(cx.theme().foreground)
+            .child(self.content.clone())
+    }
+}
+
 /// A list item component for displaying a plan entry
 #[derive(IntoElement)]
 struct PlanEntryItem {
     id: ElementId,
+    index: usize,
     entry: PlanEntry,
+    on_toggle: Option<EntryToggleHandler>,
+    on_move: Option<EntryMoveHandler>,
 }
 
 impl PlanEntryItem {
-    pub fn new(id: impl Into<ElementId>, entry: PlanEntry) -> Self {
+    pub fn new(id: impl Into<ElementId>, index: usize, entry: PlanEntry) -> Self {
         Self {
             id: id.into(),
+            index,
             entry,
+            on_toggle: None,
+            on_move: None,
         }
     }
+
+    fn on_toggle(mut self, handler: Option<EntryToggleHandler>) -> Self {
+        self.on_toggle = handler;
+        self
+    }
+
+    fn on_move(mut self, handler: Option<EntryMoveHandler>) -> Self {
+        self.on_move = handler;
+        self
+    }
 }
 
 impl RenderOnce for PlanEntryItem {
@@ -48,24 +123,49 @@ impl RenderOnce for PlanEntryItem {
             _ => (Icon::new(IconName::Dash), cx.theme().muted_foreground),
         };
 
-        div().id(self.id).child(
-            h_flex()
-                .items_start()
-                .gap_2()
-                .child(
-                    div()
-                        .mt(px(1.))
-                        .child(icon.text_color(icon_color).size(px(16.))),
-                )
-                .child(
-                    div()
-                        .flex_1()
-                        .text_size(px(14.))
-                        .text_color(text_color)
-                        .line_height(px(20.))
-                        .child(self.entry.content.clone()),
-                ),
-        )
+        let index = self.index;
+        let status_id = SharedString::from(format!("{}-status", self.id));
+        let drag_over_bg = cx.theme().accent.opacity(0.3);
+
+        div()
+            .id(self.id)
+            .rounded(cx.theme().radius)
+            .when_some(self.on_move, |this, on_move| {
+                let dragged = DraggedPlanEntry {
+                    index,
+                    content: self.entry.content.clone().into(),
+                };
+                this.cursor_grab()
+                    .on_drag(dragged, |dragged, _, _, cx| cx.new(|_| dragged.clone()))
+                    .drag_over::<DraggedPlanEntry>(move |style, _, _, _| style.bg(drag_over_bg))
+                    .on_drop(move |dragged: &DraggedPlanEntry, window, cx| {
+                        on_move(dragged.index, index, window, cx);
+                    })
+            })
+            .child(
+                h_flex()
+                    .items_start()
+                    .gap_2()
+                    .child(
+                        div()
+                            .id(status_id)
+                            .mt(px(1.))
+                            .child(icon.text_color(icon_color).size(px(16.)))
+                            .when_some(self.on_toggle, |this, on_toggle| {
+                                this.cursor_pointer().on_click(move |_, window, cx| {
+                                    on_toggle(index, window, cx);
+                                })
+                            }),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_size(px(14.))
+                            .text_color(text_color)
+                            .line_height(px(20.))
+                            .child(self.entry.content.clone()),
+                    ),
+            )
     }
 }
 
@@ -76,6 +176,8 @@ pub struct AgentTodoList {
     plan: Plan,
     /// Extended metadata (title, etc.) - extracted from plan.meta
     meta: PlanMeta,
+    on_toggle: Option<EntryToggleHandler>,
+    on_move: Option<EntryMoveHandler>,
 }
 
 impl AgentTodoList {
@@ -83,6 +185,8 @@ impl AgentTodoList {
         Self {
             plan: Plan::new(Vec::new()),
             meta: PlanMeta::default(),
+            on_toggle: None,
+            on_move: None,
         }
     }
 
@@ -97,7 +201,12 @@ impl AgentTodoList {
             })
             .unwrap_or_default();
 
-        Self { plan, meta }
+        Self {
+            plan,
+            meta,
+            on_toggle: None,
+            on_move: None,
+        }
     }
 
     /// Set the title of the todo list (stored in meta)
@@ -131,6 +240,21 @@ impl AgentTodoList {
         self
     }
 
+    /// Handle clicks on an entry's status icon
+    pub fn on_toggle(mut self, handler: impl Fn(usize, &mut Window, &mut App) + 'static) -> Self {
+        self.on_toggle = Some(Rc::new(handler));
+        self
+    }
+
+    /// Handle an entry dragged from one index onto another
+    pub fn on_move(
+        mut self,
+        handler: impl Fn(usize, usize, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_move = Some(Rc::new(handler));
+        self
+    }
+
     /// Get the underlying Plan
     pub fn into_plan(mut self) -> Plan {
         // Store meta back into plan
@@ -175,6 +299,8 @@ impl IntoElement for AgentTodoList {
         let title = self.display_title().to_string();
         let completed = self.completed_count();
         let total = self.total_count();
+        let on_toggle = self.on_toggle;
+        let on_move = self.on_move;
 
         v_flex()
             .gap_3()
@@ -209,7 +335,13 @@ impl IntoElement for AgentTodoList {
                     .gap_2()
                     .w_full()
                     .children(self.plan.entries.into_iter().enumerate().map(|(i, entry)| {
-                        PlanEntryItem::new(SharedString::from(format!("plan-entry-{}", i)), entry)
+                        PlanEntryItem::new(
+                            SharedString::from(format!("plan-entry-{}", i)),
+                            i,
+                            entry,
+                        )
+                        .on_toggle(on_toggle.clone())
+                        .on_move(on_move.clone())
                     })),
             )
     }
@@ -219,6 +351,7 @@ impl IntoElement for AgentTodoList {
 pub struct AgentTodoListView {
     plan: Entity<Plan>,
     meta: PlanMeta,
+    options: AgentTodoListOptions,
 }
 
 impl AgentTodoListView {
@@ -228,6 +361,7 @@ impl AgentTodoListView {
             Self {
                 plan,
                 meta: PlanMeta::default(),
+                options: AgentTodoListOptions::default(),
             }
         })
     }
@@ -248,10 +382,23 @@ impl AgentTodoListView {
             Self {
                 plan: plan_entity,
                 meta,
+                options: AgentTodoListOptions::default(),
             }
         })
     }
 
+    /// Create a new view with a Plan and editing options
+    pub fn with_options(
+        plan: Plan,
+        options: AgentTodoListOptions,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let view = Self::with_plan(plan, window, cx);
+        view.update(cx, |this, _| this.options = options);
+        view
+    }
+
     /// Create a new view with entries (convenience method)
     pub fn with_entries(
         entries: Vec<PlanEntry>,
@@ -263,6 +410,7 @@ impl AgentTodoListView {
             Self {
                 plan: plan_entity,
                 meta: PlanMeta::default(),
+                options: AgentTodoListOptions::default(),
             }
         })
     }
@@ -325,6 +473,49 @@ impl AgentTodoListView {
         self.meta.title = Some(title.into());
         cx.notify();
     }
+
+    /// Set the editing options
+    pub fn set_options(&mut self, options: AgentTodoListOptions, cx: &mut Context<Self>) {
+        self.options = options;
+        cx.notify();
+    }
+
+    /// Move an entry (user drag) and report the new order
+    pub fn move_entry(
+        &mut self,
+        from: usize,
+        to: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.edit_entries(window, cx, |entries| reorder_entries(entries, from, to));
+    }
+
+    /// Toggle an entry between done and pending (user click) and report it
+    pub fn toggle_entry(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.edit_entries(window, cx, |entries| {
+            if let Some(entry) = entries.get_mut(index) {
+                entry.status = toggle_status(&entry.status);
+            }
+        });
+    }
+
+    fn edit_entries(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        f: impl FnOnce(&mut Vec<PlanEntry>),
+    ) {
+        let entries = self.plan.update(cx, |p, cx| {
+            f(&mut p.entries);
+            cx.notify();
+            p.entries.clone()
+        });
+        if let Some(handler) = self.options.on_change.clone() {
+            handler(entries, window, cx);
+        }
+        cx.notify();
+    }
 }
 
 impl Render for AgentTodoListView {
@@ -336,6 +527,18 @@ impl Render for AgentTodoListView {
         if self.meta.title.is_some() {
             todo_list.meta = self.meta.clone();
         }
+
+        if self.options.editable {
+            let view = cx.entity().downgrade();
+            let toggle_view = view.clone();
+            todo_list = todo_list
+                .on_toggle(move |index, window, cx| {
+                    let _ = toggle_view.update(cx, |this, cx| this.toggle_entry(index, window, cx));
+                })
+                .on_move(move |from, to, window, cx| {
+                    let _ = view.update(cx, |this, cx| this.move_entry(from, to, window, cx));
+                });
+        }
         todo_list
     }
 }
@@ -381,4 +584,47 @@ mod tests {
         assert_eq!(list.completed_count(), 1);
         assert_eq!(list.total_count(), 2);
     }
+
+    fn entries() -> Vec<PlanEntry> {
+        vec![
+            PlanEntry::new("one", PlanEntryPriority::High, PlanEntryStatus::Completed),
+            PlanEntry::new(
+                "two",
+                PlanEntryPriority::Medium,
+                PlanEntryStatus::InProgress,
+            ),
+            PlanEntry::new("three", PlanEntryPriority::Low, PlanEntryStatus::Pending),
+        ]
+    }
+
+    #[test]
+    fn reorder_moves_entry_to_front() {
+        let mut entries = entries();
+        reorder_entries(&mut entries, 2, 0);
+        let contents: Vec<_> = entries.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, ["three", "one", "two"]);
+
+        // Out-of-range moves are clamped or ignored
+        reorder_entries(&mut entries, 0, 10);
+        let contents: Vec<_> = entries.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, ["one", "two", "three"]);
+        reorder_entries(&mut entries, 5, 0);
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn toggle_status_preserves_other_fields() {
+        let mut entry = PlanEntry::new("three", PlanEntryPriority::Low, PlanEntryStatus::Pending);
+        entry.status = toggle_status(&entry.status);
+        assert_eq!(entry.status, PlanEntryStatus::Completed);
+        assert_eq!(entry.content, "three");
+        assert_eq!(entry.priority, PlanEntryPriority::Low);
+
+        entry.status = toggle_status(&entry.status);
+        assert_eq!(entry.status, PlanEntryStatus::Pending);
+        assert_eq!(
+            toggle_status(&PlanEntryStatus::InProgress),
+            PlanEntryStatus::Completed
+        );
+    }
 }
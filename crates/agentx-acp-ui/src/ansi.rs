@@ -0,0 +1,300 @@
+//! Minimal ANSI escape sequence parsing for terminal output.
+//!
+//! SGR (`ESC [ ... m`) sequences are turned into styled spans; every other
+//! escape sequence (cursor movement, erase, OSC titles, ...) is stripped.
+
+/// Color set by an SGR sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiColor {
+    /// One of the 16 standard colors (0-7 normal, 8-15 bright)
+    Named(u8),
+    /// 256-color palette index (`38;5;n`)
+    Indexed(u8),
+    /// 24-bit color (`38;2;r;g;b`)
+    Rgb(u8, u8, u8),
+}
+
+impl AnsiColor {
+    /// Resolve to a `0xRRGGBB` value using the xterm palette
+    pub fn to_rgb(&self) -> u32 {
+        const NAMED: [u32; 16] = [
+            0x000000, 0xcd3131, 0x0dbc79, 0xe5e510, 0x2472c8, 0xbc3fbc, 0x11a8cd, 0xe5e5e5,
+            0x666666, 0xf14c4c, 0x23d18b, 0xf5f543, 0x3b8eea, 0xd670d6, 0x29b8db, 0xffffff,
+        ];
+        match *self {
+            AnsiColor::Named(n) => NAMED[(n & 0x0f) as usize],
+            AnsiColor::Indexed(n) if n < 16 => NAMED[n as usize],
+            AnsiColor::Indexed(n) if n < 232 => {
+                // 6x6x6 color cube
+                let n = n - 16;
+                let level = |v: u8| if v == 0 { 0 } else { 55 + v as u32 * 40 };
+                (level(n / 36) << 16) | (level((n / 6) % 6) << 8) | level(n % 6)
+            }
+            AnsiColor::Indexed(n) => {
+                // Grayscale ramp
+                let v = 8 + (n - 232) as u32 * 10;
+                (v << 16) | (v << 8) | v
+            }
+            AnsiColor::Rgb(r, g, b) => ((r as u32) << 16) | ((g as u32) << 8) | b as u32,
+        }
+    }
+}
+
+/// Text attributes active for a span
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnsiStyle {
+    pub foreground: Option<AnsiColor>,
+    pub background: Option<AnsiColor>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+/// A run of text sharing one style
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyledSpan {
+    pub text: String,
+    pub style: AnsiStyle,
+}
+
+impl StyledSpan {
+    pub fn new(text: impl Into<String>, style: AnsiStyle) -> Self {
+        Self {
+            text: text.into(),
+            style,
+        }
+    }
+}
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Parse text containing ANSI escapes into styled spans
+pub fn parse_ansi(text: &str) -> Vec<StyledSpan> {
+    let mut spans: Vec<StyledSpan> = Vec::new();
+    let mut style = AnsiStyle::default();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != ESC {
+            current.push(ch);
+            continue;
+        }
+
+        match chars.peek() {
+            Some('[') => {
+                chars.next();
+                let mut params = String::new();
+                let mut final_byte = None;
+                while let Some(&c) = chars.peek() {
+                    match c {
+                        // Parameter and intermediate bytes
+                        '\x20'..='\x3f' => {
+                            params.push(c);
+                            chars.next();
+                        }
+                        // Final byte terminates the sequence
+                        '\x40'..='\x7e' => {
+                            final_byte = Some(c);
+                            chars.next();
+                            break;
+                        }
+                        // Anything else means the sequence is malformed;
+                        // drop it and resume with the offending character
+                        _ => break,
+                    }
+                }
+
+                if final_byte == Some('m') {
+                    let next = apply_sgr(style, &params);
+                    if next != style {
+                        flush(&mut spans, &mut current, style);
+                        style = next;
+                    }
+                }
+            }
+            Some(']') => {
+                // OSC: skip until BEL or ST (`ESC \`)
+                chars.next();
+                while let Some(c) = chars.next() {
+                    if c == BEL {
+                        break;
+                    }
+                    if c == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Two-character escape (e.g. `ESC 7`, `ESC M`)
+            Some(_) => {
+                chars.next();
+            }
+            None => {}
+        }
+    }
+
+    flush(&mut spans, &mut current, style);
+    spans
+}
+
+/// Remove all ANSI escape sequences, keeping only the text
+pub fn strip_ansi(text: &str) -> String {
+    if !text.contains(ESC) {
+        return text.to_string();
+    }
+    parse_ansi(text).into_iter().map(|span| span.text).collect()
+}
+
+fn flush(spans: &mut Vec<StyledSpan>, current: &mut String, style: AnsiStyle) {
+    if current.is_empty() {
+        return;
+    }
+    let text = std::mem::take(current);
+    match spans.last_mut() {
+        Some(last) if last.style == style => last.text.push_str(&text),
+        _ => spans.push(StyledSpan::new(text, style)),
+    }
+}
+
+fn apply_sgr(mut style: AnsiStyle, params: &str) -> AnsiStyle {
+    // Private/intermediate-byte sequences aren't SGR
+    if params.chars().any(|c| !(c.is_ascii_digit() || c == ';')) {
+        return style;
+    }
+
+    let codes: Vec<u16> = if params.is_empty() {
+        vec![0]
+    } else {
+        params.split(';').map(|p| p.parse().unwrap_or(0)).collect()
+    };
+
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => style = AnsiStyle::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => style.underline = true,
+            22 => {
+                style.bold = false;
+                style.dim = false;
+            }
+            23 => style.italic = false,
+            24 => style.underline = false,
+            code @ 30..=37 => style.foreground = Some(AnsiColor::Named((code - 30) as u8)),
+            39 => style.foreground = None,
+            code @ 40..=47 => style.background = Some(AnsiColor::Named((code - 40) as u8)),
+            49 => style.background = None,
+            code @ 90..=97 => style.foreground = Some(AnsiColor::Named((code - 90 + 8) as u8)),
+            code @ 100..=107 => style.background = Some(AnsiColor::Named((code - 100 + 8) as u8)),
+            code @ (38 | 48) => {
+                let (color, consumed) = parse_extended_color(&codes[i + 1..]);
+                if code == 38 {
+                    style.foreground = color.or(style.foreground);
+                } else {
+                    style.background = color.or(style.background);
+                }
+                i += consumed;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    style
+}
+
+/// Parse the arguments of a `38`/`48` extended color, returning the color and
+/// how many codes it consumed
+fn parse_extended_color(args: &[u16]) -> (Option<AnsiColor>, usize) {
+    match args {
+        [5, n, ..] => (u8::try_from(*n).ok().map(AnsiColor::Indexed), 2),
+        [2, r, g, b, ..] => {
+            let rgb = (u8::try_from(*r), u8::try_from(*g), u8::try_from(*b));
+            match rgb {
+                (Ok(r), Ok(g), Ok(b)) => (Some(AnsiColor::Rgb(r, g, b)), 4),
+                _ => (None, 4),
+            }
+        }
+        _ => (None, args.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sgr_colors_into_spans() {
+        let spans = parse_ansi("ok \x1b[1;31merror\x1b[0m done \x1b[38;5;208mx\x1b[38;2;1;2;3my");
+        assert_eq!(
+            spans,
+            vec![
+                StyledSpan::new("ok ", AnsiStyle::default()),
+                StyledSpan::new(
+                    "error",
+                    AnsiStyle {
+                        foreground: Some(AnsiColor::Named(1)),
+                        bold: true,
+                        ..Default::default()
+                    }
+                ),
+                StyledSpan::new(" done ", AnsiStyle::default()),
+                StyledSpan::new(
+                    "x",
+                    AnsiStyle {
+                        foreground: Some(AnsiColor::Indexed(208)),
+                        ..Default::default()
+                    }
+                ),
+                StyledSpan::new(
+                    "y",
+                    AnsiStyle {
+                        foreground: Some(AnsiColor::Rgb(1, 2, 3)),
+                        ..Default::default()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_palette_colors() {
+        assert_eq!(AnsiColor::Named(1).to_rgb(), 0xcd3131);
+        assert_eq!(AnsiColor::Indexed(16).to_rgb(), 0x000000);
+        assert_eq!(AnsiColor::Indexed(231).to_rgb(), 0xffffff);
+        assert_eq!(AnsiColor::Indexed(232).to_rgb(), 0x080808);
+        assert_eq!(AnsiColor::Rgb(1, 2, 3).to_rgb(), 0x010203);
+    }
+
+    #[test]
+    fn bright_colors_and_default_reset() {
+        let spans = parse_ansi("\x1b[92mgreen\x1b[39mplain");
+        assert_eq!(spans[0].style.foreground, Some(AnsiColor::Named(10)));
+        assert_eq!(spans[1], StyledSpan::new("plain", AnsiStyle::default()));
+    }
+
+    #[test]
+    fn strips_cursor_movement_and_osc() {
+        let text = "\x1b[2K\x1b[1Gprogress\x1b[3A 100%\x1b]0;title\x07\x1b[?25l!";
+        assert_eq!(strip_ansi(text), "progress 100%!");
+        assert_eq!(parse_ansi(text).len(), 1);
+    }
+
+    #[test]
+    fn handles_malformed_sequences() {
+        // Unterminated CSI at end of input
+        assert_eq!(strip_ansi("abc\x1b[31"), "abc");
+        // Lone ESC at end of input
+        assert_eq!(strip_ansi("abc\x1b"), "abc");
+        // CSI interrupted by a control character keeps the following text
+        assert_eq!(strip_ansi("a\x1b[3\nb"), "a\nb");
+        // Out-of-range extended color is ignored
+        let spans = parse_ansi("\x1b[38;2;300;0;0mx");
+        assert_eq!(spans, vec![StyledSpan::new("x", AnsiStyle::default())]);
+    }
+}
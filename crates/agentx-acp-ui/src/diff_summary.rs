@@ -3,7 +3,10 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use agent_client_protocol::{Diff, ToolCall, ToolCallContent, ToolCallId, ToolCallStatus};
-use gpui::{App, Context, IntoElement, ParentElement, Render, Styled, Window, div, prelude::*, px};
+use gpui::{
+    App, ClipboardItem, Context, IntoElement, ParentElement, Render, Styled, Window, div,
+    prelude::*, px,
+};
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
     button::{Button, ButtonVariants},
@@ -187,6 +190,62 @@ impl DiffSummaryData {
     pub fn has_changes(&self) -> bool {
         !self.files.is_empty()
     }
+
+    /// Build a unified diff (`git apply` compatible) covering every changed
+    /// file, going from each file's initial state to its final state.
+    /// Files are ordered by path so the output is stable.
+    pub fn to_unified_patch(&self) -> String {
+        let mut paths: Vec<_> = self.merged_states.keys().collect();
+        paths.sort();
+
+        let mut patch = String::new();
+        for path in paths {
+            let (old_text, new_text) = &self.merged_states[path];
+            patch.push_str(&unified_file_patch(path, old_text.as_deref(), new_text));
+        }
+        patch
+    }
+}
+
+/// Context lines around each hunk, matching `diff -u` / `git diff`
+const PATCH_CONTEXT_LINES: usize = 3;
+
+/// Render one file's change as a unified diff section with `---`/`+++`
+/// headers and `@@` hunks. Binary content is reported rather than inlined.
+fn unified_file_patch(path: &std::path::Path, old_text: Option<&str>, new_text: &str) -> String {
+    let display = path.display().to_string().replace('\\', "/");
+    let old_header = match old_text {
+        Some(_) => format!("a/{}", display),
+        None => "/dev/null".to_string(),
+    };
+    let new_header = format!("b/{}", display);
+
+    let is_binary = |text: &str| text.contains('\0');
+    if old_text.is_some_and(is_binary) || is_binary(new_text) {
+        return format!(
+            "diff --git a/{0} b/{0}\nBinary files {1} and {2} differ\n",
+            display, old_header, new_header
+        );
+    }
+
+    let mut patch = format!("diff --git a/{0} b/{0}\n", display);
+    if old_text.is_none() {
+        patch.push_str("new file mode 100644\n");
+    }
+
+    let old_text = old_text.unwrap_or("");
+    if old_text == new_text {
+        return String::new();
+    }
+
+    let diff = TextDiff::from_lines(old_text, new_text);
+    let hunks = diff
+        .unified_diff()
+        .context_radius(PATCH_CONTEXT_LINES)
+        .header(&old_header, &new_header)
+        .to_string();
+    patch.push_str(&hunks);
+    patch
 }
 
 /// UI component to display diff summary
@@ -221,6 +280,11 @@ impl DiffSummary {
         cx.notify();
     }
 
+    /// Copy the unified diff of all changes to the clipboard
+    pub fn copy_patch(&self, cx: &mut Context<Self>) {
+        cx.write_to_clipboard(ClipboardItem::new_string(self.data.to_unified_patch()));
+    }
+
     /// Update the summary data
     pub fn update_data(&mut self, data: DiffSummaryData, cx: &mut Context<Self>) {
         self.data = data;
@@ -386,6 +450,16 @@ impl Render for DiffSummary {
                             )),
                     )
                     .child(self.render_stats(total_additions, total_deletions, cx))
+                    .child(
+                        Button::new("diff-summary-copy-patch")
+                            .icon(IconName::Copy)
+                            .ghost()
+                            .small()
+                            .tooltip("Copy patch")
+                            .on_click(cx.listener(|this, _ev, _window, cx| {
+                                this.copy_patch(cx);
+                            })),
+                    )
                     .child(
                         Button::new("diff-summary-toggle")
                             .icon(if is_collapsed {
@@ -432,4 +506,80 @@ mod tests {
         assert_eq!(merged.status, ToolCallStatus::Completed);
         assert!(merged.title.contains("file.txt"));
     }
+
+    /// Minimal unified-diff applier used to check generated patches
+    fn apply_patch(old: &str, file_patch: &str) -> String {
+        let old_lines: Vec<&str> = old.lines().collect();
+        let mut result: Vec<String> = Vec::new();
+        let mut old_ix = 0;
+
+        for line in file_patch.lines() {
+            if let Some(header) = line.strip_prefix("@@ -") {
+                let start: usize = header
+                    .split([',', ' '])
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap();
+                let hunk_start = start.saturating_sub(1);
+                result.extend(old_lines[old_ix..hunk_start].iter().map(|l| l.to_string()));
+                old_ix = hunk_start;
+            } else if line.starts_with("---") || line.starts_with("+++") {
+                continue;
+            } else if let Some(added) = line.strip_prefix('+') {
+                result.push(added.to_string());
+            } else if let Some(removed) = line.strip_prefix('-') {
+                assert_eq!(old_lines[old_ix], removed);
+                old_ix += 1;
+            } else if let Some(context) = line.strip_prefix(' ') {
+                assert_eq!(old_lines[old_ix], context);
+                result.push(context.to_string());
+                old_ix += 1;
+            }
+        }
+        result.extend(old_lines[old_ix..].iter().map(|l| l.to_string()));
+        result.join("\n")
+    }
+
+    #[test]
+    fn unified_patch_round_trips() {
+        let old = (1..=12)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n";
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 11\n", "");
+
+        let mut tool_call = ToolCall::new("tc-1", "Edit file");
+        tool_call.content = vec![ToolCallContent::Diff(
+            Diff::new(PathBuf::from("src/lib.rs"), new.clone()).old_text(old.clone()),
+        )];
+        let patch = DiffSummaryData::from_tool_calls(&[tool_call]).to_unified_patch();
+
+        assert!(patch.starts_with("diff --git a/src/lib.rs b/src/lib.rs\n"));
+        assert!(patch.contains("--- a/src/lib.rs\n+++ b/src/lib.rs\n"));
+        assert!(patch.contains("@@ -"));
+        assert_eq!(apply_patch(&old, &patch), new.trim_end_matches('\n'));
+    }
+
+    #[test]
+    fn unified_patch_marks_new_and_binary_files() {
+        let mut new_file = ToolCall::new("tc-1", "Create file");
+        new_file.content = vec![ToolCallContent::Diff(Diff::new(
+            PathBuf::from("a.txt"),
+            "hello\n".to_string(),
+        ))];
+        let mut binary = ToolCall::new("tc-2", "Write blob");
+        binary.content = vec![ToolCallContent::Diff(
+            Diff::new(PathBuf::from("b.bin"), "\0\x01new".to_string())
+                .old_text("\0\x01old".to_string()),
+        )];
+
+        let patch = DiffSummaryData::from_tool_calls(&[new_file, binary]).to_unified_patch();
+        assert!(patch.contains("new file mode 100644\n--- /dev/null\n+++ b/a.txt\n"));
+        assert!(patch.contains("+hello\n"));
+        assert!(patch.contains("Binary files a/b.bin and b/b.bin differ\n"));
+        assert!(!patch.contains("\0"));
+    }
 }
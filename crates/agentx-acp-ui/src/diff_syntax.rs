@@ -0,0 +1,186 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Range;
+use std::path::Path;
+use std::rc::Rc;
+
+use gpui::{App, HighlightStyle};
+use gpui_component::{
+    ActiveTheme,
+    highlighter::{Language, SyntaxHighlighter},
+    input::Rope,
+};
+
+/// Resolve the highlighter language for a file from its extension.
+///
+/// Returns `None` for extensions the highlighter doesn't know so callers can
+/// fall back to plain text.
+pub(crate) fn language_for_path(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?;
+    let language = Language::from_str(ext);
+    (!matches!(language, Language::Plain)).then(|| language.name())
+}
+
+/// Parse the whole text at once so multi-line constructs (block comments,
+/// strings) are colored correctly
+pub(crate) fn parse_syntax(text: &str, language: &str) -> SyntaxHighlighter {
+    let mut highlighter = SyntaxHighlighter::new(language);
+    highlighter.update(None, &Rope::from(text));
+    highlighter
+}
+
+/// Hash of the text a value was computed from, used as a [`ContentCache`] key
+pub(crate) fn content_hash(parts: &[Option<&str>]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    parts.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A value computed from some text, kept across renders and recomputed only
+/// when the text's [`content_hash`] changes
+pub(crate) struct ContentCache<T> {
+    entry: Option<(u64, Rc<T>)>,
+}
+
+impl<T> Default for ContentCache<T> {
+    fn default() -> Self {
+        Self { entry: None }
+    }
+}
+
+impl<T> ContentCache<T> {
+    pub fn get_or_insert_with(&mut self, key: u64, compute: impl FnOnce() -> T) -> Rc<T> {
+        match &self.entry {
+            Some((cached, value)) if *cached == key => value.clone(),
+            _ => {
+                let value = Rc::new(compute());
+                self.entry = Some((key, value.clone()));
+                value
+            }
+        }
+    }
+}
+
+/// Syntax highlight styles for one version of a file, addressable by line
+pub(crate) struct LineHighlights {
+    lines: Vec<Vec<(Range<usize>, HighlightStyle)>>,
+}
+
+impl LineHighlights {
+    /// Style `text` with its parsed syntax in the current theme, split per line
+    pub fn new(highlighter: &SyntaxHighlighter, text: &str, cx: &App) -> Self {
+        let styles = highlighter.styles(&(0..text.len()), &cx.theme().highlight_theme);
+        Self {
+            lines: split_styles_by_line(text, styles),
+        }
+    }
+
+    /// Styles for the 1-based `line_num`, with ranges relative to the line start
+    pub fn line(&self, line_num: usize) -> &[(Range<usize>, HighlightStyle)] {
+        line_num
+            .checked_sub(1)
+            .and_then(|ix| self.lines.get(ix))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
+
+/// Split document-wide highlight ranges into per-line ranges relative to each
+/// line start. Line terminators are excluded to match `DiffLine` text.
+fn split_styles_by_line(
+    text: &str,
+    styles: impl IntoIterator<Item = (Range<usize>, HighlightStyle)>,
+) -> Vec<Vec<(Range<usize>, HighlightStyle)>> {
+    let mut bounds = Vec::new();
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches('\n');
+        bounds.push(start..start + content.len());
+        start += line.len();
+    }
+
+    let mut lines = vec![Vec::new(); bounds.len()];
+    let mut line_ix = 0;
+    for (range, style) in styles {
+        while line_ix < bounds.len() && bounds[line_ix].end < range.start {
+            line_ix += 1;
+        }
+        let mut ix = line_ix;
+        while ix < bounds.len() && bounds[ix].start < range.end {
+            let line = &bounds[ix];
+            let clipped = range.start.max(line.start)..range.end.min(line.end);
+            if !clipped.is_empty() {
+                lines[ix].push((clipped.start - line.start..clipped.end - line.start, style));
+            }
+            ix += 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_for_path_uses_extension() {
+        assert_eq!(language_for_path(Path::new("src/main.rs")), Some("rust"));
+        assert_eq!(language_for_path(Path::new("notes.unknownext")), None);
+        assert_eq!(language_for_path(Path::new("Makefile")), None);
+    }
+
+    #[test]
+    fn rust_files_are_highlighted() {
+        use gpui_component::highlighter::HighlightTheme;
+
+        let text = "fn main() {\n    let answer = 42;\n}\n";
+        let language = language_for_path(Path::new("src/main.rs")).unwrap();
+        let highlighter = parse_syntax(text, language);
+        let styles = highlighter.styles(&(0..text.len()), &HighlightTheme::default_dark());
+        let lines = split_styles_by_line(text, styles);
+
+        // The `fn` and `let` keywords are styled on their own lines
+        assert!(lines[0].iter().any(|(range, _)| *range == (0..2)));
+        assert!(lines[1].iter().any(|(range, _)| *range == (4..7)));
+    }
+
+    #[test]
+    fn content_cache_recomputes_only_when_the_text_changes() {
+        let mut cache = ContentCache::default();
+        let mut computed = 0;
+        let mut get = |text: &str| {
+            *cache.get_or_insert_with(content_hash(&[Some(text)]), || {
+                computed += 1;
+                text.len()
+            })
+        };
+
+        assert_eq!(get("fn main() {}"), 12);
+        assert_eq!(get("fn main() {}"), 12);
+        assert_eq!(get("fn main() { run() }"), 19);
+        assert_eq!(computed, 2);
+    }
+
+    #[test]
+    fn content_hash_tells_old_and_new_text_apart() {
+        assert_ne!(
+            content_hash(&[Some("a"), Some("b")]),
+            content_hash(&[Some("b"), Some("a")])
+        );
+        assert_ne!(
+            content_hash(&[None, Some("a")]),
+            content_hash(&[Some(""), Some("a")])
+        );
+    }
+
+    #[test]
+    fn split_styles_by_line_clips_to_lines() {
+        let text = "let a = 1;\n/* x\ny */\n";
+        let style = HighlightStyle::default();
+        let lines = split_styles_by_line(text, vec![(0..3, style), (11..20, style)]);
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], vec![(0..3, style)]);
+        assert_eq!(lines[1], vec![(0..4, style)]);
+        assert_eq!(lines[2], vec![(0..4, style)]);
+    }
+}
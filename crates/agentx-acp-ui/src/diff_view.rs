@@ -1,11 +1,22 @@
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Range;
 use std::path::PathBuf;
 
 use agent_client_protocol::Diff;
 use gpui::prelude::FluentBuilder;
-use gpui::{AnyElement, App, IntoElement, ParentElement, RenderOnce, Styled, Window, div, px};
-use gpui_component::{ActiveTheme, Icon, IconName, h_flex, v_flex};
+use gpui::{
+    AnyElement, App, ElementId, Entity, HighlightStyle, InteractiveElement, IntoElement,
+    ParentElement, RenderOnce, SharedString, StatefulInteractiveElement, Styled, StyledText,
+    Window, combine_highlights, div, px,
+};
+use gpui_component::{ActiveTheme, Icon, IconName, h_flex, highlighter::SyntaxHighlighter, v_flex};
 use similar::{ChangeTag, TextDiff};
 
+use crate::diff_syntax::{
+    ContentCache, LineHighlights, content_hash, language_for_path, parse_syntax,
+};
+
 /// Represents a single line in a diff view
 #[derive(Debug, Clone)]
 pub enum DiffLine {
@@ -16,9 +27,19 @@ pub enum DiffLine {
         new_num: usize,
     },
     /// Line added in new version
-    Insert { line: String, new_num: usize },
+    Insert {
+        line: String,
+        new_num: usize,
+        /// Byte ranges that differ from the paired deleted line (word-level mode)
+        changed: Vec<Range<usize>>,
+    },
     /// Line deleted from old version
-    Delete { line: String, old_num: usize },
+    Delete {
+        line: String,
+        old_num: usize,
+        /// Byte ranges that differ from the paired inserted line (word-level mode)
+        changed: Vec<Range<usize>>,
+    },
 }
 
 /// Represents a display item in the diff view (can be a line or a collapsed section)
@@ -26,20 +47,46 @@ pub enum DiffLine {
 pub enum DiffDisplayItem {
     /// A regular diff line
     Line(DiffLine),
-    /// A collapsed section of unchanged lines
+    /// A collapsed section of unchanged lines; `range` indexes into the full
+    /// list of diff lines so expanding the section can reveal them in place
     Collapsed {
         start_old: usize,
         start_new: usize,
         count: usize,
+        range: Range<usize>,
     },
 }
 
+/// A collapsed section as rendered: the line numbers and length of the
+/// hidden run, and its [`fold_key`]
+struct FoldedSection {
+    start_old: usize,
+    start_new: usize,
+    count: usize,
+    key: u64,
+}
+
+/// Key remembering that a collapsed section was expanded: its starting line in
+/// the old text plus a hash of the hidden lines, so the section stays expanded
+/// when lines before it are added or removed as the diff updates
+fn fold_key(lines: &[DiffLine]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for line in lines {
+        if let DiffLine::Context { line, old_num, .. } = line {
+            old_num.hash(&mut hasher);
+            line.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
 /// Configuration for DiffView rendering
 #[derive(Debug, Clone)]
 pub struct DiffViewConfig {
     /// Maximum number of lines to display (default: 5000)
     pub max_lines: usize,
-    /// Number of context lines to show before/after changes (default: 5)
+    /// Number of unchanged lines to keep around each change; longer unchanged
+    /// runs are folded behind an expander (default: 5)
     pub context_lines: usize,
     /// Whether to show file header (default: true)
     pub show_file_header: bool,
@@ -47,6 +94,10 @@ pub struct DiffViewConfig {
     pub show_truncation_warning: bool,
     /// Whether to show collapsed placeholders at file edges (default: false)
     pub show_edge_collapsed: bool,
+    /// Whether to highlight changed words within modified lines (default: true)
+    pub word_level: bool,
+    /// Whether to apply syntax highlighting based on the file extension (default: true)
+    pub syntax_highlight: bool,
 }
 
 impl Default for DiffViewConfig {
@@ -57,12 +108,15 @@ impl Default for DiffViewConfig {
             show_file_header: true,
             show_truncation_warning: false,
             show_edge_collapsed: false,
+            word_level: true,
+            syntax_highlight: true,
         }
     }
 }
 
 /// A reusable diff view component that displays file diffs with syntax highlighting
 pub struct DiffView {
+    id: ElementId,
     diff: Diff,
     config: DiffViewConfig,
 }
@@ -70,15 +124,22 @@ pub struct DiffView {
 impl DiffView {
     /// Create a new DiffView with default configuration
     pub fn new(diff: Diff) -> Self {
-        Self {
-            diff,
-            config: DiffViewConfig::default(),
-        }
+        Self::with_config(diff, DiffViewConfig::default())
     }
 
     /// Create a new DiffView with custom configuration
     pub fn with_config(diff: Diff, config: DiffViewConfig) -> Self {
-        Self { diff, config }
+        let id = ElementId::Name(format!("diff-view-{}", diff.path.display()).into());
+        Self { id, diff, config }
+    }
+
+    /// Set the element id used to keep fold state across renders.
+    ///
+    /// Defaults to one derived from the file path; set it explicitly when the
+    /// same file can be shown by several diff views at once.
+    pub fn id(mut self, id: impl Into<ElementId>) -> Self {
+        self.id = id.into();
+        self
     }
 
     /// Set maximum number of lines to display
@@ -111,6 +172,53 @@ impl DiffView {
         self
     }
 
+    /// Set whether to highlight changed words within modified lines
+    pub fn word_level(mut self, word_level: bool) -> Self {
+        self.config.word_level = word_level;
+        self
+    }
+
+    /// Set whether to apply syntax highlighting based on the file extension
+    pub fn syntax_highlight(mut self, syntax_highlight: bool) -> Self {
+        self.config.syntax_highlight = syntax_highlight;
+        self
+    }
+
+    /// Build syntax highlights for both sides of the diff, or `None` when
+    /// disabled or the file extension has no known language.
+    fn compute_syntax(&self, window: &mut Window, cx: &mut App) -> Option<DiffSyntax> {
+        if !self.config.syntax_highlight {
+            return None;
+        }
+        let language = language_for_path(&self.diff.path)?;
+        let old_text = self.diff.old_text.as_deref();
+        let new_text = self.diff.new_text.as_str();
+
+        // Parsing is the expensive part, so it is only redone when the text
+        // changes; styling follows the current theme on every render
+        let cache = window.use_keyed_state(
+            SharedString::from(format!("{}-syntax", self.id)),
+            cx,
+            |_, _| ContentCache::<ParsedDiff>::default(),
+        );
+        let key = content_hash(&[Some(language), old_text, Some(new_text)]);
+        let parsed = cache.update(cx, |cache, _| {
+            cache.get_or_insert_with(key, || ParsedDiff {
+                old: old_text.map(|text| parse_syntax(text, language)),
+                new: parse_syntax(new_text, language),
+            })
+        });
+
+        Some(DiffSyntax {
+            old: parsed
+                .old
+                .as_ref()
+                .zip(old_text)
+                .map(|(highlighter, text)| LineHighlights::new(highlighter, text, cx)),
+            new: LineHighlights::new(&parsed.new, new_text, cx),
+        })
+    }
+
     /// Compute line-by-line diff using similar crate
     fn compute_diff(&self, old_text: &str, new_text: &str) -> Vec<DiffLine> {
         let diff = TextDiff::from_lines(old_text, new_text);
@@ -136,6 +244,7 @@ impl DiffView {
                     result.push(DiffLine::Delete {
                         line,
                         old_num: old_line_num,
+                        changed: Vec::new(),
                     });
                     old_line_num += 1;
                 }
@@ -143,145 +252,121 @@ impl DiffView {
                     result.push(DiffLine::Insert {
                         line,
                         new_num: new_line_num,
+                        changed: Vec::new(),
                     });
                     new_line_num += 1;
                 }
             }
         }
 
+        if self.config.word_level {
+            apply_word_diff(&mut result);
+        }
+
         result
     }
 
-    /// Apply context collapsing to diff lines
-    /// Only show changed lines with N lines of context before/after
-    fn apply_context_collapsing(&self, diff_lines: Vec<DiffLine>) -> Vec<DiffDisplayItem> {
+    /// Fold unchanged regions so only `context_lines` of context surround each
+    /// change. Folded regions become `DiffDisplayItem::Collapsed` placeholders
+    /// whose `range` indexes into `diff_lines`; unchanged regions at the file
+    /// edges are only shown as placeholders when `show_edge_collapsed` is set.
+    fn apply_context_collapsing(&self, diff_lines: &[DiffLine]) -> Vec<DiffDisplayItem> {
         let context_lines = self.config.context_lines;
-        let min_collapse_size = context_lines * 2 + 1; // Minimum lines to collapse
-
-        let mut display_items: Vec<DiffDisplayItem> = Vec::new();
-        let mut context_buffer: Vec<DiffLine> = Vec::new();
-        let mut last_change_index: Option<usize> = None;
+        let mut display_items = Vec::new();
+        let mut i = 0;
+
+        while i < diff_lines.len() {
+            if !matches!(diff_lines[i], DiffLine::Context { .. }) {
+                display_items.push(DiffDisplayItem::Line(diff_lines[i].clone()));
+                i += 1;
+                continue;
+            }
 
-        for (i, line) in diff_lines.iter().enumerate() {
-            match line {
-                DiffLine::Context { .. } => {
-                    // Accumulate context lines
-                    context_buffer.push(line.clone());
-                }
-                DiffLine::Insert { .. } | DiffLine::Delete { .. } => {
-                    // Found a change - process buffered context
-                    if !context_buffer.is_empty() {
-                        if let Some(last_idx) = last_change_index {
-                            // There was a previous change
-                            let distance = i - last_idx - 1;
-
-                            if distance >= min_collapse_size {
-                                // Show context_lines after previous change
-                                for ctx in context_buffer.iter().take(context_lines) {
-                                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                                }
-
-                                // Collapse the middle
-                                let collapsed_count = distance - context_lines * 2;
-                                if collapsed_count > 0 {
-                                    if let DiffLine::Context {
-                                        old_num, new_num, ..
-                                    } = &context_buffer[context_lines]
-                                    {
-                                        display_items.push(DiffDisplayItem::Collapsed {
-                                            start_old: *old_num,
-                                            start_new: *new_num,
-                                            count: collapsed_count,
-                                        });
-                                    }
-                                }
-
-                                // Show context_lines before current change
-                                let start = context_buffer.len().saturating_sub(context_lines);
-                                for ctx in context_buffer.iter().skip(start) {
-                                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                                }
-                            } else {
-                                // Distance is small, show all context
-                                for ctx in &context_buffer {
-                                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                                }
-                            }
-                        } else {
-                            // This is the first change
-                            if context_buffer.len() > context_lines {
-                                // Collapse leading context, only show last context_lines
-                                if self.config.show_edge_collapsed {
-                                    let collapsed_count = context_buffer.len() - context_lines;
-                                    if let DiffLine::Context {
-                                        old_num, new_num, ..
-                                    } = &context_buffer[0]
-                                    {
-                                        display_items.push(DiffDisplayItem::Collapsed {
-                                            start_old: *old_num,
-                                            start_new: *new_num,
-                                            count: collapsed_count,
-                                        });
-                                    }
-                                }
-
-                                let start = context_buffer.len() - context_lines;
-                                for ctx in context_buffer.iter().skip(start) {
-                                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                                }
-                            } else {
-                                // Show all leading context
-                                for ctx in &context_buffer {
-                                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                                }
-                            }
-                        }
-
-                        context_buffer.clear();
-                    }
-
-                    // Add the change line
-                    display_items.push(DiffDisplayItem::Line(line.clone()));
-                    last_change_index = Some(i);
-                }
+            // Collect the run of unchanged lines starting here
+            let start = i;
+            while i < diff_lines.len() && matches!(diff_lines[i], DiffLine::Context { .. }) {
+                i += 1;
+            }
+            let end = i;
+            let has_prev_change = start > 0;
+            let has_next_change = end < diff_lines.len();
+
+            // Lines kept visible at the start and end of the run
+            let (head, tail) = match (has_prev_change, has_next_change) {
+                (true, true) => (context_lines, context_lines),
+                (false, true) => (0, context_lines),
+                // Trailing context, or a diff with no changes at all
+                (_, false) => (context_lines, 0),
+            };
+            let run_len = end - start;
+            if run_len <= head + tail {
+                display_items.extend(
+                    diff_lines[start..end]
+                        .iter()
+                        .cloned()
+                        .map(DiffDisplayItem::Line),
+                );
+                continue;
             }
-        }
 
-        // Handle trailing context
-        if !context_buffer.is_empty() {
-            if context_buffer.len() > context_lines {
-                // Show first context_lines, collapse the rest
-                for ctx in context_buffer.iter().take(context_lines) {
-                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                }
-                if self.config.show_edge_collapsed {
-                    let collapsed_count = context_buffer.len() - context_lines;
-                    if let DiffLine::Context {
+            let folded = start + head..end - tail;
+            let is_edge = !(has_prev_change && has_next_change);
+            display_items.extend(
+                diff_lines[start..folded.start]
+                    .iter()
+                    .cloned()
+                    .map(DiffDisplayItem::Line),
+            );
+            if !is_edge || self.config.show_edge_collapsed {
+                let (start_old, start_new) = match &diff_lines[folded.start] {
+                    DiffLine::Context {
                         old_num, new_num, ..
-                    } = &context_buffer[context_lines]
-                    {
-                        display_items.push(DiffDisplayItem::Collapsed {
-                            start_old: *old_num,
-                            start_new: *new_num,
-                            count: collapsed_count,
-                        });
-                    }
-                }
-            } else {
-                // Show all trailing context
-                for ctx in &context_buffer {
-                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                }
+                    } => (*old_num, *new_num),
+                    _ => unreachable!("folded runs only contain context lines"),
+                };
+                display_items.push(DiffDisplayItem::Collapsed {
+                    start_old,
+                    start_new,
+                    count: folded.len(),
+                    range: folded.clone(),
+                });
             }
+            display_items.extend(
+                diff_lines[folded.end..end]
+                    .iter()
+                    .cloned()
+                    .map(DiffDisplayItem::Line),
+            );
         }
 
         display_items
     }
 
+    /// Replace folds the user has expanded with the lines they hide
+    fn expand_folds(
+        display_items: Vec<DiffDisplayItem>,
+        diff_lines: &[DiffLine],
+        expanded: &HashSet<u64>,
+    ) -> Vec<DiffDisplayItem> {
+        let mut result = Vec::with_capacity(display_items.len());
+        for item in display_items {
+            match item {
+                DiffDisplayItem::Collapsed { range, .. }
+                    if expanded.contains(&fold_key(&diff_lines[range.clone()])) =>
+                {
+                    result.extend(diff_lines[range].iter().cloned().map(DiffDisplayItem::Line));
+                }
+                item => result.push(item),
+            }
+        }
+        result
+    }
+
     /// Render a single diff line
     fn render_diff_line<'a>(
         &self,
         diff_line: &'a DiffLine,
+        syntax: Option<&DiffSyntax>,
         _window: &'a mut Window,
         cx: &'a mut App,
     ) -> impl IntoElement + 'a {
@@ -309,9 +394,18 @@ impl DiffView {
                         .flex_1()
                         .px_2()
                         .text_color(cx.theme().foreground)
-                        .child(line.clone()),
+                        .child(Self::render_line_text(
+                            line,
+                            &[],
+                            cx.theme().foreground,
+                            syntax.map(|s| s.new.line(*new_num)).unwrap_or(&[]),
+                        )),
                 ),
-            DiffLine::Insert { line, new_num } => h_flex()
+            DiffLine::Insert {
+                line,
+                new_num,
+                changed,
+            } => h_flex()
                 .w_full()
                 .bg(cx.theme().green.opacity(0.1))
                 .border_l_2()
@@ -326,86 +420,177 @@ impl DiffView {
                         .text_color(cx.theme().green)
                         .child(format!("     {:>4} +", new_num)),
                 )
-                .child(
-                    div()
-                        .flex_1()
-                        .px_2()
-                        .text_color(cx.theme().green)
-                        .child(line.clone()),
-                ),
-            DiffLine::Delete { line, old_num } => h_flex()
-                .w_full()
-                .bg(cx.theme().red.opacity(0.1))
-                .border_l_2()
-                .border_color(cx.theme().red)
-                .font_family("Monaco, 'Courier New', monospace")
-                .text_size(px(12.))
-                .line_height(px(18.))
-                .child(
-                    div()
-                        .min_w(px(70.))
-                        .px_2()
-                        .text_color(cx.theme().red)
-                        .child(format!("{:>4}      -", old_num)),
-                )
-                .child(
-                    div()
-                        .flex_1()
-                        .px_2()
-                        .text_color(cx.theme().red)
-                        .child(line.clone()),
-                ),
+                .child(div().flex_1().px_2().text_color(cx.theme().green).child(
+                    Self::render_line_text(
+                        line,
+                        changed,
+                        cx.theme().green.opacity(0.3),
+                        syntax.map(|s| s.new.line(*new_num)).unwrap_or(&[]),
+                    ),
+                )),
+            DiffLine::Delete {
+                line,
+                old_num,
+                changed,
+            } => {
+                h_flex()
+                    .w_full()
+                    .bg(cx.theme().red.opacity(0.1))
+                    .border_l_2()
+                    .border_color(cx.theme().red)
+                    .font_family("Monaco, 'Courier New', monospace")
+                    .text_size(px(12.))
+                    .line_height(px(18.))
+                    .child(
+                        div()
+                            .min_w(px(70.))
+                            .px_2()
+                            .text_color(cx.theme().red)
+                            .child(format!("{:>4}      -", old_num)),
+                    )
+                    .child(
+                        div().flex_1().px_2().text_color(cx.theme().red).child(
+                            Self::render_line_text(
+                                line,
+                                changed,
+                                cx.theme().red.opacity(0.3),
+                                syntax
+                                    .and_then(|s| s.old.as_ref())
+                                    .map(|old| old.line(*old_num))
+                                    .unwrap_or(&[]),
+                            ),
+                        ),
+                    )
+            }
         }
     }
 
-    /// Render a collapsed section placeholder
+    /// Render line text with syntax colors, emphasizing changed word ranges
+    /// and muting the rest of a modified line
+    fn render_line_text(
+        line: &str,
+        changed: &[Range<usize>],
+        emphasis: gpui::Hsla,
+        syntax: &[(Range<usize>, HighlightStyle)],
+    ) -> StyledText {
+        let text = StyledText::new(line.to_string());
+        if changed.is_empty() && syntax.is_empty() {
+            return text;
+        }
+
+        let mut highlights = Vec::new();
+        let mut cursor = 0;
+        for range in changed {
+            if cursor < range.start {
+                highlights.push((cursor..range.start, muted_highlight()));
+            }
+            highlights.push((
+                range.clone(),
+                HighlightStyle {
+                    background_color: Some(emphasis),
+                    font_weight: Some(gpui::FontWeight::BOLD),
+                    ..Default::default()
+                },
+            ));
+            cursor = range.end;
+        }
+        if !changed.is_empty() && cursor < line.len() {
+            highlights.push((cursor..line.len(), muted_highlight()));
+        }
+
+        let syntax = syntax
+            .iter()
+            .filter(|(range, _)| range.end <= line.len())
+            .cloned();
+        text.with_highlights(combine_highlights(syntax, highlights).collect::<Vec<_>>())
+    }
+
+    /// Render a collapsed section placeholder; clicking it reveals the hidden lines
     fn render_collapsed_section<'a>(
         &self,
-        start_old: usize,
-        start_new: usize,
-        count: usize,
+        section: FoldedSection,
+        folds: &Entity<HashSet<u64>>,
         _window: &'a mut Window,
         cx: &'a mut App,
     ) -> impl IntoElement + 'a {
+        let FoldedSection {
+            start_old,
+            start_new,
+            count,
+            key,
+        } = section;
+        let label = format!(
+            "… {} unchanged lines ({}..{}, {}..{}) …",
+            count,
+            start_old,
+            start_old + count - 1,
+            start_new,
+            start_new + count - 1
+        );
+        let folds = folds.clone();
+        let hover_bg = cx.theme().muted.opacity(0.5);
+
         h_flex()
+            .id(ElementId::Name(format!("diff-fold-{:x}", key).into()))
             .w_full()
             .items_center()
             .justify_center()
+            .gap_1()
             .bg(cx.theme().muted.opacity(0.3))
             .border_y_1()
             .border_color(cx.theme().border)
+            .cursor_pointer()
+            .hover(move |this| this.bg(hover_bg))
+            .on_click(move |_, _window, cx| {
+                folds.update(cx, |expanded, cx| {
+                    expanded.insert(key);
+                    cx.notify();
+                });
+            })
+            .child(
+                Icon::new(IconName::ChevronsUpDown)
+                    .size(px(12.))
+                    .text_color(cx.theme().muted_foreground),
+            )
             .child(
                 div()
                     .text_size(px(11.))
                     .text_color(cx.theme().muted_foreground)
-                    .child(format!(
-                        "... {} unchanged lines hidden ({}..{}, {}..{}) ...",
-                        count,
-                        start_old,
-                        start_old + count - 1,
-                        start_new,
-                        start_new + count - 1
-                    )),
+                    .child(label),
             )
     }
 
-    /// Render a diff display item (either a line or a collapsed section)
+    /// Render a diff display item (either a line or a folded section)
     fn render_diff_display_item<'a>(
         &self,
         item: &'a DiffDisplayItem,
+        diff_lines: &[DiffLine],
+        syntax: Option<&DiffSyntax>,
+        folds: &Entity<HashSet<u64>>,
         window: &'a mut Window,
         cx: &'a mut App,
     ) -> AnyElement {
         match item {
-            DiffDisplayItem::Line(line) => {
-                self.render_diff_line(line, window, cx).into_any_element()
-            }
+            DiffDisplayItem::Line(line) => self
+                .render_diff_line(line, syntax, window, cx)
+                .into_any_element(),
             DiffDisplayItem::Collapsed {
                 start_old,
                 start_new,
                 count,
+                range,
             } => self
-                .render_collapsed_section(*start_old, *start_new, *count, window, cx)
+                .render_collapsed_section(
+                    FoldedSection {
+                        start_old: *start_old,
+                        start_new: *start_new,
+                        count: *count,
+                        key: fold_key(&diff_lines[range.clone()]),
+                    },
+                    folds,
+                    window,
+                    cx,
+                )
                 .into_any_element(),
         }
     }
@@ -471,6 +656,111 @@ impl DiffView {
     }
 }
 
+/// Syntax highlights for both sides of a diff
+struct DiffSyntax {
+    old: Option<LineHighlights>,
+    new: LineHighlights,
+}
+
+/// Parsed syntax of a diff's old and new text
+struct ParsedDiff {
+    old: Option<SyntaxHighlighter>,
+    new: SyntaxHighlighter,
+}
+
+fn muted_highlight() -> HighlightStyle {
+    HighlightStyle {
+        fade_out: Some(0.35),
+        ..Default::default()
+    }
+}
+
+/// Minimum similarity for a deleted/inserted line pair to get word-level
+/// highlighting; below this the lines are unrelated and whole-line coloring
+/// reads better.
+const WORD_DIFF_MIN_RATIO: f32 = 0.5;
+
+/// Compute the byte ranges that changed between two versions of a line,
+/// returned as `(old_ranges, new_ranges)`. Adjacent changed tokens are merged
+/// into a single range.
+fn word_diff_ranges(old_line: &str, new_line: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let diff = TextDiff::from_words(old_line, new_line);
+    let mut old_ranges: Vec<Range<usize>> = Vec::new();
+    let mut new_ranges: Vec<Range<usize>> = Vec::new();
+    let mut old_pos = 0;
+    let mut new_pos = 0;
+
+    fn push_merged(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+        match ranges.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => ranges.push(range),
+        }
+    }
+
+    for change in diff.iter_all_changes() {
+        let len = change.value().len();
+        match change.tag() {
+            ChangeTag::Equal => {
+                old_pos += len;
+                new_pos += len;
+            }
+            ChangeTag::Delete => {
+                push_merged(&mut old_ranges, old_pos..old_pos + len);
+                old_pos += len;
+            }
+            ChangeTag::Insert => {
+                push_merged(&mut new_ranges, new_pos..new_pos + len);
+                new_pos += len;
+            }
+        }
+    }
+
+    (old_ranges, new_ranges)
+}
+
+/// Pair each run of deleted lines with the run of inserted lines that follows
+/// it and fill in their changed word ranges.
+fn apply_word_diff(lines: &mut [DiffLine]) {
+    let mut i = 0;
+    while i < lines.len() {
+        let delete_start = i;
+        while i < lines.len() && matches!(lines[i], DiffLine::Delete { .. }) {
+            i += 1;
+        }
+        let insert_start = i;
+        while i < lines.len() && matches!(lines[i], DiffLine::Insert { .. }) {
+            i += 1;
+        }
+
+        let pairs = (insert_start - delete_start).min(i - insert_start);
+        for offset in 0..pairs {
+            let (before, after) = lines.split_at_mut(insert_start + offset);
+            if let (
+                DiffLine::Delete {
+                    line: old_line,
+                    changed: old_changed,
+                    ..
+                },
+                DiffLine::Insert {
+                    line: new_line,
+                    changed: new_changed,
+                    ..
+                },
+            ) = (&mut before[delete_start + offset], &mut after[0])
+            {
+                let ratio = TextDiff::from_chars(old_line.as_str(), new_line.as_str()).ratio();
+                if ratio >= WORD_DIFF_MIN_RATIO {
+                    (*old_changed, *new_changed) = word_diff_ranges(old_line, new_line);
+                }
+            }
+        }
+
+        if i == delete_start {
+            i += 1;
+        }
+    }
+}
+
 impl RenderOnce for DiffView {
     fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
         // Compute diff
@@ -491,17 +781,24 @@ impl RenderOnce for DiffView {
                     .map(|(i, line)| DiffLine::Insert {
                         line: line.to_string(),
                         new_num: i + 1,
+                        changed: Vec::new(),
                     })
                     .collect()
             }
         };
 
-        // Apply context collapsing to show only changed parts + context
-        let display_items = self.apply_context_collapsing(diff_lines);
+        // Fold unchanged regions, then reveal any folds the user has expanded
+        let folds = window.use_keyed_state(self.id.clone(), cx, |_, _| HashSet::<u64>::new());
+        let display_items = Self::expand_folds(
+            self.apply_context_collapsing(&diff_lines),
+            &diff_lines,
+            folds.read(cx),
+        );
 
         let total_lines = display_items.len();
         let truncated = total_lines > self.config.max_lines;
         let is_new_file = self.diff.old_text.is_none();
+        let syntax = self.compute_syntax(window, cx);
 
         v_flex()
             .w_full()
@@ -540,12 +837,18 @@ impl RenderOnce for DiffView {
                                         .child("No changes"),
                                 )
                             })
-                            .children(
-                                display_items
-                                    .iter()
-                                    .take(self.config.max_lines)
-                                    .map(|item| self.render_diff_display_item(item, window, cx)),
-                            ),
+                            .children(display_items.iter().take(self.config.max_lines).map(
+                                |item| {
+                                    self.render_diff_display_item(
+                                        item,
+                                        &diff_lines,
+                                        syntax.as_ref(),
+                                        &folds,
+                                        window,
+                                        cx,
+                                    )
+                                },
+                            )),
                     ),
             )
     }
@@ -567,11 +870,134 @@ mod tests {
     fn apply_context_collapsing_shows_collapsed_items() {
         let diff = Diff::new("file.txt", "a\nb\nc\nd\ne\nf".to_string())
             .old_text("a\nb\nc\nX\ne\nf".to_string());
-        let mut view = DiffView::new(diff)
+        let view = DiffView::new(diff)
             .context_lines(1)
             .show_edge_collapsed(true);
         let lines = view.compute_diff("a\nb\nc\nX\ne\nf", "a\nb\nc\nd\ne\nf");
-        let items = view.apply_context_collapsing(lines);
+        let items = view.apply_context_collapsing(&lines);
         assert!(!items.is_empty());
     }
+
+    fn synthetic_diff() -> (String, String) {
+        // 30 lines, with changes at lines 5 and 25
+        let old: Vec<String> = (1..=30).map(|i| format!("line {}", i)).collect();
+        let mut new = old.clone();
+        new[4] = "changed 5".to_string();
+        new[24] = "changed 25".to_string();
+        (old.join("\n"), new.join("\n"))
+    }
+
+    fn folds(items: &[DiffDisplayItem]) -> Vec<(usize, Range<usize>)> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                DiffDisplayItem::Collapsed { count, range, .. } => Some((*count, range.clone())),
+                DiffDisplayItem::Line(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn context_folding_places_folds_between_hunks() {
+        let (old, new) = synthetic_diff();
+        let view = DiffView::new(Diff::new("file.txt", new.clone()).old_text(old.clone()))
+            .context_lines(2);
+        let lines = view.compute_diff(&old, &new);
+        // Each change is a delete + insert pair
+        assert_eq!(lines.len(), 32);
+
+        let items = view.apply_context_collapsing(&lines);
+        // Leading lines 1-2 and trailing lines 28-30 are hidden without
+        // placeholders; lines 8-22 (indices 8..23 after the first hunk's two
+        // extra lines) are folded between the hunks.
+        assert_eq!(folds(&items), vec![(15, 8..23)]);
+
+        let view = view.show_edge_collapsed(true);
+        let items = view.apply_context_collapsing(&lines);
+        assert_eq!(folds(&items), vec![(2, 0..2), (15, 8..23), (3, 29..32)]);
+    }
+
+    #[test]
+    fn expanding_all_folds_restores_full_line_set() {
+        let (old, new) = synthetic_diff();
+        let view = DiffView::new(Diff::new("file.txt", new.clone()).old_text(old.clone()))
+            .context_lines(2)
+            .show_edge_collapsed(true);
+        let lines = view.compute_diff(&old, &new);
+        let items = view.apply_context_collapsing(&lines);
+
+        let expanded: HashSet<u64> = folds(&items)
+            .iter()
+            .map(|(_, range)| fold_key(&lines[range.clone()]))
+            .collect();
+        let restored = DiffView::expand_folds(items, &lines, &expanded);
+        assert_eq!(restored.len(), lines.len());
+        assert!(
+            restored
+                .iter()
+                .zip(&lines)
+                .all(|(item, line)| matches!(item, DiffDisplayItem::Line(l) if format!("{:?}", l) == format!("{:?}", line)))
+        );
+
+        // Expanding just one fold reveals only that fold's lines in place
+        let items = view.apply_context_collapsing(&lines);
+        let partial =
+            DiffView::expand_folds(items, &lines, &HashSet::from([fold_key(&lines[8..23])]));
+        assert_eq!(folds(&partial), vec![(2, 0..2), (3, 29..32)]);
+    }
+
+    #[test]
+    fn expanded_fold_survives_lines_added_before_it() {
+        let (old, new) = synthetic_diff();
+        let view = DiffView::new(Diff::new("file.txt", new.clone()).old_text(old.clone()))
+            .context_lines(2);
+        let lines = view.compute_diff(&old, &new);
+        let (_, range) = folds(&view.apply_context_collapsing(&lines))[0].clone();
+        let expanded = HashSet::from([fold_key(&lines[range])]);
+
+        // The agent inserts a line at the top; the fold moves down by one line
+        let new = format!("header\n{}", new);
+        let lines = view.compute_diff(&old, &new);
+        let items = view.apply_context_collapsing(&lines);
+        assert_eq!(folds(&items).len(), 1);
+        assert!(folds(&DiffView::expand_folds(items, &lines, &expanded)).is_empty());
+    }
+
+    #[test]
+    fn word_diff_ranges_isolate_single_word_change() {
+        let (old, new) = word_diff_ranges("let foo = compute(x);", "let bar = compute(x);");
+        assert_eq!(old, vec![(4..7)]);
+        assert_eq!(new, vec![(4..7)]);
+
+        let (old, new) = word_diff_ranges("value = 1", "value = 1000");
+        assert_eq!(old, vec![(8..9)]);
+        assert_eq!(new, vec![(8..12)]);
+    }
+
+    #[test]
+    fn compute_diff_pairs_modified_lines_for_word_level() {
+        let old = "fn main() {\n    let foo = 1;\n}";
+        let new = "fn main() {\n    let bar = 1;\n}";
+        let view = DiffView::new(Diff::new("main.rs", new.to_string()).old_text(old.to_string()));
+
+        let lines = view.compute_diff(old, new);
+        let delete = lines.iter().find_map(|l| match l {
+            DiffLine::Delete { changed, .. } => Some(changed.clone()),
+            _ => None,
+        });
+        let insert = lines.iter().find_map(|l| match l {
+            DiffLine::Insert { changed, .. } => Some(changed.clone()),
+            _ => None,
+        });
+        assert_eq!(delete, Some(vec![(8..11)]));
+        assert_eq!(insert, Some(vec![(8..11)]));
+
+        let view = view.word_level(false);
+        let lines = view.compute_diff(old, new);
+        assert!(lines.iter().all(|l| match l {
+            DiffLine::Insert { changed, .. } | DiffLine::Delete { changed, .. } =>
+                changed.is_empty(),
+            DiffLine::Context { .. } => true,
+        }));
+    }
 }
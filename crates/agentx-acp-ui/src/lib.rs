@@ -1,7 +1,9 @@
 mod agent_message;
 mod agent_thought;
 mod agent_todo_list;
+mod ansi;
 mod diff_summary;
+mod diff_syntax;
 mod diff_view;
 mod message_stream;
 mod permission_request;
@@ -11,24 +13,39 @@ mod utils;
 
 pub use agent_message::{
     AgentIconProvider, AgentMessage, AgentMessageData, AgentMessageMeta, AgentMessageOptions,
-    AgentMessageView,
+    AgentMessageView, CodeBlock, TimestampFormatter, extract_code_blocks,
+};
+pub use agent_thought::{AgentThoughtItem, thought_summary};
+pub use agent_todo_list::{
+    AgentTodoList, AgentTodoListOptions, AgentTodoListView, PlanMeta, TodoListChangeHandler,
+    reorder_entries, toggle_status,
 };
-pub use agent_thought::AgentThoughtItem;
-pub use agent_todo_list::{AgentTodoList, AgentTodoListView, PlanMeta};
 pub use diff_summary::{
     DiffSummary, DiffSummaryData, DiffSummaryOptions, DiffSummaryToolCallHandler, FileChangeStats,
 };
 pub use diff_view::{DiffDisplayItem, DiffLine, DiffView, DiffViewConfig};
-pub use message_stream::{AcpMessageStream, AcpMessageStreamOptions};
+pub use message_stream::{
+    AcpMessageStream, AcpMessageStreamOptions, MESSAGE_STREAM_CONTEXT, MessageStreamEvent,
+    ScrollAnchor, SelectNextToolCall, SelectPrevToolCall, StreamMatch, ToggleSelectedToolCall,
+    ToggleStreamSearch,
+};
 pub use permission_request::{
-    PermissionRequest, PermissionRequestOptions, PermissionRequestView, PermissionResponseHandler,
+    PermissionLookupHandler, PermissionRememberHandler, PermissionRequest,
+    PermissionRequestOptions, PermissionRequestView, PermissionResponseHandler,
     permission_is_allow, permission_option_kind_to_icon,
 };
 pub use tool_call_item::{
-    ToolCallDetailHandler, ToolCallItem, ToolCallItemOptions, ToolCallItemView,
+    ToolCallDetailHandler, ToolCallGroupHeader, ToolCallItem, ToolCallItemOptions,
+    ToolCallItemView, ToolCallRetryHandler, ToolKindLabeler, aggregate_tool_call_status,
+    group_consecutive_tool_calls, tool_call_export_json, tool_kind_label,
 };
 pub use user_message::{
-    ResourceInfo, UserMessage, UserMessageData, UserMessageView, get_resource_info,
+    ResourceInfo, ResourceKind, SLASH_COMMAND_META_KEY, UserMessage, UserMessageData,
+    UserMessageView, get_resource_info, user_display_text,
 };
 
-pub use utils::{extract_terminal_output, extract_xml_content, truncate_lines};
+pub use ansi::{AnsiColor, AnsiStyle, StyledSpan, parse_ansi, strip_ansi};
+pub use utils::{
+    TruncationInfo, extract_terminal_output, extract_terminal_output_styled, extract_xml_content,
+    truncate_lines, truncate_lines_with_info,
+};
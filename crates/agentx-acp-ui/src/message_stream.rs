@@ -1,44 +1,207 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::time::SystemTime;
 
 use agent_client_protocol::{
-    ContentBlock, ContentChunk, Plan, PlanEntryStatus, SessionUpdate, ToolCall, ToolCallUpdate,
+    ContentBlock, ContentChunk, Plan, PlanEntry, PlanEntryStatus, SessionUpdate, ToolCall,
+    ToolCallStatus, ToolCallUpdate, ToolKind,
 };
 use gpui::{
-    App, Context, Entity, IntoElement, ParentElement, Render, Styled, Window, div, prelude::*,
+    App, Context, Entity, EventEmitter, FocusHandle, HighlightStyle, IntoElement, MouseButton,
+    ParentElement, Pixels, Render, ScrollHandle, Styled, StyledText, Subscription, Window, actions,
+    div, point, prelude::*, px,
+};
+use gpui_component::{
+    ActiveTheme, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
 };
-use gpui_component::{ActiveTheme, v_flex};
 
 use crate::agent_thought::AgentThoughtItem;
-use crate::user_message::{ResourceItem, get_resource_info};
+use crate::user_message::{ResourceItem, get_resource_info, user_display_text};
 use crate::{
     AgentIconProvider, AgentMessage, AgentMessageData, AgentMessageOptions, AgentTodoList,
-    DiffSummary, DiffSummaryData, DiffSummaryOptions, PermissionRequestView, ToolCallItem,
-    ToolCallItemOptions, UserMessageData, UserMessageView,
+    AgentTodoListOptions, DiffSummary, DiffSummaryData, DiffSummaryOptions, PermissionRequestView,
+    TimestampFormatter, ToolCallGroupHeader, ToolCallItem, ToolCallItemOptions, ToolKindLabeler,
+    UserMessageData, UserMessageView, aggregate_tool_call_status, group_consecutive_tool_calls,
+    reorder_entries, toggle_status,
 };
 
+actions!(
+    acp_message_stream,
+    [
+        ToggleStreamSearch,
+        SelectNextToolCall,
+        SelectPrevToolCall,
+        ToggleSelectedToolCall
+    ]
+);
+
+/// Key context of the message stream's items, for binding
+/// [`ToggleStreamSearch`] and the tool call navigation actions. The find bar
+/// sits outside it, so keys typed into the find input reach the input.
+pub const MESSAGE_STREAM_CONTEXT: &str = "AcpMessageStream";
+
+/// Space kept above a match when scrolling to it
+const MATCH_SCROLL_MARGIN: f32 = 16.;
+
+/// Bytes of a line kept on either side of the hits in a find excerpt
+const EXCERPT_CONTEXT: usize = 40;
+
+/// Distance from the bottom within which the view counts as "at the bottom"
+const AUTO_SCROLL_THRESHOLD_PX: f32 = 120.;
+
 #[derive(Clone)]
 pub struct AcpMessageStreamOptions {
     pub agent_icon_provider: AgentIconProvider,
+    /// How message times are displayed; without one no times are shown
+    pub timestamp_formatter: Option<TimestampFormatter>,
+    /// How tool kinds are named in tool call group headers; English without one
+    pub tool_kind_labeler: Option<ToolKindLabeler>,
     pub tool_call_item_options: ToolCallItemOptions,
     pub diff_summary_options: DiffSummaryOptions,
+    /// Editing of the agent's plans; `on_change` gets the edited entries
+    pub todo_list_options: AgentTodoListOptions,
+    /// Enable the find bar (toggled with [`ToggleStreamSearch`])
+    pub searchable: bool,
+    /// Scroll handle of the container the stream is scrolled in, used to
+    /// bring matches into view
+    pub scroll_handle: Option<ScrollHandle>,
+    /// Render agent thoughts; hide them for a less noisy stream
+    pub show_thoughts: bool,
+    /// Offer to edit sent user messages, see [`MessageStreamEvent::EditUserMessage`]
+    pub editable_user_messages: bool,
+    /// Follow new output to the bottom; when off, a "jump to latest" pill
+    /// announces it instead
+    pub auto_scroll: bool,
+}
+
+/// Events emitted by [`AcpMessageStream`]
+#[derive(Clone, Debug)]
+pub enum MessageStreamEvent {
+    /// The user asked to edit the user message that opens turn `turn` (0-based)
+    EditUserMessage {
+        turn: usize,
+        contents: Vec<ContentBlock>,
+    },
 }
 
 impl Default for AcpMessageStreamOptions {
     fn default() -> Self {
         Self {
             agent_icon_provider: AgentMessageOptions::default().icon_provider,
+            timestamp_formatter: None,
+            tool_kind_labeler: None,
             tool_call_item_options: ToolCallItemOptions::default(),
             diff_summary_options: DiffSummaryOptions::default(),
+            todo_list_options: AgentTodoListOptions::default(),
+            searchable: false,
+            scroll_handle: None,
+            show_thoughts: true,
+            editable_user_messages: false,
+            auto_scroll: true,
+        }
+    }
+}
+
+/// A search hit inside one stream item
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamMatch {
+    /// Index of the item in the stream
+    pub item_index: usize,
+    /// Byte range of the hit in the item's searchable text
+    pub range: Range<usize>,
+}
+
+/// Decides whether new output keeps the view pinned to the bottom.
+///
+/// Auto-scroll turns off when the user scrolls away from the bottom and back
+/// on when they return; output arriving meanwhile raises a "jump to latest"
+/// indicator instead of moving the view. With following turned off, output
+/// never moves the view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScrollAnchor {
+    auto_scroll: bool,
+    has_unseen: bool,
+    follow_output: bool,
+}
+
+impl Default for ScrollAnchor {
+    fn default() -> Self {
+        Self {
+            auto_scroll: true,
+            has_unseen: false,
+            follow_output: true,
         }
     }
 }
 
+impl ScrollAnchor {
+    /// Whether new output should scroll the view to the bottom
+    pub fn auto_scroll(&self) -> bool {
+        self.follow_output && self.auto_scroll
+    }
+
+    /// Turn following new output on or off
+    pub fn set_follow_output(&mut self, follow: bool) {
+        self.follow_output = follow;
+    }
+
+    /// Whether to show the "jump to latest" pill
+    pub fn show_jump_to_latest(&self) -> bool {
+        !self.auto_scroll && self.has_unseen
+    }
+
+    /// Record the current scroll position as distance from the bottom
+    pub fn on_scroll(&mut self, distance_to_bottom: Pixels) {
+        self.auto_scroll = distance_to_bottom <= px(AUTO_SCROLL_THRESHOLD_PX);
+        if self.auto_scroll {
+            self.has_unseen = false;
+        }
+    }
+
+    /// Record new output; returns true if the view should scroll to the bottom
+    pub fn on_content_added(&mut self) -> bool {
+        if !self.auto_scroll() {
+            // Output lands below the view, which is no longer at the bottom
+            self.auto_scroll = false;
+            self.has_unseen = true;
+        }
+        self.auto_scroll
+    }
+
+    /// The user asked to jump to the latest output
+    pub fn jump_to_latest(&mut self) {
+        self.auto_scroll = true;
+        self.has_unseen = false;
+    }
+}
+
+/// Find bar state
+struct StreamSearch {
+    input: Entity<InputState>,
+    matches: Vec<StreamMatch>,
+    active: usize,
+    _subscription: Subscription,
+}
+
 /// Message stream UI for ACP SessionUpdate rendering.
 pub struct AcpMessageStream {
     items: Vec<RenderedItem>,
     index: UpdateStateIndex,
     next_index: usize,
     options: AcpMessageStreamOptions,
+    /// Tracks the bounds of each rendered item
+    item_scroll_handle: ScrollHandle,
+    focus_handle: Option<FocusHandle>,
+    search: Option<StreamSearch>,
+    anchor: ScrollAnchor,
+    /// Start indices of tool call groups the user has expanded
+    expanded_tool_groups: HashSet<usize>,
+    /// Position among the tool call items of the one selected from the keyboard
+    selected_tool_call: Option<usize>,
 }
 
 impl AcpMessageStream {
@@ -47,11 +210,21 @@ impl AcpMessageStream {
     }
 
     pub fn with_options(options: AcpMessageStreamOptions) -> Self {
+        let auto_scroll = options.auto_scroll;
         Self {
             items: Vec::new(),
             index: UpdateStateIndex::new(),
             next_index: 0,
             options,
+            item_scroll_handle: ScrollHandle::new(),
+            focus_handle: None,
+            search: None,
+            anchor: ScrollAnchor {
+                follow_output: auto_scroll,
+                ..ScrollAnchor::default()
+            },
+            expanded_tool_groups: HashSet::new(),
+            selected_tool_call: None,
         }
     }
 
@@ -77,6 +250,43 @@ impl AcpMessageStream {
         })
     }
 
+    pub fn show_thoughts(&self) -> bool {
+        self.options.show_thoughts
+    }
+
+    /// Show or hide agent thoughts
+    pub fn set_show_thoughts(&mut self, show: bool, cx: &mut Context<Self>) {
+        if self.options.show_thoughts != show {
+            self.options.show_thoughts = show;
+            self.refresh_search(cx);
+            cx.notify();
+        }
+    }
+
+    /// Follow new output to the bottom, or leave the view where it is
+    pub fn set_auto_scroll(&mut self, auto_scroll: bool, cx: &mut Context<Self>) {
+        if self.options.auto_scroll != auto_scroll {
+            self.options.auto_scroll = auto_scroll;
+            self.anchor.set_follow_output(auto_scroll);
+            cx.notify();
+        }
+    }
+
+    /// Whether the latest item is a thought the agent is still streaming.
+    pub fn thought_in_flight(&self) -> bool {
+        matches!(self.items.last(), Some(RenderedItem::AgentThought(..)))
+    }
+
+    /// Whether any tool call is still pending or running.
+    pub fn tool_call_in_flight(&self, cx: &App) -> bool {
+        self.items.iter().rev().any(|item| {
+            matches!(item, RenderedItem::ToolCall(entity) if matches!(
+                entity.read(cx).tool_call().status,
+                ToolCallStatus::Pending | ToolCallStatus::InProgress
+            ))
+        })
+    }
+
     /// Process a SessionUpdate and add/update items.
     pub fn process_update(
         &mut self,
@@ -96,20 +306,116 @@ impl AcpMessageStream {
 
         processor.process_update(update, cx);
         self.next_index += 1;
+        self.content_added();
+        self.refresh_search(cx);
         cx.notify();
     }
 
+    /// Drop the last user message and everything after it, e.g. before the
+    /// turn is sent again. Returns false if there is no user message.
+    pub fn truncate_at_last_user_message(&mut self, cx: &mut Context<Self>) -> bool {
+        match self.user_message_positions().last() {
+            Some(&start) => {
+                self.truncate_items(start, cx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop the user message that opens turn `turn` (0-based) and everything
+    /// after it. Returns false if there is no such turn.
+    pub fn truncate_at_user_message(&mut self, turn: usize, cx: &mut Context<Self>) -> bool {
+        match self.user_message_positions().get(turn) {
+            Some(&start) => {
+                self.truncate_items(start, cx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn user_message_positions(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| matches!(item, RenderedItem::UserMessage(..)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn truncate_items(&mut self, len: usize, cx: &mut Context<Self>) {
+        self.items.truncate(len);
+        self.index.rebuild(&self.items, cx);
+        // New chunks must not merge into messages of the dropped turn
+        self.index.clear_streaming_state();
+        self.index.clear_user_message_state();
+        self.expanded_tool_groups.retain(|start| *start < len);
+        self.search = None;
+        self.selected_tool_call = None;
+        cx.notify();
+    }
+
+    /// Scroll-anchoring state, for rendering the "jump to latest" pill
+    pub fn scroll_anchor(&self) -> ScrollAnchor {
+        self.anchor
+    }
+
+    /// Re-read the scroll position after the user scrolled
+    pub fn sync_scroll_position(&mut self, cx: &mut Context<Self>) {
+        let Some(distance) = self.distance_to_bottom() else {
+            return;
+        };
+        let before = self.anchor;
+        self.anchor.on_scroll(distance);
+        if self.anchor != before {
+            cx.notify();
+        }
+    }
+
+    /// Scroll to the newest output and pin the view there again
+    pub fn jump_to_latest(&mut self, cx: &mut Context<Self>) {
+        self.anchor.jump_to_latest();
+        if let Some(scroll_handle) = self.options.scroll_handle.as_ref() {
+            scroll_handle.scroll_to_bottom();
+        }
+        cx.notify();
+    }
+
+    fn distance_to_bottom(&self) -> Option<Pixels> {
+        let scroll_handle = self.options.scroll_handle.as_ref()?;
+        // Offsets grow negative as the content scrolls up
+        Some(scroll_handle.max_offset().height + scroll_handle.offset().y)
+    }
+
+    /// Keep the view pinned to the bottom if it was there before new output
+    fn content_added(&mut self) {
+        // Picks up scrolls that happened since the last sync
+        if let Some(distance) = self.distance_to_bottom() {
+            self.anchor.on_scroll(distance);
+        }
+        if self.anchor.on_content_added() {
+            if let Some(scroll_handle) = self.options.scroll_handle.as_ref() {
+                scroll_handle.scroll_to_bottom();
+            }
+        }
+    }
+
     pub fn add_permission_request(
         &mut self,
         request: Entity<PermissionRequestView>,
         cx: &mut Context<Self>,
     ) {
         self.items.push(RenderedItem::PermissionRequest(request));
+        self.content_added();
+        self.refresh_search(cx);
         cx.notify();
     }
 
     pub fn add_info_update(&mut self, text: impl Into<String>, cx: &mut Context<Self>) {
         self.items.push(RenderedItem::InfoUpdate(text.into()));
+        self.content_added();
+        self.refresh_search(cx);
         cx.notify();
     }
 
@@ -133,6 +439,307 @@ impl AcpMessageStream {
         }
     }
 
+    /// Find `query` (case-insensitive) in the text of every item
+    pub fn find(&self, query: &str, cx: &App) -> Vec<StreamMatch> {
+        search_items(
+            self.items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| {
+                    self.options.show_thoughts || !matches!(item, RenderedItem::AgentThought(..))
+                })
+                .filter_map(|(index, item)| Some((index, item.searchable_text(cx)?))),
+            query,
+        )
+    }
+
+    /// Re-run the open find bar's query over the current items, keeping the
+    /// active match where it was when it still matches
+    fn refresh_search(&mut self, cx: &App) {
+        let Some(query) = self
+            .search
+            .as_ref()
+            .map(|search| search.input.read(cx).value().to_string())
+        else {
+            return;
+        };
+        let matches = self.find(&query, cx);
+        if let Some(search) = self.search.as_mut() {
+            let active = search.matches.get(search.active);
+            search.active = active
+                .and_then(|active| matches.iter().position(|m| m == active))
+                .unwrap_or_else(|| search.active.min(matches.len().saturating_sub(1)));
+            search.matches = matches;
+        }
+    }
+
+    /// Scroll the item holding the `index`-th match of the open find bar into view
+    pub fn scroll_to_match(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        let Some(item_index) = search.matches.get(index).map(|m| m.item_index) else {
+            return;
+        };
+        search.active = index;
+        self.scroll_to_item(item_index, cx);
+    }
+
+    /// Scroll the item at `item_index` into view, expanding the collapsed
+    /// tool call group it may be hidden in
+    fn scroll_to_item(&mut self, item_index: usize, cx: &mut Context<Self>) {
+        if let Some(range) = self
+            .tool_call_groups(cx)
+            .into_values()
+            .find(|range| range.contains(&item_index))
+        {
+            self.expanded_tool_groups.insert(range.start);
+        }
+
+        if let (Some(scroll_handle), Some(item_bounds)) = (
+            self.options.scroll_handle.as_ref(),
+            self.item_scroll_handle.bounds_for_item(item_index),
+        ) {
+            let viewport = scroll_handle.bounds();
+            let offset = scroll_handle.offset();
+            let max_offset = scroll_handle.max_offset().height;
+            let delta = item_bounds.top() - viewport.top() - px(MATCH_SCROLL_MARGIN);
+            let y = (offset.y - delta).clamp(-max_offset, px(0.));
+            scroll_handle.set_offset(point(offset.x, y));
+        }
+        cx.notify();
+    }
+
+    /// Open or close the find bar
+    pub fn toggle_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search.take().is_some() {
+            cx.notify();
+            return;
+        }
+        if !self.options.searchable {
+            return;
+        }
+
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Find in conversation"));
+        let subscription =
+            cx.subscribe(&input, |this, input, event: &InputEvent, cx| match event {
+                InputEvent::Change => {
+                    let query = input.read(cx).value().to_string();
+                    let matches = this.find(&query, cx);
+                    if let Some(search) = this.search.as_mut() {
+                        search.matches = matches;
+                        search.active = 0;
+                    }
+                    this.scroll_to_match(0, cx);
+                }
+                InputEvent::PressEnter { .. } => this.select_next_match(cx),
+                _ => {}
+            });
+        input.update(cx, |input, cx| input.focus(window, cx));
+        self.search = Some(StreamSearch {
+            input,
+            matches: Vec::new(),
+            active: 0,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    fn select_next_match(&mut self, cx: &mut Context<Self>) {
+        if let Some(search) = self.search.as_ref().filter(|s| !s.matches.is_empty()) {
+            let next = (search.active + 1) % search.matches.len();
+            self.scroll_to_match(next, cx);
+        }
+    }
+
+    fn select_prev_match(&mut self, cx: &mut Context<Self>) {
+        if let Some(search) = self.search.as_ref().filter(|s| !s.matches.is_empty()) {
+            let len = search.matches.len();
+            let prev = (search.active + len - 1) % len;
+            self.scroll_to_match(prev, cx);
+        }
+    }
+
+    /// Item indices of the tool calls in the stream, in display order
+    fn tool_call_positions(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| matches!(item, RenderedItem::ToolCall(..)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Move the tool call selection forward or backward and scroll it into view
+    pub fn select_tool_call(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let positions = self.tool_call_positions();
+        let Some(selected) = step_selection(self.selected_tool_call, positions.len(), forward)
+        else {
+            return;
+        };
+        self.selected_tool_call = Some(selected);
+        self.scroll_to_item(positions[selected], cx);
+    }
+
+    /// Expand or collapse the detail of the selected tool call
+    pub fn toggle_selected_tool_call(&mut self, cx: &mut Context<Self>) {
+        let Some(item_index) = self
+            .selected_tool_call
+            .and_then(|selected| self.tool_call_positions().get(selected).copied())
+        else {
+            return;
+        };
+        if let Some(RenderedItem::ToolCall(entity)) = self.items.get(item_index) {
+            entity.update(cx, |item, cx| item.toggle(cx));
+        }
+    }
+
+    /// Expand or collapse the tool call group starting at `start`
+    pub fn toggle_tool_group(&mut self, start: usize, cx: &mut Context<Self>) {
+        if !self.expanded_tool_groups.remove(&start) {
+            self.expanded_tool_groups.insert(start);
+        }
+        cx.notify();
+    }
+
+    /// Groups of consecutive same-kind tool calls, keyed by the index of
+    /// their first item
+    fn tool_call_groups(&self, cx: &App) -> HashMap<usize, Range<usize>> {
+        if !self.options.tool_call_item_options.group_consecutive {
+            return HashMap::new();
+        }
+
+        let kinds = self
+            .items
+            .iter()
+            .map(|item| match item {
+                RenderedItem::ToolCall(entity) => Some(entity.read(cx).tool_call().kind),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        group_consecutive_tool_calls(&kinds)
+            .into_iter()
+            .map(|range| (range.start, range))
+            .collect()
+    }
+
+    fn render_tool_group_header(
+        &self,
+        range: &Range<usize>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let tool_calls = self.items[range.clone()]
+            .iter()
+            .filter_map(|item| match item {
+                RenderedItem::ToolCall(entity) => Some(entity.read(cx).tool_call()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let kind = tool_calls
+            .first()
+            .map(|tool_call| tool_call.kind)
+            .unwrap_or(ToolKind::Other);
+        let status = aggregate_tool_call_status(tool_calls.iter().map(|tc| &tc.status));
+        let start = range.start;
+        let view = cx.entity().downgrade();
+
+        ToolCallGroupHeader::new(
+            format!("tool-call-group-{}", start),
+            kind,
+            range.len(),
+            status,
+        )
+        .open(self.expanded_tool_groups.contains(&start))
+        .labeler(self.options.tool_kind_labeler.clone())
+        .on_toggle(move |_, cx| {
+            _ = view.update(cx, |this, cx| this.toggle_tool_group(start, cx));
+        })
+    }
+
+    fn render_search_bar(&self, search: &StreamSearch, cx: &mut Context<Self>) -> impl IntoElement {
+        let count = if search.matches.is_empty() {
+            "0/0".to_string()
+        } else {
+            format!("{}/{}", search.active + 1, search.matches.len())
+        };
+
+        h_flex()
+            .gap_1()
+            .items_center()
+            .w_full()
+            .on_key_down(cx.listener(|this, event: &gpui::KeyDownEvent, _, cx| {
+                if event.keystroke.key == "escape" {
+                    this.search = None;
+                    cx.notify();
+                }
+            }))
+            .child(div().flex_1().child(Input::new(&search.input).small()))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(count),
+            )
+            .child(
+                Button::new("stream-search-prev")
+                    .icon(IconName::ChevronUp)
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, _, cx| this.select_prev_match(cx))),
+            )
+            .child(
+                Button::new("stream-search-next")
+                    .icon(IconName::ChevronDown)
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, _, cx| this.select_next_match(cx))),
+            )
+            .child(
+                Button::new("stream-search-close")
+                    .icon(IconName::Close)
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.search = None;
+                        cx.notify();
+                    })),
+            )
+    }
+
+    /// Lines of an item holding find hits, with the hits highlighted
+    fn render_match_excerpts(
+        &self,
+        excerpts: Vec<MatchExcerpt>,
+        active_match: Option<usize>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let accent = cx.theme().accent;
+        v_flex()
+            .pl_6()
+            .gap_1()
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .children(excerpts.into_iter().map(|excerpt| {
+                let highlights = excerpt
+                    .hits
+                    .into_iter()
+                    .map(|(match_index, range)| {
+                        let opacity = if active_match == Some(match_index) {
+                            0.6
+                        } else {
+                            0.25
+                        };
+                        let style = HighlightStyle {
+                            background_color: Some(accent.opacity(opacity)),
+                            ..Default::default()
+                        };
+                        (range, style)
+                    })
+                    .collect::<Vec<_>>();
+                div().child(StyledText::new(excerpt.text).with_highlights(highlights))
+            }))
+    }
+
     fn collect_tool_calls(&self, cx: &App) -> Vec<ToolCall> {
         let mut tool_calls = Vec::new();
 
@@ -149,59 +756,197 @@ impl AcpMessageStream {
 
 impl Render for AcpMessageStream {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let mut children = v_flex().gap_3().w_full();
+        let focus_handle = self
+            .focus_handle
+            .get_or_insert_with(|| cx.focus_handle())
+            .clone();
+
+        // Hits of the open find bar per item, as (match index, byte range)
+        let mut item_hits: HashMap<usize, Vec<(usize, Range<usize>)>> = HashMap::new();
+        let mut active_match = None;
+        if let Some(search) = self.search.as_ref() {
+            for (match_index, m) in search.matches.iter().enumerate() {
+                item_hits
+                    .entry(m.item_index)
+                    .or_default()
+                    .push((match_index, m.range.clone()));
+            }
+            active_match = Some(search.active);
+        }
 
-        for item in &self.items {
-            match item {
-                RenderedItem::UserMessage(entity) => {
-                    children = children.child(entity.clone());
+        let selected_item = self
+            .selected_tool_call
+            .and_then(|selected| self.tool_call_positions().get(selected).copied());
+
+        let tool_groups = self.tool_call_groups(cx);
+        let mut collapsed_until = 0;
+        let mut first_visible = true;
+
+        // Each item is exactly one child so `item_scroll_handle` bounds line up
+        // with item indices. Items hidden inside a collapsed tool call group
+        // still get an empty child.
+        let mut children = v_flex()
+            .id("acp-message-stream-items")
+            .key_context(MESSAGE_STREAM_CONTEXT)
+            .track_focus(&focus_handle)
+            .w_full()
+            .track_scroll(&self.item_scroll_handle)
+            .on_mouse_down(MouseButton::Left, move |_, window, _| {
+                focus_handle.focus(window)
+            })
+            .on_action(cx.listener(|this, _: &ToggleStreamSearch, window, cx| {
+                this.toggle_search(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &SelectNextToolCall, _, cx| {
+                this.select_tool_call(true, cx);
+            }))
+            .on_action(cx.listener(|this, _: &SelectPrevToolCall, _, cx| {
+                this.select_tool_call(false, cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleSelectedToolCall, _, cx| {
+                this.toggle_selected_tool_call(cx);
+            }));
+
+        let mut user_turn = 0;
+        for (item_index, item) in self.items.iter().enumerate() {
+            let turn = user_turn;
+            if matches!(item, RenderedItem::UserMessage(..)) {
+                user_turn += 1;
+            }
+
+            let group_header = tool_groups.get(&item_index).map(|range| {
+                if !self.expanded_tool_groups.contains(&item_index) {
+                    collapsed_until = range.end;
                 }
-                RenderedItem::AgentMessage(id, data) => {
-                    let msg = AgentMessage::new(get_element_id(id), data.clone())
-                        .icon_provider(self.options.agent_icon_provider.clone());
-                    children = children.child(msg);
+                v_flex()
+                    .pl_6()
+                    .child(self.render_tool_group_header(range, cx))
+            });
+            let collapsed = item_index < collapsed_until;
+            let hidden_thought =
+                !self.options.show_thoughts && matches!(item, RenderedItem::AgentThought(..));
+            if (collapsed && group_header.is_none()) || hidden_thought {
+                children = children.child(div());
+                continue;
+            }
+
+            let element = match item {
+                RenderedItem::UserMessage(entity) if self.options.editable_user_messages => {
+                    let message = entity.clone();
+                    v_flex()
+                        .child(entity.clone())
+                        .child(
+                            h_flex().pl_6().child(
+                                Button::new(("edit-user-message", turn))
+                                    .label("Edit")
+                                    .ghost()
+                                    .xsmall()
+                                    .on_click(cx.listener(move |_, _, _, cx| {
+                                        let contents =
+                                            message.read(cx).data.read(cx).contents.clone();
+                                        cx.emit(MessageStreamEvent::EditUserMessage {
+                                            turn,
+                                            contents,
+                                        });
+                                    })),
+                            ),
+                        )
+                        .into_any_element()
                 }
-                RenderedItem::AgentThought(entity) => {
-                    children = children.child(entity.clone());
+                RenderedItem::UserMessage(entity) => entity.clone().into_any_element(),
+                RenderedItem::AgentMessage(id, data) => {
+                    AgentMessage::new(get_element_id(id), data.clone())
+                        .icon_provider(self.options.agent_icon_provider.clone())
+                        .timestamp_formatter(self.options.timestamp_formatter.clone())
+                        .into_any_element()
                 }
+                RenderedItem::AgentThought(entity) => entity.clone().into_any_element(),
                 RenderedItem::Plan(plan) => {
-                    let todo_list = AgentTodoList::from_plan(plan.clone());
-                    children = children.child(v_flex().pl_6().child(todo_list));
+                    let mut todo_list = AgentTodoList::from_plan(plan.clone());
+                    if self.options.todo_list_options.editable {
+                        let stream = cx.entity().downgrade();
+                        let toggle_stream = stream.clone();
+                        todo_list = todo_list
+                            .on_toggle(move |index, window, cx| {
+                                let _ = toggle_stream.update(cx, |this, cx| {
+                                    this.edit_plan(item_index, window, cx, |entries| {
+                                        if let Some(entry) = entries.get_mut(index) {
+                                            entry.status = toggle_status(&entry.status);
+                                        }
+                                    });
+                                });
+                            })
+                            .on_move(move |from, to, window, cx| {
+                                let _ = stream.update(cx, |this, cx| {
+                                    this.edit_plan(item_index, window, cx, |entries| {
+                                        reorder_entries(entries, from, to)
+                                    });
+                                });
+                            });
+                    }
+                    v_flex().pl_6().child(todo_list).into_any_element()
                 }
                 RenderedItem::ToolCall(entity) => {
-                    children = children.child(v_flex().pl_6().child(entity.clone()));
+                    v_flex().pl_6().child(entity.clone()).into_any_element()
                 }
                 RenderedItem::PermissionRequest(entity) => {
-                    children = children.child(v_flex().pl_6().child(entity.clone()));
-                }
-                RenderedItem::DiffSummary(entity) => {
-                    children = children.child(entity.clone());
+                    v_flex().pl_6().child(entity.clone()).into_any_element()
                 }
-                RenderedItem::InfoUpdate(text) => {
-                    children = children.child(
-                        div().pl_6().child(
-                            div()
-                                .p_2()
-                                .rounded(cx.theme().radius)
-                                .bg(cx.theme().muted.opacity(0.5))
-                                .border_1()
-                                .border_color(cx.theme().border.opacity(0.3))
-                                .child(
-                                    div()
-                                        .text_xs()
-                                        .text_color(cx.theme().muted_foreground)
-                                        .child(text.clone()),
-                                ),
-                        ),
-                    );
-                }
-            }
+                RenderedItem::DiffSummary(entity) => entity.clone().into_any_element(),
+                RenderedItem::InfoUpdate(text) => div()
+                    .pl_6()
+                    .child(
+                        div()
+                            .p_2()
+                            .rounded(cx.theme().radius)
+                            .bg(cx.theme().muted.opacity(0.5))
+                            .border_1()
+                            .border_color(cx.theme().border.opacity(0.3))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(text.clone()),
+                            ),
+                    )
+                    .into_any_element(),
+            };
+
+            let excerpts = item_hits
+                .get(&item_index)
+                .filter(|_| !collapsed)
+                .map(|hits| {
+                    let text = item.searchable_text(cx).unwrap_or_default();
+                    self.render_match_excerpts(match_excerpts(&text, hits), active_match, cx)
+                });
+            children = children.child(
+                v_flex()
+                    .w_full()
+                    .gap_2()
+                    .when(!first_visible, |this| this.mt_3())
+                    .rounded(cx.theme().radius)
+                    .when(selected_item == Some(item_index), |this| {
+                        this.border_1().border_color(cx.theme().primary)
+                    })
+                    .children(group_header)
+                    .when(!collapsed, |this| this.child(element))
+                    .children(excerpts),
+            );
+            first_visible = false;
         }
 
-        children
+        v_flex()
+            .gap_3()
+            .w_full()
+            .when_some(self.search.as_ref(), |this, search| {
+                this.child(self.render_search_bar(search, cx))
+            })
+            .child(children)
     }
 }
 
+impl EventEmitter<MessageStreamEvent> for AcpMessageStream {}
+
 // ============================================================================
 // Rendered Item
 // ============================================================================
@@ -220,6 +965,39 @@ enum RenderedItem {
 }
 
 impl RenderedItem {
+    /// Text matched by the stream's find, if the item has any
+    fn searchable_text(&self, cx: &App) -> Option<String> {
+        match self {
+            RenderedItem::UserMessage(entity) => {
+                let view = entity.read(cx);
+                let text = view
+                    .data
+                    .read(cx)
+                    .contents
+                    .iter()
+                    .filter_map(|content| match content {
+                        ContentBlock::Text(text) => Some(user_display_text(text)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                Some(text)
+            }
+            RenderedItem::AgentMessage(_, data) => Some(data.full_text().to_string()),
+            RenderedItem::AgentThought(entity) => Some(entity.read(cx).text().to_string()),
+            RenderedItem::Plan(plan) => Some(
+                plan.entries
+                    .iter()
+                    .map(|entry| entry.content.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            RenderedItem::ToolCall(entity) => Some(entity.read(cx).tool_call().title.clone()),
+            RenderedItem::InfoUpdate(text) => Some(text.clone()),
+            RenderedItem::PermissionRequest(_) | RenderedItem::DiffSummary(_) => None,
+        }
+    }
+
     /// Try to append an AgentMessageChunk to this item (returns true if successful)
     pub fn try_append_agent_message_chunk(&mut self, chunk: ContentChunk) -> bool {
         if let RenderedItem::AgentMessage(_id, data) = self {
@@ -491,7 +1269,12 @@ impl<'a> UpdateProcessor<'a> {
         }
 
         log::debug!("  └─ Creating UserMessage");
-        let item = create_user_message(chunk, self.session_id, cx);
+        let item = create_user_message(
+            chunk,
+            self.session_id,
+            self.options.timestamp_formatter.clone(),
+            cx,
+        );
         let new_index = self.items.len();
         self.items.push(item);
         self.index.set_last_user_message(new_index);
@@ -527,6 +1310,18 @@ impl<'a> UpdateProcessor<'a> {
             }
         }
 
+        // A chunk without text only carries meta (e.g. the turn's usage);
+        // attach it to the turn's reply instead of opening an empty bubble
+        if is_meta_only_chunk(&chunk) {
+            if let Some(data) = self.current_turn_agent_message() {
+                data.push_chunk(chunk);
+                log::debug!("  └─ Attached meta-only chunk to the turn's last AgentMessage");
+            } else {
+                log::debug!("  └─ Dropped meta-only chunk, no AgentMessage in this turn");
+            }
+            return;
+        }
+
         if self.index.last_thought().is_some() {
             self.complete_last_item();
         }
@@ -543,6 +1338,18 @@ impl<'a> UpdateProcessor<'a> {
         self.index.set_last_thought(new_index);
     }
 
+    /// Last agent message since the latest user message
+    fn current_turn_agent_message(&mut self) -> Option<&mut AgentMessageData> {
+        self.items
+            .iter_mut()
+            .rev()
+            .take_while(|item| !matches!(item, RenderedItem::UserMessage(..)))
+            .find_map(|item| match item {
+                RenderedItem::AgentMessage(_, data) => Some(data),
+                _ => None,
+            })
+    }
+
     fn process_agent_thought_chunk(
         &mut self,
         chunk: ContentChunk,
@@ -667,6 +1474,25 @@ impl<'a> UpdateProcessor<'a> {
         self.items.push(RenderedItem::Plan(plan));
     }
 
+    /// Apply a user edit to the plan at `item_index` and report the new entries
+    fn edit_plan(
+        &mut self,
+        item_index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        f: impl FnOnce(&mut Vec<PlanEntry>),
+    ) {
+        let Some(RenderedItem::Plan(plan)) = self.items.get_mut(item_index) else {
+            return;
+        };
+        f(&mut plan.entries);
+        let entries = plan.entries.clone();
+        if let Some(handler) = self.options.todo_list_options.on_change.clone() {
+            handler(entries, window, cx);
+        }
+        cx.notify();
+    }
+
     fn complete_last_item(&mut self) {
         if let Some(last_item) = self.items.last_mut() {
             last_item.mark_complete();
@@ -681,13 +1507,16 @@ impl<'a> UpdateProcessor<'a> {
 fn create_user_message<T>(
     chunk: ContentChunk,
     session_id: Option<&str>,
+    timestamp_formatter: Option<TimestampFormatter>,
     cx: &mut Context<T>,
 ) -> RenderedItem {
     let content_vec = vec![chunk.content.clone()];
     let session_id = session_id
         .map(str::to_string)
         .unwrap_or_else(|| "default-session".to_string());
-    let user_data = UserMessageData::new(session_id).with_contents(content_vec.clone());
+    let user_data = UserMessageData::new(session_id)
+        .with_contents(content_vec.clone())
+        .with_timestamp(SystemTime::now());
 
     let entity = cx.new(|cx| {
         let data_entity = cx.new(|_| user_data);
@@ -701,6 +1530,8 @@ fn create_user_message<T>(
         UserMessageView {
             data: data_entity,
             resource_items,
+            show_timestamp: true,
+            timestamp_formatter,
         }
     });
 
@@ -713,7 +1544,9 @@ fn create_agent_message_data(
     agent_name: Option<&str>,
 ) -> AgentMessageData {
     let session_id = session_id.unwrap_or("default-session").to_string();
-    let mut data = AgentMessageData::new(session_id).add_chunk(chunk);
+    let mut data = AgentMessageData::new(session_id)
+        .with_timestamp(SystemTime::now())
+        .add_chunk(chunk);
     if let Some(agent_name) = agent_name {
         data = data.with_agent_name(agent_name);
     }
@@ -743,6 +1576,12 @@ fn extract_text_from_content(content: &ContentBlock) -> String {
     }
 }
 
+/// Whether a chunk has no text and only exists to carry `_meta`
+fn is_meta_only_chunk(chunk: &ContentChunk) -> bool {
+    chunk.meta.is_some()
+        && matches!(&chunk.content, ContentBlock::Text(text) if text.text.is_empty())
+}
+
 fn session_update_type_name(update: &SessionUpdate) -> &'static str {
     match update {
         SessionUpdate::UserMessageChunk(_) => "UserMessageChunk",
@@ -757,6 +1596,134 @@ fn session_update_type_name(update: &SessionUpdate) -> &'static str {
     }
 }
 
+/// Collect every case-insensitive hit of `query` in the given (item index, text) pairs
+fn search_items(items: impl IntoIterator<Item = (usize, String)>, query: &str) -> Vec<StreamMatch> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    items
+        .into_iter()
+        .flat_map(|(item_index, text)| {
+            find_ranges(&text, query)
+                .into_iter()
+                .map(move |range| StreamMatch { item_index, range })
+        })
+        .collect()
+}
+
+/// Byte ranges of non-overlapping case-insensitive occurrences of `query` in `text`
+fn find_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let mut matched = 0;
+        let mut end = start;
+        for (offset, ch) in text[start..].char_indices() {
+            let lower: Vec<char> = ch.to_lowercase().collect();
+            if query[matched..].starts_with(&lower) {
+                matched += lower.len();
+                end = start + offset + ch.len_utf8();
+                if matched == query.len() {
+                    break;
+                }
+            } else {
+                matched = 0;
+                break;
+            }
+        }
+
+        if matched == query.len() {
+            ranges.push(start..end);
+            start = end;
+        } else {
+            start += text[start..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    ranges
+}
+
+/// A line of an item's text holding find hits, trimmed around them
+#[derive(Debug, PartialEq, Eq)]
+struct MatchExcerpt {
+    text: String,
+    /// Match index and byte range in `text` of each hit on the line
+    hits: Vec<(usize, Range<usize>)>,
+}
+
+/// Group `hits` (match index and byte range in `text`, in order) into one
+/// excerpt per line, keeping [`EXCERPT_CONTEXT`] bytes around them
+fn match_excerpts(text: &str, hits: &[(usize, Range<usize>)]) -> Vec<MatchExcerpt> {
+    let mut excerpts = Vec::new();
+    let mut line: Option<Range<usize>> = None;
+    let mut line_hits = Vec::new();
+
+    for (match_index, range) in hits {
+        let line_start = text[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        if line.as_ref().map(|line| line.start) != Some(line_start) {
+            if let Some(line) = line.take() {
+                excerpts.push(line_excerpt(text, line, std::mem::take(&mut line_hits)));
+            }
+            let line_end = text[range.start..]
+                .find('\n')
+                .map_or(text.len(), |i| range.start + i);
+            line = Some(line_start..line_end);
+        }
+        line_hits.push((*match_index, range.clone()));
+    }
+    if let Some(line) = line {
+        excerpts.push(line_excerpt(text, line, line_hits));
+    }
+    excerpts
+}
+
+fn line_excerpt(text: &str, line: Range<usize>, hits: Vec<(usize, Range<usize>)>) -> MatchExcerpt {
+    let first = hits.first().map_or(line.start, |(_, range)| range.start);
+    let last = hits.last().map_or(line.end, |(_, range)| range.end);
+
+    let mut start = first.saturating_sub(EXCERPT_CONTEXT).max(line.start);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (last + EXCERPT_CONTEXT).min(line.end);
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let prefix = if start > line.start { "…" } else { "" };
+    let suffix = if end < line.end { "…" } else { "" };
+    let shift = |offset: usize| offset - start + prefix.len();
+    MatchExcerpt {
+        text: format!("{}{}{}", prefix, &text[start..end], suffix),
+        hits: hits
+            .into_iter()
+            .map(|(match_index, range)| (match_index, shift(range.start)..shift(range.end)))
+            .collect(),
+    }
+}
+
+/// Next selection among `len` entries, wrapping around at either end.
+///
+/// Without a selection, moving forward picks the first entry and moving
+/// backward the last; a selection past the end (after entries were dropped)
+/// is clamped to the last entry first.
+fn step_selection(current: Option<usize>, len: usize, forward: bool) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    Some(match (current.map(|index| index.min(last)), forward) {
+        (None, true) => 0,
+        (None, false) => last,
+        (Some(index), true) if index == last => 0,
+        (Some(index), true) => index + 1,
+        (Some(0), false) => last,
+        (Some(index), false) => index - 1,
+    })
+}
+
 fn get_element_id(id: &str) -> gpui::ElementId {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
@@ -802,4 +1769,185 @@ mod tests {
         assert_eq!(index.last_message(), None);
         assert_eq!(index.last_thought(), None);
     }
+
+    #[test]
+    fn usage_only_chunks_are_meta_only() {
+        use crate::agent_message::USAGE_META_KEY;
+
+        let with_usage = |text: &str| {
+            let mut chunk = ContentChunk::new(ContentBlock::from(text.to_string()));
+            chunk.meta = Some(
+                [(USAGE_META_KEY.to_string(), serde_json::json!({}))]
+                    .into_iter()
+                    .collect(),
+            );
+            chunk
+        };
+
+        assert!(is_meta_only_chunk(&with_usage("")));
+        assert!(!is_meta_only_chunk(&with_usage("Done")));
+        assert!(!is_meta_only_chunk(&ContentChunk::new(ContentBlock::from(
+            String::new()
+        ))));
+    }
+
+    #[test]
+    fn search_matches_user_and_agent_items() {
+        let items = vec![
+            (0, "Please fix the Parser".to_string()),
+            (1, "I updated parser.rs and the parser tests".to_string()),
+            (2, "Done".to_string()),
+        ];
+        let matches = search_items(items, "parser");
+        assert_eq!(
+            matches,
+            vec![
+                StreamMatch {
+                    item_index: 0,
+                    range: 15..21,
+                },
+                StreamMatch {
+                    item_index: 1,
+                    range: 10..16,
+                },
+                StreamMatch {
+                    item_index: 1,
+                    range: 28..34,
+                },
+            ]
+        );
+        assert!(search_items(vec![(0, "text".to_string())], "").is_empty());
+    }
+
+    #[test]
+    fn find_ranges_is_case_insensitive() {
+        assert_eq!(
+            find_ranges("Hello HELLO hello", "hElLo"),
+            vec![0..5, 6..11, 12..17]
+        );
+        assert_eq!(find_ranges("ÄPFEL äpfel", "äpfel"), vec![0..6, 7..13]);
+        assert_eq!(find_ranges("aaa", "aa"), vec![(0..2)]);
+        assert!(find_ranges("abc", "abcd").is_empty());
+    }
+
+    #[test]
+    fn match_excerpts_group_hits_by_line() {
+        let text = "first parser line\nno hit here\nparser and parser";
+        let hits: Vec<_> = find_ranges(text, "parser")
+            .into_iter()
+            .enumerate()
+            .collect();
+
+        let excerpts = match_excerpts(text, &hits);
+        assert_eq!(
+            excerpts,
+            vec![
+                MatchExcerpt {
+                    text: "first parser line".to_string(),
+                    hits: vec![(0, 6..12)],
+                },
+                MatchExcerpt {
+                    text: "parser and parser".to_string(),
+                    hits: vec![(1, 0..6), (2, 11..17)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn match_excerpts_trim_long_lines_around_the_hit() {
+        let text = format!("{}needle{}", "a".repeat(100), "é".repeat(100));
+        let hits: Vec<_> = find_ranges(&text, "needle")
+            .into_iter()
+            .enumerate()
+            .collect();
+
+        let excerpts = match_excerpts(&text, &hits);
+        assert_eq!(excerpts.len(), 1);
+        let excerpt = &excerpts[0];
+        assert!(excerpt.text.starts_with('…') && excerpt.text.ends_with('…'));
+        let (_, range) = &excerpt.hits[0];
+        assert_eq!(&excerpt.text[range.clone()], "needle");
+        assert_eq!(
+            excerpt.text.len(),
+            3 + EXCERPT_CONTEXT + 6 + EXCERPT_CONTEXT + 3
+        );
+    }
+
+    #[test]
+    fn tool_call_selection_wraps_at_the_ends() {
+        assert_eq!(step_selection(None, 0, true), None);
+        assert_eq!(step_selection(Some(2), 0, false), None);
+
+        assert_eq!(step_selection(None, 3, true), Some(0));
+        assert_eq!(step_selection(None, 3, false), Some(2));
+        assert_eq!(step_selection(Some(0), 3, true), Some(1));
+        assert_eq!(step_selection(Some(2), 3, true), Some(0));
+        assert_eq!(step_selection(Some(1), 3, false), Some(0));
+        assert_eq!(step_selection(Some(0), 3, false), Some(2));
+
+        // A single entry stays selected in both directions
+        assert_eq!(step_selection(Some(0), 1, true), Some(0));
+        assert_eq!(step_selection(Some(0), 1, false), Some(0));
+    }
+
+    #[test]
+    fn stale_tool_call_selection_is_clamped() {
+        assert_eq!(step_selection(Some(7), 3, false), Some(1));
+        assert_eq!(step_selection(Some(7), 3, true), Some(0));
+    }
+
+    #[test]
+    fn scroll_anchor_transitions() {
+        let mut anchor = ScrollAnchor::default();
+        assert!(anchor.auto_scroll());
+
+        // Pinned at the bottom: new output keeps following
+        anchor.on_scroll(px(0.));
+        assert!(anchor.on_content_added());
+        assert!(!anchor.show_jump_to_latest());
+
+        // User scrolls up: output no longer moves the view, pill appears
+        anchor.on_scroll(px(800.));
+        assert!(!anchor.auto_scroll());
+        assert!(!anchor.show_jump_to_latest());
+        assert!(!anchor.on_content_added());
+        assert!(anchor.show_jump_to_latest());
+
+        // Scrolling within the threshold of the bottom re-enables following
+        anchor.on_scroll(px(AUTO_SCROLL_THRESHOLD_PX));
+        assert!(anchor.auto_scroll());
+        assert!(!anchor.show_jump_to_latest());
+
+        // Jump to latest clears the pill
+        anchor.on_scroll(px(500.));
+        anchor.on_content_added();
+        anchor.jump_to_latest();
+        assert!(anchor.auto_scroll());
+        assert!(!anchor.show_jump_to_latest());
+    }
+
+    #[test]
+    fn scroll_anchor_without_follow_output_never_scrolls() {
+        let mut anchor = ScrollAnchor::default();
+        anchor.set_follow_output(false);
+        assert!(!anchor.auto_scroll());
+
+        // Even at the bottom, output stays put and the pill offers to catch up
+        anchor.on_scroll(px(0.));
+        assert!(!anchor.on_content_added());
+        assert!(anchor.show_jump_to_latest());
+
+        // Jumping clears the pill, but the next output does not move the view
+        anchor.jump_to_latest();
+        assert!(!anchor.show_jump_to_latest());
+        assert!(!anchor.on_content_added());
+        assert!(anchor.show_jump_to_latest());
+
+        // Turning following back on resumes once the view is at the bottom
+        anchor.set_follow_output(true);
+        anchor.on_scroll(px(0.));
+        assert!(anchor.on_content_added());
+        assert!(!anchor.show_jump_to_latest());
+    }
 }
@@ -1,14 +1,16 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use agent_client_protocol::{self as acp, PermissionOption, PermissionOptionKind};
 use gpui::{
     App, AppContext, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
-    Window, div, prelude::FluentBuilder as _, px,
+    Task, Window, div, prelude::FluentBuilder as _, px,
 };
 
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
     button::{Button, ButtonVariants},
+    checkbox::Checkbox,
     h_flex, v_flex,
 };
 
@@ -16,9 +18,53 @@ pub type PermissionResponseHandler = Arc<
     dyn Fn(String, acp::RequestPermissionResponse, &mut Context<PermissionRequest>) + Send + Sync,
 >;
 
-#[derive(Clone, Default)]
+/// Called with (session_id, tool_kind, chosen kind, all sessions) when the
+/// user picks an option with "Don't ask again" checked
+pub type PermissionRememberHandler = Arc<
+    dyn Fn(String, acp::ToolKind, PermissionOptionKind, bool, &mut Context<PermissionRequest>)
+        + Send
+        + Sync,
+>;
+
+/// Looks up a remembered decision for (session_id, tool_kind)
+pub type PermissionLookupHandler =
+    Arc<dyn Fn(&str, acp::ToolKind) -> Option<PermissionOptionKind> + Send + Sync>;
+
+/// How often the auto-timeout countdown refreshes
+const AUTO_TIMEOUT_TICK: Duration = Duration::from_secs(1);
+
+#[derive(Clone)]
 pub struct PermissionRequestOptions {
     pub on_response: Option<PermissionResponseHandler>,
+    /// Pick `default_kind` automatically once this much time has passed
+    /// without a response. `None` waits indefinitely.
+    pub auto_timeout: Option<Duration>,
+    /// Option kind selected when `auto_timeout` elapses
+    pub default_kind: PermissionOptionKind,
+    /// Persists "Don't ask again" decisions; the checkbox is shown only when set
+    pub on_remember: Option<PermissionRememberHandler>,
+    /// Consulted on first render to resolve the request from a remembered decision
+    pub lookup_remembered: Option<PermissionLookupHandler>,
+}
+
+impl Default for PermissionRequestOptions {
+    fn default() -> Self {
+        Self {
+            on_response: None,
+            auto_timeout: None,
+            default_kind: PermissionOptionKind::RejectOnce,
+            on_remember: None,
+            lookup_remembered: None,
+        }
+    }
+}
+
+impl PermissionRequestOptions {
+    pub fn auto_timeout(mut self, timeout: Duration, default_kind: PermissionOptionKind) -> Self {
+        self.auto_timeout = Some(timeout);
+        self.default_kind = default_kind;
+        self
+    }
 }
 
 pub fn permission_option_kind_to_icon(kind: PermissionOptionKind) -> IconName {
@@ -46,14 +92,25 @@ pub struct PermissionRequest {
     session_id: String,
     /// Tool call title
     tool_title: String,
-    /// Tool call kind
+    /// Tool call kind (display label)
     tool_kind: Option<String>,
+    /// Tool call kind used to key remembered decisions
+    tool_call_kind: acp::ToolKind,
+    /// "Don't ask again for this tool" checkbox state
+    remember: bool,
+    /// "For all sessions" checkbox state, applies to `remember`
+    remember_globally: bool,
+    /// Whether the remembered-decision lookup already ran
+    checked_remembered: bool,
     /// Available permission options
     options: Vec<PermissionOption>,
     /// Whether the request has been responded to
     responded: bool,
     /// Response handler
     request_options: PermissionRequestOptions,
+    /// When the auto-timeout countdown started
+    timeout_started_at: Option<Instant>,
+    _auto_timeout_task: Option<Task<()>>,
 }
 
 impl PermissionRequest {
@@ -85,15 +142,22 @@ impl PermissionRequest {
             .clone()
             .unwrap_or_else(|| "Tool Call".to_string());
         let tool_kind = tool_call.fields.kind.as_ref().map(|k| format!("{:?}", k));
+        let tool_call_kind = tool_call.fields.kind.unwrap_or_default();
 
         Self {
             permission_id,
             session_id,
             tool_title,
             tool_kind,
+            tool_call_kind,
+            remember: false,
+            remember_globally: false,
+            checked_remembered: false,
             options: options.into_iter().collect(),
             responded: false,
             request_options,
+            timeout_started_at: None,
+            _auto_timeout_task: None,
         }
     }
 
@@ -102,6 +166,109 @@ impl PermissionRequest {
         self
     }
 
+    pub fn auto_timeout(mut self, timeout: Duration, default_kind: PermissionOptionKind) -> Self {
+        self.request_options = self.request_options.auto_timeout(timeout, default_kind);
+        self
+    }
+
+    /// The option matching a remembered decision for this tool, if any
+    fn remembered_option(&self) -> Option<String> {
+        let lookup = self.request_options.lookup_remembered.as_ref()?;
+        let kind = lookup(&self.session_id, self.tool_call_kind)?;
+        self.options
+            .iter()
+            .find(|option| option.kind == kind)
+            .map(|option| option.option_id.to_string())
+    }
+
+    /// Resolve the request from a remembered decision, once
+    fn apply_remembered_choice(&mut self, cx: &mut Context<Self>) {
+        if self.responded || self.checked_remembered {
+            return;
+        }
+        self.checked_remembered = true;
+
+        if let Some(option_id) = self.remembered_option() {
+            log::info!(
+                "Permission {} resolved from remembered choice {}",
+                self.permission_id,
+                option_id
+            );
+            self.select_option(option_id, false, cx);
+        }
+    }
+
+    /// The option picked when the auto-timeout elapses
+    fn default_option(&self) -> Option<&PermissionOption> {
+        self.options
+            .iter()
+            .find(|option| option.kind == self.request_options.default_kind)
+    }
+
+    /// Time left before the default option is picked, if a countdown is running
+    fn remaining(&self, now: Instant) -> Option<Duration> {
+        let timeout = self.request_options.auto_timeout?;
+        let started_at = self.timeout_started_at?;
+        Some((started_at + timeout).saturating_duration_since(now))
+    }
+
+    /// The option to pick automatically at `now`, once the timeout has elapsed
+    fn timed_out_option(&self, now: Instant) -> Option<String> {
+        if self.responded || self.remaining(now) != Some(Duration::ZERO) {
+            return None;
+        }
+        self.default_option()
+            .map(|option| option.option_id.to_string())
+    }
+
+    /// Start the auto-timeout countdown, if configured and not yet running.
+    /// Call this when the request arrives, so the timeout runs whether or
+    /// not the request is on screen.
+    pub fn start_auto_timeout(&mut self, cx: &mut Context<Self>) {
+        if self.responded
+            || self.request_options.auto_timeout.is_none()
+            || self.timeout_started_at.is_some()
+        {
+            return;
+        }
+
+        self.timeout_started_at = Some(Instant::now());
+        self._auto_timeout_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(AUTO_TIMEOUT_TICK).await;
+                let done = this.update(cx, |this, cx| {
+                    if this.responded {
+                        return true;
+                    }
+                    let now = Instant::now();
+                    if let Some(option_id) = this.timed_out_option(now) {
+                        log::info!(
+                            "Permission {} timed out, selecting default option {}",
+                            this.permission_id,
+                            option_id
+                        );
+                        this.select_option(option_id, false, cx);
+                        return true;
+                    }
+                    if this.remaining(now) == Some(Duration::ZERO) {
+                        log::warn!(
+                            "Permission {} timed out but has no {:?} option",
+                            this.permission_id,
+                            this.request_options.default_kind
+                        );
+                        return true;
+                    }
+                    // Refresh the countdown
+                    cx.notify();
+                    false
+                });
+                if done.unwrap_or(true) {
+                    break;
+                }
+            }
+        }));
+    }
+
     /// Handle user selection of a permission option
     fn on_option_selected(
         &mut self,
@@ -109,10 +276,32 @@ impl PermissionRequest {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.select_option(option_id, self.remember, cx);
+    }
+
+    /// Send the response for `option_id`, persisting it when `remember` is set
+    fn select_option(&mut self, option_id: String, remember: bool, cx: &mut Context<Self>) {
         if self.responded {
             return; // Already responded
         }
 
+        if remember {
+            let kind = self
+                .options
+                .iter()
+                .find(|option| option.option_id.to_string() == option_id)
+                .map(|option| option.kind);
+            if let (Some(kind), Some(handler)) = (kind, self.request_options.on_remember.as_ref()) {
+                handler(
+                    self.session_id.clone(),
+                    self.tool_call_kind,
+                    kind,
+                    self.remember_globally,
+                    cx,
+                );
+            }
+        }
+
         log::info!(
             "Permission option selected: {} for permission_id: {}",
             option_id,
@@ -131,13 +320,27 @@ impl PermissionRequest {
 
         // Mark as responded and trigger re-render
         self.responded = true;
+        self._auto_timeout_task = None;
         cx.notify();
     }
 }
 
 impl Render for PermissionRequest {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.apply_remembered_choice(cx);
         let responded = self.responded;
+        let can_remember = self.request_options.on_remember.is_some();
+        let countdown = self
+            .remaining(Instant::now())
+            .filter(|_| !responded)
+            .zip(self.default_option())
+            .map(|(remaining, option)| {
+                format!(
+                    "\u{201c}{}\u{201d} in {}s",
+                    option.name,
+                    remaining.as_secs_f32().ceil() as u64
+                )
+            });
 
         v_flex()
             .w_full()
@@ -181,7 +384,16 @@ impl Render for PermissionRequest {
                             } else {
                                 "Permission Request"
                             }),
-                    ),
+                    )
+                    .when_some(countdown, |this, countdown| {
+                        this.child(
+                            div()
+                                .ml_auto()
+                                .text_size(px(12.))
+                                .text_color(cx.theme().muted_foreground)
+                                .child(countdown),
+                        )
+                    }),
             )
             .child(
                 // Tool call information
@@ -228,6 +440,44 @@ impl Render for PermissionRequest {
                             ))
                         })),
                 )
+                .when(can_remember, |this| {
+                    this.child(
+                        div().pl_6().child(
+                            Checkbox::new(SharedString::from(format!(
+                                "permission-{}-remember",
+                                self.permission_id
+                            )))
+                            .label("Don't ask again for this tool")
+                            .checked(self.remember)
+                            .small()
+                            .on_click(cx.listener(
+                                |this, checked: &bool, _, cx| {
+                                    this.remember = *checked;
+                                    cx.notify();
+                                },
+                            )),
+                        ),
+                    )
+                })
+                .when(can_remember && self.remember, |this| {
+                    this.child(
+                        div().pl_6().child(
+                            Checkbox::new(SharedString::from(format!(
+                                "permission-{}-remember-globally",
+                                self.permission_id
+                            )))
+                            .label("For all sessions")
+                            .checked(self.remember_globally)
+                            .small()
+                            .on_click(cx.listener(
+                                |this, checked: &bool, _, cx| {
+                                    this.remember_globally = *checked;
+                                    cx.notify();
+                                },
+                            )),
+                        ),
+                    )
+                })
             })
     }
 }
@@ -302,4 +552,108 @@ mod tests {
         assert!(permission_is_allow(PermissionOptionKind::AllowAlways));
         assert!(!permission_is_allow(PermissionOptionKind::RejectOnce));
     }
+
+    fn request_with_timeout(timeout: Duration) -> PermissionRequest {
+        let tool_call = acp::ToolCallUpdate::new("tool-call-1", acp::ToolCallUpdateFields::new());
+        let options = vec![
+            PermissionOption::new("allow-once", "Allow once", PermissionOptionKind::AllowOnce),
+            PermissionOption::new(
+                "reject-once",
+                "Reject once",
+                PermissionOptionKind::RejectOnce,
+            ),
+        ];
+        PermissionRequest::new(
+            "permission-1".to_string(),
+            "session-1".to_string(),
+            &tool_call,
+            options,
+        )
+        .auto_timeout(timeout, PermissionOptionKind::RejectOnce)
+    }
+
+    #[test]
+    fn auto_timeout_resolves_to_default_option() {
+        let mut request = request_with_timeout(Duration::from_millis(10));
+        let start = Instant::now();
+        assert_eq!(request.timed_out_option(start), None);
+
+        request.timeout_started_at = Some(start);
+        assert_eq!(request.timed_out_option(start), None);
+        assert_eq!(
+            request.timed_out_option(start + Duration::from_millis(20)),
+            Some("reject-once".to_string())
+        );
+
+        request.responded = true;
+        assert_eq!(
+            request.timed_out_option(start + Duration::from_millis(20)),
+            None
+        );
+    }
+
+    #[test]
+    fn auto_timeout_disabled_by_default() {
+        let tool_call = acp::ToolCallUpdate::new("tool-call-1", acp::ToolCallUpdateFields::new());
+        let mut request = PermissionRequest::new(
+            "permission-1".to_string(),
+            "session-1".to_string(),
+            &tool_call,
+            vec![],
+        );
+        request.timeout_started_at = Some(Instant::now());
+        assert_eq!(request.remaining(Instant::now()), None);
+        assert_eq!(
+            request.timed_out_option(Instant::now() + Duration::from_secs(3600)),
+            None
+        );
+    }
+
+    #[test]
+    fn remembered_option_matches_lookup() {
+        let tool_call = acp::ToolCallUpdate::new(
+            "tool-call-1",
+            acp::ToolCallUpdateFields::new().kind(acp::ToolKind::Execute),
+        );
+        let options = vec![
+            PermissionOption::new("allow-once", "Allow once", PermissionOptionKind::AllowOnce),
+            PermissionOption::new(
+                "reject-once",
+                "Reject once",
+                PermissionOptionKind::RejectOnce,
+            ),
+        ];
+        let lookup: PermissionLookupHandler =
+            Arc::new(|session_id: &str, tool_kind: acp::ToolKind| {
+                (session_id == "session-1" && tool_kind == acp::ToolKind::Execute)
+                    .then_some(PermissionOptionKind::AllowOnce)
+            });
+        let request = PermissionRequest::with_options(
+            "permission-1".to_string(),
+            "session-1".to_string(),
+            &tool_call,
+            options.clone(),
+            PermissionRequestOptions {
+                lookup_remembered: Some(lookup.clone()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(request.remembered_option(), Some("allow-once".to_string()));
+
+        let other_tool = acp::ToolCallUpdate::new(
+            "tool-call-2",
+            acp::ToolCallUpdateFields::new().kind(acp::ToolKind::Edit),
+        );
+        let request = PermissionRequest::with_options(
+            "permission-2".to_string(),
+            "session-1".to_string(),
+            &other_tool,
+            options,
+            PermissionRequestOptions {
+                lookup_remembered: Some(lookup),
+                ..Default::default()
+            },
+        );
+        assert_eq!(request.remembered_option(), None);
+    }
 }
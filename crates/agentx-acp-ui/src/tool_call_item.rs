@@ -1,3 +1,5 @@
+use std::ops::Range;
+use std::rc::Rc;
 use std::sync::Arc;
 
 use agent_client_protocol::{
@@ -5,8 +7,10 @@ use agent_client_protocol::{
     ToolKind,
 };
 use gpui::{
-    AnyElement, App, AppContext, Context, Entity, IntoElement, ParentElement, Render, RenderOnce,
-    SharedString, Styled, Window, div, prelude::FluentBuilder as _, px,
+    AnyElement, App, AppContext, ClipboardItem, Context, Entity, FontStyle, FontWeight,
+    HighlightStyle, InteractiveElement, IntoElement, ParentElement, Render, RenderOnce,
+    SharedString, StatefulInteractiveElement, Styled, StyledText, UnderlineStyle, Window, div,
+    prelude::FluentBuilder as _, px, rgb,
 };
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
@@ -16,16 +20,30 @@ use gpui_component::{
 };
 use similar::{ChangeTag, TextDiff};
 
+use crate::ansi::StyledSpan;
 use crate::diff_view::DiffView;
-use crate::utils::{extract_terminal_output, extract_xml_content, truncate_lines};
+use crate::utils::{
+    TruncationInfo, extract_terminal_output, extract_terminal_output_styled, extract_xml_content,
+    truncate_lines_with_info,
+};
 
 pub type ToolCallDetailHandler =
     Arc<dyn Fn(ToolCall, &mut Window, &mut Context<ToolCallItem>) + Send + Sync>;
 
+/// Callback for re-issuing a failed tool call
+pub type ToolCallRetryHandler =
+    Arc<dyn Fn(ToolCallId, &mut Window, &mut Context<ToolCallItem>) + Send + Sync>;
+
 #[derive(Clone)]
 pub struct ToolCallItemOptions {
     pub preview_max_lines: usize,
     pub on_open_detail: Option<ToolCallDetailHandler>,
+    /// Shows a retry button on failed tool calls. Leave unset when the host
+    /// cannot re-issue calls.
+    pub on_retry: Option<ToolCallRetryHandler>,
+    /// Collapse runs of consecutive tool calls of the same kind into one
+    /// expandable group
+    pub group_consecutive: bool,
 }
 
 impl Default for ToolCallItemOptions {
@@ -33,6 +51,8 @@ impl Default for ToolCallItemOptions {
         Self {
             preview_max_lines: 10,
             on_open_detail: None,
+            on_retry: None,
+            group_consecutive: false,
         }
     }
 }
@@ -47,6 +67,81 @@ impl ToolCallItemOptions {
         self.on_open_detail = Some(handler);
         self
     }
+
+    pub fn on_retry(mut self, handler: ToolCallRetryHandler) -> Self {
+        self.on_retry = Some(handler);
+        self
+    }
+
+    pub fn group_consecutive(mut self, group: bool) -> Self {
+        self.group_consecutive = group;
+        self
+    }
+}
+
+/// Index ranges of runs of two or more consecutive tool calls with the same
+/// kind. `None` marks an item that is not a tool call and breaks a run.
+pub fn group_consecutive_tool_calls(kinds: &[Option<ToolKind>]) -> Vec<Range<usize>> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    while start < kinds.len() {
+        let Some(kind) = kinds[start] else {
+            start += 1;
+            continue;
+        };
+        let end = start
+            + kinds[start..]
+                .iter()
+                .take_while(|other| **other == Some(kind))
+                .count();
+        if end - start > 1 {
+            groups.push(start..end);
+        }
+        start = end;
+    }
+    groups
+}
+
+/// Status shown for a group of tool calls: any failure fails the group,
+/// otherwise it is as far along as its least finished call
+pub fn aggregate_tool_call_status<'a>(
+    statuses: impl IntoIterator<Item = &'a ToolCallStatus>,
+) -> ToolCallStatus {
+    let mut aggregate = ToolCallStatus::Completed;
+    for status in statuses {
+        match status {
+            ToolCallStatus::Failed => return ToolCallStatus::Failed,
+            ToolCallStatus::InProgress => aggregate = ToolCallStatus::InProgress,
+            ToolCallStatus::Pending if aggregate == ToolCallStatus::Completed => {
+                aggregate = ToolCallStatus::Pending;
+            }
+            _ => {}
+        }
+    }
+    aggregate
+}
+
+/// Serialize a tool call for sharing: the full `ToolCall` (input and every
+/// content block) plus the complete output of its terminals. Nothing is cut
+/// to the inline preview length.
+pub fn tool_call_export_json(tool_call: &ToolCall) -> String {
+    let terminal_output = tool_call
+        .content
+        .iter()
+        .filter_map(|content| match content {
+            ToolCallContent::Terminal(terminal) => Some(serde_json::json!({
+                "terminal_id": terminal.terminal_id.to_string(),
+                "output": extract_terminal_output(terminal),
+            })),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let export = serde_json::json!({
+        "tool_call": tool_call,
+        "terminal_output": terminal_output,
+    });
+    serde_json::to_string_pretty(&export).unwrap_or_default()
 }
 
 /// Diff statistics
@@ -96,6 +191,26 @@ fn extract_diff_stats_from_tool_call(tool_call: &ToolCall) -> Option<DiffStats>
     None
 }
 
+/// Display name of a tool kind, e.g. "Read" or "Execute". Supplied by the
+/// host app so it can localize the text.
+pub type ToolKindLabeler = Arc<dyn Fn(ToolKind) -> String + Send + Sync>;
+
+/// English name of a tool kind, used when the host supplies no labeler
+pub fn tool_kind_label(kind: ToolKind) -> &'static str {
+    match kind {
+        ToolKind::Read => "Read",
+        ToolKind::Edit => "Edit",
+        ToolKind::Delete => "Delete",
+        ToolKind::Move => "Move",
+        ToolKind::Search => "Search",
+        ToolKind::Execute => "Execute",
+        ToolKind::Think => "Think",
+        ToolKind::Fetch => "Fetch",
+        ToolKind::SwitchMode => "Switch mode",
+        ToolKind::Other | _ => "Other",
+    }
+}
+
 fn tool_kind_icon(kind: &ToolKind) -> Icon {
     match kind {
         ToolKind::Read => Icon::new(IconName::Eye),
@@ -121,6 +236,107 @@ fn tool_call_status_icon(status: &ToolCallStatus) -> Icon {
     }
 }
 
+/// Callback for expanding or collapsing a tool call group
+type ToolCallGroupToggleHandler = Rc<dyn Fn(&mut Window, &mut App)>;
+
+/// Header row for a group of consecutive tool calls of the same kind
+#[derive(IntoElement)]
+pub struct ToolCallGroupHeader {
+    id: SharedString,
+    kind: ToolKind,
+    count: usize,
+    status: ToolCallStatus,
+    open: bool,
+    labeler: Option<ToolKindLabeler>,
+    on_toggle: Option<ToolCallGroupToggleHandler>,
+}
+
+impl ToolCallGroupHeader {
+    pub fn new(
+        id: impl Into<SharedString>,
+        kind: ToolKind,
+        count: usize,
+        status: ToolCallStatus,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            kind,
+            count,
+            status,
+            open: false,
+            labeler: None,
+            on_toggle: None,
+        }
+    }
+
+    pub fn open(mut self, open: bool) -> Self {
+        self.open = open;
+        self
+    }
+
+    pub fn labeler(mut self, labeler: Option<ToolKindLabeler>) -> Self {
+        self.labeler = labeler;
+        self
+    }
+
+    pub fn on_toggle(mut self, handler: impl Fn(&mut Window, &mut App) + 'static) -> Self {
+        self.on_toggle = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for ToolCallGroupHeader {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let status_color = match self.status {
+            ToolCallStatus::Completed => cx.theme().green,
+            ToolCallStatus::Failed => cx.theme().red,
+            ToolCallStatus::InProgress => cx.theme().accent,
+            ToolCallStatus::Pending | _ => cx.theme().muted_foreground,
+        };
+        let label = match &self.labeler {
+            Some(labeler) => labeler(self.kind),
+            None => tool_kind_label(self.kind).to_string(),
+        };
+
+        h_flex()
+            .items_center()
+            .gap_3()
+            .p_2()
+            .rounded(cx.theme().radius)
+            .bg(cx.theme().secondary)
+            .child(
+                tool_kind_icon(&self.kind)
+                    .size(px(16.))
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_size(px(13.))
+                    .text_color(cx.theme().foreground)
+                    .child(format!("{} \u{00d7} {}", label, self.count)),
+            )
+            .child(
+                tool_call_status_icon(&self.status)
+                    .size(px(14.))
+                    .text_color(status_color),
+            )
+            .child(
+                Button::new(SharedString::from(format!("{}-toggle", self.id)))
+                    .icon(if self.open {
+                        IconName::ChevronUp
+                    } else {
+                        IconName::ChevronDown
+                    })
+                    .ghost()
+                    .xsmall()
+                    .when_some(self.on_toggle, |button, on_toggle| {
+                        button.on_click(move |_, window, cx| on_toggle(window, cx))
+                    }),
+            )
+    }
+}
+
 /// Tool call item component based on ACP's ToolCall - stateful version
 pub struct ToolCallItem {
     tool_call: ToolCall,
@@ -221,6 +437,19 @@ impl ToolCallItem {
         cx.notify();
     }
 
+    /// Copy the complete tool call as JSON to the clipboard, see
+    /// [`tool_call_export_json`]
+    pub fn copy_as_json(&self, cx: &mut Context<Self>) {
+        cx.write_to_clipboard(ClipboardItem::new_string(tool_call_export_json(
+            &self.tool_call,
+        )));
+    }
+
+    /// Whether the retry control is shown for this tool call
+    pub fn can_retry(&self) -> bool {
+        self.options.on_retry.is_some() && self.tool_call.status == ToolCallStatus::Failed
+    }
+
     pub fn has_content(&self) -> bool {
         !self.tool_call.content.is_empty()
     }
@@ -256,6 +485,45 @@ impl ToolCallItem {
         self.tool_call.title.clone()
     }
 
+    /// Render the "N more lines hidden" note under a truncated preview;
+    /// clicking it opens the full output when a detail handler is set
+    fn render_truncation_hint(
+        &self,
+        truncation: TruncationInfo,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let label = format!(
+            "… {} more line{} hidden",
+            truncation.hidden_lines,
+            if truncation.hidden_lines == 1 {
+                ""
+            } else {
+                "s"
+            }
+        );
+        let hint = div()
+            .id(SharedString::from(format!(
+                "tool-call-{}-show-more",
+                self.tool_call.tool_call_id
+            )))
+            .text_size(px(11.))
+            .text_color(cx.theme().muted_foreground)
+            .child(label);
+
+        match self.options.on_open_detail.clone() {
+            Some(handler) => {
+                let tool_call = self.tool_call.clone();
+                hint.cursor_pointer()
+                    .text_color(cx.theme().accent_foreground)
+                    .on_click(cx.listener(move |_, _ev, window, cx| {
+                        handler(tool_call.clone(), window, cx);
+                    }))
+                    .into_any_element()
+            }
+            None => hint.into_any_element(),
+        }
+    }
+
     /// Render content based on type
     fn render_content(
         &self,
@@ -267,6 +535,10 @@ impl ToolCallItem {
             ToolCallContent::Diff(diff) => {
                 // Use DiffView component for diff content, limited to 10 lines
                 let diff_view = DiffView::new(diff.clone())
+                    .id(SharedString::from(format!(
+                        "tool-call-diff-{}",
+                        self.tool_call.tool_call_id
+                    )))
                     .max_lines(8)
                     .context_lines(1)
                     .show_file_header(false); // Hide file header in compact view
@@ -276,17 +548,20 @@ impl ToolCallItem {
             ToolCallContent::Content(c) => match &c.content {
                 acp::ContentBlock::Text(text) => {
                     let cleaned_text = extract_xml_content(&text.text, &self.tool_call.kind);
-                    let display_text = if cleaned_text.lines().count() > 20 {
+                    let (display_text, truncation) = if cleaned_text.lines().count() > 20 {
                         let max_lines = self.options.preview_max_lines;
-                        truncate_lines(&cleaned_text, max_lines)
+                        truncate_lines_with_info(&cleaned_text, max_lines)
                     } else {
-                        cleaned_text
+                        (cleaned_text, TruncationInfo::default())
                     };
-                    div()
+                    v_flex()
                         .text_size(px(12.))
                         .text_color(cx.theme().muted_foreground)
                         .line_height(px(18.))
                         .child(display_text)
+                        .when(truncation.is_truncated(), |this| {
+                            this.child(self.render_truncation_hint(truncation, cx))
+                        })
                         .into_any_element()
                 }
                 _ => div()
@@ -297,25 +572,27 @@ impl ToolCallItem {
             },
             ToolCallContent::Terminal(terminal) => {
                 let max_lines = self.options.preview_max_lines;
-                let output = extract_terminal_output(terminal).and_then(|text| {
-                    if text.trim().is_empty() {
-                        None
-                    } else {
-                        Some(text)
-                    }
-                });
-                let display_text = match output {
-                    Some(text) => {
-                        let truncated = truncate_lines(&text, max_lines);
-                        format!("Terminal: {}\n{}", terminal.terminal_id, truncated)
+                let spans = extract_terminal_output_styled(terminal)
+                    .filter(|spans| spans.iter().any(|span| !span.text.trim().is_empty()));
+                let header = format!("Terminal: {}", terminal.terminal_id);
+                let (text, truncation) = match spans {
+                    Some(spans) => {
+                        let plain: String = spans.iter().map(|span| span.text.as_str()).collect();
+                        (
+                            render_ansi_text(&header, &spans, max_lines),
+                            truncate_lines_with_info(&plain, max_lines).1,
+                        )
                     }
-                    None => format!("Terminal: {}", terminal.terminal_id),
+                    None => (StyledText::new(header), TruncationInfo::default()),
                 };
-                div()
+                v_flex()
                     .text_size(px(12.))
                     .text_color(cx.theme().muted_foreground)
                     .line_height(px(18.))
-                    .child(display_text)
+                    .child(text)
+                    .when(truncation.is_truncated(), |this| {
+                        this.child(self.render_truncation_hint(truncation, cx))
+                    })
                     .into_any_element()
             }
             _ => div()
@@ -327,6 +604,51 @@ impl ToolCallItem {
     }
 }
 
+/// Build the terminal preview: a header line followed by the first
+/// `max_lines` lines of output, colored from the ANSI styles in `spans`
+fn render_ansi_text(header: &str, spans: &[StyledSpan], max_lines: usize) -> StyledText {
+    let mut text = format!("{}\n", header);
+    let mut highlights = Vec::new();
+    let mut lines_left = if max_lines == 0 {
+        usize::MAX
+    } else {
+        max_lines
+    };
+
+    for span in spans {
+        if lines_left == 0 {
+            break;
+        }
+        let mut chunk = span.text.as_str();
+        if let Some((cut, _)) = chunk.match_indices('\n').nth(lines_left - 1) {
+            chunk = &chunk[..cut];
+            lines_left = 0;
+        } else {
+            lines_left -= chunk.matches('\n').count();
+        }
+
+        let start = text.len();
+        text.push_str(chunk);
+        let style = &span.style;
+        if *style != Default::default() && !chunk.is_empty() {
+            highlights.push((
+                start..text.len(),
+                HighlightStyle {
+                    color: style.foreground.map(|c| rgb(c.to_rgb()).into()),
+                    background_color: style.background.map(|c| rgb(c.to_rgb()).into()),
+                    font_weight: style.bold.then_some(FontWeight::BOLD),
+                    font_style: style.italic.then_some(FontStyle::Italic),
+                    underline: style.underline.then(UnderlineStyle::default),
+                    fade_out: style.dim.then_some(0.4),
+                    ..Default::default()
+                },
+            ));
+        }
+    }
+
+    StyledText::new(text).with_highlights(highlights)
+}
+
 impl Render for ToolCallItem {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let has_content = self.has_content();
@@ -398,6 +720,27 @@ impl Render for ToolCallItem {
                         )
                     })
                     .child(status_icon.size(px(14.)).text_color(status_color))
+                    .when(self.can_retry(), |this| {
+                        let retry_handler = self.options.on_retry.clone();
+                        let retry_id = self.tool_call.tool_call_id.clone();
+                        this.child(
+                            Button::new(SharedString::from(format!(
+                                "tool-call-{}-retry",
+                                tool_call_id
+                            )))
+                            .icon(IconName::Redo)
+                            .label("Retry")
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(
+                                move |_, _ev, window, cx| {
+                                    if let Some(handler) = retry_handler.as_ref() {
+                                        handler(retry_id.clone(), window, cx);
+                                    }
+                                },
+                            )),
+                        )
+                    })
                     .when(has_content, |this| {
                         let tool_call_clone_for_detail = self.tool_call.clone();
                         let detail_handler = detail_handler.clone();
@@ -420,6 +763,19 @@ impl Render for ToolCallItem {
                                         this.toggle(cx);
                                     })),
                                 )
+                                .child(
+                                    Button::new(SharedString::from(format!(
+                                        "tool-call-{}-copy-json",
+                                        tool_call_id
+                                    )))
+                                    .icon(IconName::Copy)
+                                    .ghost()
+                                    .xsmall()
+                                    .tooltip("Copy as JSON")
+                                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                                        this.copy_as_json(cx);
+                                    })),
+                                )
                                 .when(detail_handler.is_some(), |this| {
                                     let tool_call_id = tool_call_id.clone();
                                     this.child(
@@ -536,6 +892,13 @@ impl ToolCallItemView {
         cx.notify();
     }
 
+    /// Copy the complete tool call as JSON to the clipboard
+    pub fn copy_as_json(&mut self, cx: &mut Context<Self>) {
+        self.item.update(cx, |item, cx| {
+            item.copy_as_json(cx);
+        });
+    }
+
     /// Set the open state
     pub fn set_open(&mut self, open: bool, cx: &mut Context<Self>) {
         self.item.update(cx, |item, cx| {
@@ -579,4 +942,116 @@ mod tests {
         assert_eq!(stats.additions, 2);
         assert_eq!(stats.deletions, 0);
     }
+
+    #[test]
+    fn export_keeps_untruncated_content() {
+        let long_text = (1..=50)
+            .map(|n| format!("text line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let long_output = (1..=50)
+            .map(|n| format!("\x1b[32moutput line {n}\x1b[0m"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let meta = serde_json::json!({ "output": long_output })
+            .as_object()
+            .unwrap()
+            .clone();
+
+        let mut tool_call = ToolCall::new("tc-3", "Run tests");
+        tool_call.kind = ToolKind::Execute;
+        tool_call.raw_input = Some(serde_json::json!({"command": "cargo test"}));
+        tool_call.content = vec![
+            ToolCallContent::from(acp::ContentBlock::from(long_text.clone())),
+            ToolCallContent::Terminal(acp::Terminal::new("term-1").meta(meta)),
+        ];
+
+        // The inline preview would stop well before the last line
+        let item = ToolCallItem::with_options(
+            tool_call,
+            ToolCallItemOptions::default().preview_max_lines(5),
+        );
+        assert!(truncate_lines_with_info(&long_text, 5).1.is_truncated());
+
+        let export: serde_json::Value =
+            serde_json::from_str(&tool_call_export_json(item.tool_call())).unwrap();
+        assert_eq!(export["tool_call"]["rawInput"]["command"], "cargo test");
+        assert_eq!(
+            export["tool_call"]["content"][0]["content"]["text"],
+            long_text
+        );
+        let output = export["terminal_output"][0]["output"].as_str().unwrap();
+        assert_eq!(export["terminal_output"][0]["terminal_id"], "term-1");
+        assert_eq!(output.lines().count(), 50);
+        assert!(output.ends_with("output line 50"));
+    }
+
+    #[test]
+    fn groups_consecutive_calls_of_same_kind() {
+        let kinds = [
+            Some(ToolKind::Read),
+            Some(ToolKind::Read),
+            Some(ToolKind::Read),
+            Some(ToolKind::Edit),
+            None,
+            Some(ToolKind::Edit),
+            Some(ToolKind::Edit),
+        ];
+        assert_eq!(group_consecutive_tool_calls(&kinds), vec![0..3, 5..7]);
+
+        // A single call is not a group
+        assert!(group_consecutive_tool_calls(&[Some(ToolKind::Read), None]).is_empty());
+    }
+
+    #[test]
+    fn group_status_reports_failures() {
+        let statuses = [
+            ToolCallStatus::Completed,
+            ToolCallStatus::Failed,
+            ToolCallStatus::InProgress,
+        ];
+        assert_eq!(
+            aggregate_tool_call_status(&statuses),
+            ToolCallStatus::Failed
+        );
+
+        let statuses = [ToolCallStatus::Completed, ToolCallStatus::Pending];
+        assert_eq!(
+            aggregate_tool_call_status(&statuses),
+            ToolCallStatus::Pending
+        );
+
+        let statuses = [ToolCallStatus::Pending, ToolCallStatus::InProgress];
+        assert_eq!(
+            aggregate_tool_call_status(&statuses),
+            ToolCallStatus::InProgress
+        );
+
+        let statuses = [ToolCallStatus::Completed, ToolCallStatus::Completed];
+        assert_eq!(
+            aggregate_tool_call_status(&statuses),
+            ToolCallStatus::Completed
+        );
+    }
+
+    #[test]
+    fn retry_is_offered_only_for_failed_calls() {
+        let handler: ToolCallRetryHandler = Arc::new(|_, _, _| {});
+        let options = ToolCallItemOptions::default().on_retry(handler);
+
+        let mut tool_call = ToolCall::new("tc-1", "Run tests");
+        tool_call.status = ToolCallStatus::Failed;
+        assert!(ToolCallItem::with_options(tool_call.clone(), options.clone()).can_retry());
+        // Hosts that cannot re-issue calls never show the control
+        assert!(!ToolCallItem::new(tool_call.clone()).can_retry());
+
+        for status in [
+            ToolCallStatus::Completed,
+            ToolCallStatus::Pending,
+            ToolCallStatus::InProgress,
+        ] {
+            tool_call.status = status;
+            assert!(!ToolCallItem::with_options(tool_call.clone(), options.clone()).can_retry());
+        }
+    }
 }
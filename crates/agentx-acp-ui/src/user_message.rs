@@ -1,10 +1,16 @@
+use std::sync::Arc;
+use std::time::SystemTime;
+
 use agent_client_protocol::{
-    ContentBlock, EmbeddedResource, EmbeddedResourceResource, ResourceLink, SessionId,
+    ContentBlock, EmbeddedResource, EmbeddedResourceResource, ResourceLink, SessionId, TextContent,
     TextResourceContents,
 };
+use base64::Engine as _;
 use gpui::{
-    App, AppContext, Context, ElementId, Entity, IntoElement, ParentElement, Render, RenderOnce,
-    SharedString, Styled, Window, div, prelude::FluentBuilder as _, px,
+    App, AppContext, Context, ElementId, Entity, Image, ImageFormat, ImageSource,
+    InteractiveElement, IntoElement, ObjectFit, ParentElement, Render, RenderOnce, SharedString,
+    StatefulInteractiveElement, Styled, StyledImage, Window, div, img, prelude::FluentBuilder as _,
+    px,
 };
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
@@ -13,6 +19,8 @@ use gpui_component::{
     h_flex, v_flex,
 };
 
+use crate::TimestampFormatter;
+
 /// User message data structure based on ACP's PromptRequest format
 #[derive(Clone, Debug)]
 pub struct UserMessageData {
@@ -20,6 +28,8 @@ pub struct UserMessageData {
     pub session_id: SessionId,
     /// Message content blocks (following ACP ContentBlock format)
     pub contents: Vec<ContentBlock>,
+    /// When the message was sent (optional)
+    pub timestamp: Option<SystemTime>,
 }
 
 impl UserMessageData {
@@ -27,9 +37,15 @@ impl UserMessageData {
         Self {
             session_id: session_id.into(),
             contents: Vec::new(),
+            timestamp: None,
         }
     }
 
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
     pub fn with_contents(mut self, contents: Vec<ContentBlock>) -> Self {
         self.contents = contents;
         self
@@ -72,6 +88,20 @@ impl UserMessageData {
     }
 }
 
+/// `_meta` key of a text block expanded from a configured slash command,
+/// holding the command as the user typed it
+pub const SLASH_COMMAND_META_KEY: &str = "agentx/slashCommand";
+
+/// Text shown for a user text block: the slash command it was expanded from,
+/// or the text itself
+pub fn user_display_text(text: &TextContent) -> &str {
+    text.meta
+        .as_ref()
+        .and_then(|meta| meta.get(SLASH_COMMAND_META_KEY))
+        .and_then(|command| command.as_str())
+        .unwrap_or(&text.text)
+}
+
 /// Helper to extract display information from ContentBlock
 pub fn get_resource_info(content: &ContentBlock) -> Option<ResourceInfo> {
     match content {
@@ -80,6 +110,8 @@ pub fn get_resource_info(content: &ContentBlock) -> Option<ResourceInfo> {
             name: link.name.clone().into(),
             mime_type: link.mime_type.clone().map(|s| s.into()),
             text: None,
+            blob: None,
+            kind: resource_kind(link.mime_type.as_deref(), &link.uri),
         }),
         ContentBlock::Resource(embedded) => match &embedded.resource {
             EmbeddedResourceResource::TextResourceContents(text_res) => Some(ResourceInfo {
@@ -87,16 +119,36 @@ pub fn get_resource_info(content: &ContentBlock) -> Option<ResourceInfo> {
                 name: extract_filename(&text_res.uri).into(),
                 mime_type: text_res.mime_type.clone().map(|s| s.into()),
                 text: Some(text_res.text.clone().into()),
+                blob: None,
+                kind: ResourceKind::Generic,
             }),
             EmbeddedResourceResource::BlobResourceContents(blob_res) => Some(ResourceInfo {
                 uri: blob_res.uri.clone().into(),
                 name: extract_filename(&blob_res.uri).into(),
                 mime_type: blob_res.mime_type.clone().map(|s| s.into()),
                 text: None, // Blob content is not displayable as text
+                blob: Some(blob_res.blob.clone().into()),
+                kind: resource_kind(blob_res.mime_type.as_deref(), &blob_res.uri),
             }),
             // Handle future variants
             _ => None,
         },
+        // Pasted images carry their data inline
+        ContentBlock::Image(image) => {
+            let uri = image.uri.clone().unwrap_or_default();
+            Some(ResourceInfo {
+                name: if uri.is_empty() {
+                    "image".into()
+                } else {
+                    extract_filename(&uri).into()
+                },
+                uri: uri.into(),
+                mime_type: Some(image.mime_type.clone().into()),
+                text: None,
+                blob: Some(image.data.clone().into()),
+                kind: ResourceKind::Image,
+            })
+        }
         _ => None,
     }
 }
@@ -106,15 +158,78 @@ fn extract_filename(uri: &str) -> String {
     uri.split('/').next_back().unwrap_or("unknown").to_string()
 }
 
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp", "svg"];
+
+/// Classify a resource by MIME type, falling back to the URI's extension
+fn resource_kind(mime_type: Option<&str>, uri: &str) -> ResourceKind {
+    if let Some(mime) = mime_type {
+        return if mime.starts_with("image/") {
+            ResourceKind::Image
+        } else {
+            ResourceKind::Generic
+        };
+    }
+
+    let extension = uri
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_ascii_lowercase());
+    match extension {
+        Some(ext) if IMAGE_EXTENSIONS.contains(&ext.as_str()) => ResourceKind::Image,
+        _ => ResourceKind::Generic,
+    }
+}
+
+/// How a resource is displayed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceKind {
+    /// Rendered as an inline thumbnail
+    Image,
+    /// Rendered as a collapsible chip
+    Generic,
+}
+
 /// Resource information for display
 pub struct ResourceInfo {
     pub uri: SharedString,
     pub name: SharedString,
     pub mime_type: Option<SharedString>,
     pub text: Option<SharedString>,
+    /// Base64 payload of blob resources and inline images
+    pub blob: Option<SharedString>,
+    pub kind: ResourceKind,
 }
 
 impl ResourceInfo {
+    pub fn is_image(&self) -> bool {
+        self.kind == ResourceKind::Image
+    }
+
+    /// Image source for the thumbnail, preferring inline data over the URI
+    fn image_source(&self) -> Option<ImageSource> {
+        if !self.is_image() {
+            return None;
+        }
+
+        if let Some(blob) = self.blob.as_ref() {
+            let format = self
+                .mime_type
+                .as_deref()
+                .and_then(|mime| ImageFormat::from_mime_type(mime))?;
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(blob.as_bytes())
+                .inspect_err(|err| log::warn!("Failed to decode image {}: {}", self.name, err))
+                .ok()?;
+            return Some(Arc::new(Image::from_bytes(format, bytes)).into());
+        }
+
+        if let Some(path) = self.uri.strip_prefix("file://") {
+            return Some(std::path::PathBuf::from(path).into());
+        }
+        (!self.uri.is_empty()).then(|| self.uri.clone().into())
+    }
+
     /// Get icon based on MIME type
     fn icon(&self) -> IconName {
         if let Some(ref mime) = self.mime_type {
@@ -131,16 +246,23 @@ impl ResourceInfo {
     }
 }
 
+/// Bounded size of image thumbnails
+const THUMBNAIL_MAX_SIZE: f32 = 160.;
+
 /// Resource item component (collapsible) - stateful version
 pub struct ResourceItem {
     resource: ResourceInfo,
+    /// Decoded once so re-renders don't decode the image again
+    image: Option<ImageSource>,
     open: bool,
 }
 
 impl ResourceItem {
     pub fn new(resource: ResourceInfo) -> Self {
+        let image = resource.image_source();
         Self {
             resource,
+            image,
             open: false,
         }
     }
@@ -158,8 +280,44 @@ impl ResourceItem {
     }
 }
 
+impl ResourceItem {
+    /// Inline thumbnail; clicking toggles between the bounded thumbnail and
+    /// the full image
+    fn render_image(&self, image: ImageSource, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_open = self.open;
+        let image = img(image).object_fit(ObjectFit::Contain).map(|this| {
+            if is_open {
+                this.max_w_full()
+            } else {
+                this.max_w(px(THUMBNAIL_MAX_SIZE))
+                    .max_h(px(THUMBNAIL_MAX_SIZE))
+            }
+        });
+
+        div()
+            .id(SharedString::from(format!(
+                "resource-image-{}",
+                self.resource.name
+            )))
+            .max_w_full()
+            .overflow_hidden()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().border)
+            .cursor_pointer()
+            .on_click(cx.listener(|this, _ev, _window, cx| {
+                this.toggle(cx);
+            }))
+            .child(image)
+    }
+}
+
 impl Render for ResourceItem {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if let Some(image) = self.image.clone() {
+            return self.render_image(image, cx).into_any_element();
+        }
+
         let line_count = self
             .resource
             .text
@@ -248,6 +406,7 @@ impl Render for ResourceItem {
                         ),
                 )
             })
+            .into_any_element()
     }
 }
 
@@ -316,6 +475,8 @@ fn parse_code_selection_text(text: &str) -> Option<CodeSelectionChip> {
 pub struct UserMessage {
     id: ElementId,
     data: UserMessageData,
+    show_timestamp: bool,
+    timestamp_formatter: Option<TimestampFormatter>,
 }
 
 impl UserMessage {
@@ -323,12 +484,44 @@ impl UserMessage {
         Self {
             id: id.into(),
             data,
+            show_timestamp: true,
+            timestamp_formatter: None,
         }
     }
+
+    /// Show when the message was sent, if it carries a timestamp
+    pub fn show_timestamp(mut self, show: bool) -> Self {
+        self.show_timestamp = show;
+        self
+    }
+
+    /// How to display the send time; without one no time is shown
+    pub fn timestamp_formatter(mut self, formatter: Option<TimestampFormatter>) -> Self {
+        self.timestamp_formatter = formatter;
+        self
+    }
+}
+
+/// Relative send time for the header, when enabled and known
+fn display_timestamp(
+    data: &UserMessageData,
+    show: bool,
+    formatter: Option<&TimestampFormatter>,
+) -> Option<String> {
+    data.timestamp
+        .filter(|_| show)
+        .zip(formatter)
+        .map(|(time, format)| format(time))
 }
 
 impl RenderOnce for UserMessage {
     fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let timestamp = display_timestamp(
+            &self.data,
+            self.show_timestamp,
+            self.timestamp_formatter.as_ref(),
+        );
+
         v_flex()
             .gap_3()
             .w_full()
@@ -348,7 +541,15 @@ impl RenderOnce for UserMessage {
                             .font_weight(gpui::FontWeight::SEMIBOLD)
                             .text_color(cx.theme().foreground)
                             .child("You"),
-                    ),
+                    )
+                    .when_some(timestamp, |this, timestamp| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(timestamp),
+                        )
+                    }),
             )
             // Message content
             .child(v_flex().gap_3().pl_6().w_full().children(
@@ -359,7 +560,7 @@ impl RenderOnce for UserMessage {
                                 .text_size(px(14.))
                                 .text_color(cx.theme().foreground)
                                 .line_height(px(22.))
-                                .child(text_content.text.clone())
+                                .child(user_display_text(text_content).to_string())
                                 .into_any_element(),
                         ),
                         // Skip resources in simple render - use UserMessageView for interactive resources
@@ -376,6 +577,8 @@ impl RenderOnce for UserMessage {
 pub struct UserMessageView {
     pub(crate) data: Entity<UserMessageData>,
     pub(crate) resource_items: Vec<Entity<ResourceItem>>,
+    pub(crate) show_timestamp: bool,
+    pub(crate) timestamp_formatter: Option<TimestampFormatter>,
 }
 
 impl UserMessageView {
@@ -394,6 +597,8 @@ impl UserMessageView {
             Self {
                 data: data_entity,
                 resource_items,
+                show_timestamp: true,
+                timestamp_formatter: None,
             }
         })
     }
@@ -420,7 +625,7 @@ impl UserMessageView {
     pub fn add_content(&mut self, content: ContentBlock, cx: &mut Context<Self>) {
         let is_resource = matches!(
             content,
-            ContentBlock::ResourceLink(_) | ContentBlock::Resource(_)
+            ContentBlock::ResourceLink(_) | ContentBlock::Resource(_) | ContentBlock::Image(_)
         );
 
         self.data.update(cx, |d, cx| {
@@ -439,6 +644,22 @@ impl UserMessageView {
         cx.notify();
     }
 
+    /// Show or hide the relative send time in the header
+    pub fn set_show_timestamp(&mut self, show: bool, cx: &mut Context<Self>) {
+        self.show_timestamp = show;
+        cx.notify();
+    }
+
+    /// Set how the send time is displayed; `None` hides it
+    pub fn set_timestamp_formatter(
+        &mut self,
+        formatter: Option<TimestampFormatter>,
+        cx: &mut Context<Self>,
+    ) {
+        self.timestamp_formatter = formatter;
+        cx.notify();
+    }
+
     /// Toggle resource open state by index
     pub fn toggle_resource(&mut self, index: usize, cx: &mut Context<Self>) {
         if let Some(item) = self.resource_items.get(index) {
@@ -461,6 +682,11 @@ impl UserMessageView {
 impl Render for UserMessageView {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let data = self.data.read(cx).clone();
+        let timestamp = display_timestamp(
+            &data,
+            self.show_timestamp,
+            self.timestamp_formatter.as_ref(),
+        );
         let mut resource_index = 0;
         let theme = cx.theme().clone();
 
@@ -499,7 +725,15 @@ impl Render for UserMessageView {
                             .font_weight(gpui::FontWeight::SEMIBOLD)
                             .text_color(cx.theme().foreground)
                             .child("You"),
-                    ),
+                    )
+                    .when_some(timestamp, |this, timestamp| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(timestamp),
+                        )
+                    }),
             )
             // Message content
             .child(
@@ -515,10 +749,12 @@ impl Render for UserMessageView {
                                     .text_size(px(14.))
                                     .text_color(theme.foreground)
                                     .line_height(px(22.))
-                                    .child(text_content.text.clone())
+                                    .child(user_display_text(text_content).to_string())
                                     .into_any_element(),
                             ),
-                            ContentBlock::ResourceLink(_) | ContentBlock::Resource(_) => {
+                            ContentBlock::ResourceLink(_)
+                            | ContentBlock::Resource(_)
+                            | ContentBlock::Image(_) => {
                                 if get_resource_info(&content).is_some() {
                                     let current_index = resource_index;
                                     resource_index += 1;
@@ -532,7 +768,7 @@ impl Render for UserMessageView {
                                     None
                                 }
                             }
-                            // Skip other content types for now (Image, Audio)
+                            // Skip other content types for now (Audio)
                             _ => None,
                         }
                     }))
@@ -573,6 +809,7 @@ impl Render for UserMessageView {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use agent_client_protocol::ImageContent;
 
     #[test]
     fn resource_info_from_link() {
@@ -592,4 +829,37 @@ mod tests {
         assert_eq!(info.name.as_ref(), "a.txt");
         assert_eq!(info.text.as_ref().unwrap().as_ref(), "text");
     }
+
+    #[test]
+    fn resource_info_classifies_images() {
+        for uri in ["file:///tmp/shot.png", "file:///tmp/photo.JPG"] {
+            let content = ContentBlock::ResourceLink(ResourceLink::new("image", uri));
+            let info = get_resource_info(&content).unwrap();
+            assert_eq!(info.kind, ResourceKind::Image, "{uri}");
+        }
+
+        let content = ContentBlock::Image(ImageContent::new("aGVsbG8=", "image/png"));
+        let info = get_resource_info(&content).unwrap();
+        assert!(info.is_image());
+        assert_eq!(info.blob.as_ref().unwrap().as_ref(), "aGVsbG8=");
+    }
+
+    #[test]
+    fn resource_info_classifies_other_types_as_generic() {
+        for uri in [
+            "file:///tmp/a.txt",
+            "file:///tmp/main.rs",
+            "file:///tmp/Makefile",
+        ] {
+            let content = ContentBlock::ResourceLink(ResourceLink::new("file", uri));
+            let info = get_resource_info(&content).unwrap();
+            assert_eq!(info.kind, ResourceKind::Generic, "{uri}");
+        }
+
+        // The MIME type wins over the extension
+        let content = ContentBlock::ResourceLink(
+            ResourceLink::new("data", "file:///tmp/data.png").mime_type("application/json"),
+        );
+        assert!(!get_resource_info(&content).unwrap().is_image());
+    }
 }
@@ -1,25 +1,66 @@
 use agent_client_protocol::{self as acp, ToolKind};
 use serde_json::Value;
 
+use crate::ansi::{StyledSpan, parse_ansi, strip_ansi};
+
+/// What `truncate_lines_with_info` left out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TruncationInfo {
+    /// Number of lines dropped
+    pub hidden_lines: usize,
+    /// Number of bytes of the original text dropped
+    pub hidden_bytes: usize,
+}
+
+impl TruncationInfo {
+    pub fn is_truncated(&self) -> bool {
+        self.hidden_lines > 0
+    }
+}
+
+/// Keep the first `max_lines` lines of `text` (all of it when `max_lines` is 0)
 pub fn truncate_lines(text: &str, max_lines: usize) -> String {
+    truncate_lines_with_info(text, max_lines).0
+}
+
+/// Like [`truncate_lines`], also reporting how much was hidden so the UI can
+/// offer a "show more" affordance
+pub fn truncate_lines_with_info(text: &str, max_lines: usize) -> (String, TruncationInfo) {
     if max_lines == 0 {
-        return text.to_string();
+        return (text.to_string(), TruncationInfo::default());
     }
 
     let mut result = String::new();
-    for (index, line) in text.lines().take(max_lines).enumerate() {
+    let mut consumed = 0;
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        if index == max_lines {
+            break;
+        }
         if index > 0 {
             result.push('\n');
         }
-        result.push_str(line);
+        result.push_str(line.trim_end_matches('\n').trim_end_matches('\r'));
+        consumed += line.len();
     }
 
-    result
+    let info = TruncationInfo {
+        hidden_lines: text.lines().count().saturating_sub(max_lines),
+        hidden_bytes: text.len() - consumed,
+    };
+    (result, info)
 }
 
+/// Terminal output as plain text, with ANSI escape sequences stripped
 pub fn extract_terminal_output(terminal: &acp::Terminal) -> Option<String> {
     let meta = terminal.meta.as_ref()?;
-    extract_terminal_output_from_meta(meta)
+    extract_terminal_output_from_meta(meta).map(|text| strip_ansi(&text))
+}
+
+/// Terminal output split into spans carrying the ANSI (SGR) colors and
+/// attributes; non-color escape sequences are stripped
+pub fn extract_terminal_output_styled(terminal: &acp::Terminal) -> Option<Vec<StyledSpan>> {
+    let meta = terminal.meta.as_ref()?;
+    extract_terminal_output_from_meta(meta).map(|text| parse_ansi(&text))
 }
 
 pub fn extract_xml_content(text: &str, tool_kind: &ToolKind) -> String {
@@ -40,11 +81,9 @@ pub fn extract_xml_content(text: &str, tool_kind: &ToolKind) -> String {
         return strip_code_fences(&filtered_text);
     }
 
-    let extracted = extract_tagged_text(&filtered_text);
-    if extracted.trim().is_empty() {
-        strip_code_fences(&filtered_text)
-    } else {
-        extracted
+    match extract_tagged_text(&filtered_text) {
+        Some(extracted) if !extracted.trim().is_empty() => extracted,
+        _ => strip_code_fences(&filtered_text),
     }
 }
 
@@ -76,7 +115,12 @@ fn strip_system_reminder_blocks(text: &str) -> String {
     output
 }
 
-fn extract_tagged_text(text: &str) -> String {
+/// Collect the trimmed content of every top-level tag in `text`.
+///
+/// Tag names may carry a namespace prefix (`<tool:result>`), and nested tags
+/// with the same name are balanced so the inner close does not end the outer
+/// element. Returns `None` if any opened tag is never closed.
+fn extract_tagged_text(text: &str) -> Option<String> {
     let mut result = String::new();
     let mut cursor = 0;
 
@@ -89,15 +133,7 @@ fn extract_tagged_text(text: &str) -> String {
             continue;
         }
 
-        let mut name_end = name_start;
-        for ch in text[name_start..].chars() {
-            if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
-                name_end += ch.len_utf8();
-            } else {
-                break;
-            }
-        }
-
+        let name_end = name_start + tag_name_len(&text[name_start..]);
         if name_end == name_start {
             cursor = name_start;
             continue;
@@ -113,16 +149,14 @@ fn extract_tagged_text(text: &str) -> String {
             Some(offset) => name_end + offset,
             None => break,
         };
+        if text[..open_end].ends_with('/') {
+            // Self-closing tag has no content
+            cursor = open_end + 1;
+            continue;
+        }
 
-        let closing_tag = format!("</{}>", tag_name);
         let after_open = open_end + 1;
-        let close_start = match text[after_open..].find(&closing_tag) {
-            Some(offset) => after_open + offset,
-            None => {
-                cursor = after_open;
-                continue;
-            }
-        };
+        let (close_start, close_end) = find_matching_close(text, after_open, tag_name)?;
 
         let content = text[after_open..close_start].trim();
         if !content.is_empty() {
@@ -132,10 +166,66 @@ fn extract_tagged_text(text: &str) -> String {
             result.push_str(content);
         }
 
-        cursor = close_start + closing_tag.len();
+        cursor = close_end;
+    }
+
+    Some(result)
+}
+
+/// Length in bytes of the tag name at the start of `text`, including an
+/// optional `namespace:` prefix.
+fn tag_name_len(text: &str) -> usize {
+    let mut len = 0;
+    for ch in text.chars() {
+        let allowed = ch.is_ascii_alphanumeric()
+            || ch == '_'
+            || ch == '-'
+            || (ch == ':' && len > 0 && !text[..len].contains(':'));
+        if !allowed {
+            break;
+        }
+        len += ch.len_utf8();
+    }
+    // A trailing colon is not part of the name
+    if text[..len].ends_with(':') {
+        len -= 1;
+    }
+    len
+}
+
+/// Find the close tag that balances an open `<tag_name>` whose content
+/// starts at `from`. Returns the byte range of that close tag.
+fn find_matching_close(text: &str, from: usize, tag_name: &str) -> Option<(usize, usize)> {
+    let mut depth = 1usize;
+    let mut cursor = from;
+
+    while let Some(offset) = text[cursor..].find('<') {
+        let tag_start = cursor + offset;
+        let is_close = text[tag_start + 1..].starts_with('/');
+        let name_start = tag_start + 1 + usize::from(is_close);
+        let name_end = name_start + tag_name_len(&text[name_start..]);
+        if &text[name_start..name_end] != tag_name {
+            cursor = name_start;
+            continue;
+        }
+
+        let tag_end = name_end + text[name_end..].find('>')?;
+
+        if is_close {
+            if text[name_end..tag_end].trim().is_empty() {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((tag_start, tag_end + 1));
+                }
+            }
+        } else if !text[..tag_end].ends_with('/') {
+            depth += 1;
+        }
+
+        cursor = tag_end + 1;
     }
 
-    result
+    None
 }
 
 fn extract_terminal_output_from_meta(meta: &serde_json::Map<String, Value>) -> Option<String> {
@@ -188,6 +278,38 @@ mod tests {
         assert_eq!(truncate_lines(text, 0), text);
     }
 
+    #[test]
+    fn truncate_lines_with_info_reports_nothing_hidden() {
+        let (text, info) = truncate_lines_with_info("line1\nline2", 5);
+        assert_eq!(text, "line1\nline2");
+        assert_eq!(info, TruncationInfo::default());
+        assert!(!info.is_truncated());
+
+        let (_, info) = truncate_lines_with_info("line1\nline2", 0);
+        assert_eq!(info, TruncationInfo::default());
+    }
+
+    #[test]
+    fn truncate_lines_with_info_exact_boundary() {
+        let (text, info) = truncate_lines_with_info("line1\nline2\n", 2);
+        assert_eq!(text, "line1\nline2");
+        assert_eq!(info.hidden_lines, 0);
+        assert_eq!(info.hidden_bytes, 0);
+    }
+
+    #[test]
+    fn truncate_lines_with_info_over_limit() {
+        let (text, info) = truncate_lines_with_info("line1\r\nline2\nline3\nline4", 2);
+        assert_eq!(text, "line1\nline2");
+        assert_eq!(
+            info,
+            TruncationInfo {
+                hidden_lines: 2,
+                hidden_bytes: "line3\nline4".len(),
+            }
+        );
+    }
+
     #[test]
     fn extract_xml_content_strips_code_fences() {
         let text = "```\ncontent\n```";
@@ -209,4 +331,55 @@ mod tests {
         let output = extract_terminal_output(&terminal).unwrap();
         assert_eq!(output, "line1\nline2");
     }
+
+    #[test]
+    fn extract_terminal_output_handles_ansi() {
+        let meta = serde_json::json!({ "output": "\x1b[32mok\x1b[0m\x1b[K done" })
+            .as_object()
+            .unwrap()
+            .clone();
+        let terminal = acp::Terminal::new("term-1").meta(meta);
+
+        assert_eq!(extract_terminal_output(&terminal).unwrap(), "ok done");
+
+        let spans = extract_terminal_output_styled(&terminal).unwrap();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "ok");
+        assert_eq!(
+            spans[0].style.foreground,
+            Some(crate::ansi::AnsiColor::Named(2))
+        );
+        assert_eq!(spans[1].text, " done");
+    }
+
+    #[test]
+    fn extract_tagged_text_balances_nested_tags() {
+        let text = "<output>outer <output>inner</output> tail</output>";
+        assert_eq!(
+            extract_tagged_text(text).as_deref(),
+            Some("outer <output>inner</output> tail")
+        );
+    }
+
+    #[test]
+    fn extract_tagged_text_supports_namespaced_tags() {
+        let text = "<tool:result>done</tool:result>\n<tool:result>again</tool:result>";
+        assert_eq!(extract_tagged_text(text).as_deref(), Some("done\nagain"));
+
+        // A different namespace does not close the tag
+        let text = "<tool:result>a</other:result>b</tool:result>";
+        assert_eq!(
+            extract_tagged_text(text).as_deref(),
+            Some("a</other:result>b")
+        );
+    }
+
+    #[test]
+    fn extract_tagged_text_rejects_unbalanced_input() {
+        assert_eq!(extract_tagged_text("<output>partial"), None);
+        assert_eq!(extract_tagged_text("<output>a <output>b</output>"), None);
+
+        let text = "<output>partial <output>content</output>";
+        assert_eq!(extract_xml_content(text, &ToolKind::Execute), text);
+    }
 }
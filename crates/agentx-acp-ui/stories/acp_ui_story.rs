@@ -165,6 +165,7 @@ impl AcpUiStory {
                     on_response: Some(Arc::new(|permission_id, response, _cx| {
                         log::info!("Permission {} responded with {:?}", permission_id, response);
                     })),
+                    ..Default::default()
                 },
             )
         });
@@ -4,13 +4,15 @@
 //! which agent binaries to spawn, and provides a REPL to interact with them.
 
 use std::{
-    collections::HashMap,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
     rc::Rc,
     sync::{
         Arc,
         atomic::{AtomicU64, AtomicUsize, Ordering},
     },
     thread,
+    time::{Duration, SystemTime},
 };
 
 use agent_client_protocol::{self as acp, Agent as _};
@@ -23,10 +25,20 @@ use tokio::{
     task::LocalSet,
 };
 
-use agentx_event_bus::{EventHub, PermissionRequestEvent, SessionUpdateEvent};
-use agentx_types::{AgentProcessConfig, ProxyConfig};
+use agentx_event_bus::{AgentConfigEvent, EventHub, PermissionRequestEvent, SessionUpdateEvent};
+use agentx_types::{
+    AgentProcessConfig, AgentRuntimeInfo, AgentRuntimeStatus, ProxyConfig, mask_proxy_url,
+};
 
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+use tracing::Instrument as _;
+
+/// How long an agent gets to wind down its turns and exit on its own before
+/// it is signalled to terminate
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long an agent gets to exit after SIGTERM before its processes are killed
+const TERMINATE_GRACE: Duration = Duration::from_secs(2);
 
 #[derive(Clone)]
 pub struct AgentManager {
@@ -53,6 +65,13 @@ impl AgentManager {
         }
     }
 
+    /// Register an already-built handle, e.g. [`AgentHandle::scripted`]
+    #[cfg(any(test, feature = "test-support"))]
+    pub async fn insert_handle(&self, handle: AgentHandle) {
+        let mut agents = self.agents.write().await;
+        agents.insert(handle.name.clone(), Arc::new(handle));
+    }
+
     pub async fn initialize(
         configs: HashMap<String, AgentProcessConfig>,
         permission_store: Arc<PermissionStore>,
@@ -62,6 +81,7 @@ impl AgentManager {
         if configs.is_empty() {
             return Err(anyhow!("no agents defined in config"));
         }
+        let configs: HashMap<_, _> = configs.into_iter().filter(|(_, cfg)| cfg.enabled).collect();
         let proxy_config = Arc::new(RwLock::new(proxy_config));
         let manager = Arc::new(Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
@@ -106,6 +126,23 @@ impl AgentManager {
             .and_then(|handle| handle.get_init_response())
     }
 
+    /// Current runtime status of every agent held by the manager
+    pub async fn agent_statuses(&self) -> HashMap<String, AgentRuntimeStatus> {
+        let agents = self.agents.read().await;
+        agents
+            .iter()
+            .map(|(name, handle)| (name.clone(), handle.status()))
+            .collect()
+    }
+
+    /// Every agent with its status and process details, sorted by name
+    pub async fn list_agents_detailed(&self) -> Vec<AgentRuntimeInfo> {
+        let agents = self.agents.read().await;
+        let mut list: Vec<_> = agents.values().map(|handle| handle.info()).collect();
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        list
+    }
+
     /// Get all agents with their initialize responses
     pub async fn list_agents_with_info(&self) -> Vec<(String, Option<acp::InitializeResponse>)> {
         let agents = self.agents.read().await;
@@ -117,6 +154,11 @@ impl AgentManager {
         list
     }
 
+    /// Store shared by every agent's permission requests
+    pub fn permission_store(&self) -> &Arc<PermissionStore> {
+        &self.permission_store
+    }
+
     pub async fn get(&self, name: &str) -> Option<Arc<AgentHandle>> {
         let agents = self.agents.read().await;
         agents.get(name).cloned()
@@ -181,18 +223,31 @@ impl AgentManager {
         Ok(true)
     }
 
-    /// Restart an agent with new configuration
-    pub async fn restart_agent(&self, name: &str, config: AgentProcessConfig) -> Result<()> {
-        // Remove old agent
-        let old_handle = {
+    /// Stop an agent and remove it from the manager. Running turns are
+    /// cancelled over ACP and its stdin is closed; if it has not exited within
+    /// `timeout` it is sent SIGTERM, then killed together with any processes
+    /// it spawned.
+    pub async fn shutdown_agent(&self, name: &str, timeout: Duration) -> Result<()> {
+        let handle = {
             let mut agents = self.agents.write().await;
             agents
                 .remove(name)
                 .ok_or_else(|| anyhow!("Agent '{}' not found", name))?
         };
 
-        // Shutdown old agent
-        if let Err(e) = old_handle.shutdown().await {
+        handle.shutdown_with_timeout(timeout).await?;
+        log::info!("Agent '{}' shut down", name);
+        Ok(())
+    }
+
+    /// Restart an agent with new configuration
+    pub async fn restart_agent(&self, name: &str, config: AgentProcessConfig) -> Result<()> {
+        if !self.agents.read().await.contains_key(name) {
+            return Err(anyhow!("Agent '{}' not found", name));
+        }
+
+        // Stop the old agent, including its child processes, before respawning
+        if let Err(e) = self.shutdown_agent(name, DEFAULT_SHUTDOWN_TIMEOUT).await {
             warn!("Failed to shutdown old agent '{}': {}", name, e);
         }
 
@@ -243,14 +298,154 @@ impl AgentManager {
     }
 }
 
+/// Tracks an agent's runtime status and announces each change on the bus
+#[derive(Clone)]
+struct StatusTracker {
+    name: String,
+    status: Arc<std::sync::RwLock<AgentRuntimeStatus>>,
+    event_hub: EventHub,
+}
+
+impl StatusTracker {
+    /// Start tracking in [`AgentRuntimeStatus::Starting`]
+    fn new(name: String, event_hub: EventHub) -> Self {
+        let tracker = Self {
+            name,
+            status: Arc::new(std::sync::RwLock::new(AgentRuntimeStatus::Starting)),
+            event_hub,
+        };
+        tracker.publish(AgentRuntimeStatus::Starting);
+        tracker
+    }
+
+    fn get(&self) -> AgentRuntimeStatus {
+        self.status.read().unwrap().clone()
+    }
+
+    /// Move to `next`, ignoring steps the state machine does not allow
+    fn set(&self, next: AgentRuntimeStatus) {
+        {
+            let mut status = self.status.write().unwrap();
+            if !status.can_transition_to(&next) {
+                log::debug!(
+                    "Agent {} ignoring status change {:?} -> {:?}",
+                    self.name,
+                    *status,
+                    next
+                );
+                return;
+            }
+            *status = next.clone();
+        }
+        self.publish(next);
+    }
+
+    fn publish(&self, status: AgentRuntimeStatus) {
+        log::info!("Agent {} status: {:?}", self.name, status);
+        self.event_hub
+            .publish_agent_config_update(AgentConfigEvent::AgentStatusChanged {
+                name: self.name.clone(),
+                status,
+            });
+    }
+}
+
+/// The OS process behind a running agent
+#[derive(Clone, Copy, Debug)]
+struct AgentProcess {
+    pid: u32,
+    started_at: SystemTime,
+}
+
 pub struct AgentHandle {
     name: String,
     sender: mpsc::Sender<AgentCommand>,
     /// Initialize response from the agent
     init_response: Arc<std::sync::RwLock<Option<acp::InitializeResponse>>>,
+    status: StatusTracker,
+    /// Set by the worker while the agent process is running
+    process: Arc<std::sync::RwLock<Option<AgentProcess>>>,
 }
 
 impl AgentHandle {
+    /// A handle with no agent process behind it, for driving status in tests
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn mock(name: impl Into<String>, event_hub: EventHub) -> Self {
+        let name = name.into();
+        let (sender, _receiver) = mpsc::channel(1);
+        Self {
+            status: StatusTracker::new(name.clone(), event_hub),
+            name,
+            sender,
+            init_response: Arc::new(std::sync::RwLock::new(None)),
+            process: Arc::new(std::sync::RwLock::new(None)),
+        }
+    }
+
+    /// A handle backed by an in-process fake agent. New sessions get
+    /// sequential ids and every prompt streams `reply` as agent message
+    /// chunks before ending the turn. With an empty `reply` the turn runs
+    /// until it is cancelled. Other requests fail.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn scripted(name: impl Into<String>, event_hub: EventHub, reply: Vec<String>) -> Self {
+        let name = name.into();
+        let (sender, mut receiver) = mpsc::channel(32);
+        let status = StatusTracker::new(name.clone(), event_hub.clone());
+        status.set(AgentRuntimeStatus::Ready);
+
+        let agent_name = name.clone();
+        thread::spawn(move || {
+            let mut sessions = 0;
+            let mut running = HashMap::new();
+            while let Some(command) = receiver.blocking_recv() {
+                match command {
+                    AgentCommand::NewSession { respond, .. } => {
+                        sessions += 1;
+                        let session_id = format!("{}-session-{}", agent_name, sessions);
+                        let _ = respond.send(Ok(acp::NewSessionResponse::new(session_id)));
+                    }
+                    AgentCommand::Prompt { request, respond } if reply.is_empty() => {
+                        running.insert(request.session_id.to_string(), respond);
+                    }
+                    AgentCommand::Prompt { request, respond } => {
+                        for chunk in &reply {
+                            event_hub.publish_session_update(SessionUpdateEvent {
+                                session_id: request.session_id.to_string(),
+                                agent_name: Some(agent_name.clone()),
+                                update: Arc::new(acp::SessionUpdate::AgentMessageChunk(
+                                    acp::ContentChunk::new(chunk.clone().into()),
+                                )),
+                            });
+                        }
+                        let _ =
+                            respond.send(Ok(acp::PromptResponse::new(acp::StopReason::EndTurn)));
+                    }
+                    AgentCommand::Cancel { request, respond } => {
+                        if let Some(turn) = running.remove(&request.session_id.to_string()) {
+                            let _ =
+                                turn.send(Ok(acp::PromptResponse::new(acp::StopReason::Cancelled)));
+                        }
+                        let _ = respond.send(Ok(()));
+                    }
+                    AgentCommand::Shutdown { respond, .. } => {
+                        let _ = respond.send(Ok(()));
+                        break;
+                    }
+                    // Dropping the responder reports the request as failed
+                    _ => {}
+                }
+            }
+        });
+
+        Self {
+            status,
+            name,
+            sender,
+            init_response: Arc::new(std::sync::RwLock::new(None)),
+            process: Arc::new(std::sync::RwLock::new(None)),
+        }
+    }
+
     async fn spawn(
         name: String,
         config: AgentProcessConfig,
@@ -262,13 +457,17 @@ impl AgentHandle {
         let (ready_tx, ready_rx) = oneshot::channel();
         let init_response = Arc::new(std::sync::RwLock::new(None));
         let init_response_clone = init_response.clone();
+        let status = StatusTracker::new(name.clone(), event_hub.clone());
+        let worker_status = status.clone();
+        let process = Arc::new(std::sync::RwLock::new(None));
+        let worker_process = process.clone();
         let thread_name = format!("agent-worker-{name}");
         let worker_name = name.clone();
         thread::Builder::new()
             .name(thread_name)
             .spawn(move || {
                 let log_name = worker_name.clone();
-                if let Err(err) = run_agent_worker(
+                match run_agent_worker(
                     worker_name,
                     config,
                     permission_store,
@@ -277,10 +476,23 @@ impl AgentHandle {
                     ready_tx,
                     init_response_clone,
                     proxy_config,
+                    worker_status.clone(),
+                    worker_process,
                 ) {
-                    error!("agent {log_name} exited with error: {:?}", err);
+                    Ok(()) => worker_status.set(AgentRuntimeStatus::Stopped),
+                    Err(err) => {
+                        error!("agent {log_name} exited with error: {:?}", err);
+                        worker_status.set(AgentRuntimeStatus::Failed {
+                            reason: err.to_string(),
+                        });
+                    }
                 }
             })
+            .inspect_err(|err| {
+                status.set(AgentRuntimeStatus::Failed {
+                    reason: err.to_string(),
+                })
+            })
             .context("failed to spawn worker thread")?;
         let start_name = name.clone();
         ready_rx
@@ -291,6 +503,8 @@ impl AgentHandle {
             name,
             sender,
             init_response,
+            status,
+            process,
         })
     }
 
@@ -435,11 +649,20 @@ impl AgentHandle {
         result
     }
 
-    /// Shutdown the agent gracefully
+    /// Shutdown the agent gracefully, waiting up to [`DEFAULT_SHUTDOWN_TIMEOUT`]
     pub async fn shutdown(&self) -> Result<()> {
+        self.shutdown_with_timeout(DEFAULT_SHUTDOWN_TIMEOUT).await
+    }
+
+    /// Shutdown the agent, signalling its process group if it has not exited
+    /// within `timeout`
+    pub async fn shutdown_with_timeout(&self, timeout: Duration) -> Result<()> {
         let (tx, rx) = oneshot::channel();
         self.sender
-            .send(AgentCommand::Shutdown { respond: tx })
+            .send(AgentCommand::Shutdown {
+                timeout,
+                respond: tx,
+            })
             .await
             .map_err(|_| anyhow!("agent {} is not running", self.name))?;
         rx.await
@@ -450,6 +673,22 @@ impl AgentHandle {
     pub fn get_init_response(&self) -> Option<acp::InitializeResponse> {
         self.init_response.read().unwrap().clone()
     }
+
+    /// Whether the agent finished the ACP handshake, failed, or was stopped
+    pub fn status(&self) -> AgentRuntimeStatus {
+        self.status.get()
+    }
+
+    /// Status plus the pid and start time of the running process
+    pub fn info(&self) -> AgentRuntimeInfo {
+        let process = *self.process.read().unwrap();
+        AgentRuntimeInfo {
+            name: self.name.clone(),
+            status: self.status(),
+            pid: process.map(|process| process.pid),
+            started_at: process.map(|process| process.started_at),
+        }
+    }
 }
 
 enum AgentCommand {
@@ -491,6 +730,7 @@ enum AgentCommand {
         respond: oneshot::Sender<Result<()>>,
     },
     Shutdown {
+        timeout: Duration,
         respond: oneshot::Sender<Result<()>>,
     },
 }
@@ -504,6 +744,8 @@ fn run_agent_worker(
     ready_tx: oneshot::Sender<Result<agent_client_protocol::InitializeResponse>>,
     init_response: Arc<std::sync::RwLock<Option<acp::InitializeResponse>>>,
     proxy_config: ProxyConfig,
+    status: StatusTracker,
+    process: Arc<std::sync::RwLock<Option<AgentProcess>>>,
 ) -> Result<()> {
     let runtime = RuntimeBuilder::new_current_thread()
         .enable_all()
@@ -522,6 +764,8 @@ fn run_agent_worker(
                 ready_tx,
                 init_response,
                 proxy_config,
+                status,
+                process,
             ))
             .await
     })
@@ -536,6 +780,8 @@ async fn agent_event_loop(
     ready_tx: oneshot::Sender<Result<agent_client_protocol::InitializeResponse>>,
     init_response: Arc<std::sync::RwLock<Option<acp::InitializeResponse>>>,
     proxy_config: ProxyConfig,
+    status: StatusTracker,
+    process: Arc<std::sync::RwLock<Option<AgentProcess>>>,
 ) -> Result<()> {
     // Node.js environment validation
     let requires_nodejs = config.command.ends_with(".js")
@@ -568,7 +814,10 @@ async fn agent_event_loop(
                 let error_msg = format!(
                     "Node.js required but not found for agent '{}'.\n\n{}",
                     agent_name,
-                    result.install_hint.unwrap_or_default()
+                    result
+                        .install_hint
+                        .map(|hint| hint.to_string())
+                        .unwrap_or_default()
                 );
                 log::error!("{}", error_msg);
                 let _ = ready_tx.send(Err(anyhow!(error_msg.clone())));
@@ -583,6 +832,19 @@ async fn agent_event_loop(
         }
     }
 
+    // A missing binary would otherwise only surface as an opaque spawn error.
+    // An agent with its own PATH is left for the spawn to resolve.
+    if !config.env.contains_key("PATH")
+        && crate::nodejs::resolve_command(&config.command)
+            .await
+            .is_none()
+    {
+        let error_msg = format!("Command '{}' not found on PATH", config.command);
+        log::error!("Agent '{}': {}", agent_name, error_msg);
+        let _ = ready_tx.send(Err(anyhow!(error_msg.clone())));
+        return Err(anyhow!(error_msg));
+    }
+
     let mut command = if cfg!(target_os = "windows") {
         let mut shell_cmd = tokio::process::Command::new("cmd");
         let mut full_args = vec!["/C".to_string(), config.command.clone()];
@@ -611,10 +873,20 @@ async fn agent_event_loop(
     if !proxy_envs.is_empty() {
         log::info!("Setting proxy env vars for agent '{}'", agent_name);
         for (key, value) in proxy_envs {
+            log::debug!("{}={}", key, mask_proxy_url(&value));
             command.env(key, value);
         }
     }
 
+    // Give the agent its own process group so shutdown can reach the node or
+    // MCP server processes it starts. The trade-off is that signals sent to
+    // the app's group (e.g. Ctrl+C in a terminal) no longer reach the agent,
+    // and if the app dies without shutting agents down the group is orphaned.
+    // Agents still see their stdin close then, which ACP agents treat as the
+    // signal to exit; only grandchildren that ignore it can outlive the app.
+    #[cfg(unix)]
+    command.process_group(0);
+
     // Set stdio for all platforms
     command.stdin(std::process::Stdio::piped());
     command.stdout(std::process::Stdio::piped());
@@ -623,6 +895,12 @@ async fn agent_event_loop(
     let mut child = command
         .spawn()
         .with_context(|| format!("failed to spawn agent {agent_name}"))?;
+    if let Some(pid) = child.id() {
+        *process.write().unwrap() = Some(AgentProcess {
+            pid,
+            started_at: SystemTime::now(),
+        });
+    }
     let outgoing = child
         .stdin
         .take()
@@ -666,6 +944,7 @@ async fn agent_event_loop(
         Ok(res) => {
             // Save the initialize response
             *init_response.write().unwrap() = Some(res.clone());
+            status.set(AgentRuntimeStatus::Ready);
             let _ = ready_tx.send(Ok(res));
         }
         Err(err) => {
@@ -675,6 +954,9 @@ async fn agent_event_loop(
         }
     }
 
+    // Sessions with a prompt in flight, cancelled when the agent shuts down
+    let active_prompts: Rc<RefCell<HashSet<acp::SessionId>>> = Rc::default();
+    let mut shutdown = None;
     while let Some(command) = command_rx.recv().await {
         match command {
             AgentCommand::Initialize { request, respond } => {
@@ -690,12 +972,15 @@ async fn agent_event_loop(
 
                 // Check if child process is still alive
                 match child.try_wait() {
-                    Ok(Some(status)) => {
+                    Ok(Some(exit_status)) => {
                         let error_msg = format!(
                             "Agent {} process exited with status: {:?}",
-                            agent_name, status
+                            agent_name, exit_status
                         );
                         log::error!("{}", error_msg);
+                        status.set(AgentRuntimeStatus::Failed {
+                            reason: error_msg.clone(),
+                        });
                         let _ = respond.send(Err(anyhow!(error_msg)));
                         continue;
                     }
@@ -728,11 +1013,23 @@ async fn agent_event_loop(
             AgentCommand::Prompt { request, respond } => {
                 let conn = conn.clone();
                 let agent_name = agent_name.clone();
-                tokio::task::spawn_local(async move {
-                    log::info!("Agent {} received prompt command", agent_name);
-                    let result = conn.prompt(request).await.map_err(|err| anyhow!(err));
-                    let _ = respond.send(result);
-                });
+                let span = tracing::info_span!(
+                    "agent_prompt",
+                    agent = %agent_name,
+                    session_id = %request.session_id,
+                );
+                let session_id = request.session_id.clone();
+                let active_prompts = active_prompts.clone();
+                active_prompts.borrow_mut().insert(session_id.clone());
+                tokio::task::spawn_local(
+                    async move {
+                        log::info!("Agent {} received prompt command", agent_name);
+                        let result = conn.prompt(request).await.map_err(|err| anyhow!(err));
+                        active_prompts.borrow_mut().remove(&session_id);
+                        let _ = respond.send(result);
+                    }
+                    .instrument(span),
+                );
             }
             AgentCommand::Cancel { request, respond } => {
                 log::info!("Agent {} received cancel command", agent_name);
@@ -766,9 +1063,9 @@ async fn agent_event_loop(
                     .map_err(|err| anyhow!(err));
                 let _ = respond.send(result);
             }
-            AgentCommand::Shutdown { respond } => {
+            AgentCommand::Shutdown { timeout, respond } => {
                 log::info!("Agent {} received shutdown command", agent_name);
-                let _ = respond.send(Ok(()));
+                shutdown = Some((timeout, respond));
                 break; // Exit the command loop to shutdown
             }
         }
@@ -776,33 +1073,128 @@ async fn agent_event_loop(
 
     log::info!("Agent {} command loop ended, cleaning up", agent_name);
 
+    let (timeout, respond) = match shutdown {
+        Some((timeout, respond)) => (timeout, Some(respond)),
+        None => (DEFAULT_SHUTDOWN_TIMEOUT, None),
+    };
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    // Cancel running turns so the agent can wind them down, and give them
+    // until the deadline to finish
+    let running: Vec<_> = active_prompts.borrow().iter().cloned().collect();
+    for session_id in running {
+        log::info!("Agent {} cancelling turn in {}", agent_name, session_id);
+        if let Err(e) = conn.cancel(acp::CancelNotification::new(session_id)).await {
+            log::debug!("Agent {} cancel failed: {:?}", agent_name, e);
+        }
+    }
+    let _ = tokio::time::timeout_at(deadline, async {
+        while !active_prompts.borrow().is_empty() {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await;
+
+    // Dropping the connection's I/O task closes the agent's stdin, the ACP
+    // signal to exit
     drop(conn);
+    io_handle.abort();
     let _ = io_handle.await;
 
-    // Check if child process is still running
-    match child.try_wait() {
-        Ok(Some(status)) => {
-            log::warn!(
-                "Agent {} process already exited with status: {:?}",
+    let result = terminate_process(
+        &mut child,
+        deadline.saturating_duration_since(tokio::time::Instant::now()),
+    )
+    .await;
+    *process.write().unwrap() = None;
+    let result = match result {
+        Ok(ProcessExit::Graceful) => Ok(()),
+        Ok(ProcessExit::Terminated) => {
+            log::info!(
+                "Agent {} did not exit within {:?}, terminated it",
                 agent_name,
-                status
+                timeout
             );
+            Ok(())
         }
-        Ok(None) => {
-            // Process is still running, kill it
-            log::info!("Agent {} process still running, killing it", agent_name);
-            if let Err(e) = child.kill().await {
-                log::error!("Failed to kill agent {} process: {}", agent_name, e);
-            }
+        Ok(ProcessExit::Killed) => {
+            log::warn!(
+                "Agent {} ignored SIGTERM for {:?}, killed it",
+                agent_name,
+                TERMINATE_GRACE
+            );
+            Ok(())
         }
         Err(e) => {
-            log::error!("Failed to check agent {} process status: {}", agent_name, e);
+            log::error!("Failed to stop agent {} process: {}", agent_name, e);
+            Err(e)
         }
+    };
+    if let Some(respond) = respond {
+        let _ = respond.send(result);
     }
 
     Ok(())
 }
 
+/// How an agent process ended during shutdown
+#[derive(Debug, PartialEq, Eq)]
+enum ProcessExit {
+    /// Exited on its own once its stdin was closed
+    Graceful,
+    /// Exited after SIGTERM
+    #[cfg_attr(not(unix), allow(dead_code))]
+    Terminated,
+    /// Killed after ignoring SIGTERM
+    Killed,
+}
+
+/// Wait up to `timeout` for the agent process to exit on its own, then ask it
+/// (on Unix, its whole process group) to terminate and kill it if it is still
+/// running after [`TERMINATE_GRACE`]
+async fn terminate_process(
+    child: &mut tokio::process::Child,
+    timeout: Duration,
+) -> Result<ProcessExit> {
+    if let Ok(status) = tokio::time::timeout(timeout, child.wait()).await {
+        status?;
+        return Ok(ProcessExit::Graceful);
+    }
+
+    #[cfg(unix)]
+    {
+        signal_process_group(child, libc::SIGTERM);
+        if let Ok(status) = tokio::time::timeout(TERMINATE_GRACE, child.wait()).await {
+            status?;
+            return Ok(ProcessExit::Terminated);
+        }
+    }
+
+    #[cfg(unix)]
+    signal_process_group(child, libc::SIGKILL);
+    let _ = child.start_kill();
+    child.wait().await?;
+    Ok(ProcessExit::Killed)
+}
+
+/// Send `signal` to the process group led by `child`, reaching the processes
+/// the agent spawned as well
+#[cfg(unix)]
+fn signal_process_group(child: &tokio::process::Child, signal: libc::c_int) {
+    let Some(pid) = child.id() else {
+        return;
+    };
+    // SAFETY: `kill` has no memory safety requirements; a negative pid
+    // addresses the process group created by `process_group(0)` at spawn
+    if unsafe { libc::kill(-(pid as libc::pid_t), signal) } != 0 {
+        log::debug!(
+            "Failed to signal process group {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
 /// GUI Client that publishes session updates to the event bus
 struct GuiClient {
     agent_name: String,
@@ -830,6 +1222,20 @@ impl acp::Client for GuiClient {
         &self,
         args: acp::RequestPermissionRequest,
     ) -> acp::Result<acp::RequestPermissionResponse> {
+        let tool_kind = args.tool_call.fields.kind.unwrap_or_default();
+        if let Some(response) = self.permission_store.resolve_remembered(
+            &args.session_id.to_string(),
+            tool_kind,
+            &args.options,
+        ) {
+            log::debug!(
+                "[GuiClient] Auto-resolved permission for {:?} in session '{}' from remembered choice",
+                tool_kind,
+                args.session_id
+            );
+            return Ok(response);
+        }
+
         let (tx, rx) = oneshot::channel();
         let permission_id = self
             .permission_store
@@ -909,6 +1315,22 @@ impl acp::Client for GuiClient {
             args.update
         );
 
+        // Tool calls get their own span so their dispatch can be followed
+        let tool_call_id = match &args.update {
+            acp::SessionUpdate::ToolCall(tool_call) => Some(&tool_call.tool_call_id),
+            acp::SessionUpdate::ToolCallUpdate(update) => Some(&update.tool_call_id),
+            _ => None,
+        };
+        let _tool_call_span = tool_call_id.map(|tool_call_id| {
+            tracing::debug_span!(
+                "tool_call",
+                agent = %self.agent_name,
+                session_id = %args.session_id,
+                tool_call_id = %tool_call_id,
+            )
+            .entered()
+        });
+
         // Publish event to the session bus
         let event = SessionUpdateEvent {
             session_id: args.session_id.to_string(),
@@ -938,13 +1360,142 @@ pub struct PendingPermission {
     responder: oneshot::Sender<acp::RequestPermissionResponse>,
 }
 
+/// How widely a remembered permission decision applies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PermissionScope {
+    /// Only for the session the decision was made in
+    #[default]
+    Session,
+    /// For every session
+    Global,
+}
+
+/// Hashable stand-in for [`acp::ToolKind`], which doesn't implement `Hash`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ToolKindKey {
+    Read,
+    Edit,
+    Delete,
+    Move,
+    Search,
+    Execute,
+    Think,
+    Fetch,
+    SwitchMode,
+    Other,
+}
+
+impl From<acp::ToolKind> for ToolKindKey {
+    fn from(kind: acp::ToolKind) -> Self {
+        match kind {
+            acp::ToolKind::Read => Self::Read,
+            acp::ToolKind::Edit => Self::Edit,
+            acp::ToolKind::Delete => Self::Delete,
+            acp::ToolKind::Move => Self::Move,
+            acp::ToolKind::Search => Self::Search,
+            acp::ToolKind::Execute => Self::Execute,
+            acp::ToolKind::Think => Self::Think,
+            acp::ToolKind::Fetch => Self::Fetch,
+            acp::ToolKind::SwitchMode => Self::SwitchMode,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum RememberedKey {
+    Session(String, ToolKindKey),
+    Global(ToolKindKey),
+}
+
 #[derive(Default)]
 pub struct PermissionStore {
     pending: RwLock<HashMap<String, PendingPermission>>,
     next_id: AtomicU64,
+    /// "Don't ask again" decisions, consulted before prompting
+    remembered: std::sync::RwLock<HashMap<RememberedKey, acp::PermissionOptionKind>>,
 }
 
 impl PermissionStore {
+    /// Remember `kind` for future requests of `tool_kind` in `session_id`
+    pub fn remember_choice(
+        &self,
+        session_id: &str,
+        tool_kind: acp::ToolKind,
+        kind: acp::PermissionOptionKind,
+    ) {
+        self.remember_choice_with_scope(PermissionScope::Session, session_id, tool_kind, kind);
+    }
+
+    /// Remember `kind` for future requests of `tool_kind`, either in
+    /// `session_id` only or in every session
+    pub fn remember_choice_with_scope(
+        &self,
+        scope: PermissionScope,
+        session_id: &str,
+        tool_kind: acp::ToolKind,
+        kind: acp::PermissionOptionKind,
+    ) {
+        let key = match scope {
+            PermissionScope::Session => {
+                RememberedKey::Session(session_id.to_string(), tool_kind.into())
+            }
+            PermissionScope::Global => RememberedKey::Global(tool_kind.into()),
+        };
+        log::info!(
+            "Remembering permission {:?} for {:?} ({:?} scope)",
+            kind,
+            tool_kind,
+            scope
+        );
+        self.remembered
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, kind);
+    }
+
+    /// The remembered decision for `tool_kind` in `session_id`, preferring a
+    /// session decision over a global one
+    pub fn remembered_choice(
+        &self,
+        session_id: &str,
+        tool_kind: acp::ToolKind,
+    ) -> Option<acp::PermissionOptionKind> {
+        let remembered = self.remembered.read().unwrap_or_else(|e| e.into_inner());
+        remembered
+            .get(&RememberedKey::Session(
+                session_id.to_string(),
+                tool_kind.into(),
+            ))
+            .or_else(|| remembered.get(&RememberedKey::Global(tool_kind.into())))
+            .copied()
+    }
+
+    /// Drop the decisions remembered for one session
+    pub fn forget_session(&self, session_id: &str) {
+        self.remembered
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|key, _| !matches!(key, RememberedKey::Session(id, _) if id == session_id));
+    }
+
+    /// Build a response for a request that matches a remembered decision.
+    /// Returns `None` when the user should be prompted.
+    pub fn resolve_remembered(
+        &self,
+        session_id: &str,
+        tool_kind: acp::ToolKind,
+        options: &[acp::PermissionOption],
+    ) -> Option<acp::RequestPermissionResponse> {
+        let kind = self.remembered_choice(session_id, tool_kind)?;
+        let option = options.iter().find(|option| option.kind == kind)?;
+        Some(acp::RequestPermissionResponse::new(
+            acp::RequestPermissionOutcome::Selected(acp::SelectedPermissionOutcome::new(
+                option.option_id.clone(),
+            )),
+        ))
+    }
+
     pub async fn add(
         &self,
         agent: String,
@@ -984,3 +1535,277 @@ impl PermissionStore {
         self.pending.write().await.remove(id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> Vec<acp::PermissionOption> {
+        vec![
+            acp::PermissionOption::new(
+                "allow",
+                "Allow always",
+                acp::PermissionOptionKind::AllowAlways,
+            ),
+            acp::PermissionOption::new(
+                "reject",
+                "Reject always",
+                acp::PermissionOptionKind::RejectAlways,
+            ),
+        ]
+    }
+
+    fn selected_option(response: acp::RequestPermissionResponse) -> String {
+        match response.outcome {
+            acp::RequestPermissionOutcome::Selected(selected) => selected.option_id.to_string(),
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+    }
+
+    /// Spawn `script` in its own process group, returning once it is running
+    #[cfg(unix)]
+    async fn spawn_script(script: &str) -> tokio::process::Child {
+        use tokio::io::AsyncBufReadExt;
+
+        let mut child = tokio::process::Command::new("sh")
+            .args(["-c", script])
+            .process_group(0)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let mut line = String::new();
+        tokio::io::BufReader::new(stdout)
+            .read_line(&mut line)
+            .await
+            .unwrap();
+        assert_eq!(line.trim(), "ready");
+        child
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn process_ignoring_sigterm_is_killed_after_timeout() {
+        let mut child = spawn_script("trap '' TERM; echo ready; sleep 30").await;
+
+        let timeout = Duration::from_millis(200);
+        let started = std::time::Instant::now();
+        let exit = terminate_process(&mut child, timeout).await.unwrap();
+
+        assert_eq!(exit, ProcessExit::Killed);
+        assert!(started.elapsed() >= timeout);
+        assert!(child.try_wait().unwrap().is_some());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn process_exiting_on_closed_stdin_is_not_signalled() {
+        let mut child = spawn_script("echo ready; read _").await;
+        drop(child.stdin.take());
+
+        let exit = terminate_process(&mut child, Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(exit, ProcessExit::Graceful);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn process_still_running_after_timeout_is_terminated() {
+        let mut child = spawn_script("echo ready; sleep 30").await;
+
+        let exit = terminate_process(&mut child, Duration::from_millis(200))
+            .await
+            .unwrap();
+        assert_eq!(exit, ProcessExit::Terminated);
+    }
+
+    #[tokio::test]
+    async fn detailed_list_reflects_started_agents() {
+        let manager = AgentManager::new(
+            HashMap::new(),
+            Arc::new(PermissionStore::default()),
+            EventHub::new(),
+            ProxyConfig::default(),
+        );
+
+        let starting = AgentHandle::mock("b-starting", EventHub::new());
+        let started = AgentHandle::mock("a-started", EventHub::new());
+        let started_at = SystemTime::now();
+        *started.process.write().unwrap() = Some(AgentProcess {
+            pid: 4242,
+            started_at,
+        });
+        started.status.set(AgentRuntimeStatus::Ready);
+        {
+            let mut agents = manager.agents.write().await;
+            agents.insert(starting.name.clone(), Arc::new(starting));
+            agents.insert(started.name.clone(), Arc::new(started));
+        }
+
+        let detailed = manager.list_agents_detailed().await;
+        assert_eq!(
+            detailed,
+            vec![
+                AgentRuntimeInfo {
+                    name: "a-started".to_string(),
+                    status: AgentRuntimeStatus::Ready,
+                    pid: Some(4242),
+                    started_at: Some(started_at),
+                },
+                AgentRuntimeInfo {
+                    name: "b-starting".to_string(),
+                    status: AgentRuntimeStatus::Starting,
+                    pid: None,
+                    started_at: None,
+                },
+            ]
+        );
+        assert!(detailed[0].uptime().is_some());
+        assert!(detailed[1].uptime().is_none());
+
+        // The simple listing is unchanged
+        assert_eq!(manager.list_agents().await, vec!["a-started", "b-starting"]);
+    }
+
+    #[test]
+    fn handle_status_follows_the_handshake_state_machine() {
+        let event_hub = EventHub::new();
+        let published = Arc::new(std::sync::Mutex::new(Vec::new()));
+        event_hub.subscribe_agent_config_agent_events({
+            let published = published.clone();
+            move |event| {
+                if let AgentConfigEvent::AgentStatusChanged { name, status } = event {
+                    published
+                        .lock()
+                        .unwrap()
+                        .push((name.clone(), status.clone()));
+                }
+            }
+        });
+
+        let handle = AgentHandle::mock("mock-agent", event_hub);
+        assert_eq!(handle.status(), AgentRuntimeStatus::Starting);
+
+        handle.status.set(AgentRuntimeStatus::Ready);
+        assert_eq!(handle.status(), AgentRuntimeStatus::Ready);
+
+        // A ready agent cannot go back to starting
+        handle.status.set(AgentRuntimeStatus::Starting);
+        assert_eq!(handle.status(), AgentRuntimeStatus::Ready);
+
+        let failed = AgentRuntimeStatus::Failed {
+            reason: "process exited".to_string(),
+        };
+        handle.status.set(failed.clone());
+        assert_eq!(handle.status(), failed);
+
+        // Failure is final: the failure reason stays visible after shutdown
+        handle.status.set(AgentRuntimeStatus::Stopped);
+        assert_eq!(handle.status(), failed);
+
+        let published = published.lock().unwrap().clone();
+        assert_eq!(
+            published,
+            vec![
+                ("mock-agent".to_string(), AgentRuntimeStatus::Starting),
+                ("mock-agent".to_string(), AgentRuntimeStatus::Ready),
+                ("mock-agent".to_string(), failed),
+            ]
+        );
+    }
+
+    #[test]
+    fn remembers_allow_and_deny_per_tool() {
+        let store = PermissionStore::default();
+        store.remember_choice(
+            "session-1",
+            acp::ToolKind::Execute,
+            acp::PermissionOptionKind::AllowAlways,
+        );
+        store.remember_choice(
+            "session-1",
+            acp::ToolKind::Delete,
+            acp::PermissionOptionKind::RejectAlways,
+        );
+
+        let allow = store
+            .resolve_remembered("session-1", acp::ToolKind::Execute, &options())
+            .unwrap();
+        assert_eq!(selected_option(allow), "allow");
+
+        let deny = store
+            .resolve_remembered("session-1", acp::ToolKind::Delete, &options())
+            .unwrap();
+        assert_eq!(selected_option(deny), "reject");
+    }
+
+    #[test]
+    fn other_tools_and_sessions_still_prompt() {
+        let store = PermissionStore::default();
+        store.remember_choice(
+            "session-1",
+            acp::ToolKind::Execute,
+            acp::PermissionOptionKind::AllowAlways,
+        );
+
+        assert!(
+            store
+                .resolve_remembered("session-1", acp::ToolKind::Edit, &options())
+                .is_none()
+        );
+        assert!(
+            store
+                .resolve_remembered("session-2", acp::ToolKind::Execute, &options())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn global_scope_applies_to_every_session() {
+        let store = PermissionStore::default();
+        store.remember_choice_with_scope(
+            PermissionScope::Global,
+            "session-1",
+            acp::ToolKind::Read,
+            acp::PermissionOptionKind::AllowAlways,
+        );
+        store.remember_choice(
+            "session-2",
+            acp::ToolKind::Read,
+            acp::PermissionOptionKind::RejectAlways,
+        );
+
+        assert_eq!(
+            store.remembered_choice("session-3", acp::ToolKind::Read),
+            Some(acp::PermissionOptionKind::AllowAlways)
+        );
+        // Session decisions win over global ones
+        assert_eq!(
+            store.remembered_choice("session-2", acp::ToolKind::Read),
+            Some(acp::PermissionOptionKind::RejectAlways)
+        );
+
+        store.forget_session("session-2");
+        assert_eq!(
+            store.remembered_choice("session-2", acp::ToolKind::Read),
+            Some(acp::PermissionOptionKind::AllowAlways)
+        );
+    }
+
+    #[test]
+    fn remembered_kind_missing_from_options_prompts() {
+        let store = PermissionStore::default();
+        store.remember_choice(
+            "session-1",
+            acp::ToolKind::Execute,
+            acp::PermissionOptionKind::AllowOnce,
+        );
+        assert!(
+            store
+                .resolve_remembered("session-1", acp::ToolKind::Execute, &options())
+                .is_none()
+        );
+    }
+}
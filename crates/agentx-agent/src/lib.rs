@@ -1,4 +1,4 @@
 pub mod client;
 pub mod nodejs;
 
-pub use client::{AgentHandle, AgentManager, PermissionStore};
+pub use client::{AgentHandle, AgentManager, PermissionScope, PermissionStore};
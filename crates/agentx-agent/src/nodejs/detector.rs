@@ -157,6 +157,17 @@ async fn try_which_from_login_shell(command: &str) -> Option<PathBuf> {
     None
 }
 
+/// Resolve the program an agent's `command` launches: a path is checked as
+/// is, a bare name is looked up on PATH like `which`/`where` would
+pub async fn resolve_command(command: &str) -> Option<PathBuf> {
+    let is_path = command.contains('/') || (cfg!(windows) && command.contains('\\'));
+    if is_path {
+        let path = PathBuf::from(command);
+        return path.is_file().then_some(path);
+    }
+    try_which_command(command).await
+}
+
 /// Try to find command using 'where' (Windows) or 'which' (Unix)
 async fn try_which_command(command: &str) -> Option<PathBuf> {
     #[cfg(target_os = "windows")]
@@ -179,7 +190,7 @@ async fn try_which_command(command: &str) -> Option<PathBuf> {
 
             let path = PathBuf::from(first_path);
             if path.exists() {
-                log::debug!("Found Node.js via {}: {}", which_cmd, path.display());
+                log::debug!("Found {} via {}: {}", command, which_cmd, path.display());
                 return Some(path);
             }
         }
@@ -280,6 +291,103 @@ async fn check_nvm_unix() -> Option<PathBuf> {
     None
 }
 
+/// Runs external programs for detection probes, so tests can fake the output
+pub trait CommandRunner {
+    /// Run `program` with `args`, returning its stdout if it exits successfully
+    fn run(&self, program: &str, args: &[&str]) -> impl Future<Output = Option<Vec<u8>>> + Send;
+}
+
+/// [`CommandRunner`] that spawns real processes with [`COMMAND_TIMEOUT`]
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    async fn run(&self, program: &str, args: &[&str]) -> Option<Vec<u8>> {
+        match tokio::time::timeout(COMMAND_TIMEOUT, new_command(program).args(args).output()).await
+        {
+            Ok(Ok(output)) if output.status.success() => Some(output.stdout),
+            Ok(Ok(output)) => {
+                log::debug!("{} {:?} exited with {}", program, args, output.status);
+                None
+            }
+            Ok(Err(e)) => {
+                log::debug!("Failed to run {}: {}", program, e);
+                None
+            }
+            Err(_) => {
+                log::warn!(
+                    "{} {:?} timed out after {:?}",
+                    program,
+                    args,
+                    COMMAND_TIMEOUT
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Node.js running inside the default WSL distribution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WslNodeJs {
+    /// Path of node inside the distribution
+    pub path: PathBuf,
+    pub version: String,
+}
+
+/// Detect Node.js inside WSL, the last resort when no Windows-native
+/// installation exists
+#[cfg(target_os = "windows")]
+pub async fn detect_wsl_nodejs() -> Option<WslNodeJs> {
+    probe_wsl_nodejs(&SystemCommandRunner).await
+}
+
+/// Ask `wsl.exe` for node's version and location
+pub async fn probe_wsl_nodejs(runner: &impl CommandRunner) -> Option<WslNodeJs> {
+    let output = runner.run("wsl.exe", &["node", "--version"]).await?;
+    let version = parse_wsl_node_version(&output)?;
+    // `which` failing is not fatal: `wsl node` still resolves it from PATH
+    let path = match runner.run("wsl.exe", &["which", "node"]).await {
+        Some(output) => decode_wsl_output(&output)
+            .lines()
+            .map(str::trim)
+            .find(|line| line.starts_with('/'))
+            .map(PathBuf::from),
+        None => None,
+    }
+    .unwrap_or_else(|| PathBuf::from("node"));
+
+    log::debug!("Found Node.js {} in WSL: {}", version, path.display());
+    Some(WslNodeJs { path, version })
+}
+
+/// Parse the output of `wsl.exe node --version`, e.g. `v20.11.1`
+fn parse_wsl_node_version(output: &[u8]) -> Option<String> {
+    decode_wsl_output(output)
+        .lines()
+        .map(str::trim)
+        .rev()
+        .find(|line| !line.is_empty())
+        .filter(|line| {
+            line.strip_prefix('v')
+                .and_then(|rest| rest.chars().next())
+                .is_some_and(|c| c.is_ascii_digit())
+        })
+        .map(str::to_string)
+}
+
+/// `wsl.exe` writes its own messages as UTF-16LE, while programs inside the
+/// distribution write UTF-8
+fn decode_wsl_output(output: &[u8]) -> String {
+    if output.len() >= 2 && output.len().is_multiple_of(2) && output[1] == 0 {
+        let units = output
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect::<Vec<_>>();
+        return String::from_utf16_lossy(&units);
+    }
+    String::from_utf8_lossy(output).to_string()
+}
+
 /// Recursively search for node.exe (Windows) or node (Unix) in a directory
 fn search_for_node_in_directory(dir: &Path) -> Option<PathBuf> {
     search_for_node_in_directory_inner(dir, 0)
@@ -400,10 +508,86 @@ mod tests {
         log::debug!("Detected Node.js: {:?}", result);
     }
 
+    #[tokio::test]
+    async fn test_resolve_command() {
+        #[cfg(windows)]
+        let real = "cmd";
+        #[cfg(not(windows))]
+        let real = "sh";
+
+        let found = resolve_command(real)
+            .await
+            .expect("shell should be on PATH");
+        assert!(found.is_file());
+        assert_eq!(resolve_command("agentx-no-such-command").await, None);
+
+        // Paths are checked directly instead of searched for
+        let found = found.to_str().unwrap();
+        assert_eq!(resolve_command(found).await, Some(PathBuf::from(found)));
+        assert_eq!(resolve_command("/nonexistent/agent").await, None);
+    }
+
     #[tokio::test]
     async fn test_verify_invalid_path() {
         let invalid_path = PathBuf::from("/nonexistent/node");
         let result = verify_nodejs_executable(&invalid_path).await;
         assert!(result.is_err());
     }
+
+    struct FakeRunner {
+        version: &'static [u8],
+        which: Option<&'static [u8]>,
+    }
+
+    impl CommandRunner for FakeRunner {
+        async fn run(&self, program: &str, args: &[&str]) -> Option<Vec<u8>> {
+            assert_eq!(program, "wsl.exe");
+            match args {
+                ["node", "--version"] => Some(self.version.to_vec()),
+                ["which", "node"] => self.which.map(<[u8]>::to_vec),
+                _ => None,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_wsl_nodejs() {
+        let runner = FakeRunner {
+            version: b"v20.11.1\r\n",
+            which: Some(b"/home/dev/.nvm/versions/node/v20.11.1/bin/node\n"),
+        };
+        assert_eq!(
+            probe_wsl_nodejs(&runner).await,
+            Some(WslNodeJs {
+                path: PathBuf::from("/home/dev/.nvm/versions/node/v20.11.1/bin/node"),
+                version: "v20.11.1".to_string(),
+            })
+        );
+
+        let runner = FakeRunner {
+            version: b"v18.19.0\n",
+            which: None,
+        };
+        let found = probe_wsl_nodejs(&runner).await.unwrap();
+        assert_eq!(found.path, PathBuf::from("node"));
+    }
+
+    #[test]
+    fn test_parse_wsl_node_version() {
+        assert_eq!(
+            parse_wsl_node_version(b"v20.11.1\n").as_deref(),
+            Some("v20.11.1")
+        );
+        // wsl.exe reports a missing distribution in UTF-16LE
+        let utf16 = "Windows Subsystem for Linux has no installed distributions.\r\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+        assert_eq!(parse_wsl_node_version(&utf16), None);
+        assert_eq!(
+            parse_wsl_node_version(b"/bin/bash: node: command not found\n"),
+            None
+        );
+        assert_eq!(parse_wsl_node_version(b""), None);
+    }
 }
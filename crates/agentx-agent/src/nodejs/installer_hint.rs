@@ -1,3 +1,4 @@
+use std::fmt;
 use std::path::Path;
 
 /// Supported package managers across platforms
@@ -108,55 +109,86 @@ pub async fn detect_package_manager() -> PackageManager {
     PackageManager::Unknown
 }
 
-/// Generate a helpful installation hint for the user
-pub async fn generate_install_hint() -> String {
-    let pm = detect_package_manager().await;
-
-    let mut hint = String::new();
+/// Official Node.js download page
+pub const NODEJS_DOWNLOAD_URL: &str = "https://nodejs.org/en/download";
 
-    if pm != PackageManager::Unknown {
-        hint.push_str(&format!(
-            "Install using {} ({}
+/// Structured installation guidance, so the UI can offer the command and the
+/// download link separately
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallHint {
+    /// Detected package manager
+    pub package_manager: PackageManager,
+    /// Command installing Node.js with `package_manager`; empty when unknown
+    pub command: String,
+    /// Where to download Node.js manually
+    pub url: String,
+}
 
-):\n   {}\n\n",
-            pm.name(),
-            get_platform_name(),
-            pm.install_command()
-        ));
+impl InstallHint {
+    /// Installation guidance for `package_manager`
+    pub fn new(package_manager: PackageManager) -> Self {
+        Self {
+            package_manager,
+            command: package_manager.install_command().to_string(),
+            url: NODEJS_DOWNLOAD_URL.to_string(),
+        }
     }
+}
 
-    // Always provide manual download option
-    hint.push_str("Or download manually from:\n");
-    hint.push_str("   https://nodejs.org/\n\n");
+impl fmt::Display for InstallHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pm = self.package_manager;
 
-    // Platform-specific additional guidance
-    #[cfg(target_os = "windows")]
-    {
-        if pm == PackageManager::Unknown {
-            hint.push_str("You can also install Chocolatey first:\n");
-            hint.push_str("   https://chocolatey.org/install\n\n");
+        if !self.command.is_empty() {
+            write!(
+                f,
+                "Install using {} ({}):\n   {}\n\n",
+                pm.name(),
+                get_platform_name(),
+                self.command
+            )?;
         }
-    }
 
-    #[cfg(target_os = "macos")]
-    {
-        if pm == PackageManager::Unknown {
-            hint.push_str("You can also install Homebrew first:\n");
-            hint.push_str("   https://brew.sh/\n\n");
+        // Always provide manual download option
+        write!(f, "Or download manually from:\n   {}\n\n", self.url)?;
+
+        // Platform-specific additional guidance
+        #[cfg(target_os = "windows")]
+        {
+            if pm == PackageManager::Unknown {
+                f.write_str("You can also install Chocolatey first:\n")?;
+                f.write_str("   https://chocolatey.org/install\n\n")?;
+            }
         }
-    }
 
-    #[cfg(target_os = "linux")]
-    {
-        hint.push_str("Or use nvm (Node Version Manager):\n");
-        hint.push_str("   curl -o- https://raw.githubusercontent.com/nvm-sh/nvm/v0.39.0/install.sh | bash\n\n");
+        #[cfg(target_os = "macos")]
+        {
+            if pm == PackageManager::Unknown {
+                f.write_str("You can also install Homebrew first:\n")?;
+                f.write_str("   https://brew.sh/\n\n")?;
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            f.write_str("Or use nvm (Node Version Manager):\n")?;
+            f.write_str("   curl -o- https://raw.githubusercontent.com/nvm-sh/nvm/v0.39.0/install.sh | bash\n\n")?;
+        }
+
+        f.write_str("After installation, you may need to:\n")?;
+        f.write_str("1. Restart this application\n")?;
+        f.write_str("2. Or configure the Node.js path in: Settings > General > Node.js Path")
     }
+}
 
-    hint.push_str("After installation, you may need to:\n");
-    hint.push_str("1. Restart this application\n");
-    hint.push_str("2. Or configure the Node.js path in: Settings > General > Node.js Path");
+/// Detect the package manager and build installation guidance for it
+pub async fn detect_install_hint() -> InstallHint {
+    InstallHint::new(detect_package_manager().await)
+}
 
-    hint
+/// Generate a helpful installation hint for the user
+pub async fn generate_install_hint() -> String {
+    detect_install_hint().await.to_string()
 }
 
 /// Check if a command exists in PATH
@@ -225,6 +257,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_install_hint_fields() {
+        let hint = InstallHint::new(PackageManager::Homebrew);
+        assert_eq!(hint.command, "brew install node");
+        assert_eq!(hint.url, "https://nodejs.org/en/download");
+
+        let hint = InstallHint::new(PackageManager::Winget);
+        assert_eq!(hint.command, "winget install OpenJS.NodeJS");
+        assert_eq!(hint.url, NODEJS_DOWNLOAD_URL);
+
+        let hint = InstallHint::new(PackageManager::Unknown);
+        assert!(hint.command.is_empty());
+        assert_eq!(hint.url, NODEJS_DOWNLOAD_URL);
+    }
+
+    #[test]
+    fn test_install_hint_formatting() {
+        let text = InstallHint::new(PackageManager::Pacman).to_string();
+        assert!(text.contains("Install using Pacman"));
+        assert!(text.contains("   sudo pacman -S nodejs npm\n"));
+        assert!(text.contains(NODEJS_DOWNLOAD_URL));
+
+        let text = InstallHint::new(PackageManager::Unknown).to_string();
+        assert!(!text.contains("Install using"));
+        assert!(text.contains(NODEJS_DOWNLOAD_URL));
+    }
+
     #[tokio::test]
     async fn test_detect_package_manager() {
         // This test will return different results based on the system
@@ -1,8 +1,13 @@
 mod detector;
 mod error;
 mod installer_hint;
+mod version_manager;
 
-pub use installer_hint::{PackageManager, generate_install_hint};
+pub use detector::resolve_command;
+pub use installer_hint::{
+    InstallHint, NODEJS_DOWNLOAD_URL, PackageManager, detect_install_hint, generate_install_hint,
+};
+pub use version_manager::{InstalledNode, NodeVersion, VersionManager};
 
 use anyhow::Result;
 use std::path::PathBuf;
@@ -22,7 +27,9 @@ pub struct NodeJsCheckResult {
     /// Error message if Node.js is not available
     pub error_message: Option<String>,
     /// Installation hint for the user
-    pub install_hint: Option<String>,
+    pub install_hint: Option<InstallHint>,
+    /// Whether `path` is inside WSL, so launching it needs a `wsl` prefix
+    pub is_wsl: bool,
 }
 
 /// Detection strategy for Node.js discovery
@@ -38,6 +45,8 @@ pub enum NodeJsDetectionMode {
 pub struct NodeJsChecker {
     custom_path: Option<PathBuf>,
     detection_mode: NodeJsDetectionMode,
+    version_manager: Option<VersionManager>,
+    workspace_path: Option<PathBuf>,
 }
 
 impl NodeJsChecker {
@@ -50,6 +59,8 @@ impl NodeJsChecker {
         Self {
             custom_path,
             detection_mode: NodeJsDetectionMode::Full,
+            version_manager: None,
+            workspace_path: None,
         }
     }
 
@@ -59,6 +70,20 @@ impl NodeJsChecker {
         self
     }
 
+    /// Prefer the highest Node.js version installed by `manager` over the
+    /// one found on PATH.
+    pub fn with_version_manager(mut self, manager: VersionManager) -> Self {
+        self.version_manager = Some(manager);
+        self
+    }
+
+    /// Pick the version manager's Node.js from a `.nvmrc` in `path` or one of
+    /// its parents.
+    pub fn with_workspace_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.workspace_path = Some(path.into());
+        self
+    }
+
     /// Check if Node.js is available
     ///
     /// Returns a detailed result with path, version, and installation hints if needed.
@@ -75,6 +100,7 @@ impl NodeJsChecker {
                         version: Some(version),
                         error_message: None,
                         install_hint: None,
+                        is_wsl: false,
                     });
                 }
                 Err(e) => {
@@ -84,7 +110,42 @@ impl NodeJsChecker {
             }
         }
 
-        // Priority 2-4: Auto-detection (PATH, standard locations, NVM)
+        // Priority 2: Version installed by the configured version manager
+        if let Some(manager) = self.version_manager {
+            if let Some(installed) =
+                version_manager::find_managed_nodejs(manager, self.workspace_path.as_deref())
+            {
+                log::debug!(
+                    "Found Node.js {} via {:?}: {}",
+                    installed.version,
+                    installed.manager,
+                    installed.path.display()
+                );
+
+                match detector::verify_nodejs_executable(&installed.path).await {
+                    Ok(version) => {
+                        return Ok(NodeJsCheckResult {
+                            available: true,
+                            path: Some(installed.path),
+                            version: Some(version),
+                            error_message: None,
+                            install_hint: None,
+                            is_wsl: false,
+                        });
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "Node.js {} from {:?} failed verification: {}",
+                            installed.version,
+                            installed.manager,
+                            e
+                        );
+                    }
+                }
+            }
+        }
+
+        // Priority 3-5: Auto-detection (PATH, standard locations, NVM)
         if let Some(detected_path) = detector::detect_system_nodejs(self.detection_mode).await {
             log::debug!("Auto-detected Node.js at: {}", detected_path.display());
 
@@ -96,6 +157,7 @@ impl NodeJsChecker {
                         version: Some(version),
                         error_message: None,
                         install_hint: None,
+                        is_wsl: false,
                     });
                 }
                 Err(e) => {
@@ -108,10 +170,26 @@ impl NodeJsChecker {
             }
         }
 
+        // Last resort on Windows: node inside WSL (full mode only, since it
+        // may boot the WSL VM)
+        #[cfg(target_os = "windows")]
+        if self.detection_mode == NodeJsDetectionMode::Full
+            && let Some(wsl) = detector::detect_wsl_nodejs().await
+        {
+            return Ok(NodeJsCheckResult {
+                available: true,
+                path: Some(wsl.path),
+                version: Some(wsl.version),
+                error_message: None,
+                install_hint: None,
+                is_wsl: true,
+            });
+        }
+
         // Node.js not found - generate installation hint
         log::warn!("Node.js not found on system");
 
-        let install_hint = installer_hint::generate_install_hint().await;
+        let install_hint = installer_hint::detect_install_hint().await;
 
         Ok(NodeJsCheckResult {
             available: false,
@@ -119,6 +197,7 @@ impl NodeJsChecker {
             version: None,
             error_message: Some("Node.js is not installed or could not be found".to_string()),
             install_hint: Some(install_hint),
+            is_wsl: false,
         })
     }
 
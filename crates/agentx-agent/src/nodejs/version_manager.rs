@@ -0,0 +1,283 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Node.js version manager whose installed versions can be selected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionManager {
+    /// nvm, or nvm-windows on Windows
+    Nvm,
+    /// fnm (Fast Node Manager)
+    Fnm,
+    /// Every supported manager that is installed
+    Auto,
+}
+
+impl VersionManager {
+    fn concrete(self) -> &'static [VersionManager] {
+        match self {
+            VersionManager::Nvm => &[VersionManager::Nvm],
+            VersionManager::Fnm => &[VersionManager::Fnm],
+            VersionManager::Auto => &[VersionManager::Nvm, VersionManager::Fnm],
+        }
+    }
+
+    /// Directory holding one subdirectory per installed version
+    fn versions_dir(self) -> Option<PathBuf> {
+        match self {
+            #[cfg(target_os = "windows")]
+            VersionManager::Nvm => std::env::var_os("NVM_HOME")
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("nvm"))),
+            #[cfg(not(target_os = "windows"))]
+            VersionManager::Nvm => std::env::var_os("NVM_DIR")
+                .map(PathBuf::from)
+                .or_else(|| dirs::home_dir().map(|home| home.join(".nvm")))
+                .map(|dir| dir.join("versions").join("node")),
+            VersionManager::Fnm => std::env::var_os("FNM_DIR")
+                .map(PathBuf::from)
+                .or_else(|| dirs::data_dir().map(|dir| dir.join("fnm")))
+                .map(|dir| dir.join("node-versions")),
+            VersionManager::Auto => None,
+        }
+    }
+
+    /// Node executable inside one version's directory
+    fn node_path(self, version_dir: &Path) -> PathBuf {
+        let install_dir = match self {
+            VersionManager::Fnm => version_dir.join("installation"),
+            _ => version_dir.to_path_buf(),
+        };
+
+        #[cfg(target_os = "windows")]
+        return install_dir.join("node.exe");
+        #[cfg(not(target_os = "windows"))]
+        install_dir.join("bin").join("node")
+    }
+}
+
+/// Semantic Node.js version such as `v20.11.1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl NodeVersion {
+    /// Parse `v20.11.1` or `20.11.1`
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().trim_start_matches('v').split('.');
+        let version = Self {
+            major: parts.next()?.parse().ok()?,
+            minor: parts.next()?.parse().ok()?,
+            patch: parts.next()?.parse().ok()?,
+        };
+        parts.next().is_none().then_some(version)
+    }
+
+    fn matches(&self, prefix: &[u64]) -> bool {
+        [self.major, self.minor, self.patch]
+            .iter()
+            .zip(prefix)
+            .all(|(part, wanted)| part == wanted)
+    }
+}
+
+impl fmt::Display for NodeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A Node.js version installed by a version manager
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledNode {
+    pub manager: VersionManager,
+    pub version: NodeVersion,
+    pub path: PathBuf,
+}
+
+/// Version requested by a `.nvmrc`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VersionPin {
+    /// `node`, `stable` or an `lts/*` alias; the highest installed version
+    Latest,
+    /// `20`, `v20.11` or `20.11.1`
+    Prefix(Vec<u64>),
+}
+
+impl VersionPin {
+    fn parse(contents: &str) -> Option<Self> {
+        let value = contents
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .find(|line| !line.is_empty())?;
+
+        if matches!(value, "node" | "stable" | "latest") || value.starts_with("lts/") {
+            return Some(VersionPin::Latest);
+        }
+
+        let prefix = value
+            .trim_start_matches('v')
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        (!prefix.is_empty() && prefix.len() <= 3).then_some(VersionPin::Prefix(prefix))
+    }
+
+    fn matches(&self, version: &NodeVersion) -> bool {
+        match self {
+            VersionPin::Latest => true,
+            VersionPin::Prefix(prefix) => version.matches(prefix),
+        }
+    }
+}
+
+/// Find the Node.js version to use from `manager`'s installed versions,
+/// honoring a `.nvmrc` in `workspace` or one of its parents
+pub fn find_managed_nodejs(
+    manager: VersionManager,
+    workspace: Option<&Path>,
+) -> Option<InstalledNode> {
+    let dirs = manager
+        .concrete()
+        .iter()
+        .filter_map(|manager| Some((*manager, manager.versions_dir()?)))
+        .collect::<Vec<_>>();
+    find_in_dirs(&dirs, workspace)
+}
+
+fn find_in_dirs(
+    dirs: &[(VersionManager, PathBuf)],
+    workspace: Option<&Path>,
+) -> Option<InstalledNode> {
+    let mut installed = dirs
+        .iter()
+        .flat_map(|(manager, dir)| installed_versions(*manager, dir))
+        .collect::<Vec<_>>();
+    installed.sort_by_key(|node| std::cmp::Reverse(node.version));
+
+    let pin = workspace.and_then(read_nvmrc);
+    if let Some(pin) = &pin {
+        if let Some(found) = installed.iter().find(|node| pin.matches(&node.version)) {
+            log::debug!(
+                "Selected Node.js {} for .nvmrc: {}",
+                found.version,
+                found.path.display()
+            );
+            return Some(found.clone());
+        }
+        log::warn!(
+            "No installed Node.js matches .nvmrc ({:?}), using the highest version",
+            pin
+        );
+    }
+
+    installed.into_iter().next()
+}
+
+/// Versions installed under `versions_dir` that have a node executable
+fn installed_versions(manager: VersionManager, versions_dir: &Path) -> Vec<InstalledNode> {
+    let Ok(entries) = std::fs::read_dir(versions_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let version = NodeVersion::parse(entry.file_name().to_str()?)?;
+            let path = manager.node_path(&entry.path());
+            path.is_file().then_some(InstalledNode {
+                manager,
+                version,
+                path,
+            })
+        })
+        .collect()
+}
+
+fn read_nvmrc(workspace: &Path) -> Option<VersionPin> {
+    workspace.ancestors().find_map(|dir| {
+        let path = dir.join(".nvmrc");
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let pin = VersionPin::parse(&contents);
+        if pin.is_none() {
+            log::warn!("Ignoring unsupported version in {}", path.display());
+        }
+        pin
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeNvm {
+        root: PathBuf,
+    }
+
+    impl FakeNvm {
+        fn new(name: &str, versions: &[&str]) -> Self {
+            let root =
+                std::env::temp_dir().join(format!("agentx-nodejs-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&root);
+            for version in versions {
+                let node = VersionManager::Nvm.node_path(&root.join("versions").join(version));
+                std::fs::create_dir_all(node.parent().unwrap()).unwrap();
+                std::fs::write(&node, "").unwrap();
+            }
+            // Not a version, and a version without an executable
+            std::fs::create_dir_all(root.join("versions").join("system")).unwrap();
+            std::fs::create_dir_all(root.join("versions").join("v21.0.0")).unwrap();
+            Self { root }
+        }
+
+        fn dirs(&self) -> Vec<(VersionManager, PathBuf)> {
+            vec![(VersionManager::Nvm, self.root.join("versions"))]
+        }
+    }
+
+    impl Drop for FakeNvm {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn selects_highest_installed_version() {
+        let nvm = FakeNvm::new("highest", &["v9.11.2", "v18.16.0", "v20.1.0", "v20.10.0"]);
+
+        let found = find_in_dirs(&nvm.dirs(), None).unwrap();
+
+        assert_eq!(found.version, NodeVersion::parse("v20.10.0").unwrap());
+        assert_eq!(
+            found.path,
+            VersionManager::Nvm.node_path(&nvm.root.join("versions").join("v20.10.0"))
+        );
+    }
+
+    #[test]
+    fn honors_nvmrc_in_workspace_parent() {
+        let nvm = FakeNvm::new("nvmrc", &["v16.20.2", "v18.16.0", "v18.19.1", "v20.1.0"]);
+        let workspace = nvm.root.join("project").join("src");
+        std::fs::create_dir_all(&workspace).unwrap();
+        std::fs::write(nvm.root.join("project").join(".nvmrc"), "v18\n").unwrap();
+
+        let found = find_in_dirs(&nvm.dirs(), Some(&workspace)).unwrap();
+        assert_eq!(found.version, NodeVersion::parse("18.19.1").unwrap());
+
+        std::fs::write(nvm.root.join("project").join(".nvmrc"), "22\n").unwrap();
+        let found = find_in_dirs(&nvm.dirs(), Some(&workspace)).unwrap();
+        assert_eq!(found.version, NodeVersion::parse("20.1.0").unwrap());
+    }
+
+    #[test]
+    fn parses_nvmrc_values() {
+        assert_eq!(VersionPin::parse("lts/iron\n"), Some(VersionPin::Latest));
+        assert_eq!(
+            VersionPin::parse("# pinned\nv20.11\n"),
+            Some(VersionPin::Prefix(vec![20, 11]))
+        );
+        assert_eq!(VersionPin::parse("iojs"), None);
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use crate::core::{EventBusContainer, EventBusStats, SubscriptionId};
 use agentx_types::{
     AgentConfigEvent, CodeSelectionEvent, Config, PermissionRequestEvent, SessionStatus,
@@ -291,6 +293,50 @@ impl EventHub {
         )
     }
 
+    pub fn subscribe_cwd_changes<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&Path) + Send + Sync + 'static,
+    {
+        self.subscribe_with_filter(
+            move |event| {
+                if let AppEvent::WorkspaceUpdate(WorkspaceUpdateEvent::CwdChanged { path }) = event
+                {
+                    callback(path);
+                }
+                true
+            },
+            |event| {
+                matches!(
+                    event,
+                    AppEvent::WorkspaceUpdate(WorkspaceUpdateEvent::CwdChanged { .. })
+                )
+            },
+        )
+    }
+
+    pub fn subscribe_active_agent_changes<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.subscribe_with_filter(
+            move |event| {
+                if let AppEvent::WorkspaceUpdate(WorkspaceUpdateEvent::ActiveAgentChanged {
+                    agent_name,
+                }) = event
+                {
+                    callback(agent_name);
+                }
+                true
+            },
+            |event| {
+                matches!(
+                    event,
+                    AppEvent::WorkspaceUpdate(WorkspaceUpdateEvent::ActiveAgentChanged { .. })
+                )
+            },
+        )
+    }
+
     pub fn subscribe_agent_config_updates<F>(&self, callback: F) -> SubscriptionId
     where
         F: Fn(&AgentConfigEvent) + Send + Sync + 'static,
@@ -324,6 +370,7 @@ impl EventHub {
                         AgentConfigEvent::AgentAdded { .. }
                             | AgentConfigEvent::AgentUpdated { .. }
                             | AgentConfigEvent::AgentRemoved { .. }
+                            | AgentConfigEvent::AgentStatusChanged { .. }
                     )
                 )
             },
@@ -444,6 +491,7 @@ impl EventHub {
                         AgentConfigEvent::AgentAdded { name, .. }
                             | AgentConfigEvent::AgentUpdated { name, .. }
                             | AgentConfigEvent::AgentRemoved { name }
+                            | AgentConfigEvent::AgentStatusChanged { name, .. }
                     ) if name == &agent_name
                 )
             },
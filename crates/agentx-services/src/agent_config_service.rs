@@ -3,13 +3,16 @@
 //! This service manages agent configuration CRUD operations, validation,
 //! persistence, and hot-reload functionality.
 
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 use crate::AgentService;
 use agentx_agent::AgentManager;
-use agentx_event_bus::{AgentConfigEvent, EventHub};
+use agentx_event_bus::{AgentConfigEvent, ConfigSection, EventHub};
 use agentx_types::{AgentProcessConfig, Config};
 use anyhow::{Context, Result, anyhow};
 
@@ -27,6 +30,75 @@ pub struct AgentConfigService {
     agent_service: Option<Arc<AgentService>>,
     /// Event hub for publishing configuration changes
     event_hub: EventHub,
+    /// Hash of the contents last written by [`Self::write_config_file`], so the
+    /// file watcher can ignore our own saves
+    last_written_hash: Mutex<Option<u64>>,
+    /// Node.js path from the app settings, injected into agents started by
+    /// hot-reload the same way it is at startup
+    nodejs_path: Mutex<Option<String>>,
+}
+
+/// How long to wait before retrying a failed configuration save
+const SAVE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Hash of a configuration file's contents
+pub(crate) fn content_hash(contents: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Agents to start, stop and restart to bring the running agents in line
+/// with a reloaded configuration. Names are sorted.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AgentChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub restarted: Vec<String>,
+}
+
+impl AgentChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.restarted.is_empty()
+    }
+}
+
+/// Compare the running agents (started from `old`) with the agents in `new`.
+///
+/// Agents missing from `running` are started even when their config did not
+/// change, so fixing a broken entry brings the agent up; disabled agents are
+/// treated as absent. Only the fields read from the file are compared; the
+/// runtime-only `nodejs_path` is ignored.
+pub fn diff_agents(
+    running: &[String],
+    old: &HashMap<String, AgentProcessConfig>,
+    new: &HashMap<String, AgentProcessConfig>,
+) -> AgentChanges {
+    let same_process = |a: &AgentProcessConfig, b: &AgentProcessConfig| {
+        a.command == b.command && a.args == b.args && a.env == b.env
+    };
+
+    let mut changes = AgentChanges::default();
+    for (name, config) in new.iter().filter(|(_, config)| config.enabled) {
+        if !running.contains(name) {
+            changes.added.push(name.clone());
+        } else if !old
+            .get(name)
+            .is_some_and(|previous| same_process(previous, config))
+        {
+            changes.restarted.push(name.clone());
+        }
+    }
+    changes.removed = running
+        .iter()
+        .filter(|name| !new.get(*name).is_some_and(|config| config.enabled))
+        .cloned()
+        .collect();
+
+    changes.added.sort();
+    changes.removed.sort();
+    changes.restarted.sort();
+    changes
 }
 
 impl AgentConfigService {
@@ -43,6 +115,8 @@ impl AgentConfigService {
             agent_manager,
             agent_service: None,
             event_hub,
+            last_written_hash: Mutex::new(None),
+            nodejs_path: Mutex::new(None),
         }
     }
 
@@ -51,6 +125,19 @@ impl AgentConfigService {
         self.agent_service = Some(agent_service);
     }
 
+    /// Set the custom Node.js path used for agents started by hot-reload
+    pub fn set_nodejs_path(&self, nodejs_path: Option<String>) {
+        *self.nodejs_path.lock().unwrap() = nodejs_path;
+    }
+
+    /// `config` with the runtime-only settings applied
+    fn runtime_config(&self, mut config: AgentProcessConfig) -> AgentProcessConfig {
+        if let Some(nodejs_path) = self.nodejs_path.lock().unwrap().clone() {
+            config.nodejs_path = Some(nodejs_path);
+        }
+        config
+    }
+
     // ========== Query Operations ==========
 
     /// List all configured agents
@@ -225,7 +312,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Agents).await?;
 
         // Publish event
         self.event_hub
@@ -238,23 +325,28 @@ impl AgentConfigService {
         Ok(())
     }
 
-    /// Update an existing agent's configuration
-    pub async fn update_agent(&self, name: &str, config: AgentProcessConfig) -> Result<()> {
+    /// Update an existing agent's configuration. The agent stays enabled or
+    /// disabled as it was.
+    pub async fn update_agent(&self, name: &str, mut config: AgentProcessConfig) -> Result<()> {
         // Validate command
         self.validate_command(&config.command)?;
 
         // Check if agent exists
         {
             let current_config = self.config.read().await;
-            if !current_config.agent_servers.contains_key(name) {
-                return Err(anyhow!("Agent '{}' not found", name));
-            }
+            let existing = current_config
+                .agent_servers
+                .get(name)
+                .ok_or_else(|| anyhow!("Agent '{}' not found", name))?;
+            config.enabled = existing.enabled;
         }
 
         // Restart agent with new config (hot-reload)
-        self.agent_manager
-            .restart_agent(name, config.clone())
-            .await?;
+        if config.enabled {
+            self.agent_manager
+                .restart_agent(name, config.clone())
+                .await?;
+        }
 
         // Update config
         {
@@ -265,7 +357,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Agents).await?;
 
         // Publish event
         self.event_hub
@@ -304,7 +396,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Agents).await?;
 
         // Publish event
         self.event_hub
@@ -316,6 +408,80 @@ impl AgentConfigService {
         Ok(())
     }
 
+    /// Enable or disable several agents at once, starting or stopping them as
+    /// needed. The configuration is written once and a single
+    /// [`AgentConfigEvent::ConfigReloaded`] is published.
+    pub async fn set_agents_enabled(&self, names: &[String], enabled: bool) -> Result<()> {
+        let (old_agents, updated_config) = {
+            let mut config = self.config.write().await;
+            if let Some(name) = names
+                .iter()
+                .find(|name| !config.agent_servers.contains_key(*name))
+            {
+                return Err(anyhow!("Agent '{}' not found", name));
+            }
+
+            let old_agents = config.agent_servers.clone();
+            for name in names {
+                if let Some(agent) = config.agent_servers.get_mut(name) {
+                    agent.enabled = enabled;
+                }
+            }
+            (old_agents, config.clone())
+        };
+
+        self.save_to_file(ConfigSection::Agents).await?;
+
+        let running = self.agent_manager.list_agents().await;
+        let changes = diff_agents(&running, &old_agents, &updated_config.agent_servers);
+        if !changes.is_empty() {
+            // Covered by the reload event below
+            let _ = self
+                .apply_agent_changes(&changes, &updated_config.agent_servers)
+                .await;
+        }
+
+        self.event_hub
+            .publish_agent_config_update(AgentConfigEvent::ConfigReloaded {
+                config: Box::new(updated_config),
+            });
+
+        log::info!(
+            "Successfully {} {} agent(s)",
+            if enabled { "enabled" } else { "disabled" },
+            names.len()
+        );
+        Ok(())
+    }
+
+    /// Set the icon shown for `name`, or restore the default with `None`.
+    /// `icon` is a built-in logo name or the path of an SVG file; the agent
+    /// does not have to be configured yet.
+    pub async fn set_agent_icon(&self, name: &str, icon: Option<String>) -> Result<()> {
+        let updated_config = {
+            let mut config = self.config.write().await;
+            match &icon {
+                Some(icon) => {
+                    config.agent_icons.insert(name.to_string(), icon.clone());
+                }
+                None => {
+                    config.agent_icons.remove(name);
+                }
+            }
+            config.clone()
+        };
+
+        self.save_to_file(ConfigSection::Agents).await?;
+
+        self.event_hub
+            .publish_agent_config_update(AgentConfigEvent::ConfigReloaded {
+                config: Box::new(updated_config),
+            });
+
+        log::info!("Successfully updated icon for agent '{}'", name);
+        Ok(())
+    }
+
     /// Update proxy configuration
     pub async fn update_proxy_config(
         &self,
@@ -327,7 +493,7 @@ impl AgentConfigService {
             config.clone()
         };
 
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Network).await?;
 
         self.event_hub
             .publish_agent_config_update(AgentConfigEvent::ConfigReloaded {
@@ -360,7 +526,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Models).await?;
 
         // Publish event
         self.event_hub
@@ -396,7 +562,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Models).await?;
 
         // Publish event
         self.event_hub
@@ -426,7 +592,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Models).await?;
 
         // Publish event
         self.event_hub
@@ -463,7 +629,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::McpServers).await?;
 
         // Publish event
         self.event_hub
@@ -499,7 +665,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::McpServers).await?;
 
         // Publish event
         self.event_hub
@@ -529,7 +695,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::McpServers).await?;
 
         // Publish event
         self.event_hub
@@ -564,7 +730,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Commands).await?;
 
         // Publish event
         self.event_hub
@@ -600,7 +766,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Commands).await?;
 
         // Publish event
         self.event_hub
@@ -630,7 +796,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Commands).await?;
 
         // Publish event
         self.event_hub
@@ -652,6 +818,9 @@ impl AgentConfigService {
                 .cloned()
                 .ok_or_else(|| anyhow!("Agent '{}' not found", name))?
         };
+        if !config.enabled {
+            return Err(anyhow!("Agent '{}' is disabled", name));
+        }
 
         // Validate command before restart
         self.validate_command(&config.command)?;
@@ -674,16 +843,32 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::General).await?;
 
         log::info!("Successfully updated upload_dir to: {:?}", path);
         Ok(())
     }
 
+    /// Set how many lines of tool call output are shown inline (0 disables truncation)
+    pub async fn set_tool_call_preview_max_lines(&self, max_lines: usize) -> Result<()> {
+        {
+            let mut current_config = self.config.write().await;
+            current_config.tool_call_preview_max_lines = max_lines;
+        }
+
+        self.save_to_file(ConfigSection::General).await?;
+
+        log::info!(
+            "Successfully updated tool_call_preview_max_lines to: {}",
+            max_lines
+        );
+        Ok(())
+    }
+
     /// Update system prompts configuration
     pub async fn update_system_prompts(
         &self,
-        system_prompts: std::collections::HashMap<String, String>,
+        system_prompts: HashMap<String, String>,
     ) -> Result<()> {
         // Update config
         {
@@ -692,7 +877,7 @@ impl AgentConfigService {
         }
 
         // Save to file
-        self.save_to_file().await?;
+        self.save_to_file(ConfigSection::Prompts).await?;
 
         // Publish config reload event
         let config = self.config.read().await;
@@ -707,8 +892,33 @@ impl AgentConfigService {
 
     // ========== Persistence ==========
 
-    /// Save configuration to file
-    async fn save_to_file(&self) -> Result<()> {
+    /// Save configuration to file, retrying once if the write fails.
+    ///
+    /// If the retry fails too, [`AgentConfigEvent::SaveFailed`] is published
+    /// so the user learns about it instead of the edit being lost silently.
+    async fn save_to_file(&self, section: ConfigSection) -> Result<()> {
+        let Err(e) = self.write_config_file().await else {
+            return Ok(());
+        };
+        log::warn!("Failed to save configuration, retrying: {:#}", e);
+
+        smol::Timer::after(SAVE_RETRY_DELAY).await;
+        self.write_config_file().await.inspect_err(|e| {
+            log::error!("Failed to save configuration: {:#}", e);
+            self.publish_save_failed(section, e);
+        })
+    }
+
+    fn publish_save_failed(&self, section: ConfigSection, error: &anyhow::Error) {
+        self.event_hub
+            .publish_agent_config_update(AgentConfigEvent::SaveFailed {
+                section,
+                error: format!("{:#}", error),
+            });
+    }
+
+    /// Write the current configuration to the config file
+    async fn write_config_file(&self) -> Result<()> {
         let config = self.config.read().await;
 
         // Create backup before saving
@@ -723,6 +933,9 @@ impl AgentConfigService {
         let json =
             serde_json::to_string_pretty(&*config).context("Failed to serialize configuration")?;
 
+        // Remember what we wrote so the watcher skips the resulting events
+        *self.last_written_hash.lock().unwrap() = Some(content_hash(&json));
+
         // Write to file (atomic write using temp file)
         let temp_path = self.config_path.with_extension("json.tmp");
         std::fs::write(&temp_path, json).context("Failed to write configuration to temp file")?;
@@ -734,15 +947,88 @@ impl AgentConfigService {
         Ok(())
     }
 
+    /// Bring the running agents in line with a reloaded configuration,
+    /// returning an event for each agent that changed. Failures are logged so
+    /// one broken entry does not block the others.
+    async fn apply_agent_changes(
+        &self,
+        changes: &AgentChanges,
+        agents: &HashMap<String, AgentProcessConfig>,
+    ) -> Vec<AgentConfigEvent> {
+        let mut events = Vec::new();
+        log::info!(
+            "Hot-reloading agents: added {:?}, removed {:?}, restarted {:?}",
+            changes.added,
+            changes.removed,
+            changes.restarted
+        );
+
+        for name in &changes.removed {
+            match self.agent_manager.remove_agent_if_present(name).await {
+                Ok(_) => events.push(AgentConfigEvent::AgentRemoved { name: name.clone() }),
+                Err(e) => log::error!("Failed to stop agent '{}': {}", name, e),
+            }
+        }
+
+        for name in &changes.added {
+            let config = agents[name].clone();
+            match self
+                .agent_manager
+                .add_agent(name.clone(), self.runtime_config(config.clone()))
+                .await
+            {
+                Ok(()) => events.push(AgentConfigEvent::AgentAdded {
+                    name: name.clone(),
+                    config,
+                }),
+                Err(e) => log::error!("Failed to start agent '{}': {}", name, e),
+            }
+        }
+
+        for name in &changes.restarted {
+            let config = agents[name].clone();
+            match self
+                .agent_manager
+                .restart_agent(name, self.runtime_config(config.clone()))
+                .await
+            {
+                Ok(()) => events.push(AgentConfigEvent::AgentUpdated {
+                    name: name.clone(),
+                    config,
+                }),
+                Err(e) => log::error!("Failed to restart agent '{}': {}", name, e),
+            }
+        }
+        events
+    }
+
+    /// Whether `contents` is exactly what this service last saved
+    pub fn is_own_write(&self, contents: &str) -> bool {
+        *self.last_written_hash.lock().unwrap() == Some(content_hash(contents))
+    }
+
     /// Reload configuration from file
     pub async fn reload_from_file(&self) -> Result<()> {
         // Read file
         let json = std::fs::read_to_string(&self.config_path)
             .with_context(|| format!("Failed to read config file: {:?}", self.config_path))?;
 
-        // Parse config
-        let new_config: Config =
-            serde_json::from_str(&json).context("Failed to parse configuration file")?;
+        // Parse config, upgrading documents written by older versions
+        let new_config = agentx_types::config_manager::parse_config(&json)
+            .context("Failed to parse configuration file")?;
+
+        // Start, stop or restart only the agents whose entries changed
+        let running = self.agent_manager.list_agents().await;
+        let old_agents = self.config.read().await.agent_servers.clone();
+        let changes = diff_agents(&running, &old_agents, &new_config.agent_servers);
+        if !changes.is_empty() {
+            for event in self
+                .apply_agent_changes(&changes, &new_config.agent_servers)
+                .await
+            {
+                self.event_hub.publish_agent_config_update(event);
+            }
+        }
 
         // Update internal config
         {
@@ -796,6 +1082,7 @@ mod tests {
         let _service = create_test_service();
 
         let _config = AgentProcessConfig {
+            enabled: true,
             command: if cfg!(target_os = "windows") {
                 "cmd".to_string()
             } else {
@@ -811,9 +1098,201 @@ mod tests {
         // Note: This test requires mocking AgentManager for full coverage
     }
 
+    fn agent(command: &str, args: &[&str]) -> AgentProcessConfig {
+        AgentProcessConfig {
+            enabled: true,
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            env: HashMap::new(),
+            nodejs_path: None,
+        }
+    }
+
+    #[test]
+    fn diff_agents_only_touches_changed_entries() {
+        let old = HashMap::from([
+            ("claude".to_string(), agent("claude-code-acp", &[])),
+            ("codex".to_string(), agent("codex", &["--acp"])),
+            (
+                "gemini".to_string(),
+                agent("gemini", &["--experimental-acp"]),
+            ),
+        ]);
+        let mut new = old.clone();
+        new.remove("gemini");
+        new.insert("codex".to_string(), agent("codex", &["--acp", "--verbose"]));
+        new.insert("qwen".to_string(), agent("qwen", &[]));
+
+        let running = vec![
+            "claude".to_string(),
+            "codex".to_string(),
+            "gemini".to_string(),
+        ];
+        assert_eq!(
+            diff_agents(&running, &old, &new),
+            AgentChanges {
+                added: vec!["qwen".to_string()],
+                removed: vec!["gemini".to_string()],
+                restarted: vec!["codex".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn diff_agents_ignores_runtime_only_fields_and_starts_stopped_agents() {
+        let old = HashMap::from([("claude".to_string(), agent("claude-code-acp", &[]))]);
+        let mut new = old.clone();
+        new.get_mut("claude").unwrap().nodejs_path = Some("/opt/node".to_string());
+
+        let running = vec!["claude".to_string()];
+        assert!(diff_agents(&running, &old, &new).is_empty());
+
+        // An agent that failed to start is retried on the next reload
+        assert_eq!(
+            diff_agents(&[], &old, &new).added,
+            vec!["claude".to_string()]
+        );
+    }
+
+    fn test_model() -> agentx_types::config::ModelConfig {
+        agentx_types::config::ModelConfig {
+            enabled: true,
+            provider: "openai".to_string(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: String::new(),
+            model_name: "gpt-4o".to_string(),
+            max_concurrent_requests: None,
+            requests_per_minute: None,
+        }
+    }
+
+    fn record_events(service: &AgentConfigService) -> Arc<Mutex<Vec<AgentConfigEvent>>> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        service
+            .event_hub
+            .subscribe_agent_config_updates(move |event| {
+                recorded.lock().unwrap().push(event.clone());
+            });
+        events
+    }
+
+    #[tokio::test]
+    async fn save_recovered_by_the_retry_is_not_reported() {
+        let dir = std::env::temp_dir().join(format!("agentx-save-retry-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let service = create_test_service_at(dir.join("config.json"));
+        let events = record_events(&service);
+
+        // The first write fails because the directory is missing; it appears
+        // before the retry, so the retry succeeds
+        let missing_dir = dir.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(SAVE_RETRY_DELAY / 5);
+            std::fs::create_dir_all(&missing_dir).unwrap();
+        });
+
+        service
+            .add_model("gpt".to_string(), test_model())
+            .await
+            .unwrap();
+        let saved = dir.join("config.json").exists();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(saved);
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], AgentConfigEvent::ModelAdded { name, .. } if name == "gpt"));
+    }
+
+    #[tokio::test]
+    async fn save_failing_after_the_retry_is_reported_once() {
+        let dir = std::env::temp_dir().join(format!("agentx-save-missing-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let service = create_test_service_at(dir.join("config.json"));
+        let events = record_events(&service);
+
+        assert!(
+            service
+                .add_model("gpt".to_string(), test_model())
+                .await
+                .is_err()
+        );
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            AgentConfigEvent::SaveFailed {
+                section: ConfigSection::Models,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn set_agents_enabled_updates_every_flag_with_one_write() {
+        let dir = std::env::temp_dir().join(format!("agentx-bulk-enable-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.json");
+        let service = create_test_service_at(config_path.clone());
+        let names: Vec<String> = ["claude", "codex", "gemini"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        for name in &names {
+            service
+                .config
+                .write()
+                .await
+                .agent_servers
+                .insert(name.clone(), agent(name, &[]));
+        }
+        service.save_to_file(ConfigSection::Agents).await.unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        service
+            .event_hub
+            .subscribe_agent_config_updates(move |event| {
+                recorded.lock().unwrap().push(event.clone());
+            });
+        service.set_agents_enabled(&names, false).await.unwrap();
+
+        let read_agents = |path: &Path| {
+            let json = std::fs::read_to_string(path).unwrap();
+            serde_json::from_str::<Config>(&json).unwrap().agent_servers
+        };
+        let saved = read_agents(&config_path);
+        // The backup is taken before each write, so it only still holds the
+        // original file if nothing was written in between
+        let backup = read_agents(&config_path.with_extension("json.backup"));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(saved.values().all(|agent| !agent.enabled));
+        assert!(backup.values().all(|agent| agent.enabled));
+        assert!(
+            service
+                .config
+                .read()
+                .await
+                .agent_servers
+                .values()
+                .all(|agent| !agent.enabled)
+        );
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], AgentConfigEvent::ConfigReloaded { .. }));
+    }
+
     fn create_test_service() -> AgentConfigService {
+        create_test_service_at(std::env::temp_dir().join("test-config.json"))
+    }
+
+    fn create_test_service_at(config_path: PathBuf) -> AgentConfigService {
         // Create test dependencies
         let config = Config {
+            schema_version: agentx_types::CURRENT_CONFIG_SCHEMA_VERSION,
             agent_servers: HashMap::new(),
             upload_dir: PathBuf::from("."),
             models: HashMap::new(),
@@ -822,10 +1301,11 @@ mod tests {
             system_prompts: HashMap::new(),
             tool_call_preview_max_lines: 10,
             proxy: ProxyConfig::default(),
+            keybindings: HashMap::new(),
+            agent_icons: HashMap::new(),
         };
 
         let event_hub = EventHub::new();
-        let config_path = std::env::temp_dir().join("test-config.json");
 
         // Mock agent manager for testing
         let agent_manager = Arc::new(agentx_agent::AgentManager::new(
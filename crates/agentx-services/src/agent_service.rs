@@ -14,10 +14,15 @@ use agent_client_protocol::{self as acp, AvailableCommand, PromptResponse};
 use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument as _;
 
 use agentx_agent::{AgentHandle, AgentManager};
 use agentx_event_bus::{EventHub, WorkspaceUpdateEvent};
-use agentx_types::SessionStatus;
+use agentx_types::{AgentRuntimeInfo, SessionStatus};
+
+/// Session kept for a task, filled in by the first request for it
+type TaskSessionSlot = Arc<tokio::sync::Mutex<Option<String>>>;
 
 /// Agent service - manages agents and their sessions
 pub struct AgentService {
@@ -26,6 +31,12 @@ pub struct AgentService {
     sessions: Arc<RwLock<HashMap<String, HashMap<String, AgentSessionInfo>>>>,
     /// Tracks sessions currently loading history via session/load
     loading_sessions: Arc<RwLock<HashSet<String>>>,
+    /// Cancellation tokens of each session's in-flight turns, by turn id, so a
+    /// finishing turn never drops the token of a newer one in the same session
+    turns: Arc<RwLock<HashMap<String, HashMap<String, CancellationToken>>>>,
+    /// Session created for each task; the async lock makes concurrent
+    /// requests for the same task wait for the first one
+    task_sessions: Arc<RwLock<HashMap<String, TaskSessionSlot>>>,
     /// Event hub for publishing status updates
     event_hub: Option<EventHub>,
 }
@@ -42,6 +53,8 @@ pub struct AgentSessionInfo {
     pub new_session_response: Option<acp::NewSessionResponse>,
     /// Available commands for this session (slash commands, etc.)
     pub available_commands: Vec<AvailableCommand>,
+    /// Model chosen for this session, reapplied when the session is resumed or loaded
+    pub selected_model: Option<String>,
 }
 
 impl AgentService {
@@ -50,6 +63,8 @@ impl AgentService {
             agent_manager,
             sessions: Arc::new(RwLock::new(HashMap::new())),
             loading_sessions: Arc::new(RwLock::new(HashSet::new())),
+            turns: Arc::new(RwLock::new(HashMap::new())),
+            task_sessions: Arc::new(RwLock::new(HashMap::new())),
             event_hub: None,
         }
     }
@@ -67,6 +82,11 @@ impl AgentService {
         self.agent_manager.list_agents().await
     }
 
+    /// List agents with their runtime status, pid and start time
+    pub async fn list_agents_detailed(&self) -> Vec<AgentRuntimeInfo> {
+        self.agent_manager.list_agents_detailed().await
+    }
+
     /// Get the initialize response for a specific agent
     pub async fn get_agent_init_response(
         &self,
@@ -175,6 +195,7 @@ impl AgentService {
                     status: SessionStatus::Active,
                     new_session_response: Some(new_session_response),
                     available_commands: Vec::new(), // Will be populated by AvailableCommandsUpdate
+                    selected_model: None,
                 });
                 log::info!("Created session {} for agent {}", session_id, agent_name);
             }
@@ -182,6 +203,60 @@ impl AgentService {
         Ok(session_id)
     }
 
+    /// Create a session for `task_id`, or return the one already created for it
+    ///
+    /// Opening the same task twice (a double-click, say) must not spawn a second
+    /// agent session: concurrent calls wait for the first and get its session.
+    /// A closed session is replaced by a new one.
+    pub async fn create_session_for_task(
+        &self,
+        task_id: &str,
+        agent_name: &str,
+        mcp_servers: Vec<acp::McpServer>,
+        cwd: std::path::PathBuf,
+    ) -> Result<AgentSessionInfo> {
+        let slot = self
+            .task_sessions
+            .write()
+            .unwrap()
+            .entry(task_id.to_string())
+            .or_default()
+            .clone();
+        let mut task_session = slot.lock().await;
+
+        let existing = task_session
+            .as_deref()
+            .and_then(|session_id| self.get_session_by_id(session_id))
+            .filter(|info| info.status != SessionStatus::Closed);
+        if let Some(info) = existing {
+            log::info!("Reusing session {} for task {}", info.session_id, task_id);
+            return Ok(info);
+        }
+
+        let session_id = self
+            .create_session_with_mcp_and_cwd(agent_name, mcp_servers, cwd)
+            .await?;
+        *task_session = Some(session_id.clone());
+        self.get_session_info(agent_name, &session_id)
+            .ok_or_else(|| anyhow!("Session {} not found after creation", session_id))
+    }
+
+    /// Record `session_id`, created before its task, as the session of
+    /// `task_id`. It stops being the session of any other task.
+    pub fn assign_task_session(&self, task_id: &str, session_id: &str) {
+        let mut task_sessions = self.task_sessions.write().unwrap();
+        forget_session_tasks(&mut task_sessions, session_id);
+        task_sessions.insert(
+            task_id.to_string(),
+            Arc::new(tokio::sync::Mutex::new(Some(session_id.to_string()))),
+        );
+    }
+
+    /// Stop keeping a session for `task_id`, e.g. once the task is removed
+    pub fn forget_task(&self, task_id: &str) {
+        self.task_sessions.write().unwrap().remove(task_id);
+    }
+
     /// Resume an existing session with specified session_id
     pub async fn resume_session(&self, agent_name: &str, session_id: &str) -> Result<String> {
         self.resume_session_with_mcp(agent_name, session_id, Vec::new())
@@ -234,40 +309,10 @@ impl AgentService {
             .modes(resume_session_response.modes)
             .meta(resume_session_response.meta);
 
-        let now = Utc::now();
+        self.store_reloaded_session(agent_name, session_id, new_session_response, "Resumed");
 
-        // Insert into nested HashMap structure
-        let mut sessions = self.sessions.write().unwrap();
-        let agent_sessions = sessions
-            .entry(agent_name.to_string())
-            .or_insert_with(HashMap::new);
-
-        match agent_sessions.entry(session_id.to_string()) {
-            Entry::Occupied(mut entry) => {
-                let info = entry.get_mut();
-                info.agent_name = agent_name.to_string();
-                info.last_active = now;
-                info.status = SessionStatus::Active;
-                info.new_session_response = Some(new_session_response);
-                log::info!("Resumed session {} for agent {}", session_id, agent_name);
-            }
-            Entry::Vacant(entry) => {
-                entry.insert(AgentSessionInfo {
-                    session_id: session_id.to_string(),
-                    agent_name: agent_name.to_string(),
-                    created_at: now,
-                    last_active: now,
-                    status: SessionStatus::Active,
-                    new_session_response: Some(new_session_response),
-                    available_commands: Vec::new(),
-                });
-                log::info!(
-                    "Resumed session {} for agent {} (created new entry)",
-                    session_id,
-                    agent_name
-                );
-            }
-        }
+        self.reapply_session_model(&agent_handle, agent_name, session_id)
+            .await;
         Ok(session_id.to_string())
     }
 
@@ -335,6 +380,21 @@ impl AgentService {
             .modes(load_session_response.modes)
             .meta(load_session_response.meta);
 
+        self.store_reloaded_session(agent_name, session_id, new_session_response, "Loaded");
+
+        self.reapply_session_model(&agent_handle, agent_name, session_id)
+            .await;
+        Ok(session_id.to_string())
+    }
+
+    /// Store the metadata of a resumed or loaded session, keeping the model chosen for it
+    fn store_reloaded_session(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        new_session_response: acp::NewSessionResponse,
+        action: &str,
+    ) {
         let now = Utc::now();
 
         // Insert into nested HashMap structure
@@ -350,7 +410,7 @@ impl AgentService {
                 info.last_active = now;
                 info.status = SessionStatus::Active;
                 info.new_session_response = Some(new_session_response);
-                log::info!("Loaded session {} for agent {}", session_id, agent_name);
+                log::info!("{} session {} for agent {}", action, session_id, agent_name);
             }
             Entry::Vacant(entry) => {
                 entry.insert(AgentSessionInfo {
@@ -361,15 +421,16 @@ impl AgentService {
                     status: SessionStatus::Active,
                     new_session_response: Some(new_session_response),
                     available_commands: Vec::new(),
+                    selected_model: None,
                 });
                 log::info!(
-                    "Loaded session {} for agent {} (created new entry)",
+                    "{} session {} for agent {} (created new entry)",
+                    action,
                     session_id,
                     agent_name
                 );
             }
         }
-        Ok(session_id.to_string())
     }
 
     /// Get session information
@@ -384,6 +445,11 @@ impl AgentService {
 
     /// Close an agent's session
     pub async fn close_session(&self, agent_name: &str, session_id: &str) -> Result<()> {
+        forget_session_tasks(&mut self.task_sessions.write().unwrap(), session_id);
+        self.agent_manager
+            .permission_store()
+            .forget_session(session_id);
+
         let mut sessions = self.sessions.write().unwrap();
         if let Some(agent_sessions) = sessions.get_mut(agent_name) {
             if let Some(info) = agent_sessions.get_mut(session_id) {
@@ -395,6 +461,9 @@ impl AgentService {
     }
 
     /// Cancel an ongoing session operation
+    ///
+    /// The in-flight turn resolves with [`acp::StopReason::Cancelled`] right
+    /// away, without waiting for the agent to acknowledge `session/cancel`.
     pub async fn cancel_session(&self, agent_name: &str, session_id: &str) -> Result<()> {
         log::info!(
             "AgentService: cancel_session called for agent={}, session={}",
@@ -402,6 +471,10 @@ impl AgentService {
             session_id
         );
 
+        if let Some(turns) = self.turns.read().unwrap().get(session_id) {
+            turns.values().for_each(CancellationToken::cancel);
+        }
+
         // Get the agent handle
         let agent_handle = self.get_agent_handle(agent_name).await?;
         log::info!("AgentService: Got agent handle for {}", agent_name);
@@ -448,7 +521,7 @@ impl AgentService {
     }
 
     /// Cancel a session by ID without requiring the caller to know the agent name
-    pub async fn cancel_session_by_id(&self, session_id: &str) -> Result<()> {
+    pub async fn cancel(&self, session_id: &str) -> Result<()> {
         let agent_name = self
             .get_agent_for_session(session_id)
             .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
@@ -540,6 +613,7 @@ impl AgentService {
                     status: SessionStatus::Active,
                     new_session_response: None,
                     available_commands: commands,
+                    selected_model: None,
                 });
             }
         }
@@ -585,23 +659,202 @@ impl AgentService {
         }
     }
 
+    // ========== Model Operations ==========
+
+    /// Switch the model used by a session and remember the choice
+    pub async fn set_session_model(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        model_id: &str,
+    ) -> Result<()> {
+        let agent_handle = self.get_agent_handle(agent_name).await?;
+
+        let request = acp::SetSessionModelRequest::new(
+            acp::SessionId::from(session_id.to_string()),
+            model_id.to_string(),
+        );
+
+        agent_handle
+            .set_session_model(request)
+            .await
+            .map_err(|e| anyhow!("Failed to set session model: {}", e))?;
+
+        self.record_session_model(agent_name, session_id, model_id);
+        Ok(())
+    }
+
+    /// Remember the model chosen for a session without contacting the agent
+    pub fn record_session_model(&self, agent_name: &str, session_id: &str, model_id: &str) {
+        let mut sessions = self.sessions.write().unwrap();
+        let Some(info) = sessions
+            .get_mut(agent_name)
+            .and_then(|agent_sessions| agent_sessions.get_mut(session_id))
+        else {
+            log::warn!(
+                "Cannot record model for unknown session {}:{}",
+                agent_name,
+                session_id
+            );
+            return;
+        };
+
+        info.selected_model = Some(model_id.to_string());
+        if let Some(models) = info
+            .new_session_response
+            .as_mut()
+            .and_then(|response| response.models.as_mut())
+        {
+            models.current_model_id = model_id.to_string().into();
+        }
+    }
+
+    /// Model chosen for a session, if one was selected
+    pub fn session_model(&self, agent_name: &str, session_id: &str) -> Option<String> {
+        self.get_session_info(agent_name, session_id)?
+            .selected_model
+    }
+
+    /// Apply a model saved with the session's task, e.g. after a restart, to a
+    /// resumed or loaded session that has no model remembered yet
+    pub async fn restore_session_model(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        saved_model: Option<String>,
+    ) {
+        let Some(model_id) = saved_model else {
+            return;
+        };
+        if self.session_model(agent_name, session_id).is_some() {
+            return;
+        }
+        if let Err(e) = self
+            .set_session_model(agent_name, session_id, &model_id)
+            .await
+        {
+            log::warn!(
+                "Failed to restore model '{}' for session {}: {}",
+                model_id,
+                session_id,
+                e
+            );
+        }
+    }
+
+    /// Send the remembered model to the agent after a session was resumed or loaded
+    async fn reapply_session_model(
+        &self,
+        agent_handle: &AgentHandle,
+        agent_name: &str,
+        session_id: &str,
+    ) {
+        let Some(model_id) = self.session_model(agent_name, session_id) else {
+            return;
+        };
+
+        let request = acp::SetSessionModelRequest::new(
+            acp::SessionId::from(session_id.to_string()),
+            model_id.clone(),
+        );
+        match agent_handle.set_session_model(request).await {
+            Ok(_) => self.record_session_model(agent_name, session_id, &model_id),
+            Err(e) => log::warn!(
+                "Failed to reapply model '{}' to session {}: {}",
+                model_id,
+                session_id,
+                e
+            ),
+        }
+    }
+
     // ========== Prompt Operations ==========
 
     /// Send a prompt to an agent's session
+    ///
+    /// The turn runs in an `agent_turn` span tagged with the agent, session
+    /// and a fresh turn id, so its logs can be filtered per session.
     pub async fn send_prompt(
         &self,
         agent_name: &str,
         session_id: &str,
         prompt: Vec<acp::ContentBlock>,
+    ) -> Result<PromptResponse> {
+        let turn_id = uuid::Uuid::new_v4().to_string();
+        let span = tracing::info_span!(
+            "agent_turn",
+            agent = agent_name,
+            session_id,
+            turn_id = turn_id.as_str(),
+            stop_reason = tracing::field::Empty,
+        );
+
+        let token = CancellationToken::new();
+        self.turns
+            .write()
+            .unwrap()
+            .entry(session_id.to_string())
+            .or_default()
+            .insert(turn_id.clone(), token.clone());
+
+        let result = self
+            .run_turn(agent_name, session_id, prompt, &token)
+            .instrument(span.clone())
+            .await;
+        if let Ok(response) = &result {
+            span.record("stop_reason", tracing::field::debug(&response.stop_reason));
+        }
+
+        self.finish_turn(session_id, &turn_id);
+        result
+    }
+
+    /// Forget the token of a turn that has ended
+    fn finish_turn(&self, session_id: &str, turn_id: &str) {
+        let mut turns = self.turns.write().unwrap();
+        if let Some(session_turns) = turns.get_mut(session_id) {
+            session_turns.remove(turn_id);
+            if session_turns.is_empty() {
+                turns.remove(session_id);
+            }
+        }
+    }
+
+    async fn run_turn(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        prompt: Vec<acp::ContentBlock>,
+        token: &CancellationToken,
     ) -> Result<PromptResponse> {
         let agent_handle = self.get_agent_handle(agent_name).await?;
+        if token.is_cancelled() {
+            return Ok(PromptResponse::new(acp::StopReason::Cancelled));
+        }
         self.update_session_status(agent_name, session_id, SessionStatus::InProgress);
         let request = acp::PromptRequest::new(acp::SessionId::from(session_id.to_string()), prompt);
 
-        let result = agent_handle
-            .prompt(request)
-            .await
-            .map_err(|e| anyhow!("Failed to send prompt: {}", e))?;
+        let prompt = async {
+            agent_handle
+                .prompt(request)
+                .await
+                .map_err(|e| anyhow!("Failed to send prompt: {}", e))
+        };
+        let cancelled = async {
+            token.cancelled().await;
+            Ok(PromptResponse::new(acp::StopReason::Cancelled))
+        };
+        let result = smol::future::or(prompt, cancelled).await?;
+
+        if matches!(result.stop_reason, acp::StopReason::Cancelled) {
+            // cancel_session already moved the session to Idle
+            log::debug!(
+                "Turn cancelled for agent {} session {}",
+                agent_name,
+                session_id
+            );
+            return Ok(result);
+        }
 
         self.update_session_status(agent_name, session_id, SessionStatus::Completed);
         // Update activity time
@@ -666,3 +919,306 @@ impl AgentService {
             .map(|info| info.agent_name)
     }
 }
+
+/// Drop the tasks `session_id` is kept for. A slot still being filled holds
+/// no session yet, so it is left alone.
+fn forget_session_tasks(task_sessions: &mut HashMap<String, TaskSessionSlot>, session_id: &str) {
+    task_sessions.retain(|_, slot| {
+        !slot
+            .try_lock()
+            .is_ok_and(|task_session| task_session.as_deref() == Some(session_id))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_service() -> AgentService {
+        let agent_manager = Arc::new(AgentManager::new(
+            HashMap::new(),
+            Arc::new(Default::default()),
+            EventHub::new(),
+            Default::default(),
+        ));
+        AgentService::new(agent_manager)
+    }
+
+    #[test]
+    fn cancelling_resolves_the_running_turn() {
+        let hub = EventHub::new();
+        let agent_manager = Arc::new(AgentManager::new(
+            HashMap::new(),
+            Arc::new(Default::default()),
+            hub.clone(),
+            Default::default(),
+        ));
+        let mut service = AgentService::new(agent_manager.clone());
+        service.set_event_hub(hub.clone());
+        let service = Arc::new(service);
+
+        smol::block_on(async {
+            // An empty reply keeps the turn running until it is cancelled
+            agent_manager
+                .insert_handle(AgentHandle::scripted("agent", hub, Vec::new()))
+                .await;
+            let session_id = service.create_session("agent").await.unwrap();
+
+            let turn = smol::spawn({
+                let service = service.clone();
+                let session_id = session_id.clone();
+                async move {
+                    service
+                        .send_prompt("agent", &session_id, vec!["hi".to_string().into()])
+                        .await
+                }
+            });
+            let token = loop {
+                let token = {
+                    let turns = service.turns.read().unwrap();
+                    turns
+                        .get(&session_id)
+                        .and_then(|turns| turns.values().next().cloned())
+                };
+                if let Some(token) = token {
+                    break token;
+                }
+                smol::Timer::after(Duration::from_millis(10)).await;
+            };
+
+            service.cancel(&session_id).await.unwrap();
+            assert!(token.is_cancelled());
+
+            let response = turn.await.unwrap();
+            assert!(matches!(response.stop_reason, acp::StopReason::Cancelled));
+            assert!(service.turns.read().unwrap().is_empty());
+            assert_eq!(
+                service.get_session_by_id(&session_id).unwrap().status,
+                SessionStatus::Idle
+            );
+        });
+    }
+
+    #[test]
+    fn finishing_a_turn_keeps_newer_turns_in_the_session() {
+        let agent_manager = Arc::new(AgentManager::new(
+            HashMap::new(),
+            Arc::new(Default::default()),
+            EventHub::new(),
+            Default::default(),
+        ));
+        let service = AgentService::new(agent_manager);
+        service.turns.write().unwrap().insert(
+            "session-1".to_string(),
+            HashMap::from([
+                ("turn-1".to_string(), CancellationToken::new()),
+                ("turn-2".to_string(), CancellationToken::new()),
+            ]),
+        );
+
+        service.finish_turn("session-1", "turn-1");
+        let turns = service.turns.read().unwrap();
+        assert_eq!(turns["session-1"].len(), 1);
+        assert!(turns["session-1"].contains_key("turn-2"));
+        drop(turns);
+
+        service.finish_turn("session-1", "turn-2");
+        assert!(service.turns.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn creating_a_session_twice_for_a_task_spawns_it_once() {
+        let hub = EventHub::new();
+        let agent_manager = Arc::new(AgentManager::new(
+            HashMap::new(),
+            Arc::new(Default::default()),
+            hub.clone(),
+            Default::default(),
+        ));
+        let service = AgentService::new(agent_manager.clone());
+
+        smol::block_on(async {
+            agent_manager
+                .insert_handle(AgentHandle::scripted("agent", hub, Vec::new()))
+                .await;
+            let create = |task_id: &'static str| {
+                service.create_session_for_task(task_id, "agent", Vec::new(), "/tmp".into())
+            };
+
+            // Both requests are in flight at once, like a double-click
+            let (first, second) = smol::future::zip(create("task-1"), create("task-1")).await;
+            let (first, second) = (first.unwrap(), second.unwrap());
+            assert_eq!(first.session_id, "agent-session-1");
+            assert_eq!(second.session_id, first.session_id);
+
+            // The scripted agent numbers its sessions, so a second spawn would
+            // have been `agent-session-2`
+            let other = create("task-2").await.unwrap();
+            assert_eq!(other.session_id, "agent-session-2");
+
+            service
+                .close_session("agent", &first.session_id)
+                .await
+                .unwrap();
+            let reopened = create("task-1").await.unwrap();
+            assert_eq!(reopened.session_id, "agent-session-3");
+
+            // Closing a session or removing its task forgets it
+            assert_eq!(service.task_sessions.read().unwrap().len(), 2);
+            service
+                .close_session("agent", &reopened.session_id)
+                .await
+                .unwrap();
+            service.forget_task("task-2");
+            assert!(service.task_sessions.read().unwrap().is_empty());
+
+            // A session made before its task is kept for the task from then on
+            service.assign_task_session("task-3", &other.session_id);
+            assert_eq!(create("task-3").await.unwrap().session_id, other.session_id);
+        });
+    }
+
+    #[test]
+    fn closing_a_session_forgets_its_permission_choices() {
+        let service = create_test_service();
+        let store = service.agent_manager.permission_store().clone();
+        store.remember_choice(
+            "session-1",
+            acp::ToolKind::Execute,
+            acp::PermissionOptionKind::AllowOnce,
+        );
+
+        smol::block_on(service.close_session("agent", "session-1")).unwrap();
+        assert_eq!(
+            store.remembered_choice("session-1", acp::ToolKind::Execute),
+            None
+        );
+    }
+
+    #[test]
+    fn selected_model_survives_session_reload() {
+        let service = create_test_service();
+        let response = || acp::NewSessionResponse::new("session-1".to_string());
+
+        service.store_reloaded_session("agent", "session-1", response(), "Loaded");
+        assert_eq!(service.session_model("agent", "session-1"), None);
+
+        service.record_session_model("agent", "session-1", "fast-model");
+        assert_eq!(
+            service
+                .get_session_info("agent", "session-1")
+                .and_then(|info| info.selected_model),
+            Some("fast-model".to_string())
+        );
+
+        // Reloading refreshes the metadata but keeps the choice to reapply
+        service.store_reloaded_session("agent", "session-1", response(), "Resumed");
+        assert_eq!(
+            service.session_model("agent", "session-1"),
+            Some("fast-model".to_string())
+        );
+    }
+
+    /// A span seen by [`SpanRecorder`], with its fields as strings
+    struct RecordedSpan {
+        id: u64,
+        name: &'static str,
+        fields: HashMap<&'static str, String>,
+    }
+
+    /// Layer keeping every span created, including fields recorded later
+    #[derive(Clone, Default)]
+    struct SpanRecorder(Arc<std::sync::Mutex<Vec<RecordedSpan>>>);
+
+    struct FieldVisitor<'a>(&'a mut HashMap<&'static str, String>);
+
+    impl tracing::field::Visit for FieldVisitor<'_> {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name(), format!("{:?}", value));
+        }
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanRecorder {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = HashMap::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            self.0.lock().unwrap().push(RecordedSpan {
+                id: id.into_u64(),
+                name: attrs.metadata().name(),
+                fields,
+            });
+        }
+
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut spans = self.0.lock().unwrap();
+            if let Some(span) = spans.iter_mut().find(|span| span.id == id.into_u64()) {
+                values.record(&mut FieldVisitor(&mut span.fields));
+            }
+        }
+    }
+
+    #[test]
+    fn turn_runs_in_span_tagged_with_session() {
+        use tracing_subscriber::layer::SubscriberExt as _;
+
+        let hub = EventHub::new();
+        let agent_manager = Arc::new(AgentManager::new(
+            HashMap::new(),
+            Arc::new(Default::default()),
+            hub.clone(),
+            Default::default(),
+        ));
+        let service = AgentService::new(agent_manager.clone());
+        let recorder = SpanRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+
+        let session_id = tracing::subscriber::with_default(subscriber, || {
+            smol::block_on(async {
+                agent_manager
+                    .insert_handle(AgentHandle::scripted(
+                        "agent",
+                        hub,
+                        vec!["done".to_string()],
+                    ))
+                    .await;
+                let session_id = service.create_session("agent").await.unwrap();
+                service
+                    .send_prompt("agent", &session_id, vec!["hi".to_string().into()])
+                    .await
+                    .unwrap();
+                session_id
+            })
+        });
+
+        let spans = recorder.0.lock().unwrap();
+        let turn = spans
+            .iter()
+            .find(|span| span.name == "agent_turn")
+            .expect("turn span");
+        assert_eq!(turn.fields["agent"], "agent");
+        assert_eq!(turn.fields["session_id"], session_id);
+        assert!(!turn.fields["turn_id"].is_empty());
+        assert_eq!(turn.fields["stop_reason"], "EndTurn");
+    }
+
+    #[test]
+    fn recording_model_for_unknown_session_is_ignored() {
+        let service = create_test_service();
+        service.record_session_model("agent", "missing", "fast-model");
+        assert_eq!(service.session_model("agent", "missing"), None);
+    }
+}
@@ -4,15 +4,22 @@
 //! - Code documentation generation
 //! - Code explanation
 //! - Optimization suggestions
+//! - Structured edits that can be previewed as a diff before applying
+//! - Session titles
 
 use std::collections::HashMap;
-use std::sync::{Arc, OnceLock, RwLock};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
-use anyhow::{Context, Result, anyhow};
+use agent_client_protocol as acp;
+use anyhow::{Context, Result, anyhow, bail};
 use serde::{Deserialize, Serialize};
 
 use agentx_types::ModelConfig;
 
+use crate::model_limiter::ModelLimiter;
+
 /// Global Tokio runtime for HTTP requests
 static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
 
@@ -24,6 +31,8 @@ pub struct AiService {
     runtime_handle: tokio::runtime::Handle,
     /// Service configuration
     pub config: Arc<RwLock<AiServiceConfig>>,
+    /// Rate and concurrency limiters, keyed by model name
+    limiters: Mutex<HashMap<String, Arc<ModelLimiter>>>,
 }
 
 /// Configuration for AI service
@@ -34,7 +43,7 @@ pub struct AiServiceConfig {
     /// Default model to use (first enabled model)
     pub default_model: Option<String>,
     /// Global system prompts for AI features
-    /// Keys: "doc_comment", "inline_comment", "explain", "improve"
+    /// Keys: "doc_comment", "inline_comment", "explain", "improve", "edit", "title"
     pub system_prompts: HashMap<String, String>,
 }
 
@@ -47,6 +56,93 @@ pub enum CommentStyle {
     Inline,
 }
 
+/// Replacement of the bytes in `range` of a file with `replacement`. An empty
+/// range inserts text, an empty replacement deletes it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+/// Apply `edits` to `text`. Edits may be given in any order, but must not
+/// overlap, reach past the end of `text` or split a character.
+pub fn apply_edits(text: &str, edits: &[Edit]) -> Result<String> {
+    let mut sorted: Vec<&Edit> = edits.iter().collect();
+    sorted.sort_by_key(|edit| (edit.range.start, edit.range.end));
+
+    let mut result = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for edit in sorted {
+        let Range { start, end } = edit.range;
+        if start > end || end > text.len() {
+            bail!(
+                "Edit {:?} is outside the file ({} bytes)",
+                edit.range,
+                text.len()
+            );
+        }
+        if !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+            bail!("Edit {:?} splits a character", edit.range);
+        }
+        if start < cursor {
+            bail!("Edit {:?} overlaps the edit before it", edit.range);
+        }
+        result.push_str(&text[cursor..start]);
+        result.push_str(&edit.replacement);
+        cursor = end;
+    }
+    result.push_str(&text[cursor..]);
+    Ok(result)
+}
+
+/// Diff from `text` to `text` with `edits` applied, to preview in a `DiffView`
+pub fn edits_diff(path: impl Into<PathBuf>, text: &str, edits: &[Edit]) -> Result<acp::Diff> {
+    let new_text = apply_edits(text, edits)?;
+    Ok(acp::Diff::new(path.into(), new_text).old_text(text.to_string()))
+}
+
+/// An edit as returned by the model: a snippet of the file and its replacement
+#[derive(Debug, Deserialize)]
+struct SuggestedEdit {
+    old: String,
+    new: String,
+}
+
+/// Turn the model's JSON reply into edits of `file`. Each `old` snippet must
+/// occur exactly once so its range is unambiguous.
+fn parse_suggested_edits(file: &str, reply: &str) -> Result<Vec<Edit>> {
+    // Models often wrap JSON in a fenced code block despite being told not to
+    let json = reply
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+    let suggested: Vec<SuggestedEdit> =
+        serde_json::from_str(json).context("AI reply is not a JSON list of edits")?;
+
+    let edits = suggested
+        .into_iter()
+        .map(|edit| {
+            let mut matches = file.match_indices(&edit.old);
+            let start = match (matches.next(), matches.next()) {
+                _ if edit.old.is_empty() => bail!("Suggested edit has no text to replace"),
+                (Some((start, _)), None) => start,
+                (None, _) => bail!("Suggested edit not found in file: {:?}", edit.old),
+                (Some(_), Some(_)) => bail!("Suggested edit is ambiguous: {:?}", edit.old),
+            };
+            Ok(Edit {
+                range: start..start + edit.old.len(),
+                replacement: edit.new,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // Rejects overlapping suggestions before anything is previewed
+    apply_edits(file, &edits)?;
+    Ok(edits)
+}
+
 /// Request body for OpenAI Chat Completions API
 #[derive(Debug, Serialize)]
 struct ChatCompletionRequest {
@@ -127,6 +223,7 @@ impl AiService {
             http_client,
             runtime_handle,
             config: Arc::new(RwLock::new(config)),
+            limiters: Mutex::new(HashMap::new()),
         }
     }
 
@@ -162,6 +259,19 @@ impl AiService {
         default_prompt.to_string()
     }
 
+    /// Limiter shared by every request to `model`, rebuilt when its limits change
+    fn limiter(&self, model: &str, config: &ModelConfig) -> Arc<ModelLimiter> {
+        let mut limiters = self.limiters.lock().unwrap();
+        match limiters.get(model) {
+            Some(limiter) if limiter.matches(config) => limiter.clone(),
+            _ => {
+                let limiter = Arc::new(ModelLimiter::new(config));
+                limiters.insert(model.to_string(), limiter.clone());
+                limiter
+            }
+        }
+    }
+
     /// Call OpenAI-compatible API with system and user prompts
     async fn call_api(
         &self,
@@ -170,7 +280,7 @@ impl AiService {
         max_tokens: Option<u32>,
     ) -> Result<String> {
         // Extract config data and release lock immediately
-        let (url, model_name, api_key) = {
+        let (url, model_name, api_key, limiter) = {
             let config = self.config.read().unwrap();
 
             let model_name = config
@@ -196,9 +306,13 @@ impl AiService {
                 url,
                 model_config.model_name.clone(),
                 model_config.api_key.clone(),
+                self.limiter(model_name, model_config),
             )
         }; // Lock is released here
 
+        // Wait for the model's rate and concurrency limits
+        let _permit = limiter.acquire().await;
+
         let request = ChatCompletionRequest {
             model: model_name.clone(),
             messages: vec![
@@ -345,6 +459,63 @@ impl AiService {
             .await
             .context("Failed to generate improvement suggestions")
     }
+
+    /// Suggest edits to a file following an instruction
+    ///
+    /// # Arguments
+    /// * `file` - Content of the file to edit
+    /// * `instruction` - What to change
+    ///
+    /// # Returns
+    /// Non-overlapping edits of `file`, ready to preview with [`edits_diff`]
+    pub async fn suggest_edits(&self, file: &str, instruction: &str) -> Result<Vec<Edit>> {
+        let default_system = "You are a code editing expert. Change the file as instructed, \
+                            touching as little as possible. Respond ONLY with a JSON array of \
+                            objects {\"old\": ..., \"new\": ...} where \"old\" is text copied exactly \
+                            from the file that occurs only once in it, and \"new\" replaces it. \
+                            Edits must not overlap.";
+
+        let system_prompt = self.get_system_prompt("edit", default_system);
+        let user_prompt = format!("Instruction: {}\n\nFile:\n{}", instruction, file);
+
+        let reply = self
+            .call_api(&system_prompt, &user_prompt, Some(2000))
+            .await
+            .context("Failed to suggest edits")?;
+        parse_suggested_edits(file, &reply)
+    }
+
+    /// Summarize a conversation into a short session title
+    ///
+    /// # Arguments
+    /// * `conversation` - The conversation so far, usually the first turn
+    ///
+    /// # Returns
+    /// Title on a single line, without surrounding quotes
+    pub async fn generate_title(&self, conversation: &str) -> Result<String> {
+        let default_system = "You name chat sessions. Summarize the conversation into a title of \
+                            at most six words. Respond ONLY with the title, without quotes or \
+                            trailing punctuation.";
+
+        let system_prompt = self.get_system_prompt("title", default_system);
+        let user_prompt = format!("Conversation:\n\n{}", conversation);
+
+        let reply = self
+            .call_api(&system_prompt, &user_prompt, Some(30))
+            .await
+            .context("Failed to generate title")?;
+        let title = reply
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default()
+            .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+            .trim();
+        if title.is_empty() {
+            bail!("Model returned an empty title");
+        }
+        Ok(title.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -361,6 +532,8 @@ mod tests {
                 base_url: "https://api.openai.com/v1".to_string(),
                 api_key: "test-key".to_string(),
                 model_name: "gpt-3.5-turbo".to_string(),
+                max_concurrent_requests: None,
+                requests_per_minute: None,
             },
         );
         models
@@ -390,6 +563,8 @@ mod tests {
                 base_url: "https://api.openai.com/v1".to_string(),
                 api_key: "new-key".to_string(),
                 model_name: "gpt-4".to_string(),
+                max_concurrent_requests: None,
+                requests_per_minute: None,
             },
         );
 
@@ -399,6 +574,90 @@ mod tests {
         assert_eq!(config.default_model.as_ref().unwrap(), "new-model");
     }
 
+    #[test]
+    fn limiters_are_shared_per_model_until_limits_change() {
+        let service = AiService::new(create_test_config(), HashMap::new());
+        let mut config = create_test_config().remove("test-model").unwrap();
+
+        let first = service.limiter("test-model", &config);
+        assert!(Arc::ptr_eq(&first, &service.limiter("test-model", &config)));
+
+        config.max_concurrent_requests = Some(1);
+        assert!(!Arc::ptr_eq(
+            &first,
+            &service.limiter("test-model", &config)
+        ));
+    }
+
+    fn edit(range: Range<usize>, replacement: &str) -> Edit {
+        Edit {
+            range,
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn edits_apply_to_the_buffer_in_any_order() {
+        let text = "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+        let edits = [
+            // Rename the second parameter, listed before the earlier edits
+            edit(15..16, "rhs"),
+            edit(7..8, "lhs"),
+            edit(36..41, "lhs + rhs"),
+            // Insert a doc comment at the start
+            edit(0..0, "/// Sum of two numbers\n"),
+        ];
+
+        assert_eq!(
+            apply_edits(text, &edits).unwrap(),
+            "/// Sum of two numbers\nfn add(lhs: i32, rhs: i32) -> i32 {\n    lhs + rhs\n}\n"
+        );
+        assert_eq!(apply_edits(text, &[]).unwrap(), text);
+        assert_eq!(
+            apply_edits(text, &[edit(30..42, "")]).unwrap(),
+            "fn add(a: i32, b: i32) -> i32 }\n"
+        );
+
+        let diff = edits_diff("src/math.rs", text, &edits).unwrap();
+        assert_eq!(diff.old_text.as_deref(), Some(text));
+        assert!(diff.new_text.contains("lhs + rhs"));
+    }
+
+    #[test]
+    fn overlapping_edits_are_rejected() {
+        let text = "let value = compute();";
+
+        let error = apply_edits(text, &[edit(4..9, "result"), edit(8..13, " =")]).unwrap_err();
+        assert!(error.to_string().contains("overlaps"));
+        // Order does not matter, and a range inside another overlaps it too
+        assert!(apply_edits(text, &[edit(6..8, "x"), edit(4..12, "y")]).is_err());
+        // Two insertions at the same point, or touching ranges, are fine
+        assert_eq!(
+            apply_edits(text, &[edit(4..9, "total"), edit(9..9, ": u32")]).unwrap(),
+            "let total: u32 = compute();"
+        );
+
+        assert!(apply_edits(text, &[edit(20..40, "")]).is_err());
+        assert!(apply_edits("é", &[edit(1..2, "e")]).is_err());
+    }
+
+    #[test]
+    fn suggested_edits_are_located_in_the_file() {
+        let file = "let a = 1;\nlet b = 2;\n";
+        let reply = "```json\n[{\"old\": \"b = 2\", \"new\": \"b = 3\"}]\n```";
+        assert_eq!(
+            parse_suggested_edits(file, reply).unwrap(),
+            [edit(15..20, "b = 3")]
+        );
+
+        let ambiguous = r#"[{"old": "let", "new": "const"}]"#;
+        assert!(parse_suggested_edits(file, ambiguous).is_err());
+        let missing = r#"[{"old": "let c", "new": "let d"}]"#;
+        assert!(parse_suggested_edits(file, missing).is_err());
+        let overlapping = r#"[{"old": "a = 1", "new": "x"}, {"old": "1;", "new": "y"}]"#;
+        assert!(parse_suggested_edits(file, overlapping).is_err());
+    }
+
     #[test]
     fn test_no_enabled_models() {
         let mut models = HashMap::new();
@@ -410,6 +669,8 @@ mod tests {
                 base_url: "https://api.openai.com/v1".to_string(),
                 api_key: "test-key".to_string(),
                 model_name: "gpt-3.5-turbo".to_string(),
+                max_concurrent_requests: None,
+                requests_per_minute: None,
             },
         );
 
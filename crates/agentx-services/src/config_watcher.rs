@@ -12,30 +12,59 @@ use std::{
 use anyhow::{Context, Result};
 use notify::{
     Event, RecommendedWatcher, RecursiveMode, Watcher,
-    event::{EventKind, ModifyKind},
+    event::{EventKind, ModifyKind, RenameMode},
 };
 use tokio::sync::mpsc;
 
 use crate::AgentConfigService;
 
+/// Default quiet period before a burst of file events triggers a reload
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(250);
+
 /// Configuration file watcher service
 pub struct ConfigWatcher {
     config_path: PathBuf,
     agent_config_service: Arc<AgentConfigService>,
+    debounce: Duration,
 }
 
 impl ConfigWatcher {
-    /// Create a new configuration watcher
-    pub fn new(config_path: PathBuf, agent_config_service: Arc<AgentConfigService>) -> Self {
+    /// Create a new configuration watcher. Events arriving within `debounce`
+    /// of each other are coalesced into a single reload.
+    pub fn new(
+        config_path: PathBuf,
+        agent_config_service: Arc<AgentConfigService>,
+        debounce: Duration,
+    ) -> Self {
         Self {
             config_path,
             agent_config_service,
+            debounce,
         }
     }
 
+    /// Watch the configuration file on a background thread with its own runtime
+    pub fn spawn(self) {
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    log::error!("Failed to build config watcher runtime: {}", e);
+                    return;
+                }
+            };
+            if let Err(e) = runtime.block_on(self.start_watching()) {
+                log::error!("Config watcher stopped: {}", e);
+            }
+        });
+    }
+
     /// Start watching the configuration file for changes
     pub async fn start_watching(self) -> Result<()> {
-        let (tx, mut rx) = mpsc::channel(100);
+        let (tx, rx) = mpsc::channel(100);
 
         // Spawn file watcher in a separate thread
         let config_path = self.config_path.clone();
@@ -53,24 +82,14 @@ impl ConfigWatcher {
         );
 
         // Process file change events
-        while let Some(event) = rx.recv().await {
-            if Self::should_reload(&event) {
-                log::info!(
-                    "Configuration file changed, reloading: {}",
-                    config_path.display()
-                );
-
-                // Add a small delay to ensure the file is completely written
-                tokio::time::sleep(Duration::from_millis(500)).await;
-
-                // Reload configuration
-                if let Err(e) = self.reload_config().await {
-                    log::error!("Failed to reload configuration: {}", e);
-                } else {
-                    log::info!("Configuration reloaded successfully");
-                }
+        let this = &self;
+        let is_relevant = |event: &Event| Self::should_reload(&config_path, event);
+        debounce_events(rx, self.debounce, is_relevant, || async move {
+            if let Err(e) = this.reload_config().await {
+                log::error!("Failed to reload configuration: {}", e);
             }
-        }
+        })
+        .await;
 
         Ok(())
     }
@@ -87,7 +106,9 @@ impl ConfigWatcher {
         })
         .context("Failed to create file watcher")?;
 
-        // Watch the parent directory since watching a file directly can be unreliable
+        // Watch the parent directory rather than the file: editors that save by
+        // renaming a temp file over the config replace its inode, which would
+        // silently end a watch on the file itself
         let watch_path = path
             .parent()
             .unwrap_or_else(|| Path::new("."))
@@ -105,18 +126,31 @@ impl ConfigWatcher {
         }
     }
 
-    /// Determine if we should reload based on the event
-    fn should_reload(event: &Event) -> bool {
-        match event.kind {
-            EventKind::Modify(ModifyKind::Data(_)) => true,
-            EventKind::Create(_) => true,
-            _ => false,
-        }
+    /// Determine if we should reload based on the event. Only events touching
+    /// `config_path` count; for renames that means the destination, so a temp
+    /// file renamed over the config triggers a reload.
+    fn should_reload(config_path: &Path, event: &Event) -> bool {
+        changed_paths(event)
+            .iter()
+            .any(|path| is_same_file(path, config_path))
     }
 
     /// Reload the configuration file and update all configurations via AgentConfigService
     async fn reload_config(&self) -> Result<()> {
-        log::info!("Configuration file changed, reloading via AgentConfigService");
+        let contents = std::fs::read_to_string(&self.config_path).with_context(|| {
+            format!("Failed to read config file: {}", self.config_path.display())
+        })?;
+
+        // Our own atomic saves show up as events too; nothing changed in that case
+        if self.agent_config_service.is_own_write(&contents) {
+            log::debug!("Ignoring configuration change written by this app");
+            return Ok(());
+        }
+
+        log::info!(
+            "Configuration file changed, reloading: {}",
+            self.config_path.display()
+        );
 
         // Delegate to AgentConfigService - handles all config types + event publishing
         self.agent_config_service
@@ -128,3 +162,151 @@ impl ConfigWatcher {
         Ok(())
     }
 }
+
+/// Paths whose contents may differ after `event`; for renames only the
+/// destination counts
+pub(crate) fn changed_paths(event: &Event) -> &[PathBuf] {
+    match event.kind {
+        EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Any) | EventKind::Create(_) => {
+            &event.paths[..]
+        }
+        // `Both` lists `[from, to]`; `To` and `Any` only the path they know
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+            event.paths.get(1..).unwrap_or_default()
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::To | RenameMode::Any)) => &event.paths[..],
+        _ => &[],
+    }
+}
+
+/// Whether an event path refers to the watched config file. Paths reported by
+/// the OS may be canonicalized (e.g. `/private/var` on macOS), so compare file
+/// names within the single directory being watched.
+fn is_same_file(event_path: &Path, config_path: &Path) -> bool {
+    event_path == config_path
+        || (event_path.file_name().is_some() && event_path.file_name() == config_path.file_name())
+}
+
+/// Call `on_change` once per burst of relevant events, after no further event
+/// has arrived for `debounce`
+async fn debounce_events<F, Fut>(
+    mut rx: mpsc::Receiver<Event>,
+    debounce: Duration,
+    is_relevant: impl Fn(&Event) -> bool,
+    mut on_change: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    while let Some(event) = rx.recv().await {
+        if !is_relevant(&event) {
+            continue;
+        }
+
+        // Keep draining until the file has been quiet for the whole window
+        while let Ok(Some(_)) = tokio::time::timeout(debounce, rx.recv()).await {}
+
+        on_change().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, DataChange, RemoveKind};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn config_path() -> PathBuf {
+        PathBuf::from("/home/user/.agentx/config.json")
+    }
+
+    fn modify_event() -> Event {
+        Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content))).add_path(config_path())
+    }
+
+    fn is_relevant(event: &Event) -> bool {
+        ConfigWatcher::should_reload(&config_path(), event)
+    }
+
+    /// Feed `events` through the debouncer and count the reloads
+    async fn count_reloads(events: Vec<Event>) -> usize {
+        let (tx, rx) = mpsc::channel(100);
+        for event in events {
+            tx.send(event).await.unwrap();
+        }
+        drop(tx);
+
+        let reloads = AtomicUsize::new(0);
+        debounce_events(rx, Duration::from_millis(10), is_relevant, || {
+            reloads.fetch_add(1, Ordering::SeqCst);
+            async {}
+        })
+        .await;
+        reloads.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn rapid_events_trigger_a_single_reload() {
+        let (tx, rx) = mpsc::channel(100);
+        let reloads = AtomicUsize::new(0);
+
+        tokio::spawn(async move {
+            for _ in 0..5 {
+                tx.send(modify_event()).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            tx.send(Event::new(EventKind::Create(CreateKind::File)).add_path(config_path()))
+                .await
+                .unwrap();
+        });
+
+        debounce_events(rx, Duration::from_millis(100), is_relevant, || {
+            reloads.fetch_add(1, Ordering::SeqCst);
+            async {}
+        })
+        .await;
+
+        assert_eq!(reloads.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn irrelevant_events_do_not_reload() {
+        let other = config_path().with_file_name("config.json.backup");
+        assert_eq!(
+            count_reloads(vec![
+                Event::new(EventKind::Access(AccessKind::Any)).add_path(config_path()),
+                Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content)))
+                    .add_path(other),
+            ])
+            .await,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn rename_over_target_still_reloads() {
+        // An editor's atomic save: write a temp file, then rename it over the config
+        let temp = config_path().with_file_name(".config.json.swp");
+        let events = vec![
+            Event::new(EventKind::Create(CreateKind::File)).add_path(temp.clone()),
+            Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content)))
+                .add_path(temp.clone()),
+            Event::new(EventKind::Remove(RemoveKind::File)).add_path(config_path()),
+            Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+                .add_path(temp)
+                .add_path(config_path()),
+        ];
+        assert_eq!(count_reloads(events).await, 1);
+
+        // Renaming the config away is not a change to reload
+        let moved = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path(config_path())
+            .add_path(config_path().with_file_name("config.old.json"));
+        assert!(!is_relevant(&moved));
+
+        // Some backends report the two halves of a rename separately
+        let to =
+            Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::To))).add_path(config_path());
+        assert_eq!(count_reloads(vec![to]).await, 1);
+    }
+}
@@ -0,0 +1,169 @@
+//! Event Log Service
+//!
+//! Appends every session update and permission request seen on the event bus
+//! to a JSON-lines file, so a misbehaving session can be reconstructed later.
+//! Enabled with `--event-log <path>`.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::mpsc,
+    thread::{self, JoinHandle},
+};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde_json::{Value, json};
+
+use agentx_event_bus::{EventHub, PermissionRequestEvent, SessionUpdateEvent, SubscriptionId};
+
+/// Writes bus events to a JSON-lines file until dropped
+pub struct EventLog {
+    event_hub: EventHub,
+    subscriptions: Vec<SubscriptionId>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl EventLog {
+    /// Open `path` for appending and start logging events published on `event_hub`.
+    ///
+    /// Lines are serialized on the publishing thread, which keeps them in
+    /// publish order, and written by a background thread so the UI never
+    /// waits on disk.
+    pub fn start(path: &Path, event_hub: &EventHub) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open event log {}", path.display()))?;
+
+        let (sender, receiver) = mpsc::channel::<String>();
+        let writer = thread::Builder::new()
+            .name("event-log".to_string())
+            .spawn(move || write_lines(file, receiver))
+            .context("Failed to start event log writer")?;
+
+        let session_sender = sender.clone();
+        let session_updates = event_hub.subscribe_session_updates(move |event| {
+            let _ = session_sender.send(session_update_line(event).to_string());
+        });
+        let permission_requests = event_hub.subscribe_permission_requests(move |event| {
+            let _ = sender.send(permission_request_line(event).to_string());
+        });
+
+        log::info!("Logging session events to {}", path.display());
+        Ok(Self {
+            event_hub: event_hub.clone(),
+            subscriptions: vec![session_updates, permission_requests],
+            writer: Some(writer),
+        })
+    }
+
+    /// Keep logging for the rest of the process. Each batch of events is
+    /// flushed as soon as it is written, so nothing is lost on exit.
+    pub fn detach(mut self) {
+        self.subscriptions.clear();
+        self.writer = None;
+    }
+}
+
+impl Drop for EventLog {
+    fn drop(&mut self) {
+        // Unsubscribing drops the senders, which ends the writer thread
+        for id in self.subscriptions.drain(..) {
+            self.event_hub.unsubscribe(id);
+        }
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Write lines as they arrive, flushing whenever the queue runs dry
+fn write_lines(file: File, receiver: mpsc::Receiver<String>) {
+    let mut out = BufWriter::new(file);
+    while let Ok(line) = receiver.recv() {
+        let mut result = writeln!(out, "{}", line);
+        while let Ok(line) = receiver.try_recv() {
+            result = result.and_then(|_| writeln!(out, "{}", line));
+        }
+        if let Err(e) = result.and_then(|_| out.flush()) {
+            log::error!("Failed to write event log: {}", e);
+        }
+    }
+}
+
+fn session_update_line(event: &SessionUpdateEvent) -> Value {
+    json!({
+        "timestamp": Utc::now().to_rfc3339(),
+        "type": "session_update",
+        "session_id": event.session_id,
+        "agent_name": event.agent_name,
+        "update": &*event.update,
+    })
+}
+
+fn permission_request_line(event: &PermissionRequestEvent) -> Value {
+    json!({
+        "timestamp": Utc::now().to_rfc3339(),
+        "type": "permission_request",
+        "permission_id": event.permission_id,
+        "session_id": event.session_id,
+        "agent_name": event.agent_name,
+        "tool_call": event.tool_call,
+        "options": event.options,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use agent_client_protocol as acp;
+
+    use super::*;
+
+    #[test]
+    fn events_are_appended_as_json_lines_in_order() {
+        let dir = std::env::temp_dir().join(format!("agentx-event-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("events.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let hub = EventHub::new();
+        let event_log = EventLog::start(&path, &hub).unwrap();
+        for text in ["first", "second"] {
+            hub.publish_session_update(SessionUpdateEvent {
+                session_id: "s1".to_string(),
+                agent_name: Some("mock".to_string()),
+                update: Arc::new(acp::SessionUpdate::AgentMessageChunk(
+                    acp::ContentChunk::new(text.to_string().into()),
+                )),
+            });
+        }
+        hub.publish_permission_request(PermissionRequestEvent {
+            permission_id: "p1".to_string(),
+            session_id: "s1".to_string(),
+            agent_name: "mock".to_string(),
+            tool_call: acp::ToolCallUpdate::new("call-1", acp::ToolCallUpdateFields::new()),
+            options: Vec::new(),
+        });
+        drop(event_log);
+
+        let lines: Vec<Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["type"], "session_update");
+        assert_eq!(lines[0]["update"]["content"]["text"], "first");
+        assert_eq!(lines[1]["update"]["content"]["text"], "second");
+        assert_eq!(lines[2]["type"], "permission_request");
+        assert_eq!(lines[2]["permission_id"], "p1");
+        assert!(lines.iter().all(|line| line["timestamp"].is_string()));
+    }
+}
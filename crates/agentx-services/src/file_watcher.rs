@@ -0,0 +1,91 @@
+//! Open File Watcher
+//!
+//! Reports changes to individual files on disk, such as buffers open in the
+//! code editor, using the same notify setup as [`ConfigWatcher`](crate::ConfigWatcher).
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::config_watcher::changed_paths;
+
+/// Watches a changing set of files
+pub struct FileWatcher {
+    watcher: RecommendedWatcher,
+    files: HashSet<PathBuf>,
+    /// Watched directories and how many watched files each holds
+    dirs: HashMap<PathBuf, usize>,
+}
+
+impl FileWatcher {
+    /// Create a watcher calling `on_change` on the watcher thread with every
+    /// path whose contents may have changed. Paths next to watched files are
+    /// reported too; callers ignore the ones they don't know.
+    pub fn new(on_change: impl Fn(PathBuf) + Send + 'static) -> Result<Self> {
+        let watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) => {
+                for path in changed_paths(&event) {
+                    on_change(path.clone());
+                }
+            }
+            Err(e) => {
+                log::error!("Watch error: {:?}", e);
+            }
+        })
+        .context("Failed to create file watcher")?;
+
+        Ok(Self {
+            watcher,
+            files: HashSet::new(),
+            dirs: HashMap::new(),
+        })
+    }
+
+    /// Start reporting changes to `path`
+    pub fn watch(&mut self, path: &Path) -> Result<()> {
+        if self.files.contains(path) {
+            return Ok(());
+        }
+
+        // Watch the parent directory, as `ConfigWatcher` does, so atomic saves
+        // that rename a temp file over `path` are still seen
+        let dir = watch_dir(path);
+        if !self.dirs.contains_key(&dir) {
+            self.watcher
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .with_context(|| format!("Failed to watch directory: {}", dir.display()))?;
+        }
+        *self.dirs.entry(dir).or_default() += 1;
+        self.files.insert(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Stop reporting changes to `path`
+    pub fn unwatch(&mut self, path: &Path) {
+        if !self.files.remove(path) {
+            return;
+        }
+
+        let dir = watch_dir(path);
+        if let Some(count) = self.dirs.get_mut(&dir) {
+            *count -= 1;
+            if *count == 0 {
+                self.dirs.remove(&dir);
+                if let Err(e) = self.watcher.unwatch(&dir) {
+                    log::warn!("Failed to unwatch {}: {}", dir.display(), e);
+                }
+            }
+        }
+    }
+}
+
+fn watch_dir(path: &Path) -> PathBuf {
+    path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."))
+        .to_path_buf()
+}
@@ -0,0 +1,228 @@
+//! Headless mode - run a single agent turn without the GUI
+//!
+//! Backs `agentx --headless --agent <name> --prompt <text>` for scripting and
+//! CI: the agent's reply is streamed to stdout and the exit code reflects how
+//! the turn ended.
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use agent_client_protocol::{self as acp, ContentBlock, SessionUpdate};
+use anyhow::{Result, anyhow, bail};
+
+use agentx_agent::PermissionStore;
+use agentx_event_bus::{EventHub, PermissionRequestEvent};
+
+use crate::AgentService;
+
+/// How long to wait for the agent process to finish starting
+pub const AGENT_START_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Arguments of a headless run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadlessArgs {
+    pub agent: String,
+    pub prompt: String,
+}
+
+impl HeadlessArgs {
+    /// Parse `--headless --agent <name> --prompt <text>` from the command line
+    /// (without the program name). Returns `None` when `--headless` is absent;
+    /// other flags such as `--config` are left to their own parsers.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut headless = false;
+        let mut agent = None;
+        let mut prompt = None;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--headless" => headless = true,
+                "--agent" => {
+                    agent = Some(
+                        args.next()
+                            .ok_or_else(|| anyhow!("--agent requires a value"))?,
+                    )
+                }
+                "--prompt" => {
+                    prompt = Some(
+                        args.next()
+                            .ok_or_else(|| anyhow!("--prompt requires a value"))?,
+                    )
+                }
+                _ => {}
+            }
+        }
+
+        if !headless {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            agent: agent.ok_or_else(|| anyhow!("--headless requires --agent <name>"))?,
+            prompt: prompt.ok_or_else(|| anyhow!("--headless requires --prompt <text>"))?,
+        }))
+    }
+}
+
+/// Process exit code for a headless turn: 0 when the agent ended its turn,
+/// 2 when it stopped early (cancelled, refused, ran out of tokens) and 1 on
+/// errors
+pub fn exit_code(result: &Result<acp::StopReason>) -> i32 {
+    match result {
+        Ok(acp::StopReason::EndTurn) => 0,
+        Ok(_) => 2,
+        Err(_) => 1,
+    }
+}
+
+/// Run one prompt against `agent_name` in a new session, writing the agent's
+/// reply to `out` as it streams in.
+///
+/// Nobody is around to answer permission requests, so they are declined.
+pub async fn run_turn<W: Write + Send + 'static>(
+    agent_service: &AgentService,
+    event_hub: &EventHub,
+    permission_store: Arc<PermissionStore>,
+    agent_name: &str,
+    prompt: &str,
+    out: W,
+) -> Result<acp::StopReason> {
+    wait_for_agent(agent_service, agent_name, AGENT_START_TIMEOUT).await?;
+    let session_id = agent_service.create_session(agent_name).await?;
+
+    let out = Arc::new(Mutex::new(out));
+    let writer = out.clone();
+    let updates =
+        event_hub.subscribe_session_updates_for_session(session_id.clone(), move |event| {
+            if let SessionUpdate::AgentMessageChunk(chunk) = &*event.update
+                && let ContentBlock::Text(text) = &chunk.content
+            {
+                let mut out = writer.lock().unwrap();
+                let _ = out.write_all(text.text.as_bytes());
+                let _ = out.flush();
+            }
+        });
+    let permissions =
+        event_hub.subscribe_permission_requests_for_session(session_id.clone(), move |event| {
+            decline_permission(permission_store.clone(), event);
+        });
+
+    let result = agent_service
+        .send_prompt(
+            agent_name,
+            &session_id,
+            vec![ContentBlock::from(prompt.to_string())],
+        )
+        .await;
+
+    event_hub.unsubscribe(updates);
+    event_hub.unsubscribe(permissions);
+    let _ = writeln!(out.lock().unwrap());
+
+    Ok(result?.stop_reason)
+}
+
+/// Wait until the agent has started and registered with the manager
+async fn wait_for_agent(
+    agent_service: &AgentService,
+    agent_name: &str,
+    timeout: Duration,
+) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if agent_service
+            .list_agents()
+            .await
+            .iter()
+            .any(|name| name == agent_name)
+        {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            bail!(
+                "Agent '{}' did not start within {}s",
+                agent_name,
+                timeout.as_secs()
+            );
+        }
+        smol::Timer::after(Duration::from_millis(100)).await;
+    }
+}
+
+/// Reject a permission request, preferring the agent's own "reject once" option
+fn decline_permission(permission_store: Arc<PermissionStore>, event: &PermissionRequestEvent) {
+    eprintln!(
+        "Declined permission request: {}",
+        event
+            .tool_call
+            .fields
+            .title
+            .as_deref()
+            .unwrap_or("tool call")
+    );
+
+    let outcome = event
+        .options
+        .iter()
+        .find(|option| option.kind == acp::PermissionOptionKind::RejectOnce)
+        .map(|option| {
+            acp::RequestPermissionOutcome::Selected(acp::SelectedPermissionOutcome::new(
+                option.option_id.clone(),
+            ))
+        })
+        .unwrap_or(acp::RequestPermissionOutcome::Cancelled);
+    let response = acp::RequestPermissionResponse::new(outcome);
+
+    // Called while the event bus is locked; answer from another thread
+    let permission_id = event.permission_id.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = smol::block_on(permission_store.respond(&permission_id, response)) {
+            log::error!("Failed to decline permission request: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_headless_arguments() {
+        assert_eq!(
+            HeadlessArgs::parse(args(&[
+                "--config",
+                "ci.json",
+                "--headless",
+                "--agent",
+                "claude",
+                "--prompt",
+                "Summarize README.md",
+            ]))
+            .unwrap(),
+            Some(HeadlessArgs {
+                agent: "claude".to_string(),
+                prompt: "Summarize README.md".to_string(),
+            })
+        );
+        assert_eq!(
+            HeadlessArgs::parse(args(&["--config", "ci.json"])).unwrap(),
+            None
+        );
+        assert!(HeadlessArgs::parse(args(&["--headless", "--agent", "claude"])).is_err());
+        assert!(HeadlessArgs::parse(args(&["--headless", "--prompt"])).is_err());
+    }
+
+    #[test]
+    fn exit_code_reflects_how_the_turn_ended() {
+        assert_eq!(exit_code(&Ok(acp::StopReason::EndTurn)), 0);
+        assert_eq!(exit_code(&Ok(acp::StopReason::Cancelled)), 2);
+        assert_eq!(exit_code(&Err(anyhow!("agent crashed"))), 1);
+    }
+}
@@ -2,16 +2,26 @@ pub mod agent_config_service;
 pub mod agent_service;
 pub mod ai_service;
 pub mod config_watcher;
+pub mod event_log;
+pub mod file_watcher;
+pub mod headless;
+pub mod merge_queue;
 pub mod message_service;
+pub mod model_limiter;
 pub mod persistence_service;
+pub mod session_title;
 pub mod workspace_service;
 
 pub use agent_config_service::AgentConfigService;
 pub use agent_service::{AgentService, AgentSessionInfo};
-pub use ai_service::{AiService, AiServiceConfig, CommentStyle};
+pub use ai_service::{AiService, AiServiceConfig, CommentStyle, Edit, apply_edits, edits_diff};
 pub use config_watcher::ConfigWatcher;
-pub use message_service::MessageService;
-pub use persistence_service::PersistenceService;
+pub use event_log::EventLog;
+pub use file_watcher::FileWatcher;
+pub use merge_queue::{BranchMergeResult, MergeQueue, MergeRequest};
+pub use message_service::{MessageService, SessionBranch};
+pub use persistence_service::{PersistenceService, is_imported_session};
+pub use session_title::{ReplyCollector, SessionTitler};
 pub use workspace_service::WorkspaceService;
 
 // Re-export SessionStatus from types for convenience
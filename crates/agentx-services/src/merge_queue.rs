@@ -0,0 +1,250 @@
+//! Merge Queue
+//!
+//! Serializes merges of agent branches into the same target branch. Several
+//! agents finishing at once would otherwise run `WorktreeManager::merge`
+//! concurrently and race on the target's `HEAD`. Merges into different
+//! target branches still run in parallel.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use git_worktree_manager::{MergeOptions, MergeOutcome, WorktreeManager};
+
+/// A merge of `source_branch` into `target_branch`, checked out in
+/// `target_worktree`
+#[derive(Debug, Clone)]
+pub struct MergeRequest {
+    pub target_worktree: PathBuf,
+    pub target_branch: String,
+    pub source_branch: String,
+    pub options: MergeOptions,
+}
+
+/// Outcome of one merge processed by [`MergeQueue::merge_all`]
+#[derive(Debug)]
+pub struct BranchMergeResult {
+    pub source_branch: String,
+    pub outcome: Result<MergeOutcome>,
+}
+
+/// Queue running merges into a target branch one at a time, in the order they
+/// were enqueued
+#[derive(Clone)]
+pub struct MergeQueue {
+    manager: WorktreeManager,
+    /// One lock per target branch; tokio's mutex hands out the lock in FIFO
+    /// order, which keeps merges in the order they were requested
+    locks: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+}
+
+impl MergeQueue {
+    /// Create a queue for the repository managed by `manager`
+    pub fn new(manager: WorktreeManager) -> Self {
+        Self {
+            manager,
+            locks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Merge once every merge enqueued earlier for the same target branch has
+    /// finished.
+    ///
+    /// A conflicted merge is aborted after its conflicts are collected, so the
+    /// target worktree is left clean for the merges queued behind it. The
+    /// conflicts are returned in [`MergeOutcome::Conflicts`].
+    pub async fn merge(&self, request: MergeRequest) -> Result<MergeOutcome> {
+        let lock = self.lock_for(&request.target_branch);
+        let _guard = lock.lock().await;
+
+        let manager = self.manager.clone();
+        let context = format!(
+            "Failed to merge {} into {}",
+            request.source_branch, request.target_branch
+        );
+        smol::unblock(move || -> Result<MergeOutcome> {
+            let outcome = manager.merge(
+                &request.target_worktree,
+                &request.target_branch,
+                &request.source_branch,
+                request.options,
+            )?;
+            if matches!(outcome, MergeOutcome::Conflicts(_)) {
+                log::info!(
+                    "[MergeQueue] Aborting conflicted merge of {} into {}",
+                    request.source_branch,
+                    request.target_branch
+                );
+                manager.abort_merge(&request.target_worktree)?;
+            }
+            Ok(outcome)
+        })
+        .await
+        .context(context)
+    }
+
+    /// Run `requests` in order, collecting each branch's outcome. A failed or
+    /// conflicted merge does not stop the ones after it.
+    pub async fn merge_all(&self, requests: Vec<MergeRequest>) -> Vec<BranchMergeResult> {
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            let source_branch = request.source_branch.clone();
+            let outcome = self.merge(request).await;
+            if let Err(e) = &outcome {
+                log::warn!("[MergeQueue] {:#}", e);
+            }
+            results.push(BranchMergeResult {
+                source_branch,
+                outcome,
+            });
+        }
+        results
+    }
+
+    fn lock_for(&self, target_branch: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.locks
+            .lock()
+            .unwrap()
+            .entry(target_branch.to_string())
+            .or_default()
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use git2::{Repository, Signature, build::CheckoutBuilder};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn commit_file(repo: &Repository, path: &str, content: &str, message: &str) {
+        std::fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("tests", "tests@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap();
+    }
+
+    fn checkout(repo: &Repository, branch: &str) {
+        repo.set_head(&format!("refs/heads/{}", branch)).unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+    }
+
+    fn request(temp: &TempDir, target_branch: &str, source_branch: &str) -> MergeRequest {
+        MergeRequest {
+            target_worktree: temp.path().to_path_buf(),
+            target_branch: target_branch.to_string(),
+            source_branch: source_branch.to_string(),
+            options: MergeOptions::default(),
+        }
+    }
+
+    /// Repository with `agent-a`, `agent-b` and `agent-c` branched from the
+    /// same commit; `agent-b` conflicts with `main` when `conflicting_b` is set
+    fn repo_with_agent_branches(conflicting_b: bool) -> (TempDir, Repository, String) {
+        let temp = TempDir::new().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        // Merge commits are signed with the repository's configured identity
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "tests").unwrap();
+        config.set_str("user.email", "tests@example.com").unwrap();
+        commit_file(&repo, "shared.txt", "base\n", "init");
+        let main_branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+
+        let b_file = if conflicting_b { "shared.txt" } else { "b.txt" };
+        for (branch, file, content) in [
+            ("agent-a", "a.txt", "a\n"),
+            ("agent-b", b_file, "agent b\n"),
+            ("agent-c", "c.txt", "c\n"),
+        ] {
+            repo.branch(branch, &base, false).unwrap();
+            checkout(&repo, branch);
+            commit_file(&repo, file, content, branch);
+        }
+        checkout(&repo, &main_branch);
+        commit_file(&repo, "shared.txt", "main\n", "main change");
+        drop(base);
+        (temp, repo, main_branch)
+    }
+
+    /// Source branches of the merge commits on `HEAD`, newest first
+    fn merged_branches(repo: &Repository) -> Vec<String> {
+        let mut commit = repo.head().unwrap().peel_to_commit().unwrap();
+        let mut branches = Vec::new();
+        while commit.parent_count() == 2 {
+            let message = commit.message().unwrap().to_string();
+            branches.push(message.split('\'').nth(1).unwrap().to_string());
+            commit = commit.parent(0).unwrap();
+        }
+        branches
+    }
+
+    #[tokio::test]
+    async fn queued_merges_run_in_order_past_conflicts() {
+        let (temp, repo, main_branch) = repo_with_agent_branches(true);
+
+        let queue = MergeQueue::new(WorktreeManager::new(temp.path()));
+        let results = queue
+            .merge_all(vec![
+                request(&temp, &main_branch, "agent-a"),
+                request(&temp, &main_branch, "agent-b"),
+                request(&temp, &main_branch, "agent-c"),
+            ])
+            .await;
+
+        let summary: Vec<_> = results
+            .iter()
+            .map(|result| {
+                let outcome = match result.outcome.as_ref().unwrap() {
+                    MergeOutcome::Merged => "merged",
+                    MergeOutcome::Conflicts(_) => "conflicts",
+                };
+                (result.source_branch.as_str(), outcome)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("agent-a", "merged"),
+                ("agent-b", "conflicts"),
+                ("agent-c", "merged"),
+            ]
+        );
+
+        // agent-c was merged on top of agent-a, and agent-b left nothing behind
+        assert_eq!(merged_branches(&repo), ["agent-c", "agent-a"]);
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("shared.txt")).unwrap(),
+            "main\n"
+        );
+        assert!(repo.statuses(None).unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_merges_into_one_branch_run_one_at_a_time() {
+        let (temp, repo, main_branch) = repo_with_agent_branches(false);
+
+        let queue = MergeQueue::new(WorktreeManager::new(temp.path()));
+        let (a, b, c) = tokio::join!(
+            queue.merge(request(&temp, &main_branch, "agent-a")),
+            queue.merge(request(&temp, &main_branch, "agent-b")),
+            queue.merge(request(&temp, &main_branch, "agent-c")),
+        );
+
+        for outcome in [a, b, c] {
+            assert_eq!(outcome.unwrap(), MergeOutcome::Merged);
+        }
+        assert_eq!(merged_branches(&repo), ["agent-c", "agent-b", "agent-a"]);
+    }
+}
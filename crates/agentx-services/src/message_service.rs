@@ -4,13 +4,14 @@
 //! to session updates. It orchestrates between AgentService and SessionBus.
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
     sync::{Arc, Mutex},
 };
 
 use agent_client_protocol::{
-    AvailableCommand, ContentBlock, ContentChunk, ImageContent, PromptResponse, SessionUpdate,
-    TextContent,
+    AvailableCommand, ContentBlock, ContentChunk, ImageContent, McpServer, PromptResponse,
+    SessionUpdate, TextContent,
 };
 use anyhow::{Result, anyhow};
 
@@ -18,13 +19,51 @@ use agentx_event_bus::{EventHub, SessionUpdateEvent, WorkspaceUpdateEvent};
 use agentx_types::SessionStatus;
 
 use super::agent_service::AgentService;
-use super::persistence_service::{PersistedMessage, PersistenceService};
+use super::persistence_service::{
+    PersistedMessage, PersistenceService, history_transcript, last_turn_start, turn_starts,
+    user_prompt_at,
+};
+
+/// `_meta` key agents report a turn's token usage and cost under; the
+/// conversation view reads it from agent message chunks
+/// (`agentx_acp_ui::USAGE_META_KEY`)
+const USAGE_META_KEY: &str = "usage";
+
+/// A message typed while its session was busy, waiting to be sent
+#[derive(Clone, Debug)]
+pub struct QueuedMessage {
+    pub agent_name: String,
+    pub content_blocks: Vec<ContentBlock>,
+}
+
+/// Messages waiting for one session
+#[derive(Default)]
+struct SessionQueue {
+    messages: VecDeque<QueuedMessage>,
+    /// A queued message was sent and its turn has not ended yet
+    in_flight: bool,
+}
+
+/// A conversation moved to a new agent session, waiting for the prompt
+/// that continues it (see [`MessageService::send_branch`])
+#[derive(Debug)]
+pub struct SessionBranch {
+    pub agent_name: String,
+    /// The new session
+    pub session_id: String,
+    /// Kept history, for the agent to pick the conversation up from
+    transcript: Option<String>,
+    prompt: Vec<ContentBlock>,
+}
 
 /// Message service - handles message sending and event bus interaction
+#[derive(Clone)]
 pub struct MessageService {
     event_hub: EventHub,
     agent_service: Arc<AgentService>,
     persistence_service: Arc<PersistenceService>,
+    /// Outbound messages per session, sent in order once the session is idle
+    queues: Arc<Mutex<HashMap<String, SessionQueue>>>,
 }
 
 impl MessageService {
@@ -37,6 +76,7 @@ impl MessageService {
             event_hub,
             agent_service,
             persistence_service,
+            queues: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -188,10 +228,375 @@ impl MessageService {
             .send_prompt(agent_name, session_id, content_blocks)
             .await
             .map_err(|e| anyhow!("Failed to send message: {}", e))?;
+        self.publish_turn_usage(session_id, &result);
 
         Ok(result)
     }
 
+    /// Send a user message, or queue it while the agent is working on a turn
+    ///
+    /// Queued messages are sent one at a time, in order, each time the session
+    /// becomes idle (see [`Self::init_message_queue`]). Returns `None` when the
+    /// message was queued.
+    pub async fn send_or_queue(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        content_blocks: Vec<ContentBlock>,
+    ) -> Result<Option<PromptResponse>> {
+        let session = self
+            .agent_service
+            .get_session_info(agent_name, session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+
+        let busy = session.status.is_turn_in_progress();
+        if busy || self.queued_count(session_id) > 0 {
+            self.enqueue(
+                session_id,
+                QueuedMessage {
+                    agent_name: agent_name.to_string(),
+                    content_blocks,
+                },
+            );
+            if !busy {
+                self.flush_next_queued(session_id);
+            }
+            return Ok(None);
+        }
+
+        self.send_message_to_session(agent_name, session_id, content_blocks)
+            .await
+            .map(Some)
+    }
+
+    /// Send queued messages as their sessions become idle
+    ///
+    /// This should be called once after the MessageService is created.
+    pub fn init_message_queue(&self) {
+        let service = self.clone();
+        self.event_hub.subscribe_workspace_updates(move |event| {
+            if let WorkspaceUpdateEvent::SessionStatusUpdated { session_id, .. } = event {
+                // Status updates are published while the session table and the
+                // event bus are locked, so check the queue outside the callback
+                let service = service.clone();
+                let session_id = session_id.clone();
+                smol::spawn(async move {
+                    service.flush_next_queued(&session_id);
+                })
+                .detach();
+            }
+        });
+    }
+
+    /// Number of messages waiting to be sent to a session
+    pub fn queued_count(&self, session_id: &str) -> usize {
+        self.queues
+            .lock()
+            .unwrap()
+            .get(session_id)
+            .map_or(0, |queue| queue.messages.len())
+    }
+
+    /// Drop every message waiting for a session
+    pub fn clear_queue(&self, session_id: &str) {
+        let cleared = {
+            let mut queues = self.queues.lock().unwrap();
+            let Some(queue) = queues.get_mut(session_id) else {
+                return;
+            };
+            let cleared = !queue.messages.is_empty();
+            queue.messages.clear();
+            if !queue.in_flight {
+                queues.remove(session_id);
+            }
+            cleared
+        };
+        if cleared {
+            self.publish_queue_changed(session_id, 0);
+        }
+    }
+
+    /// Cancel the running turn of a session and drop its queued messages
+    pub async fn cancel_session(&self, session_id: &str) -> Result<()> {
+        self.clear_queue(session_id);
+        self.agent_service.cancel(session_id).await
+    }
+
+    fn enqueue(&self, session_id: &str, message: QueuedMessage) {
+        let queued = {
+            let mut queues = self.queues.lock().unwrap();
+            let queue = queues.entry(session_id.to_string()).or_default();
+            queue.messages.push_back(message);
+            queue.messages.len()
+        };
+        log::info!(
+            "Queued message for busy session {} ({} waiting)",
+            session_id,
+            queued
+        );
+        self.publish_queue_changed(session_id, queued);
+    }
+
+    /// Take the next queued message if the session is ready for a new turn
+    ///
+    /// Every status update tries to flush the queue, and the session only
+    /// reports the new turn once its prompt is on the way, so a message is
+    /// marked in flight until [`Self::finish_queued_turn`]; until then nothing
+    /// else is taken.
+    fn take_ready(&self, session_id: &str) -> Option<QueuedMessage> {
+        let ready = self
+            .agent_service
+            .get_session_by_id(session_id)
+            .is_some_and(|session| {
+                matches!(
+                    session.status,
+                    SessionStatus::Active | SessionStatus::Idle | SessionStatus::Completed
+                )
+            });
+        if !ready {
+            return None;
+        }
+
+        let (message, queued) = {
+            let mut queues = self.queues.lock().unwrap();
+            let queue = queues.get_mut(session_id)?;
+            if queue.in_flight {
+                return None;
+            }
+            let message = queue.messages.pop_front()?;
+            queue.in_flight = true;
+            (message, queue.messages.len())
+        };
+        self.publish_queue_changed(session_id, queued);
+        Some(message)
+    }
+
+    /// Mark the turn of the last queued message of a session as ended
+    fn finish_queued_turn(&self, session_id: &str) {
+        let mut queues = self.queues.lock().unwrap();
+        if let Some(queue) = queues.get_mut(session_id) {
+            queue.in_flight = false;
+            if queue.messages.is_empty() {
+                queues.remove(session_id);
+            }
+        }
+    }
+
+    fn flush_next_queued(&self, session_id: &str) {
+        let Some(message) = self.take_ready(session_id) else {
+            return;
+        };
+
+        let service = self.clone();
+        let session_id = session_id.to_string();
+        smol::spawn(async move {
+            if let Err(e) = service
+                .send_message_to_session(&message.agent_name, &session_id, message.content_blocks)
+                .await
+            {
+                log::error!(
+                    "Failed to send queued message to session {}: {}",
+                    session_id,
+                    e
+                );
+            }
+            service.finish_queued_turn(&session_id);
+            service.flush_next_queued(&session_id);
+        })
+        .detach();
+    }
+
+    fn publish_queue_changed(&self, session_id: &str, queued: usize) {
+        self.event_hub
+            .publish_workspace_update(WorkspaceUpdateEvent::MessageQueueChanged {
+                session_id: session_id.to_string(),
+                queued,
+            });
+    }
+
+    /// Move a session to a new agent session to ask again for its last turn
+    ///
+    /// The new session keeps the history before that turn; send the turn's
+    /// user message with [`Self::send_branch`]. Fails while the agent is
+    /// still working on a turn.
+    pub async fn branch_to_regenerate(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        mcp_servers: Vec<McpServer>,
+        cwd: PathBuf,
+    ) -> Result<SessionBranch> {
+        self.ensure_turn_finished(agent_name, session_id)?;
+
+        self.persistence_service.flush_session(session_id).await?;
+        let mut messages = self.persistence_service.load_messages(session_id).await?;
+        let start = last_turn_start(&messages)
+            .ok_or_else(|| anyhow!("No user message to regenerate in session {}", session_id))?;
+        let prompt = user_prompt_at(&messages, start);
+        messages.truncate(start);
+        log::info!(
+            "Regenerating last response for session {} ({} prompt blocks)",
+            session_id,
+            prompt.len()
+        );
+
+        self.branch_session(agent_name, session_id, messages, prompt, mcp_servers, cwd)
+            .await
+    }
+
+    /// Move a session to a new agent session to replace the user message that
+    /// opens turn `turn` (0-based) with `content_blocks`
+    ///
+    /// The new session keeps the history before that turn; send the edited
+    /// message with [`Self::send_branch`]. Fails while the agent is still
+    /// working on a turn.
+    pub async fn branch_to_edit(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        turn: usize,
+        content_blocks: Vec<ContentBlock>,
+        mcp_servers: Vec<McpServer>,
+        cwd: PathBuf,
+    ) -> Result<SessionBranch> {
+        self.ensure_turn_finished(agent_name, session_id)?;
+
+        self.persistence_service.flush_session(session_id).await?;
+        let mut messages = self.persistence_service.load_messages(session_id).await?;
+        if let Some(&start) = turn_starts(&messages).get(turn) {
+            messages.truncate(start);
+        }
+        log::info!("Resending edited turn {} of session {}", turn, session_id);
+
+        self.branch_session(
+            agent_name,
+            session_id,
+            messages,
+            content_blocks,
+            mcp_servers,
+            cwd,
+        )
+        .await
+    }
+
+    /// Send the prompt that continues a branched session
+    ///
+    /// The agent gets the kept history as a transcript ahead of the prompt;
+    /// the conversation only shows the prompt.
+    pub async fn send_branch(&self, branch: SessionBranch) -> Result<PromptResponse> {
+        for block in &branch.prompt {
+            self.publish_user_content_block(&branch.session_id, block);
+        }
+
+        let mut content_blocks = Vec::with_capacity(branch.prompt.len() + 1);
+        if let Some(transcript) = branch.transcript {
+            content_blocks.push(ContentBlock::from(format!(
+                "Continue this earlier conversation with me:\n\n{}\n\n\
+                 My next message follows.",
+                transcript
+            )));
+        }
+        content_blocks.extend(branch.prompt);
+
+        let result = self
+            .agent_service
+            .send_prompt(&branch.agent_name, &branch.session_id, content_blocks)
+            .await
+            .map_err(|e| anyhow!("Failed to send message: {}", e))?;
+        self.publish_turn_usage(&branch.session_id, &result);
+
+        Ok(result)
+    }
+
+    /// Replace a session with a new agent session whose history is `history`
+    ///
+    /// ACP has no way to drop turns the agent already holds, so rewriting a
+    /// conversation starts it over: the new session gets the kept history and
+    /// the selected model, and the old session is closed and its history
+    /// deleted.
+    async fn branch_session(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        history: Vec<PersistedMessage>,
+        prompt: Vec<ContentBlock>,
+        mcp_servers: Vec<McpServer>,
+        cwd: PathBuf,
+    ) -> Result<SessionBranch> {
+        let branch_id = self
+            .agent_service
+            .create_session_with_mcp_and_cwd(agent_name, mcp_servers, cwd)
+            .await?;
+
+        if let Some(model_id) = self.agent_service.session_model(agent_name, session_id) {
+            if let Err(e) = self
+                .agent_service
+                .set_session_model(agent_name, &branch_id, &model_id)
+                .await
+            {
+                log::warn!(
+                    "Failed to keep model '{}' for session {}: {}",
+                    model_id,
+                    branch_id,
+                    e
+                );
+            }
+        }
+
+        let transcript = history_transcript(&history);
+        self.persistence_service
+            .write_messages(&branch_id, history)
+            .await?;
+        self.clear_queue(session_id);
+        self.agent_service
+            .close_session(agent_name, session_id)
+            .await?;
+        self.persistence_service.delete_session(session_id).await?;
+        log::info!("Session {} continues as {}", session_id, branch_id);
+
+        Ok(SessionBranch {
+            agent_name: agent_name.to_string(),
+            session_id: branch_id,
+            transcript,
+            prompt,
+        })
+    }
+
+    fn ensure_turn_finished(&self, agent_name: &str, session_id: &str) -> Result<()> {
+        let session = self
+            .agent_service
+            .get_session_info(agent_name, session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+        if session.status.is_turn_in_progress() {
+            return Err(anyhow!("Session {} is still working on a turn", session_id));
+        }
+        Ok(())
+    }
+
+    /// Pass the usage a prompt response reports in its `_meta` on to the
+    /// conversation, as an empty agent message chunk carrying it
+    fn publish_turn_usage(&self, session_id: &str, response: &PromptResponse) {
+        let Some(usage) = response
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.get(USAGE_META_KEY))
+        else {
+            return;
+        };
+
+        let mut chunk = ContentChunk::new(ContentBlock::from(String::new()));
+        chunk.meta = Some(
+            [(USAGE_META_KEY.to_string(), usage.clone())]
+                .into_iter()
+                .collect(),
+        );
+        self.event_hub.publish_session_update(SessionUpdateEvent {
+            session_id: session_id.to_string(),
+            agent_name: self.agent_service.get_agent_for_session(session_id),
+            update: Arc::new(SessionUpdate::AgentMessageChunk(chunk)),
+        });
+    }
+
     /// Publish a user message to the event bus (immediate UI feedback)
     pub fn publish_user_message(&self, session_id: &str, message: &str) {
         let content_block = ContentBlock::from(message.to_string());
@@ -215,8 +620,10 @@ impl MessageService {
         let schema_block = match block {
             ContentBlock::Text(text) => {
                 // Create TextContent using new() or default methods to handle non-exhaustive struct
-                let text_content = TextContent::new(text.text.clone());
+                let mut text_content = TextContent::new(text.text.clone());
                 // Note: annotations field might not be directly settable due to version mismatch
+                // Keep _meta, which records the slash command an expanded message came from
+                text_content.meta = text.meta.clone();
                 ContentBlock::Text(text_content)
             }
             ContentBlock::Image(img) => {
@@ -306,3 +713,189 @@ impl MessageService {
             .get_session_commands(&agent_name, session_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::StopReason;
+    use agentx_agent::{AgentHandle, AgentManager};
+
+    fn create_test_service() -> MessageService {
+        let event_hub = EventHub::new();
+        let agent_manager = Arc::new(AgentManager::new(
+            HashMap::new(),
+            Arc::new(Default::default()),
+            event_hub.clone(),
+            Default::default(),
+        ));
+        let agent_service = Arc::new(AgentService::new(agent_manager));
+        let persistence_service = Arc::new(PersistenceService::new(
+            std::env::temp_dir().join("agentx-message-queue-test"),
+        ));
+        MessageService::new(event_hub, agent_service, persistence_service)
+    }
+
+    fn busy_session(service: &MessageService) {
+        service
+            .agent_service
+            .update_session_commands("agent", "session-1", Vec::new());
+        service.agent_service.update_session_status(
+            "agent",
+            "session-1",
+            SessionStatus::InProgress,
+        );
+    }
+
+    fn text_of(message: QueuedMessage) -> String {
+        match &message.content_blocks[0] {
+            ContentBlock::Text(text) => text.text.clone(),
+            other => panic!("unexpected block: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn messages_sent_while_busy_are_delivered_in_order_once_idle() {
+        let service = create_test_service();
+        busy_session(&service);
+
+        for text in ["first", "second"] {
+            let blocks = vec![ContentBlock::from(text.to_string())];
+            let response = smol::block_on(service.send_or_queue("agent", "session-1", blocks));
+            assert!(response.unwrap().is_none());
+        }
+        assert_eq!(service.queued_count("session-1"), 2);
+        assert!(service.take_ready("session-1").is_none());
+
+        service
+            .agent_service
+            .update_session_status("agent", "session-1", SessionStatus::Idle);
+        assert_eq!(text_of(service.take_ready("session-1").unwrap()), "first");
+        service.finish_queued_turn("session-1");
+        assert_eq!(text_of(service.take_ready("session-1").unwrap()), "second");
+        service.finish_queued_turn("session-1");
+        assert!(service.take_ready("session-1").is_none());
+        assert_eq!(service.queued_count("session-1"), 0);
+    }
+
+    #[test]
+    fn concurrent_flushes_send_one_queued_message_at_a_time() {
+        let service = create_test_service();
+        busy_session(&service);
+
+        for text in ["first", "second"] {
+            let blocks = vec![ContentBlock::from(text.to_string())];
+            smol::block_on(service.send_or_queue("agent", "session-1", blocks)).unwrap();
+        }
+        service
+            .agent_service
+            .update_session_status("agent", "session-1", SessionStatus::Idle);
+
+        // Two status updates arrive before the first message starts its turn
+        let taken: Vec<_> = std::thread::scope(|scope| {
+            let flushes: Vec<_> = (0..2)
+                .map(|_| scope.spawn(|| service.take_ready("session-1")))
+                .collect();
+            flushes
+                .into_iter()
+                .filter_map(|flush| flush.join().unwrap())
+                .collect()
+        });
+        assert_eq!(taken.len(), 1);
+        assert_eq!(text_of(taken.into_iter().next().unwrap()), "first");
+        assert_eq!(service.queued_count("session-1"), 1);
+
+        service.finish_queued_turn("session-1");
+        assert_eq!(text_of(service.take_ready("session-1").unwrap()), "second");
+    }
+
+    #[test]
+    fn clearing_the_queue_drops_pending_messages() {
+        let service = create_test_service();
+        busy_session(&service);
+
+        let blocks = vec![ContentBlock::from("pending".to_string())];
+        smol::block_on(service.send_or_queue("agent", "session-1", blocks)).unwrap();
+        assert_eq!(service.queued_count("session-1"), 1);
+
+        service.clear_queue("session-1");
+        assert_eq!(service.queued_count("session-1"), 0);
+
+        service
+            .agent_service
+            .update_session_status("agent", "session-1", SessionStatus::Idle);
+        assert!(service.take_ready("session-1").is_none());
+    }
+
+    #[test]
+    fn editing_a_turn_moves_the_conversation_to_a_fresh_agent_session() {
+        let dir = std::env::temp_dir().join(format!("agentx-branch-{}", std::process::id()));
+        let event_hub = EventHub::new();
+        let agent_manager = Arc::new(AgentManager::new(
+            HashMap::new(),
+            Arc::new(Default::default()),
+            event_hub.clone(),
+            Default::default(),
+        ));
+        let mut agent_service = AgentService::new(agent_manager.clone());
+        agent_service.set_event_hub(event_hub.clone());
+        let agent_service = Arc::new(agent_service);
+        let persistence_service = Arc::new(PersistenceService::new(dir.clone()));
+        let service = MessageService::new(
+            event_hub.clone(),
+            agent_service.clone(),
+            persistence_service.clone(),
+        );
+
+        smol::block_on(async {
+            agent_manager
+                .insert_handle(AgentHandle::scripted(
+                    "agent",
+                    event_hub,
+                    vec!["Autumn moonlight".to_string()],
+                ))
+                .await;
+            let session_id = agent_service.create_session("agent").await.unwrap();
+            for (user, agent) in [("Hello", "Hi!"), ("Write a poem", "Roses are red")] {
+                for update in [
+                    SessionUpdate::UserMessageChunk(ContentChunk::new(user.to_string().into())),
+                    SessionUpdate::AgentMessageChunk(ContentChunk::new(agent.to_string().into())),
+                ] {
+                    persistence_service
+                        .save_update(&session_id, update)
+                        .await
+                        .unwrap();
+                }
+            }
+
+            let edited = vec![ContentBlock::from("Write a haiku".to_string())];
+            let branch = service
+                .branch_to_edit("agent", &session_id, 1, edited, Vec::new(), dir.clone())
+                .await
+                .unwrap();
+
+            // The agent still remembers both turns of its session, so the
+            // edited turn must go to a session it has never seen
+            assert_eq!(session_id, "agent-session-1");
+            assert_eq!(branch.session_id, "agent-session-2");
+            assert_eq!(
+                agent_service.get_session_by_id(&session_id).unwrap().status,
+                SessionStatus::Closed
+            );
+            assert_eq!(
+                branch.transcript.as_deref(),
+                Some("User: Hello\n\nAssistant: Hi!")
+            );
+            let kept = persistence_service
+                .load_messages(&branch.session_id)
+                .await
+                .unwrap();
+            assert_eq!(kept.len(), 2);
+            assert!(!persistence_service.session_file_exists(&session_id));
+
+            let response = service.send_branch(branch).await.unwrap();
+            assert!(matches!(response.stop_reason, StopReason::EndTurn));
+        });
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,148 @@
+//! Model Limiter
+//!
+//! Keeps requests to one model under its configured concurrency and
+//! requests-per-minute limits. Requests over a limit wait for their turn
+//! instead of failing, so agents sharing an endpoint stop tripping its rate
+//! limit.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use agentx_types::ModelConfig;
+
+/// Limits applied to requests for a single model
+pub struct ModelLimiter {
+    max_concurrent_requests: Option<u32>,
+    requests_per_minute: Option<u32>,
+    concurrency: Option<Arc<Semaphore>>,
+    /// Earliest time the next request may start under the RPM limit
+    next_slot: Mutex<Instant>,
+}
+
+/// Held for the duration of a request; releases its concurrency slot on drop
+pub struct ModelPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl ModelLimiter {
+    /// Limiter for `config`; a missing or zero limit means unlimited
+    pub fn new(config: &ModelConfig) -> Self {
+        let max_concurrent_requests = config.max_concurrent_requests.filter(|max| *max > 0);
+        let requests_per_minute = config.requests_per_minute.filter(|rpm| *rpm > 0);
+        Self {
+            max_concurrent_requests,
+            requests_per_minute,
+            concurrency: max_concurrent_requests.map(|max| Arc::new(Semaphore::new(max as usize))),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Whether this limiter enforces the limits currently set in `config`
+    pub fn matches(&self, config: &ModelConfig) -> bool {
+        self.max_concurrent_requests == config.max_concurrent_requests.filter(|max| *max > 0)
+            && self.requests_per_minute == config.requests_per_minute.filter(|rpm| *rpm > 0)
+    }
+
+    /// Wait until a request may start. Requests are spaced evenly across the
+    /// minute, then wait for a free concurrency slot.
+    pub async fn acquire(&self) -> ModelPermit {
+        if let Some(rpm) = self.requests_per_minute {
+            let interval = Duration::from_secs(60) / rpm;
+            let start = {
+                let mut next_slot = self.next_slot.lock().unwrap();
+                let start = (*next_slot).max(Instant::now());
+                *next_slot = start + interval;
+                start
+            };
+            smol::Timer::at(start).await;
+        }
+
+        let permit = match &self.concurrency {
+            Some(semaphore) => Some(
+                semaphore
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("model semaphore is never closed"),
+            ),
+            None => None,
+        };
+        ModelPermit { _permit: permit }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn model(
+        max_concurrent_requests: Option<u32>,
+        requests_per_minute: Option<u32>,
+    ) -> ModelConfig {
+        ModelConfig {
+            enabled: true,
+            provider: "openai".to_string(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: "test-key".to_string(),
+            model_name: "gpt-4o".to_string(),
+            max_concurrent_requests,
+            requests_per_minute,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrency_limit_of_one_serializes_requests() {
+        let limiter = Arc::new(ModelLimiter::new(&model(Some(1), None)));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let requests: Vec<_> = (0..4)
+            .map(|_| {
+                let limiter = limiter.clone();
+                let in_flight = in_flight.clone();
+                let peak = peak.clone();
+                tokio::spawn(async move {
+                    let _permit = limiter.acquire().await;
+                    let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(current, Ordering::SeqCst);
+                    smol::Timer::after(Duration::from_millis(20)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for request in requests {
+            request.await.unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn requests_per_minute_spaces_requests() {
+        // 600 requests per minute is one every 100ms
+        let limiter = ModelLimiter::new(&model(None, Some(600)));
+        let started = Instant::now();
+        let mut starts = Vec::new();
+        for _ in 0..3 {
+            let _permit = limiter.acquire().await;
+            starts.push(started.elapsed());
+        }
+
+        assert!(starts[0] < Duration::from_millis(50));
+        assert!(starts[1] >= Duration::from_millis(100));
+        assert!(starts[2] >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn zero_limits_mean_unlimited() {
+        let limiter = ModelLimiter::new(&model(Some(0), Some(0)));
+        assert!(limiter.concurrency.is_none());
+        assert!(limiter.requests_per_minute.is_none());
+        assert!(limiter.matches(&model(None, None)));
+        assert!(!limiter.matches(&model(Some(2), None)));
+    }
+}
@@ -4,7 +4,7 @@
 //! and loads historical messages when needed.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use agent_client_protocol::{
@@ -36,6 +36,101 @@ impl PersistedMessage {
     }
 }
 
+/// Format version written by [`PersistenceService::export_session`]
+pub const SESSION_EXPORT_VERSION: u32 = 1;
+
+/// Prefix of session ids created by [`PersistenceService::import_session`]
+const IMPORTED_SESSION_PREFIX: &str = "imported-";
+
+/// A session's full history in a self-contained, portable file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionExport {
+    /// Format version, bumped on incompatible changes
+    pub version: u32,
+    /// Id of the session on the machine it was exported from
+    pub session_id: String,
+    /// When the export was made, in ISO 8601 format
+    pub exported_at: String,
+    /// Messages, tool calls and plans in chronological order
+    pub messages: Vec<PersistedMessage>,
+}
+
+/// Whether a session was imported from a file; imported sessions have no
+/// agent behind them and are only replayed
+pub fn is_imported_session(session_id: &str) -> bool {
+    session_id.starts_with(IMPORTED_SESSION_PREFIX)
+}
+
+fn is_user_message(message: &PersistedMessage) -> bool {
+    matches!(message.update, SessionUpdate::UserMessageChunk(_))
+}
+
+/// Index of the user message that opens each turn of a history
+///
+/// A message sent with code selections or images is persisted as several
+/// consecutive chunks; each index points at the first of them.
+pub fn turn_starts(messages: &[PersistedMessage]) -> Vec<usize> {
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(index, message)| {
+            is_user_message(message) && (*index == 0 || !is_user_message(&messages[index - 1]))
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Index of the user message that opens the last turn of a history
+pub fn last_turn_start(messages: &[PersistedMessage]) -> Option<usize> {
+    turn_starts(messages).last().copied()
+}
+
+/// Plain-text transcript of the conversation in a history, one paragraph
+/// per speaker, for seeding a new agent session with it
+///
+/// Only text is kept: thoughts, tool calls and images are left out.
+pub fn history_transcript(messages: &[PersistedMessage]) -> Option<String> {
+    let mut transcript = String::new();
+    let mut speaker = None;
+    for message in messages {
+        let (role, chunk) = match &message.update {
+            SessionUpdate::UserMessageChunk(chunk) => ("User", chunk),
+            SessionUpdate::AgentMessageChunk(chunk) => ("Assistant", chunk),
+            _ => continue,
+        };
+        let ContentBlock::Text(text) = &chunk.content else {
+            continue;
+        };
+
+        if speaker != Some(role) {
+            if !transcript.is_empty() {
+                transcript.push_str("\n\n");
+            }
+            transcript.push_str(role);
+            transcript.push_str(": ");
+            speaker = Some(role);
+        } else if role == "User" {
+            // Each block of a user message is a separate chunk
+            transcript.push('\n');
+        }
+        transcript.push_str(&text.text);
+    }
+    (!transcript.is_empty()).then_some(transcript)
+}
+
+/// Content blocks of the user message starting at `start`
+pub fn user_prompt_at(messages: &[PersistedMessage], start: usize) -> Vec<ContentBlock> {
+    messages
+        .get(start..)
+        .unwrap_or_default()
+        .iter()
+        .map_while(|message| match &message.update {
+            SessionUpdate::UserMessageChunk(chunk) => Some(chunk.content.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Type of chunk being accumulated
 #[derive(Debug, Clone, PartialEq)]
 enum AccumulatedChunkType {
@@ -315,9 +410,13 @@ fn merge_text_chunks(chunks: &[ContentChunk]) -> ContentChunk {
         let text_block = ContentBlock::Text(TextContent::new(merged_text));
         let mut merged_chunk = ContentChunk::new(text_block);
 
-        // Preserve meta from first chunk if any
-        if let Some(meta) = &chunks[0].meta {
-            merged_chunk.meta = Some(meta.clone());
+        // Preserve the meta of every chunk, e.g. usage reported on the last
+        // one; later chunks win
+        for meta in chunks.iter().filter_map(|chunk| chunk.meta.as_ref()) {
+            merged_chunk
+                .meta
+                .get_or_insert_with(Default::default)
+                .extend(meta.clone());
         }
 
         return merged_chunk;
@@ -338,6 +437,12 @@ fn extract_text_from_content_chunk(chunk: &ContentChunk) -> String {
     }
 }
 
+/// Whether a chunk has no text and only exists to carry `_meta`
+fn is_meta_only_chunk(chunk: &ContentChunk) -> bool {
+    chunk.meta.is_some()
+        && matches!(&chunk.content, ContentBlock::Text(text) if text.text.is_empty())
+}
+
 /// Message persistence service
 pub struct PersistenceService {
     /// Base directory for session files
@@ -388,6 +493,17 @@ impl PersistenceService {
                 .or_insert_with(ChunkAccumulator::new);
 
             match update {
+                SessionUpdate::AgentMessageChunk(chunk)
+                    if is_meta_only_chunk(&chunk)
+                        && accumulator.chunk_type != AccumulatedChunkType::AgentMessage =>
+                {
+                    // Usage reported after a tool call has no reply to merge into
+                    log::debug!(
+                        "Skipping meta-only AgentMessageChunk for session: {}",
+                        session_id
+                    );
+                    None
+                }
                 SessionUpdate::AgentMessageChunk(chunk) => {
                     log::debug!("Accumulating AgentMessageChunk for session: {}", session_id);
                     accumulator.try_append_agent_message_chunk(chunk)
@@ -619,6 +735,48 @@ impl PersistenceService {
         .await
     }
 
+    /// Keep only the first `len` messages of a session's history
+    ///
+    /// Flushes pending chunks first so they aren't appended after the rewrite
+    pub async fn truncate_messages(&self, session_id: &str, len: usize) -> Result<()> {
+        self.flush_session(session_id).await?;
+
+        let mut messages = self.load_messages(session_id).await?;
+        if messages.len() <= len {
+            return Ok(());
+        }
+        messages.truncate(len);
+        self.write_messages(session_id, messages).await
+    }
+
+    /// Replace a session's history with `messages`
+    pub async fn write_messages(
+        &self,
+        session_id: &str,
+        messages: Vec<PersistedMessage>,
+    ) -> Result<()> {
+        self.ensure_base_dir_sync()?;
+
+        let file_path = self.session_file_path(session_id);
+        smol::unblock(move || {
+            let mut contents = String::new();
+            for message in &messages {
+                let json = serde_json::to_string(message).context("Failed to serialize message")?;
+                contents.push_str(&json);
+                contents.push('\n');
+            }
+            std::fs::write(&file_path, contents).context("Failed to rewrite session file")?;
+
+            log::info!(
+                "Wrote {} messages to session file: {}",
+                messages.len(),
+                file_path.display()
+            );
+            Ok(())
+        })
+        .await
+    }
+
     /// Delete a session's history file
     ///
     /// Flushes any pending chunks before deleting
@@ -645,6 +803,71 @@ impl PersistenceService {
         .await
     }
 
+    /// Write a session's full history to `path` as a portable JSON file
+    pub async fn export_session(&self, session_id: &str, path: &Path) -> Result<()> {
+        self.flush_session(session_id).await?;
+
+        let export = SessionExport {
+            version: SESSION_EXPORT_VERSION,
+            session_id: session_id.to_string(),
+            exported_at: Utc::now().to_rfc3339(),
+            messages: self.load_messages(session_id).await?,
+        };
+        let path = path.to_path_buf();
+        smol::unblock(move || {
+            let json =
+                serde_json::to_string_pretty(&export).context("Failed to serialize session")?;
+            std::fs::write(&path, json)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+
+            log::info!(
+                "Exported {} messages of session {} to {}",
+                export.messages.len(),
+                export.session_id,
+                path.display()
+            );
+            Ok(())
+        })
+        .await
+    }
+
+    /// Import a session exported with [`Self::export_session`] under a new
+    /// session id, which is returned
+    pub async fn import_session(&self, path: &Path) -> Result<String> {
+        let path = path.to_path_buf();
+        let export = smol::unblock(move || {
+            let json = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str::<SessionExport>(&json)
+                .with_context(|| format!("{} is not a session export", path.display()))
+        })
+        .await?;
+        if export.version > SESSION_EXPORT_VERSION {
+            anyhow::bail!(
+                "Session export version {} is newer than supported version {}",
+                export.version,
+                SESSION_EXPORT_VERSION
+            );
+        }
+
+        self.ensure_base_dir_sync()?;
+        let session_id = format!("{}{}", IMPORTED_SESSION_PREFIX, uuid::Uuid::new_v4());
+        let file_path = self.session_file_path(&session_id);
+        smol::unblock(move || {
+            let mut contents = String::new();
+            for message in &export.messages {
+                let json = serde_json::to_string(message).context("Failed to serialize message")?;
+                contents.push_str(&json);
+                contents.push('\n');
+            }
+            std::fs::write(&file_path, contents).context("Failed to write session file")
+        })
+        .await?;
+
+        log::info!("Imported session {} as {}", export.session_id, session_id);
+        Ok(session_id)
+    }
+
     /// List all available sessions
     pub async fn list_workspace_sessions(&self) -> Result<Vec<String>> {
         let base_dir = self.base_dir.clone();
@@ -677,3 +900,225 @@ impl PersistenceService {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::ToolCall;
+
+    fn text(text: &str) -> ContentChunk {
+        ContentChunk::new(ContentBlock::from(text.to_string()))
+    }
+
+    /// Two turns; the second prompt carries a code selection as a separate block
+    fn multi_turn_session() -> Vec<SessionUpdate> {
+        vec![
+            SessionUpdate::UserMessageChunk(text("Hello")),
+            SessionUpdate::AgentMessageChunk(text("Hi, ")),
+            SessionUpdate::AgentMessageChunk(text("how can I help?")),
+            SessionUpdate::UserMessageChunk(text("```rust\nfn main() {}\n```")),
+            SessionUpdate::UserMessageChunk(text("Why does this not print?")),
+            SessionUpdate::AgentThoughtChunk(text("It has no body.")),
+            SessionUpdate::ToolCall(ToolCall::new("tc-1", "Read main.rs")),
+            SessionUpdate::AgentMessageChunk(text("`main` is empty.")),
+        ]
+    }
+
+    fn texts(blocks: &[ContentBlock]) -> Vec<String> {
+        blocks
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text(text) => Some(text.text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn usage_only_chunk_is_stored_with_the_reply() {
+        let dir = std::env::temp_dir().join(format!("agentx-usage-{}", std::process::id()));
+        let service = PersistenceService::new(dir.clone());
+        let usage = || {
+            let mut chunk = text("");
+            chunk.meta = Some(
+                [(
+                    "usage".to_string(),
+                    serde_json::json!({ "inputTokens": 12 }),
+                )]
+                .into_iter()
+                .collect(),
+            );
+            SessionUpdate::AgentMessageChunk(chunk)
+        };
+
+        smol::block_on(async {
+            for update in multi_turn_session() {
+                service.save_update("session", update).await.unwrap();
+            }
+            service.save_update("session", usage()).await.unwrap();
+            service
+                .save_update(
+                    "session",
+                    SessionUpdate::ToolCall(ToolCall::new("tc-2", "Run tests")),
+                )
+                .await
+                .unwrap();
+            service.save_update("session", usage()).await.unwrap();
+            service.flush_session("session").await.unwrap();
+
+            let messages = service.load_messages("session").await.unwrap();
+            assert_eq!(messages.len(), 8);
+            let SessionUpdate::AgentMessageChunk(reply) = &messages[6].update else {
+                panic!("expected the last reply before the tool call");
+            };
+            assert_eq!(
+                texts(std::slice::from_ref(&reply.content)),
+                ["`main` is empty."]
+            );
+            assert!(reply.meta.as_ref().unwrap().contains_key("usage"));
+            assert!(matches!(messages[7].update, SessionUpdate::ToolCall(_)));
+        });
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn exported_session_imports_with_the_same_history() {
+        let dir = std::env::temp_dir().join(format!("agentx-export-{}", std::process::id()));
+        let service = PersistenceService::new(dir.join("sessions"));
+        let export_path = dir.join("session.json");
+
+        smol::block_on(async {
+            for update in multi_turn_session() {
+                service.save_update("original", update).await.unwrap();
+            }
+            service
+                .export_session("original", &export_path)
+                .await
+                .unwrap();
+
+            let session_id = service.import_session(&export_path).await.unwrap();
+            assert_ne!(session_id, "original");
+            assert!(is_imported_session(&session_id));
+            assert!(!is_imported_session("original"));
+
+            let original = service.load_messages("original").await.unwrap();
+            let imported = service.load_messages(&session_id).await.unwrap();
+            assert_eq!(imported.len(), original.len());
+            for (imported, original) in imported.iter().zip(&original) {
+                assert_eq!(imported.timestamp, original.timestamp);
+                assert_eq!(
+                    serde_json::to_value(&imported.update).unwrap(),
+                    serde_json::to_value(&original.update).unwrap()
+                );
+            }
+        });
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn regenerate_drops_last_agent_turn_and_resends_prompt() {
+        let dir = std::env::temp_dir().join(format!("agentx-persistence-{}", std::process::id()));
+        let service = PersistenceService::new(dir.clone());
+
+        smol::block_on(async {
+            for update in multi_turn_session() {
+                service.save_update("session", update).await.unwrap();
+            }
+            service.flush_session("session").await.unwrap();
+
+            let messages = service.load_messages("session").await.unwrap();
+            let start = last_turn_start(&messages).unwrap();
+            let prompt = user_prompt_at(&messages, start);
+            assert_eq!(start, 2);
+            assert_eq!(
+                texts(&prompt),
+                ["```rust\nfn main() {}\n```", "Why does this not print?"]
+            );
+
+            service.truncate_messages("session", start).await.unwrap();
+            for block in prompt.clone() {
+                let update = SessionUpdate::UserMessageChunk(ContentChunk::new(block));
+                service.save_update("session", update).await.unwrap();
+            }
+
+            let messages = service.load_messages("session").await.unwrap();
+            assert_eq!(messages.len(), 4);
+            assert!(matches!(
+                messages[1].update,
+                SessionUpdate::AgentMessageChunk(_)
+            ));
+            assert_eq!(last_turn_start(&messages), Some(2));
+            assert_eq!(texts(&user_prompt_at(&messages, 2)), texts(&prompt));
+        });
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn editing_a_turn_truncates_and_resends_it() {
+        let dir = std::env::temp_dir().join(format!("agentx-edit-{}", std::process::id()));
+        let service = PersistenceService::new(dir.clone());
+
+        smol::block_on(async {
+            for update in multi_turn_session() {
+                service.save_update("session", update).await.unwrap();
+            }
+            for update in [
+                SessionUpdate::UserMessageChunk(text("Thanks")),
+                SessionUpdate::AgentMessageChunk(text("You're welcome.")),
+            ] {
+                service.save_update("session", update).await.unwrap();
+            }
+            service.flush_session("session").await.unwrap();
+
+            let messages = service.load_messages("session").await.unwrap();
+            assert_eq!(turn_starts(&messages), [0, 2, 7]);
+
+            // Edit the second of three turns
+            let start = turn_starts(&messages)[1];
+            service.truncate_messages("session", start).await.unwrap();
+            let edited = SessionUpdate::UserMessageChunk(text("Why is nothing printed?"));
+            service.save_update("session", edited).await.unwrap();
+
+            let messages = service.load_messages("session").await.unwrap();
+            assert_eq!(turn_starts(&messages), [0, 2]);
+            assert_eq!(messages.len(), 3);
+            assert_eq!(texts(&user_prompt_at(&messages, 0)), ["Hello"]);
+            assert_eq!(
+                texts(&user_prompt_at(&messages, 2)),
+                ["Why is nothing printed?"]
+            );
+        });
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn history_without_user_message_has_no_turn() {
+        let messages = vec![PersistedMessage::new(SessionUpdate::AgentMessageChunk(
+            text("Ready"),
+        ))];
+
+        assert_eq!(last_turn_start(&messages), None);
+        assert!(user_prompt_at(&messages, 5).is_empty());
+    }
+
+    #[test]
+    fn transcript_keeps_the_text_of_each_turn() {
+        let messages: Vec<_> = multi_turn_session()
+            .into_iter()
+            .map(PersistedMessage::new)
+            .collect();
+
+        assert_eq!(
+            history_transcript(&messages).unwrap(),
+            "User: Hello\n\n\
+             Assistant: Hi, how can I help?\n\n\
+             User: ```rust\nfn main() {}\n```\nWhy does this not print?\n\n\
+             Assistant: `main` is empty."
+        );
+        assert_eq!(history_transcript(&[]), None);
+    }
+}
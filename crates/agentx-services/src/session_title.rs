@@ -0,0 +1,269 @@
+//! Session Titles
+//!
+//! Names a task after the first turn of its session. Tasks start out named
+//! after the raw first message; once the agent has replied, the active AI
+//! model is asked for a short title summarizing the message and the reply.
+//! Without a model, or when generation fails, the first message is tidied up
+//! and truncated instead.
+
+use agent_client_protocol::{ContentBlock, SessionUpdate};
+use agentx_event_bus::{EventHub, SubscriptionId};
+use anyhow::Result;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::{AiService, WorkspaceService};
+
+/// Longest title, in characters, derived from the first message
+pub const MAX_FALLBACK_TITLE_CHARS: usize = 48;
+
+/// Title derived from the first user message: its first non-empty line with
+/// runs of whitespace collapsed, truncated to [`MAX_FALLBACK_TITLE_CHARS`]
+pub fn fallback_title(first_message: &str) -> Option<String> {
+    let line = first_message
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .find(|line| !line.is_empty())?;
+
+    if line.chars().count() <= MAX_FALLBACK_TITLE_CHARS {
+        return Some(line);
+    }
+    let truncated: String = line.chars().take(MAX_FALLBACK_TITLE_CHARS - 1).collect();
+    Some(format!("{}…", truncated.trim_end()))
+}
+
+/// Longest part of the agent's reply, in characters, given to the model
+const MAX_TITLE_REPLY_CHARS: usize = 2000;
+
+/// Conversation of a first turn, as given to the model to title it
+fn first_turn_transcript(first_message: &str, first_reply: &str) -> String {
+    let first_reply = first_reply.trim();
+    if first_reply.is_empty() {
+        return first_message.to_string();
+    }
+    let first_reply: String = first_reply.chars().take(MAX_TITLE_REPLY_CHARS).collect();
+    format!(
+        "User: {}\n\nAssistant: {}",
+        first_message.trim(),
+        first_reply
+    )
+}
+
+/// Text the agent replies with in a session, collected from the session bus
+/// until [`ReplyCollector::finish`] is called
+pub struct ReplyCollector {
+    event_hub: EventHub,
+    subscription: SubscriptionId,
+    reply: Arc<Mutex<String>>,
+}
+
+impl ReplyCollector {
+    /// Start collecting; call this before sending the prompt
+    pub fn start(event_hub: &EventHub, session_id: &str) -> Self {
+        let reply = Arc::new(Mutex::new(String::new()));
+        let subscription =
+            event_hub.subscribe_session_updates_for_session(session_id.to_string(), {
+                let reply = reply.clone();
+                move |event| {
+                    if let SessionUpdate::AgentMessageChunk(chunk) = event.update.as_ref()
+                        && let ContentBlock::Text(text) = &chunk.content
+                    {
+                        reply.lock().unwrap().push_str(&text.text);
+                    }
+                }
+            });
+        Self {
+            event_hub: event_hub.clone(),
+            subscription,
+            reply,
+        }
+    }
+
+    /// Stop collecting and return the reply received so far
+    pub fn finish(self) -> String {
+        std::mem::take(&mut *self.reply.lock().unwrap())
+    }
+}
+
+impl Drop for ReplyCollector {
+    fn drop(&mut self) {
+        self.event_hub.unsubscribe(self.subscription);
+    }
+}
+
+/// Titles each session once, after its first turn
+pub struct SessionTitler {
+    workspace_service: Arc<WorkspaceService>,
+    /// Sessions already titled, or being titled right now
+    titled: Mutex<HashSet<String>>,
+}
+
+impl SessionTitler {
+    pub fn new(workspace_service: Arc<WorkspaceService>) -> Self {
+        Self {
+            workspace_service,
+            titled: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Title the session's task from its first turn and store it with
+    /// [`WorkspaceService::rename_session`]. The title is generated from the
+    /// first user message and the agent's reply with `ai_service` when given,
+    /// otherwise derived from the message.
+    ///
+    /// Only the first call for a session does anything; later calls return
+    /// `Ok(None)`, as does a message no title can be derived from.
+    pub async fn title_session(
+        &self,
+        session_id: &str,
+        first_message: &str,
+        first_reply: &str,
+        ai_service: Option<&AiService>,
+    ) -> Result<Option<String>> {
+        if !self.titled.lock().unwrap().insert(session_id.to_string()) {
+            return Ok(None);
+        }
+
+        let Some(title) = Self::title_for(first_message, first_reply, ai_service).await else {
+            return Ok(None);
+        };
+        self.workspace_service
+            .rename_session(session_id, title.clone())
+            .await?;
+        log::info!("[SessionTitler] Titled session {}: {}", session_id, title);
+        Ok(Some(title))
+    }
+
+    async fn title_for(
+        first_message: &str,
+        first_reply: &str,
+        ai_service: Option<&AiService>,
+    ) -> Option<String> {
+        if let Some(ai_service) = ai_service {
+            let conversation = first_turn_transcript(first_message, first_reply);
+            match ai_service.generate_title(&conversation).await {
+                Ok(title) => return Some(title),
+                Err(e) => log::warn!("[SessionTitler] Falling back to the first message: {:#}", e),
+            }
+        }
+        fallback_title(first_message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use agentx_types::schemas::workspace::{Workspace, WorkspaceConfig, WorkspaceTask};
+
+    use super::*;
+
+    #[test]
+    fn fallback_title_is_the_tidied_first_line() {
+        assert_eq!(
+            fallback_title("\n  Fix the   login\tbug \nIt fails on Safari").as_deref(),
+            Some("Fix the login bug")
+        );
+        assert_eq!(fallback_title(" \n\t\n"), None);
+
+        let long = "Refactor the settings panel so that every page loads lazily";
+        let title = fallback_title(long).unwrap();
+        assert_eq!(title, "Refactor the settings panel so that every page…");
+
+        // Truncation counts characters, not bytes
+        let title = fallback_title(&"标题".repeat(30)).unwrap();
+        assert_eq!(title.chars().count(), MAX_FALLBACK_TITLE_CHARS);
+        assert!(title.ends_with('…'));
+    }
+
+    #[test]
+    fn first_turn_transcript_includes_the_reply() {
+        assert_eq!(
+            first_turn_transcript("Add dark mode\n", "  Done: added a theme toggle. "),
+            "User: Add dark mode\n\nAssistant: Done: added a theme toggle."
+        );
+        assert_eq!(
+            first_turn_transcript("Add dark mode", " \n"),
+            "Add dark mode"
+        );
+
+        let long_reply = "a".repeat(MAX_TITLE_REPLY_CHARS + 10);
+        let transcript = first_turn_transcript("Hi", &long_reply);
+        assert!(transcript.ends_with(&"a".repeat(MAX_TITLE_REPLY_CHARS)));
+        assert!(!transcript.ends_with(&long_reply));
+    }
+
+    #[test]
+    fn reply_collector_keeps_agent_text_until_finished() {
+        use agent_client_protocol::ContentChunk;
+        use agentx_event_bus::SessionUpdateEvent;
+
+        let event_hub = EventHub::new();
+        let publish = |session_id: &str, update: SessionUpdate| {
+            event_hub.publish_session_update(SessionUpdateEvent {
+                session_id: session_id.to_string(),
+                agent_name: None,
+                update: Arc::new(update),
+            })
+        };
+        let chunk = |text: &str| ContentChunk::new(ContentBlock::from(text.to_string()));
+
+        let collector = ReplyCollector::start(&event_hub, "session-1");
+        publish("session-1", SessionUpdate::UserMessageChunk(chunk("Hi")));
+        publish(
+            "session-1",
+            SessionUpdate::AgentMessageChunk(chunk("Hello, ")),
+        );
+        publish(
+            "session-2",
+            SessionUpdate::AgentMessageChunk(chunk("other")),
+        );
+        publish(
+            "session-1",
+            SessionUpdate::AgentMessageChunk(chunk("there")),
+        );
+        assert_eq!(collector.finish(), "Hello, there");
+
+        // Finishing unsubscribes
+        assert_eq!(event_hub.stats().active_subscriptions, 0);
+    }
+
+    #[test]
+    fn sessions_are_titled_from_the_first_message_once() {
+        let dir = std::env::temp_dir().join(format!("agentx-session-title-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("workspace.json");
+
+        let workspace = Workspace::new(dir.clone());
+        let first_message = "Add dark mode to the settings page\n\nUse the theme registry.";
+        let mut task = WorkspaceTask::new(
+            workspace.id.clone(),
+            first_message.to_string(),
+            "agent".to_string(),
+            "Auto".to_string(),
+        );
+        task.set_session("session-1".to_string());
+        let mut config = WorkspaceConfig::default();
+        config.add_workspace(workspace);
+        config.add_task(task.clone());
+        std::fs::write(&path, serde_json::to_string(&config).unwrap()).unwrap();
+
+        let workspace_service = Arc::new(WorkspaceService::new(path));
+        let titler = SessionTitler::new(workspace_service.clone());
+
+        // No AI service: generation is disabled
+        let title = smol::block_on(titler.title_session("session-1", first_message, "", None));
+        assert_eq!(
+            title.unwrap().as_deref(),
+            Some("Add dark mode to the settings page")
+        );
+        let renamed = smol::block_on(workspace_service.get_task(&task.id)).unwrap();
+        assert_eq!(renamed.name, "Add dark mode to the settings page");
+
+        // A second turn leaves the title alone
+        let again = smol::block_on(titler.title_session("session-1", "Now light mode", "", None));
+        assert_eq!(again.unwrap(), None);
+        let renamed = smol::block_on(workspace_service.get_task(&task.id)).unwrap();
+        assert_eq!(renamed.name, "Add dark mode to the settings page");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
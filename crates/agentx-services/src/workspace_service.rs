@@ -55,9 +55,18 @@ impl WorkspaceService {
 
         let content = std::fs::read_to_string(path).context("Failed to read workspace config")?;
 
-        let config: WorkspaceConfig =
+        let mut config: WorkspaceConfig =
             serde_json::from_str(&content).context("Failed to parse workspace config")?;
 
+        // A turn still marked as running was cut off when the app last exited
+        let interrupted = config.mark_interrupted_tasks();
+        if interrupted > 0 {
+            log::info!(
+                "[WorkspaceService] Marked {} task(s) left running as interrupted",
+                interrupted
+            );
+        }
+
         Ok(config)
     }
 
@@ -141,6 +150,15 @@ impl WorkspaceService {
         config.workspaces.clone()
     }
 
+    /// List the most recently opened workspaces, newest first
+    pub async fn recent_workspaces(&self, limit: usize) -> Vec<Workspace> {
+        let config = self.config.read().await;
+        let mut workspaces = config.workspaces.clone();
+        workspaces.sort_by_key(|w| std::cmp::Reverse(w.last_accessed));
+        workspaces.truncate(limit);
+        workspaces
+    }
+
     /// Get the entire workspace configuration
     pub async fn get_config(&self) -> WorkspaceConfig {
         let config = self.config.read().await;
@@ -222,6 +240,44 @@ impl WorkspaceService {
         Ok(task_clone)
     }
 
+    /// Add a finished task for an imported session, so it can be replayed
+    /// from the task list
+    pub async fn import_task(
+        &self,
+        workspace_id: &str,
+        name: String,
+        agent_name: String,
+        session_id: String,
+    ) -> Result<WorkspaceTask> {
+        let mut task =
+            WorkspaceTask::new(workspace_id.to_string(), name, agent_name, String::new());
+        task.set_session(session_id);
+        task.status = SessionStatus::Completed;
+        let task_clone = task.clone();
+
+        {
+            let mut config = self.config.write().await;
+            if config.get_workspace(workspace_id).is_none() {
+                anyhow::bail!("Workspace not found: {}", workspace_id);
+            }
+            config.add_task(task);
+        }
+
+        self.save_config().await?;
+
+        self.publish_event(WorkspaceUpdateEvent::TaskCreated {
+            workspace_id: workspace_id.to_string(),
+            task_id: task_clone.id.clone(),
+        });
+
+        log::info!(
+            "Imported task '{}' into workspace {}",
+            task_clone.name,
+            workspace_id
+        );
+        Ok(task_clone)
+    }
+
     /// Associate a session with a task
     pub async fn set_task_session(&self, task_id: &str, session_id: String) -> Result<()> {
         {
@@ -237,6 +293,9 @@ impl WorkspaceService {
         }
 
         self.save_config().await?;
+        self.publish_event(WorkspaceUpdateEvent::TaskUpdated {
+            task_id: task_id.to_string(),
+        });
 
         Ok(())
     }
@@ -263,6 +322,7 @@ impl WorkspaceService {
                 .context("Task not found")?;
 
             task.status = status;
+            task.last_active_at = Some(chrono::Utc::now());
         }
 
         self.save_config().await?;
@@ -286,6 +346,41 @@ impl WorkspaceService {
         Ok(())
     }
 
+    /// Rename the task a session belongs to
+    pub async fn rename_session(&self, session_id: &str, title: String) -> Result<()> {
+        let task_id = {
+            let mut config = self.config.write().await;
+
+            let task = config
+                .find_task_by_session(session_id)
+                .context("Task not found for session")?;
+
+            task.name = title;
+            task.id.clone()
+        };
+
+        self.save_config().await?;
+
+        self.publish_event(WorkspaceUpdateEvent::TaskUpdated { task_id });
+
+        Ok(())
+    }
+
+    /// Save the model chosen for a session on its task
+    pub async fn set_session_model(&self, session_id: &str, model_id: &str) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+
+            let task = config
+                .find_task_by_session(session_id)
+                .context("Task not found for session")?;
+
+            task.selected_model = Some(model_id.to_string());
+        }
+
+        self.save_config().await
+    }
+
     /// Get a task by its session ID
     pub async fn get_task_by_session(&self, session_id: &str) -> Option<WorkspaceTask> {
         let config = self.config.read().await;
@@ -330,3 +425,71 @@ impl WorkspaceService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn running_tasks_are_marked_interrupted_on_load() {
+        let dir = std::env::temp_dir().join(format!("agentx-workspace-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("workspace.json");
+
+        let workspace = Workspace::new(dir.clone());
+        let mut running = WorkspaceTask::new(
+            workspace.id.clone(),
+            "running".to_string(),
+            "agent".to_string(),
+            "Auto".to_string(),
+        );
+        running.set_session("session-1".to_string());
+        let mut done = running.clone();
+        done.id = "done".to_string();
+        done.status = SessionStatus::Completed;
+
+        let mut config = WorkspaceConfig::default();
+        config.add_task(running.clone());
+        config.add_task(done);
+        config.add_workspace(workspace.clone());
+        std::fs::write(&path, serde_json::to_string(&config).unwrap()).unwrap();
+
+        let service = WorkspaceService::new(path);
+        let tasks = smol::block_on(service.get_workspace_tasks(&workspace.id));
+        let status = |id: &str| tasks.iter().find(|t| t.id == id).unwrap().status.clone();
+        assert_eq!(status(&running.id), SessionStatus::Interrupted);
+        assert_eq!(status("done"), SessionStatus::Completed);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn session_model_is_saved_on_the_task() {
+        let dir =
+            std::env::temp_dir().join(format!("agentx-workspace-model-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("workspace.json");
+
+        let mut task = WorkspaceTask::new(
+            "workspace-1".to_string(),
+            "task".to_string(),
+            "agent".to_string(),
+            "Auto".to_string(),
+        );
+        task.set_session("session-1".to_string());
+        let mut config = WorkspaceConfig::default();
+        config.add_task(task);
+        std::fs::write(&path, serde_json::to_string(&config).unwrap()).unwrap();
+
+        let service = WorkspaceService::new(path.clone());
+        smol::block_on(service.set_session_model("session-1", "fast-model")).unwrap();
+        assert!(smol::block_on(service.set_session_model("missing", "fast-model")).is_err());
+
+        // A fresh service reads the choice back from disk
+        let service = WorkspaceService::new(path);
+        let task = smol::block_on(service.get_task_by_session("session-1")).unwrap();
+        assert_eq!(task.selected_model.as_deref(), Some("fast-model"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
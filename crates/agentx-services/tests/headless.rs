@@ -0,0 +1,64 @@
+//! Drives a scripted agent through one headless turn
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+use agentx_agent::{AgentHandle, AgentManager, PermissionStore};
+use agentx_event_bus::EventHub;
+use agentx_services::{AgentService, headless};
+
+/// Collects everything written by the headless run
+#[derive(Clone, Default)]
+struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn headless_turn_prints_the_reply_and_exits_cleanly() {
+    let event_hub = EventHub::new();
+    let permission_store = Arc::new(PermissionStore::default());
+    let manager = Arc::new(AgentManager::new(
+        HashMap::new(),
+        permission_store.clone(),
+        event_hub.clone(),
+        Default::default(),
+    ));
+    let mut agent_service = AgentService::new(manager.clone());
+    agent_service.set_event_hub(event_hub.clone());
+
+    let output = Output::default();
+    let result = smol::block_on(async {
+        manager
+            .insert_handle(AgentHandle::scripted(
+                "mock",
+                event_hub.clone(),
+                vec!["Hello, ".to_string(), "world".to_string()],
+            ))
+            .await;
+
+        headless::run_turn(
+            &agent_service,
+            &event_hub,
+            permission_store,
+            "mock",
+            "Say hello",
+            output.clone(),
+        )
+        .await
+    });
+
+    assert_eq!(headless::exit_code(&result), 0);
+    let printed = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(printed, "Hello, world\n");
+}
@@ -0,0 +1,53 @@
+use std::time::{Duration, SystemTime};
+
+/// Lifecycle of a spawned agent process, as seen by the ACP client
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum AgentRuntimeStatus {
+    /// The process is launching or the ACP `initialize` handshake is pending
+    #[default]
+    Starting,
+    /// The handshake completed and the agent accepts requests
+    Ready,
+    /// The agent could not start or its process died
+    Failed { reason: String },
+    /// The agent was shut down
+    Stopped,
+}
+
+impl AgentRuntimeStatus {
+    /// Whether the agent can no longer change status
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Failed { .. } | Self::Stopped)
+    }
+
+    /// Whether moving to `next` is a valid step. `Failed` and `Stopped` are
+    /// final, so a restart spawns a new handle instead.
+    pub fn can_transition_to(&self, next: &Self) -> bool {
+        matches!(
+            (self, next),
+            (
+                Self::Starting,
+                Self::Ready | Self::Failed { .. } | Self::Stopped
+            ) | (Self::Ready, Self::Failed { .. } | Self::Stopped)
+        )
+    }
+}
+
+/// Live state of an agent managed by the `AgentManager`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AgentRuntimeInfo {
+    pub name: String,
+    pub status: AgentRuntimeStatus,
+    /// Process id while the agent process is running
+    pub pid: Option<u32>,
+    /// When the agent process was spawned
+    pub started_at: Option<SystemTime>,
+}
+
+impl AgentRuntimeInfo {
+    /// Time since the agent process was spawned, while it is running
+    pub fn uptime(&self) -> Option<Duration> {
+        self.pid?;
+        SystemTime::now().duration_since(self.started_at?).ok()
+    }
+}
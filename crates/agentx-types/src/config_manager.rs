@@ -1,6 +1,10 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Map, Value};
+use std::fmt;
 use std::path::{Path, PathBuf};
 
+use crate::config::{CURRENT_CONFIG_SCHEMA_VERSION, Config};
+
 /// Get the user data directory for AgentX
 /// - macOS: ~/.agentx/
 /// - Windows: %APPDATA%\agentx\
@@ -112,3 +116,508 @@ pub fn get_docks_layout_path() -> PathBuf {
 pub fn get_sessions_dir() -> PathBuf {
     user_data_dir_or_temp().join("sessions")
 }
+
+/// Upgrades a config document from one schema version to the next
+type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// Ordered migrations; entry `n` upgrades a version `n` document to `n + 1`
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
+
+/// Parse a config file's contents, upgrading older documents to the current shape
+pub fn parse_config(raw: &str) -> Result<Config> {
+    let value = serde_json::from_str(raw).context("Config is not valid JSON")?;
+    if let Err(errors) = validate_raw(&value) {
+        let details = errors
+            .iter()
+            .map(|error| format!("  {}", error))
+            .collect::<Vec<_>>()
+            .join("\n");
+        bail!("Config has {} problem(s):\n{}", errors.len(), details);
+    }
+    migrate(value)
+}
+
+/// A problem found by [`validate_raw`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Dotted path to the offending value, e.g. `agent_servers.claude.command`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Expected JSON shape of a config field
+#[derive(Clone, Copy)]
+enum FieldKind {
+    String,
+    Bool,
+    /// Non-negative integer
+    Count,
+    StringList,
+    StringMap,
+}
+
+impl FieldKind {
+    fn describe(self) -> &'static str {
+        match self {
+            FieldKind::String => "string",
+            FieldKind::Bool => "boolean",
+            FieldKind::Count => "non-negative integer",
+            FieldKind::StringList => "array of strings",
+            FieldKind::StringMap => "object of strings",
+        }
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// Collects [`ValidationError`]s while walking a config document
+struct Validator {
+    errors: Vec<ValidationError>,
+}
+
+impl Validator {
+    fn error(&mut self, path: String, message: impl Into<String>) {
+        self.errors.push(ValidationError {
+            path,
+            message: message.into(),
+        });
+    }
+
+    fn expected(&mut self, path: String, expected: &str, found: &Value) {
+        self.error(
+            path,
+            format!("expected {}, found {}", expected, json_type(found)),
+        );
+    }
+
+    /// Check `object[key]` has the given shape; a missing key is only an
+    /// error when `required`
+    fn field(
+        &mut self,
+        object: &Map<String, Value>,
+        parent: &str,
+        key: &str,
+        kind: FieldKind,
+        required: bool,
+    ) {
+        let path = join_path(parent, key);
+        let Some(value) = object.get(key) else {
+            if required {
+                self.error(path, "missing");
+            }
+            return;
+        };
+
+        match (kind, value) {
+            (FieldKind::String, Value::String(_)) | (FieldKind::Bool, Value::Bool(_)) => {}
+            (FieldKind::Count, Value::Number(number)) if number.as_u64().is_some() => {}
+            (FieldKind::StringList, Value::Array(items)) => {
+                for (index, item) in items.iter().enumerate() {
+                    if !item.is_string() {
+                        self.expected(join_path(&path, &index.to_string()), "string", item);
+                    }
+                }
+            }
+            (FieldKind::StringMap, Value::Object(entries)) => {
+                for (name, item) in entries {
+                    if !item.is_string() {
+                        self.expected(join_path(&path, name), "string", item);
+                    }
+                }
+            }
+            _ => self.expected(path, kind.describe(), value),
+        }
+    }
+
+    /// Check a map of named entries, calling `check` on each object entry
+    fn entries(
+        &mut self,
+        document: &Map<String, Value>,
+        key: &str,
+        required: bool,
+        mut check: impl FnMut(&mut Self, &str, &Value),
+    ) {
+        let Some(value) = document.get(key) else {
+            if required {
+                self.error(key.to_string(), "missing");
+            }
+            return;
+        };
+        let Some(entries) = value.as_object() else {
+            self.expected(key.to_string(), "object", value);
+            return;
+        };
+        for (name, entry) in entries {
+            check(self, &join_path(key, name), entry);
+        }
+    }
+
+    /// Require `value` to be an object and return it
+    fn object<'a>(&mut self, path: &str, value: &'a Value) -> Option<&'a Map<String, Value>> {
+        let object = value.as_object();
+        if object.is_none() {
+            self.expected(path.to_string(), "object", value);
+        }
+        object
+    }
+}
+
+/// Check the shape of a raw config document, reporting every problem with a
+/// path-qualified message (e.g. `agent_servers.claude.command: missing`).
+///
+/// Accepts both the current layout and documents that still need
+/// [`migrate`]; unknown keys are ignored.
+pub fn validate_raw(value: &Value) -> Result<(), Vec<ValidationError>> {
+    let mut v = Validator { errors: Vec::new() };
+    let Some(document) = v.object("", value) else {
+        return Err(v.errors);
+    };
+
+    v.field(document, "", "schema_version", FieldKind::Count, false);
+    v.field(document, "", "upload_dir", FieldKind::String, false);
+    v.field(
+        document,
+        "",
+        "tool_call_preview_max_lines",
+        FieldKind::Count,
+        false,
+    );
+    v.field(document, "", "system_prompts", FieldKind::StringMap, false);
+    v.field(document, "", "keybindings", FieldKind::StringMap, false);
+    v.field(document, "", "agent_icons", FieldKind::StringMap, false);
+
+    v.entries(document, "agent_servers", true, |v, path, entry| {
+        if let Some(agent) = v.object(path, entry) {
+            v.field(agent, path, "enabled", FieldKind::Bool, false);
+            v.field(agent, path, "command", FieldKind::String, true);
+            v.field(agent, path, "args", FieldKind::StringList, false);
+            v.field(agent, path, "env", FieldKind::StringMap, false);
+        }
+    });
+
+    v.entries(document, "models", false, |v, path, entry| {
+        if let Some(model) = v.object(path, entry) {
+            v.field(model, path, "enabled", FieldKind::Bool, true);
+            for key in ["provider", "base_url", "api_key", "model_name"] {
+                v.field(model, path, key, FieldKind::String, true);
+            }
+            for key in ["max_concurrent_requests", "requests_per_minute"] {
+                v.field(model, path, key, FieldKind::Count, false);
+            }
+        }
+    });
+
+    // v0 documents use `mcpServers`, may hold `_comment` strings and may
+    // give a server as a single command line
+    for key in ["mcp_servers", "mcpServers"] {
+        v.entries(document, key, false, |v, path, entry| {
+            if path
+                .rsplit('.')
+                .next()
+                .is_some_and(|name| name.starts_with("_comment"))
+                || entry.is_string()
+            {
+                return;
+            }
+            if let Some(server) = v.object(path, entry) {
+                v.field(server, path, "enabled", FieldKind::Bool, false);
+                v.field(server, path, "command", FieldKind::String, true);
+                v.field(server, path, "args", FieldKind::StringList, false);
+                v.field(server, path, "env", FieldKind::StringMap, false);
+            }
+        });
+    }
+
+    v.entries(document, "commands", false, |v, path, entry| {
+        if let Some(command) = v.object(path, entry) {
+            v.field(command, path, "description", FieldKind::String, true);
+            v.field(command, path, "template", FieldKind::String, true);
+        }
+    });
+
+    if let Some(proxy) = document.get("proxy")
+        && let Some(proxy) = v.object("proxy", proxy)
+    {
+        v.field(proxy, "proxy", "enabled", FieldKind::Bool, false);
+        for key in [
+            "http_proxy_url",
+            "https_proxy_url",
+            "all_proxy_url",
+            "proxy_type",
+            "host",
+            "username",
+            "password",
+        ] {
+            v.field(proxy, "proxy", key, FieldKind::String, false);
+        }
+        v.field(proxy, "proxy", "port", FieldKind::Count, false);
+    }
+
+    if v.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(v.errors)
+    }
+}
+
+/// Apply every migration between the document's `schema_version` (0 when
+/// absent) and [`CURRENT_CONFIG_SCHEMA_VERSION`], then deserialize it
+pub fn migrate(mut config_value: Value) -> Result<Config> {
+    let document = config_value
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("Config must be a JSON object"))?;
+
+    let version = match document.get("schema_version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| anyhow!("Invalid schema_version: {}", version))?,
+    };
+    if version > CURRENT_CONFIG_SCHEMA_VERSION {
+        bail!(
+            "Config schema version {} is newer than supported version {}",
+            version,
+            CURRENT_CONFIG_SCHEMA_VERSION
+        );
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        log::info!(
+            "Migrating config from schema version {} to {}",
+            from,
+            from + 1
+        );
+        migration(document).with_context(|| format!("Failed to migrate config from v{}", from))?;
+    }
+    document.insert(
+        "schema_version".to_string(),
+        CURRENT_CONFIG_SCHEMA_VERSION.into(),
+    );
+
+    serde_json::from_value(config_value).context("Failed to parse config")
+}
+
+/// v0 documents used the Claude Desktop style `mcpServers` key, interleaved
+/// `_comment` entries, and allowed a server to be a single command line string.
+fn migrate_v0_to_v1(document: &mut Map<String, Value>) -> Result<()> {
+    let servers = match document.remove("mcp_servers") {
+        Some(servers) => {
+            document.remove("mcpServers");
+            servers
+        }
+        None => document.remove("mcpServers").unwrap_or_default(),
+    };
+    let servers = match servers {
+        Value::Null => Map::new(),
+        Value::Object(servers) => servers,
+        other => bail!("mcpServers must be an object, found {}", other),
+    };
+
+    let mut migrated = Map::new();
+    for (name, server) in servers {
+        if name.starts_with("_comment") {
+            continue;
+        }
+        let server = match server {
+            Value::String(command_line) => {
+                let mut parts = command_line.split_whitespace().map(str::to_string);
+                let command = parts
+                    .next()
+                    .ok_or_else(|| anyhow!("MCP server '{}' has an empty command", name))?;
+                serde_json::json!({
+                    "command": command,
+                    "args": parts.collect::<Vec<_>>(),
+                })
+            }
+            server => server,
+        };
+        migrated.insert(name, server);
+    }
+    document.insert("mcp_servers".to_string(), Value::Object(migrated));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrates_v0_document_with_flat_mcp_map() {
+        let v0 = json!({
+            "agent_servers": {
+                "Claude Code": { "command": "claude-code-acp" }
+            },
+            "mcpServers": {
+                "_comment_simplified": "Simplified format - most common use case",
+                "filesystem": "npx -y @modelcontextprotocol/server-filesystem /tmp",
+                "github": {
+                    "command": "npx",
+                    "args": ["-y", "@modelcontextprotocol/server-github"],
+                    "env": { "GITHUB_TOKEN": "token" }
+                }
+            }
+        });
+
+        let config = migrate(v0).unwrap();
+        assert_eq!(config.schema_version, CURRENT_CONFIG_SCHEMA_VERSION);
+        assert_eq!(config.mcp_servers.len(), 2);
+
+        let filesystem = &config.mcp_servers["filesystem"];
+        assert!(filesystem.enabled);
+        assert_eq!(filesystem.command, "npx");
+        assert_eq!(
+            filesystem.args,
+            vec!["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]
+        );
+        assert_eq!(config.mcp_servers["github"].env["GITHUB_TOKEN"], "token");
+
+        // Migrated documents serialize in the current shape
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["schema_version"], CURRENT_CONFIG_SCHEMA_VERSION);
+        assert!(value.get("mcpServers").is_none());
+        assert_eq!(migrate(value).unwrap().mcp_servers.len(), 2);
+    }
+
+    fn error_paths(value: Value) -> Vec<String> {
+        validate_raw(&value)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn validation_accepts_current_and_v0_documents() {
+        let current = json!({
+            "schema_version": 1,
+            "agent_servers": {
+                "claude": { "command": "claude-code-acp", "args": ["--acp"], "env": {} }
+            },
+            "models": {
+                "gpt": {
+                    "enabled": true,
+                    "provider": "openai",
+                    "base_url": "https://api.openai.com/v1",
+                    "api_key": "key",
+                    "model_name": "gpt-4o"
+                }
+            },
+            "mcp_servers": { "fs": { "command": "npx", "enabled": false } },
+            "proxy": { "enabled": true, "port": 8080 },
+            "tool_call_preview_max_lines": 0
+        });
+        assert_eq!(validate_raw(&current), Ok(()));
+
+        let v0 = json!({
+            "agent_servers": {},
+            "mcpServers": {
+                "_comment": "Simplified format",
+                "filesystem": "npx -y @modelcontextprotocol/server-filesystem /tmp"
+            }
+        });
+        assert_eq!(validate_raw(&v0), Ok(()));
+    }
+
+    #[test]
+    fn validation_reports_missing_fields_by_path() {
+        let errors = error_paths(json!({
+            "agent_servers": {
+                "claude": { "args": ["--acp"] }
+            },
+            "commands": {
+                "review": { "description": "Review code" }
+            }
+        }));
+        assert_eq!(
+            errors,
+            vec![
+                "agent_servers.claude.command: missing",
+                "commands.review.template: missing",
+            ]
+        );
+
+        assert_eq!(error_paths(json!({})), vec!["agent_servers: missing"]);
+    }
+
+    #[test]
+    fn validation_reports_wrong_types_by_path() {
+        let errors = error_paths(json!({
+            "agent_servers": {
+                "claude": { "command": "claude", "args": ["--acp", 1], "env": { "DEBUG": true } },
+                "codex": "codex --acp"
+            },
+            "models": {
+                "gpt": {
+                    "enabled": "yes",
+                    "provider": "openai",
+                    "base_url": "https://api.openai.com/v1",
+                    "api_key": "key",
+                    "model_name": "gpt-4o"
+                }
+            },
+            "proxy": { "port": -1 },
+            "tool_call_preview_max_lines": "ten"
+        }));
+        assert_eq!(
+            errors,
+            vec![
+                "tool_call_preview_max_lines: expected non-negative integer, found string",
+                "agent_servers.claude.args.1: expected string, found number",
+                "agent_servers.claude.env.DEBUG: expected string, found boolean",
+                "agent_servers.codex: expected object, found string",
+                "models.gpt.enabled: expected boolean, found string",
+                "proxy.port: expected non-negative integer, found number",
+            ]
+        );
+
+        assert_eq!(error_paths(json!([])), vec!["expected object, found array"]);
+    }
+
+    #[test]
+    fn parse_config_lists_validation_problems() {
+        let error = parse_config(r#"{ "agent_servers": { "claude": {} } }"#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("agent_servers.claude.command: missing")
+        );
+    }
+
+    #[test]
+    fn rejects_newer_schema_versions() {
+        let future = json!({
+            "schema_version": CURRENT_CONFIG_SCHEMA_VERSION + 1,
+            "agent_servers": {}
+        });
+        assert!(migrate(future).is_err());
+    }
+}
@@ -1,9 +1,11 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use agent_client_protocol as acp;
 use agent_client_protocol::SessionUpdate;
 use chrono::{DateTime, Utc};
 
+use crate::agent_status::AgentRuntimeStatus;
 use crate::config::{AgentProcessConfig, CommandConfig, Config, McpServerConfig, ModelConfig};
 use crate::session::SessionStatus;
 
@@ -23,6 +25,11 @@ pub enum AgentConfigEvent {
     },
     /// An agent was removed
     AgentRemoved { name: String },
+    /// A running agent's status changed, e.g. it finished the handshake
+    AgentStatusChanged {
+        name: String,
+        status: AgentRuntimeStatus,
+    },
 
     // ========== Model Events ==========
     /// A new model was added
@@ -57,6 +64,25 @@ pub enum AgentConfigEvent {
     // ========== Full Reload ==========
     /// The entire configuration was reloaded from file
     ConfigReloaded { config: Box<Config> },
+
+    // ========== Persistence ==========
+    /// Writing the configuration file failed, including its one retry
+    SaveFailed {
+        section: ConfigSection,
+        error: String,
+    },
+}
+
+/// Part of the configuration a change was made to, matching the settings pages
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigSection {
+    General,
+    Network,
+    Agents,
+    Models,
+    McpServers,
+    Commands,
+    Prompts,
 }
 
 /// Session update event that can be broadcast to subscribers
@@ -109,6 +135,12 @@ pub enum WorkspaceUpdateEvent {
         last_active: DateTime<Utc>,
         message_count: usize,
     },
+    /// The application working directory changed
+    CwdChanged { path: PathBuf },
+    /// The agent used for new conversations changed
+    ActiveAgentChanged { agent_name: String },
+    /// The number of messages waiting for a busy session changed
+    MessageQueueChanged { session_id: String, queued: usize },
 }
 
 /// Pure data struct for code selection (no GPUI dependency)
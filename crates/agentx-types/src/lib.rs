@@ -1,15 +1,19 @@
+pub mod agent_status;
 pub mod config;
 pub mod config_manager;
 pub mod events;
 pub mod schemas;
 pub mod session;
 
+pub use agent_status::{AgentRuntimeInfo, AgentRuntimeStatus};
 pub use config::{
-    AgentProcessConfig, CommandConfig, Config, DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES,
-    McpServerConfig, ModelConfig, ProxyConfig,
+    AgentProcessConfig, CURRENT_CONFIG_SCHEMA_VERSION, CommandConfig, Config,
+    DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES, EnvVarError, EnvVarErrorKind, McpServerConfig,
+    ModelConfig, PROXY_SCHEMES, ProxyConfig, ProxyUrlError, ProxyUrlErrorKind, TEMPLATE_VARIABLES,
+    TemplateError, TemplateErrorKind, TemplateVars, expand_template, mask_proxy_url,
 };
 pub use events::{
-    AgentConfigEvent, CodeSelectionEvent, PermissionRequestEvent, SessionUpdateEvent,
-    WorkspaceUpdateEvent,
+    AgentConfigEvent, CodeSelectionEvent, ConfigSection, PermissionRequestEvent,
+    SessionUpdateEvent, WorkspaceUpdateEvent,
 };
 pub use session::SessionStatus;
@@ -66,6 +66,12 @@ pub struct WorkspaceTask {
     /// When the task was created
     #[serde(with = "chrono::serde::ts_seconds")]
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// When the task's session last changed status
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub last_active_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Model chosen for the task's session, restored when the session is resumed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected_model: Option<String>,
     /// Last message preview (plain String, UI layer can convert to SharedString)
     #[serde(skip)]
     pub last_message: Option<String>,
@@ -83,6 +89,8 @@ impl WorkspaceTask {
             session_id: None,
             status: SessionStatus::Pending,
             created_at: chrono::Utc::now(),
+            last_active_at: None,
+            selected_model: None,
             last_message: None,
         }
     }
@@ -93,6 +101,11 @@ impl WorkspaceTask {
         self.status = SessionStatus::InProgress;
     }
 
+    /// Most recent activity on the task, falling back to its creation time
+    pub fn last_activity(&self) -> chrono::DateTime<chrono::Utc> {
+        self.last_active_at.unwrap_or(self.created_at)
+    }
+
     /// Update the last message preview
     pub fn update_last_message(&mut self, text: impl Into<String>) {
         self.last_message = Some(text.into());
@@ -160,6 +173,20 @@ impl WorkspaceConfig {
             .find(|t| t.session_id.as_ref() == Some(&session_id.to_string()))
     }
 
+    /// Mark tasks whose turn was still running when the config was saved as
+    /// interrupted, returning how many were changed. Nothing can still be
+    /// running for them after a restart.
+    pub fn mark_interrupted_tasks(&mut self) -> usize {
+        let mut count = 0;
+        for task in &mut self.tasks {
+            if task.status.is_turn_in_progress() {
+                task.status = SessionStatus::Interrupted;
+                count += 1;
+            }
+        }
+        count
+    }
+
     /// Get workspace by ID
     pub fn get_workspace(&self, workspace_id: &str) -> Option<&Workspace> {
         self.workspaces.iter().find(|w| w.id == workspace_id)
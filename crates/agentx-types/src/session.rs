@@ -10,4 +10,13 @@ pub enum SessionStatus {
     Completed,
     Closed,
     Failed,
+    /// A turn was still running when the app last exited
+    Interrupted,
+}
+
+impl SessionStatus {
+    /// Whether the agent is working on a turn that can still be cancelled
+    pub fn is_turn_in_progress(&self) -> bool {
+        matches!(self, SessionStatus::InProgress | SessionStatus::Pending)
+    }
 }
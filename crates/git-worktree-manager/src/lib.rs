@@ -1,12 +1,43 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, anyhow};
 use git2::{
-    BranchType, Index, MergeOptions as GitMergeOptions, Oid, Repository, StatusOptions,
-    WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions, build::CheckoutBuilder,
+    BranchType, CherrypickOptions, Cred, CredentialType, ErrorCode, FetchOptions, Index,
+    MergeFileOptions, MergeOptions as GitMergeOptions, Oid, RemoteCallbacks, Repository,
+    StatusOptions, WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions,
+    build::CheckoutBuilder,
 };
 
+/// Failures callers may want to handle specifically; everything else is
+/// [`WorktreeError::Other`] carrying a descriptive message
+#[derive(Debug, thiserror::Error)]
+pub enum WorktreeError {
+    /// The target path is occupied; retry with [`CreateOptions::force`] to
+    /// replace it
+    #[error("worktree path already exists: {}", .0.display())]
+    PathExists(PathBuf),
+    #[error("branch not found: {0}")]
+    BranchNotFound(String),
+    #[error("worktree not found: {}", .0.display())]
+    WorktreeNotFound(PathBuf),
+    #[error("worktree has uncommitted changes")]
+    UncommittedChanges,
+    /// A previous merge or cherry-pick left conflicts that must be resolved first
+    #[error("worktree has unresolved conflicts")]
+    HasConflicts,
+    #[error("worktree is locked: {}", .0.display())]
+    Locked(PathBuf),
+    #[error(transparent)]
+    Git(#[from] git2::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+pub type Result<T, E = WorktreeError> = std::result::Result<T, E>;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WorktreeInfo {
     pub path: PathBuf,
@@ -29,6 +60,27 @@ impl Default for CreateOptions {
     }
 }
 
+/// Criteria for [`WorktreeManager::list_filtered`]; every set criterion must
+/// match, and the default matches every worktree
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorktreeFilter {
+    pub locked_only: bool,
+    /// Only worktrees with this branch checked out
+    pub branch: Option<String>,
+    pub exclude_main: bool,
+}
+
+impl WorktreeFilter {
+    pub fn matches(&self, info: &WorktreeInfo) -> bool {
+        (!self.locked_only || info.is_locked)
+            && (!self.exclude_main || info.name.is_some())
+            && self
+                .branch
+                .as_ref()
+                .is_none_or(|branch| info.branch.as_ref() == Some(branch))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WorktreeBranch {
     Existing(String),
@@ -38,10 +90,23 @@ pub enum WorktreeBranch {
     },
 }
 
+/// How conflicting hunks are written into the worktree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictStyle {
+    /// `ours` and `theirs` sections only
+    #[default]
+    Merge,
+    /// Also include the common ancestor in a `|||||||` section
+    Diff3,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MergeOptions {
     pub no_ff: bool,
     pub message: Option<String>,
+    /// Length of the conflict markers; git's default of 7 when `None`
+    pub conflict_marker_size: Option<u16>,
+    pub style: ConflictStyle,
 }
 
 impl Default for MergeOptions {
@@ -49,6 +114,8 @@ impl Default for MergeOptions {
         Self {
             no_ff: true,
             message: None,
+            conflict_marker_size: None,
+            style: ConflictStyle::Merge,
         }
     }
 }
@@ -59,12 +126,61 @@ pub struct MergeConflict {
     pub content: String,
 }
 
+/// A conflicted file, listed without reading its content; pass it to
+/// [`WorktreeManager::read_conflict`] to load the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictEntry {
+    /// Path relative to the worktree root
+    pub path: PathBuf,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MergeOutcome {
     Merged,
     Conflicts(Vec<MergeConflict>),
 }
 
+/// Files written so far while checking out a tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckoutProgress {
+    /// The file just written, if any
+    pub path: Option<PathBuf>,
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// Objects received so far while fetching from a remote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferProgress {
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub total_objects: usize,
+    pub received_bytes: usize,
+}
+
+/// How far a worktree branch has moved from the base branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchSummary {
+    pub branch: String,
+    /// Files changed on the branch since it forked from the base
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Commits on the branch that the base does not have
+    pub ahead: usize,
+    /// Commits on the base that the branch does not have
+    pub behind: usize,
+}
+
+/// Callback reporting checkout progress
+pub type CheckoutProgressFn<'a> = &'a mut dyn FnMut(CheckoutProgress);
+
+/// Callback reporting fetch progress
+pub type TransferProgressFn<'a> = &'a mut dyn FnMut(TransferProgress);
+
+type CredentialsFn<'a> =
+    &'a mut dyn FnMut(&str, Option<&str>, CredentialType) -> std::result::Result<Cred, git2::Error>;
+
 #[derive(Debug, Clone)]
 pub struct WorktreeManager {
     repo_path: PathBuf,
@@ -131,11 +247,32 @@ impl WorktreeManager {
         Ok(worktrees)
     }
 
+    /// [`Self::list`], keeping only the worktrees matching `filter`
+    pub fn list_filtered(&self, filter: &WorktreeFilter) -> Result<Vec<WorktreeInfo>> {
+        let mut worktrees = self.list()?;
+        worktrees.retain(|info| filter.matches(info));
+        Ok(worktrees)
+    }
+
     pub fn create(
         &self,
         path: impl AsRef<Path>,
         branch: WorktreeBranch,
         options: CreateOptions,
+    ) -> Result<WorktreeInfo> {
+        self.create_with_progress(path, branch, options, None)
+    }
+
+    /// [`Self::create`], reporting progress of the checkout that finalizes the
+    /// new worktree. libgit2 writes the initial files while adding the
+    /// worktree and git2 does not expose that checkout, so only files it left
+    /// behind are reported.
+    pub fn create_with_progress(
+        &self,
+        path: impl AsRef<Path>,
+        branch: WorktreeBranch,
+        options: CreateOptions,
+        progress: Option<CheckoutProgressFn<'_>>,
     ) -> Result<WorktreeInfo> {
         let path = path.as_ref();
         let repo = self.open_repo(&self.repo_path)?;
@@ -144,7 +281,7 @@ impl WorktreeManager {
                 fs::remove_dir_all(path)
                     .with_context(|| format!("failed to remove {}", path.display()))?;
             } else {
-                bail!("worktree path already exists: {}", path.display());
+                return Err(WorktreeError::PathExists(path.to_path_buf()));
             }
         }
         let name = path
@@ -159,15 +296,25 @@ impl WorktreeManager {
             WorktreeBranch::Existing(name) => name,
             WorktreeBranch::New { name, start_point } => {
                 let commit = resolve_commit(&repo, start_point.as_deref())?;
-                repo.branch(&name, &commit, false)
+                let mut new_branch = repo
+                    .branch(&name, &commit, false)
                     .with_context(|| format!("failed to create branch {}", name))?;
+                // Starting from a remote-tracking branch such as `origin/main`
+                // also tracks it, like `git worktree add -b name path origin/main`
+                if let Some(point) = start_point.as_deref()
+                    && repo.find_branch(point, BranchType::Remote).is_ok()
+                {
+                    new_branch
+                        .set_upstream(Some(point))
+                        .with_context(|| format!("failed to track {}", point))?;
+                }
                 name
             }
         };
 
         let reference = repo
             .find_reference(&format!("refs/heads/{}", branch_name))
-            .with_context(|| format!("failed to find branch {}", branch_name))?;
+            .map_err(branch_lookup_error(&branch_name))?;
         add_opts.reference(Some(&reference));
 
         let worktree = repo
@@ -179,7 +326,7 @@ impl WorktreeManager {
             .set_head(&format!("refs/heads/{}", branch_name))
             .with_context(|| format!("failed to set head to {}", branch_name))?;
         worktree_repo
-            .checkout_head(Some(CheckoutBuilder::new().safe()))
+            .checkout_head(Some(&mut checkout_builder(progress)))
             .with_context(|| "failed to checkout new worktree")?;
 
         let worktree_repo = self.open_repo(path)?;
@@ -209,10 +356,11 @@ impl WorktreeManager {
         if let Some(root) = repo.workdir() {
             let resolved_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
             if resolved_root == resolved_path {
-                bail!(
+                return Err(anyhow!(
                     "refusing to remove main worktree at {}",
                     resolved_path.display()
-                );
+                )
+                .into());
             }
         }
 
@@ -221,7 +369,7 @@ impl WorktreeManager {
             .into_iter()
             .find(|info| info.path == resolved_path)
             .and_then(|info| info.name)
-            .with_context(|| format!("worktree not found: {}", resolved_path.display()))?;
+            .ok_or_else(|| WorktreeError::WorktreeNotFound(resolved_path.clone()))?;
 
         let worktree = repo
             .find_worktree(&worktree_name)
@@ -242,6 +390,115 @@ impl WorktreeManager {
         Ok(())
     }
 
+    /// Relocate a linked worktree on disk, keeping its git linkage intact.
+    /// Equivalent to `git worktree move`.
+    pub fn move_worktree(&self, from: &Path, to: &Path) -> Result<WorktreeInfo> {
+        let repo = self.open_repo(&self.repo_path)?;
+        let info = self.find(from)?;
+        let Some(name) = info.name else {
+            return Err(
+                anyhow!("refusing to move main worktree at {}", info.path.display()).into(),
+            );
+        };
+        if info.is_locked {
+            return Err(WorktreeError::Locked(info.path));
+        }
+        if to.exists() {
+            return Err(WorktreeError::PathExists(to.to_path_buf()));
+        }
+
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        move_dir(&info.path, to)?;
+        let to = to
+            .canonicalize()
+            .with_context(|| format!("failed to resolve path {}", to.display()))?;
+
+        // `<commondir>/worktrees/<name>/gitdir` points at the worktree's `.git`
+        // file, which in turn points back at that admin directory. The admin
+        // directory and its `commondir` stay put, so only these two move.
+        let admin_dir = repo.path().join("worktrees").join(&name);
+        fs::write(
+            admin_dir.join("gitdir"),
+            format!("{}\n", to.join(".git").display()),
+        )
+        .with_context(|| format!("failed to update gitdir for worktree {}", name))?;
+        fs::write(
+            to.join(".git"),
+            format!("gitdir: {}\n", admin_dir.display()),
+        )
+        .with_context(|| format!("failed to update .git file in {}", to.display()))?;
+
+        self.find(&to)
+    }
+
+    /// Fetch `remote`, updating its remote-tracking branches, using the
+    /// default credentials (ssh-agent, then the git credential helper)
+    pub fn fetch(&self, remote: &str) -> Result<()> {
+        self.fetch_with_progress(remote, None)
+    }
+
+    /// [`Self::fetch`], reporting progress as objects are received
+    pub fn fetch_with_progress(
+        &self,
+        remote: &str,
+        progress: Option<TransferProgressFn<'_>>,
+    ) -> Result<()> {
+        let repo = self.open_repo(&self.repo_path)?;
+        let config = repo.config().context("failed to read git config")?;
+        self.fetch_inner(
+            remote,
+            &mut |url, username, allowed| default_credentials(&config, url, username, allowed),
+            progress,
+        )
+    }
+
+    /// Fetch `remote`, asking `credentials` for authentication when the
+    /// transport requires it. The callback receives the URL, the username from
+    /// the URL if any, and the credential types the server accepts.
+    pub fn fetch_with_credentials<F>(&self, remote: &str, mut credentials: F) -> Result<()>
+    where
+        F: FnMut(&str, Option<&str>, CredentialType) -> std::result::Result<Cred, git2::Error>,
+    {
+        self.fetch_inner(remote, &mut credentials, None)
+    }
+
+    fn fetch_inner(
+        &self,
+        remote: &str,
+        credentials: CredentialsFn<'_>,
+        progress: Option<TransferProgressFn<'_>>,
+    ) -> Result<()> {
+        let repo = self.open_repo(&self.repo_path)?;
+        let mut git_remote = repo
+            .find_remote(remote)
+            .with_context(|| format!("remote not found: {}", remote))?;
+
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|url, username, allowed| credentials(url, username, allowed));
+        if let Some(progress) = progress {
+            callbacks.transfer_progress(move |stats| {
+                progress(TransferProgress {
+                    received_objects: stats.received_objects(),
+                    indexed_objects: stats.indexed_objects(),
+                    total_objects: stats.total_objects(),
+                    received_bytes: stats.received_bytes(),
+                });
+                true
+            });
+        }
+        let mut fetch_opts = FetchOptions::new();
+        fetch_opts.remote_callbacks(callbacks);
+
+        // An empty refspec list uses the remote's configured fetch refspecs
+        git_remote
+            .fetch::<&str>(&[], Some(&mut fetch_opts), None)
+            .with_context(|| format!("failed to fetch {}", remote))?;
+        Ok(())
+    }
+
     pub fn switch(&self, worktree_path: impl AsRef<Path>, branch: &str) -> Result<()> {
         let worktree_path = worktree_path.as_ref();
         let repo = self.open_repo(worktree_path)?;
@@ -258,6 +515,18 @@ impl WorktreeManager {
         target_branch: &str,
         source_branch: &str,
         options: MergeOptions,
+    ) -> Result<MergeOutcome> {
+        self.merge_with_progress(target_worktree, target_branch, source_branch, options, None)
+    }
+
+    /// [`Self::merge`], reporting progress while merged files are checked out
+    pub fn merge_with_progress(
+        &self,
+        target_worktree: impl AsRef<Path>,
+        target_branch: &str,
+        source_branch: &str,
+        options: MergeOptions,
+        progress: Option<CheckoutProgressFn<'_>>,
     ) -> Result<MergeOutcome> {
         let target_worktree = target_worktree.as_ref();
         let repo = self.open_repo(target_worktree)?;
@@ -272,7 +541,7 @@ impl WorktreeManager {
         let source_commit = find_branch_commit(&repo, source_branch)?;
         let source_ref = repo
             .find_reference(&format!("refs/heads/{}", source_branch))
-            .with_context(|| format!("branch not found: {}", source_branch))?;
+            .map_err(branch_lookup_error(source_branch))?;
         let annotated = repo.reference_to_annotated_commit(&source_ref)?;
         let (analysis, _) = repo.merge_analysis(&[&annotated])?;
         if analysis.is_up_to_date() {
@@ -280,17 +549,43 @@ impl WorktreeManager {
         }
 
         if analysis.is_fast_forward() && !options.no_ff {
-            fast_forward(&repo, target_branch, source_commit.id())?;
+            fast_forward(&repo, target_branch, source_commit.id(), progress)?;
             return Ok(MergeOutcome::Merged);
         }
 
         let mut merge_opts = GitMergeOptions::new();
-        let mut checkout = CheckoutBuilder::new();
-        checkout.safe();
+        let mut checkout = checkout_builder(progress);
+        checkout
+            .ancestor_label(CONFLICT_ANCESTOR_LABEL)
+            .our_label(target_branch)
+            .their_label(source_branch);
+        match options.style {
+            ConflictStyle::Merge => {
+                merge_opts.standard_style(true);
+                checkout.conflict_style_merge(true);
+            }
+            ConflictStyle::Diff3 => {
+                merge_opts.diff3_style(true);
+                checkout.conflict_style_diff3(true);
+            }
+        }
         repo.merge(&[&annotated], Some(&mut merge_opts), Some(&mut checkout))?;
 
         let mut index = repo.index()?;
         if index.has_conflicts() {
+            // Checkout always writes git's default marker size, so conflicted
+            // files are re-merged when a different size is requested
+            if let Some(marker_size) = options.conflict_marker_size {
+                rewrite_conflict_files(
+                    &repo,
+                    &mut index,
+                    target_worktree,
+                    target_branch,
+                    source_branch,
+                    options.style,
+                    marker_size,
+                )?;
+            }
             let conflicts = collect_conflicts_from_index(&mut index, target_worktree)?;
             return Ok(MergeOutcome::Conflicts(conflicts));
         }
@@ -315,24 +610,216 @@ impl WorktreeManager {
         Ok(MergeOutcome::Merged)
     }
 
-    fn find_by_path(&self, path: &Path) -> Result<WorktreeInfo> {
+    /// Throw away an in-progress merge or cherry-pick, resetting the worktree
+    /// to its `HEAD` so the next merge starts from a clean state
+    pub fn abort_merge(&self, worktree_path: impl AsRef<Path>) -> Result<()> {
+        let repo = self.open_repo(worktree_path.as_ref())?;
+        let head = repo.head()?.peel_to_commit()?;
+        repo.reset(head.as_object(), git2::ResetType::Hard, None)?;
+        repo.cleanup_state()?;
+        Ok(())
+    }
+
+    /// Apply a single commit onto the branch checked out in `worktree_path`.
+    /// On a clean pick the commit is recreated with its original author and
+    /// message; otherwise the conflicts are returned and left in the worktree.
+    pub fn cherry_pick(
+        &self,
+        worktree_path: impl AsRef<Path>,
+        commit: &str,
+    ) -> Result<MergeOutcome> {
+        let worktree_path = worktree_path.as_ref();
+        let repo = self.open_repo(worktree_path)?;
+        ensure_clean_repo(&repo)?;
+
+        let picked = repo
+            .revparse_single(commit)
+            .and_then(|object| object.peel_to_commit())
+            .with_context(|| format!("commit not found: {}", commit))?;
+        if picked.parent_count() > 1 {
+            return Err(anyhow!("cannot cherry-pick merge commit {}", picked.id()).into());
+        }
+
+        let mut checkout = CheckoutBuilder::new();
+        checkout.safe();
+        let mut cherrypick_opts = CherrypickOptions::new();
+        cherrypick_opts.checkout_builder(checkout);
+        repo.cherrypick(&picked, Some(&mut cherrypick_opts))
+            .with_context(|| format!("failed to cherry-pick {}", picked.id()))?;
+
+        let mut index = repo.index()?;
+        if index.has_conflicts() {
+            let conflicts = collect_conflicts_from_index(&mut index, worktree_path)?;
+            return Ok(MergeOutcome::Conflicts(conflicts));
+        }
+
+        let tree_oid = index.write_tree()?;
+        let head_commit = repo.head()?.peel_to_commit()?;
+        // Already applied: nothing to commit
+        if tree_oid != head_commit.tree_id() {
+            let tree = repo.find_tree(tree_oid)?;
+            let message = String::from_utf8_lossy(picked.message_bytes()).to_string();
+            let committer = repo.signature()?;
+            repo.commit(
+                Some("HEAD"),
+                &picked.author(),
+                &committer,
+                &message,
+                &tree,
+                &[&head_commit],
+            )?;
+        }
+        repo.cleanup_state()?;
+
+        Ok(MergeOutcome::Merged)
+    }
+
+    /// Files left conflicted in `worktree_path` by a merge or cherry-pick.
+    /// Unlike [`MergeOutcome::Conflicts`] no file content is read.
+    pub fn list_conflicts(&self, worktree_path: impl AsRef<Path>) -> Result<Vec<ConflictEntry>> {
+        let repo = self.open_repo(worktree_path.as_ref())?;
+        let index = repo.index()?;
+        conflict_entries(&index)
+    }
+
+    /// Current content of a conflicted file, markers included
+    pub fn read_conflict(
+        &self,
+        worktree_path: impl AsRef<Path>,
+        entry: &ConflictEntry,
+    ) -> Result<MergeConflict> {
+        let content = read_conflict_content(&worktree_path.as_ref().join(&entry.path))?;
+        Ok(MergeConflict {
+            path: entry.path.clone(),
+            content,
+        })
+    }
+
+    /// Diff stats of every worktree branch against `base`, in worktree order
+    pub fn summarize_branches(&self, base: &str) -> Result<Vec<BranchSummary>> {
+        let repo = self.open_repo(&self.repo_path)?;
+        let base_commit = find_branch_commit(&repo, base)?;
+
+        let mut summaries: Vec<BranchSummary> = Vec::new();
+        for worktree in self.list()? {
+            let Some(branch) = worktree.branch else {
+                continue;
+            };
+            if branch == base || summaries.iter().any(|summary| summary.branch == branch) {
+                continue;
+            }
+            summaries.push(summarize_branch(&repo, &branch, &base_commit)?);
+        }
+        Ok(summaries)
+    }
+
+    /// The worktree checked out at `path`, main worktree included
+    pub fn find(&self, path: impl AsRef<Path>) -> Result<WorktreeInfo> {
+        let path = path.as_ref();
         let path = path
             .canonicalize()
-            .with_context(|| format!("failed to resolve path {}", path.display()))?;
+            .map_err(|_| WorktreeError::WorktreeNotFound(path.to_path_buf()))?;
         let worktrees = self.list()?;
         worktrees
             .into_iter()
             .find(|info| info.path == path)
-            .with_context(|| format!("worktree not found: {}", path.display()))
+            .ok_or(WorktreeError::WorktreeNotFound(path))
     }
 
     fn open_repo(&self, path: &Path) -> Result<Repository> {
-        Repository::open(path)
-            .with_context(|| format!("failed to open repository at {}", path.display()))
+        Ok(Repository::open(path)
+            .with_context(|| format!("failed to open repository at {}", path.display()))?)
+    }
+}
+
+/// Rename `from` to `to`, copying when they are on different filesystems
+fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
     }
+    copy_dir(from, to)?;
+    fs::remove_dir_all(from).with_context(|| format!("failed to remove {}", from.display()))?;
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir(to).with_context(|| format!("failed to create {}", to.display()))?;
+    for entry in fs::read_dir(from).with_context(|| format!("failed to read {}", from.display()))? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+        .with_context(|| format!("failed to copy link {}", from.display()))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> Result<()> {
+    fs::copy(from, to).with_context(|| format!("failed to copy {}", from.display()))?;
+    Ok(())
+}
+
+const CONFLICT_ANCESTOR_LABEL: &str = "base";
+
+fn rewrite_conflict_files(
+    repo: &Repository,
+    index: &mut Index,
+    worktree_path: &Path,
+    our_label: &str,
+    their_label: &str,
+    style: ConflictStyle,
+    marker_size: u16,
+) -> Result<()> {
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        // Add/add conflicts have no ancestor to merge against; they keep the
+        // markers checkout wrote
+        let (Some(ancestor), Some(ours), Some(theirs)) =
+            (&conflict.ancestor, &conflict.our, &conflict.their)
+        else {
+            continue;
+        };
+        let mut file_opts = MergeFileOptions::new();
+        file_opts
+            .ancestor_label(CONFLICT_ANCESTOR_LABEL)
+            .our_label(our_label)
+            .their_label(their_label)
+            .marker_size(marker_size);
+        match style {
+            ConflictStyle::Merge => file_opts.style_standard(true),
+            ConflictStyle::Diff3 => file_opts.style_diff3(true),
+        };
+        let result = repo.merge_file_from_index(ancestor, ours, theirs, Some(&mut file_opts))?;
+        let relative = PathBuf::from(String::from_utf8_lossy(&ours.path).to_string());
+        let path = worktree_path.join(&relative);
+        fs::write(&path, result.content())
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+thread_local! {
+    static CONFLICT_READS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
 }
 
 fn read_conflict_content(path: &Path) -> Result<String> {
+    #[cfg(test)]
+    CONFLICT_READS.with(|reads| reads.set(reads.get() + 1));
     match fs::read(path) {
         Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).to_string()),
         Err(error) => Ok(format!("<<unable to read conflict file: {}>>", error)),
@@ -355,53 +842,140 @@ fn repo_head_branch(repo: &Repository) -> Result<(Option<String>, Option<String>
 
 fn resolve_commit<'a>(repo: &'a Repository, start_point: Option<&str>) -> Result<git2::Commit<'a>> {
     if let Some(point) = start_point {
-        let object = repo.revparse_single(point)?;
-        object
+        let object = repo.revparse_single(point).with_context(|| {
+            format!(
+                "start point not found: {} (fetch the remote if it is a remote branch)",
+                point
+            )
+        })?;
+        Ok(object
             .peel_to_commit()
-            .with_context(|| format!("invalid start point {}", point))
+            .with_context(|| format!("invalid start point {}", point))?)
     } else {
-        repo.head()?
+        Ok(repo
+            .head()?
             .peel_to_commit()
-            .context("failed to resolve HEAD")
+            .context("failed to resolve HEAD")?)
+    }
+}
+
+fn default_credentials(
+    config: &git2::Config,
+    url: &str,
+    username: Option<&str>,
+    allowed: CredentialType,
+) -> std::result::Result<Cred, git2::Error> {
+    if allowed.contains(CredentialType::SSH_KEY)
+        && let Some(username) = username
+    {
+        return Cred::ssh_key_from_agent(username);
+    }
+    if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+        return Cred::credential_helper(config, url, username);
+    }
+    if allowed.contains(CredentialType::USERNAME) {
+        return Cred::username(username.unwrap_or("git"));
+    }
+    Cred::default()
+}
+
+/// Map a failed lookup of `branch` to [`WorktreeError::BranchNotFound`] when
+/// git reports it missing; other failures (e.g. an invalid name) keep their
+/// git error
+fn branch_lookup_error(branch: &str) -> impl FnOnce(git2::Error) -> WorktreeError + '_ {
+    move |err| {
+        if err.code() == ErrorCode::NotFound {
+            WorktreeError::BranchNotFound(branch.to_string())
+        } else {
+            WorktreeError::Git(err)
+        }
     }
 }
 
 fn find_branch_commit<'a>(repo: &'a Repository, branch: &str) -> Result<git2::Commit<'a>> {
     let reference = repo
         .find_branch(branch, BranchType::Local)
-        .with_context(|| format!("branch not found: {}", branch))?
+        .map_err(branch_lookup_error(branch))?
         .into_reference();
     let object = reference.peel_to_commit()?;
     Ok(object)
 }
 
+fn summarize_branch(
+    repo: &Repository,
+    branch: &str,
+    base: &git2::Commit<'_>,
+) -> Result<BranchSummary> {
+    let commit = find_branch_commit(repo, branch)?;
+    let (ahead, behind) = repo
+        .graph_ahead_behind(commit.id(), base.id())
+        .with_context(|| format!("failed to compare {} with base", branch))?;
+
+    // Diff from the fork point so work landing on the base doesn't count
+    let fork_point = repo
+        .merge_base(commit.id(), base.id())
+        .with_context(|| format!("{} shares no history with base", branch))?;
+    let fork_tree = repo.find_commit(fork_point)?.tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&fork_tree), Some(&commit.tree()?), None)?;
+    let stats = diff.stats()?;
+
+    Ok(BranchSummary {
+        branch: branch.to_string(),
+        files_changed: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+        ahead,
+        behind,
+    })
+}
+
 fn ensure_clean_repo(repo: &Repository) -> Result<()> {
+    if repo.index()?.has_conflicts() {
+        return Err(WorktreeError::HasConflicts);
+    }
     let mut options = StatusOptions::new();
     options.include_untracked(true).recurse_untracked_dirs(true);
     let statuses = repo.statuses(Some(&mut options))?;
     if !statuses.is_empty() {
-        bail!("worktree has uncommitted changes");
+        return Err(WorktreeError::UncommittedChanges);
     }
     Ok(())
 }
 
-fn fast_forward(repo: &Repository, branch: &str, target: Oid) -> Result<()> {
+fn fast_forward(
+    repo: &Repository,
+    branch: &str,
+    target: Oid,
+    progress: Option<CheckoutProgressFn<'_>>,
+) -> Result<()> {
     let mut reference = repo
         .find_reference(&format!("refs/heads/{}", branch))
-        .with_context(|| format!("missing branch {}", branch))?;
+        .map_err(branch_lookup_error(branch))?;
     reference.set_target(target, "fast-forward")?;
     repo.set_head(&format!("refs/heads/{}", branch))?;
-    repo.checkout_head(Some(CheckoutBuilder::new().safe()))?;
+    repo.checkout_head(Some(&mut checkout_builder(progress)))?;
     Ok(())
 }
 
-fn collect_conflicts_from_index(
-    index: &mut Index,
-    worktree_path: &Path,
-) -> Result<Vec<MergeConflict>> {
-    let mut conflicts = Vec::new();
-    let entries = index.conflicts()?;
-    for conflict in entries {
+/// A safe checkout that forwards git2's progress notifications to `progress`
+fn checkout_builder<'a>(progress: Option<CheckoutProgressFn<'a>>) -> CheckoutBuilder<'a> {
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+    if let Some(progress) = progress {
+        checkout.progress(move |path, completed, total| {
+            progress(CheckoutProgress {
+                path: path.map(Path::to_path_buf),
+                completed,
+                total,
+            })
+        });
+    }
+    checkout
+}
+
+fn conflict_entries(index: &Index) -> Result<Vec<ConflictEntry>> {
+    let mut entries = Vec::new();
+    for conflict in index.conflicts()? {
         let conflict = conflict?;
         let entry = conflict
             .our
@@ -409,15 +983,28 @@ fn collect_conflicts_from_index(
             .or(conflict.their.as_ref())
             .or(conflict.ancestor.as_ref());
         if let Some(entry) = entry {
-            let relative = PathBuf::from(String::from_utf8_lossy(entry.path.as_ref()).to_string());
-            let content = read_conflict_content(&worktree_path.join(&relative))?;
-            conflicts.push(MergeConflict {
-                path: relative,
-                content,
+            entries.push(ConflictEntry {
+                path: PathBuf::from(String::from_utf8_lossy(entry.path.as_ref()).to_string()),
             });
         }
     }
-    Ok(conflicts)
+    Ok(entries)
+}
+
+fn collect_conflicts_from_index(
+    index: &mut Index,
+    worktree_path: &Path,
+) -> Result<Vec<MergeConflict>> {
+    conflict_entries(index)?
+        .into_iter()
+        .map(|entry| {
+            let content = read_conflict_content(&worktree_path.join(&entry.path))?;
+            Ok(MergeConflict {
+                path: entry.path,
+                content,
+            })
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -442,6 +1029,7 @@ mod tests {
 
         let mut index = repo.index().unwrap();
         index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
         let tree_id = index.write_tree().unwrap();
         let tree = repo.find_tree(tree_id).unwrap();
 
@@ -464,6 +1052,45 @@ mod tests {
             .unwrap_or_else(|| "main".to_string())
     }
 
+    #[test]
+    fn summarizes_worktree_branches_against_base() {
+        let (temp, repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        let main_branch = current_branch(&repo);
+        commit_file(&repo, "lib.rs", "one\ntwo\nthree\n");
+
+        let worktree_path = temp.path().join("agent-a");
+        manager
+            .create(
+                &worktree_path,
+                WorktreeBranch::New {
+                    name: "agent-a".to_string(),
+                    start_point: None,
+                },
+                CreateOptions::default(),
+            )
+            .unwrap();
+        let agent_repo = Repository::open(&worktree_path).unwrap();
+        commit_file(&agent_repo, "lib.rs", "one\n2\nthree\nfour\n");
+        commit_file(&agent_repo, "notes.md", "a\nb\n");
+
+        // Work landing on the base after the fork only shows up as `behind`
+        commit_file(&repo, "README.md", "init\nmore\n");
+
+        let summaries = manager.summarize_branches(&main_branch).unwrap();
+        assert_eq!(
+            summaries,
+            [BranchSummary {
+                branch: "agent-a".to_string(),
+                files_changed: 2,
+                insertions: 4,
+                deletions: 1,
+                ahead: 2,
+                behind: 1,
+            }]
+        );
+    }
+
     #[test]
     fn create_list_switch_worktree() {
         let (temp, _repo) = init_repo();
@@ -491,6 +1118,340 @@ mod tests {
         assert!(!list.iter().any(|entry| entry.path == expected));
     }
 
+    #[test]
+    fn fast_forward_reports_checkout_progress() {
+        let (temp, repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        let main_branch = current_branch(&repo);
+
+        let base_commit = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("agent", &base_commit, false).unwrap();
+        repo.set_head("refs/heads/agent").unwrap();
+        for i in 0..5 {
+            commit_file(&repo, &format!("file-{}.txt", i), "content\n");
+        }
+        repo.set_head(&format!("refs/heads/{}", main_branch))
+            .unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+
+        let mut updates = Vec::new();
+        let outcome = manager
+            .merge_with_progress(
+                temp.path(),
+                &main_branch,
+                "agent",
+                MergeOptions::default(),
+                Some(&mut |progress| updates.push(progress)),
+            )
+            .unwrap();
+
+        assert!(matches!(outcome, MergeOutcome::Merged));
+        assert!(!updates.is_empty());
+        let last = updates.last().unwrap();
+        assert_eq!(last.completed, last.total);
+        assert_eq!(last.total, 5);
+        assert!(temp.path().join("file-4.txt").exists());
+    }
+
+    #[test]
+    fn move_worktree_keeps_it_linked() {
+        let (temp, _repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        let from = temp.path().join("agent-a");
+        let created = manager
+            .create(
+                &from,
+                WorktreeBranch::New {
+                    name: "agent-a".to_string(),
+                    start_point: None,
+                },
+                CreateOptions::default(),
+            )
+            .unwrap();
+
+        let to = temp.path().join("fast-disk").join("agent-a");
+        let moved = manager.move_worktree(&from, &to).unwrap();
+        let expected = to.canonicalize().unwrap();
+        assert_eq!(moved.path, expected);
+        assert_eq!(moved.head, created.head);
+        assert_eq!(moved.branch.as_deref(), Some("agent-a"));
+        assert!(!from.exists());
+
+        let paths: Vec<_> = manager
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|w| w.path)
+            .collect();
+        assert!(paths.contains(&expected));
+        assert!(
+            !paths
+                .iter()
+                .any(|path| path.ends_with("agent-a") && *path != expected)
+        );
+
+        // The moved worktree is still a working checkout of its branch
+        let moved_repo = Repository::open(&to).unwrap();
+        assert_eq!(
+            moved_repo
+                .head()
+                .unwrap()
+                .target()
+                .map(|oid| oid.to_string()),
+            created.head
+        );
+        commit_file(&moved_repo, "moved.txt", "still works\n");
+        manager.delete(&to, true).unwrap();
+    }
+
+    #[test]
+    fn find_returns_worktree_at_path() {
+        let (temp, repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        let worktree_path = temp.path().join("agent-a");
+        manager
+            .create(
+                &worktree_path,
+                WorktreeBranch::New {
+                    name: "agent-a".to_string(),
+                    start_point: None,
+                },
+                CreateOptions::default(),
+            )
+            .unwrap();
+
+        let info = manager.find(&worktree_path).unwrap();
+        assert_eq!(info.path, worktree_path.canonicalize().unwrap());
+        assert_eq!(info.branch.as_deref(), Some("agent-a"));
+
+        let main = manager.find(temp.path()).unwrap();
+        assert_eq!(main.name, None);
+        assert_eq!(main.branch, Some(current_branch(&repo)));
+
+        assert!(matches!(
+            manager.find(temp.path().join("missing")),
+            Err(WorktreeError::WorktreeNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn list_filtered_selects_locked_worktrees() {
+        let (temp, repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        for name in ["agent-a", "agent-b"] {
+            manager
+                .create(
+                    temp.path().join(name),
+                    WorktreeBranch::New {
+                        name: name.to_string(),
+                        start_point: None,
+                    },
+                    CreateOptions::default(),
+                )
+                .unwrap();
+        }
+        repo.find_worktree("agent-b")
+            .unwrap()
+            .lock(Some("in use"))
+            .unwrap();
+
+        let locked = manager
+            .list_filtered(&WorktreeFilter {
+                locked_only: true,
+                ..WorktreeFilter::default()
+            })
+            .unwrap();
+        assert_eq!(locked.len(), 1);
+        assert_eq!(locked[0].name.as_deref(), Some("agent-b"));
+        assert_eq!(locked[0].lock_reason.as_deref(), Some("in use"));
+
+        let linked = manager
+            .list_filtered(&WorktreeFilter {
+                exclude_main: true,
+                ..WorktreeFilter::default()
+            })
+            .unwrap();
+        assert_eq!(linked.len(), 2);
+
+        let by_branch = manager
+            .list_filtered(&WorktreeFilter {
+                branch: Some("agent-a".to_string()),
+                ..WorktreeFilter::default()
+            })
+            .unwrap();
+        assert_eq!(by_branch.len(), 1);
+        assert_eq!(by_branch[0].name.as_deref(), Some("agent-a"));
+    }
+
+    #[test]
+    fn move_worktree_rejects_main_and_existing_paths() {
+        let (temp, _repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        let from = temp.path().join("agent-a");
+        manager
+            .create(
+                &from,
+                WorktreeBranch::New {
+                    name: "agent-a".to_string(),
+                    start_point: None,
+                },
+                CreateOptions::default(),
+            )
+            .unwrap();
+
+        let elsewhere = TempDir::new().unwrap();
+        assert!(
+            manager
+                .move_worktree(temp.path(), &elsewhere.path().join("main"))
+                .is_err()
+        );
+
+        let occupied = temp.path().join("occupied");
+        fs::create_dir(&occupied).unwrap();
+        assert!(matches!(
+            manager.move_worktree(&from, &occupied),
+            Err(WorktreeError::PathExists(path)) if path == occupied
+        ));
+        assert!(from.exists());
+    }
+
+    #[test]
+    fn errors_identify_existing_paths_and_missing_branches() {
+        let (temp, repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        let main_branch = current_branch(&repo);
+
+        let occupied = temp.path().join("occupied");
+        fs::create_dir(&occupied).unwrap();
+        let result = manager.create(
+            &occupied,
+            WorktreeBranch::New {
+                name: "agent-a".to_string(),
+                start_point: None,
+            },
+            CreateOptions::default(),
+        );
+        assert!(matches!(result, Err(WorktreeError::PathExists(path)) if path == occupied));
+
+        let result = manager.create(
+            temp.path().join("agent-b"),
+            WorktreeBranch::Existing("missing".to_string()),
+            CreateOptions::default(),
+        );
+        assert!(matches!(result, Err(WorktreeError::BranchNotFound(name)) if name == "missing"));
+
+        let result = manager.merge(
+            temp.path(),
+            &main_branch,
+            "missing",
+            MergeOptions::default(),
+        );
+        assert!(matches!(result, Err(WorktreeError::BranchNotFound(name)) if name == "missing"));
+
+        // An invalid branch name is a git error, not a missing branch
+        let result = manager.merge(
+            temp.path(),
+            &main_branch,
+            "bad..name",
+            MergeOptions::default(),
+        );
+        assert!(matches!(result, Err(WorktreeError::Git(_))));
+
+        fs::write(temp.path().join("dirty.txt"), "dirty\n").unwrap();
+        let result = manager.merge(
+            temp.path(),
+            &main_branch,
+            &main_branch,
+            MergeOptions::default(),
+        );
+        assert!(matches!(result, Err(WorktreeError::UncommittedChanges)));
+    }
+
+    #[test]
+    fn fetch_updates_remote_tracking_ref() {
+        let (remote_dir, remote_repo) = init_repo();
+        let main_branch = current_branch(&remote_repo);
+        let local_dir = TempDir::new().unwrap();
+        let url = format!("file://{}", remote_dir.path().display());
+        Repository::clone(&url, local_dir.path()).unwrap();
+        let manager = WorktreeManager::new(local_dir.path());
+
+        let new_commit = commit_file(&remote_repo, "remote.txt", "remote change\n");
+        manager.fetch("origin").unwrap();
+
+        let local = Repository::open(local_dir.path()).unwrap();
+        let tracking = local
+            .find_reference(&format!("refs/remotes/origin/{}", main_branch))
+            .unwrap();
+        assert_eq!(tracking.target(), Some(new_commit));
+
+        // A new branch can start from the freshly fetched remote branch
+        let start_point = format!("origin/{}", main_branch);
+        let info = manager
+            .create(
+                local_dir.path().join("agent-remote"),
+                WorktreeBranch::New {
+                    name: "agent-remote".to_string(),
+                    start_point: Some(start_point.clone()),
+                },
+                CreateOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(info.head, Some(new_commit.to_string()));
+
+        let branch = local
+            .find_branch("agent-remote", BranchType::Local)
+            .unwrap();
+        assert_eq!(
+            branch.upstream().unwrap().name().unwrap(),
+            Some(start_point.as_str())
+        );
+
+        assert!(manager.fetch("missing").is_err());
+    }
+
+    #[test]
+    fn cherry_pick_applies_commit_from_agent_branch() {
+        let (temp, repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        // Outside the main worktree, which must be clean to pick onto
+        let worktrees = TempDir::new().unwrap();
+        let worktree_path = worktrees.path().join("agent-a");
+        manager
+            .create(
+                &worktree_path,
+                WorktreeBranch::New {
+                    name: "agent-a".to_string(),
+                    start_point: None,
+                },
+                CreateOptions::default(),
+            )
+            .unwrap();
+
+        let agent_repo = Repository::open(&worktree_path).unwrap();
+        commit_file(&agent_repo, "skipped.txt", "not picked\n");
+        let picked = commit_file(&agent_repo, "feature.txt", "agent feature\n");
+        let main_head = repo.head().unwrap().target().unwrap();
+
+        let outcome = manager
+            .cherry_pick(temp.path(), &picked.to_string())
+            .unwrap();
+        assert_eq!(outcome, MergeOutcome::Merged);
+
+        assert_eq!(
+            fs::read_to_string(temp.path().join("feature.txt")).unwrap(),
+            "agent feature\n"
+        );
+        assert!(!temp.path().join("skipped.txt").exists());
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let original = repo.find_commit(picked).unwrap();
+        assert_ne!(head.id(), picked);
+        assert_eq!(head.parent_id(0).unwrap(), main_head);
+        assert_eq!(head.message(), original.message());
+    }
+
     #[test]
     fn merge_without_conflicts() {
         let (temp, repo) = init_repo();
@@ -552,4 +1513,130 @@ mod tests {
             MergeOutcome::Merged => panic!("expected merge conflicts"),
         }
     }
+
+    #[test]
+    fn abort_merge_leaves_a_clean_worktree() {
+        let (temp, repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        let main_branch = current_branch(&repo);
+
+        let base_commit = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("agent", &base_commit, false).unwrap();
+
+        let main_head = commit_file(&repo, "conflict.txt", "main change\n");
+
+        repo.set_head("refs/heads/agent").unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+        commit_file(&repo, "conflict.txt", "agent change\n");
+        commit_file(&repo, "agent.txt", "agent only\n");
+
+        repo.set_head(&format!("refs/heads/{}", main_branch))
+            .unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+
+        let outcome = manager
+            .merge(temp.path(), &main_branch, "agent", MergeOptions::default())
+            .unwrap();
+        assert!(matches!(outcome, MergeOutcome::Conflicts(_)));
+
+        manager.abort_merge(temp.path()).unwrap();
+
+        assert!(ensure_clean_repo(&repo).is_ok());
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert_eq!(repo.head().unwrap().target(), Some(main_head));
+        assert_eq!(
+            fs::read_to_string(temp.path().join("conflict.txt")).unwrap(),
+            "main change\n"
+        );
+        assert!(!temp.path().join("agent.txt").exists());
+    }
+
+    #[test]
+    fn list_conflicts_reads_content_only_on_demand() {
+        let (temp, repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        let main_branch = current_branch(&repo);
+
+        let base_commit = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("agent", &base_commit, false).unwrap();
+
+        commit_file(&repo, "conflict.txt", "main change\n");
+
+        repo.set_head("refs/heads/agent").unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+        commit_file(&repo, "conflict.txt", "agent change\n");
+
+        repo.set_head(&format!("refs/heads/{}", main_branch))
+            .unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+
+        let outcome = manager
+            .merge(temp.path(), &main_branch, "agent", MergeOptions::default())
+            .unwrap();
+        assert!(matches!(outcome, MergeOutcome::Conflicts(_)));
+
+        CONFLICT_READS.with(|reads| reads.set(0));
+        let entries = manager.list_conflicts(temp.path()).unwrap();
+        assert_eq!(
+            entries,
+            vec![ConflictEntry {
+                path: PathBuf::from("conflict.txt")
+            }]
+        );
+        assert_eq!(CONFLICT_READS.with(|reads| reads.get()), 0);
+
+        let conflict = manager.read_conflict(temp.path(), &entries[0]).unwrap();
+        assert_eq!(conflict.path, entries[0].path);
+        assert!(conflict.content.contains("<<<<<<<"));
+        assert_eq!(CONFLICT_READS.with(|reads| reads.get()), 1);
+    }
+
+    #[test]
+    fn diff3_conflict_includes_base_section() {
+        let (temp, repo) = init_repo();
+        let manager = WorktreeManager::new(temp.path());
+        let main_branch = current_branch(&repo);
+
+        commit_file(&repo, "conflict.txt", "base\n");
+        let base_commit = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("agent", &base_commit, false).unwrap();
+
+        commit_file(&repo, "conflict.txt", "main change\n");
+
+        repo.set_head("refs/heads/agent").unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+        commit_file(&repo, "conflict.txt", "agent change\n");
+
+        repo.set_head(&format!("refs/heads/{}", main_branch))
+            .unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+
+        let outcome = manager
+            .merge(
+                temp.path(),
+                &main_branch,
+                "agent",
+                MergeOptions {
+                    conflict_marker_size: Some(10),
+                    style: ConflictStyle::Diff3,
+                    ..MergeOptions::default()
+                },
+            )
+            .unwrap();
+
+        let MergeOutcome::Conflicts(conflicts) = outcome else {
+            panic!("expected merge conflicts");
+        };
+        let content = &conflicts[0].content;
+        assert!(content.contains("<<<<<<<<<< "));
+        assert!(content.contains("||||||||||"));
+        assert!(content.contains("base\n"));
+        assert!(content.contains(">>>>>>>>>> agent"));
+    }
 }
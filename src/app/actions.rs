@@ -3,7 +3,7 @@
 //! 本模块集中管理所有应用中使用的 GPUI Actions，便于维护和查找。
 //! Actions 是 GPUI 中用于触发用户操作的类型安全机制。
 
-use agent_client_protocol::{ImageContent, ToolCall};
+use agent_client_protocol::{ContentBlock, ImageContent, ToolCall};
 use gpui::{Action, SharedString, actions};
 use gpui_component::{ThemeMode, dock::DockPlacement, scroll::ScrollbarShow};
 use serde::Deserialize;
@@ -131,9 +131,42 @@ fn default_dock_placement() -> DockPlacement {
     DockPlacement::Center
 }
 
+/// 关闭会话面板
+///
+/// 关闭中间区域的所有会话面板，其他面板不受影响
+#[derive(Action, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[action(namespace = agent_studio, no_json)]
+pub struct CloseConversationPanels {
+    /// 只关闭没有进行中回合的会话面板
+    #[serde(default)]
+    pub idle_only: bool,
+    /// 同时取消被关闭会话中进行中的回合
+    #[serde(default)]
+    pub cancel_sessions: bool,
+}
+
 // 切换 Dock 切换按钮的显示状态 / 打开会话管理面板
 actions!(agent_studio, [ToggleDockToggleButton, OpenSessionManager]);
 
+// 打开命令面板
+actions!(agent_studio, [ToggleCommandPalette]);
+
+// 重新打开启动向导，重新配置 Node.js / Agent / 代理 / 工作区
+actions!(agent_studio, [ReopenStartup]);
+
+// 导出 / 导入 Dock 布局文件，保存当前布局为预设
+actions!(agent_studio, [ExportLayout, ImportLayout, SaveLayoutPreset]);
+
+// 保存代码编辑器当前标签页的文件
+actions!(agent_studio, [SaveFile]);
+
+/// 应用已保存的布局预设
+///
+/// 参数为预设名称
+#[derive(Action, Clone, PartialEq, Eq, Deserialize)]
+#[action(namespace = agent_studio, no_json)]
+pub struct ApplyLayoutPreset(pub SharedString);
+
 // ============================================================================
 // Task List Actions - 任务列表相关操作
 // ============================================================================
@@ -224,6 +257,12 @@ pub struct SendMessageToSession {
     pub images: Vec<(ImageContent, String)>,
     /// 附带的代码选择列表
     pub code_selections: Vec<AddCodeSelection>,
+    /// 拖放附加的文件（ACP 资源链接）
+    #[serde(default)]
+    pub resources: Vec<ContentBlock>,
+    /// 编辑已发送的消息时，被替换的用户消息序号（从 0 开始），该消息及之后的内容会被截断
+    #[serde(default)]
+    pub edit_turn: Option<usize>,
 }
 
 /// 取消会话
@@ -236,6 +275,17 @@ pub struct CancelSession {
     /// 会话唯一标识符
     pub session_id: String,
 }
+
+/// 重新生成最后一条回复
+///
+/// 丢弃会话最后一轮的 Agent 回复并重新发送之前的用户消息，由 ConversationPanel 触发
+/// 实际的重新发送逻辑在 workspace/actions.rs 中实现
+#[derive(Action, Clone, Debug, PartialEq, Deserialize)]
+#[action(namespace = agentx, no_json)]
+pub struct RegenerateResponse {
+    /// 会话唯一标识符
+    pub session_id: String,
+}
 /// 显示会话对话面板
 ///
 #[derive(Action, Clone, PartialEq, Deserialize)]
@@ -269,6 +319,51 @@ pub struct AddCodeSelection {
     pub content: String,
 }
 
+impl AddCodeSelection {
+    /// Format the selection as text context for the ACP prompt.
+    ///
+    /// Produces a markdown-style code block with file path and line range metadata,
+    /// suitable for inclusion as a `ContentBlock::Text` in the prompt.
+    pub fn to_prompt_context(&self) -> String {
+        let line_range = if self.start_line == self.end_line {
+            format!("Line {}", self.start_line)
+        } else {
+            format!("Lines {}-{}", self.start_line, self.end_line)
+        };
+
+        format!(
+            "```\n// File: {} ({})\n{}\n```",
+            self.file_path, line_range, self.content
+        )
+    }
+
+    /// Read back a selection formatted by [`Self::to_prompt_context`], e.g. when
+    /// a sent message is edited. Columns aren't part of the context and come
+    /// back as 1.
+    pub fn from_prompt_context(text: &str) -> Option<Self> {
+        let body = text.strip_prefix("```\n// File: ")?.strip_suffix("\n```")?;
+        let (header, content) = body.split_once('\n').unwrap_or((body, ""));
+        let (file_path, line_range) = header.strip_suffix(')')?.rsplit_once(" (")?;
+
+        let (start_line, end_line) = if let Some(line) = line_range.strip_prefix("Line ") {
+            let line = line.parse().ok()?;
+            (line, line)
+        } else {
+            let (start, end) = line_range.strip_prefix("Lines ")?.split_once('-')?;
+            (start.parse().ok()?, end.parse().ok()?)
+        };
+
+        Some(Self {
+            file_path: file_path.to_string(),
+            start_line,
+            start_column: 1,
+            end_line,
+            end_column: 1,
+            content: content.to_string(),
+        })
+    }
+}
+
 impl From<agentx_types::events::CodeSelectionData> for AddCodeSelection {
     fn from(data: agentx_types::events::CodeSelectionData) -> Self {
         Self {
@@ -302,6 +397,7 @@ actions!(
         About,         // 显示关于对话框
         Open,          // 打开文件或项目
         Quit,          // 退出应用
+        NewWindow,     // 打开新的工作区窗口
         CloseWindow,   // 关闭当前窗口
         ToggleSearch,  // 切换搜索面板
         TestAction,    // 测试用操作
@@ -411,6 +507,30 @@ pub struct RestartAgent {
     pub name: String,
 }
 
+/// 批量启用或禁用 Agent
+///
+/// 一次性更新多个 agent 的启用状态，并启动或停止对应进程
+#[derive(Action, Clone, PartialEq, Eq, Deserialize)]
+#[action(namespace = agent_config, no_json)]
+pub struct SetAgentsEnabled {
+    /// Agent names to update / 要更新的 Agent 名称
+    pub names: Vec<String>,
+    /// Whether the agents should run / 是否启用
+    pub enabled: bool,
+}
+
+/// 设置 Agent 图标
+///
+/// 为 agent 名称关联自定义图标并写入配置，传入 None 恢复默认图标
+#[derive(Action, Clone, PartialEq, Eq, Deserialize)]
+#[action(namespace = agent_config, no_json)]
+pub struct SetAgentIcon {
+    /// Agent name / Agent 名称
+    pub name: String,
+    /// Built-in logo name or SVG file path / 内置图标名称或 SVG 文件路径
+    pub icon: Option<String>,
+}
+
 /// 重新加载 Agent 配置
 ///
 /// 从 config.json 文件重新加载所有 agent 配置
@@ -437,3 +557,35 @@ pub struct ChangeConfigPath {
     /// Config file path / 配置文件路径
     pub path: std::path::PathBuf,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_selection_context_round_trips() {
+        let selection = AddCodeSelection {
+            file_path: "/tmp/project/src/main.rs".to_string(),
+            start_line: 3,
+            start_column: 1,
+            end_line: 5,
+            end_column: 1,
+            content: "fn main() {\n    run();\n}".to_string(),
+        };
+        let context = selection.to_prompt_context();
+
+        assert_eq!(
+            AddCodeSelection::from_prompt_context(&context),
+            Some(selection)
+        );
+
+        let single = AddCodeSelection::from_prompt_context(
+            "```\n// File: a (1).rs (Line 7)\nlet x = 1;\n```",
+        )
+        .unwrap();
+        assert_eq!(single.file_path, "a (1).rs");
+        assert_eq!((single.start_line, single.end_line), (7, 7));
+
+        assert_eq!(AddCodeSelection::from_prompt_context("plain text"), None);
+    }
+}
@@ -3,7 +3,7 @@ use gpui_component::{ThemeMode, ThemeRegistry};
 use rust_i18n::t;
 
 use crate::{
-    About, CloseWindow, Open, Quit, SelectLocale, ToggleSearch,
+    About, CloseWindow, NewWindow, Open, Quit, ReopenStartup, SelectLocale, ToggleSearch,
     app::actions::{SwitchTheme, SwitchThemeMode},
 };
 
@@ -15,6 +15,7 @@ pub fn init(title: impl Into<SharedString>, cx: &mut App) {
                 MenuItem::action(t!("menu.app.about").to_string(), About),
                 MenuItem::Separator,
                 MenuItem::action(t!("menu.app.open").to_string(), Open),
+                MenuItem::action(t!("menu.app.reopen_startup").to_string(), ReopenStartup),
                 MenuItem::Separator,
                 MenuItem::Submenu(Menu {
                     name: t!("menu.app.appearance").to_string().into(),
@@ -84,6 +85,7 @@ pub fn init(title: impl Into<SharedString>, cx: &mut App) {
         Menu {
             name: t!("menu.window.title").to_string().into(),
             items: vec![
+                MenuItem::action(t!("menu.window.new").to_string(), NewWindow),
                 MenuItem::action(t!("menu.window.close").to_string(), CloseWindow),
                 MenuItem::separator(),
                 MenuItem::action(t!("menu.window.toggle_search").to_string(), ToggleSearch),
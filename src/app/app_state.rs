@@ -1,3 +1,4 @@
+use anyhow::{Result, anyhow};
 use gpui::{App, AppContext, Entity, Global, SharedString};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -5,13 +6,15 @@ use std::sync::Arc;
 use crate::{
     core::agent::{AgentManager, PermissionStore},
     core::config::DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES,
-    core::event_bus::EventHub,
+    core::event_bus::{AgentConfigEvent, EventHub, WorkspaceUpdateEvent},
     core::services::{
-        AgentConfigService, AgentService, AiService, MessageService, PersistenceService,
-        WorkspaceService,
+        AgentConfigService, AgentService, AiService, ConfigWatcher, MessageService,
+        PersistenceService, SessionTitler, WorkspaceService,
     },
 };
 
+use agentx_services::config_watcher::DEFAULT_DEBOUNCE;
+
 use super::service_registry::ServiceRegistry;
 
 /// Welcome session info - stores the session created when user selects an agent
@@ -29,6 +32,8 @@ pub struct AppState {
     // Infrastructure
     agent_manager: Option<Arc<AgentManager>>,
     permission_store: Option<Arc<PermissionStore>>,
+    /// Agent used for new conversations, when one has been chosen
+    active_agent: Option<String>,
 
     /// Service registry — Clone + Send, can be captured in async closures
     pub services: ServiceRegistry,
@@ -57,7 +62,9 @@ impl AppState {
         // Create workspace service and set its bus
         let mut workspace_service = WorkspaceService::new(config_path);
         workspace_service.set_event_hub(event_hub.clone());
-        services.set_workspace_service(Arc::new(workspace_service));
+        let workspace_service = Arc::new(workspace_service);
+        services.set_session_titler(Arc::new(SessionTitler::new(workspace_service.clone())));
+        services.set_workspace_service(workspace_service);
 
         let sessions_dir = crate::core::config_manager::get_sessions_dir();
         services.set_persistence_service(Arc::new(PersistenceService::new(sessions_dir)));
@@ -66,6 +73,7 @@ impl AppState {
             invisible_panels: cx.new(|_| Vec::new()),
             agent_manager: None,
             permission_store: None,
+            active_agent: None,
             services,
             welcome_session: None,
             config_path: None,
@@ -167,7 +175,11 @@ impl AppState {
                 event_hub.clone(),
             );
             service.set_agent_service(agent_service.clone());
-            self.services.set_agent_config_service(Arc::new(service));
+            let service = Arc::new(service);
+            self.services.set_agent_config_service(service.clone());
+
+            // Hot-reload agents when config.json is edited outside the app
+            ConfigWatcher::new(config_path.clone(), service, DEFAULT_DEBOUNCE).spawn();
         } else {
             log::warn!("Config path not set, AgentConfigService will not be initialized");
         }
@@ -191,6 +203,14 @@ impl AppState {
         self.services.set_message_service(message_service);
         self.tool_call_preview_max_lines = initial_config.tool_call_preview_max_lines;
 
+        // Keep custom agent icons in step with the config file
+        crate::assets::set_agent_icons(&initial_config.agent_icons);
+        event_hub.subscribe_agent_config_updates(|event| {
+            if let AgentConfigEvent::ConfigReloaded { config } = event {
+                crate::assets::set_agent_icons(&config.agent_icons);
+            }
+        });
+
         log::info!(
             "Initialized service layer (AgentService, MessageService, PersistenceService, AgentConfigService, AiService)"
         );
@@ -220,6 +240,42 @@ impl AppState {
         self.agent_manager.as_ref()
     }
 
+    /// Get the agent new conversations default to
+    pub fn active_agent(&self) -> Option<&str> {
+        self.active_agent.as_deref()
+    }
+
+    /// Make `name` the active agent, publishing
+    /// [`WorkspaceUpdateEvent::ActiveAgentChanged`] when it changes.
+    ///
+    /// The running agents are listed in the background, so the change applies
+    /// once that completes; an agent that is not running is logged and ignored.
+    pub fn set_active_agent(name: String, cx: &mut App) {
+        let Some(manager) = Self::global(cx).agent_manager.clone() else {
+            log::warn!(
+                "Cannot set active agent '{}': AgentManager not initialized",
+                name
+            );
+            return;
+        };
+        cx.spawn(async move |cx| {
+            let available = manager.list_agents().await;
+            let result = cx.update(|cx| {
+                let this = Self::global_mut(cx);
+                change_active_agent(
+                    &mut this.active_agent,
+                    &name,
+                    &available,
+                    &this.services.event_hub,
+                )
+            });
+            if let Ok(Err(e)) = result {
+                log::warn!("Failed to set active agent: {}", e);
+            }
+        })
+        .detach();
+    }
+
     /// Get the PermissionStore if set
     pub fn permission_store(&self) -> Option<&Arc<PermissionStore>> {
         self.permission_store.as_ref()
@@ -282,15 +338,102 @@ impl AppState {
         &self.current_working_dir
     }
 
-    /// Set the current working directory
+    /// Set the current working directory, publishing
+    /// [`WorkspaceUpdateEvent::CwdChanged`] when it differs from the old one
     pub fn set_current_working_dir(&mut self, path: PathBuf) {
         log::info!("Setting current working directory: {:?}", path);
-        self.current_working_dir = path;
+        change_working_dir(
+            &mut self.current_working_dir,
+            path,
+            &self.services.event_hub,
+        );
     }
 
     /// Get the tool call preview line limit
     pub fn tool_call_preview_max_lines(&self) -> usize {
         self.tool_call_preview_max_lines
     }
+
+    /// Set the tool call preview line limit used by newly opened conversations
+    pub fn set_tool_call_preview_max_lines(&mut self, max_lines: usize) {
+        self.tool_call_preview_max_lines = max_lines;
+    }
 }
 impl Global for AppState {}
+
+fn change_working_dir(current: &mut PathBuf, path: PathBuf, event_hub: &EventHub) {
+    if *current == path {
+        return;
+    }
+    *current = path.clone();
+    event_hub.publish_workspace_update(WorkspaceUpdateEvent::CwdChanged { path });
+}
+
+fn change_active_agent(
+    current: &mut Option<String>,
+    name: &str,
+    available: &[String],
+    event_hub: &EventHub,
+) -> Result<()> {
+    if !available.iter().any(|agent| agent == name) {
+        return Err(anyhow!("Unknown agent: {}", name));
+    }
+    if current.as_deref() == Some(name) {
+        return Ok(());
+    }
+    *current = Some(name.to_string());
+    event_hub.publish_workspace_update(WorkspaceUpdateEvent::ActiveAgentChanged {
+        agent_name: name.to_string(),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn changing_cwd_publishes_one_event() {
+        let event_hub = EventHub::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        event_hub.subscribe_cwd_changes(move |path| {
+            received_clone.lock().unwrap().push(path.to_path_buf());
+        });
+
+        let mut cwd = PathBuf::from("/projects/old");
+        change_working_dir(&mut cwd, PathBuf::from("/projects/new"), &event_hub);
+        assert_eq!(cwd, PathBuf::from("/projects/new"));
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![PathBuf::from("/projects/new")]
+        );
+
+        // Setting the same directory again is not a change
+        change_working_dir(&mut cwd, PathBuf::from("/projects/new"), &event_hub);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn active_agent_changes_only_to_known_agents() {
+        let event_hub = EventHub::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        event_hub.subscribe_active_agent_changes(move |name| {
+            received_clone.lock().unwrap().push(name.to_string());
+        });
+
+        let available = vec!["claude".to_string(), "codex".to_string()];
+        let mut active = None;
+        change_active_agent(&mut active, "codex", &available, &event_hub).unwrap();
+        assert_eq!(active.as_deref(), Some("codex"));
+
+        assert!(change_active_agent(&mut active, "missing", &available, &event_hub).is_err());
+        assert_eq!(active.as_deref(), Some("codex"));
+
+        // Re-selecting the active agent is not a change
+        change_active_agent(&mut active, "codex", &available, &event_hub).unwrap();
+        assert_eq!(*received.lock().unwrap(), vec!["codex".to_string()]);
+    }
+}
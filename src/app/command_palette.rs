@@ -0,0 +1,95 @@
+use gpui_component::{ThemeMode, dock::DockPlacement};
+use rust_i18n::t;
+
+use crate::{
+    ExportLayout, ImportLayout, NewWindow, Open, OpenSessionManager, PanelAction, Quit,
+    ReopenStartup, SaveLayoutPreset, ToggleDockToggleButton, ToggleSearch,
+    app::{actions::SwitchThemeMode, title_bar::OpenSettings},
+    components::PaletteCommand,
+};
+
+/// Actions reachable from the command palette, in display order
+pub fn palette_commands() -> Vec<PaletteCommand> {
+    let workspace = t!("command_palette.category.workspace").to_string();
+    let layout = t!("command_palette.category.layout").to_string();
+    let appearance = t!("command_palette.category.appearance").to_string();
+    let application = t!("command_palette.category.application").to_string();
+
+    vec![
+        PaletteCommand::new(
+            workspace.clone(),
+            t!("command_palette.action.new_conversation").to_string(),
+            PanelAction::add_conversation(DockPlacement::Center),
+        ),
+        PaletteCommand::new(
+            workspace.clone(),
+            t!("command_palette.action.show_welcome").to_string(),
+            PanelAction::show_welcome(None),
+        ),
+        PaletteCommand::new(
+            workspace.clone(),
+            t!("command_palette.action.open_session_manager").to_string(),
+            OpenSessionManager,
+        ),
+        PaletteCommand::new(
+            workspace.clone(),
+            t!("command_palette.action.open_settings").to_string(),
+            OpenSettings,
+        ),
+        PaletteCommand::new(
+            workspace.clone(),
+            t!("command_palette.action.toggle_search").to_string(),
+            ToggleSearch,
+        ),
+        PaletteCommand::new(
+            workspace.clone(),
+            t!("command_palette.action.open_folder").to_string(),
+            Open,
+        ),
+        PaletteCommand::new(
+            workspace,
+            t!("command_palette.action.new_window").to_string(),
+            NewWindow,
+        ),
+        PaletteCommand::new(
+            layout.clone(),
+            t!("command_palette.action.toggle_dock_button").to_string(),
+            ToggleDockToggleButton,
+        ),
+        PaletteCommand::new(
+            layout.clone(),
+            t!("command_palette.action.export_layout").to_string(),
+            ExportLayout,
+        ),
+        PaletteCommand::new(
+            layout.clone(),
+            t!("command_palette.action.import_layout").to_string(),
+            ImportLayout,
+        ),
+        PaletteCommand::new(
+            layout,
+            t!("command_palette.action.save_layout_preset").to_string(),
+            SaveLayoutPreset,
+        ),
+        PaletteCommand::new(
+            appearance.clone(),
+            t!("command_palette.action.light_mode").to_string(),
+            SwitchThemeMode(ThemeMode::Light),
+        ),
+        PaletteCommand::new(
+            appearance,
+            t!("command_palette.action.dark_mode").to_string(),
+            SwitchThemeMode(ThemeMode::Dark),
+        ),
+        PaletteCommand::new(
+            application.clone(),
+            t!("command_palette.action.reopen_startup").to_string(),
+            ReopenStartup,
+        ),
+        PaletteCommand::new(
+            application,
+            t!("command_palette.action.quit").to_string(),
+            Quit,
+        ),
+    ]
+}
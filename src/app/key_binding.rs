@@ -1,40 +1,235 @@
-use gpui::{App, KeyBinding};
+use std::collections::HashMap;
 
-use crate::app::actions::{Open, Paste, Quit, ToggleSearch};
+use agentx_acp_ui::{
+    MESSAGE_STREAM_CONTEXT, SelectNextToolCall, SelectPrevToolCall, ToggleSelectedToolCall,
+    ToggleStreamSearch,
+};
+use gpui::{Action, App, Global, KeyBinding, Keystroke, NoAction};
+
+use crate::app::actions::{Open, Paste, Quit, SaveFile, ToggleCommandPalette, ToggleSearch};
 use gpui_term::{Clear, Copy, SelectAll};
 
-// 导出KeyBinding设置函数,供主应用使用
-pub fn init(cx: &mut App) {
-    cx.bind_keys([
-        KeyBinding::new("/", ToggleSearch, None),
+/// A default binding that can be remapped from the `keybindings` config section
+struct BindingSpec {
+    /// Qualified action name used as the config key, e.g. `agent_studio::Open`
+    action: &'static str,
+    default: &'static str,
+    context: Option<&'static str>,
+    build: fn(&str) -> KeyBinding,
+}
+
+macro_rules! binding {
+    ($keys:expr, $action:expr, $context:expr) => {
+        BindingSpec {
+            action: Action::name(&$action),
+            default: $keys,
+            context: $context,
+            build: |keys| KeyBinding::new(keys, $action, $context),
+        }
+    };
+}
+
+// 默认快捷键，可通过配置文件中的 keybindings 覆盖
+fn default_bindings() -> Vec<BindingSpec> {
+    vec![
+        binding!("/", ToggleSearch, None),
+        binding!("/", ToggleStreamSearch, Some(MESSAGE_STREAM_CONTEXT)),
+        binding!("down", SelectNextToolCall, Some(MESSAGE_STREAM_CONTEXT)),
+        binding!("up", SelectPrevToolCall, Some(MESSAGE_STREAM_CONTEXT)),
+        binding!(
+            "enter",
+            ToggleSelectedToolCall,
+            Some(MESSAGE_STREAM_CONTEXT)
+        ),
+        #[cfg(target_os = "macos")]
+        binding!("cmd-shift-p", ToggleCommandPalette, None),
+        #[cfg(not(target_os = "macos"))]
+        binding!("ctrl-shift-p", ToggleCommandPalette, None),
         #[cfg(target_os = "macos")]
-        KeyBinding::new("cmd-o", Open, None),
+        binding!("cmd-o", Open, None),
         #[cfg(not(target_os = "macos"))]
-        KeyBinding::new("ctrl-o", Open, None),
+        binding!("ctrl-o", Open, None),
         #[cfg(target_os = "macos")]
-        KeyBinding::new("cmd-q", Quit, None),
+        binding!("cmd-q", Quit, None),
         #[cfg(not(target_os = "macos"))]
-        KeyBinding::new("alt-f4", Quit, None),
+        binding!("alt-f4", Quit, None),
         #[cfg(target_os = "macos")]
-        KeyBinding::new("cmd-v", Paste, None),
+        binding!("cmd-v", Paste, None),
         #[cfg(not(target_os = "macos"))]
-        KeyBinding::new("ctrl-v", Paste, None),
+        binding!("ctrl-v", Paste, None),
+        #[cfg(target_os = "macos")]
+        binding!("cmd-s", SaveFile, Some("CodeEditor")),
+        #[cfg(not(target_os = "macos"))]
+        binding!("ctrl-s", SaveFile, Some("CodeEditor")),
         // Terminal keybindings
         #[cfg(target_os = "macos")]
-        KeyBinding::new("cmd-c", Copy, Some("Terminal")),
+        binding!("cmd-c", Copy, Some("Terminal")),
         #[cfg(not(target_os = "macos"))]
-        KeyBinding::new("ctrl-shift-c", Copy, Some("Terminal")),
+        binding!("ctrl-shift-c", Copy, Some("Terminal")),
         #[cfg(target_os = "macos")]
-        KeyBinding::new("cmd-v", gpui_term::Paste, Some("Terminal")),
+        binding!("cmd-v", gpui_term::Paste, Some("Terminal")),
         #[cfg(not(target_os = "macos"))]
-        KeyBinding::new("ctrl-shift-v", gpui_term::Paste, Some("Terminal")),
+        binding!("ctrl-shift-v", gpui_term::Paste, Some("Terminal")),
         #[cfg(target_os = "macos")]
-        KeyBinding::new("cmd-k", Clear, Some("Terminal")),
+        binding!("cmd-k", Clear, Some("Terminal")),
         #[cfg(not(target_os = "macos"))]
-        KeyBinding::new("ctrl-shift-k", Clear, Some("Terminal")),
+        binding!("ctrl-shift-k", Clear, Some("Terminal")),
         #[cfg(target_os = "macos")]
-        KeyBinding::new("cmd-a", SelectAll, Some("Terminal")),
+        binding!("cmd-a", SelectAll, Some("Terminal")),
         #[cfg(not(target_os = "macos"))]
-        KeyBinding::new("ctrl-shift-a", SelectAll, Some("Terminal")),
-    ]);
+        binding!("ctrl-shift-a", SelectAll, Some("Terminal")),
+    ]
+}
+
+/// A binding as currently applied, listed on the settings page
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveKeyBinding {
+    pub action: &'static str,
+    pub keystrokes: String,
+    pub context: Option<&'static str>,
+    /// Whether `keystrokes` comes from the config rather than the defaults
+    pub customized: bool,
+}
+
+/// Bindings applied by [`init`] and [`apply_config`]
+#[derive(Clone, Debug, Default)]
+pub struct ActiveKeyBindings(pub Vec<ActiveKeyBinding>);
+
+impl Global for ActiveKeyBindings {}
+
+const MODIFIERS: &[&str] = &[
+    "ctrl",
+    "alt",
+    "shift",
+    "cmd",
+    "super",
+    "win",
+    "fn",
+    "secondary",
+    "platform",
+];
+
+/// Whether `chord` is a valid sequence of keystrokes such as `cmd-k cmd-s`
+pub fn is_valid_chord(chord: &str) -> bool {
+    let mut keystrokes = chord.split_whitespace().peekable();
+    keystrokes.peek().is_some()
+        && keystrokes.all(|keystroke| {
+            let (modifiers, key) = split_keystroke(keystroke);
+            !key.is_empty()
+                && !MODIFIERS.contains(&key)
+                && (modifiers.is_empty() || modifiers.split('-').all(|m| MODIFIERS.contains(&m)))
+                && Keystroke::parse(keystroke).is_ok()
+        })
+}
+
+/// Split `ctrl-shift-p` into `("ctrl-shift", "p")`. `-` is both the separator
+/// and a key, as in `ctrl--`.
+fn split_keystroke(keystroke: &str) -> (&str, &str) {
+    if keystroke == "-" {
+        return ("", "-");
+    }
+    if let Some(modifiers) = keystroke.strip_suffix("--") {
+        return (modifiers, "-");
+    }
+    keystroke.rsplit_once('-').unwrap_or(("", keystroke))
+}
+
+/// The chord to bind: the configured one when valid, otherwise the default
+pub fn resolve_chord<'a>(action: &str, configured: Option<&'a str>, default: &'a str) -> &'a str {
+    match configured.map(str::trim) {
+        Some(chord) if is_valid_chord(chord) => chord,
+        Some(chord) => {
+            log::warn!(
+                "Invalid keybinding '{}' for {}, using default '{}'",
+                chord,
+                action,
+                default
+            );
+            default
+        }
+        None => default,
+    }
+}
+
+// 导出KeyBinding设置函数,供主应用使用
+pub fn init(cx: &mut App) {
+    let specs = default_bindings();
+    cx.bind_keys(specs.iter().map(|spec| (spec.build)(spec.default)));
+    cx.set_global(ActiveKeyBindings(
+        specs
+            .iter()
+            .map(|spec| ActiveKeyBinding {
+                action: spec.action,
+                keystrokes: spec.default.to_string(),
+                context: spec.context,
+                customized: false,
+            })
+            .collect(),
+    ));
+}
+
+/// Apply the `keybindings` config section (action name -> chord) on top of
+/// the defaults bound by [`init`]
+pub fn apply_config(overrides: &HashMap<String, String>, cx: &mut App) {
+    for action in overrides.keys() {
+        if !default_bindings().iter().any(|spec| spec.action == action) {
+            log::warn!("Ignoring keybinding for unknown action: {}", action);
+        }
+    }
+
+    let mut bindings = Vec::new();
+    let mut active = Vec::new();
+    for spec in default_bindings() {
+        let keystrokes = resolve_chord(
+            spec.action,
+            overrides.get(spec.action).map(String::as_str),
+            spec.default,
+        );
+        let customized = keystrokes != spec.default;
+        if customized {
+            // Later bindings win, so unbind the default before adding the new chord
+            bindings.push(KeyBinding::new(spec.default, NoAction, spec.context));
+            bindings.push((spec.build)(keystrokes));
+        }
+        active.push(ActiveKeyBinding {
+            action: spec.action,
+            keystrokes: keystrokes.to_string(),
+            context: spec.context,
+            customized,
+        });
+    }
+
+    cx.bind_keys(bindings);
+    cx.set_global(ActiveKeyBindings(active));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_well_formed_chords() {
+        assert!(is_valid_chord("cmd-o"));
+        assert!(is_valid_chord("ctrl-shift-p"));
+        assert!(is_valid_chord("/"));
+        assert!(is_valid_chord("cmd-k cmd-s"));
+        assert!(is_valid_chord("ctrl--"));
+    }
+
+    #[test]
+    fn invalid_chord_falls_back_to_default() {
+        for chord in ["", "ctrl-shift", "hyper-p", "cmd-k ctrl-"] {
+            assert!(!is_valid_chord(chord), "{chord:?} should be rejected");
+            assert_eq!(
+                resolve_chord("agent_studio::Open", Some(chord), "cmd-o"),
+                "cmd-o"
+            );
+        }
+
+        assert_eq!(
+            resolve_chord("agent_studio::Open", Some(" ctrl-alt-o "), "cmd-o"),
+            "ctrl-alt-o"
+        );
+        assert_eq!(resolve_chord("agent_studio::Open", None, "cmd-o"), "cmd-o");
+    }
 }
@@ -1,6 +1,7 @@
 pub mod actions;
 pub mod app_menus;
 pub mod app_state;
+pub mod command_palette;
 pub mod key_binding;
 pub mod menu;
 pub mod service_registry;
@@ -4,7 +4,7 @@ use crate::core::{
     event_bus::EventHub,
     services::{
         AgentConfigService, AgentService, AiService, MessageService, PersistenceService,
-        WorkspaceService,
+        SessionTitler, WorkspaceService,
     },
 };
 
@@ -21,6 +21,7 @@ pub struct ServiceRegistry {
     workspace_service: Option<Arc<WorkspaceService>>,
     agent_config_service: Option<Arc<AgentConfigService>>,
     ai_service: Option<Arc<AiService>>,
+    session_titler: Option<Arc<SessionTitler>>,
 }
 
 impl ServiceRegistry {
@@ -33,6 +34,7 @@ impl ServiceRegistry {
             workspace_service: None,
             agent_config_service: None,
             ai_service: None,
+            session_titler: None,
         }
     }
 
@@ -72,6 +74,12 @@ impl ServiceRegistry {
             .ok_or_else(|| anyhow::anyhow!("AiService not initialized"))
     }
 
+    pub fn session_titler(&self) -> anyhow::Result<&Arc<SessionTitler>> {
+        self.session_titler
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("SessionTitler not initialized"))
+    }
+
     // --- Setters (used by AppState during initialization) ---
 
     pub(crate) fn set_agent_service(&mut self, service: Arc<AgentService>) {
@@ -97,4 +105,8 @@ impl ServiceRegistry {
     pub(crate) fn set_ai_service(&mut self, service: Arc<AiService>) {
         self.ai_service = Some(service);
     }
+
+    pub(crate) fn set_session_titler(&mut self, titler: Arc<SessionTitler>) {
+        self.session_titler = Some(titler);
+    }
 }
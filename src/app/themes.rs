@@ -1,7 +1,9 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
+use anyhow::{Context, Result, bail};
 use gpui::{App, SharedString, px};
-use gpui_component::{ActiveTheme, Theme, ThemeRegistry, scroll::ScrollbarShow};
+use gpui_component::{ActiveTheme, Theme, ThemeConfig, ThemeRegistry, scroll::ScrollbarShow};
 use serde::{Deserialize, Serialize};
 
 use crate::app::actions::{SwitchTheme, SwitchThemeMode};
@@ -13,7 +15,9 @@ struct State {
     scrollbar_show: Option<ScrollbarShow>,
     #[serde(default)]
     app_settings: Option<AppSettings>,
-    #[serde(default)]
+    /// Superseded by `AppSettings::setup_completed`; only read to migrate
+    /// older state files
+    #[serde(default, skip_serializing)]
     startup_completed: bool,
 }
 
@@ -43,17 +47,14 @@ fn write_state_file(state: &State) {
     }
 }
 
-pub(crate) fn startup_completed() -> bool {
-    load_state_file().startup_completed
-}
-
-pub(crate) fn set_startup_completed(completed: bool) {
-    let mut state = load_state_file();
-    if state.startup_completed == completed {
-        return;
+/// App settings saved in `state`, carrying over the startup flag written by
+/// older versions
+fn app_settings_from_state(state: &State) -> AppSettings {
+    let mut app_settings = state.app_settings.clone().unwrap_or_default();
+    if state.startup_completed {
+        app_settings.setup_completed = true;
     }
-    state.startup_completed = completed;
-    write_state_file(&state);
+    app_settings
 }
 
 pub fn init(cx: &mut App) {
@@ -65,7 +66,7 @@ pub fn init(cx: &mut App) {
     tracing::info!("Load themes and app settings from: {:?}", state_file);
 
     // Initialize AppSettings globally (before it was only initialized in SettingsPanel::new)
-    let app_settings = state.app_settings.unwrap_or_else(AppSettings::default);
+    let app_settings = app_settings_from_state(&state);
     tracing::info!(
         "Loaded app_settings with font_size: {}",
         app_settings.font_size
@@ -179,15 +180,180 @@ pub fn init(cx: &mut App) {
     });
 }
 
+/// Colors a theme must define to be imported
+const REQUIRED_THEME_COLORS: &[&str] = &[
+    "background",
+    "foreground",
+    "border",
+    "primary.background",
+    "primary.foreground",
+];
+
+/// Apply the theme in `path` to the active theme. The file is either a theme
+/// set like those in the themes directory, whose first theme is used, or a
+/// single theme. An invalid file leaves the active theme untouched.
+pub fn import_theme(path: &Path, cx: &mut App) -> Result<()> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read theme {}", path.display()))?;
+    let config = parse_theme(&json)?;
+
+    Theme::global_mut(cx).apply_config(&Rc::new(config));
+    // User font size takes precedence over the theme's
+    let font_size = AppSettings::global(cx).font_size;
+    Theme::global_mut(cx).font_size = px(font_size as f32);
+    cx.refresh_windows();
+    Ok(())
+}
+
+/// Write the active theme to `path` as a theme set, so it can be imported
+/// again or dropped into the themes directory
+pub fn export_theme(path: &Path, cx: &App) -> Result<()> {
+    let theme = cx.theme();
+    let config = if theme.mode.is_dark() {
+        &theme.dark_theme
+    } else {
+        &theme.light_theme
+    };
+    std::fs::write(path, theme_to_json(config)?)
+        .with_context(|| format!("failed to write theme {}", path.display()))
+}
+
+fn parse_theme(json: &str) -> Result<ThemeConfig> {
+    let mut value: serde_json::Value = serde_json::from_str(json).context("invalid theme JSON")?;
+    if value.get("themes").is_some() {
+        value = value["themes"]
+            .get(0)
+            .cloned()
+            .context("theme file contains no themes")?;
+    }
+
+    let colors = value
+        .get("colors")
+        .and_then(|colors| colors.as_object())
+        .context("theme has no colors")?;
+    for key in REQUIRED_THEME_COLORS {
+        if !colors.contains_key(*key) {
+            bail!("theme is missing color \"{}\"", key);
+        }
+    }
+    for (key, color) in colors {
+        if !color.as_str().is_some_and(is_hex_color) {
+            bail!("invalid color for \"{}\": {}", key, color);
+        }
+    }
+
+    serde_json::from_value(value).context("invalid theme")
+}
+
+fn theme_to_json(config: &ThemeConfig) -> Result<String> {
+    let set = serde_json::json!({
+        "name": config.name,
+        "themes": [config],
+    });
+    Ok(serde_json::to_string_pretty(&set)?)
+}
+
+/// `#rgb`, `#rrggbb` or `#rrggbbaa`
+fn is_hex_color(color: &str) -> bool {
+    color.strip_prefix('#').is_some_and(|hex| {
+        matches!(hex.len(), 3 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
 /// Helper function to save current state to file
 pub(crate) fn save_state(cx: &mut App) {
-    let existing_state = load_state_file();
     let state = State {
         theme: cx.theme().theme_name().clone(),
         scrollbar_show: Some(cx.theme().scrollbar_show),
         app_settings: Some(AppSettings::global(cx).clone()),
-        startup_completed: existing_state.startup_completed,
+        startup_completed: false,
     };
 
     write_state_file(&state);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::panels::SkippedSetupSteps;
+
+    #[test]
+    fn setup_progress_round_trips_through_state_file() {
+        let mut app_settings = AppSettings::default();
+        app_settings.setup_completed = true;
+        app_settings.skipped_setup_steps = SkippedSetupSteps {
+            nodejs: false,
+            agents: true,
+            proxy: true,
+        };
+        let state = State {
+            app_settings: Some(app_settings),
+            ..State::default()
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(!json.contains("startup_completed"));
+        let loaded = app_settings_from_state(&serde_json::from_str(&json).unwrap());
+
+        assert!(loaded.setup_completed);
+        assert_eq!(
+            loaded.skipped_setup_steps,
+            SkippedSetupSteps {
+                nodejs: false,
+                agents: true,
+                proxy: true,
+            }
+        );
+    }
+
+    #[test]
+    fn migrates_legacy_startup_flag() {
+        let json = r#"{
+            "theme": "Default Light",
+            "scrollbar_show": null,
+            "app_settings": null,
+            "startup_completed": true
+        }"#;
+
+        let loaded = app_settings_from_state(&serde_json::from_str(json).unwrap());
+
+        assert!(loaded.setup_completed);
+        assert_eq!(loaded.skipped_setup_steps, SkippedSetupSteps::default());
+    }
+
+    #[test]
+    fn exported_theme_imports_with_identical_colors() {
+        let original = parse_theme(include_str!("../../themes/ayu.json")).unwrap();
+
+        let exported = theme_to_json(&original).unwrap();
+        let imported = parse_theme(&exported).unwrap();
+
+        assert_eq!(imported.name, original.name);
+        assert_eq!(
+            serde_json::to_value(&imported.colors).unwrap(),
+            serde_json::to_value(&original.colors).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_themes_missing_colors_or_with_invalid_ones() {
+        let missing =
+            r##"{ "name": "Broken", "mode": "light", "colors": { "background": "#fff" } }"##;
+        let error = parse_theme(missing).unwrap_err().to_string();
+        assert!(error.contains("foreground"), "{error}");
+
+        let invalid = r##"{
+            "name": "Broken",
+            "mode": "light",
+            "colors": {
+                "background": "#fff",
+                "foreground": "#000",
+                "border": "#ccc",
+                "primary.background": "blue",
+                "primary.foreground": "#fff"
+            }
+        }"##;
+        let error = parse_theme(invalid).unwrap_err().to_string();
+        assert!(error.contains("primary.background"), "{error}");
+    }
+}
@@ -3,6 +3,9 @@ use gpui::*;
 use gpui_component::IconNamed;
 use rust_embed::RustEmbed;
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
 
 #[derive(RustEmbed)]
 #[folder = "./assets"]
@@ -23,9 +26,19 @@ pub struct ThemeAssets;
 
 impl AssetSource for Assets {
     fn load(&self, path: &str) -> Result<Option<Cow<'static, [u8]>>> {
-        Self::get(path)
-            .map(|f| Some(f.data))
-            .ok_or_else(|| anyhow!("could not find asset at path \"{path}\""))
+        if let Some(file) = Self::get(path) {
+            return Ok(Some(file.data));
+        }
+        // Custom agent icons are SVG files outside the bundle
+        if Path::new(path).is_absolute() {
+            if !is_allowed_icon_path(Path::new(path), &allowed_icon_dirs()) {
+                return Err(anyhow!(
+                    "refusing to load asset outside icon folders: \"{path}\""
+                ));
+            }
+            return Ok(Some(Cow::Owned(std::fs::read(path)?)));
+        }
+        Err(anyhow!("could not find asset at path \"{path}\""))
     }
 
     fn list(&self, path: &str) -> Result<Vec<SharedString>> {
@@ -66,6 +79,33 @@ pub enum Icon {
     MoveRight,
     TextWrap,
     ArrowRightToLine,
+    /// An SVG file registered with [`register_agent_icon`]
+    Custom(SharedString),
+}
+
+impl Icon {
+    /// Agent logo by its config name, e.g. "gemini"
+    pub fn logo(name: &str) -> Option<Self> {
+        let icon = match name.to_lowercase().as_str() {
+            "augment" | "augment_code" => Icon::AugmentCode,
+            "claude" => Icon::Claude,
+            "cursor" => Icon::Cursor,
+            "deepseek" => Icon::DeepSeek,
+            "gemini" => Icon::Gemini,
+            "iflow" => Icon::Iflow,
+            "kimi" => Icon::Kimi,
+            "mcp" => Icon::MCP,
+            "minimax" => Icon::Minimax,
+            "moonshot" => Icon::Moonshot,
+            "openai" => Icon::OpenAI,
+            "opencode" => Icon::OpenCode,
+            "qoder" => Icon::Qoder,
+            "qwen" => Icon::Qwen,
+            "zai" => Icon::Zai,
+            _ => return None,
+        };
+        Some(icon)
+    }
 }
 
 impl IconNamed for Icon {
@@ -101,13 +141,76 @@ impl IconNamed for Icon {
             Icon::MoveRight => "icons2/move-right.svg",
             Icon::TextWrap => "icons2/text-wrap.svg",
             Icon::ArrowRightToLine => "icons2/arrow-right-to-line.svg",
+            Icon::Custom(path) => return path,
         }
         .into()
     }
 }
 
-/// Get icon based on agent name
+/// Icons registered for agent names, from the `agent_icons` config section
+static AGENT_ICONS: LazyLock<RwLock<HashMap<String, String>>> = LazyLock::new(Default::default);
+
+/// Show `icon` for the agent called `name`. `icon` is a built-in logo name
+/// (see [`Icon::logo`]) or the absolute path of an SVG file.
+pub fn register_agent_icon(name: impl Into<String>, icon: impl Into<String>) {
+    AGENT_ICONS
+        .write()
+        .unwrap()
+        .insert(name.into(), icon.into());
+}
+
+/// Go back to the default icon for `name`
+pub fn unregister_agent_icon(name: &str) {
+    AGENT_ICONS.write().unwrap().remove(name);
+}
+
+/// Replace every registered icon with `icons`
+pub fn set_agent_icons(icons: &HashMap<String, String>) {
+    *AGENT_ICONS.write().unwrap() = icons.clone();
+}
+
+/// Folders custom icons may be read from: the user data directory and the
+/// folders holding the SVG files registered in the `agent_icons` config
+fn allowed_icon_dirs() -> Vec<PathBuf> {
+    let registered: Vec<PathBuf> = AGENT_ICONS
+        .read()
+        .unwrap()
+        .values()
+        .map(Path::new)
+        .filter(|icon| icon.is_absolute())
+        .filter_map(|icon| icon.parent().map(Path::to_path_buf))
+        .collect();
+    crate::core::config_manager::get_user_data_dir()
+        .ok()
+        .into_iter()
+        .chain(registered)
+        .filter_map(|dir| dir.canonicalize().ok())
+        .collect()
+}
+
+/// Whether `path` is an SVG file inside one of `allowed_dirs`, after
+/// resolving `..` and symlinks
+fn is_allowed_icon_path(path: &Path, allowed_dirs: &[PathBuf]) -> bool {
+    if path.extension().and_then(|ext| ext.to_str()) != Some("svg") {
+        return false;
+    }
+    let Ok(path) = path.canonicalize() else {
+        return false;
+    };
+    allowed_dirs.iter().any(|dir| path.starts_with(dir))
+}
+
+/// Get the icon for an agent: the registered one if any, otherwise one
+/// guessed from its name
 pub fn get_agent_icon(name: &str) -> Icon {
+    let registered = AGENT_ICONS.read().unwrap().get(name).cloned();
+    match registered {
+        Some(icon) => Icon::logo(&icon).unwrap_or_else(|| Icon::Custom(icon.into())),
+        None => default_agent_icon(name),
+    }
+}
+
+fn default_agent_icon(name: &str) -> Icon {
     let name_lower = name.to_lowercase();
     // TODO Check for specific agent names
     if name_lower.contains("augment") {
@@ -167,3 +270,47 @@ pub fn get_embedded_themes() -> Vec<(String, String)> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_icon_overrides_name_lookup() {
+        assert_eq!(get_agent_icon("claude-helper").path(), "logo/claude.svg");
+
+        register_agent_icon("claude-helper", "gemini");
+        assert_eq!(get_agent_icon("claude-helper").path(), "logo/gemini.svg");
+
+        register_agent_icon("claude-helper", "/home/me/icons/helper.svg");
+        assert_eq!(
+            get_agent_icon("claude-helper").path(),
+            "/home/me/icons/helper.svg"
+        );
+
+        unregister_agent_icon("claude-helper");
+        assert_eq!(get_agent_icon("claude-helper").path(), "logo/claude.svg");
+    }
+
+    #[test]
+    fn only_svg_files_in_allowed_folders_are_loaded() {
+        let root = std::env::temp_dir().join(format!("agentx_icons_{}", std::process::id()));
+        let icons = root.join("icons");
+        std::fs::create_dir_all(&icons).unwrap();
+        for file in ["icons/agent.svg", "icons/notes.txt", "secret.svg"] {
+            std::fs::write(root.join(file), "<svg/>").unwrap();
+        }
+        let allowed = [icons.canonicalize().unwrap()];
+
+        let results = [
+            is_allowed_icon_path(&icons.join("agent.svg"), &allowed),
+            is_allowed_icon_path(&icons.join("notes.txt"), &allowed),
+            is_allowed_icon_path(&root.join("secret.svg"), &allowed),
+            is_allowed_icon_path(&icons.join("../secret.svg"), &allowed),
+            is_allowed_icon_path(&icons.join("missing.svg"), &allowed),
+        ];
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(results, [true, false, false, false, false]);
+    }
+}
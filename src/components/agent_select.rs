@@ -1,15 +1,31 @@
-use gpui::{AnyElement, App, IntoElement, ParentElement, SharedString, Styled, Window};
+use agentx_types::AgentRuntimeStatus;
+use gpui::{
+    AnyElement, App, IntoElement, ParentElement, SharedString, Styled, Window,
+    prelude::FluentBuilder,
+};
 use gpui_component::{Icon, Sizable, h_flex, select::SelectItem};
 
+use super::StatusIndicator;
+
 /// An agent item with icon for the select dropdown
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct AgentItem {
     pub name: String,
+    /// Shown as a health dot when known
+    pub status: Option<AgentRuntimeStatus>,
 }
 
 impl AgentItem {
     pub fn new(name: impl Into<String>) -> Self {
-        Self { name: name.into() }
+        Self {
+            name: name.into(),
+            status: None,
+        }
+    }
+
+    pub fn status(mut self, status: AgentRuntimeStatus) -> Self {
+        self.status = Some(status);
+        self
     }
 }
 
@@ -28,6 +44,9 @@ impl SelectItem for AgentItem {
                 .items_center()
                 .child(Icon::new(icon).xsmall())
                 .child(self.name.clone())
+                .when_some(self.status.as_ref(), |this, status| {
+                    this.child(StatusIndicator::for_agent(status).size(6.0))
+                })
                 .into_any_element(),
         )
     }
@@ -39,6 +58,9 @@ impl SelectItem for AgentItem {
             .items_center()
             .child(Icon::new(icon).xsmall())
             .child(self.name.clone())
+            .when_some(self.status.as_ref(), |this, status| {
+                this.child(StatusIndicator::for_agent(status).size(6.0))
+            })
     }
 
     fn value(&self) -> &Self::Value {
@@ -0,0 +1,189 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use agent_client_protocol::{ContentBlock, ResourceLink};
+use rust_i18n::t;
+
+/// Largest file that can be attached to a message
+pub const MAX_ATTACHMENT_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Bytes inspected to tell text files from binary ones
+const SNIFF_LEN: usize = 8 * 1024;
+
+/// Why a dropped file was not attached
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AttachmentError {
+    /// Larger than [`MAX_ATTACHMENT_SIZE`]
+    TooLarge(String),
+    /// A directory or a binary file that is not an image
+    Unsupported(String),
+    /// The file could not be read
+    Unreadable(String),
+}
+
+impl AttachmentError {
+    /// Inline message shown under the attachment chips
+    pub fn message(&self) -> String {
+        match self {
+            Self::TooLarge(name) => t!(
+                "chat_input.attachment.too_large",
+                name = name,
+                limit = MAX_ATTACHMENT_SIZE / (1024 * 1024)
+            )
+            .to_string(),
+            Self::Unsupported(name) => {
+                t!("chat_input.attachment.unsupported", name = name).to_string()
+            }
+            Self::Unreadable(name) => {
+                t!("chat_input.attachment.unreadable", name = name).to_string()
+            }
+        }
+    }
+}
+
+/// Result of dropping files on the chat input
+#[derive(Clone, Debug, Default)]
+pub struct DroppedAttachments {
+    /// Resource links for the accepted files, rendered as chips
+    pub resources: Vec<ContentBlock>,
+    /// Files that were turned away
+    pub rejected: Vec<AttachmentError>,
+}
+
+/// Turn dropped paths into resource links, skipping files already in
+/// `existing`. Images and text files up to [`MAX_ATTACHMENT_SIZE`] are
+/// accepted; everything else is reported in `rejected`.
+pub fn attachments_from_paths(paths: &[PathBuf], existing: &[ContentBlock]) -> DroppedAttachments {
+    let mut dropped = DroppedAttachments::default();
+    for path in paths {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        let uri = format!("file://{}", path.display());
+        let already_attached = existing
+            .iter()
+            .chain(&dropped.resources)
+            .any(|block| matches!(block, ContentBlock::ResourceLink(link) if link.uri == uri));
+        if already_attached {
+            continue;
+        }
+
+        match check_attachment(path, &name) {
+            Ok(mime_type) => {
+                let mut link = ResourceLink::new(name, uri);
+                if let Some(mime_type) = mime_type {
+                    link = link.mime_type(mime_type);
+                }
+                dropped.resources.push(ContentBlock::ResourceLink(link));
+            }
+            Err(error) => dropped.rejected.push(error),
+        }
+    }
+    dropped
+}
+
+/// MIME type of an attachable file, `None` for plain text
+fn check_attachment(path: &Path, name: &str) -> Result<Option<&'static str>, AttachmentError> {
+    let unreadable = |_| AttachmentError::Unreadable(name.to_string());
+    let metadata = std::fs::metadata(path).map_err(unreadable)?;
+    if !metadata.is_file() {
+        return Err(AttachmentError::Unsupported(name.to_string()));
+    }
+    if metadata.len() > MAX_ATTACHMENT_SIZE {
+        return Err(AttachmentError::TooLarge(name.to_string()));
+    }
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+    if let Some(mime_type) = extension.as_deref().and_then(image_mime_type) {
+        return Ok(Some(mime_type));
+    }
+
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    std::fs::File::open(path)
+        .and_then(|file| file.take(SNIFF_LEN as u64).read_to_end(&mut head))
+        .map_err(unreadable)?;
+    if head.contains(&0) {
+        return Err(AttachmentError::Unsupported(name.to_string()));
+    }
+    Ok(None)
+}
+
+fn image_mime_type(extension: &str) -> Option<&'static str> {
+    Some(match extension {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentx_acp_ui::{ResourceKind, get_resource_info};
+
+    #[test]
+    fn dropped_paths_become_resource_chips() {
+        let dir = std::env::temp_dir().join(format!("agentx-attachments-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let notes = dir.join("notes.md");
+        std::fs::write(&notes, "# Notes\n").unwrap();
+        let screenshot = dir.join("screenshot.PNG");
+        std::fs::write(&screenshot, [0x89, b'P', b'N', b'G', 0, 0]).unwrap();
+        let binary = dir.join("app.bin");
+        std::fs::write(&binary, [0x7f, b'E', b'L', b'F', 0, 1]).unwrap();
+        let large = dir.join("large.log");
+        std::fs::File::create(&large)
+            .unwrap()
+            .set_len(MAX_ATTACHMENT_SIZE + 1)
+            .unwrap();
+
+        let paths = vec![
+            notes.clone(),
+            screenshot,
+            binary,
+            large,
+            dir.clone(),
+            dir.join("missing.txt"),
+            notes,
+        ];
+        let dropped = attachments_from_paths(&paths, &[]);
+
+        let chips: Vec<_> = dropped
+            .resources
+            .iter()
+            .filter_map(get_resource_info)
+            .map(|info| (info.name.to_string(), info.kind))
+            .collect();
+        assert_eq!(
+            chips,
+            [
+                ("notes.md".to_string(), ResourceKind::Generic),
+                ("screenshot.PNG".to_string(), ResourceKind::Image),
+            ]
+        );
+
+        let dir_name = dir.file_name().unwrap().to_string_lossy().to_string();
+        assert_eq!(
+            dropped.rejected,
+            [
+                AttachmentError::Unsupported("app.bin".to_string()),
+                AttachmentError::TooLarge("large.log".to_string()),
+                AttachmentError::Unsupported(dir_name),
+                AttachmentError::Unreadable("missing.txt".to_string()),
+            ]
+        );
+
+        // Dropping the same file again does not add a second chip
+        let again = attachments_from_paths(&paths[..1], &dropped.resources);
+        assert!(again.resources.is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,149 @@
+use gpui::{
+    AnyElement, App, Div, ElementId, Hsla, InteractiveElement as _, IntoElement, ParentElement,
+    Pixels, RenderOnce, ScrollHandle, StatefulInteractiveElement as _, StyleRefinement, Styled,
+    Window, div, linear_color_stop, linear_gradient, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{ActiveTheme, scroll::ScrollableElement as _};
+
+/// Height of the fade shown over the bottom edge while more content follows
+const FADE_HEIGHT: f32 = 24.;
+
+/// Content hidden below the fold by less than this is treated as fully shown,
+/// so sub-pixel layout rounding doesn't leave a fade over the last line
+const OVERFLOW_TOLERANCE: f32 = 1.;
+
+/// Whether content of `content_height`, shown in a region at most
+/// `max_height` tall and scrolled down by `scroll_top`, continues below the
+/// visible part of the region
+pub fn has_more_below(content_height: Pixels, max_height: Pixels, scroll_top: Pixels) -> bool {
+    let visible_height = if content_height < max_height {
+        content_height
+    } else {
+        max_height
+    };
+    content_height - visible_height - scroll_top > px(OVERFLOW_TOLERANCE)
+}
+
+/// A region that grows with its content up to a maximum height and scrolls
+/// beyond it, fading out the bottom edge while there is more to read.
+///
+/// The scroll handle is owned by the caller so the scroll position and the
+/// overflow measured on the last frame survive re-renders.
+#[derive(IntoElement)]
+pub struct BoundedScroll {
+    id: ElementId,
+    base: Div,
+    scroll_handle: ScrollHandle,
+    max_height: Pixels,
+    fade_color: Option<Hsla>,
+    children: Vec<AnyElement>,
+}
+
+impl BoundedScroll {
+    pub fn new(id: impl Into<ElementId>, scroll_handle: &ScrollHandle, max_height: Pixels) -> Self {
+        Self {
+            id: id.into(),
+            base: div(),
+            scroll_handle: scroll_handle.clone(),
+            max_height,
+            fade_color: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Color the fade blends into; match the region's background.
+    /// Defaults to the theme background.
+    pub fn fade_color(mut self, color: impl Into<Hsla>) -> Self {
+        self.fade_color = Some(color.into());
+        self
+    }
+
+    /// Whether the content overflowed the region below the visible part on
+    /// the last frame
+    fn has_more(&self) -> bool {
+        let viewport_height = self.scroll_handle.bounds().size.height;
+        // Offsets grow negative as the content scrolls up
+        let content_height = viewport_height + self.scroll_handle.max_offset().height;
+        has_more_below(
+            content_height,
+            self.max_height,
+            -self.scroll_handle.offset().y,
+        )
+    }
+}
+
+impl ParentElement for BoundedScroll {
+    fn extend(&mut self, elements: impl IntoIterator<Item = AnyElement>) {
+        self.children.extend(elements);
+    }
+}
+
+impl Styled for BoundedScroll {
+    fn style(&mut self) -> &mut StyleRefinement {
+        self.base.style()
+    }
+}
+
+impl RenderOnce for BoundedScroll {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let has_more = self.has_more();
+        let fade_color = self.fade_color.unwrap_or(cx.theme().background);
+
+        self.base
+            .relative()
+            .w_full()
+            .overflow_hidden()
+            .child(
+                div()
+                    .id(self.id)
+                    .w_full()
+                    .max_h(self.max_height)
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .children(self.children),
+            )
+            .when(has_more, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .left_0()
+                        .right_0()
+                        .bottom_0()
+                        .h(px(FADE_HEIGHT))
+                        .bg(linear_gradient(
+                            180.,
+                            linear_color_stop(fade_color.opacity(0.), 0.),
+                            linear_color_stop(fade_color, 1.),
+                        )),
+                )
+            })
+            .vertical_scrollbar(&self.scroll_handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_taller_than_the_bound_has_more_below() {
+        let max_height = px(240.);
+
+        assert!(has_more_below(px(600.), max_height, px(0.)));
+        assert!(has_more_below(px(600.), max_height, px(200.)));
+        // Scrolled to the bottom: 600 - 240 = 360
+        assert!(!has_more_below(px(600.), max_height, px(360.)));
+        assert!(!has_more_below(px(600.), max_height, px(359.5)));
+    }
+
+    #[test]
+    fn content_within_the_bound_has_nothing_below() {
+        let max_height = px(240.);
+
+        assert!(!has_more_below(px(0.), max_height, px(0.)));
+        assert!(!has_more_below(px(120.), max_height, px(0.)));
+        assert!(!has_more_below(px(240.), max_height, px(0.)));
+        assert!(!has_more_below(px(240.5), max_height, px(0.)));
+        assert!(has_more_below(px(242.), max_height, px(0.)));
+    }
+}
@@ -1,7 +1,10 @@
 use gpui::{
-    App, ElementId, Entity, InteractiveElement, IntoElement, ParentElement, RenderOnce,
-    SharedString, Styled, Window, div, prelude::FluentBuilder, px,
+    App, ElementId, Entity, ExternalPaths, FontWeight, HighlightStyle, InteractiveElement,
+    IntoElement, KeyDownEvent, ParentElement, RenderOnce, SharedString, Styled, StyledText, Window,
+    div, prelude::FluentBuilder, px,
 };
+use rust_i18n::t;
+use std::ops::Range;
 use std::rc::Rc;
 
 use gpui_component::{
@@ -14,12 +17,13 @@ use gpui_component::{
     v_flex,
 };
 
-use agent_client_protocol::{AvailableCommand, ImageContent};
+use agent_client_protocol::{AvailableCommand, ContentBlock, ImageContent};
+use agentx_acp_ui::get_resource_info;
 
 use crate::app::actions::AddCodeSelection;
 use crate::components::{
-    AgentItem, FileItem, InputSuggestion, InputSuggestionItem, InputSuggestionState,
-    ModeSelectItem, ModelSelectItem,
+    AgentItem, FileItem, HistoryDirection, InputSuggestion, InputSuggestionItem,
+    InputSuggestionState, ModeSelectItem, ModelSelectItem, fuzzy_match, should_navigate_history,
 };
 use crate::core::config::McpServerConfig;
 use crate::core::services::SessionStatus;
@@ -62,6 +66,27 @@ impl InputSuggestionItem for ChatSuggestion {
     }
 }
 
+/// The command name typed after a leading `/`, until the first whitespace
+fn command_query(value: &str) -> Option<&str> {
+    let query = value.trim_start().strip_prefix('/')?;
+    (!query.chars().any(char::is_whitespace)).then_some(query)
+}
+
+/// Byte ranges in `/name` of the characters that `query` fuzzy-matched
+fn command_label_highlights(name: &str, query: &str) -> Vec<Range<usize>> {
+    let Some(matched) = fuzzy_match(query, name).filter(|_| !query.is_empty()) else {
+        return Vec::new();
+    };
+    let chars = name.char_indices().collect::<Vec<_>>();
+    matched
+        .positions
+        .into_iter()
+        .filter_map(|pos| chars.get(pos))
+        // Shifted past the leading `/`
+        .map(|(start, ch)| start + 1..start + 1 + ch.len_utf8())
+        .collect()
+}
+
 /// A reusable chat input component with context controls and send button.
 ///
 /// Features:
@@ -71,6 +96,7 @@ impl InputSuggestionItem for ChatSuggestion {
 /// - Send button with icon
 /// - Optional title displayed above the input box
 /// - Support for pasting multiple images with filename display
+/// - Dropping files to attach them as resource links
 #[derive(IntoElement)]
 pub struct ChatInputBox {
     id: ElementId,
@@ -85,9 +111,15 @@ pub struct ChatInputBox {
     pasted_images: Vec<(ImageContent, String)>, // (ImageContent, filename for display)
     code_selections: Vec<AddCodeSelection>,     // Code selections from editor
     selected_files: Vec<String>,                // Selected file paths from file picker
+    /// Files dropped on the input, as ACP resource links
+    attached_resources: Vec<ContentBlock>,
+    /// Why the last dropped files were not all attached
+    attachment_error: Option<String>,
     on_remove_image: Option<Rc<dyn Fn(&usize, &mut Window, &mut App) + 'static>>,
     on_remove_code_selection: Option<Rc<dyn Fn(&usize, &mut Window, &mut App) + 'static>>,
     on_remove_file: Option<Rc<dyn Fn(&usize, &mut Window, &mut App) + 'static>>,
+    on_remove_resource: Option<Rc<dyn Fn(&usize, &mut Window, &mut App) + 'static>>,
+    on_drop_paths: Option<Rc<dyn Fn(&ExternalPaths, &mut Window, &mut App) + 'static>>,
     on_paste: Option<Rc<dyn Fn(&mut Window, &mut App) + 'static>>,
     session_status: Option<SessionStatus>, // Session status for button state
     file_suggestions: Vec<FileItem>,
@@ -106,6 +138,10 @@ pub struct ChatInputBox {
     on_mcp_toggle: Option<Rc<dyn Fn(&(String, bool), &mut Window, &mut App) + 'static>>,
     /// Whether the input is disabled (e.g., for closed/failed sessions)
     disabled: bool,
+    /// A cancel was requested and the turn is still winding down
+    cancelling: bool,
+    /// Called on Up/Down at the first/last line to recall sent messages
+    on_history_navigate: Option<Rc<dyn Fn(&HistoryDirection, &mut Window, &mut App) + 'static>>,
 }
 
 impl ChatInputBox {
@@ -124,9 +160,13 @@ impl ChatInputBox {
             pasted_images: Vec::new(),
             code_selections: Vec::new(),
             selected_files: Vec::new(),
+            attached_resources: Vec::new(),
+            attachment_error: None,
             on_remove_image: None,
             on_remove_code_selection: None,
             on_remove_file: None,
+            on_remove_resource: None,
+            on_drop_paths: None,
             on_paste: None,
             session_status: None,
             file_suggestions: Vec::new(),
@@ -138,6 +178,8 @@ impl ChatInputBox {
             selected_mcps: Vec::new(),
             on_mcp_toggle: None,
             disabled: false,
+            cancelling: false,
+            on_history_navigate: None,
         }
     }
 
@@ -243,6 +285,36 @@ impl ChatInputBox {
         self
     }
 
+    /// Set the resources attached by dropping files
+    pub fn attached_resources(mut self, resources: Vec<ContentBlock>) -> Self {
+        self.attached_resources = resources;
+        self
+    }
+
+    /// Set a callback for when an attached resource is removed
+    pub fn on_remove_resource<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&usize, &mut Window, &mut App) + 'static,
+    {
+        self.on_remove_resource = Some(Rc::new(callback));
+        self
+    }
+
+    /// Set a callback for files dropped on the input; without one, drops are ignored
+    pub fn on_drop_paths<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ExternalPaths, &mut Window, &mut App) + 'static,
+    {
+        self.on_drop_paths = Some(Rc::new(callback));
+        self
+    }
+
+    /// Set the inline message about dropped files that were rejected
+    pub fn attachment_error(mut self, error: Option<String>) -> Self {
+        self.attachment_error = error;
+        self
+    }
+
     /// Set the session status (affects send button appearance)
     pub fn session_status(mut self, status: Option<SessionStatus>) -> Self {
         self.session_status = status;
@@ -311,6 +383,21 @@ impl ChatInputBox {
         self.disabled = disabled;
         self
     }
+
+    /// Show the stop button as busy while a cancel is in flight
+    pub fn cancelling(mut self, cancelling: bool) -> Self {
+        self.cancelling = cancelling;
+        self
+    }
+
+    /// Set callback for recalling sent messages with Up/Down
+    pub fn on_history_navigate<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&HistoryDirection, &mut Window, &mut App) + 'static,
+    {
+        self.on_history_navigate = Some(Rc::new(callback));
+        self
+    }
 }
 
 impl RenderOnce for ChatInputBox {
@@ -330,12 +417,14 @@ impl RenderOnce for ChatInputBox {
         let is_empty = input_value.trim().is_empty();
         let has_attachments = !self.pasted_images.is_empty()
             || !self.code_selections.is_empty()
-            || !self.selected_files.is_empty();
+            || !self.selected_files.is_empty()
+            || !self.attached_resources.is_empty();
 
         // Get theme after use_keyed_state to avoid borrow conflicts
         let theme = cx.theme();
 
         let show_commands = self.show_command_suggestions && !self.command_suggestions.is_empty();
+        let command_query = command_query(&input_value).unwrap_or_default().to_string();
         let show_files = !self.file_suggestions.is_empty();
         let (suggestions, suggestion_header, apply_on_confirm) = if show_files {
             (
@@ -385,6 +474,47 @@ impl RenderOnce for ChatInputBox {
                     .border_color(theme.border)
                     .bg(theme.background)
                     .shadow_md()
+                    .when_some(self.on_drop_paths.clone(), |this, callback| {
+                        this.drag_over::<ExternalPaths>(|style, _, _, cx| {
+                            style.border_color(cx.theme().primary)
+                        })
+                        .on_drop(
+                            move |paths: &ExternalPaths, window, cx| {
+                                callback(paths, window, cx);
+                            },
+                        )
+                    })
+                    // Suggestion popovers own Up/Down while they are open
+                    .when_some(
+                        self.on_history_navigate
+                            .clone()
+                            .filter(|_| suggestions.is_empty() && !disabled),
+                        |this, callback| {
+                            let input_state = input_state.clone();
+                            this.capture_key_down(move |event: &KeyDownEvent, window, cx| {
+                                let keystroke = &event.keystroke;
+                                if keystroke.modifiers.modified() {
+                                    return;
+                                }
+                                let direction = match keystroke.key.as_str() {
+                                    "up" => HistoryDirection::Previous,
+                                    "down" => HistoryDirection::Next,
+                                    _ => return,
+                                };
+
+                                let state = input_state.read(cx);
+                                let navigate = should_navigate_history(
+                                    &state.value(),
+                                    state.cursor(),
+                                    direction,
+                                );
+                                if navigate {
+                                    cx.stop_propagation();
+                                    callback(&direction, window, cx);
+                                }
+                            })
+                        },
+                    )
                     .when_some(on_paste_callback, |this, callback| {
                         let input_state = input_state_for_paste.clone();
                         this.on_action(move |_: &crate::app::actions::Paste, window, cx| {
@@ -531,6 +661,39 @@ impl RenderOnce for ChatInputBox {
                                 ),
                             );
 
+                            attachment_chips.extend(
+                                self.attached_resources
+                                    .iter()
+                                    .filter_map(get_resource_info)
+                                    .enumerate()
+                                    .map(|(idx, info)| {
+                                        let (bg_color, border_color, icon_color) =
+                                            if info.is_image() {
+                                                (
+                                                    theme.accent.opacity(0.1),
+                                                    theme.accent.opacity(0.3),
+                                                    theme.accent,
+                                                )
+                                            } else {
+                                                (
+                                                    theme.muted.opacity(0.6),
+                                                    theme.border,
+                                                    theme.foreground.opacity(0.7),
+                                                )
+                                            };
+                                        render_chip(
+                                            "remove-resource",
+                                            idx,
+                                            IconName::File,
+                                            info.name.to_string(),
+                                            bg_color,
+                                            border_color,
+                                            icon_color,
+                                            self.on_remove_resource.clone(),
+                                        )
+                                    }),
+                            );
+
                             h_flex()
                                 .w_full()
                                 .gap_1p5()
@@ -539,6 +702,9 @@ impl RenderOnce for ChatInputBox {
                                 .children(attachment_chips)
                         })
                     })
+                    .when_some(self.attachment_error, |this, error| {
+                        this.child(div().text_xs().text_color(theme.danger).child(error))
+                    })
                     .child(
                         // Textarea (multi-line input)
                         {
@@ -555,30 +721,49 @@ impl RenderOnce for ChatInputBox {
                                 .input(move |state| {
                                     Input::new(state).appearance(false).disabled(disabled)
                                 })
-                                .render_item(|item, _selected, _window, cx| {
+                                .render_item(move |item, _selected, _window, cx| {
                                     let theme = cx.theme();
                                     match item {
-                                        ChatSuggestion::Command(command) => h_flex()
-                                            .w_full()
-                                            .gap_3()
-                                            .items_center()
-                                            .child(
-                                                div()
-                                                    .w(px(140.))
-                                                    .text_sm()
-                                                    .font_family("Monaco, 'Courier New', monospace")
-                                                    .text_color(theme.popover_foreground)
-                                                    .child(format!("/{}", command.name)),
+                                        ChatSuggestion::Command(command) => {
+                                            let highlight = HighlightStyle {
+                                                color: Some(theme.primary),
+                                                font_weight: Some(FontWeight::BOLD),
+                                                ..Default::default()
+                                            };
+                                            let highlights = command_label_highlights(
+                                                &command.name,
+                                                &command_query,
                                             )
-                                            .child(
-                                                div()
-                                                    .flex_1()
-                                                    .text_sm()
-                                                    .text_color(theme.muted_foreground)
-                                                    .overflow_x_hidden()
-                                                    .text_ellipsis()
-                                                    .child(command.description.clone()),
-                                            ),
+                                            .into_iter()
+                                            .map(|range| (range, highlight))
+                                            .collect::<Vec<_>>();
+                                            let label =
+                                                StyledText::new(format!("/{}", command.name))
+                                                    .with_highlights(highlights);
+                                            h_flex()
+                                                .w_full()
+                                                .gap_3()
+                                                .items_center()
+                                                .child(
+                                                    div()
+                                                        .w(px(140.))
+                                                        .text_sm()
+                                                        .font_family(
+                                                            "Monaco, 'Courier New', monospace",
+                                                        )
+                                                        .text_color(theme.popover_foreground)
+                                                        .child(label),
+                                                )
+                                                .child(
+                                                    div()
+                                                        .flex_1()
+                                                        .text_sm()
+                                                        .text_color(theme.muted_foreground)
+                                                        .overflow_x_hidden()
+                                                        .text_ellipsis()
+                                                        .child(command.description.clone()),
+                                                )
+                                        }
                                         ChatSuggestion::File(file) => {
                                             let icon = if file.is_folder {
                                                 Icon::new(IconName::Folder)
@@ -772,13 +957,22 @@ impl RenderOnce for ChatInputBox {
                             )
                             .child({
                                 // Determine button icon and behavior based on session status
-                                let (icon, is_in_progress) = match self.session_status {
-                                    Some(SessionStatus::InProgress) => {
-                                        (Icon::new(crate::assets::Icon::SquarePause), true)
-                                    }
-                                    _ => (Icon::new(IconName::ArrowUp), false),
+                                // While the agent is busy, a typed message is queued instead of
+                                // cancelling the turn
+                                let is_in_progress = self
+                                    .session_status
+                                    .as_ref()
+                                    .is_some_and(SessionStatus::is_turn_in_progress)
+                                    && is_empty
+                                    && !has_attachments;
+                                let icon = if is_in_progress {
+                                    Icon::new(crate::assets::Icon::SquarePause)
+                                } else {
+                                    Icon::new(IconName::ArrowUp)
                                 };
 
+                                let is_cancelling = is_in_progress && self.cancelling;
+
                                 // Button is disabled if: input disabled OR (empty and not in progress)
                                 let btn_disabled = disabled || (is_empty && !is_in_progress);
 
@@ -786,7 +980,11 @@ impl RenderOnce for ChatInputBox {
                                     .icon(icon)
                                     .rounded_full()
                                     .small()
-                                    .disabled(btn_disabled);
+                                    .disabled(btn_disabled)
+                                    .loading(is_cancelling)
+                                    .when(is_cancelling, |btn| {
+                                        btn.tooltip(t!("conversation.cancelling").to_string())
+                                    });
 
                                 // Apply appropriate color scheme
                                 btn = if btn_disabled {
@@ -814,9 +1012,9 @@ impl RenderOnce for ChatInputBox {
                                     )
                                 };
 
-                                // Attach click handler
+                                // Attach click handler; a second stop while cancelling is a no-op
                                 if is_in_progress {
-                                    if let Some(on_cancel_handler) = on_cancel {
+                                    if !is_cancelling && let Some(on_cancel_handler) = on_cancel {
                                         btn = btn.on_click(move |ev, window, cx| {
                                             on_cancel_handler(ev, window, cx);
                                         });
@@ -833,3 +1031,28 @@ impl RenderOnce for ChatInputBox {
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_query_is_the_first_word_after_the_slash() {
+        assert_eq!(command_query("/"), Some(""));
+        assert_eq!(command_query("  /git-c"), Some("git-c"));
+        assert_eq!(command_query("/review now"), None);
+        assert_eq!(command_query("hello /review"), None);
+    }
+
+    #[test]
+    fn command_label_highlights_skip_the_slash() {
+        // "/git-commit": `g` at 1, `c` at 5
+        assert_eq!(
+            command_label_highlights("git-commit", "gc"),
+            vec![1..2, 5..6]
+        );
+        assert_eq!(command_label_highlights("résumé", "su"), vec![4..5, 5..6]);
+        assert!(command_label_highlights("git-commit", "").is_empty());
+        assert!(command_label_highlights("git-commit", "xyz").is_empty());
+    }
+}
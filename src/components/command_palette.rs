@@ -0,0 +1,259 @@
+use std::rc::Rc;
+
+use gpui::{
+    Action, App, Context, FontWeight, HighlightStyle, ParentElement, SharedString, Styled,
+    StyledText, Task, Window, div,
+};
+use gpui_component::{
+    ActiveTheme, IndexPath, h_flex,
+    list::{ListDelegate, ListItem, ListState},
+};
+
+use super::fuzzy_match;
+
+/// An action listed in the command palette
+pub struct PaletteCommand {
+    /// Human-readable name, e.g. "Open Settings"
+    pub name: SharedString,
+    /// Group shown next to the name, e.g. "Workspace"
+    pub category: SharedString,
+    pub action: Box<dyn Action>,
+}
+
+impl PaletteCommand {
+    pub fn new(
+        category: impl Into<SharedString>,
+        name: impl Into<SharedString>,
+        action: impl Action,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            category: category.into(),
+            action: Box::new(action),
+        }
+    }
+
+    /// Text matched against the query, so both `settings` and `ws open`
+    /// find "Workspace: Open Settings"
+    fn label(&self) -> String {
+        format!("{}: {}", self.category, self.name)
+    }
+}
+
+impl Clone for PaletteCommand {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            category: self.category.clone(),
+            action: self.action.boxed_clone(),
+        }
+    }
+}
+
+impl std::fmt::Debug for PaletteCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaletteCommand")
+            .field("name", &self.name)
+            .field("category", &self.category)
+            .field("action", &self.action.name())
+            .finish()
+    }
+}
+
+/// Indices of the commands matching `query`, best first. An empty query keeps
+/// every command in registration order.
+pub fn filter_palette_commands(query: &str, commands: &[PaletteCommand]) -> Vec<usize> {
+    let query = query.split_whitespace().collect::<String>();
+    if query.is_empty() {
+        return (0..commands.len()).collect();
+    }
+
+    let mut matches = commands
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, command)| {
+            // Prefer a match within the name over one spanning the category
+            let score = fuzzy_match(&query, &command.name)
+                .map(|m| m.score)
+                .or_else(|| Some(fuzzy_match(&query, &command.label())?.score))?;
+            Some((score, ix))
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|(a_score, a), (b_score, b)| b_score.cmp(a_score).then(a.cmp(b)));
+    matches.into_iter().map(|(_, ix)| ix).collect()
+}
+
+/// Handler run with the chosen command's action
+pub type PaletteConfirmHandler = Rc<dyn Fn(Box<dyn Action>, &mut Window, &mut App)>;
+
+/// List delegate for the command palette dialog
+pub struct CommandPaletteDelegate {
+    commands: Vec<PaletteCommand>,
+    /// Indices into `commands` matching the current query
+    matches: Vec<usize>,
+    query: String,
+    selected_index: Option<usize>,
+    on_confirm: PaletteConfirmHandler,
+}
+
+impl CommandPaletteDelegate {
+    pub fn new(commands: Vec<PaletteCommand>, on_confirm: PaletteConfirmHandler) -> Self {
+        let matches = (0..commands.len()).collect();
+        Self {
+            commands,
+            matches,
+            query: String::new(),
+            selected_index: Some(0),
+            on_confirm,
+        }
+    }
+
+    fn command_at(&self, row: usize) -> Option<&PaletteCommand> {
+        self.commands.get(*self.matches.get(row)?)
+    }
+}
+
+impl ListDelegate for CommandPaletteDelegate {
+    type Item = ListItem;
+
+    fn perform_search(
+        &mut self,
+        query: &str,
+        _: &mut Window,
+        _: &mut Context<ListState<Self>>,
+    ) -> Task<()> {
+        self.query = query.to_string();
+        self.matches = filter_palette_commands(query, &self.commands);
+        self.selected_index = (!self.matches.is_empty()).then_some(0);
+        Task::ready(())
+    }
+
+    fn items_count(&self, _: usize, _: &App) -> usize {
+        self.matches.len()
+    }
+
+    fn render_item(
+        &mut self,
+        ix: IndexPath,
+        _: &mut Window,
+        cx: &mut Context<ListState<Self>>,
+    ) -> Option<Self::Item> {
+        let command = self.command_at(ix.row)?;
+        let theme = cx.theme();
+
+        let highlight = HighlightStyle {
+            color: Some(theme.primary),
+            font_weight: Some(FontWeight::BOLD),
+            ..Default::default()
+        };
+        let highlights = fuzzy_match(&self.query.replace(' ', ""), &command.name)
+            .map(|m| {
+                let offsets = command
+                    .name
+                    .char_indices()
+                    .map(|(i, c)| i..i + c.len_utf8())
+                    .collect::<Vec<_>>();
+                m.positions
+                    .into_iter()
+                    .filter_map(|pos| offsets.get(pos).cloned())
+                    .map(|range| (range, highlight))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        Some(
+            ListItem::new(ix).w_full().py_1().px_2().child(
+                h_flex()
+                    .w_full()
+                    .gap_3()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div().text_sm().child(
+                            StyledText::new(command.name.clone()).with_highlights(highlights),
+                        ),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(command.category.clone()),
+                    ),
+            ),
+        )
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: Option<IndexPath>,
+        _: &mut Window,
+        _: &mut Context<ListState<Self>>,
+    ) {
+        self.selected_index = ix.map(|ix| ix.row);
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<ListState<Self>>) {
+        let Some(command) = self.selected_index.and_then(|row| self.command_at(row)) else {
+            return;
+        };
+        (self.on_confirm)(command.action.boxed_clone(), window, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::actions;
+
+    actions!(
+        palette_test,
+        [OpenSettings, OpenSessionManager, ToggleSearch, Quit]
+    );
+
+    fn commands() -> Vec<PaletteCommand> {
+        vec![
+            PaletteCommand::new("Workspace", "Open Settings", OpenSettings),
+            PaletteCommand::new("Workspace", "Open Session Manager", OpenSessionManager),
+            PaletteCommand::new("Window", "Toggle Search", ToggleSearch),
+            PaletteCommand::new("Application", "Quit", Quit),
+        ]
+    }
+
+    fn names(query: &str) -> Vec<String> {
+        let commands = commands();
+        filter_palette_commands(query, &commands)
+            .into_iter()
+            .map(|ix| commands[ix].name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn empty_query_lists_every_command_in_order() {
+        assert_eq!(
+            names(" "),
+            vec![
+                "Open Settings",
+                "Open Session Manager",
+                "Toggle Search",
+                "Quit"
+            ]
+        );
+    }
+
+    #[test]
+    fn fuzzy_query_ranks_best_match_first() {
+        assert_eq!(names("osm"), vec!["Open Session Manager"]);
+        assert_eq!(names("settings")[0], "Open Settings");
+        assert_eq!(names("tgl srch"), vec!["Toggle Search"]);
+        assert!(names("zzz").is_empty());
+    }
+
+    #[test]
+    fn query_can_match_the_category() {
+        assert_eq!(names("application"), vec!["Quit"]);
+        assert_eq!(
+            names("window").first().map(|s| s.as_str()),
+            Some("Toggle Search")
+        );
+    }
+}
@@ -11,6 +11,75 @@ use gpui_component::{
 
 use agent_client_protocol::AvailableCommand;
 
+/// Bonus that keeps exact-prefix matches above any scattered match
+const PREFIX_BONUS: i32 = 1000;
+const CONSECUTIVE_BONUS: i32 = 8;
+const BOUNDARY_BONUS: i32 = 6;
+const GAP_PENALTY: i32 = 1;
+
+/// Result of matching a query against a command name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Char indices of the matched characters in the candidate
+    pub positions: Vec<usize>,
+}
+
+/// Case-insensitive subsequence match, so `gc` matches `git-commit`.
+///
+/// Consecutive characters and characters at word boundaries score higher;
+/// a candidate starting with the whole query outranks every other match.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let query = query.to_lowercase().chars().collect::<Vec<_>>();
+    let candidate_chars = candidate.to_lowercase().chars().collect::<Vec<_>>();
+
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = 0;
+    let mut next = 0;
+    for q in &query {
+        let index = next + candidate_chars[next..].iter().position(|c| c == q)?;
+        let at_boundary = index == 0
+            || matches!(
+                candidate_chars[index - 1],
+                '-' | '_' | ' ' | ':' | '/' | '.'
+            );
+        score += if positions.last() == Some(&(index.wrapping_sub(1))) {
+            CONSECUTIVE_BONUS
+        } else if at_boundary {
+            BOUNDARY_BONUS
+        } else {
+            -GAP_PENALTY * (index - next) as i32
+        };
+        positions.push(index);
+        next = index + 1;
+    }
+
+    if candidate_chars.starts_with(&query) {
+        score += PREFIX_BONUS;
+    }
+    // Prefer shorter names among otherwise equal matches
+    score -= (candidate_chars.len() - query.len()) as i32;
+
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Commands matching `query`, best first. An empty query keeps every command
+/// in its original order.
+pub fn filter_commands(query: &str, commands: Vec<AvailableCommand>) -> Vec<AvailableCommand> {
+    if query.is_empty() {
+        return commands;
+    }
+
+    let mut matches = commands
+        .into_iter()
+        .filter_map(|command| Some((fuzzy_match(query, &command.name)?.score, command)))
+        .collect::<Vec<_>>();
+    matches.sort_by(|(a_score, a), (b_score, b)| {
+        b_score.cmp(a_score).then_with(|| a.name.cmp(&b.name))
+    });
+    matches.into_iter().map(|(_, command)| command).collect()
+}
+
 struct CommandSuggestionsListDelegate {
     commands: Vec<AvailableCommand>,
     selected_index: Option<usize>,
@@ -250,3 +319,46 @@ impl RenderOnce for CommandSuggestionsPopover {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(names: &[&str]) -> Vec<AvailableCommand> {
+        names
+            .iter()
+            .map(|name| AvailableCommand::new(*name, ""))
+            .collect()
+    }
+
+    fn names(commands: &[AvailableCommand]) -> Vec<&str> {
+        commands.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn matches_subsequence() {
+        let m = fuzzy_match("gc", "git-commit").unwrap();
+        assert_eq!(m.positions, vec![0, 4]);
+        assert!(fuzzy_match("GC", "git-commit").is_some());
+        assert!(fuzzy_match("cg", "git-commit").is_none());
+    }
+
+    #[test]
+    fn exact_prefix_ranks_above_scattered_match() {
+        let filtered = filter_commands("com", commands(&["git-commit", "compact", "co-mention"]));
+        assert_eq!(names(&filtered)[0], "compact");
+        assert_eq!(filtered.len(), 3);
+
+        let filtered = filter_commands("gc", commands(&["graphics", "git-commit"]));
+        assert_eq!(names(&filtered), vec!["git-commit", "graphics"]);
+    }
+
+    #[test]
+    fn no_match_returns_empty() {
+        assert!(filter_commands("xyz", commands(&["git-commit", "review"])).is_empty());
+        assert_eq!(
+            names(&filter_commands("", commands(&["review", "init"]))),
+            vec!["review", "init"]
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::components::DiffView;
+use crate::core::services::{Edit, edits_diff};
+
+/// Diff of `edits` suggested by the AI service against `text`, so they can be
+/// reviewed before they are applied
+pub fn edit_preview(path: impl Into<PathBuf>, text: &str, edits: &[Edit]) -> Result<DiffView> {
+    let diff = edits_diff(path, text, edits)?;
+    Ok(DiffView::new(diff).id("ai-edit-preview").context_lines(3))
+}
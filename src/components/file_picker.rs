@@ -1,11 +1,63 @@
-use gpui::{App, Context, ParentElement, Styled, Task, Window, div, px};
+use gpui::{App, Context, Entity, ParentElement, Styled, Task, Window, div, px};
 use gpui_component::{
     ActiveTheme, Icon, IconName, IndexPath,
     list::{ListDelegate, ListItem, ListState},
 };
+use ignore::WalkBuilder;
 use std::path::{Path, PathBuf};
 use tokio::sync::mpsc;
 
+use crate::AppState;
+
+/// Deepest level scanned below the root
+const MAX_SCAN_DEPTH: usize = 4;
+/// Upper bound on filtered results kept for the list
+const MAX_FILTERED_ITEMS: usize = 200;
+
+const SEGMENT_BONUS: i32 = 10;
+const CONSECUTIVE_BONUS: i32 = 6;
+const FILENAME_BONUS: i32 = 2;
+const GAP_PENALTY: i32 = 1;
+
+/// Score `path` against `query` as a case-insensitive subsequence, or `None`
+/// if it doesn't match.
+///
+/// Matches at the start of a path segment (after `/`, `_`, `-` or `.`) and
+/// runs of consecutive characters score higher, as do characters in the file
+/// name, so `srmain` ranks `src/main.rs` above scattered matches.
+pub fn fuzzy_path_score(query: &str, path: &str) -> Option<i32> {
+    let query = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    let path = path.to_lowercase().chars().collect::<Vec<_>>();
+    let name_start = path.iter().rposition(|c| *c == '/').map_or(0, |i| i + 1);
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for q in &query {
+        let index = next + path[next..].iter().position(|c| c == q)?;
+        let at_boundary = index == 0 || matches!(path[index - 1], '/' | '_' | '-' | '.');
+
+        score += if previous.is_some_and(|prev| prev + 1 == index) {
+            CONSECUTIVE_BONUS
+        } else if at_boundary {
+            SEGMENT_BONUS
+        } else {
+            -GAP_PENALTY * (index - next).min(10) as i32
+        };
+        if index >= name_start {
+            score += FILENAME_BONUS;
+        }
+        previous = Some(index);
+        next = index + 1;
+    }
+
+    Some(score)
+}
+
 /// File item in the file picker
 #[derive(Clone, Debug)]
 pub struct FileItem {
@@ -115,10 +167,26 @@ impl FilePickerDelegate {
         self.scan_state = ScanState::NotStarted;
     }
 
-    /// Scan directory recursively and return all files and folders
-    pub(crate) fn scan_directory(path: &Path, base_path: &Path) -> Vec<FileItem> {
-        let mut items = Vec::new();
+    /// Re-root the picker whenever the application working directory changes;
+    /// the new root is scanned on next use
+    pub fn follow_working_dir(list: &Entity<ListState<Self>>, cx: &mut App) {
+        crate::core::event_bus::subscribe_entity_to_cwd_changes(
+            list,
+            AppState::global(cx).event_hub().clone(),
+            "FilePicker",
+            |state, path, cx| {
+                state.delegate_mut().reset_root(path);
+                cx.notify();
+            },
+            cx,
+        );
+    }
 
+    /// Scan directory recursively and return all files and folders.
+    ///
+    /// Honors `.gitignore` files (even outside a git repository). Blocking, so
+    /// run it on a background executor.
+    pub(crate) fn scan_directory(path: &Path, base_path: &Path) -> Vec<FileItem> {
         // Skip common ignore patterns
         let ignore_patterns = [
             "node_modules",
@@ -132,44 +200,26 @@ impl FilePickerDelegate {
             ".idea",
         ];
 
-        if let Ok(entries) = std::fs::read_dir(path) {
-            for entry in entries.flatten() {
-                let entry_path = entry.path();
-                let file_name = entry_path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                // Skip ignored directories
-                if ignore_patterns.contains(&file_name.as_str()) {
-                    continue;
-                }
-
-                let is_folder = entry_path.is_dir();
-
-                // Add the current item (folder or file)
-                items.push(FileItem::new(
-                    file_name.clone(),
-                    entry_path.clone(),
-                    is_folder,
-                    base_path,
-                ));
-
-                // Recursively scan subdirectories (limit depth to avoid too many files)
-                if is_folder {
-                    // Only go 3 levels deep
-                    let depth = entry_path
-                        .strip_prefix(base_path)
-                        .map(|p| p.components().count())
-                        .unwrap_or(0);
-
-                    if depth < 3 {
-                        items.extend(Self::scan_directory(&entry_path, base_path));
-                    }
-                }
-            }
-        }
+        let walker = WalkBuilder::new(path)
+            .max_depth(Some(MAX_SCAN_DEPTH))
+            .hidden(false)
+            .require_git(false)
+            .filter_entry(move |entry| {
+                let name = entry.file_name().to_str().unwrap_or("");
+                !ignore_patterns.contains(&name)
+            })
+            .build();
+
+        let mut items = walker
+            .flatten()
+            // The first entry is the root itself
+            .filter(|entry| entry.depth() > 0)
+            .map(|entry| {
+                let is_folder = entry.file_type().is_some_and(|ft| ft.is_dir());
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                FileItem::new(file_name, entry.into_path(), is_folder, base_path)
+            })
+            .collect::<Vec<_>>();
 
         // Sort: folders first, then files, alphabetically within each group
         items.sort_by(|a, b| match (a.is_folder, b.is_folder) {
@@ -181,27 +231,35 @@ impl FilePickerDelegate {
         items
     }
 
-    /// Update search query and filter items
+    /// Update search query and fuzzy-filter items, best matches first
     pub fn set_search_query(&mut self, query: String) {
         self.search_query = query.to_lowercase();
+        self.filtered_items = filter_items(&self.search_query, &self.all_items);
+    }
+}
 
-        if self.search_query.is_empty() {
-            self.filtered_items = self.all_items.clone();
-        } else {
-            self.filtered_items = self
-                .all_items
-                .iter()
-                .filter(|item| {
-                    item.name.to_lowercase().contains(&self.search_query)
-                        || item
-                            .relative_path
-                            .to_lowercase()
-                            .contains(&self.search_query)
-                })
-                .cloned()
-                .collect();
-        }
+/// Items matching `query`, ranked by [`fuzzy_path_score`] and capped at
+/// [`MAX_FILTERED_ITEMS`]
+fn filter_items(query: &str, items: &[FileItem]) -> Vec<FileItem> {
+    if query.is_empty() {
+        return items.iter().take(MAX_FILTERED_ITEMS).cloned().collect();
     }
+
+    let mut matches = items
+        .iter()
+        .filter_map(|item| Some((fuzzy_path_score(query, &item.relative_path)?, item)))
+        .collect::<Vec<_>>();
+    matches.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| a.relative_path.len().cmp(&b.relative_path.len()))
+            .then_with(|| a.relative_path.cmp(&b.relative_path))
+    });
+    matches
+        .into_iter()
+        .take(MAX_FILTERED_ITEMS)
+        .map(|(_, item)| item.clone())
+        .collect()
 }
 
 impl ListDelegate for FilePickerDelegate {
@@ -293,3 +351,64 @@ impl ListDelegate for FilePickerDelegate {
         // Close the popover on cancel
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(paths: &[&str]) -> Vec<FileItem> {
+        let base = Path::new("/workspace");
+        paths
+            .iter()
+            .map(|path| {
+                let path = base.join(path);
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                FileItem::new(name, path, false, base)
+            })
+            .collect()
+    }
+
+    fn ranked(query: &str, paths: &[&str]) -> Vec<String> {
+        filter_items(query, &items(paths))
+            .into_iter()
+            .map(|item| item.relative_path)
+            .collect()
+    }
+
+    #[test]
+    fn matcher_prefers_segment_boundaries_and_runs() {
+        let result = ranked(
+            "srmain",
+            &["tests/server_main_test.rs", "README.md", "src/main.rs"],
+        );
+        assert_eq!(result[0], "src/main.rs");
+        assert!(!result.contains(&"README.md".to_string()));
+
+        assert_eq!(
+            ranked("main", &["src/domain.rs", "src/main.rs"]),
+            vec!["src/main.rs", "src/domain.rs"]
+        );
+        assert!(fuzzy_path_score("xyz", "src/main.rs").is_none());
+    }
+
+    #[test]
+    fn scan_skips_gitignored_paths() {
+        let root = std::env::temp_dir().join(format!("agentx_file_picker_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("logs")).unwrap();
+        std::fs::write(root.join(".gitignore"), "secret.txt\nlogs/\n").unwrap();
+        std::fs::write(root.join("secret.txt"), "").unwrap();
+        std::fs::write(root.join("keep.txt"), "").unwrap();
+        std::fs::write(root.join("logs/app.log"), "").unwrap();
+
+        let paths = FilePickerDelegate::scan_directory(&root, &root)
+            .into_iter()
+            .map(|item| item.relative_path)
+            .collect::<Vec<_>>();
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(paths.contains(&"keep.txt".to_string()));
+        assert!(!paths.contains(&"secret.txt".to_string()));
+        assert!(!paths.iter().any(|path| path.starts_with("logs")));
+    }
+}
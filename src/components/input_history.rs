@@ -0,0 +1,189 @@
+use std::collections::VecDeque;
+
+/// Default number of sent messages kept for Up/Down recall
+pub const DEFAULT_HISTORY_CAPACITY: usize = 50;
+
+/// Direction of a history navigation request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryDirection {
+    /// Towards older messages
+    Previous,
+    /// Towards newer messages, ending at the draft
+    Next,
+}
+
+/// Bounded ring of recently sent inputs, navigated like a shell history.
+///
+/// Leaving the newest slot stashes the in-progress draft so it can be restored
+/// when the user navigates back down.
+#[derive(Clone, Debug)]
+pub struct InputHistory {
+    entries: VecDeque<String>,
+    capacity: usize,
+    /// Index into `entries` while browsing, `None` at the draft slot
+    position: Option<usize>,
+    draft: String,
+}
+
+impl Default for InputHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+impl InputHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            position: None,
+            draft: String::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether the user is currently browsing older entries
+    pub fn is_browsing(&self) -> bool {
+        self.position.is_some()
+    }
+
+    /// Record a sent message and return to the draft slot
+    pub fn push(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        self.reset();
+        if text.trim().is_empty() || self.entries.back() == Some(&text) {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(text);
+    }
+
+    /// Step in `direction`, returning the text to show, or `None` when there
+    /// is nothing further in that direction
+    pub fn navigate(&mut self, direction: HistoryDirection, current: &str) -> Option<&str> {
+        match direction {
+            HistoryDirection::Previous => self.previous(current),
+            HistoryDirection::Next => self.next(),
+        }
+    }
+
+    /// Step to an older entry, stashing `current` as the draft when leaving
+    /// the newest slot
+    pub fn previous(&mut self, current: &str) -> Option<&str> {
+        let index = match self.position {
+            _ if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+            Some(0) => return None,
+            Some(index) => index - 1,
+        };
+        self.position = Some(index);
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Step to a newer entry, restoring the draft past the newest one
+    pub fn next(&mut self) -> Option<&str> {
+        let index = self.position?;
+        if index + 1 < self.entries.len() {
+            self.position = Some(index + 1);
+            self.entries.get(index + 1).map(String::as_str)
+        } else {
+            self.position = None;
+            Some(&self.draft)
+        }
+    }
+
+    /// Return to the draft slot without restoring anything
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+}
+
+/// Up/Down only navigate history when the cursor is on the first/last line, so
+/// moving within a multi-line draft keeps working
+pub fn should_navigate_history(text: &str, cursor: usize, direction: HistoryDirection) -> bool {
+    let cursor = cursor.min(text.len());
+    match direction {
+        HistoryDirection::Previous => !text[..cursor].contains('\n'),
+        HistoryDirection::Next => !text[cursor..].contains('\n'),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_drops_oldest_entries() {
+        let mut history = InputHistory::new(2);
+        history.push("one");
+        history.push("two");
+        history.push("three");
+        assert_eq!(history.len(), 2);
+
+        assert_eq!(history.previous(""), Some("three"));
+        assert_eq!(history.previous(""), Some("two"));
+        assert_eq!(history.previous(""), None);
+    }
+
+    #[test]
+    fn ring_skips_blank_and_repeated_entries() {
+        let mut history = InputHistory::default();
+        history.push("  ");
+        history.push("same");
+        history.push("same");
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn returning_to_newest_slot_restores_draft() {
+        let mut history = InputHistory::default();
+        history.push("first");
+        history.push("second");
+
+        assert_eq!(history.next(), None);
+        assert_eq!(history.previous("half-typed"), Some("second"));
+        assert_eq!(history.previous("second"), Some("first"));
+        assert_eq!(history.next(), Some("second"));
+        assert_eq!(history.next(), Some("half-typed"));
+        assert!(!history.is_browsing());
+        assert_eq!(history.next(), None);
+    }
+
+    #[test]
+    fn up_inside_multiline_draft_moves_cursor() {
+        let text = "line one\nline two\nline three";
+        let middle = text.find("two").unwrap();
+
+        assert!(!should_navigate_history(
+            text,
+            middle,
+            HistoryDirection::Previous
+        ));
+        assert!(!should_navigate_history(
+            text,
+            middle,
+            HistoryDirection::Next
+        ));
+
+        assert!(should_navigate_history(text, 3, HistoryDirection::Previous));
+        assert!(should_navigate_history(
+            text,
+            text.len(),
+            HistoryDirection::Next
+        ));
+        assert!(should_navigate_history("", 0, HistoryDirection::Previous));
+    }
+}
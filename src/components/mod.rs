@@ -1,7 +1,12 @@
 mod agent_select;
+mod attachments;
+mod bounded_scroll;
 mod chat_input_box;
+mod command_palette;
 mod command_suggestions_popover;
+mod edit_preview;
 mod file_picker;
+mod input_history;
 mod input_suggestion;
 mod select_items;
 mod status_indicator;
@@ -9,21 +14,41 @@ mod status_indicator;
 // ACP UI components live in the agentx-acp-ui crate.
 pub use agentx_acp_ui::{
     AcpMessageStream, AcpMessageStreamOptions, AgentMessage, AgentMessageData, AgentMessageMeta,
-    AgentMessageOptions, AgentMessageView, AgentThoughtItem, AgentTodoList, AgentTodoListView,
-    DiffSummary, DiffSummaryData, DiffSummaryOptions, DiffSummaryToolCallHandler, DiffView,
-    FileChangeStats, PermissionRequest, PermissionRequestOptions, PermissionRequestView,
-    PermissionResponseHandler, PlanMeta, ToolCallItem, ToolCallItemOptions, ToolCallItemView,
-    UserMessage, UserMessageData, UserMessageView,
+    AgentMessageOptions, AgentMessageView, AgentThoughtItem, AgentTodoList, AgentTodoListOptions,
+    AgentTodoListView, DiffSummary, DiffSummaryData, DiffSummaryOptions,
+    DiffSummaryToolCallHandler, DiffView, FileChangeStats, MessageStreamEvent,
+    PermissionLookupHandler, PermissionRememberHandler, PermissionRequest,
+    PermissionRequestOptions, PermissionRequestView, PermissionResponseHandler, PlanMeta,
+    TodoListChangeHandler, ToolCallItem, ToolCallItemOptions, ToolCallItemView, UserMessage,
+    UserMessageData, UserMessageView,
 };
 
 pub use agent_select::AgentItem;
 
+pub use attachments::{
+    AttachmentError, DroppedAttachments, MAX_ATTACHMENT_SIZE, attachments_from_paths,
+};
+
+pub use bounded_scroll::{BoundedScroll, has_more_below};
+
 pub use chat_input_box::ChatInputBox;
 
+pub use command_palette::{
+    CommandPaletteDelegate, PaletteCommand, PaletteConfirmHandler, filter_palette_commands,
+};
+
+pub use command_suggestions_popover::{FuzzyMatch, filter_commands, fuzzy_match};
+
+pub use edit_preview::edit_preview;
+
+pub use input_history::{
+    DEFAULT_HISTORY_CAPACITY, HistoryDirection, InputHistory, should_navigate_history,
+};
+
 pub use input_suggestion::{InputSuggestion, InputSuggestionItem, InputSuggestionState};
 
 pub use file_picker::{FileItem, FilePickerDelegate};
 
 pub use select_items::{ModeSelectItem, ModelSelectItem};
 
-pub use status_indicator::StatusIndicator;
+pub use status_indicator::{IndicatorKind, StatusIndicator, indicator_kind};
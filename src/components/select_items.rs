@@ -1,3 +1,4 @@
+use agent_client_protocol as acp;
 use gpui::SharedString;
 use gpui_component::select::SelectItem;
 
@@ -41,6 +42,22 @@ impl ModelSelectItem {
             label: label.into(),
         }
     }
+
+    /// Items for the models an agent offers in a session, labelled by name when it has one
+    pub fn from_session_models(models: &acp::SessionModelState) -> Vec<Self> {
+        models
+            .available_models
+            .iter()
+            .map(|model| {
+                let label = if model.name.is_empty() {
+                    model.model_id.to_string()
+                } else {
+                    model.name.clone()
+                };
+                Self::new(model.model_id.to_string(), label)
+            })
+            .collect()
+    }
 }
 
 impl SelectItem for ModelSelectItem {
@@ -1,12 +1,51 @@
-use gpui::{IntoElement, RenderOnce, Styled, Window, div, px};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use gpui::{IntoElement, RenderOnce, Styled, Window, div, prelude::*, px};
+use gpui_component::{Icon, IconName, Sizable, spinner::Spinner};
+
+use agentx_types::AgentRuntimeStatus;
 
 use crate::core::services::SessionStatus;
 
-/// A status indicator dot that shows different colors for different statuses
+/// What the indicator shows for a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndicatorKind {
+    /// The agent is working on a turn: a spinner
+    Thinking,
+    /// The agent is running a tool: a gear
+    Tool,
+    /// Nothing in progress: a dot colored by status
+    Idle,
+    /// The session failed: a red dot
+    Error,
+}
+
+/// Map a session status to its indicator. `thought_in_flight` is set while an
+/// agent thought is streaming and `tool_in_flight` while a tool call is
+/// pending or running; a streaming thought takes precedence.
+pub fn indicator_kind(
+    status: &SessionStatus,
+    thought_in_flight: bool,
+    tool_in_flight: bool,
+) -> IndicatorKind {
+    match status {
+        SessionStatus::Failed => IndicatorKind::Error,
+        status if status.is_turn_in_progress() => {
+            if tool_in_flight && !thought_in_flight {
+                IndicatorKind::Tool
+            } else {
+                IndicatorKind::Thinking
+            }
+        }
+        _ => IndicatorKind::Idle,
+    }
+}
+
+/// A status indicator that shows different colors for different statuses,
+/// and a spinner or gear while the agent is thinking or running a tool
 #[derive(IntoElement)]
 pub struct StatusIndicator {
     status: SessionStatus,
+    thought_in_flight: bool,
+    tool_in_flight: bool,
     size: f32,
     opacity: Option<f32>,
 }
@@ -15,11 +54,35 @@ impl StatusIndicator {
     pub fn new(status: SessionStatus) -> Self {
         Self {
             status,
+            thought_in_flight: false,
+            tool_in_flight: false,
             size: 8.0,
             opacity: None,
         }
     }
 
+    /// Indicator for an agent process: spinning while starting, green when
+    /// ready, red when failed and grey once stopped
+    pub fn for_agent(status: &AgentRuntimeStatus) -> Self {
+        Self::new(match status {
+            AgentRuntimeStatus::Starting => SessionStatus::Pending,
+            AgentRuntimeStatus::Ready => SessionStatus::Active,
+            AgentRuntimeStatus::Failed { .. } => SessionStatus::Failed,
+            AgentRuntimeStatus::Stopped => SessionStatus::Closed,
+        })
+    }
+
+    /// Show whether a thought is streaming or a tool call is running
+    pub fn activity(mut self, thought_in_flight: bool, tool_in_flight: bool) -> Self {
+        self.thought_in_flight = thought_in_flight;
+        self.tool_in_flight = tool_in_flight;
+        self
+    }
+
+    pub fn kind(&self) -> IndicatorKind {
+        indicator_kind(&self.status, self.thought_in_flight, self.tool_in_flight)
+    }
+
     pub fn size(mut self, size: f32) -> Self {
         self.size = size;
         self
@@ -39,53 +102,84 @@ impl StatusIndicator {
             SessionStatus::Completed => gpui::rgb(0x22c55e).into(),
             SessionStatus::Failed => gpui::rgb(0xef4444).into(),
             SessionStatus::Closed => gpui::rgb(0x6b7280).into(),
+            SessionStatus::Interrupted => gpui::rgb(0xf59e0b).into(),
         }
     }
+}
 
-    fn should_pulse(&self) -> bool {
-        matches!(
-            self.status,
-            SessionStatus::InProgress | SessionStatus::Pending
-        )
-    }
+impl RenderOnce for StatusIndicator {
+    fn render(self, _window: &mut Window, _cx: &mut gpui::App) -> impl IntoElement {
+        let color = self.status_color();
+        let size_px = px(self.size);
+        // Icons need a little more room than a dot to stay legible
+        let icon_size = px(self.size * 1.5);
 
-    fn calculate_pulse_opacity() -> f32 {
-        // Calculate opacity based on current time for smooth pulsing
-        const PULSE_DURATION_MS: u64 = 1500; // 1.5 seconds for one full pulse cycle
+        match self.kind() {
+            IndicatorKind::Thinking => Spinner::new()
+                .icon(IconName::Loader)
+                .with_size(icon_size)
+                .color(color)
+                .into_any_element(),
+            IndicatorKind::Tool => Icon::new(IconName::Settings)
+                .size(icon_size)
+                .text_color(color)
+                .into_any_element(),
+            IndicatorKind::Idle | IndicatorKind::Error => div()
+                .flex_shrink_0()
+                .w(size_px)
+                .h(size_px)
+                .rounded(size_px / 2.0) // Make it circular
+                .bg(color)
+                .opacity(self.opacity.unwrap_or(1.0))
+                .into_any_element(),
+        }
+    }
+}
 
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::ZERO);
-        let elapsed_ms = now.as_millis() as u64;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let phase = (elapsed_ms % PULSE_DURATION_MS) as f32 / PULSE_DURATION_MS as f32;
-        let angle = phase * 2.0 * std::f32::consts::PI;
+    #[test]
+    fn maps_session_states_to_indicator_kinds() {
+        let cases = [
+            (SessionStatus::Active, IndicatorKind::Idle),
+            (SessionStatus::Idle, IndicatorKind::Idle),
+            (SessionStatus::Pending, IndicatorKind::Thinking),
+            (SessionStatus::InProgress, IndicatorKind::Thinking),
+            (SessionStatus::Completed, IndicatorKind::Idle),
+            (SessionStatus::Closed, IndicatorKind::Idle),
+            (SessionStatus::Failed, IndicatorKind::Error),
+            (SessionStatus::Interrupted, IndicatorKind::Idle),
+        ];
 
-        // Oscillate between 0.3 and 1.0 opacity
-        0.3 + (0.7 * ((angle.sin() + 1.0) / 2.0))
+        for (status, expected) in cases {
+            assert_eq!(
+                indicator_kind(&status, false, false),
+                expected,
+                "{status:?}"
+            );
+        }
     }
-}
 
-impl RenderOnce for StatusIndicator {
-    fn render(self, _window: &mut Window, _cx: &mut gpui::App) -> impl IntoElement {
-        let color = self.status_color();
-        let size_px = px(self.size);
+    #[test]
+    fn running_tool_shows_gear_unless_thinking() {
+        let status = SessionStatus::InProgress;
 
-        // Use provided opacity or calculate pulse opacity if needed
-        let opacity = self.opacity.unwrap_or_else(|| {
-            if self.should_pulse() {
-                Self::calculate_pulse_opacity()
-            } else {
-                1.0
-            }
-        });
-
-        div()
-            .flex_shrink_0()
-            .w(size_px)
-            .h(size_px)
-            .rounded(size_px / 2.0) // Make it circular
-            .bg(color)
-            .opacity(opacity)
+        assert_eq!(indicator_kind(&status, false, true), IndicatorKind::Tool);
+        assert_eq!(
+            indicator_kind(&status, true, false),
+            IndicatorKind::Thinking
+        );
+        assert_eq!(indicator_kind(&status, true, true), IndicatorKind::Thinking);
+        // Leftover activity doesn't matter once the turn is over
+        assert_eq!(
+            indicator_kind(&SessionStatus::Completed, true, true),
+            IndicatorKind::Idle
+        );
+        assert_eq!(
+            indicator_kind(&SessionStatus::Failed, false, true),
+            IndicatorKind::Error
+        );
     }
 }
@@ -1,2 +1,2 @@
 // Re-export from agentx-agent crate
-pub use agentx_agent::{AgentHandle, AgentManager, PermissionStore};
+pub use agentx_agent::{AgentHandle, AgentManager, PermissionScope, PermissionStore};
@@ -1,6 +1,9 @@
 use anyhow::{Context, Result};
+use rust_i18n::t;
 use std::path::{Path, PathBuf};
 
+pub use agentx_types::config_manager::{ValidationError, migrate, parse_config, validate_raw};
+
 /// Get the user data directory for AgentX
 /// - macOS: ~/.agentx/
 /// - Windows: %APPDATA%\agentx\
@@ -114,12 +117,70 @@ pub fn load_user_config() -> Result<crate::core::config::Config> {
     let config_content = std::fs::read_to_string(&config_path)
         .with_context(|| format!("Failed to read config file: {:?}", config_path))?;
 
-    let config: crate::core::config::Config = serde_json::from_str(&config_content)
+    let config = parse_config(&config_content)
         .with_context(|| format!("Failed to parse config file: {:?}", config_path))?;
 
     Ok(config)
 }
 
+/// Why [`load_config_or_default`] fell back to the embedded default config
+#[derive(Debug)]
+pub enum ConfigFallback {
+    /// The file did not exist and the default was written in its place
+    Created,
+    /// The file could not be read or parsed; it is left untouched
+    Invalid(anyhow::Error),
+}
+
+impl ConfigFallback {
+    /// User-facing explanation, pointing at the file to fix
+    pub fn message(&self, config_path: &Path) -> String {
+        match self {
+            Self::Created => {
+                t!("config.fallback.created", path = config_path.display()).to_string()
+            }
+            Self::Invalid(e) => t!(
+                "config.fallback.invalid",
+                path = config_path.display(),
+                error = format!("{:#}", e)
+            )
+            .to_string(),
+        }
+    }
+}
+
+/// Load the config at `config_path`, falling back to the embedded default
+/// when the file is missing or broken so the app still starts with agents.
+///
+/// A missing file is created from the default; a broken one is left for the
+/// user to fix. Only fails if the embedded default itself is unusable.
+pub fn load_config_or_default(
+    config_path: &Path,
+) -> Result<(crate::core::config::Config, Option<ConfigFallback>)> {
+    let fallback = match std::fs::read_to_string(config_path) {
+        Ok(raw) => match parse_config(&raw)
+            .with_context(|| format!("invalid config at {}", config_path.display()))
+        {
+            Ok(config) => return Ok((config, None)),
+            Err(e) => ConfigFallback::Invalid(e),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            if let Err(e) = ensure_default_config_at(config_path) {
+                log::warn!("Failed to write default config: {:#}", e);
+            }
+            ConfigFallback::Created
+        }
+        Err(e) => ConfigFallback::Invalid(
+            anyhow::Error::new(e).context(format!("failed to read {}", config_path.display())),
+        ),
+    };
+
+    let raw = crate::assets::get_default_config()
+        .ok_or_else(|| anyhow::anyhow!("embedded default config missing"))?;
+    let config = parse_config(&raw).context("invalid embedded default config")?;
+    Ok((config, Some(fallback)))
+}
+
 /// Get the themes directory path in the user data directory
 pub fn get_themes_dir() -> Result<PathBuf> {
     Ok(user_data_dir_or_temp().join("themes"))
@@ -178,8 +239,65 @@ pub fn get_docks_layout_path() -> PathBuf {
     user_data_dir_or_temp().join("docks-layout.json")
 }
 
+/// Get docks layout file path for a workspace window
+/// The first window uses <user_data_dir>/docks-layout.json, later windows
+/// <user_data_dir>/docks-layout-<index>.json
+pub fn get_window_docks_layout_path(window_index: usize) -> PathBuf {
+    user_data_dir_or_temp().join(docks_layout_file_name(window_index))
+}
+
+fn docks_layout_file_name(window_index: usize) -> String {
+    match window_index {
+        0 => "docks-layout.json".to_string(),
+        index => format!("docks-layout-{}.json", index),
+    }
+}
+
+/// Get docks layout presets file path
+/// Always uses user data directory: <user_data_dir>/docks-presets.json
+pub fn get_docks_presets_path() -> PathBuf {
+    user_data_dir_or_temp().join("docks-presets.json")
+}
+
 /// Get sessions directory path
 /// Always uses user data directory: <user_data_dir>/sessions
 pub fn get_sessions_dir() -> PathBuf {
     user_data_dir_or_temp().join("sessions")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_config_falls_back_to_embedded_default() {
+        let dir = std::env::temp_dir().join(format!("agentx_config_{}", std::process::id()));
+        let path = dir.join("config.json");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let (config, fallback) = load_config_or_default(&path).unwrap();
+        assert!(matches!(fallback, Some(ConfigFallback::Created)));
+        assert!(!config.agent_servers.is_empty());
+
+        // The default was written out, so the next start reads it normally
+        let (reloaded, fallback) = load_config_or_default(&path).unwrap();
+        assert!(fallback.is_none());
+        assert_eq!(reloaded.agent_servers.len(), config.agent_servers.len());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn window_layout_paths_are_distinct() {
+        assert_eq!(docks_layout_file_name(0), "docks-layout.json");
+        assert_eq!(docks_layout_file_name(1), "docks-layout-1.json");
+        assert_eq!(
+            get_window_docks_layout_path(0),
+            get_docks_layout_path(),
+            "the first window keeps the original layout file"
+        );
+        assert_ne!(
+            get_window_docks_layout_path(1),
+            get_window_docks_layout_path(2)
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use agentx_event_bus::EventHub;
+use gpui::{App, Context, Entity};
+
+/// Helper function to keep a panel entity in sync with the application working
+/// directory; `on_change` runs with the new path after every change.
+pub fn subscribe_entity_to_cwd_changes<T, F>(
+    entity: &Entity<T>,
+    event_hub: EventHub,
+    panel_name: &'static str,
+    on_change: F,
+    cx: &mut App,
+) where
+    T: 'static,
+    F: Fn(&mut T, PathBuf, &mut Context<T>) + 'static,
+{
+    let weak_entity = entity.downgrade();
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<PathBuf>();
+
+    event_hub.subscribe_cwd_changes(move |path| {
+        log::debug!("[{}] Working directory changed: {:?}", panel_name, path);
+        let _ = tx.send(path.to_path_buf());
+    });
+
+    cx.spawn(async move |cx| {
+        while let Some(path) = rx.recv().await {
+            if let Some(entity) = weak_entity.upgrade() {
+                let _ = cx.update(|cx| {
+                    entity.update(cx, |panel, cx| {
+                        on_change(panel, path, cx);
+                    });
+                });
+            } else {
+                break;
+            }
+        }
+    })
+    .detach();
+}
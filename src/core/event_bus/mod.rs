@@ -9,10 +9,12 @@ pub use agentx_event_bus::batching::{
 pub use agentx_event_bus::core::{EventBus, EventBusContainer, EventBusStats, SubscriptionId};
 pub use agentx_event_bus::hub::{AppEvent, EventHub};
 pub use agentx_event_bus::{
-    AgentConfigEvent, CodeSelectionEvent, PermissionRequestEvent, SessionUpdateEvent,
-    WorkspaceUpdateEvent,
+    AgentConfigEvent, CodeSelectionEvent, ConfigSection, PermissionRequestEvent,
+    SessionUpdateEvent, WorkspaceUpdateEvent,
 };
 
 // GPUI-specific helpers that depend on gpui types
 mod code_selection_helper;
+mod cwd_change_helper;
 pub use code_selection_helper::subscribe_entity_to_code_selections;
+pub use cwd_change_helper::subscribe_entity_to_cwd_changes;
@@ -8,7 +8,17 @@ pub use agentx_services::AgentSessionInfo;
 pub use agentx_services::AiService;
 pub use agentx_services::CommentStyle;
 pub use agentx_services::ConfigWatcher;
+pub use agentx_services::Edit;
+pub use agentx_services::EventLog;
+pub use agentx_services::FileWatcher;
 pub use agentx_services::MessageService;
 pub use agentx_services::PersistenceService;
+pub use agentx_services::ReplyCollector;
+pub use agentx_services::SessionBranch;
 pub use agentx_services::SessionStatus;
+pub use agentx_services::SessionTitler;
 pub use agentx_services::WorkspaceService;
+pub use agentx_services::apply_edits;
+pub use agentx_services::edits_diff;
+pub use agentx_services::headless;
+pub use agentx_services::is_imported_session;
@@ -0,0 +1,44 @@
+use std::fmt;
+
+use super::version::Version;
+
+/// Release channel an installation follows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UpdateChannel {
+    /// Only final releases
+    #[default]
+    Stable,
+    /// Final releases plus pre-release (`-beta`, `-rc`, ...) tags
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+
+    /// Parse a channel name, falling back to `Stable` for unknown values
+    pub fn from_str_or_default(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "beta" => UpdateChannel::Beta,
+            _ => UpdateChannel::Stable,
+        }
+    }
+
+    /// Whether a release with the given version belongs to this channel
+    pub fn accepts(&self, version: &Version) -> bool {
+        match self {
+            UpdateChannel::Stable => !version.is_prerelease(),
+            UpdateChannel::Beta => true,
+        }
+    }
+}
+
+impl fmt::Display for UpdateChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
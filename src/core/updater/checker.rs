@@ -1,3 +1,4 @@
+use super::channel::UpdateChannel;
 use super::version::Version;
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
@@ -6,7 +7,7 @@ use std::time::Duration;
 
 static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
 
-fn tokio_handle() -> tokio::runtime::Handle {
+pub(super) fn tokio_handle() -> tokio::runtime::Handle {
     tokio::runtime::Handle::try_current().unwrap_or_else(|_| {
         RUNTIME
             .get_or_init(|| {
@@ -25,6 +26,9 @@ fn tokio_handle() -> tokio::runtime::Handle {
 pub struct UpdateInfo {
     pub version: String,
     pub download_url: String,
+    /// URL of the minisign signature published for `download_url`
+    #[serde(default)]
+    pub signature_url: Option<String>,
     pub release_notes: String,
     pub published_at: String,
     pub file_size: Option<u64>,
@@ -41,24 +45,36 @@ pub enum UpdateCheckResult {
 pub struct UpdateChecker {
     check_url: String,
     timeout: Duration,
+    channel: UpdateChannel,
 }
 
 impl UpdateChecker {
     pub fn new() -> Self {
         Self {
-            check_url: "https://api.github.com/repos/sxhxliang/agent-studio/releases/latest"
-                .to_string(),
+            check_url: "https://api.github.com/repos/sxhxliang/agent-studio/releases".to_string(),
             timeout: Duration::from_secs(10),
+            channel: UpdateChannel::default(),
         }
     }
 
+    /// Set the release channel used when picking the latest release
+    pub fn with_channel(mut self, channel: UpdateChannel) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    pub fn channel(&self) -> UpdateChannel {
+        self.channel
+    }
+
     /// Safe to call from any async executor (GPUI, tokio, etc.).
     pub async fn check_for_updates(&self) -> UpdateCheckResult {
         let check_url = self.check_url.clone();
         let timeout = self.timeout;
+        let channel = self.channel;
 
         let fetch_result = tokio_handle()
-            .spawn(async move { fetch_latest_release(&check_url, timeout).await })
+            .spawn(async move { fetch_latest_release(&check_url, timeout, channel).await })
             .await;
 
         let info = match fetch_result {
@@ -97,8 +113,16 @@ impl Default for UpdateChecker {
     }
 }
 
-async fn fetch_latest_release(check_url: &str, timeout: Duration) -> Result<UpdateInfo> {
-    log::info!("Fetching latest release from: {}", check_url);
+async fn fetch_latest_release(
+    check_url: &str,
+    timeout: Duration,
+    channel: UpdateChannel,
+) -> Result<UpdateInfo> {
+    log::info!(
+        "Fetching latest {} release from: {}",
+        channel.as_str(),
+        check_url
+    );
 
     let client = reqwest::Client::builder()
         .timeout(timeout)
@@ -116,18 +140,40 @@ async fn fetch_latest_release(check_url: &str, timeout: Duration) -> Result<Upda
     }
 
     let body = response.text().await?;
-    let release: GitHubRelease = serde_json::from_str(&body)?;
+    let releases: Vec<GitHubRelease> = serde_json::from_str(&body)?;
+    let release = select_latest_release(releases, channel)
+        .ok_or_else(|| anyhow!("No {} release found", channel.as_str()))?;
     let download_url = find_platform_asset(&release.assets);
+    let signature_url = find_signature_asset(&release.assets, &download_url);
 
     Ok(UpdateInfo {
         version: release.tag_name,
         download_url,
+        signature_url,
         release_notes: release.body.unwrap_or_default(),
         published_at: release.published_at,
         file_size: release.assets.first().map(|a| a.size),
     })
 }
 
+/// Pick the highest-versioned release that belongs to `channel`.
+///
+/// Drafts and tags that don't parse as versions are skipped. A release counts
+/// as a pre-release if either its tag carries a pre-release suffix or GitHub
+/// flags it as one.
+fn select_latest_release(
+    releases: Vec<GitHubRelease>,
+    channel: UpdateChannel,
+) -> Option<GitHubRelease> {
+    releases
+        .into_iter()
+        .filter(|r| !r.draft)
+        .filter_map(|r| Version::parse(&r.tag_name).ok().map(|v| (v, r)))
+        .filter(|(v, r)| channel.accepts(v) && (channel == UpdateChannel::Beta || !r.prerelease))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, r)| r)
+}
+
 fn find_platform_asset(assets: &[GitHubAsset]) -> String {
     let patterns: &[&str] = match (std::env::consts::OS, std::env::consts::ARCH) {
         ("macos", "aarch64") => &["aarch64-apple-darwin", "arm64-macos", "darwin-arm64"],
@@ -154,11 +200,27 @@ fn find_platform_asset(assets: &[GitHubAsset]) -> String {
         .unwrap_or_default()
 }
 
+/// Locate the minisign signature published for the asset at `download_url`,
+/// a sibling `<asset>.minisig` file
+fn find_signature_asset(assets: &[GitHubAsset], download_url: &str) -> Option<String> {
+    let asset_name = download_url.rsplit('/').next().filter(|s| !s.is_empty())?;
+    let sibling = format!("{}.minisig", asset_name).to_lowercase();
+
+    assets
+        .iter()
+        .find(|a| a.name.to_lowercase() == sibling)
+        .map(|a| a.browser_download_url.clone())
+}
+
 #[derive(Debug, Deserialize)]
 struct GitHubRelease {
     tag_name: String,
     body: Option<String>,
     published_at: String,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
     assets: Vec<GitHubAsset>,
 }
 
@@ -168,3 +230,82 @@ struct GitHubAsset {
     browser_download_url: String,
     size: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_releases() -> Vec<GitHubRelease> {
+        serde_json::from_str(
+            r#"[
+                {"tag_name": "v0.5.0-beta.2", "body": null, "published_at": "2025-12-20T00:00:00Z", "prerelease": true, "assets": []},
+                {"tag_name": "v0.5.0-beta.1", "body": null, "published_at": "2025-12-10T00:00:00Z", "prerelease": true, "assets": []},
+                {"tag_name": "v0.4.2", "body": "stable", "published_at": "2025-12-01T00:00:00Z", "assets": []},
+                {"tag_name": "v0.4.1", "body": null, "published_at": "2025-11-01T00:00:00Z", "assets": []},
+                {"tag_name": "v0.6.0", "body": null, "published_at": "2025-12-30T00:00:00Z", "draft": true, "assets": []},
+                {"tag_name": "nightly", "body": null, "published_at": "2025-12-31T00:00:00Z", "assets": []}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_stable_channel_ignores_prereleases() {
+        let release = select_latest_release(fixture_releases(), UpdateChannel::Stable).unwrap();
+        assert_eq!(release.tag_name, "v0.4.2");
+    }
+
+    #[test]
+    fn test_beta_channel_picks_prereleases() {
+        let release = select_latest_release(fixture_releases(), UpdateChannel::Beta).unwrap();
+        assert_eq!(release.tag_name, "v0.5.0-beta.2");
+    }
+
+    #[test]
+    fn test_beta_channel_prefers_final_release() {
+        let mut releases = fixture_releases();
+        releases.push(GitHubRelease {
+            tag_name: "v0.5.0".to_string(),
+            body: None,
+            published_at: "2025-12-25T00:00:00Z".to_string(),
+            prerelease: false,
+            draft: false,
+            assets: vec![],
+        });
+        let release = select_latest_release(releases, UpdateChannel::Beta).unwrap();
+        assert_eq!(release.tag_name, "v0.5.0");
+    }
+
+    #[test]
+    fn test_find_signature_asset() {
+        let asset = |name: &str| GitHubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+            size: 0,
+        };
+        let assets = vec![
+            asset("agentx-linux-x64.tar.gz"),
+            asset("agentx-linux-x64.tar.gz.minisig"),
+            asset("agentx-macos.dmg.minisig"),
+        ];
+        assert_eq!(
+            find_signature_asset(&assets, "https://example.com/agentx-linux-x64.tar.gz"),
+            Some("https://example.com/agentx-linux-x64.tar.gz.minisig".to_string())
+        );
+
+        // An unsigned checksum file is not a signature
+        let assets = vec![
+            asset("agentx-linux-x64.tar.gz"),
+            asset("agentx-linux-x64.tar.gz.sha256"),
+        ];
+        assert_eq!(
+            find_signature_asset(&assets, "https://example.com/agentx-linux-x64.tar.gz"),
+            None
+        );
+
+        assert_eq!(
+            find_signature_asset(&[asset("agentx-linux-x64.tar.gz")], ""),
+            None
+        );
+    }
+}
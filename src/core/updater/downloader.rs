@@ -1,5 +1,7 @@
+use super::checker::tokio_handle;
 use anyhow::{Result, anyhow};
 use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
 
 /// Progress callback for download operations
 pub type ProgressCallback = Box<dyn Fn(u64, u64) + Send + Sync>;
@@ -38,7 +40,7 @@ impl UpdateDownloader {
         &self,
         url: &str,
         filename: Option<&str>,
-        _progress: Option<ProgressCallback>,
+        progress: Option<ProgressCallback>,
     ) -> Result<PathBuf> {
         let filename = filename
             .map(|s| s.to_string())
@@ -46,31 +48,29 @@ impl UpdateDownloader {
             .ok_or_else(|| anyhow!("Could not determine filename"))?;
 
         let file_path = self.download_dir.join(&filename);
+        log::info!("Downloading update from {} to {:?}", url, file_path);
 
-        // TODO: Implement real HTTP download
-        // Example implementation:
-        // let client = reqwest::Client::new();
-        // let mut response = client.get(url).send().await?;
-        // let total_size = response.content_length().unwrap_or(0);
-        //
-        // let mut file = tokio::fs::File::create(&file_path).await?;
-        // let mut downloaded = 0u64;
-        //
-        // while let Some(chunk) = response.chunk().await? {
-        //     file.write_all(&chunk).await?;
-        //     downloaded += chunk.len() as u64;
-        //     if let Some(ref callback) = progress {
-        //         callback(downloaded, total_size);
-        //     }
-        // }
-        //
-        // file.flush().await?;
-
-        log::info!("Download would save to: {:?}", file_path);
-
-        Err(anyhow!(
-            "Download not yet implemented. Please add reqwest dependency and implement HTTP client."
-        ))
+        let url = url.to_string();
+        let target = file_path.clone();
+        tokio_handle()
+            .spawn(async move { download_to_file(&url, &target, progress).await })
+            .await??;
+
+        Ok(file_path)
+    }
+
+    /// Fetch a small text resource (e.g. a checksum manifest)
+    pub async fn fetch_text(&self, url: &str) -> Result<String> {
+        let url = url.to_string();
+        tokio_handle()
+            .spawn(async move {
+                let response = http_client()?.get(&url).send().await?;
+                if !response.status().is_success() {
+                    return Err(anyhow!("Request to {} failed: {}", url, response.status()));
+                }
+                Ok(response.text().await?)
+            })
+            .await?
     }
 
     /// Extract filename from URL
@@ -96,6 +96,41 @@ impl UpdateDownloader {
     }
 }
 
+fn http_client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .user_agent(format!("AgentStudio/{}", env!("CARGO_PKG_VERSION")))
+        .build()?)
+}
+
+async fn download_to_file(
+    url: &str,
+    file_path: &Path,
+    progress: Option<ProgressCallback>,
+) -> Result<()> {
+    let mut response = http_client()?.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Download failed with status: {}",
+            response.status()
+        ));
+    }
+    let total_size = response.content_length().unwrap_or(0);
+
+    let mut file = tokio::fs::File::create(file_path).await?;
+    let mut downloaded = 0u64;
+
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk).await?;
+        downloaded += chunk.len() as u64;
+        if let Some(ref callback) = progress {
+            callback(downloaded, total_size);
+        }
+    }
+
+    file.flush().await?;
+    Ok(())
+}
+
 impl Default for UpdateDownloader {
     fn default() -> Self {
         Self::new().expect("Failed to create default downloader")
@@ -1,9 +1,13 @@
+mod channel;
 mod checker;
 mod downloader;
+mod verify;
 mod version;
 
+pub use channel::UpdateChannel;
 pub use checker::{UpdateCheckResult, UpdateChecker, UpdateInfo};
 pub use downloader::{ProgressCallback, UpdateDownloader};
+pub use verify::UpdateError;
 pub use version::Version;
 
 /// Update manager that coordinates checking, downloading, and installing updates
@@ -20,21 +24,56 @@ impl UpdateManager {
         })
     }
 
+    /// Follow the given release channel when checking for updates
+    pub fn with_channel(mut self, channel: UpdateChannel) -> Self {
+        self.checker = self.checker.with_channel(channel);
+        self
+    }
+
+    /// Release channel this manager checks against
+    pub fn channel(&self) -> UpdateChannel {
+        self.checker.channel()
+    }
+
     /// Check for available updates
     pub async fn check_for_updates(&self) -> UpdateCheckResult {
         self.checker.check_for_updates().await
     }
 
-    /// Download an update
+    /// Download an update and verify its minisign signature against the
+    /// public key built into the app.
+    ///
+    /// The path is only returned once verification succeeds; a tampered or
+    /// unverifiable artifact is deleted and reported as an [`UpdateError`].
     pub async fn download_update(
         &self,
         info: &UpdateInfo,
         progress: Option<ProgressCallback>,
     ) -> anyhow::Result<std::path::PathBuf> {
+        let public_key = verify::update_public_key()?;
+        let signature_url = info
+            .signature_url
+            .clone()
+            .ok_or_else(|| UpdateError::MissingSignature(info.download_url.clone()))?;
+
         let downloader = UpdateDownloader::new()?;
-        downloader
+        let path = downloader
             .download(&info.download_url, None, progress)
-            .await
+            .await?;
+
+        let verified = async {
+            let signature = downloader.fetch_text(&signature_url).await?;
+            verify::verify_signature(&path, &signature, public_key)
+        }
+        .await;
+
+        if let Err(e) = verified {
+            log::error!("Rejecting update {:?}: {}", path, e);
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+
+        Ok(path)
     }
 
     /// Get current application version
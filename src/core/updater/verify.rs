@@ -0,0 +1,127 @@
+use anyhow::Result;
+use minisign_verify::{PublicKey, Signature};
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+/// Minisign public key release artifacts are signed with, built into release
+/// binaries through the `AGENTX_UPDATE_PUBLIC_KEY` environment variable.
+/// Without it no update can be verified, so none is installed.
+const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("AGENTX_UPDATE_PUBLIC_KEY");
+
+/// Errors raised while verifying a downloaded update artifact
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateError {
+    /// This build has no public key to verify updates with
+    MissingPublicKey,
+    /// The release does not publish a signature for the asset
+    MissingSignature(String),
+    /// The signature is malformed, made with another key or does not match
+    /// the downloaded artifact
+    SignatureMismatch(String),
+}
+
+impl fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateError::MissingPublicKey => {
+                write!(
+                    f,
+                    "This build cannot verify updates: no public key built in"
+                )
+            }
+            UpdateError::MissingSignature(name) => {
+                write!(f, "No signature published for update asset: {}", name)
+            }
+            UpdateError::SignatureMismatch(reason) => {
+                write!(f, "Update signature verification failed: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UpdateError {}
+
+/// Public key updates must be signed with
+pub fn update_public_key() -> Result<&'static str, UpdateError> {
+    UPDATE_PUBLIC_KEY
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .ok_or(UpdateError::MissingPublicKey)
+}
+
+/// Verify the minisign `signature` of the file at `path` against
+/// `public_key` (the base64 line of a minisign `.pub` file). Only prehashed
+/// signatures are accepted, as made by current minisign versions.
+pub fn verify_signature(path: &Path, signature: &str, public_key: &str) -> Result<()> {
+    let mismatch = |e: minisign_verify::Error| UpdateError::SignatureMismatch(e.to_string());
+    let public_key = PublicKey::from_base64(public_key).map_err(mismatch)?;
+    let signature = Signature::decode(signature).map_err(mismatch)?;
+    let mut verifier = public_key.verify_stream(&signature).map_err(mismatch)?;
+
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        verifier.update(&buf[..n]);
+    }
+    verifier.finalize().map_err(mismatch)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test key pair generated for these tests only
+    const TEST_PUBLIC_KEY: &str = "RWQaKzxNXm9wgQ66YaN55ZSDNvSC/y8tHi72E8RmRF1rDU4FmW83xfWC";
+
+    /// Signature of `b"agentx release artifact"` made with the test key
+    const TEST_SIGNATURE: &str = "untrusted comment: signature from agentx test key
+RUQaKzxNXm9wgeBTQz362YF1BHuDYgmah1IeUUtw7JQ5OkwLO9DRIXCOLILt+Tfi2fN283IJMpYz+LXwshFy76qnWN7njNh7BgE=
+trusted comment: timestamp:1700000000\tfile:agentx-linux-x64.tar.gz
+1B43JiR5S2mHPBuEqHQlxtdfmAccYii1D0wVuPmOysRcuQen4mdNQYBdQbwOSx5gEYH9iWyBdzo1eHx/YmuECA==
+";
+
+    fn write_artifact(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join("agentx_update_verify_tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn is_mismatch(err: &anyhow::Error) -> bool {
+        matches!(
+            err.downcast_ref::<UpdateError>(),
+            Some(UpdateError::SignatureMismatch(_))
+        )
+    }
+
+    #[test]
+    fn test_verify_valid_artifact() {
+        let path = write_artifact("valid.bin", b"agentx release artifact");
+        verify_signature(&path, TEST_SIGNATURE, TEST_PUBLIC_KEY).unwrap();
+    }
+
+    #[test]
+    fn test_verify_tampered_artifact() {
+        let path = write_artifact("tampered.bin", b"agentx release artifact!");
+        let err = verify_signature(&path, TEST_SIGNATURE, TEST_PUBLIC_KEY).unwrap_err();
+        assert!(is_mismatch(&err));
+    }
+
+    #[test]
+    fn test_verify_with_another_key() {
+        let path = write_artifact("other_key.bin", b"agentx release artifact");
+        let other_key = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+        let err = verify_signature(&path, TEST_SIGNATURE, other_key).unwrap_err();
+        assert!(is_mismatch(&err));
+
+        let err = verify_signature(&path, "not a signature", TEST_PUBLIC_KEY).unwrap_err();
+        assert!(is_mismatch(&err));
+    }
+}
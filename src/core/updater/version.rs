@@ -6,6 +6,8 @@ pub struct Version {
     pub major: u32,
     pub minor: u32,
     pub patch: u32,
+    /// Pre-release identifier, e.g. `beta.1` for `0.4.1-beta.1`
+    pub pre: Option<String>,
 }
 
 impl Version {
@@ -14,12 +16,24 @@ impl Version {
             major,
             minor,
             patch,
+            pre: None,
         }
     }
 
-    /// Parse version from string like "0.4.1" or "v0.4.1"
+    /// Attach a pre-release identifier (e.g. `beta.1`)
+    pub fn with_pre(mut self, pre: impl Into<String>) -> Self {
+        self.pre = Some(pre.into());
+        self
+    }
+
+    /// Parse version from string like "0.4.1", "v0.4.1" or "v0.4.1-beta.1"
     pub fn parse(s: &str) -> Result<Self, String> {
         let s = s.trim().trim_start_matches('v');
+        let (s, pre) = match s.split_once('-') {
+            Some((core, pre)) if !pre.is_empty() => (core, Some(pre.to_string())),
+            Some(_) => return Err(format!("Invalid pre-release tag: {}", s)),
+            None => (s, None),
+        };
         let parts: Vec<&str> = s.split('.').collect();
 
         if parts.len() != 3 {
@@ -36,7 +50,12 @@ impl Version {
             .parse()
             .map_err(|_| format!("Invalid patch version: {}", parts[2]))?;
 
-        Ok(Self::new(major, minor, patch))
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
     }
 
     /// Get the current application version from Cargo.toml
@@ -48,11 +67,20 @@ impl Version {
     pub fn is_newer_than(&self, other: &Version) -> bool {
         self > other
     }
+
+    /// Whether this is a pre-release build (e.g. `-beta`, `-rc`)
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
 }
 
 impl fmt::Display for Version {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
     }
 }
 
@@ -66,7 +94,10 @@ impl Ord for Version {
     fn cmp(&self, other: &Self) -> Ordering {
         match self.major.cmp(&other.major) {
             Ordering::Equal => match self.minor.cmp(&other.minor) {
-                Ordering::Equal => self.patch.cmp(&other.patch),
+                Ordering::Equal => match self.patch.cmp(&other.patch) {
+                    Ordering::Equal => compare_pre(self.pre.as_deref(), other.pre.as_deref()),
+                    other => other,
+                },
                 other => other,
             },
             other => other,
@@ -74,6 +105,38 @@ impl Ord for Version {
     }
 }
 
+/// Semver pre-release precedence: a release outranks any of its pre-releases,
+/// and dot-separated identifiers compare numerically when both are numbers.
+fn compare_pre(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            let mut a_ids = a.split('.');
+            let mut b_ids = b.split('.');
+            loop {
+                match (a_ids.next(), b_ids.next()) {
+                    (None, None) => return Ordering::Equal,
+                    (None, Some(_)) => return Ordering::Less,
+                    (Some(_), None) => return Ordering::Greater,
+                    (Some(x), Some(y)) => {
+                        let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+                            (Ok(x), Ok(y)) => x.cmp(&y),
+                            (Ok(_), Err(_)) => Ordering::Less,
+                            (Err(_), Ok(_)) => Ordering::Greater,
+                            (Err(_), Err(_)) => x.cmp(y),
+                        };
+                        if ord != Ordering::Equal {
+                            return ord;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,6 +161,17 @@ mod tests {
         assert!(!v1.is_newer_than(&v2));
     }
 
+    #[test]
+    fn test_version_prerelease() {
+        let beta = Version::parse("v1.2.3-beta.2").unwrap();
+        assert_eq!(beta, Version::new(1, 2, 3).with_pre("beta.2"));
+        assert!(beta.is_prerelease());
+        assert!(Version::new(1, 2, 3).is_newer_than(&beta));
+        assert!(beta.is_newer_than(&Version::parse("1.2.3-beta.1").unwrap()));
+        assert!(beta.is_newer_than(&Version::new(1, 2, 2)));
+        assert_eq!(beta.to_string(), "1.2.3-beta.2");
+    }
+
     #[test]
     fn test_version_display() {
         let v = Version::new(1, 2, 3);
@@ -14,6 +14,7 @@ rust_i18n::i18n!("locales", fallback = "en");
 pub use app::key_binding;
 pub use assets::Assets;
 pub use assets::get_default_config;
+pub use assets::{register_agent_icon, unregister_agent_icon};
 
 // Re-export from panels module
 use crate::panels::{DockPanelContainer, DockPanelState};
@@ -36,12 +37,14 @@ pub use core::{
 pub use app::app_state::{AppState, WelcomeSession};
 pub use app::{
     actions::{
-        About, AddAgent, AddSessionToList, CancelSession, CloseWindow, CreateTaskFromWelcome, Info,
-        NewSessionConversationPanel, Open, OpenSessionManager, PanelAction, Quit,
-        ReloadAgentConfig, RemoveAgent, RestartAgent, SelectFont, SelectLocale, SelectRadius,
-        SelectScrollbarShow, SelectedAgentTask, SendMessageToSession, SetUploadDir, ShowPanelInfo,
-        Tab, TabPrev, TestAction, ToggleDockToggleButton, TogglePanelVisible, ToggleSearch,
-        UpdateAgent,
+        About, AddAgent, AddSessionToList, ApplyLayoutPreset, CancelSession,
+        CloseConversationPanels, CloseWindow, CreateTaskFromWelcome, ExportLayout, ImportLayout,
+        Info, NewSessionConversationPanel, NewWindow, Open, OpenSessionManager, PanelAction, Quit,
+        RegenerateResponse, ReloadAgentConfig, RemoveAgent, ReopenStartup, RestartAgent, SaveFile,
+        SaveLayoutPreset, SelectFont, SelectLocale, SelectRadius, SelectScrollbarShow,
+        SelectedAgentTask, SendMessageToSession, SetAgentIcon, SetAgentsEnabled, SetUploadDir,
+        ShowPanelInfo, Tab, TabPrev, TestAction, ToggleCommandPalette, ToggleDockToggleButton,
+        TogglePanelVisible, ToggleSearch, UpdateAgent,
     },
     app_menus, menu, system_tray, themes, title_bar,
 };
@@ -57,11 +60,12 @@ pub use title_bar::AppTitleBar;
 // Export components
 pub use components::{
     AcpMessageStream, AcpMessageStreamOptions, AgentMessage, AgentMessageData, AgentMessageMeta,
-    AgentMessageOptions, AgentMessageView, AgentThoughtItem, AgentTodoList, AgentTodoListView,
-    ChatInputBox, DiffSummary, DiffSummaryData, DiffSummaryOptions, DiffSummaryToolCallHandler,
-    FileChangeStats, PermissionRequest, PermissionRequestOptions, PermissionRequestView,
-    PermissionResponseHandler, PlanMeta, StatusIndicator, ToolCallItem, ToolCallItemOptions,
-    ToolCallItemView, UserMessage, UserMessageData, UserMessageView,
+    AgentMessageOptions, AgentMessageView, AgentThoughtItem, AgentTodoList, AgentTodoListOptions,
+    AgentTodoListView, ChatInputBox, DiffSummary, DiffSummaryData, DiffSummaryOptions,
+    DiffSummaryToolCallHandler, FileChangeStats, PermissionLookupHandler,
+    PermissionRememberHandler, PermissionRequest, PermissionRequestOptions, PermissionRequestView,
+    PermissionResponseHandler, PlanMeta, StatusIndicator, TodoListChangeHandler, ToolCallItem,
+    ToolCallItemOptions, ToolCallItemView, UserMessage, UserMessageData, UserMessageView,
 };
 
 // Re-export ACP types for convenience
@@ -207,11 +211,18 @@ pub fn init(cx: &mut App) {
         cx.quit();
     });
 
+    // Every window shares the global AppState (agent manager, event buses)
+    cx.on_action(|_: &NewWindow, cx: &mut App| {
+        workspace::open_new(cx, |_, _, _| {}).detach();
+    });
+
     // Register agent config action handlers
     cx.on_action(workspace::actions::add_agent);
     cx.on_action(workspace::actions::update_agent);
     cx.on_action(workspace::actions::remove_agent);
     cx.on_action(workspace::actions::restart_agent);
+    cx.on_action(workspace::actions::set_agents_enabled);
+    cx.on_action(workspace::actions::set_agent_icon);
     cx.on_action(workspace::actions::reload_agent_config);
     cx.on_action(workspace::actions::set_upload_dir);
     cx.on_action(workspace::actions::change_config_path);
@@ -2,14 +2,30 @@
 
 use agentx::Assets;
 use agentx::core::config_manager;
-use agentx::{AgentManager, Config, PermissionStore, workspace::open_new};
-use anyhow::Context as _;
+use agentx::core::services::{
+    AgentService, EventLog,
+    headless::{self, HeadlessArgs},
+};
+use agentx::{AgentManager, Config, EventHub, PermissionStore, workspace::open_new};
 use gpui::Application;
-use std::sync::Arc;
+use gpui_component::{WindowExt as _, notification::Notification};
+use std::{collections::HashMap, sync::Arc};
 
 fn main() {
     // Parse config path from command line arguments
     let config_path = parse_config_path();
+    let event_log_path = parse_event_log_path();
+
+    match HeadlessArgs::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => {
+            std::process::exit(run_headless(&config_path, event_log_path.as_deref(), args))
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
 
     let app = Application::new().with_assets(Assets);
     app.run(move |cx| {
@@ -34,62 +50,53 @@ fn main() {
         // Get EventHub from global AppState
         let event_hub = agentx::AppState::global(cx).event_hub().clone();
 
+        // Record session traffic for debugging when asked to
+        if let Some(path) = &event_log_path {
+            match EventLog::start(path, &event_hub) {
+                Ok(event_log) => event_log.detach(),
+                Err(e) => eprintln!("{:#}", e),
+            }
+        }
+
+        let loaded = load_config(&config_path);
+        let notice = loaded.as_ref().and_then(|(_, notice)| notice.clone());
+
         // Open GUI window immediately (non-blocking)
-        open_new(cx, |_, _, _| {
-            // GUI window is now open
+        open_new(cx, move |_, window, cx| {
+            // Tell the user the defaults are in use and which file to fix
+            if let Some(notice) = notice {
+                window.push_notification(Notification::warning(notice), cx);
+            }
         })
         .detach();
 
         // Initialize agents in the background (async, non-blocking)
         cx.spawn(async move |cx| {
-            let config: Config = match std::fs::read_to_string(&config_path)
-                .with_context(|| format!("failed to read {}", config_path.display()))
-            {
-                Ok(raw) => match serde_json::from_str(&raw)
-                    .with_context(|| format!("invalid config at {}", config_path.display()))
-                {
-                    Ok(config) => config,
-                    Err(e) => {
-                        eprintln!("Failed to parse config: {}", e);
-                        match load_default_config() {
-                            Ok(config) => config,
-                            Err(e) => {
-                                eprintln!("Failed to load default config: {}", e);
-                                return;
-                            }
-                        }
-                    }
-                },
-                Err(e) => {
-                    eprintln!("Failed to read config file: {}", e);
-                    match load_default_config() {
-                        Ok(config) => config,
-                        Err(e) => {
-                            eprintln!("Failed to load default config: {}", e);
-                            return;
-                        }
-                    }
-                }
+            let Some((config, _)) = loaded else {
+                return;
             };
 
             println!("Config loaded from {}", config_path.display());
 
+            // Apply keybinding overrides on top of the defaults
+            _ = cx.update(|cx| agentx::key_binding::apply_config(&config.keybindings, cx));
+
             // Inject nodejs_path from AppSettings into agent configs
-            let nodejs_path = cx.update(|cx| {
-                agentx::AppSettings::global(cx).nodejs_path.clone()
-            });
+            let nodejs_path = cx
+                .update(|cx| agentx::AppSettings::global(cx).nodejs_path.clone())
+                .ok()
+                .filter(|nodejs_path| !nodejs_path.is_empty())
+                .map(|nodejs_path| nodejs_path.to_string());
 
             let mut agent_servers = config.agent_servers.clone();
-            if let Ok(nodejs_path) = nodejs_path
-                && !nodejs_path.is_empty()
-            {
+            if let Some(nodejs_path) = &nodejs_path {
                 log::info!("Using custom Node.js path from settings: {}", nodejs_path);
                 // Inject nodejs_path into all agent configs
                 for (_name, agent_config) in agent_servers.iter_mut() {
-                    agent_config.nodejs_path = Some(nodejs_path.to_string());
+                    agent_config.nodejs_path = Some(nodejs_path.clone());
                 }
             }
-            let agent_server_count = agent_servers.len();
+            let agent_server_count = agent_servers.values().filter(|cfg| cfg.enabled).count();
 
             // Initialize agent manager (this happens in background after GUI is shown)
             let permission_store = Arc::new(PermissionStore::default());
@@ -115,6 +122,10 @@ fn main() {
                         // Then set agent manager with config
                         agentx::AppState::global_mut(cx).set_agent_manager(manager, config);
                         agentx::AppState::global_mut(cx).set_permission_store(permission_store);
+                        // Agents started by config hot-reload use the same Node.js path
+                        if let Some(service) = agentx::AppState::global(cx).agent_config_service() {
+                            service.set_nodejs_path(nodejs_path.clone());
+                        }
 
                         // Get message service for persistence initialization
                         agentx::AppState::global(cx).message_service().cloned()
@@ -123,6 +134,7 @@ fn main() {
                     // Initialize persistence subscription in async context
                     if let Ok(Some(message_service)) = init_result {
                         message_service.init_persistence();
+                        message_service.init_message_queue();
                         println!("Agent initialization started - agents will appear as they are ready");
                     } else {
                         eprintln!("MessageService not initialized");
@@ -154,7 +166,7 @@ fn parse_config_path() -> std::path::PathBuf {
     // No custom config specified, use user data directory
     match config_manager::initialize_user_config() {
         Ok(path) => {
-            println!("Using config from user data directory: {}", path.display());
+            eprintln!("Using config from user data directory: {}", path.display());
             path
         }
         Err(e) => {
@@ -173,9 +185,96 @@ fn parse_config_path() -> std::path::PathBuf {
     }
 }
 
-fn load_default_config() -> anyhow::Result<Config> {
-    let raw = agentx::get_default_config()
-        .ok_or_else(|| anyhow::anyhow!("embedded default config missing"))?;
-    let config = serde_json::from_str(&raw).context("invalid embedded default config")?;
-    Ok(config)
+/// Parse the `--event-log <path>` flag, which enables the JSON-lines event log
+fn parse_event_log_path() -> Option<std::path::PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        if flag == "--event-log" {
+            return args.next().map(std::path::PathBuf::from);
+        }
+    }
+    None
+}
+
+/// Run a single agent turn without opening a window and return the exit code
+fn run_headless(
+    config_path: &std::path::Path,
+    event_log_path: Option<&std::path::Path>,
+    args: HeadlessArgs,
+) -> i32 {
+    let Some((config, _)) = load_config(config_path) else {
+        return 1;
+    };
+    let Some(agent_config) = config.agent_servers.get(&args.agent).cloned() else {
+        eprintln!(
+            "Unknown agent '{}' in {}",
+            args.agent,
+            config_path.display()
+        );
+        return 1;
+    };
+    if !agent_config.enabled {
+        eprintln!("Agent '{}' is disabled", args.agent);
+        return 1;
+    }
+
+    let event_hub = EventHub::new();
+    let permission_store = Arc::new(PermissionStore::default());
+    // Dropped once the turn is over, which flushes the log
+    let _event_log = match event_log_path.map(|path| EventLog::start(path, &event_hub)) {
+        Some(Ok(event_log)) => Some(event_log),
+        Some(Err(e)) => {
+            eprintln!("{:#}", e);
+            return 1;
+        }
+        None => None,
+    };
+
+    let result = smol::block_on(async {
+        let manager = AgentManager::initialize(
+            HashMap::from([(args.agent.clone(), agent_config)]),
+            permission_store.clone(),
+            event_hub.clone(),
+            config.proxy.clone(),
+        )
+        .await?;
+        let mut agent_service = AgentService::new(manager);
+        agent_service.set_event_hub(event_hub.clone());
+
+        headless::run_turn(
+            &agent_service,
+            &event_hub,
+            permission_store,
+            &args.agent,
+            &args.prompt,
+            std::io::stdout(),
+        )
+        .await
+    });
+
+    if let Err(e) = &result {
+        eprintln!("Headless run failed: {:#}", e);
+    }
+    headless::exit_code(&result)
+}
+
+/// Load the config file, falling back to the embedded default when it is
+/// missing or broken. The second value explains the fallback, if any.
+fn load_config(config_path: &std::path::Path) -> Option<(Config, Option<String>)> {
+    match config_manager::load_config_or_default(config_path) {
+        Ok((config, fallback)) => {
+            let notice = fallback.map(|fallback| {
+                // `{:#}` keeps the per-field problems found by validation
+                if let config_manager::ConfigFallback::Invalid(e) = &fallback {
+                    eprintln!("Failed to load config: {:#}", e);
+                }
+                fallback.message(config_path)
+            });
+            Some((config, notice))
+        }
+        Err(e) => {
+            eprintln!("Failed to load default config: {:#}", e);
+            None
+        }
+    }
 }
@@ -1,20 +1,29 @@
 use std::{ops::Range, str::FromStr, time::Duration};
 
 use anyhow::anyhow;
-use gpui::{App, AppContext, Context, Entity, Result, SharedString, Task, Window};
+use gpui::{
+    App, AppContext, Context, Entity, ParentElement, Result, SharedString, Styled, Task,
+    WeakEntity, Window,
+};
 use gpui_component::{
     WindowExt,
+    dialog::DialogButtonProps,
     input::{
         CodeActionProvider, CompletionProvider, DefinitionProvider, DocumentColorProvider,
         HoverProvider, InputState, Rope, RopeExt,
     },
     notification::Notification,
+    v_flex,
 };
 use lsp_types::{
     CodeAction, CodeActionKind, CompletionContext, CompletionResponse, TextEdit, WorkspaceEdit,
 };
 
-use crate::AppState;
+use crate::{
+    AppState,
+    components::edit_preview,
+    core::services::{Edit, apply_edits},
+};
 
 use super::lsp_store::CodeEditorPanelLspStore;
 use super::types::{RUST_DOC_URLS, completion_item};
@@ -495,6 +504,17 @@ impl CodeActionProvider for TextConvertor {
                 ..Default::default()
             });
 
+            actions.push(CodeAction {
+                title: "Suggest Edits (AI)".into(),
+                kind: Some(CodeActionKind::REFACTOR),
+                data: Some(serde_json::json!({
+                    "ai_action": "suggest_edits",
+                    "code": old_text,
+                    "range": range,
+                })),
+                ..Default::default()
+            });
+
             actions.push(CodeAction {
                 title: "Suggest Improvements (AI)".into(),
                 kind: Some(CodeActionKind::REFACTOR),
@@ -806,11 +826,106 @@ impl TextConvertor {
                 })
             }
 
+            "suggest_edits" => {
+                let range: lsp_types::Range = match data.get("range") {
+                    Some(r) => serde_json::from_value(r.clone()).unwrap(),
+                    None => {
+                        return Task::ready(Err(anyhow!(
+                            "Missing range data for AI suggest edits action"
+                        )));
+                    }
+                };
+
+                // Show loading notification
+                struct AiEditsLoading;
+                let loading_note =
+                    Notification::info("Suggesting edits with AI...").id::<AiEditsLoading>();
+                window.push_notification(loading_note, cx);
+
+                window.spawn(cx, async move |cx| {
+                    let edits_result = ai_service
+                        .suggest_edits(&code, SUGGEST_EDITS_INSTRUCTION)
+                        .await;
+
+                    state_weak.clone().update_in(cx, |_, window, cx| {
+                        let result = edits_result.and_then(|edits| {
+                            open_edit_preview(state_weak, code, edits, range, window, cx)
+                        });
+                        if let Err(e) = &result {
+                            log::error!("Failed to suggest edits: {}", e);
+
+                            struct AiEditsError;
+                            let error_note =
+                                Notification::error(format!("Failed to suggest edits: {}", e))
+                                    .id::<AiEditsError>();
+                            window.push_notification(error_note, cx);
+                        }
+                        result
+                    })?
+                })
+            }
+
             _ => Task::ready(Err(anyhow!("Unknown AI action: {}", ai_action))),
         }
     }
 }
 
+const SUGGEST_EDITS_INSTRUCTION: &str = "Improve the readability and correctness of this code.";
+
+/// Preview the AI `edits` to the selected `code` and, once accepted, replace
+/// the selection at `range` with the edited code
+fn open_edit_preview(
+    state: WeakEntity<InputState>,
+    code: String,
+    edits: Vec<Edit>,
+    range: lsp_types::Range,
+    window: &mut Window,
+    cx: &mut App,
+) -> Result<()> {
+    if edits.is_empty() {
+        struct AiEditsEmpty;
+        let note = Notification::info("No edits suggested for this code.").id::<AiEditsEmpty>();
+        window.push_notification(note, cx);
+        return Ok(());
+    }
+
+    let new_text = apply_edits(&code, &edits)?;
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title("Suggested Edits (AI)")
+            .confirm()
+            .button_props(
+                DialogButtonProps::default()
+                    .ok_text("Apply")
+                    .cancel_text("Discard"),
+            )
+            .on_ok({
+                let state = state.clone();
+                let new_text = new_text.clone();
+                move |_, window, cx| {
+                    _ = state.update(cx, |state, cx| {
+                        state.apply_lsp_edits(
+                            &vec![TextEdit {
+                                range,
+                                new_text: new_text.clone(),
+                                ..Default::default()
+                            }],
+                            window,
+                            cx,
+                        );
+                    });
+                    true
+                }
+            })
+            .child(
+                v_flex()
+                    .w_full()
+                    .children(edit_preview("selection", &code, &edits).ok()),
+            )
+    });
+    Ok(())
+}
+
 /// Smart comment formatting based on code type and language
 fn format_comment_for_code(
     code: &str,
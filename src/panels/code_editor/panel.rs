@@ -1,15 +1,24 @@
-use std::{path::PathBuf, rc::Rc, str::FromStr};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    rc::Rc,
+    str::FromStr,
+    time::SystemTime,
+};
 
 use autocorrect::ignorer::Ignorer;
 use gpui::{prelude::FluentBuilder, *};
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable, StyledExt, WindowExt,
     button::{Button, ButtonVariants as _},
+    dialog::DialogButtonProps,
     h_flex,
     highlighter::{Diagnostic, DiagnosticSeverity, Language},
     input::{Input, InputEvent, InputState, Position, RopeExt, TabSize},
     list::ListItem,
+    notification::Notification,
     resizable::{h_resizable, resizable_panel},
+    tab::{Tab, TabBar},
     tree::{TreeState, tree},
     v_flex,
 };
@@ -18,8 +27,8 @@ use rust_i18n::t;
 
 use super::lsp_providers::TextConvertor;
 use super::lsp_store::CodeEditorPanelLspStore;
-use super::types::build_file_items;
-use crate::AppState;
+use super::types::{DiskChange, EditorTab, EditorTabs, build_file_items};
+use crate::{AppState, SaveFile, core::services::FileWatcher};
 
 pub struct CodeEditorPanel {
     editor: Entity<InputState>,
@@ -32,13 +41,17 @@ pub struct CodeEditorPanel {
     show_file_tree: bool,
     files_loaded: bool,
     lsp_store: CodeEditorPanelLspStore,
-    current_file_path: Option<PathBuf>,
-    has_opened_file: bool,
+    tabs: EditorTabs,
+    /// Reports changes made to open files outside the editor
+    file_watcher: Option<FileWatcher>,
+    /// Files with a reload-or-keep prompt on screen
+    pending_conflicts: HashSet<PathBuf>,
     workspace_id: Option<String>,
     workspace_name: Option<String>,
     working_directory: PathBuf,
     _subscriptions: Vec<Subscription>,
     _lint_task: Task<()>,
+    _watch_task: Task<()>,
 }
 
 impl crate::panels::dock_panel::DockPanel for CodeEditorPanel {
@@ -73,7 +86,7 @@ impl crate::panels::dock_panel::DockPanel for CodeEditorPanel {
 
 impl CodeEditorPanel {
     pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
-        cx.new(|cx| Self::new(window, None, cx))
+        Self::view_with_working_dir(window, None, cx)
     }
 
     pub fn view_with_working_dir(
@@ -81,7 +94,21 @@ impl CodeEditorPanel {
         working_dir: Option<PathBuf>,
         cx: &mut App,
     ) -> Entity<Self> {
-        cx.new(|cx| Self::new(window, working_dir, cx))
+        let follows_cwd = working_dir.is_none();
+        let entity = cx.new(|cx| Self::new(window, working_dir, cx));
+
+        // Without an explicit directory the file tree tracks the application one
+        if follows_cwd {
+            crate::core::event_bus::subscribe_entity_to_cwd_changes(
+                &entity,
+                AppState::global(cx).event_hub().clone(),
+                "CodeEditorPanel",
+                |panel, path, cx| panel.set_working_directory(path, cx),
+                cx,
+            );
+        }
+
+        entity
     }
 
     pub fn new(window: &mut Window, working_dir: Option<PathBuf>, cx: &mut Context<Self>) -> Self {
@@ -115,10 +142,30 @@ impl CodeEditorPanel {
         let working_dir =
             working_dir.unwrap_or_else(|| AppState::global(cx).current_working_dir().clone());
 
-        let _subscriptions = vec![cx.subscribe(&editor, |this, _, _: &InputEvent, cx| {
+        let _subscriptions = vec![cx.subscribe(&editor, |this, _, event: &InputEvent, cx| {
+            if matches!(event, InputEvent::Change) {
+                this.sync_active_tab(cx);
+            }
             this.lint_document(cx);
         })];
 
+        let (changed_tx, mut changed_rx) = tokio::sync::mpsc::unbounded_channel::<PathBuf>();
+        let file_watcher = FileWatcher::new(move |path| {
+            let _ = changed_tx.send(path);
+        })
+        .inspect_err(|e| log::warn!("Open files won't be watched for changes: {:#}", e))
+        .ok();
+        let _watch_task = cx.spawn_in(window, async move |this, window| {
+            while let Some(path) = changed_rx.recv().await {
+                let updated = this.update_in(window, |this, window, cx| {
+                    this.on_file_changed(path, window, cx);
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        });
+
         Self {
             editor,
             tree_state,
@@ -130,13 +177,15 @@ impl CodeEditorPanel {
             show_file_tree: true,
             files_loaded: false,
             lsp_store,
-            current_file_path: None,
-            has_opened_file: false,
+            tabs: EditorTabs::default(),
+            file_watcher,
+            pending_conflicts: HashSet::new(),
             workspace_id: None,
             workspace_name: None,
             working_directory: working_dir,
             _subscriptions,
             _lint_task: Task::ready(()),
+            _watch_task,
         }
     }
 
@@ -161,7 +210,7 @@ impl CodeEditorPanel {
     }
 
     fn ensure_file_tree_loaded(&mut self, cx: &mut App) {
-        if self.files_loaded || !crate::themes::startup_completed() {
+        if self.files_loaded || !crate::AppSettings::global(cx).setup_completed {
             return;
         }
 
@@ -184,6 +233,20 @@ impl CodeEditorPanel {
         self.working_directory.clone()
     }
 
+    /// Point the file tree at `path`, reloading it if it was already shown
+    pub fn set_working_directory(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if self.working_directory == path {
+            return;
+        }
+
+        self.working_directory = path;
+        if self.files_loaded {
+            self.files_loaded = false;
+            self.ensure_file_tree_loaded(cx);
+        }
+        cx.notify();
+    }
+
     fn go_to_line(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         let editor = self.editor.clone();
         let input_state = self.go_to_line_state.clone();
@@ -294,39 +357,309 @@ impl CodeEditorPanel {
         });
     }
 
-    fn open_file(
-        view: Entity<Self>,
+    /// Open `path` in a new tab, or focus its tab if it is already open
+    pub fn open_file(
+        &mut self,
         path: PathBuf,
         window: &mut Window,
-        cx: &mut App,
+        cx: &mut Context<Self>,
     ) -> Result<()> {
-        let language = path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or_default();
-        let language = Language::from_str(&language);
+        if let Some(index) = self.tabs.position(&path) {
+            self.activate_tab(index, window, cx);
+            return Ok(());
+        }
+
         let content = std::fs::read_to_string(&path)?;
-        let path_clone = path.clone();
-
-        window
-            .spawn(cx, async move |window| {
-                _ = view.update_in(window, |this, window, cx| {
-                    _ = this.editor.update(cx, |this, cx| {
-                        this.set_highlighter(language.name(), cx);
-                        this.set_value(content, window, cx);
-                    });
+        if let Some(watcher) = self.file_watcher.as_mut() {
+            if let Err(e) = watcher.watch(&path) {
+                log::warn!("Failed to watch {:?}: {:#}", path, e);
+            }
+        }
 
-                    this.language = language;
-                    this.current_file_path = Some(path_clone);
-                    this.has_opened_file = true;
-                    cx.notify();
-                });
-            })
-            .detach();
+        let mut tab = EditorTab::new(path, content);
+        tab.disk_mtime = file_mtime(&tab.path);
+        self.tabs.open(tab);
+        self.show_active_tab(window, cx);
+        Ok(())
+    }
 
+    /// Write the focused tab to disk. If the file was changed on disk since it
+    /// was loaded, asks whether to reload instead of overwriting the change.
+    pub fn save(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Result<()> {
+        let Some(tab) = self.tabs.active() else {
+            return Ok(());
+        };
+        let path = tab.path.clone();
+        let text = tab.text.clone();
+
+        let disk_text = std::fs::read_to_string(&path).ok();
+        if let Some(disk_text) =
+            disk_text.filter(|disk_text| tab.disk_change(disk_text) == DiskChange::Conflict)
+        {
+            let mtime = file_mtime(&path);
+            self.prompt_disk_conflict(path, disk_text, mtime, window, cx);
+            return Ok(());
+        }
+
+        std::fs::write(&path, text)?;
+        if let Some(tab) = self.tabs.active_mut() {
+            tab.mark_saved(file_mtime(&path));
+        }
+        cx.notify();
         Ok(())
     }
 
+    fn on_action_save_file(&mut self, _: &SaveFile, window: &mut Window, cx: &mut Context<Self>) {
+        if let Err(e) = self.save(window, cx) {
+            log::error!("Failed to save file: {:#}", e);
+            struct SaveFileError;
+            let note = Notification::error(format!("{:#}", e)).id::<SaveFileError>();
+            window.push_notification(note, cx);
+        }
+    }
+
+    /// React to an open file changing on disk: reload clean buffers, ask
+    /// about dirty ones
+    fn on_file_changed(&mut self, path: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(index) = self.tab_for_disk_path(&path) else {
+            return;
+        };
+        let tab = &self.tabs.tabs()[index];
+        let path = tab.path.clone();
+        if self.pending_conflicts.contains(&path) {
+            return;
+        }
+
+        let mtime = file_mtime(&path);
+        if mtime.is_some() && mtime == tab.disk_mtime {
+            return;
+        }
+        // Deleted or unreadable: keep the buffer as it is
+        let Ok(disk_text) = std::fs::read_to_string(&path) else {
+            return;
+        };
+
+        match tab.disk_change(&disk_text) {
+            DiskChange::Unchanged => {
+                if let Some(tab) = self.tabs.get_mut(index) {
+                    tab.disk_mtime = mtime;
+                }
+            }
+            DiskChange::Reload => {
+                self.resolve_disk_change(&path, disk_text, mtime, true, window, cx)
+            }
+            DiskChange::Conflict => self.prompt_disk_conflict(path, disk_text, mtime, window, cx),
+        }
+    }
+
+    /// Index of the tab for a path reported by the file watcher, which may
+    /// be canonicalized
+    fn tab_for_disk_path(&self, path: &Path) -> Option<usize> {
+        self.tabs.position(path).or_else(|| {
+            self.tabs
+                .tabs()
+                .iter()
+                .position(|tab| tab.path.canonicalize().is_ok_and(|p| p == path))
+        })
+    }
+
+    fn prompt_disk_conflict(
+        &mut self,
+        path: PathBuf,
+        disk_text: String,
+        mtime: Option<SystemTime>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.pending_conflicts.insert(path.clone()) {
+            return;
+        }
+
+        let view = cx.entity().downgrade();
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let resolve = |reload: bool| {
+                let view = view.clone();
+                let path = path.clone();
+                let disk_text = disk_text.clone();
+                move |_: &ClickEvent, window: &mut Window, cx: &mut App| {
+                    _ = view.update(cx, |this, cx| {
+                        this.resolve_disk_change(
+                            &path,
+                            disk_text.clone(),
+                            mtime,
+                            reload,
+                            window,
+                            cx,
+                        );
+                    });
+                    true
+                }
+            };
+
+            dialog
+                .title(t!("code_editor.conflict.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("code_editor.conflict.reload").to_string())
+                        .cancel_text(t!("code_editor.conflict.keep").to_string()),
+                )
+                .on_ok(resolve(true))
+                .on_cancel(resolve(false))
+                .child(t!("code_editor.conflict.message", file = file).to_string())
+        });
+    }
+
+    /// Settle new disk contents for the tab showing `path`, either replacing
+    /// the buffer or keeping local edits on top of them
+    fn resolve_disk_change(
+        &mut self,
+        path: &Path,
+        disk_text: String,
+        mtime: Option<SystemTime>,
+        reload: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.pending_conflicts.remove(path);
+        let Some(index) = self.tabs.position(path) else {
+            return;
+        };
+        let Some(tab) = self.tabs.get_mut(index) else {
+            return;
+        };
+
+        if reload {
+            tab.reload(disk_text, mtime);
+            if self.tabs.active_index() == Some(index) {
+                self.show_active_tab(window, cx);
+                return;
+            }
+        } else {
+            tab.keep_local(disk_text, mtime);
+        }
+        cx.notify();
+    }
+
+    /// Focus the tab at `index`
+    pub fn activate_tab(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if self.tabs.active_index() == Some(index) {
+            return;
+        }
+        self.tabs.activate(index);
+        self.show_active_tab(window, cx);
+    }
+
+    /// Close the tab at `index`, discarding unsaved edits
+    pub fn close_tab(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let was_active = self.tabs.active_index() == Some(index);
+        let Some(tab) = self.tabs.close(index) else {
+            return;
+        };
+        if let Some(watcher) = self.file_watcher.as_mut() {
+            watcher.unwatch(&tab.path);
+        }
+        self.pending_conflicts.remove(&tab.path);
+        if was_active {
+            self.show_active_tab(window, cx);
+        } else {
+            cx.notify();
+        }
+    }
+
+    /// Paths of the open files in tab order
+    pub fn open_files(&self) -> Vec<PathBuf> {
+        self.tabs
+            .tabs()
+            .iter()
+            .map(|tab| tab.path.clone())
+            .collect()
+    }
+
+    /// Path of the file in the focused tab
+    pub fn active_file(&self) -> Option<PathBuf> {
+        self.tabs.active().map(|tab| tab.path.clone())
+    }
+
+    /// Reopen the tabs saved with the dock layout; files that no longer
+    /// exist are skipped
+    pub fn restore_open_files(
+        &mut self,
+        files: Vec<PathBuf>,
+        active_file: Option<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for path in files {
+            if let Err(err) = self.open_file(path.clone(), window, cx) {
+                log::warn!("Failed to reopen {:?}: {}", path, err);
+            }
+        }
+        if let Some(index) = active_file.and_then(|path| self.tabs.position(&path)) {
+            self.activate_tab(index, window, cx);
+        }
+    }
+
+    /// Load the focused tab into the editor, or clear it when no tab is left
+    fn show_active_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tab) = self.tabs.active() else {
+            self.editor.update(cx, |state, cx| {
+                state.set_value("", window, cx);
+            });
+            cx.notify();
+            return;
+        };
+
+        let language = language_for_path(&tab.path);
+        let text = tab.text.clone();
+        self.editor.update(cx, |state, cx| {
+            state.set_highlighter(language.name(), cx);
+            state.set_value(text, window, cx);
+        });
+        self.language = language;
+        cx.notify();
+    }
+
+    /// Keep the focused tab's buffer and dirty flag in step with the editor
+    fn sync_active_tab(&mut self, cx: &mut Context<Self>) {
+        let text = self.editor.read(cx).value().to_string();
+        if let Some(tab) = self.tabs.active_mut() {
+            tab.dirty = text != tab.saved_text;
+            tab.text = text;
+            cx.notify();
+        }
+    }
+
+    fn render_tab_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        TabBar::new("code-editor-tabs")
+            .w_full()
+            .selected_index(self.tabs.active_index().unwrap_or_default())
+            .on_click(cx.listener(|this, index: &usize, window, cx| {
+                this.activate_tab(*index, window, cx);
+            }))
+            .children(self.tabs.tabs().iter().enumerate().map(|(index, tab)| {
+                let label = if tab.dirty {
+                    format!("{} ●", tab.title())
+                } else {
+                    tab.title()
+                };
+                Tab::new().label(label).suffix(
+                    Button::new(("close-tab", index))
+                        .icon(IconName::Close)
+                        .ghost()
+                        .xsmall()
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            cx.stop_propagation();
+                            this.close_tab(index, window, cx);
+                        })),
+                )
+            }))
+    }
+
     fn render_file_tree(&self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let view = cx.entity();
         tree(
@@ -351,20 +684,15 @@ impl CodeEditorPanel {
                         .child(h_flex().gap_2().child(icon).child(item.label.clone()))
                         .on_click(cx.listener({
                             let item = item.clone();
-                            move |_, _, _window, cx| {
+                            move |this, _, window, cx| {
                                 if item.is_folder() {
                                     return;
                                 }
 
-                                Self::open_file(
-                                    cx.entity(),
-                                    PathBuf::from(item.id.as_str()),
-                                    _window,
-                                    cx,
-                                )
-                                .ok();
-
-                                cx.notify();
+                                let path = PathBuf::from(item.id.as_str());
+                                if let Err(err) = this.open_file(path, window, cx) {
+                                    log::warn!("Failed to open {}: {}", item.id, err);
+                                }
                             }
                         }))
                 })
@@ -396,6 +724,19 @@ impl CodeEditorPanel {
             }))
     }
 
+    fn render_save_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let dirty = self.tabs.active().is_some_and(|tab| tab.dirty);
+        Button::new("save-file")
+            .icon(IconName::Check)
+            .ghost()
+            .xsmall()
+            .disabled(!dirty)
+            .tooltip(t!("code_editor.tooltip.save").to_string())
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.on_action_save_file(&SaveFile, window, cx);
+            }))
+    }
+
     fn render_line_number_button(
         &self,
         _: &mut Window,
@@ -563,9 +904,9 @@ impl CodeEditorPanel {
 
         // 获取当前文件路径
         let file_path = self
-            .current_file_path
-            .as_ref()
-            .and_then(|p| p.to_str())
+            .tabs
+            .active()
+            .and_then(|tab| tab.path.to_str())
             .unwrap_or("untitled")
             .to_string();
 
@@ -632,6 +973,20 @@ impl CodeEditorPanel {
     }
 }
 
+fn file_mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+fn language_for_path(path: &Path) -> Language {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+    Language::from_str(extension)
+}
+
 impl Render for CodeEditorPanel {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         use gpui_component::input::RopeExt;
@@ -676,17 +1031,22 @@ impl Render for CodeEditorPanel {
             }
         });
 
-        let editor_input = Input::new(&self.editor)
-            .bordered(false)
-            .p_0()
-            .h_full()
-            .font_family(cx.theme().mono_font_family.clone())
-            .text_size(cx.theme().mono_font_size)
-            .focus_bordered(false)
+        let editor_input = v_flex()
+            .size_full()
+            .child(self.render_tab_bar(cx))
+            .child(
+                Input::new(&self.editor)
+                    .bordered(false)
+                    .p_0()
+                    .flex_1()
+                    .font_family(cx.theme().mono_font_family.clone())
+                    .text_size(cx.theme().mono_font_size)
+                    .focus_bordered(false),
+            )
             .into_any_element();
 
         // 根据是否打开文件决定显示内容
-        let main_content = if self.has_opened_file {
+        let main_content = if !self.tabs.is_empty() {
             // 已打开文件，显示编辑器
             if self.show_file_tree {
                 h_resizable("editor-container")
@@ -719,38 +1079,48 @@ impl Render for CodeEditorPanel {
             }
         };
 
-        v_flex().id("app").size_full().child(
-            v_flex()
-                .id("source")
-                .w_full()
-                .flex_1()
-                .child(main_content)
-                .child(
-                    h_flex()
-                        .justify_between()
-                        .text_sm()
-                        .bg(cx.theme().background)
-                        // .py_1p5()
-                        .h(px(30.))
-                        .px_4()
-                        .border_t_1()
-                        .border_color(cx.theme().border)
-                        .text_color(cx.theme().muted_foreground)
-                        .child(
-                            h_flex()
-                                .gap_3()
-                                .child(self.render_toggle_file_tree_button(window, cx))
-                                .child(self.render_line_number_button(window, cx))
-                                .child(self.render_soft_wrap_button(window, cx))
-                                .child(self.render_indent_guides_button(window, cx)),
-                        )
-                        .child(
-                            h_flex()
-                                .gap_3()
-                                .child(self.render_selection_range_info(window, cx, selection_info))
-                                .child(self.render_go_to_line_button(window, cx)),
-                        ),
-                ),
-        )
+        v_flex()
+            .id("app")
+            .key_context("CodeEditor")
+            .size_full()
+            .on_action(cx.listener(Self::on_action_save_file))
+            .child(
+                v_flex()
+                    .id("source")
+                    .w_full()
+                    .flex_1()
+                    .child(main_content)
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .text_sm()
+                            .bg(cx.theme().background)
+                            // .py_1p5()
+                            .h(px(30.))
+                            .px_4()
+                            .border_t_1()
+                            .border_color(cx.theme().border)
+                            .text_color(cx.theme().muted_foreground)
+                            .child(
+                                h_flex()
+                                    .gap_3()
+                                    .child(self.render_toggle_file_tree_button(window, cx))
+                                    .child(self.render_save_button(cx))
+                                    .child(self.render_line_number_button(window, cx))
+                                    .child(self.render_soft_wrap_button(window, cx))
+                                    .child(self.render_indent_guides_button(window, cx)),
+                            )
+                            .child(
+                                h_flex()
+                                    .gap_3()
+                                    .child(self.render_selection_range_info(
+                                        window,
+                                        cx,
+                                        selection_info,
+                                    ))
+                                    .child(self.render_go_to_line_button(window, cx)),
+                            ),
+                    ),
+            )
     }
 }
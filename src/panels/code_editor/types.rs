@@ -1,4 +1,5 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use autocorrect::ignorer::Ignorer;
 use gpui_component::tree::TreeItem;
@@ -79,3 +80,259 @@ pub fn build_file_items(ignorer: &Ignorer, root: &PathBuf, path: &PathBuf) -> Ve
     });
     items
 }
+
+// ============================================================================
+// Open Files
+// ============================================================================
+
+/// A file open in its own editor tab
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EditorTab {
+    pub path: PathBuf,
+    /// Contents as last read from disk
+    pub saved_text: String,
+    /// Buffer contents, kept up to date while the tab is in the background
+    pub text: String,
+    /// Whether the buffer differs from `saved_text`
+    pub dirty: bool,
+    /// Modification time of the file when `saved_text` was read or written
+    pub disk_mtime: Option<SystemTime>,
+}
+
+/// What to do about the file behind a tab having new contents on disk
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiskChange {
+    /// Same contents as last seen, e.g. our own save
+    Unchanged,
+    /// The buffer has no edits to lose; take the new contents
+    Reload,
+    /// The buffer has edits of its own; the user must pick a side
+    Conflict,
+}
+
+impl EditorTab {
+    pub fn new(path: PathBuf, text: String) -> Self {
+        Self {
+            path,
+            saved_text: text.clone(),
+            text,
+            dirty: false,
+            disk_mtime: None,
+        }
+    }
+
+    /// Classify `disk_text`, freshly read from the file, against this tab
+    pub fn disk_change(&self, disk_text: &str) -> DiskChange {
+        if disk_text == self.saved_text {
+            DiskChange::Unchanged
+        } else if !self.dirty || disk_text == self.text {
+            DiskChange::Reload
+        } else {
+            DiskChange::Conflict
+        }
+    }
+
+    /// Replace the buffer with the contents on disk
+    pub fn reload(&mut self, disk_text: String, mtime: Option<SystemTime>) {
+        self.text = disk_text.clone();
+        self.saved_text = disk_text;
+        self.dirty = false;
+        self.disk_mtime = mtime;
+    }
+
+    /// Keep the buffer but remember the contents on disk, so the same change
+    /// isn't reported twice and the buffer stays dirty until saved
+    pub fn keep_local(&mut self, disk_text: String, mtime: Option<SystemTime>) {
+        self.dirty = disk_text != self.text;
+        self.saved_text = disk_text;
+        self.disk_mtime = mtime;
+    }
+
+    /// Record that the buffer was written to disk
+    pub fn mark_saved(&mut self, mtime: Option<SystemTime>) {
+        self.saved_text = self.text.clone();
+        self.dirty = false;
+        self.disk_mtime = mtime;
+    }
+
+    /// Name shown on the tab
+    pub fn title(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.to_string_lossy().to_string())
+    }
+}
+
+/// Open files of the code editor in tab order, and which one is shown
+#[derive(Clone, Debug, Default)]
+pub struct EditorTabs {
+    tabs: Vec<EditorTab>,
+    active: Option<usize>,
+}
+
+impl EditorTabs {
+    pub fn tabs(&self) -> &[EditorTab] {
+        &self.tabs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    pub fn active_index(&self) -> Option<usize> {
+        self.active
+    }
+
+    pub fn active(&self) -> Option<&EditorTab> {
+        self.tabs.get(self.active?)
+    }
+
+    pub fn active_mut(&mut self) -> Option<&mut EditorTab> {
+        self.tabs.get_mut(self.active?)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut EditorTab> {
+        self.tabs.get_mut(index)
+    }
+
+    pub fn position(&self, path: &Path) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.path == path)
+    }
+
+    /// Focus the tab at `index`; out-of-range indices are ignored
+    pub fn activate(&mut self, index: usize) {
+        if index < self.tabs.len() {
+            self.active = Some(index);
+        }
+    }
+
+    /// Focus the tab showing `tab.path`, adding `tab` at the end if the file
+    /// isn't open yet. Returns the index of the focused tab.
+    pub fn open(&mut self, tab: EditorTab) -> usize {
+        let index = self.position(&tab.path).unwrap_or_else(|| {
+            self.tabs.push(tab);
+            self.tabs.len() - 1
+        });
+        self.active = Some(index);
+        index
+    }
+
+    /// Close the tab at `index`. Closing the focused tab focuses its right
+    /// neighbor, or the left one when it was the last tab.
+    pub fn close(&mut self, index: usize) -> Option<EditorTab> {
+        if index >= self.tabs.len() {
+            return None;
+        }
+        let tab = self.tabs.remove(index);
+        self.active = match self.active {
+            _ if self.tabs.is_empty() => None,
+            Some(active) if active == index => Some(index.min(self.tabs.len() - 1)),
+            Some(active) if active > index => Some(active - 1),
+            active => active,
+        };
+        Some(tab)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab(path: &str) -> EditorTab {
+        EditorTab::new(PathBuf::from(path), String::new())
+    }
+
+    fn paths(tabs: &EditorTabs) -> Vec<&str> {
+        tabs.tabs()
+            .iter()
+            .map(|tab| tab.path.to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn opening_an_open_file_focuses_its_tab() {
+        let mut tabs = EditorTabs::default();
+        assert_eq!(tabs.open(tab("a.rs")), 0);
+        assert_eq!(tabs.open(tab("b.rs")), 1);
+        assert_eq!(tabs.active_index(), Some(1));
+
+        tabs.active_mut().unwrap().dirty = true;
+        assert_eq!(tabs.open(tab("a.rs")), 0);
+        assert_eq!(tabs.active_index(), Some(0));
+        assert_eq!(paths(&tabs), ["a.rs", "b.rs"]);
+        // Re-opening doesn't replace the existing buffer
+        assert!(tabs.tabs()[1].dirty);
+    }
+
+    #[test]
+    fn closing_the_active_tab_focuses_a_neighbor() {
+        let mut tabs = EditorTabs::default();
+        for path in ["a.rs", "b.rs", "c.rs"] {
+            tabs.open(tab(path));
+        }
+
+        // Middle tab: the right neighbor takes its place
+        tabs.activate(1);
+        assert_eq!(tabs.close(1).unwrap().path, PathBuf::from("b.rs"));
+        assert_eq!(tabs.active().unwrap().path, PathBuf::from("c.rs"));
+
+        // Last tab: falls back to the left neighbor
+        tabs.close(1);
+        assert_eq!(tabs.active().unwrap().path, PathBuf::from("a.rs"));
+
+        tabs.close(0);
+        assert!(tabs.is_empty());
+        assert_eq!(tabs.active_index(), None);
+        assert!(tabs.close(0).is_none());
+    }
+
+    #[test]
+    fn external_change_to_dirty_buffer_conflicts() {
+        let mut tab = EditorTab::new(PathBuf::from("a.rs"), "fn a() {}".to_string());
+        assert_eq!(tab.disk_change("fn a() {}"), DiskChange::Unchanged);
+        assert_eq!(tab.disk_change("fn b() {}"), DiskChange::Reload);
+
+        // Local edit, then an agent rewrites the file
+        tab.text = "fn a() { todo!() }".to_string();
+        tab.dirty = true;
+        assert_eq!(tab.disk_change("fn b() {}"), DiskChange::Conflict);
+        // The agent wrote exactly what the buffer holds: nothing to resolve
+        assert_eq!(tab.disk_change("fn a() { todo!() }"), DiskChange::Reload);
+
+        // Keeping local edits acknowledges the disk version
+        tab.keep_local("fn b() {}".to_string(), None);
+        assert!(tab.dirty);
+        assert_eq!(tab.text, "fn a() { todo!() }");
+        assert_eq!(tab.disk_change("fn b() {}"), DiskChange::Unchanged);
+
+        tab.reload("fn c() {}".to_string(), None);
+        assert!(!tab.dirty);
+        assert_eq!(tab.text, "fn c() {}");
+    }
+
+    #[test]
+    fn saving_makes_own_write_unchanged() {
+        let mut tab = EditorTab::new(PathBuf::from("a.rs"), "old".to_string());
+        tab.text = "new".to_string();
+        tab.dirty = true;
+        tab.mark_saved(None);
+        assert!(!tab.dirty);
+        assert_eq!(tab.disk_change("new"), DiskChange::Unchanged);
+    }
+
+    #[test]
+    fn closing_a_background_tab_keeps_focus() {
+        let mut tabs = EditorTabs::default();
+        for path in ["a.rs", "b.rs", "c.rs"] {
+            tabs.open(tab(path));
+        }
+
+        tabs.close(0);
+        assert_eq!(tabs.active().unwrap().path, PathBuf::from("c.rs"));
+        tabs.activate(0);
+        tabs.close(1);
+        assert_eq!(tabs.active().unwrap().path, PathBuf::from("b.rs"));
+        assert_eq!(paths(&tabs), ["b.rs"]);
+    }
+}
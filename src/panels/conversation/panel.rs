@@ -1,15 +1,25 @@
 use gpui::{
-    App, ClipboardEntry, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement,
-    Render, ScrollHandle, SharedString, Styled, Window, div, prelude::*, px,
+    App, ClipboardEntry, Context, Entity, ExternalPaths, FocusHandle, Focusable, IntoElement,
+    ParentElement, Render, ScrollHandle, SharedString, Styled, Window, div, prelude::*, px,
 };
 
 use gpui_component::{
-    ActiveTheme, Icon, IconName, Sizable, StyledExt, h_flex, input::InputState, skeleton::Skeleton,
-    spinner::Spinner, v_flex,
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::InputState,
+    select::{SelectEvent, SelectState},
+    skeleton::Skeleton,
+    spinner::Spinner,
+    v_flex,
 };
 
 // Use the published ACP schema crate
-use agent_client_protocol::{ImageContent, PlanEntryStatus, RequestPermissionResponse, ToolCall};
+use agent_client_protocol::{
+    ContentBlock, ImageContent, PermissionOptionKind, PlanEntry, PlanEntryStatus,
+    RequestPermissionResponse, ToolCall, ToolKind,
+};
+use agentx_acp_ui::user_display_text;
 use chrono::{DateTime, Utc};
 use rust_i18n::t;
 use smol::Timer;
@@ -17,9 +27,17 @@ use std::{sync::Arc, time::Duration};
 
 use crate::assets::get_agent_icon;
 use crate::{
-    AcpMessageStream, AcpMessageStreamOptions, AppState, ChatInputBox, DiffSummaryOptions,
-    PanelAction, PermissionRequestOptions, SendMessageToSession, ToolCallItemOptions,
-    app::actions::AddCodeSelection, core::services::SessionStatus, panels::dock_panel::DockPanel,
+    AcpMessageStream, AcpMessageStreamOptions, AgentTodoListOptions, AppSettings, AppState,
+    ChatInputBox, DiffSummaryOptions, PanelAction, PermissionRequestOptions, SendMessageToSession,
+    TodoListChangeHandler, ToolCallItemOptions,
+    app::actions::AddCodeSelection,
+    components::{
+        HistoryDirection, IndicatorKind, InputHistory, MessageStreamEvent, ModelSelectItem,
+        attachments_from_paths, indicator_kind,
+    },
+    core::agent::PermissionScope,
+    core::services::{SessionStatus, is_imported_session},
+    panels::dock_panel::DockPanel,
 };
 
 /// Session status information for display
@@ -46,8 +64,23 @@ pub struct ConversationPanel {
     pasted_images: Vec<(ImageContent, String)>,
     /// List of code selections from editor
     code_selections: Vec<AddCodeSelection>,
+    /// Files dropped on the input, as resource links
+    attached_resources: Vec<ContentBlock>,
+    /// Why some of the last dropped files were not attached
+    attachment_error: Option<String>,
+    /// Recently sent messages, recalled with Up/Down
+    input_history: InputHistory,
+    /// Turn of the sent message being edited; the next send replaces it
+    editing_turn: Option<usize>,
     /// Session status information for display
     session_status: Option<SessionStatusInfo>,
+    /// Model used by the session, chosen from the models the agent offers
+    model_select: Entity<SelectState<Vec<ModelSelectItem>>>,
+    has_models: bool,
+    /// Messages waiting to be sent once the agent finishes its turn
+    queued_messages: usize,
+    /// A cancel was requested and the turn has not ended yet
+    cancelling: bool,
     /// Workspace information
     workspace_id: Option<String>,
     workspace_name: Option<String>,
@@ -56,7 +89,6 @@ pub struct ConversationPanel {
 
 const MESSAGE_SERVICE_RETRY_DELAY_MS: u64 = 500;
 const MESSAGE_SERVICE_MAX_RETRIES: usize = 60;
-const AUTO_SCROLL_THRESHOLD_PX: f32 = 120.0;
 
 impl ConversationPanel {
     /// Create a new panel with mock data (for demo purposes)
@@ -105,12 +137,12 @@ impl ConversationPanel {
         self.working_directory.clone()
     }
 
-    fn new(window: &mut Window, cx: &mut App) -> Self {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
         log::info!("🔧 Initializing ConversationPanel (new)");
         Self::new_internal(None, window, cx)
     }
 
-    fn new_for_session(session_id: String, window: &mut Window, cx: &mut App) -> Self {
+    fn new_for_session(session_id: String, window: &mut Window, cx: &mut Context<Self>) -> Self {
         log::info!(
             "🔧 Initializing ConversationPanel for session: {}",
             session_id
@@ -118,13 +150,31 @@ impl ConversationPanel {
         Self::new_internal(Some(session_id), window, cx)
     }
 
-    fn new_internal(session_id: Option<String>, window: &mut Window, cx: &mut App) -> Self {
+    fn new_internal(
+        session_id: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
         let focus_handle = cx.focus_handle();
         let scroll_handle = ScrollHandle::new();
         let input_state = Self::create_input_state(window, cx);
-        let message_stream = Self::create_message_stream(cx);
+        let editable = !session_id.as_deref().is_some_and(is_imported_session);
+        let message_stream =
+            Self::create_message_stream(scroll_handle.clone(), session_id.clone(), editable, cx);
+        cx.subscribe_in(&message_stream, window, Self::on_message_stream_event)
+            .detach();
+        let model_select =
+            cx.new(|cx| SelectState::new(Vec::<ModelSelectItem>::new(), None, window, cx));
+        cx.subscribe_in(
+            &model_select,
+            window,
+            |this, _, _: &SelectEvent<Vec<ModelSelectItem>>, _window, cx| {
+                this.on_model_changed(cx);
+            },
+        )
+        .detach();
 
-        Self {
+        let mut panel = Self {
             focus_handle,
             message_stream,
             session_id,
@@ -132,11 +182,90 @@ impl ConversationPanel {
             input_state,
             pasted_images: Vec::new(),
             code_selections: Vec::new(),
+            attached_resources: Vec::new(),
+            attachment_error: None,
+            input_history: InputHistory::default(),
+            editing_turn: None,
             session_status: None,
+            model_select,
+            has_models: false,
+            queued_messages: 0,
+            cancelling: false,
             workspace_id: None,
             workspace_name: None,
             working_directory: None,
-        }
+        };
+        panel.refresh_model_select(window, cx);
+        panel
+    }
+
+    /// Fill the model selector from the session's model list, selecting its current model
+    fn refresh_model_select(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let session = self.session_id.as_deref().and_then(|session_id| {
+            AppState::global(cx)
+                .agent_service()?
+                .get_session_by_id(session_id)
+        });
+        let models = session
+            .as_ref()
+            .and_then(|info| info.new_session_response.as_ref())
+            .and_then(|response| response.models.as_ref());
+        let items = models
+            .map(ModelSelectItem::from_session_models)
+            .unwrap_or_default();
+        let current_model_id = models.map(|models| models.current_model_id.to_string());
+
+        self.has_models = !items.is_empty();
+        self.model_select.update(cx, |state, cx| {
+            state.set_items(items, window, cx);
+            if let Some(model_id) = current_model_id {
+                state.set_selected_value(&model_id, window, cx);
+            }
+        });
+    }
+
+    /// Switch the session to the model picked in the selector
+    fn on_model_changed(&mut self, cx: &mut Context<Self>) {
+        let Some(model_id) = self.model_select.read(cx).selected_value().cloned() else {
+            return;
+        };
+        let Some(session_id) = self.session_id.clone() else {
+            return;
+        };
+        let Some(agent_service) = AppState::global(cx).agent_service().cloned() else {
+            log::error!("Cannot change model: agent service not available");
+            return;
+        };
+        let Some(agent_name) = agent_service.get_agent_for_session(&session_id) else {
+            log::warn!("Cannot change model: no agent for session {}", session_id);
+            return;
+        };
+
+        let workspace_service = AppState::global(cx).workspace_service().cloned();
+
+        cx.spawn(async move |_entity, _cx| {
+            if let Err(e) = agent_service
+                .set_session_model(&agent_name, &session_id, &model_id)
+                .await
+            {
+                log::error!(
+                    "Failed to set model '{}' for session {}: {}",
+                    model_id,
+                    session_id,
+                    e
+                );
+                return;
+            }
+            if let Some(workspace_service) = workspace_service {
+                if let Err(e) = workspace_service
+                    .set_session_model(&session_id, &model_id)
+                    .await
+                {
+                    log::warn!("Failed to save model for session {}: {}", session_id, e);
+                }
+            }
+        })
+        .detach();
     }
 
     fn create_input_state(window: &mut Window, cx: &mut App) -> Entity<InputState> {
@@ -148,7 +277,12 @@ impl ConversationPanel {
         })
     }
 
-    fn create_message_stream(cx: &mut App) -> Entity<AcpMessageStream> {
+    fn create_message_stream(
+        scroll_handle: ScrollHandle,
+        session_id: Option<String>,
+        editable: bool,
+        cx: &mut App,
+    ) -> Entity<AcpMessageStream> {
         let icon_provider = Arc::new(|name: &str| Icon::new(get_agent_icon(name)));
         let tool_call_options = ToolCallItemOptions::default()
             .preview_max_lines(AppState::global(cx).tool_call_preview_max_lines())
@@ -171,20 +305,51 @@ impl ConversationPanel {
             )),
         };
 
+        // Plan edits are sent to the agent as a message in the session
+        let todo_list_options = AgentTodoListOptions {
+            editable: editable && session_id.is_some(),
+            on_change: session_id.map(|session_id| {
+                let handler: TodoListChangeHandler = Arc::new(
+                    move |entries: Vec<PlanEntry>, window: &mut Window, cx: &mut App| {
+                        let action = SendMessageToSession {
+                            session_id: session_id.clone(),
+                            message: plan_update_message(&entries),
+                            images: Vec::new(),
+                            code_selections: Vec::new(),
+                            resources: Vec::new(),
+                            edit_turn: None,
+                        };
+                        window.dispatch_action(Box::new(action), cx);
+                    },
+                );
+                handler
+            }),
+        };
+
         let options = AcpMessageStreamOptions {
             agent_icon_provider: icon_provider,
+            timestamp_formatter: Some(Arc::new(|time| {
+                crate::utils::time::format_relative(time, std::time::SystemTime::now())
+            })),
+            tool_kind_labeler: Some(Arc::new(tool_kind_label)),
             tool_call_item_options: tool_call_options,
             diff_summary_options,
+            todo_list_options,
+            searchable: true,
+            scroll_handle: Some(scroll_handle),
+            show_thoughts: AppSettings::global(cx).show_agent_thoughts,
+            editable_user_messages: editable,
+            auto_scroll: AppSettings::global(cx).conversation_auto_scroll,
         };
 
-        cx.new(|_| AcpMessageStream::with_options(options))
-    }
-
-    fn should_auto_scroll(&self) -> bool {
-        let max_offset = self.scroll_handle.max_offset().height;
-        let offset = self.scroll_handle.offset().y;
-        let distance_to_bottom = max_offset + offset;
-        distance_to_bottom <= px(AUTO_SCROLL_THRESHOLD_PX)
+        cx.new(|cx| {
+            cx.observe_global::<AppSettings>(|stream: &mut AcpMessageStream, cx| {
+                stream.set_show_thoughts(AppSettings::global(cx).show_agent_thoughts, cx);
+                stream.set_auto_scroll(AppSettings::global(cx).conversation_auto_scroll, cx);
+            })
+            .detach();
+            AcpMessageStream::with_options(options)
+        })
     }
 
     /// Load historical messages for a session
@@ -243,8 +408,8 @@ impl ConversationPanel {
 
                                 this.message_stream.update(cx, |stream, cx| {
                                     stream.add_diff_summary_if_needed(cx);
+                                    stream.jump_to_latest(cx);
                                 });
-                                this.scroll_handle.scroll_to_bottom();
                                 cx.notify();
                             });
                         } else {
@@ -342,8 +507,6 @@ impl ConversationPanel {
                 let _ = cx.update(move |cx| {
                     if let Some(entity) = weak.upgrade() {
                         entity.update(cx, |this, cx| {
-                            let should_auto_scroll = this.should_auto_scroll();
-
                             for event in events {
                                 let session_id = event.session_id.clone();
                                 let agent_name = event.agent_name.clone();
@@ -358,9 +521,6 @@ impl ConversationPanel {
                                 });
                             }
 
-                            if should_auto_scroll {
-                                this.scroll_handle.scroll_to_bottom();
-                            }
                             cx.notify();
 
                             let total_items = this.message_stream.read(cx).len();
@@ -479,18 +639,61 @@ impl ConversationPanel {
                             if permission_store.is_none() {
                                 log::error!("PermissionStore not available in AppState");
                             }
+                            let remember_handler: Option<crate::PermissionRememberHandler> =
+                                permission_store.clone().map(|store| {
+                                    let handler: crate::PermissionRememberHandler = Arc::new(
+                                        move |session_id: String,
+                                              tool_kind: ToolKind,
+                                              kind: PermissionOptionKind,
+                                              globally: bool,
+                                              _cx: &mut Context<crate::PermissionRequest>| {
+                                            let scope = if globally {
+                                                PermissionScope::Global
+                                            } else {
+                                                PermissionScope::Session
+                                            };
+                                            store.remember_choice_with_scope(
+                                                scope,
+                                                &session_id,
+                                                tool_kind,
+                                                kind,
+                                            );
+                                        },
+                                    );
+                                    handler
+                                });
+                            let lookup_handler: Option<crate::PermissionLookupHandler> =
+                                permission_store.clone().map(|store| {
+                                    let handler: crate::PermissionLookupHandler =
+                                        Arc::new(move |session_id: &str, tool_kind: ToolKind| {
+                                            store.remembered_choice(session_id, tool_kind)
+                                        });
+                                    handler
+                                });
+
+                            // Zero leaves the request waiting for the user
+                            let auto_timeout_secs =
+                                AppSettings::global(cx).permission_auto_timeout_secs;
+                            let auto_timeout = (auto_timeout_secs > 0.0)
+                                .then(|| Duration::from_secs_f64(auto_timeout_secs));
 
                             let permission_view = cx.new(|cx| {
-                                let inner = cx.new(|_| {
-                                    crate::PermissionRequest::with_options(
+                                let inner = cx.new(|cx| {
+                                    let mut request = crate::PermissionRequest::with_options(
                                         event.permission_id.clone(),
                                         event.session_id.clone(),
                                         &event.tool_call,
                                         event.options.clone(),
                                         PermissionRequestOptions {
                                             on_response: response_handler,
+                                            on_remember: remember_handler,
+                                            lookup_remembered: lookup_handler,
+                                            auto_timeout,
+                                            ..Default::default()
                                         },
-                                    )
+                                    );
+                                    request.start_auto_timeout(cx);
+                                    request
                                 });
                                 crate::PermissionRequestView::from_entity(inner)
                             });
@@ -562,10 +765,14 @@ impl ConversationPanel {
 
         // Subscribe to workspace bus, send status updates to channel in callback
         event_hub.subscribe_workspace_updates(move |event| {
-            // Only handle SessionStatusUpdated events
+            // Only handle status and message queue events
             if let crate::core::event_bus::WorkspaceUpdateEvent::SessionStatusUpdated {
                 session_id,
                 ..
+            }
+            | crate::core::event_bus::WorkspaceUpdateEvent::MessageQueueChanged {
+                session_id,
+                ..
             } = event
             {
                 // Filter by session_id if specified
@@ -591,6 +798,23 @@ impl ConversationPanel {
                 filter_log2.as_deref().unwrap_or("all")
             );
             while let Some(event) = rx.recv().await {
+                if let crate::core::event_bus::WorkspaceUpdateEvent::MessageQueueChanged {
+                    queued,
+                    ..
+                } = event
+                {
+                    let weak = weak_entity.clone();
+                    let _ = cx.update(|cx| {
+                        if let Some(entity) = weak.upgrade() {
+                            entity.update(cx, |this, cx| {
+                                this.queued_messages = queued;
+                                cx.notify();
+                            });
+                        }
+                    });
+                    continue;
+                }
+
                 if let crate::core::event_bus::WorkspaceUpdateEvent::SessionStatusUpdated {
                     session_id,
                     agent_name,
@@ -626,6 +850,10 @@ impl ConversationPanel {
                                     );
                                 }
 
+                                if !status.is_turn_in_progress() {
+                                    this.cancelling = false;
+                                }
+
                                 // Update session status
                                 this.session_status = Some(SessionStatusInfo {
                                     agent_name,
@@ -689,13 +917,29 @@ impl ConversationPanel {
         handled
     }
 
+    /// Attach files dropped on the input, reporting any that were rejected
+    fn handle_drop(&mut self, paths: &ExternalPaths, cx: &mut Context<Self>) {
+        let dropped = attachments_from_paths(paths.paths(), &self.attached_resources);
+        self.attached_resources.extend(dropped.resources);
+        self.attachment_error = (!dropped.rejected.is_empty()).then(|| {
+            dropped
+                .rejected
+                .iter()
+                .map(|error| error.message())
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        cx.notify();
+    }
+
     /// Send a message to the current session
     /// Dispatches SendMessageToSession action to workspace for handling
     fn send_message(
-        &self,
+        &mut self,
         text: String,
         images: Vec<(ImageContent, String)>,
         code_selections: Vec<AddCodeSelection>,
+        resources: Vec<ContentBlock>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -710,20 +954,152 @@ impl ConversationPanel {
             session_id
         );
 
+        // An edited message replaces its turn and everything after it
+        let edit_turn = self.editing_turn.take();
+        if let Some(turn) = edit_turn {
+            self.message_stream.update(cx, |stream, cx| {
+                stream.truncate_at_user_message(turn, cx);
+            });
+        }
+
         // Create action and dispatch to workspace
         let action = SendMessageToSession {
             session_id: session_id.clone(),
             message: text,
             images,
             code_selections,
+            resources,
+            edit_turn,
         };
 
         window.dispatch_action(Box::new(action), cx);
     }
 
-    /// Cancel the current session
-    /// Dispatches cancel via AgentService to avoid lost actions
-    fn send_cancel_message(&self, _window: &mut Window, cx: &mut Context<Self>) {
+    fn on_message_stream_event(
+        &mut self,
+        _stream: &Entity<AcpMessageStream>,
+        event: &MessageStreamEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            MessageStreamEvent::EditUserMessage { turn, contents } => {
+                self.start_editing(*turn, contents, window, cx);
+            }
+        }
+    }
+
+    /// Load a sent message back into the input; sending it replaces the
+    /// message and everything after it
+    fn start_editing(
+        &mut self,
+        turn: usize,
+        contents: &[ContentBlock],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_cancel_visible() || self.is_input_disabled() {
+            return;
+        }
+
+        let mut text = Vec::new();
+        self.code_selections.clear();
+        self.pasted_images.clear();
+        self.attached_resources.clear();
+        self.attachment_error = None;
+        for block in contents {
+            match block {
+                ContentBlock::Text(content) => {
+                    match AddCodeSelection::from_prompt_context(&content.text) {
+                        Some(selection) => self.code_selections.push(selection),
+                        // A slash command is edited as typed and expanded again on send
+                        None => text.push(user_display_text(content).to_string()),
+                    }
+                }
+                ContentBlock::Image(image) => {
+                    let filename = format!("image-{}", self.pasted_images.len() + 1);
+                    self.pasted_images.push((image.clone(), filename));
+                }
+                ContentBlock::ResourceLink(_) => self.attached_resources.push(block.clone()),
+                _ => {}
+            }
+        }
+
+        self.input_state.update(cx, |state, cx| {
+            state.set_value(SharedString::from(text.join("\n")), window, cx);
+            state.focus(window, cx);
+        });
+        self.editing_turn = Some(turn);
+        cx.notify();
+    }
+
+    fn cancel_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing_turn.take().is_none() {
+            return;
+        }
+        self.code_selections.clear();
+        self.pasted_images.clear();
+        self.attached_resources.clear();
+        self.attachment_error = None;
+        self.input_state.update(cx, |state, cx| {
+            state.set_value(SharedString::from(""), window, cx);
+        });
+        cx.notify();
+    }
+
+    /// Whether the last turn can be sent again: the session is idle and the
+    /// stream has something to replace
+    fn can_regenerate(&self, cx: &App) -> bool {
+        self.session_id.is_some()
+            && !self.is_input_disabled()
+            && !self
+                .session_status
+                .as_ref()
+                .is_some_and(|info| info.status.is_turn_in_progress())
+            && !self.message_stream.read(cx).is_empty()
+    }
+
+    /// Drop the last turn from the stream and dispatch RegenerateResponse so
+    /// the workspace resends its user message
+    fn regenerate_response(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.can_regenerate(cx) {
+            return;
+        }
+        let Some(session_id) = self.session_id.clone() else {
+            return;
+        };
+
+        let truncated = self
+            .message_stream
+            .update(cx, |stream, cx| stream.truncate_at_last_user_message(cx));
+        if !truncated {
+            return;
+        }
+
+        window.dispatch_action(Box::new(RegenerateResponse { session_id }), cx);
+    }
+
+    /// Replace the input with the previous/next sent message
+    fn navigate_history(
+        &mut self,
+        direction: HistoryDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let current = self.input_state.read(cx).value().to_string();
+        let Some(text) = self.input_history.navigate(direction, &current) else {
+            return;
+        };
+        let text = SharedString::from(text.to_string());
+        self.input_state.update(cx, |state, cx| {
+            state.set_value(text, window, cx);
+        });
+        cx.notify();
+    }
+
+    /// Cancel the current session and drop its queued messages
+    /// Dispatches cancel via MessageService to avoid lost actions
+    fn send_cancel_message(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         // Only send if we have a session_id
         let Some(ref session_id) = self.session_id else {
             log::warn!("Cannot cancel session: no session_id");
@@ -731,10 +1107,10 @@ impl ConversationPanel {
         };
 
         let session_id = session_id.clone();
-        let agent_service = match AppState::global(cx).agent_service() {
+        let message_service = match AppState::global(cx).message_service() {
             Some(service) => service.clone(),
             None => {
-                log::error!("AgentService not initialized, cannot cancel session");
+                log::error!("MessageService not initialized, cannot cancel session");
                 return;
             }
         };
@@ -744,8 +1120,12 @@ impl ConversationPanel {
             session_id
         );
 
-        cx.spawn(async move |_this, _cx| {
-            match agent_service.cancel_session_by_id(&session_id).await {
+        // Shown until the session reports the turn has ended
+        self.cancelling = true;
+        cx.notify();
+
+        cx.spawn(
+            async move |this, cx| match message_service.cancel_session(&session_id).await {
                 Ok(()) => {
                     log::info!(
                         "[ConversationPanel] Session {} cancelled successfully",
@@ -758,15 +1138,35 @@ impl ConversationPanel {
                         session_id,
                         e
                     );
+                    _ = this.update(cx, |this, cx| {
+                        this.cancelling = false;
+                        cx.notify();
+                    });
                 }
-            }
-        })
+            },
+        )
         .detach();
     }
 
+    /// Whether the inline stop button is shown
+    fn is_cancel_visible(&self) -> bool {
+        cancel_visible(
+            self.session_id.as_deref(),
+            self.session_status.as_ref().map(|info| &info.status),
+        )
+    }
+
+    /// Imported sessions have no agent behind them and can only be read
+    fn is_replay(&self) -> bool {
+        self.session_id.as_deref().is_some_and(is_imported_session)
+    }
+
     /// Check if the input should be disabled based on session status
     /// Returns true if the session is closed, failed, or not resumable
     fn is_input_disabled(&self) -> bool {
+        if self.is_replay() {
+            return true;
+        }
         match &self.session_status {
             Some(status_info) => {
                 matches!(
@@ -783,24 +1183,28 @@ impl ConversationPanel {
     /// Render the loading skeleton and status info when session is in progress
     fn render_loading_skeleton(&self, cx: &mut Context<Self>) -> impl IntoElement {
         // Only show loading skeleton when session is actively processing
-        let should_show_loading = self.session_status.as_ref().map_or(false, |status_info| {
-            matches!(
-                status_info.status,
-                SessionStatus::InProgress | SessionStatus::Pending
-            )
-        });
+        let should_show_loading = self
+            .session_status
+            .as_ref()
+            .is_some_and(|status_info| status_info.status.is_turn_in_progress());
+        let show_cancel = self.is_cancel_visible();
 
         if !should_show_loading {
             return v_flex().into_any_element();
         }
 
-        let current_todo = self.message_stream.read(cx).current_todo_in_progress();
+        let stream = self.message_stream.read(cx);
+        let current_todo = stream.current_todo_in_progress();
+        let thought_in_flight = stream.thought_in_flight();
+        let tool_in_flight = stream.tool_call_in_flight(cx);
 
         // Build status indicator row
         let status_info = self.session_status.as_ref().unwrap(); // Safe because of check above
-        let (status_icon, status_color) = match status_info.status {
-            SessionStatus::InProgress => (IconName::Loader, cx.theme().primary),
-            SessionStatus::Pending => (IconName::LoaderCircle, cx.theme().warning),
+        let kind = indicator_kind(&status_info.status, thought_in_flight, tool_in_flight);
+        let (status_icon, status_color) = match (&status_info.status, kind) {
+            (_, IndicatorKind::Tool) => (IconName::Settings, cx.theme().primary),
+            (SessionStatus::InProgress, _) => (IconName::Loader, cx.theme().primary),
+            (SessionStatus::Pending, _) => (IconName::LoaderCircle, cx.theme().warning),
             _ => return v_flex().into_any_element(), // Fallback
         };
 
@@ -879,7 +1283,20 @@ impl ConversationPanel {
                                             .child(elapsed_time),
                                     ),
                             ),
-                    ),
+                    )
+                    .when(show_cancel, |this| {
+                        this.child(
+                            Button::new("conversation-stop")
+                                .icon(Icon::new(crate::assets::Icon::SquarePause))
+                                .label(t!("conversation.stop").to_string())
+                                .outline()
+                                .small()
+                                .on_click(cx.listener(|this, _ev, window, cx| {
+                                    this.send_cancel_message(window, cx);
+                                    cx.notify();
+                                })),
+                        )
+                    }),
             )
             .child(
                 // Content skeletons - indented to align with text content
@@ -964,12 +1381,32 @@ impl Focusable for ConversationPanel {
 impl Render for ConversationPanel {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let is_empty = self.message_stream.read(cx).is_empty();
+        let show_jump_to_latest = self
+            .message_stream
+            .read(cx)
+            .scroll_anchor()
+            .show_jump_to_latest();
+        let can_regenerate = self.can_regenerate(cx);
         let message_list = v_flex()
             .p_4()
             .gap_3()
             .bg(cx.theme().background)
             .child(self.message_stream.clone())
-            .child(self.render_loading_skeleton(cx));
+            .child(self.render_loading_skeleton(cx))
+            .when(can_regenerate, |this| {
+                this.child(
+                    h_flex().pl_6().child(
+                        Button::new("conversation-regenerate")
+                            .icon(IconName::Redo)
+                            .label(t!("conversation.regenerate").to_string())
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.regenerate_response(window, cx);
+                            })),
+                    ),
+                )
+            });
 
         // Main layout: vertical flex with scroll area on top and input box at bottom
         v_flex()
@@ -978,32 +1415,69 @@ impl Render for ConversationPanel {
             .child(
                 // Scrollable message area - takes remaining space
                 div()
-                    .id("conversation-scroll-container")
+                    .relative()
                     .flex_1()
                     .w_full()
-                    .track_scroll(&self.scroll_handle)
-                    .overflow_y_scroll()
-                    .size_full()
-                    .when(is_empty, |this| {
-                        // Show empty state with centered text
+                    .min_h_0()
+                    .child(
+                        div()
+                            .id("conversation-scroll-container")
+                            .flex_1()
+                            .w_full()
+                            .track_scroll(&self.scroll_handle)
+                            .overflow_y_scroll()
+                            .size_full()
+                            .on_scroll_wheel(cx.listener(|this, _, _, cx| {
+                                // Read the new offset once the container has applied the scroll
+                                let stream = this.message_stream.clone();
+                                cx.defer(move |cx| {
+                                    stream.update(cx, |stream, cx| stream.sync_scroll_position(cx));
+                                });
+                            }))
+                            .when(is_empty, |this| {
+                                // Show empty state with centered text
+                                this.child(
+                                    div()
+                                        .size_full()
+                                        .flex()
+                                        .items_center()
+                                        .justify_center()
+                                        .child(
+                                            div()
+                                                .text_color(cx.theme().muted_foreground)
+                                                .text_sm()
+                                                .child(t!("conversation.empty").to_string()),
+                                        ),
+                                )
+                            })
+                            .when(!is_empty, |this| {
+                                // Show message list
+                                this.pb_3() // Add padding at bottom so messages don't get hidden behind input box
+                                    .child(message_list)
+                            }),
+                    )
+                    .when(show_jump_to_latest, |this| {
                         this.child(
-                            div()
-                                .size_full()
-                                .flex()
-                                .items_center()
+                            h_flex()
+                                .absolute()
+                                .bottom_3()
+                                .left_0()
+                                .right_0()
                                 .justify_center()
                                 .child(
-                                    div()
-                                        .text_color(cx.theme().muted_foreground)
-                                        .text_sm()
-                                        .child(t!("conversation.empty").to_string()),
+                                    Button::new("conversation-jump-to-latest")
+                                        .icon(IconName::ArrowDown)
+                                        .label(t!("conversation.jump_to_latest").to_string())
+                                        .primary()
+                                        .small()
+                                        .rounded_full()
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.message_stream.update(cx, |stream, cx| {
+                                                stream.jump_to_latest(cx);
+                                            });
+                                        })),
                                 ),
                         )
-                    })
-                    .when(!is_empty, |this| {
-                        // Show message list
-                        this.pb_3() // Add padding at bottom so messages don't get hidden behind input box
-                            .child(message_list)
                     }),
             )
             .child(
@@ -1015,60 +1489,231 @@ impl Render for ConversationPanel {
                     // .border_t_1()
                     .p_1()
                     // .border_color(cx.theme().border)
-                    .child({
-                        let entity = cx.entity().clone();
-                        let is_disabled = self.is_input_disabled();
-                        ChatInputBox::new("chat-input", self.input_state.clone())
-                            .pasted_images(self.pasted_images.clone())
-                            .code_selections(self.code_selections.clone())
-                            .session_status(
-                                self.session_status.as_ref().map(|info| info.status.clone()),
-                            )
-                            .disabled(is_disabled)
-                            .on_paste(move |window, cx| {
-                                entity.update(cx, |this, cx| {
-                                    this.handle_paste(window, cx);
-                                });
-                            })
-                            .on_remove_image(cx.listener(|this, idx, _, cx| {
-                                // Remove the image at the given index
-                                if *idx < this.pasted_images.len() {
-                                    this.pasted_images.remove(*idx);
-                                    cx.notify();
-                                }
-                            }))
-                            .on_remove_code_selection(cx.listener(|this, idx, _, cx| {
-                                // Remove the code selection at the given index
-                                if *idx < this.code_selections.len() {
-                                    this.code_selections.remove(*idx);
-                                    cx.notify();
-                                }
-                            }))
-                            .on_send(cx.listener(|this, _ev, window, cx| {
-                                let text = this.input_state.read(cx).value().to_string();
-                                if !text.trim().is_empty()
-                                    || !this.pasted_images.is_empty()
-                                    || !this.code_selections.is_empty()
-                                {
-                                    // Clear the input
-                                    this.input_state.update(cx, |state, cx| {
-                                        state.set_value(SharedString::from(""), window, cx);
+                    .when(self.editing_turn.is_some(), |this| {
+                        this.child(
+                            h_flex()
+                                .px_3()
+                                .py_1()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(t!("conversation.edit.hint").to_string()),
+                                )
+                                .child(
+                                    Button::new("conversation-cancel-edit")
+                                        .label(t!("conversation.edit.cancel").to_string())
+                                        .ghost()
+                                        .xsmall()
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.cancel_editing(window, cx);
+                                        })),
+                                ),
+                        )
+                    })
+                    .when(self.queued_messages > 0, |this| {
+                        this.child(
+                            div()
+                                .px_3()
+                                .py_1()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(
+                                    t!("conversation.queue.pending", count = self.queued_messages)
+                                        .to_string(),
+                                ),
+                        )
+                    })
+                    .when(self.is_replay(), |this| {
+                        this.child(
+                            div()
+                                .px_3()
+                                .py_2()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(t!("conversation.replay.hint").to_string()),
+                        )
+                    })
+                    .when(!self.is_replay(), |this| {
+                        this.child({
+                            let entity = cx.entity().clone();
+                            let is_disabled = self.is_input_disabled();
+                            ChatInputBox::new("chat-input", self.input_state.clone())
+                                .when(self.has_models, |chat| {
+                                    chat.model_select(self.model_select.clone())
+                                })
+                                .pasted_images(self.pasted_images.clone())
+                                .code_selections(self.code_selections.clone())
+                                .attached_resources(self.attached_resources.clone())
+                                .attachment_error(self.attachment_error.clone())
+                                .session_status(
+                                    self.session_status.as_ref().map(|info| info.status.clone()),
+                                )
+                                .disabled(is_disabled)
+                                .cancelling(self.cancelling)
+                                .on_paste(move |window, cx| {
+                                    entity.update(cx, |this, cx| {
+                                        this.handle_paste(window, cx);
                                     });
+                                })
+                                .on_remove_image(cx.listener(|this, idx, _, cx| {
+                                    // Remove the image at the given index
+                                    if *idx < this.pasted_images.len() {
+                                        this.pasted_images.remove(*idx);
+                                        cx.notify();
+                                    }
+                                }))
+                                .on_remove_code_selection(cx.listener(|this, idx, _, cx| {
+                                    // Remove the code selection at the given index
+                                    if *idx < this.code_selections.len() {
+                                        this.code_selections.remove(*idx);
+                                        cx.notify();
+                                    }
+                                }))
+                                .on_remove_resource(cx.listener(|this, idx, _, cx| {
+                                    if *idx < this.attached_resources.len() {
+                                        this.attached_resources.remove(*idx);
+                                        this.attachment_error = None;
+                                        cx.notify();
+                                    }
+                                }))
+                                .on_drop_paths(cx.listener(|this, paths, _, cx| {
+                                    this.handle_drop(paths, cx);
+                                }))
+                                .on_send(cx.listener(|this, _ev, window, cx| {
+                                    let text = this.input_state.read(cx).value().to_string();
+                                    if !text.trim().is_empty()
+                                        || !this.pasted_images.is_empty()
+                                        || !this.code_selections.is_empty()
+                                        || !this.attached_resources.is_empty()
+                                    {
+                                        // Clear the input
+                                        this.input_state.update(cx, |state, cx| {
+                                            state.set_value(SharedString::from(""), window, cx);
+                                        });
+
+                                        this.input_history.push(text.clone());
+
+                                        // Send the message with images and code selections
+                                        let images = std::mem::take(&mut this.pasted_images);
+                                        let code_selections =
+                                            std::mem::take(&mut this.code_selections);
+                                        let resources =
+                                            std::mem::take(&mut this.attached_resources);
+                                        this.attachment_error = None;
+                                        this.send_message(
+                                            text,
+                                            images,
+                                            code_selections,
+                                            resources,
+                                            window,
+                                            cx,
+                                        );
 
-                                    // Send the message with images and code selections
-                                    let images = std::mem::take(&mut this.pasted_images);
-                                    let code_selections = std::mem::take(&mut this.code_selections);
-                                    this.send_message(text, images, code_selections, window, cx);
-
+                                        cx.notify();
+                                    }
+                                }))
+                                .on_history_navigate(cx.listener(|this, direction, window, cx| {
+                                    this.navigate_history(*direction, window, cx);
+                                }))
+                                .on_cancel(cx.listener(|this, _ev, window, cx| {
+                                    log::info!("[ConversationPanel] on_cancel callback triggered");
+                                    this.send_cancel_message(window, cx);
                                     cx.notify();
-                                }
-                            }))
-                            .on_cancel(cx.listener(|this, _ev, window, cx| {
-                                log::info!("[ConversationPanel] on_cancel callback triggered");
-                                this.send_cancel_message(window, cx);
-                                cx.notify();
-                            }))
+                                }))
+                        })
                     }),
             )
     }
 }
+
+/// The stop button tracks the turn: shown while the agent is working on the
+/// active session, replaced by the send affordance once the turn ends
+fn cancel_visible(session_id: Option<&str>, status: Option<&SessionStatus>) -> bool {
+    session_id.is_some() && status.is_some_and(SessionStatus::is_turn_in_progress)
+}
+
+/// Localized name of a tool kind for tool call group headers
+fn tool_kind_label(kind: ToolKind) -> String {
+    match kind {
+        ToolKind::Read => t!("conversation.tool_kind.read"),
+        ToolKind::Edit => t!("conversation.tool_kind.edit"),
+        ToolKind::Delete => t!("conversation.tool_kind.delete"),
+        ToolKind::Move => t!("conversation.tool_kind.move"),
+        ToolKind::Search => t!("conversation.tool_kind.search"),
+        ToolKind::Execute => t!("conversation.tool_kind.execute"),
+        ToolKind::Think => t!("conversation.tool_kind.think"),
+        ToolKind::Fetch => t!("conversation.tool_kind.fetch"),
+        ToolKind::SwitchMode => t!("conversation.tool_kind.switch_mode"),
+        ToolKind::Other | _ => t!("conversation.tool_kind.other"),
+    }
+    .to_string()
+}
+
+/// Message telling the agent how the user edited its plan
+fn plan_update_message(entries: &[PlanEntry]) -> String {
+    let mut message = t!("conversation.plan_updated").to_string();
+    for entry in entries {
+        let mark = if entry.status == PlanEntryStatus::Completed {
+            "x"
+        } else {
+            " "
+        };
+        message.push_str(&format!("\n- [{}] {}", mark, entry.content));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::PlanEntryPriority;
+
+    #[test]
+    fn plan_update_lists_entries_in_order() {
+        let entries = [
+            PlanEntry::new(
+                "Write tests",
+                PlanEntryPriority::High,
+                PlanEntryStatus::Completed,
+            ),
+            PlanEntry::new(
+                "Fix parser",
+                PlanEntryPriority::Medium,
+                PlanEntryStatus::InProgress,
+            ),
+        ];
+        let message = plan_update_message(&entries);
+        let lines: Vec<_> = message.lines().skip(1).collect();
+        assert_eq!(lines, ["- [x] Write tests", "- [ ] Fix parser"]);
+    }
+
+    #[test]
+    fn cancel_button_tracks_session_status() {
+        let session = Some("session-1");
+        assert!(!cancel_visible(session, None));
+
+        let transitions = [
+            (SessionStatus::Active, false),
+            (SessionStatus::Pending, true),
+            (SessionStatus::InProgress, true),
+            (SessionStatus::Completed, false),
+            (SessionStatus::InProgress, true),
+            (SessionStatus::Failed, false),
+            (SessionStatus::Idle, false),
+        ];
+        for (status, visible) in transitions {
+            assert_eq!(
+                cancel_visible(session, Some(&status)),
+                visible,
+                "{status:?}"
+            );
+        }
+
+        // Nothing to cancel without a session
+        assert!(!cancel_visible(None, Some(&SessionStatus::InProgress)));
+    }
+}
@@ -15,8 +15,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::AppState;
 use crate::panels::{
-    CodeEditorPanel, ConversationPanel, SessionManagerPanel, SettingsPanel, TaskPanel,
-    TerminalPanel, ToolCallDetailPanel, WelcomePanel,
+    CodeEditorPanel, ConversationPanel, SessionManagerPanel, SettingsPanel, TaskListView,
+    TaskPanel, TerminalPanel, ToolCallDetailPanel, WelcomePanel,
 };
 use crate::{ShowPanelInfo, ToggleSearch};
 
@@ -423,7 +423,20 @@ impl DockPanelContainer {
         }
 
         match agent_state.agent_studio_klass.as_ref() {
-            "TaskPanel" => Self::panel::<TaskPanel>(window, cx),
+            "TaskPanel" => {
+                let container = Self::panel::<TaskPanel>(window, cx);
+                let task_panel = container
+                    .read(cx)
+                    .agent_studio
+                    .clone()
+                    .and_then(|view| view.downcast::<TaskPanel>().ok());
+                if let (Some(task_panel), Some(list_view)) =
+                    (task_panel, agent_state.task_list_view.clone())
+                {
+                    task_panel.update(cx, |panel, cx| panel.set_list_view(list_view, cx));
+                }
+                container
+            }
             "SessionManagerPanel" => Self::panel::<SessionManagerPanel>(window, cx),
             "SettingsPanel" => Self::panel::<SettingsPanel>(window, cx),
             "ToolCallDetailPanel" => Self::panel::<ToolCallDetailPanel>(window, cx),
@@ -439,7 +452,7 @@ impl DockPanelContainer {
                 }
             }
             "CodeEditorPanel" => {
-                if let Some(working_dir) = agent_state
+                let container = if let Some(working_dir) = agent_state
                     .working_directory
                     .as_deref()
                     .filter(|path| !path.is_empty())
@@ -448,7 +461,28 @@ impl DockPanelContainer {
                     Self::panel_for_code_editor_with_cwd(working_dir, window, cx)
                 } else {
                     Self::panel::<CodeEditorPanel>(window, cx)
+                };
+
+                let editor = container
+                    .read(cx)
+                    .agent_studio
+                    .clone()
+                    .and_then(|view| view.downcast::<CodeEditorPanel>().ok());
+                if let Some(editor) = editor {
+                    let files = agent_state
+                        .open_files
+                        .iter()
+                        .map(std::path::PathBuf::from)
+                        .collect();
+                    let active_file = agent_state
+                        .active_file
+                        .as_deref()
+                        .map(std::path::PathBuf::from);
+                    editor.update(cx, |editor, cx| {
+                        editor.restore_open_files(files, active_file, window, cx);
+                    });
                 }
+                container
             }
             "TerminalPanel" => {
                 if let Some(working_dir) = agent_state
@@ -596,6 +630,14 @@ pub struct DockPanelState {
     pub workspace_name: Option<String>,
     #[serde(default)]
     pub working_directory: Option<String>,
+    /// Files open in a code editor, in tab order
+    #[serde(default)]
+    pub open_files: Vec<String>,
+    #[serde(default)]
+    pub active_file: Option<String>,
+    /// Status filter and sort order of a task panel
+    #[serde(default)]
+    pub task_list_view: Option<TaskListView>,
 }
 
 impl DockPanelState {
@@ -606,6 +648,9 @@ impl DockPanelState {
             "workspace_id": self.workspace_id,
             "workspace_name": self.workspace_name,
             "working_directory": self.working_directory,
+            "open_files": self.open_files,
+            "active_file": self.active_file,
+            "task_list_view": self.task_list_view,
         })
     }
 
@@ -717,6 +762,9 @@ impl Panel for DockPanelContainer {
         let mut workspace_id = None;
         let mut workspace_name = None;
         let mut working_directory = None;
+        let mut open_files = Vec::new();
+        let mut active_file = None;
+        let mut task_list_view = None;
 
         // Helper function to normalize Windows paths (remove \\?\ prefix)
         fn normalize_path(path: std::path::PathBuf) -> String {
@@ -754,6 +802,13 @@ impl Panel for DockPanelContainer {
                         workspace_id = panel.workspace_id();
                         workspace_name = panel.workspace_name();
                         working_directory = Some(normalize_path(panel.working_directory()));
+                        open_files = panel.open_files().into_iter().map(normalize_path).collect();
+                        active_file = panel.active_file().map(normalize_path);
+                    }
+                }
+                "TaskPanel" => {
+                    if let Ok(entity) = agent_studio.clone().downcast::<TaskPanel>() {
+                        task_list_view = Some(entity.read(cx).list_view().clone());
                     }
                 }
                 "TerminalPanel" => {
@@ -774,6 +829,9 @@ impl Panel for DockPanelContainer {
             workspace_id,
             workspace_name,
             working_directory,
+            open_files,
+            active_file,
+            task_list_view,
         };
         state.info = PanelInfo::panel(agent_state.to_value());
         state
@@ -15,8 +15,8 @@ pub use code_editor::CodeEditorPanel;
 pub use conversation::ConversationPanel;
 pub use dock_panel::{DockPanel, DockPanelContainer, DockPanelState};
 pub use session_manager::SessionManagerPanel;
-pub use settings_panel::{AppSettings, SettingsPanel};
-pub use task_panel::TaskPanel;
+pub use settings_panel::{AppSettings, SettingsPanel, SkippedSetupSteps};
+pub use task_panel::{TaskListView, TaskPanel};
 pub use terminal_panel::TerminalPanel;
 pub use tool_call_detail_panel::ToolCallDetailPanel;
 pub use welcome_panel::WelcomePanel;
@@ -458,6 +458,7 @@ impl SessionManagerPanel {
             SessionStatus::Failed => theme.muted,
             SessionStatus::InProgress => theme.info,
             SessionStatus::Pending => theme.info,
+            SessionStatus::Interrupted => theme.warning,
         }
     }
 
@@ -471,6 +472,7 @@ impl SessionManagerPanel {
             SessionStatus::Completed => "Completed",
             SessionStatus::Failed => "Failed",
             SessionStatus::Pending => "Pending",
+            SessionStatus::Interrupted => "Interrupted",
         }
     }
 }
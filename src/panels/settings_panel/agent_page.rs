@@ -1,3 +1,4 @@
+use agentx_types::{AgentProcessConfig, AgentRuntimeStatus, EnvVarError, EnvVarErrorKind};
 use gpui::{AppContext as _, Context, Entity, ParentElement as _, Styled, Window, px};
 use gpui_component::{
     ActiveTheme, IconName, Sizable, WindowExt as _,
@@ -6,143 +7,201 @@ use gpui_component::{
     h_flex,
     input::{Input, InputState},
     label::Label,
-    setting::{SettingField, SettingGroup, SettingItem, SettingPage},
+    setting::{SettingField, SettingItem, SettingPage},
     v_flex,
 };
 use rust_i18n::t;
-use std::collections::HashMap;
 
 use super::panel::SettingsPanel;
+use super::search::SettingsSearch;
 use crate::{
-    AppState,
+    AppState, StatusIndicator,
     app::actions::{
-        AddAgent, ChangeConfigPath, ReloadAgentConfig, RemoveAgent, RestartAgent, UpdateAgent,
+        AddAgent, ChangeConfigPath, ReloadAgentConfig, RemoveAgent, RestartAgent, SetAgentsEnabled,
+        UpdateAgent,
     },
 };
 
 impl SettingsPanel {
-    pub fn agent_page(&self, view: &Entity<Self>) -> SettingPage {
-        SettingPage::new(t!("settings.agents.title").to_string())
-            .resettable(false)
-            .groups(vec![
-                SettingGroup::new()
-                    .title(t!("settings.agents.group.configuration").to_string())
-                    .items(vec![
-                        SettingItem::new(
-                            t!("settings.agents.config.path.label").to_string(),
-                            SettingField::render({
-                                let view = view.clone();
-                                move |_options, _window, cx| {
-                                    let config_path = AppState::global(cx)
-                                        .agent_config_service()
-                                        .map(|s| s.config_path().to_string_lossy().to_string())
-                                        .unwrap_or_else(|| {
-                                            t!("settings.agents.config.path.not_configured")
-                                                .to_string()
-                                        });
-
-                                    v_flex()
-                                        .w_full()
-                                        .gap_2()
-                                        .child(
-                                            gpui::div()
-                                                .w_full()
-                                                .overflow_x_hidden()
-                                                .child(
-                                                    Label::new(config_path)
-                                                        .text_sm()
-                                                        .text_color(cx.theme().muted_foreground)
-                                                        .whitespace_nowrap()
-                                                )
-                                        )
-                                        .child(
-                                            h_flex()
-                                                .gap_2()
-                                                .child(
-                                                    Button::new("browse-config")
-                                                        .label(
-                                                            t!("settings.agents.config.path.browse")
-                                                                .to_string(),
-                                                        )
-                                                        .icon(IconName::Folder)
-                                                        .outline()
-                                                        .small()
-                                                        .on_click({
-                                                            let view = view.clone();
-                                                            move |_, window, cx| {
-                                                                view.update(cx, |this, cx| {
-                                                                    this.show_config_file_picker(window, cx);
-                                                                });
-                                                            }
-                                                        })
-                                                )
-                                                .child(
-                                                    Button::new("reload-config")
-                                                        .label(
-                                                            t!("settings.agents.config.path.reload")
-                                                                .to_string(),
-                                                        )
-                                                        .icon(IconName::LoaderCircle)
-                                                        .outline()
-                                                        .small()
-                                                        .on_click(move |_, window, cx| {
-                                                            window.dispatch_action(
-                                                                Box::new(ReloadAgentConfig),
-                                                                cx
-                                                            );
-                                                        })
-                                                )
-                                        )
-                                }
-                            }),
-                        )
-                        .description(
-                            t!("settings.agents.config.path.description").to_string(),
-                        ),
-                        SettingItem::new(
-                            t!("settings.agents.upload_dir.label").to_string(),
-                            SettingField::render({
-                                let view = view.clone();
-                                move |_options, _window, cx| {
-                                    let upload_dir = view.read(cx).cached_upload_dir.to_string_lossy().to_string();
-                                    let display = if upload_dir.is_empty() {
-                                        t!("settings.agents.upload_dir.not_configured").to_string()
-                                    } else {
-                                        upload_dir
-                                    };
-
-                                    gpui::div()
-                                        .w_full()
-                                        .min_w(px(0.))
-                                        .overflow_x_hidden()
-                                        .child(
-                                            Label::new(display)
-                                                .text_sm()
-                                                .text_color(cx.theme().muted_foreground)
-                                                .whitespace_nowrap()
-                                        )
-                                }
-                            }),
-                        )
-                        .description(
-                            t!("settings.agents.upload_dir.description").to_string(),
-                        ),
-                    ]),
-                SettingGroup::new()
-                    .title(t!("settings.agents.group.configured").to_string())
-                    .item(SettingItem::render({
+    pub fn agent_page(&self, view: &Entity<Self>, search: &SettingsSearch) -> Option<SettingPage> {
+        let title = t!("settings.agents.title").to_string();
+        let search = search.page_scope(&title);
+
+        search.page(
+            SettingPage::new(title).resettable(false),
+            [
+                search
+                    .group(t!("settings.agents.group.configuration").to_string())
+                    .item(
+                        t!("settings.agents.config.path.label").to_string(),
+                        t!("settings.agents.config.path.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::render({
+                                    let view = view.clone();
+                                    move |_options, _window, cx| {
+                                        let config_path = AppState::global(cx)
+                                            .agent_config_service()
+                                            .map(|s| s.config_path().to_string_lossy().to_string())
+                                            .unwrap_or_else(|| {
+                                                t!("settings.agents.config.path.not_configured")
+                                                    .to_string()
+                                            });
+
+                                        v_flex()
+                                            .w_full()
+                                            .gap_2()
+                                            .child(
+                                                gpui::div()
+                                                    .w_full()
+                                                    .overflow_x_hidden()
+                                                    .child(
+                                                        Label::new(config_path)
+                                                            .text_sm()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_nowrap()
+                                                    )
+                                            )
+                                            .child(
+                                                h_flex()
+                                                    .gap_2()
+                                                    .child(
+                                                        Button::new("browse-config")
+                                                            .label(
+                                                                t!("settings.agents.config.path.browse")
+                                                                    .to_string(),
+                                                            )
+                                                            .icon(IconName::Folder)
+                                                            .outline()
+                                                            .small()
+                                                            .on_click({
+                                                                let view = view.clone();
+                                                                move |_, window, cx| {
+                                                                    view.update(cx, |this, cx| {
+                                                                        this.show_config_file_picker(window, cx);
+                                                                    });
+                                                                }
+                                                            })
+                                                    )
+                                                    .child(
+                                                        Button::new("reload-config")
+                                                            .label(
+                                                                t!("settings.agents.config.path.reload")
+                                                                    .to_string(),
+                                                            )
+                                                            .icon(IconName::LoaderCircle)
+                                                            .outline()
+                                                            .small()
+                                                            .on_click(move |_, window, cx| {
+                                                                window.dispatch_action(
+                                                                    Box::new(ReloadAgentConfig),
+                                                                    cx
+                                                                );
+                                                            })
+                                                    )
+                                            )
+                                    }
+                                }),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.agents.upload_dir.label").to_string(),
+                        t!("settings.agents.upload_dir.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::render({
+                                    let view = view.clone();
+                                    move |_options, _window, cx| {
+                                        let upload_dir = view.read(cx).cached_upload_dir.to_string_lossy().to_string();
+                                        let display = if upload_dir.is_empty() {
+                                            t!("settings.agents.upload_dir.not_configured").to_string()
+                                        } else {
+                                            upload_dir
+                                        };
+
+                                        gpui::div()
+                                            .w_full()
+                                            .min_w(px(0.))
+                                            .overflow_x_hidden()
+                                            .child(
+                                                Label::new(display)
+                                                    .text_sm()
+                                                    .text_color(cx.theme().muted_foreground)
+                                                    .whitespace_nowrap()
+                                            )
+                                    }
+                                }),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .build(),
+                search
+                    .group(t!("settings.agents.group.configured").to_string())
+                    .render(SettingItem::render({
                         let view = view.clone();
                         move |_options, _window, cx| {
                             let agent_configs = view.read(cx).cached_agents.clone();
+                            let agent_statuses = view.read(cx).cached_agent_statuses.clone();
+                            let agent_names: Vec<String> = agent_configs.keys().cloned().collect();
+                            let all_enabled = agent_configs.values().all(|config| config.enabled);
+                            let all_disabled = agent_configs.values().all(|config| !config.enabled);
 
                             let mut content = v_flex()
                                 .w_full()
                                 .gap_3()
                                 .child(
-                                    // Add New Agent button
+                                    // Bulk toggles and Add New Agent button
                                     h_flex()
                                         .w_full()
                                         .justify_end()
+                                        .gap_2()
+                                        .child(
+                                            Button::new("enable-all-agents-btn")
+                                                .label(
+                                                    t!("settings.agents.button.enable_all").to_string(),
+                                                )
+                                                .outline()
+                                                .small()
+                                                .disabled(all_enabled)
+                                                .on_click({
+                                                    let names = agent_names.clone();
+                                                    move |_, window, cx| {
+                                                        window.dispatch_action(
+                                                            Box::new(SetAgentsEnabled {
+                                                                names: names.clone(),
+                                                                enabled: true,
+                                                            }),
+                                                            cx
+                                                        );
+                                                    }
+                                                })
+                                        )
+                                        .child(
+                                            Button::new("disable-all-agents-btn")
+                                                .label(
+                                                    t!("settings.agents.button.disable_all").to_string(),
+                                                )
+                                                .outline()
+                                                .small()
+                                                .disabled(all_disabled)
+                                                .on_click({
+                                                    let names = agent_names.clone();
+                                                    move |_, window, cx| {
+                                                        window.dispatch_action(
+                                                            Box::new(SetAgentsEnabled {
+                                                                names: names.clone(),
+                                                                enabled: false,
+                                                            }),
+                                                            cx
+                                                        );
+                                                    }
+                                                })
+                                        )
                                         .child(
                                             Button::new("add-agent-btn")
                                                 .label(
@@ -179,14 +238,36 @@ impl SettingsPanel {
                                     let name_for_restart = name.clone();
                                     let name_for_remove = name.clone();
 
-                                    let mut agent_info = v_flex()
-                                        .flex_1()
-                                        .gap_1()
+                                    let status = agent_statuses.get(name);
+
+                                    let mut name_row = h_flex()
+                                        .gap_2()
+                                        .items_center()
                                         .child(
                                             Label::new(name.clone())
                                                 .text_sm()
                                                 .font_weight(gpui::FontWeight::SEMIBOLD)
-                                        )
+                                        );
+                                    if !config.enabled {
+                                        name_row = name_row.child(
+                                            Label::new(t!("settings.agents.status.disabled").to_string())
+                                                .text_xs()
+                                                .text_color(cx.theme().muted_foreground)
+                                        );
+                                    } else if let Some(status) = status {
+                                        name_row = name_row
+                                            .child(StatusIndicator::for_agent(status))
+                                            .child(
+                                                Label::new(agent_status_label(status))
+                                                    .text_xs()
+                                                    .text_color(cx.theme().muted_foreground)
+                                            );
+                                    }
+
+                                    let mut agent_info = v_flex()
+                                        .flex_1()
+                                        .gap_1()
+                                        .child(name_row)
                                         .child(
                                             Label::new(
                                                 t!(
@@ -213,6 +294,14 @@ impl SettingsPanel {
                                         );
                                     }
 
+                                    if let Some(AgentRuntimeStatus::Failed { reason }) = status {
+                                        agent_info = agent_info.child(
+                                            Label::new(reason.clone())
+                                                .text_xs()
+                                                .text_color(cx.theme().danger)
+                                        );
+                                    }
+
                                     if !config.env.is_empty() {
                                         agent_info = agent_info.child(
                                             Label::new(
@@ -275,6 +364,7 @@ impl SettingsPanel {
                                                             .icon(IconName::LoaderCircle)
                                                             .outline()
                                                             .small()
+                                                            .disabled(!config.enabled)
                                                             .on_click(move |_, window, cx| {
                                                                 log::info!("Restart agent: {}", name_for_restart);
                                                                 window.dispatch_action(
@@ -314,8 +404,10 @@ impl SettingsPanel {
 
                             content
                         }
-                    })),
-            ])
+                    }))
+                    .build(),
+            ],
+        )
     }
 
     /// Show dialog to add or edit an agent
@@ -418,15 +510,16 @@ impl SettingsPanel {
                         let args: Vec<String> =
                             args_text.split_whitespace().map(String::from).collect();
 
-                        let mut env = HashMap::new();
-                        for line in env_text.lines() {
-                            if let Some((key, value)) = line.trim().split_once('=') {
-                                env.insert(key.trim().to_string(), value.trim().to_string());
-                            } else if !line.trim().is_empty() {
-                                log::warn!("Invalid env format (should be KEY=VALUE): {}", line);
+                        // Problems are listed under the env input
+                        let env = match AgentProcessConfig::validate_env(&env_text) {
+                            Ok(env) => env,
+                            Err(errors) => {
+                                for error in errors {
+                                    log::warn!("Invalid agent env: {}", error);
+                                }
                                 return false;
                             }
-                        }
+                        };
 
                         // Dispatch appropriate action
                         if is_edit {
@@ -513,6 +606,19 @@ impl SettingsPanel {
                                     Label::new(t!("settings.agents.field.env_help").to_string())
                                         .text_xs()
                                         .text_color(cx.theme().muted_foreground),
+                                )
+                                .children(
+                                    AgentProcessConfig::validate_env(
+                                        &env_input.read(cx).text().to_string(),
+                                    )
+                                    .err()
+                                    .unwrap_or_default()
+                                    .iter()
+                                    .map(|error| {
+                                        Label::new(env_error_message(error))
+                                            .text_xs()
+                                            .text_color(cx.theme().danger)
+                                    }),
                                 ),
                         ),
                 )
@@ -586,3 +692,38 @@ impl SettingsPanel {
         .detach();
     }
 }
+
+fn agent_status_label(status: &AgentRuntimeStatus) -> String {
+    match status {
+        AgentRuntimeStatus::Starting => t!("settings.agents.status.starting"),
+        AgentRuntimeStatus::Ready => t!("settings.agents.status.ready"),
+        AgentRuntimeStatus::Failed { .. } => t!("settings.agents.status.failed"),
+        AgentRuntimeStatus::Stopped => t!("settings.agents.status.stopped"),
+    }
+    .to_string()
+}
+
+/// Localized description of an invalid environment variable line
+fn env_error_message(error: &EnvVarError) -> String {
+    let line = error.line;
+    match &error.kind {
+        EnvVarErrorKind::MissingSeparator => {
+            t!("settings.agents.env_error.missing_separator", line = line)
+        }
+        EnvVarErrorKind::EmptyKey => t!("settings.agents.env_error.empty_key", line = line),
+        EnvVarErrorKind::InvalidKey(key) => {
+            t!(
+                "settings.agents.env_error.invalid_key",
+                line = line,
+                key = key
+            )
+        }
+        EnvVarErrorKind::DuplicateKey { key, first_line } => t!(
+            "settings.agents.env_error.duplicate_key",
+            line = line,
+            key = key,
+            first_line = first_line
+        ),
+    }
+    .to_string()
+}
@@ -1,4 +1,6 @@
-use gpui::{AppContext as _, Context, Entity, ParentElement as _, Styled, Window, px};
+use gpui::{
+    App, AppContext as _, Context, Entity, IntoElement, ParentElement as _, Styled, Window, div, px,
+};
 use gpui_component::{
     ActiveTheme, IconName, Sizable, WindowExt as _,
     button::Button,
@@ -6,22 +8,32 @@ use gpui_component::{
     h_flex,
     input::{Input, InputState},
     label::Label,
-    setting::{SettingGroup, SettingItem, SettingPage},
+    setting::{SettingItem, SettingPage},
     v_flex,
 };
 use rust_i18n::t;
 
 use super::panel::SettingsPanel;
-use crate::AppState;
+use super::search::SettingsSearch;
+use crate::{
+    AppState,
+    core::config::{TemplateVars, expand_template},
+};
 
 impl SettingsPanel {
-    pub fn command_page(&self, view: &Entity<Self>) -> SettingPage {
-        SettingPage::new(t!("settings.commands.title").to_string())
-            .resettable(false)
-            .groups(vec![
-            SettingGroup::new()
-                .title(t!("settings.commands.group.custom").to_string())
-                .item(SettingItem::render({
+    pub fn command_page(
+        &self,
+        view: &Entity<Self>,
+        search: &SettingsSearch,
+    ) -> Option<SettingPage> {
+        let title = t!("settings.commands.title").to_string();
+        let search = search.page_scope(&title);
+
+        search.page(
+            SettingPage::new(title).resettable(false),
+            [search
+                .group(t!("settings.commands.group.custom").to_string())
+                .render(SettingItem::render({
                     let view = view.clone();
                     move |_options, _window, cx| {
                         let command_configs = view.read(cx).cached_commands.clone();
@@ -45,14 +57,12 @@ impl SettingsPanel {
 
                         if command_configs.is_empty() {
                             content = content.child(
-                            h_flex().w_full().p_4().justify_center().child(
-                                Label::new(
-                                    t!("settings.commands.empty").to_string(),
-                                )
-                                .text_sm()
-                                .text_color(cx.theme().muted_foreground),
-                            ),
-                        );
+                                h_flex().w_full().p_4().justify_center().child(
+                                    Label::new(t!("settings.commands.empty").to_string())
+                                        .text_sm()
+                                        .text_color(cx.theme().muted_foreground),
+                                ),
+                            );
                         } else {
                             for (idx, (name, config)) in command_configs.iter().enumerate() {
                                 let name_for_edit = name.clone();
@@ -90,7 +100,10 @@ impl SettingsPanel {
                                                 .items_center()
                                                 .child(
                                                     Button::new(("edit-command-btn", idx))
-                                                        .label(t!("settings.commands.button.edit").to_string())
+                                                        .label(
+                                                            t!("settings.commands.button.edit")
+                                                                .to_string(),
+                                                        )
                                                         .icon(IconName::Settings)
                                                         .outline()
                                                         .small()
@@ -109,7 +122,10 @@ impl SettingsPanel {
                                                 )
                                                 .child(
                                                     Button::new(("delete-command-btn", idx))
-                                                        .label(t!("settings.commands.button.delete").to_string())
+                                                        .label(
+                                                            t!("settings.commands.button.delete")
+                                                                .to_string(),
+                                                        )
                                                         .icon(IconName::Delete)
                                                         .outline()
                                                         .small()
@@ -133,8 +149,9 @@ impl SettingsPanel {
 
                         content
                     }
-                })),
-        ])
+                }))
+                .build()],
+        )
     }
 
     pub fn show_add_command_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -151,7 +168,7 @@ impl SettingsPanel {
                 .placeholder(t!("settings.commands.input.template.placeholder").to_string())
         });
 
-        window.open_dialog(cx, move |dialog, _window, _cx| {
+        window.open_dialog(cx, move |dialog, _window, cx| {
             dialog
                 .title(t!("settings.commands.dialog.add.title").to_string())
                 .confirm()
@@ -179,6 +196,10 @@ impl SettingsPanel {
                             log::warn!("Name, description, and template cannot be empty");
                             return false;
                         }
+                        if let Err(e) = expand_template(&template, &TemplateVars::sample()) {
+                            log::warn!("Invalid command template: {}", e);
+                            return false;
+                        }
 
                         // Save to config file
                         if let Some(service) = AppState::global(cx).agent_config_service() {
@@ -231,7 +252,8 @@ impl SettingsPanel {
                                     t!("settings.commands.field.template").to_string(),
                                 ))
                                 .child(Input::new(&template_input)),
-                        ),
+                        )
+                        .child(template_preview(&template_input, cx)),
                 )
         });
     }
@@ -261,7 +283,7 @@ impl SettingsPanel {
             state
         });
 
-        window.open_dialog(cx, move |dialog, _window, _cx| {
+        window.open_dialog(cx, move |dialog, _window, cx| {
             dialog
                 .title(t!("settings.commands.dialog.edit.title", name = command_name).to_string())
                 .confirm()
@@ -289,6 +311,10 @@ impl SettingsPanel {
                             log::warn!("Description and template cannot be empty");
                             return false;
                         }
+                        if let Err(e) = expand_template(&template, &TemplateVars::sample()) {
+                            log::warn!("Invalid command template: {}", e);
+                            return false;
+                        }
 
                         // Save to config file
                         if let Some(service) = AppState::global(cx).agent_config_service() {
@@ -352,7 +378,8 @@ impl SettingsPanel {
                                     t!("settings.commands.field.template").to_string(),
                                 ))
                                 .child(Input::new(&template_input)),
-                        ),
+                        )
+                        .child(template_preview(&template_input, cx)),
                 )
         });
     }
@@ -405,3 +432,32 @@ impl SettingsPanel {
         });
     }
 }
+
+/// The template as it would be sent, expanded against sample variables, or
+/// the placeholder error that stops it from expanding
+fn template_preview(template_input: &Entity<InputState>, cx: &App) -> impl IntoElement {
+    let template = template_input.read(cx).text().to_string();
+    let (text, color) = match expand_template(template.trim(), &TemplateVars::sample()) {
+        Ok(expanded) => (expanded, cx.theme().muted_foreground),
+        Err(e) => (
+            t!("settings.commands.preview.error", error = e).to_string(),
+            cx.theme().danger,
+        ),
+    };
+
+    v_flex()
+        .gap_2()
+        .child(Label::new(
+            t!("settings.commands.field.preview").to_string(),
+        ))
+        .child(
+            div()
+                .w_full()
+                .p_2()
+                .rounded_md()
+                .bg(cx.theme().muted)
+                .text_sm()
+                .text_color(color)
+                .child(text),
+        )
+}
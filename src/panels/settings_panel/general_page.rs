@@ -4,250 +4,427 @@ use gpui_component::{
     button::Button,
     group_box::GroupBoxVariant,
     h_flex,
-    setting::{NumberFieldOptions, SettingField, SettingGroup, SettingItem, SettingPage},
+    setting::{NumberFieldOptions, SettingField, SettingItem, SettingPage},
 };
 use rust_i18n::t;
 
 use super::panel::SettingsPanel;
+use super::search::SettingsSearch;
 use super::types::AppSettings;
+use crate::{AppState, core::config::DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES};
 
 impl SettingsPanel {
-    pub fn general_page(&self, _view: &Entity<Self>, resettable: bool) -> SettingPage {
+    pub fn general_page(
+        &self,
+        _view: &Entity<Self>,
+        resettable: bool,
+        search: &SettingsSearch,
+    ) -> Option<SettingPage> {
         let default_settings = AppSettings::default();
 
-        SettingPage::new(t!("settings.general.title").to_string())
-            .resettable(resettable)
-            .default_open(true)
-            .groups(vec![
-                SettingGroup::new()
-                    .title(t!("settings.general.group.appearance").to_string())
-                    .items(vec![
-                        SettingItem::new(
-                            t!("settings.general.appearance.dark_mode.label").to_string(),
-                            SettingField::switch(
-                                |cx: &App| cx.theme().mode.is_dark(),
-                                |val: bool, cx: &mut App| {
-                                    let mode = if val {
-                                        ThemeMode::Dark
-                                    } else {
-                                        ThemeMode::Light
-                                    };
-                                    Theme::global_mut(cx).mode = mode;
-                                    Theme::change(mode, None, cx);
-                                },
-                            )
-                            .default_value(false),
-                        )
-                        .description(
-                            t!("settings.general.appearance.dark_mode.description").to_string(),
-                        ),
-                        SettingItem::new(
-                            t!("settings.general.appearance.auto_switch.label").to_string(),
-                            SettingField::checkbox(
-                                |cx: &App| AppSettings::global(cx).auto_switch_theme,
-                                |val: bool, cx: &mut App| {
-                                    AppSettings::global_mut(cx).auto_switch_theme = val;
-                                },
-                            )
-                            .default_value(default_settings.auto_switch_theme),
-                        )
-                        .description(
-                            t!("settings.general.appearance.auto_switch.description").to_string(),
-                        ),
-                        SettingItem::new(
-                            t!("settings.general.appearance.resettable.label").to_string(),
-                            SettingField::switch(
-                                |cx: &App| AppSettings::global(cx).resettable,
-                                |checked: bool, cx: &mut App| {
-                                    AppSettings::global_mut(cx).resettable = checked
-                                },
-                            ),
-                        )
-                        .description(
-                            t!("settings.general.appearance.resettable.description").to_string(),
-                        ),
-                        SettingItem::new(
-                            t!("settings.general.appearance.group_variant.label").to_string(),
-                            SettingField::dropdown(
-                                vec![
-                                    (
-                                        GroupBoxVariant::Normal.as_str().into(),
-                                        t!("settings.general.appearance.group_variant.normal")
-                                            .to_string()
-                                            .into(),
-                                    ),
-                                    (
-                                        GroupBoxVariant::Outline.as_str().into(),
-                                        t!("settings.general.appearance.group_variant.outline")
-                                            .to_string()
-                                            .into(),
-                                    ),
-                                    (
-                                        GroupBoxVariant::Fill.as_str().into(),
-                                        t!("settings.general.appearance.group_variant.fill")
-                                            .to_string()
-                                            .into(),
-                                    ),
-                                ],
-                                |cx: &App| AppSettings::global(cx).group_variant.clone(),
-                                |val: SharedString, cx: &mut App| {
-                                    AppSettings::global_mut(cx).group_variant = val;
-                                },
-                            )
-                            .default_value(default_settings.group_variant),
-                        )
-                        .description(
-                            t!("settings.general.appearance.group_variant.description").to_string(),
-                        ),
-                        SettingItem::new(
-                            t!("settings.general.appearance.group_size.label").to_string(),
-                            SettingField::dropdown(
-                                vec![
-                                    (
-                                        Size::Medium.as_str().into(),
-                                        t!("settings.general.appearance.group_size.medium")
-                                            .to_string()
-                                            .into(),
-                                    ),
-                                    (
-                                        Size::Small.as_str().into(),
-                                        t!("settings.general.appearance.group_size.small")
-                                            .to_string()
-                                            .into(),
-                                    ),
-                                    (
-                                        Size::XSmall.as_str().into(),
-                                        t!("settings.general.appearance.group_size.xsmall")
-                                            .to_string()
-                                            .into(),
-                                    ),
-                                ],
-                                |cx: &App| AppSettings::global(cx).size.clone(),
-                                |val: SharedString, cx: &mut App| {
-                                    AppSettings::global_mut(cx).size = val;
-                                },
-                            )
-                            .default_value(default_settings.size),
-                        )
-                        .description(
-                            t!("settings.general.appearance.group_size.description").to_string(),
-                        ),
-                    ]),
-                SettingGroup::new()
-                    .title(t!("settings.general.group.font").to_string())
+        let title = t!("settings.general.title").to_string();
+        let search = search.page_scope(&title);
+
+        search.page(
+            SettingPage::new(title)
+                .resettable(resettable)
+                .default_open(true),
+            [
+                search
+                    .group(t!("settings.general.group.appearance").to_string())
+                    .item(
+                        t!("settings.general.appearance.dark_mode.label").to_string(),
+                        t!("settings.general.appearance.dark_mode.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::switch(
+                                    |cx: &App| cx.theme().mode.is_dark(),
+                                    |val: bool, cx: &mut App| {
+                                        let mode = if val {
+                                            ThemeMode::Dark
+                                        } else {
+                                            ThemeMode::Light
+                                        };
+                                        Theme::global_mut(cx).mode = mode;
+                                        Theme::change(mode, None, cx);
+                                    },
+                                )
+                                .default_value(false),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.general.appearance.auto_switch.label").to_string(),
+                        t!("settings.general.appearance.auto_switch.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::checkbox(
+                                    |cx: &App| AppSettings::global(cx).auto_switch_theme,
+                                    |val: bool, cx: &mut App| {
+                                        AppSettings::global_mut(cx).auto_switch_theme = val;
+                                    },
+                                )
+                                .default_value(default_settings.auto_switch_theme),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.general.appearance.show_thoughts.label").to_string(),
+                        t!("settings.general.appearance.show_thoughts.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::switch(
+                                    |cx: &App| AppSettings::global(cx).show_agent_thoughts,
+                                    |val: bool, cx: &mut App| {
+                                        AppSettings::global_mut(cx).show_agent_thoughts = val;
+                                    },
+                                )
+                                .default_value(default_settings.show_agent_thoughts),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.general.appearance.resettable.label").to_string(),
+                        t!("settings.general.appearance.resettable.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::switch(
+                                    |cx: &App| AppSettings::global(cx).resettable,
+                                    |checked: bool, cx: &mut App| {
+                                        AppSettings::global_mut(cx).resettable = checked
+                                    },
+                                ),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.general.appearance.group_variant.label").to_string(),
+                        t!("settings.general.appearance.group_variant.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::dropdown(
+                                    vec![
+                                        (
+                                            GroupBoxVariant::Normal.as_str().into(),
+                                            t!("settings.general.appearance.group_variant.normal")
+                                                .to_string()
+                                                .into(),
+                                        ),
+                                        (
+                                            GroupBoxVariant::Outline.as_str().into(),
+                                            t!("settings.general.appearance.group_variant.outline")
+                                                .to_string()
+                                                .into(),
+                                        ),
+                                        (
+                                            GroupBoxVariant::Fill.as_str().into(),
+                                            t!("settings.general.appearance.group_variant.fill")
+                                                .to_string()
+                                                .into(),
+                                        ),
+                                    ],
+                                    |cx: &App| AppSettings::global(cx).group_variant.clone(),
+                                    |val: SharedString, cx: &mut App| {
+                                        AppSettings::global_mut(cx).group_variant = val;
+                                    },
+                                )
+                                .default_value(default_settings.group_variant),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.general.appearance.group_size.label").to_string(),
+                        t!("settings.general.appearance.group_size.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::dropdown(
+                                    vec![
+                                        (
+                                            Size::Medium.as_str().into(),
+                                            t!("settings.general.appearance.group_size.medium")
+                                                .to_string()
+                                                .into(),
+                                        ),
+                                        (
+                                            Size::Small.as_str().into(),
+                                            t!("settings.general.appearance.group_size.small")
+                                                .to_string()
+                                                .into(),
+                                        ),
+                                        (
+                                            Size::XSmall.as_str().into(),
+                                            t!("settings.general.appearance.group_size.xsmall")
+                                                .to_string()
+                                                .into(),
+                                        ),
+                                    ],
+                                    |cx: &App| AppSettings::global(cx).size.clone(),
+                                    |val: SharedString, cx: &mut App| {
+                                        AppSettings::global_mut(cx).size = val;
+                                    },
+                                )
+                                .default_value(default_settings.size),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .build(),
+                search
+                    .group(t!("settings.general.group.font").to_string())
+                    .item(
+                        t!("settings.general.font.family.label").to_string(),
+                        t!("settings.general.font.family.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::dropdown(
+                                    vec![
+                                        ("Arial".into(), "Arial".into()),
+                                        ("Helvetica".into(), "Helvetica".into()),
+                                        ("Times New Roman".into(), "Times New Roman".into()),
+                                        ("Courier New".into(), "Courier New".into()),
+                                    ],
+                                    |cx: &App| AppSettings::global(cx).font_family.clone(),
+                                    |val: SharedString, cx: &mut App| {
+                                        AppSettings::global_mut(cx).font_family = val;
+                                    },
+                                )
+                                .default_value(default_settings.font_family),
+                            )
+                            .description(description)
+                        },
+                    )
                     .item(
-                        SettingItem::new(
-                            t!("settings.general.font.family.label").to_string(),
-                            SettingField::dropdown(
-                                vec![
-                                    ("Arial".into(), "Arial".into()),
-                                    ("Helvetica".into(), "Helvetica".into()),
-                                    ("Times New Roman".into(), "Times New Roman".into()),
-                                    ("Courier New".into(), "Courier New".into()),
-                                ],
-                                |cx: &App| AppSettings::global(cx).font_family.clone(),
-                                |val: SharedString, cx: &mut App| {
-                                    AppSettings::global_mut(cx).font_family = val;
-                                },
-                            )
-                            .default_value(default_settings.font_family),
-                        )
-                        .description(t!("settings.general.font.family.description").to_string()),
+                        t!("settings.general.font.size.label").to_string(),
+                        t!("settings.general.font.size.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::number_input(
+                                    NumberFieldOptions {
+                                        min: 8.0,
+                                        max: 72.0,
+                                        ..Default::default()
+                                    },
+                                    |cx: &App| AppSettings::global(cx).font_size,
+                                    |val: f64, cx: &mut App| {
+                                        // Only update AppSettings - Theme will auto-sync
+                                        AppSettings::global_mut(cx).font_size = val;
+                                    },
+                                )
+                                .default_value(default_settings.font_size),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.general.font.line_height.label").to_string(),
+                        t!("settings.general.font.line_height.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::number_input(
+                                    NumberFieldOptions {
+                                        min: 8.0,
+                                        max: 32.0,
+                                        ..Default::default()
+                                    },
+                                    |cx: &App| AppSettings::global(cx).line_height,
+                                    |val: f64, cx: &mut App| {
+                                        AppSettings::global_mut(cx).line_height = val;
+                                    },
+                                )
+                                .default_value(default_settings.line_height),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .build(),
+                search
+                    .group(t!("settings.general.group.conversation").to_string())
+                    .item(
+                        t!("settings.general.conversation.tool_call_preview.label").to_string(),
+                        t!("settings.general.conversation.tool_call_preview.description")
+                            .to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::number_input(
+                                    NumberFieldOptions {
+                                        min: 0.0,
+                                        max: 500.0,
+                                        ..Default::default()
+                                    },
+                                    |cx: &App| {
+                                        AppState::global(cx).tool_call_preview_max_lines() as f64
+                                    },
+                                    |val: f64, cx: &mut App| {
+                                        let max_lines = val.max(0.0) as usize;
+                                        AppState::global_mut(cx)
+                                            .set_tool_call_preview_max_lines(max_lines);
+
+                                        if let Some(service) =
+                                            AppState::global(cx).agent_config_service()
+                                        {
+                                            let service = service.clone();
+                                            let _ = cx.spawn(async move |_cx| {
+                                                if let Err(err) = service
+                                                    .set_tool_call_preview_max_lines(max_lines)
+                                                    .await
+                                                {
+                                                    log::error!(
+                                                        "Failed to update tool call preview lines: {}",
+                                                        err
+                                                    );
+                                                }
+                                            });
+                                        }
+                                    },
+                                )
+                                .default_value(DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES as f64),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.general.conversation.auto_scroll.label").to_string(),
+                        t!("settings.general.conversation.auto_scroll.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::switch(
+                                    |cx: &App| AppSettings::global(cx).conversation_auto_scroll,
+                                    |val: bool, cx: &mut App| {
+                                        AppSettings::global_mut(cx).conversation_auto_scroll = val;
+                                    },
+                                )
+                                .default_value(default_settings.conversation_auto_scroll),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.general.conversation.auto_title.label").to_string(),
+                        t!("settings.general.conversation.auto_title.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::switch(
+                                    |cx: &App| AppSettings::global(cx).auto_title_sessions,
+                                    |val: bool, cx: &mut App| {
+                                        AppSettings::global_mut(cx).auto_title_sessions = val;
+                                    },
+                                )
+                                .default_value(default_settings.auto_title_sessions),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.general.conversation.permission_timeout.label").to_string(),
+                        t!("settings.general.conversation.permission_timeout.description")
+                            .to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::number_input(
+                                    NumberFieldOptions {
+                                        min: 0.0,
+                                        max: 3600.0,
+                                        ..Default::default()
+                                    },
+                                    |cx: &App| AppSettings::global(cx).permission_auto_timeout_secs,
+                                    |val: f64, cx: &mut App| {
+                                        AppSettings::global_mut(cx).permission_auto_timeout_secs =
+                                            val.max(0.0);
+                                    },
+                                )
+                                .default_value(default_settings.permission_auto_timeout_secs),
+                            )
+                            .description(description)
+                        },
                     )
+                    .build(),
+                search
+                    .group(t!("settings.general.group.other").to_string())
+                    .render(SettingItem::render(|options, _, _| {
+                        h_flex()
+                            .w_full()
+                            .justify_between()
+                            .flex_wrap()
+                            .gap_3()
+                            .child(t!("settings.general.other.custom_item").to_string())
+                            .child(
+                                Button::new("action")
+                                    .icon(IconName::Globe)
+                                    .label(
+                                        t!("settings.general.other.repository.button").to_string(),
+                                    )
+                                    .outline()
+                                    .with_size(options.size)
+                                    .on_click(|_, _, cx| {
+                                        cx.open_url("https://github.com/sxhxliang/agent-studio");
+                                    }),
+                            )
+                    }))
                     .item(
-                        SettingItem::new(
-                            t!("settings.general.font.size.label").to_string(),
-                            SettingField::number_input(
-                                NumberFieldOptions {
-                                    min: 8.0,
-                                    max: 72.0,
-                                    ..Default::default()
-                                },
-                                |cx: &App| AppSettings::global(cx).font_size,
-                                |val: f64, cx: &mut App| {
-                                    // Only update AppSettings - Theme will auto-sync
-                                    AppSettings::global_mut(cx).font_size = val;
-                                },
-                            )
-                            .default_value(default_settings.font_size),
-                        )
-                        .description(t!("settings.general.font.size.description").to_string()),
+                        t!("settings.general.other.cli_path.label").to_string(),
+                        t!("settings.general.other.cli_path.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::input(
+                                    |cx: &App| AppSettings::global(cx).cli_path.clone(),
+                                    |val: SharedString, cx: &mut App| {
+                                        println!("cli-path set value: {}", val);
+                                        AppSettings::global_mut(cx).cli_path = val;
+                                    },
+                                )
+                                .default_value(default_settings.cli_path),
+                            )
+                            .description(description)
+                            .layout(Axis::Vertical)
+                        },
                     )
                     .item(
-                        SettingItem::new(
-                            t!("settings.general.font.line_height.label").to_string(),
-                            SettingField::number_input(
-                                NumberFieldOptions {
-                                    min: 8.0,
-                                    max: 32.0,
-                                    ..Default::default()
-                                },
-                                |cx: &App| AppSettings::global(cx).line_height,
-                                |val: f64, cx: &mut App| {
-                                    AppSettings::global_mut(cx).line_height = val;
-                                },
-                            )
-                            .default_value(default_settings.line_height),
-                        )
-                        .description(
-                            t!("settings.general.font.line_height.description").to_string(),
-                        ),
-                    ),
-                SettingGroup::new()
-                    .title(t!("settings.general.group.other").to_string())
-                    .items(vec![
-                        SettingItem::render(|options, _, _| {
-                            h_flex()
-                                .w_full()
-                                .justify_between()
-                                .flex_wrap()
-                                .gap_3()
-                                .child(t!("settings.general.other.custom_item").to_string())
-                                .child(
-                                    Button::new("action")
-                                        .icon(IconName::Globe)
-                                        .label(
-                                            t!("settings.general.other.repository.button")
-                                                .to_string(),
-                                        )
-                                        .outline()
-                                        .with_size(options.size)
-                                        .on_click(|_, _, cx| {
-                                            cx.open_url(
-                                                "https://github.com/sxhxliang/agent-studio",
+                        t!("settings.general.other.nodejs_path.label").to_string(),
+                        t!("settings.general.other.nodejs_path.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::input(
+                                    |cx: &App| AppSettings::global(cx).nodejs_path.clone(),
+                                    |val: SharedString, cx: &mut App| {
+                                        log::info!("nodejs_path set to: {}", val);
+                                        if let Some(service) =
+                                            AppState::global(cx).agent_config_service()
+                                        {
+                                            service.set_nodejs_path(
+                                                Some(val.to_string()).filter(|p| !p.is_empty()),
                                             );
-                                        }),
+                                        }
+                                        AppSettings::global_mut(cx).nodejs_path = val;
+                                    },
                                 )
-                        }),
-                        SettingItem::new(
-                            t!("settings.general.other.cli_path.label").to_string(),
-                            SettingField::input(
-                                |cx: &App| AppSettings::global(cx).cli_path.clone(),
-                                |val: SharedString, cx: &mut App| {
-                                    println!("cli-path set value: {}", val);
-                                    AppSettings::global_mut(cx).cli_path = val;
-                                },
-                            )
-                            .default_value(default_settings.cli_path),
-                        )
-                        .layout(Axis::Vertical)
-                        .description(t!("settings.general.other.cli_path.description").to_string()),
-                        SettingItem::new(
-                            t!("settings.general.other.nodejs_path.label").to_string(),
-                            SettingField::input(
-                                |cx: &App| AppSettings::global(cx).nodejs_path.clone(),
-                                |val: SharedString, cx: &mut App| {
-                                    log::info!("nodejs_path set to: {}", val);
-                                    AppSettings::global_mut(cx).nodejs_path = val;
-                                },
-                            )
-                            .default_value(default_settings.nodejs_path),
-                        )
-                        .layout(Axis::Vertical)
-                        .description(
-                            t!("settings.general.other.nodejs_path.description").to_string(),
-                        ),
-                    ]),
-            ])
+                                .default_value(default_settings.nodejs_path),
+                            )
+                            .description(description)
+                            .layout(Axis::Vertical)
+                        },
+                    )
+                    .build(),
+            ],
+        )
     }
 }
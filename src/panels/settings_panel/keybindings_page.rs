@@ -0,0 +1,72 @@
+use gpui::{Keystroke, ParentElement as _, Styled};
+use gpui_component::{
+    h_flex,
+    kbd::Kbd,
+    setting::{SettingField, SettingItem, SettingPage},
+};
+use rust_i18n::t;
+
+use super::panel::SettingsPanel;
+use super::search::SettingsSearch;
+use crate::key_binding::ActiveKeyBinding;
+
+impl SettingsPanel {
+    pub fn keybindings_page(
+        &self,
+        bindings: &[ActiveKeyBinding],
+        search: &SettingsSearch,
+    ) -> Option<SettingPage> {
+        let title = t!("settings.keybindings.title").to_string();
+        let search = search.page_scope(&title);
+
+        let group = bindings.iter().fold(
+            search
+                .group(t!("settings.keybindings.group.shortcuts").to_string())
+                .description(t!("settings.keybindings.group.description").to_string()),
+            |group, binding| {
+                let mut description = binding.action.to_string();
+                if let Some(context) = binding.context {
+                    description.push_str(&format!(" ({})", context));
+                }
+                if binding.customized {
+                    description.push_str(&format!(" · {}", t!("settings.keybindings.custom")));
+                }
+
+                let keystrokes = binding.keystrokes.clone();
+                group.item(
+                    action_title(binding.action),
+                    description,
+                    move |title, description| {
+                        SettingItem::new(
+                            title,
+                            SettingField::render(move |_options, _window, _cx| {
+                                h_flex().gap_1().children(
+                                    keystrokes
+                                        .split_whitespace()
+                                        .filter_map(|keystroke| Keystroke::parse(keystroke).ok())
+                                        .map(Kbd::new),
+                                )
+                            }),
+                        )
+                        .description(description)
+                    },
+                )
+            },
+        );
+
+        search.page(SettingPage::new(title).default_open(false), [group.build()])
+    }
+}
+
+/// `agent_studio::ToggleCommandPalette` -> `Toggle Command Palette`
+fn action_title(action: &str) -> String {
+    let name = action.rsplit("::").next().unwrap_or(action);
+    let mut title = String::with_capacity(name.len() + 4);
+    for (ix, c) in name.chars().enumerate() {
+        if ix > 0 && c.is_uppercase() {
+            title.push(' ');
+        }
+        title.push(c);
+    }
+    title
+}
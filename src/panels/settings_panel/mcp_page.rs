@@ -6,7 +6,7 @@ use gpui_component::{
     h_flex,
     input::{Input, InputState, TabSize},
     label::Label,
-    setting::{SettingGroup, SettingItem, SettingPage},
+    setting::{SettingItem, SettingPage},
     tab::{Tab, TabBar},
     v_flex,
 };
@@ -14,52 +14,60 @@ use rust_i18n::t;
 use std::collections::HashMap;
 
 use super::panel::SettingsPanel;
+use super::search::SettingsSearch;
 use crate::{AppState, core::config::McpServerConfig};
 
 impl SettingsPanel {
-    pub fn mcp_page(&self, view: &Entity<Self>) -> SettingPage {
-        SettingPage::new(t!("settings.mcp.title").to_string())
-            .resettable(false)
-            .groups(vec![SettingGroup::new().item(SettingItem::render({
-                let view = view.clone();
-                move |_options, window, cx| {
-                    let active_tab = view.read(cx).mcp_active_tab;
-
-                    v_flex()
-                        .w_full()
-                        .gap_4()
-                        .child(
-                            TabBar::new("mcp-tabs")
-                                .w_full()
-                                .segmented()
-                                .selected_index(active_tab)
-                                .on_click({
-                                    let view = view.clone();
-                                    move |ix: &usize, _window, cx| {
-                                        view.update(cx, |this, cx| {
-                                            this.mcp_active_tab = *ix;
-                                            cx.notify();
-                                        });
-                                    }
-                                })
-                                .child(
-                                    Tab::new()
-                                        .flex_1()
-                                        .label(t!("settings.mcp.tab.interactive").to_string()),
-                                )
-                                .child(
-                                    Tab::new()
-                                        .flex_1()
-                                        .label(t!("settings.mcp.tab.json_editor").to_string()),
-                                ),
-                        )
-                        .child(if active_tab == 0 {
-                            Self::render_interactive_editor(&view, window, cx)
-                        } else {
-                            Self::render_json_editor(&view, window, cx)
-                        })
-                }
-            }))])
+    pub fn mcp_page(&self, view: &Entity<Self>, search: &SettingsSearch) -> Option<SettingPage> {
+        let title = t!("settings.mcp.title").to_string();
+        let search = search.page_scope(&title);
+
+        search.page(
+            SettingPage::new(title).resettable(false),
+            [search
+                .untitled_group()
+                .render(SettingItem::render({
+                    let view = view.clone();
+                    move |_options, window, cx| {
+                        let active_tab = view.read(cx).mcp_active_tab;
+
+                        v_flex()
+                            .w_full()
+                            .gap_4()
+                            .child(
+                                TabBar::new("mcp-tabs")
+                                    .w_full()
+                                    .segmented()
+                                    .selected_index(active_tab)
+                                    .on_click({
+                                        let view = view.clone();
+                                        move |ix: &usize, _window, cx| {
+                                            view.update(cx, |this, cx| {
+                                                this.mcp_active_tab = *ix;
+                                                cx.notify();
+                                            });
+                                        }
+                                    })
+                                    .child(
+                                        Tab::new()
+                                            .flex_1()
+                                            .label(t!("settings.mcp.tab.interactive").to_string()),
+                                    )
+                                    .child(
+                                        Tab::new()
+                                            .flex_1()
+                                            .label(t!("settings.mcp.tab.json_editor").to_string()),
+                                    ),
+                            )
+                            .child(if active_tab == 0 {
+                                Self::render_interactive_editor(&view, window, cx)
+                            } else {
+                                Self::render_json_editor(&view, window, cx)
+                            })
+                    }
+                }))
+                .build()],
+        )
     }
 
     fn render_interactive_editor(
@@ -2,13 +2,15 @@ mod about_page;
 mod agent_page;
 mod command_page;
 mod general_page;
+mod keybindings_page;
 mod mcp_page;
 mod model_page;
 mod network_page;
 mod panel;
 mod prompt_page;
+mod search;
 mod types;
 mod update_page;
 
 pub use panel::SettingsPanel;
-pub use types::AppSettings;
+pub use types::{AppSettings, SkippedSetupSteps};
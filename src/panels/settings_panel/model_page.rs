@@ -9,24 +9,28 @@ use gpui_component::{
     h_flex,
     input::{Input, InputState},
     label::Label,
-    setting::{SettingGroup, SettingItem, SettingPage},
+    setting::{SettingItem, SettingPage},
     v_flex,
 };
 use rust_i18n::t;
 
 use super::panel::SettingsPanel;
+use super::search::SettingsSearch;
 use crate::AppState;
 
 impl SettingsPanel {
-    pub fn model_page(&self, view: &Entity<Self>) -> SettingPage {
-        SettingPage::new(t!("settings.models.title").to_string())
-            .resettable(false)
-            .groups(vec![
+    pub fn model_page(&self, view: &Entity<Self>, search: &SettingsSearch) -> Option<SettingPage> {
+        let title = t!("settings.models.title").to_string();
+        let search = search.page_scope(&title);
+
+        search.page(
+            SettingPage::new(title).resettable(false),
+            [
                 // Default AI Model Selection
-                SettingGroup::new()
-                    .title(t!("settings.models.default.title").to_string())
+                search
+                    .group(t!("settings.models.default.title").to_string())
                     .description(t!("settings.models.default.description").to_string())
-                    .item(SettingItem::render({
+                    .render(SettingItem::render({
                         let view = view.clone();
                         move |_options, _window, cx| {
                             let model_configs = view.read(cx).cached_models.clone();
@@ -78,11 +82,12 @@ impl SettingsPanel {
 
                             v_flex().w_full().gap_2().child(options_flex)
                         }
-                    })),
+                    }))
+                    .build(),
                 // Model Providers List
-                SettingGroup::new()
-                    .title(t!("settings.models.providers.title").to_string())
-                    .item(SettingItem::render({
+                search
+                    .group(t!("settings.models.providers.title").to_string())
+                    .render(SettingItem::render({
                         let view = view.clone();
                         move |_options, _window, cx| {
                             let model_configs = view.read(cx).cached_models.clone();
@@ -240,8 +245,10 @@ impl SettingsPanel {
 
                             content
                         }
-                    })),
-            ])
+                    }))
+                    .build(),
+            ],
+        )
     }
 
     pub fn show_add_model_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -265,6 +272,14 @@ impl SettingsPanel {
             InputState::new(window, cx)
                 .placeholder(t!("settings.models.input.model_name.placeholder").to_string())
         });
+        let concurrency_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("settings.models.input.limit.placeholder").to_string())
+        });
+        let rpm_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("settings.models.input.limit.placeholder").to_string())
+        });
         let entity = cx.entity().downgrade();
 
         window.open_dialog(cx, move |dialog, _window, _cx| {
@@ -282,6 +297,8 @@ impl SettingsPanel {
                     let url_input = url_input.clone();
                     let key_input = key_input.clone();
                     let model_input = model_input.clone();
+                    let concurrency_input = concurrency_input.clone();
+                    let rpm_input = rpm_input.clone();
                     let entity = entity.clone();
 
                     move |_, _window, cx| {
@@ -300,6 +317,13 @@ impl SettingsPanel {
                             log::warn!("Name, provider, and URL cannot be empty");
                             return false;
                         }
+                        let (Ok(max_concurrent_requests), Ok(requests_per_minute)) = (
+                            parse_limit(&concurrency_input.read(cx).text().to_string()),
+                            parse_limit(&rpm_input.read(cx).text().to_string()),
+                        ) else {
+                            log::warn!("Request limits must be whole numbers");
+                            return false;
+                        };
 
                         // Save to config file
                         if let Some(service) = AppState::global(cx).agent_config_service() {
@@ -310,6 +334,8 @@ impl SettingsPanel {
                                 base_url: url,
                                 api_key: key,
                                 model_name: model,
+                                max_concurrent_requests,
+                                requests_per_minute,
                             };
                             let name_clone = name.clone();
                             let entity = entity.clone();
@@ -379,6 +405,29 @@ impl SettingsPanel {
                                     t!("settings.models.field.model_name_label").to_string(),
                                 ))
                                 .child(Input::new(&model_input)),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_3()
+                                .child(
+                                    v_flex()
+                                        .flex_1()
+                                        .gap_2()
+                                        .child(Label::new(
+                                            t!("settings.models.field.max_concurrent_label")
+                                                .to_string(),
+                                        ))
+                                        .child(Input::new(&concurrency_input)),
+                                )
+                                .child(
+                                    v_flex()
+                                        .flex_1()
+                                        .gap_2()
+                                        .child(Label::new(
+                                            t!("settings.models.field.rpm_label").to_string(),
+                                        ))
+                                        .child(Input::new(&rpm_input)),
+                                ),
                         ),
                 )
         });
@@ -415,6 +464,18 @@ impl SettingsPanel {
             state.set_value(config.model_name.clone(), window, cx);
             state
         });
+        let limit_input = |limit: Option<u32>, window: &mut Window, cx: &mut Context<Self>| {
+            cx.new(|cx| {
+                let mut state = InputState::new(window, cx)
+                    .placeholder(t!("settings.models.input.limit.placeholder").to_string());
+                if let Some(limit) = limit {
+                    state.set_value(limit.to_string(), window, cx);
+                }
+                state
+            })
+        };
+        let concurrency_input = limit_input(config.max_concurrent_requests, window, cx);
+        let rpm_input = limit_input(config.requests_per_minute, window, cx);
 
         let enabled = config.enabled;
 
@@ -432,6 +493,8 @@ impl SettingsPanel {
                     let url_input = url_input.clone();
                     let key_input = key_input.clone();
                     let model_input = model_input.clone();
+                    let concurrency_input = concurrency_input.clone();
+                    let rpm_input = rpm_input.clone();
                     let model_name = model_name.clone();
 
                     move |_, _window, cx| {
@@ -448,6 +511,13 @@ impl SettingsPanel {
                             log::warn!("Provider and URL cannot be empty");
                             return false;
                         }
+                        let (Ok(max_concurrent_requests), Ok(requests_per_minute)) = (
+                            parse_limit(&concurrency_input.read(cx).text().to_string()),
+                            parse_limit(&rpm_input.read(cx).text().to_string()),
+                        ) else {
+                            log::warn!("Request limits must be whole numbers");
+                            return false;
+                        };
 
                         if let Some(service) = AppState::global(cx).agent_config_service() {
                             let service = service.clone();
@@ -458,6 +528,8 @@ impl SettingsPanel {
                                 base_url: url.to_string(),
                                 api_key: key.to_string(),
                                 model_name: model.to_string(),
+                                max_concurrent_requests,
+                                requests_per_minute,
                             };
 
                             cx.spawn(async move |cx| {
@@ -508,6 +580,29 @@ impl SettingsPanel {
                                     t!("settings.models.field.model_name_label").to_string(),
                                 ))
                                 .child(Input::new(&model_input)),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_3()
+                                .child(
+                                    v_flex()
+                                        .flex_1()
+                                        .gap_2()
+                                        .child(Label::new(
+                                            t!("settings.models.field.max_concurrent_label")
+                                                .to_string(),
+                                        ))
+                                        .child(Input::new(&concurrency_input)),
+                                )
+                                .child(
+                                    v_flex()
+                                        .flex_1()
+                                        .gap_2()
+                                        .child(Label::new(
+                                            t!("settings.models.field.rpm_label").to_string(),
+                                        ))
+                                        .child(Input::new(&rpm_input)),
+                                ),
                         ),
                 )
         });
@@ -571,3 +666,13 @@ impl SettingsPanel {
         cx.notify();
     }
 }
+
+/// Parse an optional request limit; empty means unlimited
+fn parse_limit(text: &str) -> Result<Option<u32>, std::num::ParseIntError> {
+    let text = text.trim();
+    if text.is_empty() {
+        Ok(None)
+    } else {
+        text.parse().map(Some)
+    }
+}
@@ -1,20 +1,30 @@
 use gpui::{App, Entity, SharedString};
-use gpui_component::setting::{SettingField, SettingGroup, SettingItem, SettingPage};
+use gpui_component::setting::{SettingField, SettingItem, SettingPage};
 use rust_i18n::t;
 
 use super::panel::SettingsPanel;
+use super::search::SettingsSearch;
 use crate::AppState;
 
 impl SettingsPanel {
-    pub fn network_page(&self, view: &Entity<Self>) -> SettingPage {
-        SettingPage::new(t!("settings.network.title").to_string())
-            .default_open(false)
-            .groups(vec![
-                SettingGroup::new()
-                    .title(t!("settings.network.group.proxy").to_string())
-                    .items(vec![
+    pub fn network_page(
+        &self,
+        view: &Entity<Self>,
+        search: &SettingsSearch,
+    ) -> Option<SettingPage> {
+        let title = t!("settings.network.title").to_string();
+        let search = search.page_scope(&title);
+
+        search.page(
+            SettingPage::new(title).default_open(false),
+            [search
+                .group(t!("settings.network.group.proxy").to_string())
+                .item(
+                    t!("settings.network.proxy.enable.label").to_string(),
+                    t!("settings.network.proxy.enable.description").to_string(),
+                    |title, description| {
                         SettingItem::new(
-                            t!("settings.network.proxy.enable.label").to_string(),
+                            title,
                             SettingField::switch(
                                 {
                                     let view = view.clone();
@@ -49,9 +59,15 @@ impl SettingsPanel {
                             )
                             .default_value(false),
                         )
-                        .description(t!("settings.network.proxy.enable.description").to_string()),
+                        .description(description)
+                    },
+                )
+                .item(
+                    t!("settings.network.proxy.http.label").to_string(),
+                    t!("settings.network.proxy.http.description").to_string(),
+                    |title, description| {
                         SettingItem::new(
-                            t!("settings.network.proxy.http.label").to_string(),
+                            title,
                             SettingField::input(
                                 {
                                     let view = view.clone();
@@ -90,9 +106,15 @@ impl SettingsPanel {
                             )
                             .default_value(SharedString::from("")),
                         )
-                        .description(t!("settings.network.proxy.http.description").to_string()),
+                        .description(description)
+                    },
+                )
+                .item(
+                    t!("settings.network.proxy.https.label").to_string(),
+                    t!("settings.network.proxy.https.description").to_string(),
+                    |title, description| {
                         SettingItem::new(
-                            t!("settings.network.proxy.https.label").to_string(),
+                            title,
                             SettingField::input(
                                 {
                                     let view = view.clone();
@@ -131,9 +153,15 @@ impl SettingsPanel {
                             )
                             .default_value(SharedString::from("")),
                         )
-                        .description(t!("settings.network.proxy.https.description").to_string()),
+                        .description(description)
+                    },
+                )
+                .item(
+                    t!("settings.network.proxy.all.label").to_string(),
+                    t!("settings.network.proxy.all.description").to_string(),
+                    |title, description| {
                         SettingItem::new(
-                            t!("settings.network.proxy.all.label").to_string(),
+                            title,
                             SettingField::input(
                                 {
                                     let view = view.clone();
@@ -172,8 +200,10 @@ impl SettingsPanel {
                             )
                             .default_value(SharedString::from("")),
                         )
-                        .description(t!("settings.network.proxy.all.description").to_string()),
-                    ]),
-            ])
+                        .description(description)
+                    },
+                )
+                .build()],
+        )
     }
 }
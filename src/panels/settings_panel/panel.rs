@@ -1,9 +1,14 @@
+use agentx_types::AgentRuntimeStatus;
 use gpui::{
-    App, AppContext as _, Context, Entity, FocusHandle, Focusable, IntoElement, Render, Window, px,
+    App, AppContext as _, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement,
+    Render, ScrollHandle, Styled, Subscription, Window, div, px,
 };
 use gpui_component::{
-    input::InputState,
+    ActiveTheme, Icon, IconName, WindowExt,
+    input::{Input, InputEvent, InputState},
+    notification::Notification,
     setting::{SettingPage, Settings},
+    v_flex,
 };
 use rust_i18n::t;
 use std::{collections::HashMap, path::PathBuf};
@@ -14,16 +19,22 @@ use crate::{
         config::{AgentProcessConfig, CommandConfig, McpServerConfig, ModelConfig},
         updater::UpdateManager,
     },
+    key_binding::ActiveKeyBindings,
 };
 
-use super::types::{AppSettings, UpdateStatus};
+use super::{
+    search::SettingsSearch,
+    types::{AppSettings, UpdateStatus},
+};
 
 pub struct SettingsPanel {
     pub(super) focus_handle: FocusHandle,
     pub(super) update_status: UpdateStatus,
     pub(super) update_manager: UpdateManager,
+    pub(super) release_notes_scroll: ScrollHandle,
     // Cached configuration state (synchronized by events)
     pub(super) cached_agents: HashMap<String, AgentProcessConfig>,
+    pub(super) cached_agent_statuses: HashMap<String, AgentRuntimeStatus>,
     pub(super) cached_models: HashMap<String, ModelConfig>,
     pub(super) cached_mcp_servers: HashMap<String, McpServerConfig>,
     pub(super) cached_commands: HashMap<String, CommandConfig>,
@@ -38,6 +49,10 @@ pub struct SettingsPanel {
     pub(super) inline_comment_input: Entity<InputState>,
     pub(super) explain_input: Entity<InputState>,
     pub(super) improve_input: Entity<InputState>,
+    // Search box filtering the visible settings
+    search_input: Entity<InputState>,
+    search_query: String,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl crate::panels::dock_panel::DockPanel for SettingsPanel {
@@ -89,11 +104,27 @@ impl SettingsPanel {
         let explain_input = cx.new(|cx| InputState::new(window, cx));
         let improve_input = cx.new(|cx| InputState::new(window, cx));
 
+        let search_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(t!("settings.search.placeholder").to_string())
+        });
+        let _subscriptions = vec![cx.subscribe_in(
+            &search_input,
+            window,
+            |this, input, event: &InputEvent, _window, cx| {
+                if let InputEvent::Change = event {
+                    this.search_query = input.read(cx).value().to_string();
+                    cx.notify();
+                }
+            },
+        )];
+
         let panel = Self {
             focus_handle: cx.focus_handle(),
             update_status: UpdateStatus::Idle,
             update_manager: UpdateManager::default(),
+            release_notes_scroll: ScrollHandle::new(),
             cached_agents: HashMap::new(),
+            cached_agent_statuses: HashMap::new(),
             cached_models: HashMap::new(),
             cached_mcp_servers: HashMap::new(),
             cached_commands: HashMap::new(),
@@ -106,14 +137,22 @@ impl SettingsPanel {
             inline_comment_input,
             explain_input,
             improve_input,
+            search_input,
+            search_query: String::new(),
+            _subscriptions,
         };
 
         // Load all configuration from service asynchronously
         let weak_entity = cx.entity().downgrade();
         if let Some(service) = AppState::global(cx).agent_config_service() {
             let service = service.clone();
+            let agent_manager = AppState::global(cx).agent_manager().cloned();
             cx.spawn_in(window, async move |_this, window| {
                 let agents = service.list_agents().await;
+                let agent_statuses = match agent_manager {
+                    Some(manager) => manager.agent_statuses().await,
+                    None => HashMap::new(),
+                };
                 let models = service.list_models().await;
                 let mcp_servers = service.list_mcp_servers().await;
                 let commands = service.list_commands().await;
@@ -124,6 +163,10 @@ impl SettingsPanel {
                     if let Some(entity) = weak_entity.upgrade() {
                         entity.update(cx, |this, cx| {
                             this.cached_agents = agents.into_iter().collect();
+                            // Keep statuses that arrived over the bus while loading
+                            for (name, status) in agent_statuses {
+                                this.cached_agent_statuses.entry(name).or_insert(status);
+                            }
                             this.cached_models = models.into_iter().collect();
                             this.cached_mcp_servers = mcp_servers.into_iter().collect();
                             this.cached_commands = commands.into_iter().collect();
@@ -151,9 +194,9 @@ impl SettingsPanel {
         cx.spawn_in(window, async move |_this, window| {
             while let Some(event) = rx.recv().await {
                 if let Some(entity) = weak_entity.upgrade() {
-                    _ = window.update(|_window, cx| {
+                    _ = window.update(|window, cx| {
                         entity.update(cx, |this, cx| {
-                            this.on_agent_config_event(&event, cx);
+                            this.on_agent_config_event(&event, window, cx);
                         });
                     });
                 } else {
@@ -170,9 +213,10 @@ impl SettingsPanel {
     fn on_agent_config_event(
         &mut self,
         event: &crate::core::event_bus::AgentConfigEvent,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        use crate::core::event_bus::AgentConfigEvent;
+        use crate::core::event_bus::{AgentConfigEvent, ConfigSection};
 
         log::info!("[SettingsPanel] Processing config event: {:?}", event);
 
@@ -187,6 +231,11 @@ impl SettingsPanel {
             }
             AgentConfigEvent::AgentRemoved { name } => {
                 self.cached_agents.remove(name);
+                self.cached_agent_statuses.remove(name);
+            }
+            AgentConfigEvent::AgentStatusChanged { name, status } => {
+                self.cached_agent_statuses
+                    .insert(name.clone(), status.clone());
             }
 
             // Model events
@@ -231,6 +280,25 @@ impl SettingsPanel {
                 self.cached_upload_dir = config.upload_dir.clone();
                 self.cached_proxy = config.proxy.clone();
             }
+
+            // The edit is still applied in memory; only the write failed
+            AgentConfigEvent::SaveFailed { section, error } => {
+                let section = match section {
+                    ConfigSection::General => t!("settings.general.title"),
+                    ConfigSection::Network => t!("settings.network.title"),
+                    ConfigSection::Agents => t!("settings.agents.title"),
+                    ConfigSection::Models => t!("settings.models.title"),
+                    ConfigSection::McpServers => t!("settings.mcp.title"),
+                    ConfigSection::Commands => t!("settings.commands.title"),
+                    ConfigSection::Prompts => t!("settings.prompts.title"),
+                };
+                window.push_notification(
+                    Notification::error(
+                        t!("settings.save_failed", section = section, error = error).to_string(),
+                    ),
+                    cx,
+                );
+            }
         }
 
         // Trigger re-render
@@ -240,18 +308,28 @@ impl SettingsPanel {
     fn setting_pages(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> Vec<SettingPage> {
         let view = cx.entity();
         let resettable = AppSettings::global(cx).resettable;
-
-        vec![
-            self.general_page(&view, resettable),
-            self.network_page(&view),
-            self.update_page(&view, resettable),
-            self.agent_page(&view),
-            self.model_page(&view),
-            self.prompt_page(&view),
-            self.mcp_page(&view),
-            self.command_page(&view),
-            super::about_page::about_page(resettable),
+        let search = SettingsSearch::new(&self.search_query);
+
+        [
+            self.general_page(&view, resettable, &search),
+            self.network_page(&view, &search),
+            self.update_page(&view, resettable, &search),
+            self.agent_page(&view, &search),
+            self.model_page(&view, &search),
+            self.prompt_page(&view, &search),
+            self.mcp_page(&view, &search),
+            self.command_page(&view, &search),
+            self.keybindings_page(
+                &cx.try_global::<ActiveKeyBindings>()
+                    .map(|bindings| bindings.0.clone())
+                    .unwrap_or_default(),
+                &search,
+            ),
+            super::about_page::about_page(resettable, &search),
         ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
@@ -269,9 +347,45 @@ impl Render for SettingsPanel {
         let size = Size::from_str(app_settings.size.as_str());
         let group_variant = GroupBoxVariant::from_str(app_settings.group_variant.as_str());
 
-        Settings::new("app-settings")
-            .with_size(size)
-            .with_group_variant(group_variant)
-            .pages(self.setting_pages(window, cx))
+        let pages = self.setting_pages(window, cx);
+
+        let search_bar = div()
+            .p_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                Input::new(&self.search_input)
+                    .prefix(
+                        Icon::new(IconName::Search)
+                            .size_4()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .cleanable(true)
+                    .with_size(size),
+            );
+
+        let content = if pages.is_empty() {
+            div()
+                .flex_1()
+                .p_4()
+                .text_sm()
+                .text_color(cx.theme().muted_foreground)
+                .child(
+                    t!(
+                        "settings.search.no_results",
+                        query = self.search_query.trim()
+                    )
+                    .to_string(),
+                )
+                .into_any_element()
+        } else {
+            Settings::new("app-settings")
+                .with_size(size)
+                .with_group_variant(group_variant)
+                .pages(pages)
+                .into_any_element()
+        };
+
+        v_flex().size_full().child(search_bar).child(content)
     }
 }
@@ -5,24 +5,28 @@ use gpui_component::{
     h_flex,
     input::Input,
     label::Label,
-    setting::{SettingGroup, SettingItem, SettingPage},
+    setting::{SettingItem, SettingPage},
     v_flex,
 };
 use rust_i18n::t;
 
 use super::panel::SettingsPanel;
+use super::search::SettingsSearch;
 use crate::AppState;
 
 impl SettingsPanel {
-    pub fn prompt_page(&self, view: &Entity<Self>) -> SettingPage {
-        SettingPage::new(t!("settings.prompts.title").to_string())
-            .resettable(false)
-            .groups(vec![
+    pub fn prompt_page(&self, view: &Entity<Self>, search: &SettingsSearch) -> Option<SettingPage> {
+        let title = t!("settings.prompts.title").to_string();
+        let search = search.page_scope(&title);
+
+        search.page(
+            SettingPage::new(title).resettable(false),
+            [
                 // Default AI Model Selection
-                SettingGroup::new()
-                    .title(t!("settings.prompts.default.title").to_string())
+                search
+                    .group(t!("settings.prompts.default.title").to_string())
                     .description(t!("settings.prompts.default.description").to_string())
-                    .item(SettingItem::render({
+                    .render(SettingItem::render({
                         let view = view.clone();
                         move |_options, _window, cx| {
                             let model_configs = view.read(cx).cached_models.clone();
@@ -74,12 +78,13 @@ impl SettingsPanel {
 
                             v_flex().w_full().gap_2().child(options_flex)
                         }
-                    })),
+                    }))
+                    .build(),
                 // System Prompts Configuration
-                SettingGroup::new()
-                    .title(t!("settings.prompts.system.title").to_string())
+                search
+                    .group(t!("settings.prompts.system.title").to_string())
                     .description(t!("settings.prompts.system.description").to_string())
-                    .item(SettingItem::render({
+                    .render(SettingItem::render({
                         let view = view.clone();
                         move |_options, _window, cx| {
                             // Read current prompt values from panel's InputStates
@@ -194,8 +199,10 @@ impl SettingsPanel {
                                     ),
                                 )
                         }
-                    })),
-            ])
+                    }))
+                    .build(),
+            ],
+        )
     }
 
     pub fn save_system_prompts(&mut self, cx: &mut Context<Self>) {
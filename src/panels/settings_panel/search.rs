@@ -0,0 +1,160 @@
+use gpui_component::setting::{SettingGroup, SettingItem, SettingPage};
+
+/// Query typed into the settings search box.
+///
+/// Every whitespace-separated term must appear (case-insensitively) in an
+/// item's title or description. A matching page or group title keeps all of
+/// its items.
+#[derive(Clone, Debug, Default)]
+pub(super) struct SettingsSearch {
+    terms: Vec<String>,
+}
+
+impl SettingsSearch {
+    pub fn new(query: &str) -> Self {
+        Self {
+            terms: query.split_whitespace().map(str::to_lowercase).collect(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.terms.is_empty()
+    }
+
+    /// Whether every term appears in at least one of `texts`
+    pub fn matches(&self, texts: &[&str]) -> bool {
+        let texts = texts.iter().map(|t| t.to_lowercase()).collect::<Vec<_>>();
+        self.terms
+            .iter()
+            .all(|term| texts.iter().any(|text| text.contains(term.as_str())))
+    }
+
+    /// Search scoped to a page; a page whose title matches shows everything
+    pub fn page_scope(&self, title: &str) -> Self {
+        if self.matches(&[title]) {
+            Self::default()
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Start a titled group
+    pub fn group(&self, title: String) -> SearchGroup {
+        SearchGroup {
+            keep_all: !self.is_active() || self.matches(&[&title]),
+            search: self.clone(),
+            group: SettingGroup::new().title(title),
+            items: Vec::new(),
+        }
+    }
+
+    /// Start a group without a title
+    pub fn untitled_group(&self) -> SearchGroup {
+        SearchGroup {
+            keep_all: !self.is_active(),
+            search: self.clone(),
+            group: SettingGroup::new(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Finish a page, dropping it when none of its groups survived. Pages with
+    /// matches open automatically while searching.
+    pub fn page(
+        &self,
+        page: SettingPage,
+        groups: impl IntoIterator<Item = Option<SettingGroup>>,
+    ) -> Option<SettingPage> {
+        let groups = groups.into_iter().flatten().collect::<Vec<_>>();
+        if groups.is_empty() {
+            return None;
+        }
+        let page = if self.is_active() {
+            page.default_open(true)
+        } else {
+            page
+        };
+        Some(page.groups(groups))
+    }
+}
+
+/// A [`SettingGroup`] under construction that only keeps matching items
+pub(super) struct SearchGroup {
+    search: SettingsSearch,
+    group: SettingGroup,
+    keep_all: bool,
+    items: Vec<SettingItem>,
+}
+
+impl SearchGroup {
+    pub fn description(mut self, description: String) -> Self {
+        self.keep_all = self.keep_all || self.search.matches(&[&description]);
+        self.group = self.group.description(description);
+        self
+    }
+
+    /// Add an item found by its title and description
+    pub fn item(
+        mut self,
+        title: String,
+        description: String,
+        build: impl FnOnce(String, String) -> SettingItem,
+    ) -> Self {
+        if self.keep_all || self.search.matches(&[&title, &description]) {
+            self.items.push(build(title, description));
+        }
+        self
+    }
+
+    /// Add a custom-rendered item, which has no text of its own and is only
+    /// shown when the group itself matches
+    pub fn render(mut self, item: SettingItem) -> Self {
+        if self.keep_all {
+            self.items.push(item);
+        }
+        self
+    }
+
+    pub fn build(self) -> Option<SettingGroup> {
+        (!self.items.is_empty()).then(|| self.group.items(self.items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEMS: &[(&str, &str)] = &[
+        ("Dark Mode", "Switch between light and dark themes"),
+        ("Font Size", "Adjust the editor font size"),
+        ("HTTP Proxy", "Proxy used for HTTP requests"),
+        ("Auto Update", "Install updates automatically"),
+    ];
+
+    fn surviving(query: &str) -> Vec<&'static str> {
+        let search = SettingsSearch::new(query);
+        ITEMS
+            .iter()
+            .filter(|(title, description)| search.matches(&[title, description]))
+            .map(|(title, _)| *title)
+            .collect()
+    }
+
+    #[test]
+    fn filters_items_by_title_and_description() {
+        assert_eq!(surviving("font"), vec!["Font Size"]);
+        assert_eq!(surviving("THEMES"), vec!["Dark Mode"]);
+        assert_eq!(surviving("http"), vec!["HTTP Proxy"]);
+        // Every term has to match
+        assert_eq!(surviving("update auto"), vec!["Auto Update"]);
+        assert!(surviving("bluetooth").is_empty());
+        assert_eq!(surviving("  ").len(), ITEMS.len());
+    }
+
+    #[test]
+    fn matching_page_title_keeps_everything() {
+        let search = SettingsSearch::new("network");
+        assert!(!search.page_scope("Network").is_active());
+        assert!(search.page_scope("General").is_active());
+    }
+}
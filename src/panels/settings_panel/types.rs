@@ -6,6 +6,8 @@ use gpui_component::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::core::updater::UpdateChannel;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub auto_switch_theme: bool,
@@ -21,9 +23,33 @@ pub struct AppSettings {
     pub auto_update: bool,
     pub auto_check_on_startup: bool,
     pub check_frequency_days: f64,
+    #[serde(default = "default_update_channel")]
+    pub update_channel: SharedString,
     pub resettable: bool,
     pub group_variant: SharedString,
     pub size: SharedString,
+    #[serde(default = "default_show_agent_thoughts")]
+    pub show_agent_thoughts: bool,
+    #[serde(default = "default_conversation_auto_scroll")]
+    pub conversation_auto_scroll: bool,
+    #[serde(default)]
+    pub auto_title_sessions: bool,
+    /// Seconds before an unanswered permission request is rejected; 0 waits
+    #[serde(default)]
+    pub permission_auto_timeout_secs: f64,
+    #[serde(default)]
+    pub setup_completed: bool,
+    #[serde(default)]
+    pub skipped_setup_steps: SkippedSetupSteps,
+}
+
+/// Startup wizard steps the user chose to skip
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkippedSetupSteps {
+    pub nodejs: bool,
+    pub agents: bool,
+    pub proxy: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -49,15 +75,34 @@ impl Default for AppSettings {
             auto_update: true,
             auto_check_on_startup: true,
             check_frequency_days: 7.0,
+            update_channel: default_update_channel(),
             resettable: true,
             group_variant: "Fill".into(),
             size: "Small".into(),
+            show_agent_thoughts: default_show_agent_thoughts(),
+            conversation_auto_scroll: default_conversation_auto_scroll(),
+            auto_title_sessions: false,
+            permission_auto_timeout_secs: 0.0,
+            setup_completed: false,
+            skipped_setup_steps: SkippedSetupSteps::default(),
         }
     }
 }
 
 impl Global for AppSettings {}
 
+fn default_update_channel() -> SharedString {
+    UpdateChannel::default().as_str().into()
+}
+
+fn default_show_agent_thoughts() -> bool {
+    true
+}
+
+fn default_conversation_auto_scroll() -> bool {
+    true
+}
+
 fn default_locale() -> SharedString {
     detect_system_locale().unwrap_or_else(|| "en".into())
 }
@@ -86,6 +131,10 @@ impl AppSettings {
     pub fn global_mut(cx: &mut App) -> &mut AppSettings {
         cx.global_mut::<AppSettings>()
     }
+
+    pub fn update_channel(&self) -> UpdateChannel {
+        UpdateChannel::from_str_or_default(&self.update_channel)
+    }
 }
 
 pub struct OpenURLSettingField {
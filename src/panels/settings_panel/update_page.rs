@@ -1,17 +1,27 @@
-use gpui::{App, Context, Entity, ParentElement as _, Styled, Window};
+use gpui::{
+    App, Context, Entity, ParentElement as _, SharedString, Styled, Window, div,
+    prelude::FluentBuilder as _, px,
+};
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
     button::Button,
     h_flex,
     label::Label,
-    setting::{NumberFieldOptions, SettingField, SettingGroup, SettingItem, SettingPage},
+    setting::{NumberFieldOptions, SettingField, SettingItem, SettingPage},
+    text::TextView,
     v_flex,
 };
 use rust_i18n::t;
 
 use super::panel::SettingsPanel;
+use super::search::SettingsSearch;
 use super::types::{AppSettings, UpdateStatus};
-use crate::core::updater::{UpdateCheckResult, Version};
+use crate::components::BoundedScroll;
+use crate::core::updater::{UpdateChannel, UpdateCheckResult, Version};
+
+/// Release notes can be long; keep them in a scrollable region so they don't
+/// stretch the settings page.
+const RELEASE_NOTES_MAX_HEIGHT: f32 = 240.;
 
 fn os_display_name() -> String {
     match std::env::consts::OS {
@@ -79,138 +89,165 @@ fn os_version() -> String {
 }
 
 impl SettingsPanel {
-    pub fn update_page(&self, view: &Entity<Self>, resettable: bool) -> SettingPage {
+    pub fn update_page(
+        &self,
+        view: &Entity<Self>,
+        resettable: bool,
+        search: &SettingsSearch,
+    ) -> Option<SettingPage> {
         let default_settings = AppSettings::default();
 
-        SettingPage::new(t!("settings.update.title").to_string())
-            .resettable(resettable)
-            .groups(vec![
-                SettingGroup::new()
-                    .title(t!("settings.update.group.version").to_string())
-                    .items(vec![
-                        SettingItem::render({
-                            let current_version = Version::current().to_string();
-                            let update_status = self.update_status.clone();
-                            move |_options, _window, cx| {
-                                v_flex()
-                                    .gap_2()
-                                    .w_full()
-                                    .child(
-                                        h_flex()
-                                            .gap_2()
-                                            .items_center()
-                                            .child(
-                                                Label::new(
-                                                    t!("settings.update.current_version.label")
-                                                        .to_string(),
-                                                )
-                                                .text_sm(),
+        let title = t!("settings.update.title").to_string();
+        let search = search.page_scope(&title);
+
+        search.page(
+            SettingPage::new(title).resettable(resettable),
+            [
+                search
+                    .group(t!("settings.update.group.version").to_string())
+                    .render(SettingItem::render({
+                        let current_version = Version::current().to_string();
+                        let update_status = self.update_status.clone();
+                        let release_notes_scroll = self.release_notes_scroll.clone();
+                        move |_options, window, cx| {
+                            v_flex()
+                                .gap_2()
+                                .w_full()
+                                .child(
+                                    h_flex()
+                                        .gap_2()
+                                        .items_center()
+                                        .child(
+                                            Label::new(
+                                                t!("settings.update.current_version.label")
+                                                    .to_string(),
                                             )
-                                            .child(
-                                                Label::new(&current_version)
-                                                    .text_sm()
-                                                    .text_color(cx.theme().muted_foreground),
-                                            ),
-                                    )
-                                    .child(match &update_status {
-                                        UpdateStatus::Idle | UpdateStatus::NoUpdate => h_flex()
-                                            .gap_2()
-                                            .items_center()
-                                            .child(Icon::new(IconName::Check).size_4())
-                                            .child(
-                                                Label::new(
-                                                    t!("settings.update.status.up_to_date")
-                                                        .to_string(),
-                                                )
-                                                .text_xs()
-                                                .text_color(cx.theme().success_foreground),
-                                            ),
-                                        UpdateStatus::Checking => h_flex()
-                                            .gap_2()
-                                            .items_center()
-                                            .child(Icon::new(IconName::LoaderCircle).size_4())
-                                            .child(
-                                                Label::new(
-                                                    t!("settings.update.status.checking")
+                                            .text_sm(),
+                                        )
+                                        .child(
+                                            Label::new(&current_version)
+                                                .text_sm()
+                                                .text_color(cx.theme().muted_foreground),
+                                        ),
+                                )
+                                .child(match &update_status {
+                                    UpdateStatus::Idle | UpdateStatus::NoUpdate => h_flex()
+                                        .gap_2()
+                                        .items_center()
+                                        .child(Icon::new(IconName::Check).size_4())
+                                        .child(
+                                            Label::new(
+                                                t!("settings.update.status.up_to_date").to_string(),
+                                            )
+                                            .text_xs()
+                                            .text_color(cx.theme().success_foreground),
+                                        ),
+                                    UpdateStatus::Checking => h_flex()
+                                        .gap_2()
+                                        .items_center()
+                                        .child(Icon::new(IconName::LoaderCircle).size_4())
+                                        .child(
+                                            Label::new(
+                                                t!("settings.update.status.checking").to_string(),
+                                            )
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground),
+                                        ),
+                                    UpdateStatus::Available { version, notes } => v_flex()
+                                        .gap_2()
+                                        .w_full()
+                                        .child(
+                                            h_flex()
+                                                .gap_2()
+                                                .items_center()
+                                                .child(Icon::new(IconName::ArrowDown).size_4())
+                                                .child(
+                                                    Label::new(
+                                                        t!(
+                                                            "settings.update.status.available",
+                                                            version = version
+                                                        )
                                                         .to_string(),
+                                                    )
+                                                    .text_xs()
+                                                    .text_color(cx.theme().accent_foreground),
+                                                ),
+                                        )
+                                        .when(!notes.is_empty(), |this| {
+                                            this.child(
+                                                BoundedScroll::new(
+                                                    "update-release-notes",
+                                                    &release_notes_scroll,
+                                                    px(RELEASE_NOTES_MAX_HEIGHT),
                                                 )
+                                                .fade_color(cx.theme().secondary)
+                                                .rounded(cx.theme().radius)
+                                                .border_1()
+                                                .border_color(cx.theme().border)
+                                                .bg(cx.theme().secondary)
                                                 .text_xs()
-                                                .text_color(cx.theme().muted_foreground),
-                                            ),
-                                        UpdateStatus::Available { version, notes } => {
-                                            let notes_elem = if notes.is_empty() {
-                                                None
-                                            } else {
-                                                Some(
-                                                    Label::new(notes)
-                                                        .text_xs()
-                                                        .text_color(cx.theme().muted_foreground),
-                                                )
-                                            };
-                                            v_flex()
-                                            .gap_2()
-                                            .w_full()
-                                            .child(
-                                                h_flex()
-                                                    .gap_2()
-                                                    .items_center()
-                                                    .child(Icon::new(IconName::ArrowDown).size_4())
-                                                    .child(
-                                                        Label::new(
-                                                            t!(
-                                                                "settings.update.status.available",
-                                                                version = version
-                                                            )
-                                                            .to_string(),
+                                                .child(
+                                                    div().p_3().child(
+                                                        TextView::markdown(
+                                                            "update-release-notes-markdown",
+                                                            notes.clone(),
+                                                            window,
+                                                            cx,
                                                         )
-                                                        .text_xs()
-                                                        .text_color(cx.theme().accent_foreground),
+                                                        .text_color(cx.theme().muted_foreground)
+                                                        .selectable(true),
                                                     ),
+                                                ),
                                             )
-                                            .children(notes_elem)
-                                        }
-                                        UpdateStatus::Error(err) => h_flex()
-                                            .gap_2()
-                                            .items_center()
-                                            .child(Icon::new(IconName::CircleX).size_4())
-                                            .child(
-                                                Label::new(
-                                                    t!("settings.update.status.error", error = err)
-                                                        .to_string(),
-                                                )
-                                                .text_xs()
-                                                .text_color(cx.theme().colors.danger_foreground),
-                                            ),
-                                    })
-                            }
-                        }),
-                        SettingItem::new(
-                            t!("settings.update.check.label").to_string(),
-                            SettingField::render({
-                                let view = view.clone();
-                                move |options, _window, _cx| {
-                                    Button::new("check-updates")
-                                        .icon(IconName::LoaderCircle)
-                                        .label(t!("settings.update.check.button").to_string())
-                                        .outline()
-                                        .with_size(options.size)
-                                        .on_click({
-                                            let view = view.clone();
-                                            move |_, window, cx| {
-                                                view.update(cx, |this, cx| {
-                                                    this.check_for_updates(window, cx);
-                                                });
-                                            }
-                                        })
-                                }
-                            }),
-                        )
-                        .description(t!("settings.update.check.description").to_string()),
-                    ]),
+                                        }),
+                                    UpdateStatus::Error(err) => h_flex()
+                                        .gap_2()
+                                        .items_center()
+                                        .child(Icon::new(IconName::CircleX).size_4())
+                                        .child(
+                                            Label::new(
+                                                t!("settings.update.status.error", error = err)
+                                                    .to_string(),
+                                            )
+                                            .text_xs()
+                                            .text_color(cx.theme().colors.danger_foreground),
+                                        ),
+                                })
+                        }
+                    }))
+                    .item(
+                        t!("settings.update.check.label").to_string(),
+                        t!("settings.update.check.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::render({
+                                    let view = view.clone();
+                                    move |options, _window, _cx| {
+                                        Button::new("check-updates")
+                                            .icon(IconName::LoaderCircle)
+                                            .label(t!("settings.update.check.button").to_string())
+                                            .outline()
+                                            .with_size(options.size)
+                                            .on_click({
+                                                let view = view.clone();
+                                                move |_, window, cx| {
+                                                    view.update(cx, |this, cx| {
+                                                        this.check_for_updates(window, cx);
+                                                    });
+                                                }
+                                            })
+                                    }
+                                }),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .build(),
                 // System Information
-                SettingGroup::new()
-                    .title(t!("settings.update.group.system").to_string())
-                    .items(vec![SettingItem::render({
+                search
+                    .group(t!("settings.update.group.system").to_string())
+                    .render(SettingItem::render({
                         let os = format!("{} {}", os_display_name(), os_version());
                         let arch = arch_display_name();
                         move |_options, _window, cx| {
@@ -250,70 +287,125 @@ impl SettingsPanel {
                                         ),
                                 )
                         }
-                    })]),
+                    }))
+                    .build(),
                 // Update Settings
-                SettingGroup::new()
-                    .title(t!("settings.update.group.settings").to_string())
-                    .items(vec![
-                        SettingItem::new(
-                            t!("settings.update.auto_check.label").to_string(),
-                            SettingField::switch(
-                                |cx: &App| AppSettings::global(cx).auto_check_on_startup,
-                                |val: bool, cx: &mut App| {
-                                    AppSettings::global_mut(cx).auto_check_on_startup = val;
-                                },
+                search
+                    .group(t!("settings.update.group.settings").to_string())
+                    .item(
+                        t!("settings.update.auto_check.label").to_string(),
+                        t!("settings.update.auto_check.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::switch(
+                                    |cx: &App| AppSettings::global(cx).auto_check_on_startup,
+                                    |val: bool, cx: &mut App| {
+                                        AppSettings::global_mut(cx).auto_check_on_startup = val;
+                                    },
+                                )
+                                .default_value(default_settings.auto_check_on_startup),
                             )
-                            .default_value(default_settings.auto_check_on_startup),
-                        )
-                        .description(t!("settings.update.auto_check.description").to_string()),
-                        SettingItem::new(
-                            t!("settings.update.notifications.label").to_string(),
-                            SettingField::switch(
-                                |cx: &App| AppSettings::global(cx).notifications_enabled,
-                                |val: bool, cx: &mut App| {
-                                    AppSettings::global_mut(cx).notifications_enabled = val;
-                                },
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.update.notifications.label").to_string(),
+                        t!("settings.update.notifications.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::switch(
+                                    |cx: &App| AppSettings::global(cx).notifications_enabled,
+                                    |val: bool, cx: &mut App| {
+                                        AppSettings::global_mut(cx).notifications_enabled = val;
+                                    },
+                                )
+                                .default_value(default_settings.notifications_enabled),
                             )
-                            .default_value(default_settings.notifications_enabled),
-                        )
-                        .description(t!("settings.update.notifications.description").to_string()),
-                        SettingItem::new(
-                            t!("settings.update.auto_update.label").to_string(),
-                            SettingField::switch(
-                                |cx: &App| AppSettings::global(cx).auto_update,
-                                |val: bool, cx: &mut App| {
-                                    AppSettings::global_mut(cx).auto_update = val;
-                                },
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.update.auto_update.label").to_string(),
+                        t!("settings.update.auto_update.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::switch(
+                                    |cx: &App| AppSettings::global(cx).auto_update,
+                                    |val: bool, cx: &mut App| {
+                                        AppSettings::global_mut(cx).auto_update = val;
+                                    },
+                                )
+                                .default_value(default_settings.auto_update),
                             )
-                            .default_value(default_settings.auto_update),
-                        )
-                        .description(t!("settings.update.auto_update.description").to_string()),
-                        SettingItem::new(
-                            t!("settings.update.frequency.label").to_string(),
-                            SettingField::number_input(
-                                NumberFieldOptions {
-                                    min: 1.0,
-                                    max: 30.0,
-                                    step: 1.0,
-                                    ..Default::default()
-                                },
-                                |cx: &App| AppSettings::global(cx).check_frequency_days,
-                                |val: f64, cx: &mut App| {
-                                    AppSettings::global_mut(cx).check_frequency_days = val;
-                                },
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.update.channel.label").to_string(),
+                        t!("settings.update.channel.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::dropdown(
+                                    vec![
+                                        (
+                                            UpdateChannel::Stable.as_str().into(),
+                                            t!("settings.update.channel.stable").to_string().into(),
+                                        ),
+                                        (
+                                            UpdateChannel::Beta.as_str().into(),
+                                            t!("settings.update.channel.beta").to_string().into(),
+                                        ),
+                                    ],
+                                    |cx: &App| AppSettings::global(cx).update_channel.clone(),
+                                    |val: SharedString, cx: &mut App| {
+                                        AppSettings::global_mut(cx).update_channel = val;
+                                    },
+                                )
+                                .default_value(default_settings.update_channel),
                             )
-                            .default_value(default_settings.check_frequency_days),
-                        )
-                        .description(t!("settings.update.frequency.description").to_string()),
-                    ]),
-            ])
+                            .description(description)
+                        },
+                    )
+                    .item(
+                        t!("settings.update.frequency.label").to_string(),
+                        t!("settings.update.frequency.description").to_string(),
+                        |title, description| {
+                            SettingItem::new(
+                                title,
+                                SettingField::number_input(
+                                    NumberFieldOptions {
+                                        min: 1.0,
+                                        max: 30.0,
+                                        step: 1.0,
+                                        ..Default::default()
+                                    },
+                                    |cx: &App| AppSettings::global(cx).check_frequency_days,
+                                    |val: f64, cx: &mut App| {
+                                        AppSettings::global_mut(cx).check_frequency_days = val;
+                                    },
+                                )
+                                .default_value(default_settings.check_frequency_days),
+                            )
+                            .description(description)
+                        },
+                    )
+                    .build(),
+            ],
+        )
     }
 
     pub fn check_for_updates(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         self.update_status = UpdateStatus::Checking;
         cx.notify();
 
-        let update_manager = self.update_manager.clone();
+        let update_manager = self
+            .update_manager
+            .clone()
+            .with_channel(AppSettings::global(cx).update_channel());
         let entity = cx.entity().downgrade();
 
         cx.spawn(async move |_this, cx| {
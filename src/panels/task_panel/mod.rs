@@ -1,3 +1,3 @@
 mod panel;
 
-pub use panel::TaskPanel;
+pub use panel::{TaskListView, TaskPanel, TaskSort, filter_and_sort_tasks};
@@ -11,16 +11,18 @@ use gpui::{
     StatefulInteractiveElement, Styled, Subscription, Window, div, prelude::FluentBuilder, px,
 };
 use gpui_component::{
-    ActiveTheme, Icon, IconName, Selectable, Sizable, StyledExt,
+    ActiveTheme, Icon, IconName, Selectable, Sizable, StyledExt, WindowExt as _,
     button::{Button, ButtonGroup, ButtonVariants},
     dock::DockPlacement,
     h_flex,
     input::{Input, InputState},
     menu::{ContextMenuExt, DropdownMenu, PopupMenuItem},
+    notification::Notification,
     scroll::ScrollableElement as _,
     v_flex,
 };
 use rust_i18n::t;
+use serde::{Deserialize, Serialize};
 use smol::Timer;
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -29,7 +31,7 @@ use std::time::Duration;
 use crate::core::services::WorkspaceService;
 use crate::core::{event_bus::WorkspaceUpdateEvent, services::SessionStatus};
 use crate::panels::dock_panel::DockPanel;
-use crate::schemas::workspace_schema::WorkspaceTask;
+use crate::schemas::workspace_schema::{Workspace, WorkspaceTask};
 use crate::{AppState, OpenSessionManager, PanelAction, StatusIndicator, utils};
 
 // ============================================================================
@@ -58,6 +60,88 @@ pub enum ViewMode {
     Timeline,
 }
 
+/// Bucket tasks under the workspace they were created in.
+///
+/// Groups follow the order of `workspaces` with the active workspace moved to the
+/// front. Tasks whose workspace no longer exists are dropped.
+pub fn group_tasks_by_workspace(
+    workspaces: &[Workspace],
+    tasks: Vec<WorkspaceTask>,
+    active_workspace_id: Option<&str>,
+    expanded: &HashMap<String, bool>,
+) -> Vec<WorkspaceGroup> {
+    let mut tasks_by_workspace: HashMap<String, Vec<Rc<WorkspaceTask>>> = HashMap::new();
+    for task in tasks {
+        tasks_by_workspace
+            .entry(task.workspace_id.clone())
+            .or_default()
+            .push(Rc::new(task));
+    }
+
+    let mut groups: Vec<WorkspaceGroup> = workspaces
+        .iter()
+        .map(|ws| WorkspaceGroup {
+            id: ws.id.clone(),
+            name: ws.name.clone(),
+            path: ws.path.clone(),
+            tasks: tasks_by_workspace.remove(&ws.id).unwrap_or_default(),
+            is_expanded: expanded.get(&ws.id).copied().unwrap_or(true),
+        })
+        .collect();
+
+    // Stable sort keeps the configured order for every other workspace
+    groups.sort_by_key(|group| Some(group.id.as_str()) != active_workspace_id);
+    groups
+}
+
+/// Order of tasks within the list
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskSort {
+    /// Newest created first
+    #[default]
+    Created,
+    /// Most recently active first
+    LastActivity,
+    /// Alphabetical by task name
+    Name,
+}
+
+/// Status filter and sort order chosen in the task list, persisted with the layout
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskListView {
+    /// Only show tasks with this status (all tasks when `None`)
+    #[serde(default)]
+    pub status: Option<SessionStatus>,
+    #[serde(default)]
+    pub sort: TaskSort,
+}
+
+/// Apply the status filter and sort order of `view` to `tasks`
+pub fn filter_and_sort_tasks(
+    tasks: &[Rc<WorkspaceTask>],
+    view: &TaskListView,
+) -> Vec<Rc<WorkspaceTask>> {
+    let mut result: Vec<_> = tasks
+        .iter()
+        .filter(|task| {
+            view.status
+                .as_ref()
+                .is_none_or(|status| &task.status == status)
+        })
+        .cloned()
+        .collect();
+
+    match view.sort {
+        TaskSort::Created => result.sort_by_key(|task| std::cmp::Reverse(task.created_at)),
+        TaskSort::LastActivity => {
+            result.sort_by_key(|task| std::cmp::Reverse(task.last_activity()))
+        }
+        TaskSort::Name => result.sort_by_key(|task| task.name.to_lowercase()),
+    }
+
+    result
+}
+
 // ============================================================================
 // Main Panel Component
 // ============================================================================
@@ -68,6 +152,8 @@ pub struct TaskPanel {
     selected_task_id: Option<String>,
     context_menu_task_id: Option<String>,
     view_mode: ViewMode,
+    /// Status filter and sort order of the task list
+    list_view: TaskListView,
     _subscriptions: Vec<Subscription>,
     /// Search input state
     search_input: Entity<InputState>,
@@ -133,6 +219,7 @@ impl TaskPanel {
             selected_task_id: None,
             context_menu_task_id: None,
             view_mode: ViewMode::Tree,
+            list_view: TaskListView::default(),
             _subscriptions: vec![search_subscription],
             search_input,
             load_generation: 0,
@@ -173,16 +260,6 @@ impl TaskPanel {
         let entity_clone = entity.clone();
         cx.spawn(async move |cx| {
             let config = workspace_service.get_config().await;
-            let workspaces_list = config.workspaces;
-            let tasks = config.tasks;
-
-            let mut tasks_by_workspace: HashMap<String, Vec<Rc<WorkspaceTask>>> = HashMap::new();
-            for task in tasks {
-                tasks_by_workspace
-                    .entry(task.workspace_id.clone())
-                    .or_default()
-                    .push(Rc::new(task));
-            }
 
             cx.update(|cx| {
                 entity_clone.update(cx, |this, cx| {
@@ -196,23 +273,12 @@ impl TaskPanel {
                         .map(|w| (w.id.clone(), w.is_expanded))
                         .collect();
 
-                    this.workspaces = workspaces_list
-                        .into_iter()
-                        .map(|ws| {
-                            let tasks = tasks_by_workspace.remove(&ws.id).unwrap_or_default();
-
-                            WorkspaceGroup {
-                                id: ws.id.clone(),
-                                name: ws.name.clone(),
-                                path: ws.path.clone(),
-                                tasks,
-                                is_expanded: previously_expanded
-                                    .get(&ws.id)
-                                    .copied()
-                                    .unwrap_or(true),
-                            }
-                        })
-                        .collect();
+                    this.workspaces = group_tasks_by_workspace(
+                        &config.workspaces,
+                        config.tasks,
+                        config.active_workspace_id.as_deref(),
+                        &previously_expanded,
+                    );
 
                     this.ensure_selected_task_valid();
                     this.is_loading = false;
@@ -504,7 +570,9 @@ impl TaskPanel {
                         }
                     }
                     WorkspaceUpdateEvent::SessionStatusUpdated {
-                        session_id, status, ..
+                        session_id,
+                        status,
+                        last_active,
                     } => {
                         log::debug!("TaskPanel received SessionStatusUpdated: {}", session_id);
                         if let Some(entity) = entity_weak.upgrade() {
@@ -513,11 +581,19 @@ impl TaskPanel {
                             cx.update(|cx| {
                                 entity.update(cx, |this, cx| {
                                     // This method already does incremental update
-                                    this.update_task_status_by_session_id(&session_id, status, cx);
+                                    this.update_task_status_by_session_id(
+                                        &session_id,
+                                        status,
+                                        last_active,
+                                        cx,
+                                    );
                                 });
                             });
                         }
                     }
+                    WorkspaceUpdateEvent::CwdChanged { .. }
+                    | WorkspaceUpdateEvent::ActiveAgentChanged { .. }
+                    | WorkspaceUpdateEvent::MessageQueueChanged { .. } => {}
                 }
             }
         })
@@ -558,6 +634,7 @@ impl TaskPanel {
         &mut self,
         session_id: &str,
         status: SessionStatus,
+        last_active: chrono::DateTime<chrono::Utc>,
         cx: &mut Context<Self>,
     ) {
         let mut updated = false;
@@ -579,6 +656,7 @@ impl TaskPanel {
 
                 let mut updated_task = (**task).clone();
                 updated_task.status = status.clone();
+                updated_task.last_active_at = Some(last_active);
                 *task = Rc::new(updated_task);
                 updated = true;
             }
@@ -716,11 +794,15 @@ impl TaskPanel {
                 return;
             }
         };
+        let agent_service = AppState::global(cx).agent_service().cloned();
 
         cx.spawn(async move |_entity, _cx| {
             match workspace_service.remove_task(&task_id).await {
                 Ok(_) => {
                     log::info!("Successfully removed task: {}", task_id);
+                    if let Some(agent_service) = agent_service {
+                        agent_service.forget_task(&task_id);
+                    }
                     // The UI will be updated via the TaskRemoved event
                 }
                 Err(e) => {
@@ -731,6 +813,94 @@ impl TaskPanel {
         .detach();
     }
 
+    /// Ask where to save a task's session and export it there
+    fn export_task_session(&mut self, task_id: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(session_id) = self.session_id_for_task(task_id) else {
+            return;
+        };
+        let Some(persistence_service) = AppState::global(cx).persistence_service().cloned() else {
+            log::warn!("PersistenceService not available");
+            return;
+        };
+
+        cx.spawn_in(window, async move |_, window| {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .set_title(t!("task_panel.task.export.dialog_title").to_string())
+                .add_filter("JSON", &["json"])
+                .set_file_name(format!("{}.json", session_id))
+                .save_file()
+                .await
+            else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+
+            let note = match persistence_service.export_session(&session_id, &path).await {
+                Ok(()) => Notification::success(
+                    t!("task_panel.task.export.success", path = path.display()).to_string(),
+                ),
+                Err(e) => {
+                    log::error!("Failed to export session {}: {:#}", session_id, e);
+                    Notification::error(format!("{:#}", e))
+                }
+            };
+            _ = window.update(|window, cx| window.push_notification(note, cx));
+        })
+        .detach();
+    }
+
+    /// Pick an exported session file and add it to a workspace as a replay task
+    fn import_session(
+        &mut self,
+        workspace_id: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(persistence_service), Some(workspace_service)) = (
+            AppState::global(cx).persistence_service().cloned(),
+            AppState::global(cx).workspace_service().cloned(),
+        ) else {
+            log::warn!("Services not available for session import");
+            return;
+        };
+
+        cx.spawn_in(window, async move |_, window| {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .set_title(t!("task_panel.workspace.import_session.dialog_title").to_string())
+                .add_filter("JSON", &["json"])
+                .pick_file()
+                .await
+            else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let result = async {
+                let session_id = persistence_service.import_session(&path).await?;
+                workspace_service
+                    .import_task(
+                        &workspace_id,
+                        name,
+                        t!("task_panel.task.replay").to_string(),
+                        session_id,
+                    )
+                    .await
+            }
+            .await;
+            // The task shows up through the TaskCreated event
+            if let Err(e) = result {
+                log::error!("Failed to import session from {}: {:#}", path.display(), e);
+                let note = Notification::error(format!("{:#}", e));
+                _ = window.update(|window, cx| window.push_notification(note, cx));
+            }
+        })
+        .detach();
+    }
+
     fn select_task(&mut self, task_id: String, cx: &mut Context<Self>) {
         self.selected_task_id = Some(task_id);
         cx.notify();
@@ -753,6 +923,25 @@ impl TaskPanel {
         self.selected_task_id.as_deref()
     }
 
+    pub fn list_view(&self) -> &TaskListView {
+        &self.list_view
+    }
+
+    pub fn set_list_view(&mut self, list_view: TaskListView, cx: &mut Context<Self>) {
+        self.list_view = list_view;
+        cx.notify();
+    }
+
+    fn set_status_filter(&mut self, status: Option<SessionStatus>, cx: &mut Context<Self>) {
+        self.list_view.status = status;
+        cx.notify();
+    }
+
+    fn set_sort(&mut self, sort: TaskSort, cx: &mut Context<Self>) {
+        self.list_view.sort = sort;
+        cx.notify();
+    }
+
     fn set_view_mode(&mut self, mode: ViewMode, cx: &mut Context<Self>) {
         self.view_mode = mode;
         cx.notify();
@@ -766,6 +955,36 @@ impl TaskPanel {
             .and_then(|t| t.session_id.clone())
     }
 
+    /// Session of a task whose turn was cut off by the app exiting
+    fn interrupted_session_for_task(&self, task_id: &str) -> Option<String> {
+        self.workspaces
+            .iter()
+            .flat_map(|w| &w.tasks)
+            .find(|t| t.id == task_id && t.status == SessionStatus::Interrupted)
+            .and_then(|t| t.session_id.clone())
+    }
+
+    /// Reopen an interrupted task's conversation, which resumes its session
+    /// with the agent, and mark it idle so it can be prompted again
+    fn resume_interrupted_task(
+        &mut self,
+        task_id: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(session_id) = self.interrupted_session_for_task(task_id) else {
+            return;
+        };
+        let action = PanelAction::show_conversation(Some(session_id.clone()));
+        window.dispatch_action(Box::new(action), cx);
+        self.update_task_status_by_session_id(
+            &session_id,
+            SessionStatus::Idle,
+            chrono::Utc::now(),
+            cx,
+        );
+    }
+
     fn open_task_in_current_panel(
         &self,
         task_id: &str,
@@ -862,20 +1081,29 @@ impl TaskPanel {
 
     fn get_filtered_workspaces(&self, cx: &Context<Self>) -> Vec<WorkspaceGroup> {
         let search_query = self.search_input.read(cx).text().to_string().to_lowercase();
+        let status_filtered = self.list_view.status.is_some();
 
-        if search_query.is_empty() {
-            return self.workspaces.clone();
+        if search_query.is_empty() && !status_filtered {
+            return self
+                .workspaces
+                .iter()
+                .map(|workspace| WorkspaceGroup {
+                    tasks: filter_and_sort_tasks(&workspace.tasks, &self.list_view),
+                    ..workspace.clone()
+                })
+                .collect();
         }
 
         self.workspaces
             .iter()
             .filter_map(|workspace| {
                 // Filter tasks that match the search query
-                let filtered_tasks: Vec<_> = workspace
+                let matching_tasks: Vec<_> = workspace
                     .tasks
                     .iter()
                     .filter(|task| {
-                        task.name.to_lowercase().contains(&search_query)
+                        search_query.is_empty()
+                            || task.name.to_lowercase().contains(&search_query)
                             || task.agent_name.to_lowercase().contains(&search_query)
                             || task.mode.to_lowercase().contains(&search_query)
                             || task
@@ -886,11 +1114,14 @@ impl TaskPanel {
                     })
                     .cloned()
                     .collect();
-
-                // Include workspace if it has matching tasks or its name matches
-                if !filtered_tasks.is_empty()
-                    || workspace.name.to_lowercase().contains(&search_query)
-                {
+                let filtered_tasks = filter_and_sort_tasks(&matching_tasks, &self.list_view);
+
+                // Include workspace if it has matching tasks or its name matches.
+                // A status filter only keeps workspaces that have matching tasks.
+                let name_matches = !search_query.is_empty()
+                    && !status_filtered
+                    && workspace.name.to_lowercase().contains(&search_query);
+                if !filtered_tasks.is_empty() || name_matches {
                     Some(WorkspaceGroup {
                         id: workspace.id.clone(),
                         name: workspace.name.clone(),
@@ -912,6 +1143,8 @@ impl TaskPanel {
     fn render_header(&self, cx: &Context<Self>) -> impl IntoElement {
         let theme = cx.theme();
         let view_mode = self.view_mode;
+        let list_view = self.list_view.clone();
+        let entity = cx.entity().clone();
 
         h_flex()
             .w_full()
@@ -935,6 +1168,70 @@ impl TaskPanel {
                         ),
                 ),
             )
+            .child(
+                // Status filter and sort order
+                Button::new("task-list-options")
+                    .icon(crate::assets::Icon::ListOrdered)
+                    .ghost()
+                    .xsmall()
+                    .selected(list_view.status.is_some())
+                    .dropdown_menu(move |mut menu, _, _| {
+                        menu = menu.label(t!("task_panel.filter.title").to_string()).item(
+                            PopupMenuItem::new(t!("task_panel.filter.all").to_string())
+                                .checked(list_view.status.is_none())
+                                .on_click({
+                                    let entity = entity.clone();
+                                    move |_, _, cx| {
+                                        entity.update(cx, |this, cx| {
+                                            this.set_status_filter(None, cx);
+                                        });
+                                    }
+                                }),
+                        );
+                        for status in [
+                            SessionStatus::Active,
+                            SessionStatus::Idle,
+                            SessionStatus::Pending,
+                            SessionStatus::InProgress,
+                            SessionStatus::Completed,
+                            SessionStatus::Failed,
+                            SessionStatus::Closed,
+                            SessionStatus::Interrupted,
+                        ] {
+                            let entity = entity.clone();
+                            menu = menu.item(
+                                PopupMenuItem::new(status_label(&status))
+                                    .checked(list_view.status.as_ref() == Some(&status))
+                                    .on_click(move |_, _, cx| {
+                                        entity.update(cx, |this, cx| {
+                                            this.set_status_filter(Some(status.clone()), cx);
+                                        });
+                                    }),
+                            );
+                        }
+
+                        menu = menu
+                            .separator()
+                            .label(t!("task_panel.sort.title").to_string());
+                        for (sort, label) in [
+                            (TaskSort::Created, t!("task_panel.sort.created")),
+                            (TaskSort::LastActivity, t!("task_panel.sort.last_activity")),
+                            (TaskSort::Name, t!("task_panel.sort.name")),
+                        ] {
+                            let entity = entity.clone();
+                            menu = menu.item(
+                                PopupMenuItem::new(label.to_string())
+                                    .checked(list_view.sort == sort)
+                                    .on_click(move |_, _, cx| {
+                                        entity.update(cx, |this, cx| {
+                                            this.set_sort(sort, cx);
+                                        });
+                                    }),
+                            );
+                        }
+                        menu
+                    }),
+            )
             .child(
                 // View toggle buttons
                 ButtonGroup::new("view-toggle")
@@ -1068,10 +1365,10 @@ impl TaskPanel {
         let workspace_id = workspace.id.clone();
         let is_expanded = workspace.is_expanded;
         let workspace_name = workspace.name.clone();
+        let task_count = workspace.tasks.len();
 
-        // Sort tasks by created_at descending (newest first)
-        let mut sorted_tasks = workspace.tasks.clone();
-        sorted_tasks.sort_by_key(|task| std::cmp::Reverse(task.created_at));
+        // Tasks are already filtered and sorted by `get_filtered_workspaces`
+        let sorted_tasks = workspace.tasks.clone();
 
         v_flex()
             .w_full()
@@ -1111,6 +1408,12 @@ impl TaskPanel {
                                     .font_medium()
                                     .text_color(theme.foreground)
                                     .child(workspace_name),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .child(task_count.to_string()),
                             ),
                     )
                     .child(h_flex().gap_2().items_center().child({
@@ -1205,13 +1508,31 @@ impl TaskPanel {
                                     t!("task_panel.workspace.open_folder").to_string(),
                                 )
                                 .icon(IconName::Folder)
-                                .on_click(move |_, _, _| {
-                                    if let Err(e) =
-                                        crate::utils::external_editor::open_in_file_manager(
-                                            &workspace_path_for_folder,
-                                        )
-                                    {
-                                        log::error!("Failed to open folder: {}", e);
+                                .on_click(move |_, window, cx| {
+                                    if let Err(e) = crate::utils::file::reveal_in_file_manager(
+                                        &workspace_path_for_folder,
+                                    ) {
+                                        log::error!("Failed to open folder: {:#}", e);
+                                        window.push_notification(
+                                            Notification::error(format!("{:#}", e)),
+                                            cx,
+                                        );
+                                    }
+                                }),
+                            );
+
+                            menu = menu.item(
+                                PopupMenuItem::new(
+                                    t!("task_panel.workspace.import_session.label").to_string(),
+                                )
+                                .icon(IconName::ArrowDown)
+                                .on_click({
+                                    let workspace_id = workspace_id.clone();
+                                    let entity = entity.clone();
+                                    move |_, window, cx| {
+                                        entity.update(cx, |this, cx| {
+                                            this.import_session(workspace_id.clone(), window, cx);
+                                        });
                                     }
                                 }),
                             );
@@ -1395,12 +1716,11 @@ impl TaskPanel {
 
         let filtered_workspaces = self.get_filtered_workspaces(cx);
 
-        let mut all_tasks: Vec<Rc<WorkspaceTask>> = filtered_workspaces
+        let all_tasks: Vec<Rc<WorkspaceTask>> = filtered_workspaces
             .iter()
             .flat_map(|w| w.tasks.clone())
             .collect();
-
-        all_tasks.sort_by_key(|task| std::cmp::Reverse(task.created_at));
+        let all_tasks = filter_and_sort_tasks(&all_tasks, &self.list_view);
 
         let now = Local::now().date_naive();
 
@@ -1581,42 +1901,7 @@ impl TaskPanel {
     // ========================================================================
 
     fn format_relative_time(&self, created_at: &chrono::DateTime<chrono::Utc>) -> String {
-        use chrono::Local;
-
-        let now = Local::now();
-        let created_local = created_at.with_timezone(&Local);
-        let duration = now.signed_duration_since(created_local);
-
-        let minutes = duration.num_minutes();
-        let hours = duration.num_hours();
-        let days = duration.num_days();
-
-        if minutes < 1 {
-            t!("task_panel.time.just_now").to_string()
-        } else if minutes < 60 {
-            t!("task_panel.time.minutes_ago", minutes = minutes).to_string()
-        } else if hours < 24 {
-            t!("task_panel.time.hours_ago", hours = hours).to_string()
-        } else if days == 1 {
-            t!("task_panel.time.yesterday").to_string()
-        } else if days == 2 {
-            t!("task_panel.time.day_before_yesterday").to_string()
-        } else if days < 7 {
-            t!("task_panel.time.days_ago", days = days).to_string()
-        } else if days < 30 {
-            let weeks = days / 7;
-            if weeks == 1 {
-                t!("task_panel.time.one_week_ago").to_string()
-            } else {
-                t!("task_panel.time.weeks_ago", weeks = weeks).to_string()
-            }
-        } else if days < 365 {
-            let months = days / 30;
-            t!("task_panel.time.months_ago", months = months).to_string()
-        } else {
-            let years = days / 365;
-            t!("task_panel.time.years_ago", years = years).to_string()
-        }
+        crate::utils::time::format_relative((*created_at).into(), std::time::SystemTime::now())
     }
 
     // ========================================================================
@@ -1625,38 +1910,19 @@ impl TaskPanel {
 
     fn render_status_badge(&self, status: &SessionStatus, cx: &Context<Self>) -> impl IntoElement {
         let theme = cx.theme();
-        let (label, color) = match status {
-            SessionStatus::Active => (
-                t!("task_panel.status.active").to_string(),
-                theme.muted_foreground,
-            ),
-            SessionStatus::Idle => (
-                t!("task_panel.status.idle").to_string(),
-                theme.muted_foreground,
-            ),
-            SessionStatus::Pending => (
-                t!("task_panel.status.pending").to_string(),
-                theme.muted_foreground,
-            ),
-            SessionStatus::InProgress => (
-                t!("task_panel.status.in_progress").to_string(),
-                gpui::rgb(0x22c55e).into(),
-            ),
-            SessionStatus::Completed => (
-                t!("task_panel.status.completed").to_string(),
-                gpui::rgb(0x22c55e).into(),
-            ),
-            SessionStatus::Failed => (
-                t!("task_panel.status.failed").to_string(),
-                gpui::rgb(0xef4444).into(),
-            ),
-            SessionStatus::Closed => (
-                t!("task_panel.status.closed").to_string(),
-                gpui::rgb(0xef4444).into(),
-            ),
+        let color = match status {
+            SessionStatus::Active | SessionStatus::Idle | SessionStatus::Pending => {
+                theme.muted_foreground
+            }
+            SessionStatus::InProgress | SessionStatus::Completed => gpui::rgb(0x22c55e).into(),
+            SessionStatus::Failed | SessionStatus::Closed => gpui::rgb(0xef4444).into(),
+            SessionStatus::Interrupted => gpui::rgb(0xf59e0b).into(),
         };
 
-        div().text_xs().text_color(color).child(label)
+        div()
+            .text_xs()
+            .text_color(color)
+            .child(status_label(status))
     }
 
     fn status_icon(&self, status: &SessionStatus) -> IconName {
@@ -1668,6 +1934,7 @@ impl TaskPanel {
             SessionStatus::Completed => IconName::CircleCheck,
             SessionStatus::Failed => IconName::CircleX,
             SessionStatus::Closed => IconName::CircleX,
+            SessionStatus::Interrupted => IconName::TriangleAlert,
         }
     }
 
@@ -1680,10 +1947,26 @@ impl TaskPanel {
             SessionStatus::Completed => gpui::rgb(0x22c55e).into(),
             SessionStatus::Failed => gpui::rgb(0xef4444).into(),
             SessionStatus::Closed => gpui::rgb(0xef4444).into(),
+            SessionStatus::Interrupted => gpui::rgb(0xf59e0b).into(),
         }
     }
 }
 
+/// Localized label of a session status
+fn status_label(status: &SessionStatus) -> String {
+    match status {
+        SessionStatus::Active => t!("task_panel.status.active"),
+        SessionStatus::Idle => t!("task_panel.status.idle"),
+        SessionStatus::Pending => t!("task_panel.status.pending"),
+        SessionStatus::InProgress => t!("task_panel.status.in_progress"),
+        SessionStatus::Completed => t!("task_panel.status.completed"),
+        SessionStatus::Failed => t!("task_panel.status.failed"),
+        SessionStatus::Closed => t!("task_panel.status.closed"),
+        SessionStatus::Interrupted => t!("task_panel.status.interrupted"),
+    }
+    .to_string()
+}
+
 impl Focusable for TaskPanel {
     fn focus_handle(&self, _: &App) -> FocusHandle {
         self.focus_handle.clone()
@@ -1719,6 +2002,37 @@ impl Render for TaskPanel {
                             return menu;
                         };
                         let entity = entity.clone();
+                        let mut menu = menu;
+                        if entity
+                            .read(cx)
+                            .interrupted_session_for_task(&task_id)
+                            .is_some()
+                        {
+                            let task_id = task_id.clone();
+                            let entity = entity.clone();
+                            menu = menu.item(
+                                PopupMenuItem::new(t!("task_panel.task.resume").to_string())
+                                    .icon(IconName::Redo)
+                                    .on_click(move |_, window, cx| {
+                                        entity.update(cx, |this, cx| {
+                                            this.resume_interrupted_task(&task_id, window, cx);
+                                        });
+                                    }),
+                            );
+                        }
+                        if entity.read(cx).session_id_for_task(&task_id).is_some() {
+                            let task_id = task_id.clone();
+                            let entity = entity.clone();
+                            menu = menu.item(
+                                PopupMenuItem::new(t!("task_panel.task.export.label").to_string())
+                                    .icon(IconName::ArrowUp)
+                                    .on_click(move |_, window, cx| {
+                                        entity.update(cx, |this, cx| {
+                                            this.export_task_session(&task_id, window, cx);
+                                        });
+                                    }),
+                            );
+                        }
                         menu.item(
                             PopupMenuItem::new(t!("task_panel.task.delete").to_string())
                                 .icon(Icon::new(crate::assets::Icon::Trash2))
@@ -1733,3 +2047,119 @@ impl Render for TaskPanel {
             .child(self.render_footer(cx))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn task(
+        name: &str,
+        status: SessionStatus,
+        created: i64,
+        active: Option<i64>,
+    ) -> Rc<WorkspaceTask> {
+        let mut task = WorkspaceTask::new(
+            "ws".to_string(),
+            name.to_string(),
+            "agent".to_string(),
+            "code".to_string(),
+        );
+        task.status = status;
+        task.created_at = Utc.timestamp_opt(created, 0).unwrap();
+        task.last_active_at = active.map(|secs| Utc.timestamp_opt(secs, 0).unwrap());
+        Rc::new(task)
+    }
+
+    fn fixture() -> Vec<Rc<WorkspaceTask>> {
+        vec![
+            task("beta", SessionStatus::Completed, 100, Some(400)),
+            task("Alpha", SessionStatus::InProgress, 300, None),
+            task("gamma", SessionStatus::Completed, 200, Some(250)),
+        ]
+    }
+
+    fn workspace(id: &str) -> Workspace {
+        let mut workspace = Workspace::new(std::path::PathBuf::from(format!("/projects/{id}")));
+        workspace.id = id.to_string();
+        workspace
+    }
+
+    fn task_in(workspace_id: &str, name: &str) -> WorkspaceTask {
+        WorkspaceTask::new(
+            workspace_id.to_string(),
+            name.to_string(),
+            "agent".to_string(),
+            "code".to_string(),
+        )
+    }
+
+    #[test]
+    fn groups_tasks_by_workspace_with_active_first() {
+        let workspaces = [workspace("a"), workspace("b"), workspace("c")];
+        let tasks = vec![
+            task_in("a", "a1"),
+            task_in("c", "c1"),
+            task_in("a", "a2"),
+            task_in("gone", "orphan"),
+        ];
+        let expanded = HashMap::from([("c".to_string(), false)]);
+
+        let groups = group_tasks_by_workspace(&workspaces, tasks, Some("c"), &expanded);
+
+        let ids: Vec<_> = groups.iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(ids, ["c", "a", "b"]);
+        assert_eq!(names(&groups[0].tasks), ["c1"]);
+        assert_eq!(names(&groups[1].tasks), ["a1", "a2"]);
+        assert!(groups[2].tasks.is_empty());
+        assert!(!groups[0].is_expanded);
+        assert!(groups[1].is_expanded);
+    }
+
+    #[test]
+    fn groups_keep_config_order_without_active_workspace() {
+        let workspaces = [workspace("a"), workspace("b")];
+        let groups = group_tasks_by_workspace(&workspaces, Vec::new(), None, &HashMap::new());
+
+        let ids: Vec<_> = groups.iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+    }
+
+    fn names(tasks: &[Rc<WorkspaceTask>]) -> Vec<&str> {
+        tasks.iter().map(|task| task.name.as_str()).collect()
+    }
+
+    #[test]
+    fn sorts_by_each_order() {
+        let tasks = fixture();
+        let sorted = |sort| filter_and_sort_tasks(&tasks, &TaskListView { status: None, sort });
+
+        assert_eq!(
+            names(&sorted(TaskSort::Created)),
+            ["Alpha", "gamma", "beta"]
+        );
+        assert_eq!(
+            names(&sorted(TaskSort::LastActivity)),
+            ["beta", "Alpha", "gamma"]
+        );
+        assert_eq!(names(&sorted(TaskSort::Name)), ["Alpha", "beta", "gamma"]);
+    }
+
+    #[test]
+    fn status_filter_keeps_matching_tasks() {
+        let view = TaskListView {
+            status: Some(SessionStatus::Completed),
+            sort: TaskSort::Name,
+        };
+        assert_eq!(
+            names(&filter_and_sort_tasks(&fixture(), &view)),
+            ["beta", "gamma"]
+        );
+
+        let view = TaskListView {
+            status: Some(SessionStatus::Failed),
+            sort: TaskSort::Created,
+        };
+        assert!(filter_and_sort_tasks(&fixture(), &view).is_empty());
+    }
+}
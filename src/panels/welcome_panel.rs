@@ -1,12 +1,15 @@
 use gpui::{
     App, AppContext, ClipboardEntry, Context, Entity, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, ParentElement, Render, SharedString, Styled, Subscription, Window, px,
+    IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled,
+    Subscription, Window, prelude::FluentBuilder, px,
 };
 use rust_i18n::t;
 use std::collections::HashSet;
 
 use gpui_component::{
-    ActiveTheme, IndexPath, StyledExt, WindowExt,
+    ActiveTheme, IconName, IndexPath, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
     input::InputState,
     list::ListState,
     notification::Notification,
@@ -17,18 +20,53 @@ use gpui_component::{
 use agent_client_protocol::{self as acp, AvailableCommand, ImageContent};
 
 use crate::{
-    AppState, CreateTaskFromWelcome, WelcomeSession,
+    AppState, CreateTaskFromWelcome, PanelAction, WelcomeSession,
     app::actions::AddCodeSelection,
     components::{
         AgentItem, ChatInputBox, FileItem, FilePickerDelegate, ModeSelectItem, ModelSelectItem,
+        filter_commands,
     },
-    core::{config::McpServerConfig, services::AgentSessionInfo},
+    core::{
+        config::McpServerConfig,
+        services::{AgentSessionInfo, WorkspaceService},
+    },
+    utils::git::{GitSummary, git_summary},
 };
 
 // File picker delegate is now imported from components module
 
 const MAX_FILE_SUGGESTIONS: usize = 8;
 
+/// Number of recently opened workspaces offered as quick launch cards
+const MAX_RECENT_PROJECTS: usize = 4;
+
+/// A recently opened workspace shown on the welcome panel
+#[derive(Clone, Debug)]
+pub struct RecentProject {
+    pub workspace_id: String,
+    pub name: String,
+    pub path: std::path::PathBuf,
+    pub last_accessed: std::time::SystemTime,
+    /// Branch and dirty state, filled in once git has been queried
+    pub git: Option<GitSummary>,
+}
+
+/// Build the recent project list from the workspaces most recently opened
+pub async fn recent_projects(service: &WorkspaceService, limit: usize) -> Vec<RecentProject> {
+    service
+        .recent_workspaces(limit)
+        .await
+        .into_iter()
+        .map(|workspace| RecentProject {
+            workspace_id: workspace.id,
+            name: workspace.name,
+            path: workspace.path,
+            last_accessed: workspace.last_accessed.into(),
+            git: None,
+        })
+        .collect()
+}
+
 /// Welcome panel displayed when creating a new task.
 /// Shows a centered input form with title, instructions, and send button.
 pub struct WelcomePanel {
@@ -41,6 +79,9 @@ pub struct WelcomePanel {
     session_select: Entity<SelectState<Vec<String>>>,
     current_agent_name: Option<String>,
     current_session_id: Option<String>,
+    /// Stands in for the task id of the session created before its task, so
+    /// repeated requests for it reuse one agent session
+    draft_task_id: String,
     has_agents: bool,
     has_modes: bool,
     has_models: bool,
@@ -51,6 +92,8 @@ pub struct WelcomePanel {
     workspace_id: Option<String>,
     /// Working directory for file operations
     working_directory: std::path::PathBuf,
+    /// Recently opened workspaces for quick launch
+    recent_projects: Vec<RecentProject>,
     pasted_images: Vec<(ImageContent, String)>,
     code_selections: Vec<AddCodeSelection>,
     selected_files: Vec<String>,
@@ -58,6 +101,8 @@ pub struct WelcomePanel {
     last_file_query: String,
     /// Command suggestions based on input
     command_suggestions: Vec<AvailableCommand>,
+    /// Custom commands from the configuration, offered alongside agent commands
+    configured_commands: Vec<AvailableCommand>,
     /// Whether to show command suggestions (input starts with /)
     show_command_suggestions: bool,
     /// Selected command index for keyboard navigation
@@ -72,6 +117,8 @@ pub struct WelcomePanel {
     mcp_selection_overridden: bool,
     /// Whether we should recreate the session after MCP config changes
     pending_mcp_session_recreate: bool,
+    /// Active agent changed elsewhere; switch to it on the next render
+    pending_active_agent: Option<String>,
 }
 
 impl crate::panels::dock_panel::DockPanel for WelcomePanel {
@@ -169,8 +216,25 @@ impl WelcomePanel {
         window: &mut Window,
         cx: &mut App,
     ) -> Entity<Self> {
+        let follows_cwd = workspace_id.is_none() && working_directory.is_none();
         let entity = cx.new(|cx| Self::new(workspace_id.clone(), working_directory, window, cx));
 
+        // Without a workspace the panel works in the application directory
+        if follows_cwd {
+            let context_list = entity.read(cx).context_list.clone();
+            FilePickerDelegate::follow_working_dir(&context_list, cx);
+            crate::core::event_bus::subscribe_entity_to_cwd_changes(
+                &entity,
+                AppState::global(cx).event_hub().clone(),
+                "WelcomePanel",
+                |panel, path, cx| {
+                    panel.working_directory = path;
+                    cx.notify();
+                },
+                cx,
+            );
+        }
+
         // Subscribe to code selection events using the shared helper function
         crate::core::event_bus::subscribe_entity_to_code_selections(
             &entity,
@@ -213,6 +277,32 @@ impl WelcomePanel {
             .detach();
         }
 
+        // Follow the active agent when it is changed from another panel
+        {
+            let event_hub = AppState::global(cx).event_hub().clone();
+            let weak_entity = entity.downgrade();
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+
+            event_hub.subscribe_active_agent_changes(move |agent_name| {
+                let _ = tx.send(agent_name.to_string());
+            });
+
+            cx.spawn(async move |cx| {
+                while let Some(agent_name) = rx.recv().await {
+                    let Some(entity) = weak_entity.upgrade() else {
+                        break;
+                    };
+                    _ = cx.update(|cx| {
+                        entity.update(cx, |this, cx| {
+                            this.pending_active_agent = Some(agent_name);
+                            cx.notify();
+                        });
+                    });
+                }
+            })
+            .detach();
+        }
+
         // Subscribe to agent_select focus to refresh agents list when no agents available
         entity.update(cx, |this, cx| {
             // Subscribe to input changes to detect @ symbol
@@ -280,6 +370,7 @@ impl WelcomePanel {
 
         // Load workspace info immediately and refresh on each panel creation
         Self::load_workspace_info(&entity, workspace_id.as_deref(), cx);
+        Self::load_recent_projects(&entity, cx);
 
         entity
     }
@@ -349,6 +440,177 @@ impl WelcomePanel {
         .detach();
     }
 
+    /// Load recently opened workspaces, then resolve their git state in the background
+    fn load_recent_projects(entity: &Entity<Self>, cx: &mut App) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+
+        let weak_entity = entity.downgrade();
+        cx.spawn(async move |cx| {
+            let mut projects = recent_projects(&workspace_service, MAX_RECENT_PROJECTS).await;
+            _ = cx.update(|cx| {
+                if let Some(entity) = weak_entity.upgrade() {
+                    entity.update(cx, |this, cx| {
+                        this.recent_projects = projects.clone();
+                        cx.notify();
+                    });
+                }
+            });
+
+            let paths: Vec<_> = projects.iter().map(|p| p.path.clone()).collect();
+            let summaries =
+                smol::unblock(move || paths.iter().map(|p| git_summary(p)).collect::<Vec<_>>())
+                    .await;
+            for (project, git) in projects.iter_mut().zip(summaries) {
+                project.git = git;
+            }
+
+            _ = cx.update(|cx| {
+                if let Some(entity) = weak_entity.upgrade() {
+                    entity.update(cx, |this, cx| {
+                        this.recent_projects = projects;
+                        cx.notify();
+                    });
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Switch to a recent workspace and open it the same way the task list does
+    fn open_recent_project(
+        &mut self,
+        project: &RecentProject,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        AppState::global_mut(cx).set_current_working_dir(project.path.clone());
+
+        if let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() {
+            let workspace_id = project.workspace_id.clone();
+            cx.spawn(async move |_this, _cx| {
+                if let Err(e) = workspace_service.set_active_workspace(&workspace_id).await {
+                    log::error!("Failed to activate workspace {}: {}", workspace_id, e);
+                }
+            })
+            .detach();
+        }
+
+        window.dispatch_action(
+            Box::new(PanelAction::show_welcome(Some(
+                project.workspace_id.clone(),
+            ))),
+            cx,
+        );
+    }
+
+    fn reveal_project(path: &std::path::Path, window: &mut Window, cx: &mut App) {
+        if let Err(e) = crate::utils::file::reveal_in_file_manager(path) {
+            log::error!("Failed to reveal {}: {:#}", path.display(), e);
+            window.push_notification(Notification::error(format!("{:#}", e)), cx);
+        }
+    }
+
+    fn render_recent_projects(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .px(px(32.))
+            .child(
+                gpui::div()
+                    .text_sm()
+                    .font_medium()
+                    .text_color(theme.muted_foreground)
+                    .child(t!("welcome.recent.title").to_string()),
+            )
+            .child(
+                h_flex().w_full().flex_wrap().gap_2().children(
+                    self.recent_projects
+                        .iter()
+                        .enumerate()
+                        .map(|(ix, project)| {
+                            let branch = project.git.as_ref().and_then(|git| git.branch.clone());
+                            let dirty = project.git.as_ref().is_some_and(|git| git.dirty);
+                            let opened = crate::utils::time::format_relative(
+                                project.last_accessed,
+                                std::time::SystemTime::now(),
+                            );
+
+                            v_flex()
+                                .id(("recent-project", ix))
+                                .w(px(176.))
+                                .gap_1()
+                                .px_3()
+                                .py_2()
+                                .rounded(theme.radius)
+                                .border_1()
+                                .border_color(theme.border)
+                                .cursor_pointer()
+                                .hover(|s| s.bg(theme.accent.opacity(0.3)))
+                                .on_click(cx.listener({
+                                    let project = project.clone();
+                                    move |this, _, window, cx| {
+                                        this.open_recent_project(&project, window, cx);
+                                    }
+                                }))
+                                .child(
+                                    h_flex()
+                                        .w_full()
+                                        .justify_between()
+                                        .gap_1()
+                                        .child(
+                                            gpui::div()
+                                                .text_sm()
+                                                .font_medium()
+                                                .text_color(theme.foreground)
+                                                .truncate()
+                                                .child(project.name.clone()),
+                                        )
+                                        .child(
+                                            Button::new(("reveal-recent-project", ix))
+                                                .icon(IconName::FolderOpen)
+                                                .ghost()
+                                                .xsmall()
+                                                .tooltip(t!("welcome.recent.reveal").to_string())
+                                                .on_click({
+                                                    let path = project.path.clone();
+                                                    move |_, window, cx| {
+                                                        cx.stop_propagation();
+                                                        Self::reveal_project(&path, window, cx);
+                                                    }
+                                                }),
+                                        ),
+                                )
+                                .child(
+                                    gpui::div()
+                                        .text_xs()
+                                        .text_color(theme.muted_foreground)
+                                        .truncate()
+                                        .child(project.path.display().to_string()),
+                                )
+                                .child(
+                                    h_flex()
+                                        .gap_2()
+                                        .text_xs()
+                                        .text_color(theme.muted_foreground)
+                                        .child(opened)
+                                        .when_some(branch, |this, branch| this.child(branch))
+                                        .when(dirty, |this| {
+                                            this.child(
+                                                gpui::div()
+                                                    .text_color(theme.warning)
+                                                    .child(t!("welcome.recent.dirty").to_string()),
+                                            )
+                                        }),
+                                )
+                        }),
+                ),
+            )
+    }
+
     fn new(
         workspace_id: Option<String>,
         working_directory: Option<std::path::PathBuf>,
@@ -401,6 +663,7 @@ impl WelcomePanel {
             session_select,
             current_agent_name: None,
             current_session_id: None,
+            draft_task_id: format!("welcome-{}", uuid::Uuid::new_v4()),
             has_agents,
             has_modes: false,
             has_models: false,
@@ -409,12 +672,14 @@ impl WelcomePanel {
             active_workspace_name: None,
             workspace_id,
             working_directory: working_dir,
+            recent_projects: Vec::new(),
             pasted_images: Vec::new(),
             code_selections: Vec::new(),
             selected_files: Vec::new(),
             file_suggestions: Vec::new(),
             last_file_query: String::new(),
             command_suggestions: Vec::new(),
+            configured_commands: Vec::new(),
             show_command_suggestions: false,
             _subscriptions: Vec::new(),
             available_mcps: Vec::new(),
@@ -422,6 +687,7 @@ impl WelcomePanel {
             mcp_selection_initialized: false,
             mcp_selection_overridden: false,
             pending_mcp_session_recreate: false,
+            pending_active_agent: None,
         };
 
         // Load sessions for the initially selected agent if any
@@ -431,8 +697,9 @@ impl WelcomePanel {
             }
         }
 
-        // Load MCP servers asynchronously
+        // Load MCP servers and custom commands asynchronously
         panel.load_mcp_servers(cx);
+        panel.load_configured_commands(cx);
 
         panel
     }
@@ -462,6 +729,31 @@ impl WelcomePanel {
         .detach();
     }
 
+    /// Load custom commands from AgentConfigService
+    fn load_configured_commands(&mut self, cx: &mut Context<Self>) {
+        let agent_config_service = match AppState::global(cx).agent_config_service() {
+            Some(service) => service.clone(),
+            None => return,
+        };
+
+        let weak_self = cx.entity().downgrade();
+        cx.spawn(async move |_this, cx| {
+            let commands = agent_config_service.list_commands().await;
+
+            _ = cx.update(|cx| {
+                if let Some(this) = weak_self.upgrade() {
+                    this.update(cx, |this, _cx| {
+                        this.configured_commands = commands
+                            .into_iter()
+                            .map(|(name, config)| AvailableCommand::new(name, config.description))
+                            .collect();
+                    });
+                }
+            });
+        })
+        .detach();
+    }
+
     fn sync_mcp_selection_with_available(&mut self) {
         let enabled_mcps = self
             .available_mcps
@@ -527,11 +819,17 @@ impl WelcomePanel {
         };
 
         let agent_select = self.agent_select.clone();
-        let current_selection = self.agent_select.read(cx).selected_value().cloned();
+        // New conversations start on the active agent
+        let current_selection = self
+            .agent_select
+            .read(cx)
+            .selected_value()
+            .cloned()
+            .or_else(|| AppState::global(cx).active_agent().map(str::to_string));
         let no_agents_label = Self::no_agents_label();
         let weak_self = cx.entity().downgrade();
         cx.spawn_in(window, async move |_this, window| {
-            let agents = agent_service.list_agents().await;
+            let agents = agent_service.list_agents_detailed().await;
 
             _ = window.update(|window, cx| {
                 if let Some(this) = weak_self.upgrade() {
@@ -551,15 +849,14 @@ impl WelcomePanel {
                         }
 
                         this.has_agents = true;
-                        let agent_items: Vec<AgentItem> = agents
-                            .clone()
-                            .into_iter()
-                            .map(|name| AgentItem::new(name))
-                            .collect();
                         let selected_index = current_selection
                             .as_ref()
-                            .and_then(|name| agents.iter().position(|agent| agent == name))
+                            .and_then(|name| agents.iter().position(|agent| &agent.name == name))
                             .unwrap_or(0);
+                        let agent_items: Vec<AgentItem> = agents
+                            .into_iter()
+                            .map(|agent| AgentItem::new(agent.name).status(agent.status))
+                            .collect();
                         agent_select.update(cx, |state, cx| {
                             state.set_items(agent_items, window, cx);
                             state.set_selected_index(
@@ -620,13 +917,19 @@ impl WelcomePanel {
                 log::info!("[WelcomePanel] MCP server removed: {}", name);
                 self.remove_mcp_server(name);
             }
+            AgentConfigEvent::AgentStatusChanged { name, .. } => {
+                log::debug!("[WelcomePanel] Agent status changed: {}", name);
+                // Refresh so the selector's health dots stay current
+                self.has_agents = false;
+            }
             // Model and Command events don't affect WelcomePanel
             AgentConfigEvent::ModelAdded { .. }
             | AgentConfigEvent::ModelUpdated { .. }
             | AgentConfigEvent::ModelRemoved { .. }
             | AgentConfigEvent::CommandAdded { .. }
             | AgentConfigEvent::CommandUpdated { .. }
-            | AgentConfigEvent::CommandRemoved { .. } => {
+            | AgentConfigEvent::CommandRemoved { .. }
+            | AgentConfigEvent::SaveFailed { .. } => {
                 // No action needed for non-agent config changes
             }
         }
@@ -660,6 +963,7 @@ impl WelcomePanel {
         }
 
         self.current_agent_name = Some(agent_name.clone());
+        AppState::set_active_agent(agent_name.clone(), cx);
         self.begin_session_recreate(agent_name, window, cx);
     }
 
@@ -833,12 +1137,10 @@ impl WelcomePanel {
             }
         };
 
-        // Get the agent service to access agent manager
-        let app_state = AppState::global(cx);
-        let agent_manager = match app_state.agent_manager() {
-            Some(manager) => manager.clone(),
+        let agent_service = match AppState::global(cx).agent_service() {
+            Some(service) => service.clone(),
             None => {
-                log::error!("[WelcomePanel] Cannot change model: agent manager not available");
+                log::error!("[WelcomePanel] Cannot change model: agent service not available");
                 return;
             }
         };
@@ -849,33 +1151,28 @@ impl WelcomePanel {
             session_id
         );
 
-        // Send SetSessionModel command to agent asynchronously
-        cx.spawn(async move |_entity, _cx| {
-            let agent_handle = match agent_manager.get(&agent_name).await {
-                Some(handle) => handle,
-                None => {
-                    log::error!(
-                        "[WelcomePanel] Cannot change model: agent '{}' not found",
-                        agent_name
-                    );
-                    return;
-                }
-            };
+        let workspace_service = AppState::global(cx).workspace_service().cloned();
 
-            use agent_client_protocol as acp;
-            let mut request = acp::SetSessionModelRequest::new(
-                acp::SessionId::from(session_id.clone()),
-                model_id.clone(),
-            );
-            request.meta = None;
-
-            match agent_handle.set_session_model(request).await {
-                Ok(response) => {
+        // Send SetSessionModel command to agent and remember the choice on the session
+        cx.spawn(async move |_entity, _cx| {
+            match agent_service
+                .set_session_model(&agent_name, &session_id, &model_id)
+                .await
+            {
+                Ok(()) => {
+                    // Draft sessions have no task yet; the model is saved when one is created
+                    if let Some(workspace_service) = workspace_service {
+                        if let Err(e) = workspace_service
+                            .set_session_model(&session_id, &model_id)
+                            .await
+                        {
+                            log::debug!("[WelcomePanel] Model not saved on a task: {}", e);
+                        }
+                    }
                     log::info!(
-                        "[WelcomePanel] Successfully set session model to '{}' for session '{}': {:?}",
+                        "[WelcomePanel] Successfully set session model to '{}' for session '{}'",
                         model_id,
-                        session_id,
-                        response
+                        session_id
                     );
                 }
                 Err(e) => {
@@ -933,22 +1230,12 @@ impl WelcomePanel {
             // Get available commands for the current session
             let all_commands = self.get_available_commands(cx);
 
-            // Filter commands by prefix
-            if command_prefix.is_empty() {
-                // Show all commands when just "/" is entered
-                self.command_suggestions = all_commands;
-                self.show_command_suggestions = !self.command_suggestions.is_empty();
-            } else {
-                // Filter commands that start with the prefix
-                self.command_suggestions = all_commands
-                    .into_iter()
-                    .filter(|cmd| cmd.name.starts_with(command_prefix))
-                    .collect();
-                self.show_command_suggestions = !self.command_suggestions.is_empty();
-            }
+            // Fuzzy-filter commands; just "/" shows all of them
+            self.command_suggestions = filter_commands(command_prefix, all_commands);
+            self.show_command_suggestions = !self.command_suggestions.is_empty();
 
             log::debug!(
-                "[WelcomePanel] Command suggestions: {} matches for prefix '{}'",
+                "[WelcomePanel] Command suggestions: {} matches for query '{}'",
                 self.command_suggestions.len(),
                 command_prefix
             );
@@ -1069,8 +1356,22 @@ impl WelcomePanel {
         cx.notify();
     }
 
-    /// Get available commands for the current session
+    /// Get available commands: the current session's agent commands followed
+    /// by configured custom commands not shadowed by them
     fn get_available_commands(&self, cx: &Context<Self>) -> Vec<AvailableCommand> {
+        let mut commands = self.get_session_commands(cx);
+        let configured = self
+            .configured_commands
+            .iter()
+            .filter(|configured| !commands.iter().any(|cmd| cmd.name == configured.name))
+            .cloned()
+            .collect::<Vec<_>>();
+        commands.extend(configured);
+        commands
+    }
+
+    /// Get commands advertised by the agent for the current session
+    fn get_session_commands(&self, cx: &Context<Self>) -> Vec<AvailableCommand> {
         // Get the current session ID
         let session_id = match &self.current_session_id {
             Some(id) => id,
@@ -1148,19 +1449,10 @@ impl WelcomePanel {
             .and_then(|info| info.new_session_response.as_ref())
             .and_then(|response| response.models.as_ref())
             .map(|models| {
-                let items = models
-                    .available_models
-                    .iter()
-                    .map(|model| {
-                        let label = if model.name.is_empty() {
-                            model.model_id.to_string()
-                        } else {
-                            model.name.clone()
-                        };
-                        ModelSelectItem::new(model.model_id.to_string(), label)
-                    })
-                    .collect::<Vec<_>>();
-                (items, Some(models.current_model_id.to_string()))
+                (
+                    ModelSelectItem::from_session_models(models),
+                    Some(models.current_model_id.to_string()),
+                )
             })
             .unwrap_or_else(|| (Vec::new(), None));
 
@@ -1259,6 +1551,10 @@ impl WelcomePanel {
     ) {
         self.pending_mcp_session_recreate = false;
         self.is_session_loading = true;
+        // A new session is wanted, not the one made for the last request
+        if let Some(agent_service) = AppState::global(cx).agent_service() {
+            agent_service.forget_task(&self.draft_task_id);
+        }
         self.current_session_id = None;
         AppState::global_mut(cx).clear_welcome_session();
         self.session_select.update(cx, |state, cx| {
@@ -1287,6 +1583,7 @@ impl WelcomePanel {
         let selected_mcps = self.selected_mcps.clone();
         let mcp_selection_initialized = self.mcp_selection_initialized;
         let cwd = self.working_directory.clone(); // 使用面板的工作目录
+        let draft_task_id = self.draft_task_id.clone();
 
         let weak_self = cx.entity().downgrade();
         let agent_name_for_session = agent_name.clone();
@@ -1312,10 +1609,15 @@ impl WelcomePanel {
             );
 
             match agent_service
-                .create_session_with_mcp_and_cwd(&agent_name_for_session, mcp_servers, cwd.clone())
+                .create_session_for_task(
+                    &draft_task_id,
+                    &agent_name_for_session,
+                    mcp_servers,
+                    cwd.clone(),
+                )
                 .await
             {
-                Ok(session_id) => {
+                Ok(AgentSessionInfo { session_id, .. }) => {
                     log::info!("[WelcomePanel] Created new session: {}", session_id);
                     _ = window.update(|window, cx| {
                         if let Some(this) = weak_self.upgrade() {
@@ -1519,6 +1821,14 @@ impl Render for WelcomePanel {
                 self.begin_session_recreate(agent_name, window, cx);
             }
         }
+        if let Some(agent_name) = self.pending_active_agent.take() {
+            if self.current_agent_name.as_ref() != Some(&agent_name) {
+                self.agent_select.update(cx, |state, cx| {
+                    state.set_selected_value(&agent_name, window, cx);
+                });
+                self.on_agent_changed(window, cx);
+            }
+        }
 
         v_flex()
             .size_full()
@@ -1689,7 +1999,46 @@ impl Render for WelcomePanel {
                                     this.handle_send_task(window, cx);
                                 }))
                         },
-                    ),
+                    )
+                    .when(!self.recent_projects.is_empty(), |this| {
+                        this.child(self.render_recent_projects(cx))
+                    }),
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_projects_list_most_recently_opened_first() {
+        let root = std::env::temp_dir().join(format!("agentx_recent_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let dirs: Vec<_> = ["alpha", "beta", "gamma"]
+            .iter()
+            .map(|name| root.join(name))
+            .collect();
+        for dir in &dirs {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+
+        let service = WorkspaceService::new(root.join("workspaces.json"));
+        let projects = smol::block_on(async {
+            let mut ids = Vec::new();
+            for dir in &dirs {
+                ids.push(service.add_workspace(dir.clone()).await.unwrap().id);
+            }
+            // Opening alpha again makes it the most recent
+            service.set_active_workspace(&ids[0]).await.unwrap();
+            recent_projects(&service, 2).await
+        });
+
+        let names: Vec<_> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "gamma"]);
+        assert_eq!(projects[0].path, dirs[0]);
+        assert!(projects.iter().all(|p| p.git.is_none()));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
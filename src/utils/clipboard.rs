@@ -1,5 +1,10 @@
 use agent_client_protocol::ImageContent;
-use gpui::{Image, ImageFormat};
+use gpui::{App, ClipboardItem, Image, ImageFormat};
+
+/// Put plain text on the system clipboard
+pub fn copy_text(text: impl Into<String>, cx: &mut App) {
+    cx.write_to_clipboard(ClipboardItem::new_string(text.into()));
+}
 
 pub async fn image_to_content(image: Image) -> anyhow::Result<(ImageContent, String)> {
     let temp_path = crate::utils::file::write_image_to_temp_file(&image).await?;
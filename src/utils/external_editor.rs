@@ -178,25 +178,3 @@ pub fn open_with_editor(path: &Path, command: &str, editor_name: &str) -> Result
     log::info!("Opened {:?} in {}", path, editor_name);
     Ok(())
 }
-
-/// Open a folder in the system file manager
-pub fn open_in_file_manager(path: &Path) -> Result<(), String> {
-    log::info!("Opening {:?} in file manager", path);
-
-    #[cfg(target_os = "macos")]
-    let command = "open";
-
-    #[cfg(target_os = "windows")]
-    let command = "explorer";
-
-    #[cfg(target_os = "linux")]
-    let command = "xdg-open";
-
-    Command::new(command)
-        .arg(path)
-        .spawn()
-        .map_err(|e| format!("Failed to open file manager: {}", e))?;
-
-    log::info!("Opened {:?} in file manager", path);
-    Ok(())
-}
@@ -1,5 +1,9 @@
+use anyhow::{Context as _, bail};
 use gpui::Image;
+use std::ffi::OsString;
 use std::fs::File;
+use std::path::Path;
+use std::process::Command;
 
 pub async fn write_image_to_temp_file(image: &Image) -> anyhow::Result<String> {
     let image_bytes = image.bytes();
@@ -53,3 +57,109 @@ pub async fn write_image_to_temp_file(image: &Image) -> anyhow::Result<String> {
         Ok(temp_file.to_string_lossy().to_string())
     }
 }
+
+/// Open the platform file manager at `path`. Directories are opened
+/// directly; files are selected in their parent folder where the platform
+/// supports it.
+pub fn reveal_in_file_manager(path: &Path) -> anyhow::Result<()> {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            bail!("{} does not exist", path.display())
+        }
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+
+    let (program, args) = reveal_command(path, metadata.is_dir());
+    log::info!("Revealing {:?} with {}", path, program);
+    Command::new(program)
+        .args(&args)
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+    Ok(())
+}
+
+/// Program and arguments that reveal `path` in the file manager
+#[cfg(target_os = "macos")]
+fn reveal_command(path: &Path, is_dir: bool) -> (&'static str, Vec<OsString>) {
+    if is_dir {
+        ("open", vec![path.into()])
+    } else {
+        ("open", vec!["-R".into(), path.into()])
+    }
+}
+
+/// Program and arguments that reveal `path` in the file manager
+#[cfg(target_os = "windows")]
+fn reveal_command(path: &Path, is_dir: bool) -> (&'static str, Vec<OsString>) {
+    if is_dir {
+        ("explorer", vec![path.into()])
+    } else {
+        let mut select = OsString::from("/select,");
+        select.push(path);
+        ("explorer", vec![select])
+    }
+}
+
+/// Program and arguments that reveal `path` in the file manager. There is no
+/// portable way to select a file, so files open their parent folder.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn reveal_command(path: &Path, is_dir: bool) -> (&'static str, Vec<OsString>) {
+    let folder = if is_dir {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+    ("xdg-open", vec![folder.into()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn reveal_command_selects_files_in_finder() {
+        let (program, args) = reveal_command(Path::new("/tmp/project"), true);
+        assert_eq!(program, "open");
+        assert_eq!(args, [OsString::from("/tmp/project")]);
+
+        let (program, args) = reveal_command(Path::new("/tmp/project/main.rs"), false);
+        assert_eq!(program, "open");
+        assert_eq!(
+            args,
+            [OsString::from("-R"), OsString::from("/tmp/project/main.rs")]
+        );
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn reveal_command_selects_files_in_explorer() {
+        let (program, args) = reveal_command(Path::new(r"C:\project"), true);
+        assert_eq!(program, "explorer");
+        assert_eq!(args, [OsString::from(r"C:\project")]);
+
+        let (program, args) = reveal_command(Path::new(r"C:\project\main.rs"), false);
+        assert_eq!(program, "explorer");
+        assert_eq!(args, [OsString::from(r"/select,C:\project\main.rs")]);
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    #[test]
+    fn reveal_command_opens_the_containing_folder() {
+        let (program, args) = reveal_command(Path::new("/tmp/project"), true);
+        assert_eq!(program, "xdg-open");
+        assert_eq!(args, [OsString::from("/tmp/project")]);
+
+        let (program, args) = reveal_command(Path::new("/tmp/project/main.rs"), false);
+        assert_eq!(program, "xdg-open");
+        assert_eq!(args, [OsString::from("/tmp/project")]);
+    }
+
+    #[test]
+    fn revealing_a_missing_path_fails_without_launching() {
+        let missing = std::env::temp_dir().join("agentx-reveal-missing/nothing-here");
+        let error = reveal_in_file_manager(&missing).unwrap_err();
+        assert!(error.to_string().contains("does not exist"));
+    }
+}
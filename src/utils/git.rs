@@ -0,0 +1,49 @@
+/// Utilities for reading the git state of a project folder
+use std::path::Path;
+use std::process::Command;
+
+/// Branch and working tree state of a git repository
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitSummary {
+    /// Current branch, `None` for a detached HEAD
+    pub branch: Option<String>,
+    /// Whether the working tree has uncommitted changes
+    pub dirty: bool,
+}
+
+/// Read the branch and dirty state of the repository at `path`.
+///
+/// Returns `None` when `path` is not inside a git repository or git is not installed.
+pub fn git_summary(path: &Path) -> Option<GitSummary> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["status", "--porcelain", "--branch"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(parse_status(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse the output of `git status --porcelain --branch`
+fn parse_status(output: &str) -> GitSummary {
+    let mut lines = output.lines();
+    let branch = lines
+        .next()
+        .and_then(|line| line.strip_prefix("## "))
+        .and_then(|header| {
+            if header.starts_with("HEAD (no branch)") {
+                return None;
+            }
+            let header = header.strip_prefix("No commits yet on ").unwrap_or(header);
+            let name = header.split("...").next().unwrap_or(header);
+            name.split_whitespace().next().map(str::to_string)
+        });
+    let dirty = lines.any(|line| !line.trim().is_empty());
+
+    GitSummary { branch, dirty }
+}
@@ -1,6 +1,7 @@
 pub mod clipboard;
 pub mod external_editor;
 pub mod file;
+pub mod git;
 pub mod time;
 pub mod tool_call;
 /// Open a folder picker dialog and return the selected path
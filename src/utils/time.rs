@@ -1,17 +1,43 @@
 use chrono::{DateTime, Local, TimeZone};
+use rust_i18n::t;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-pub fn format_time_friendly<T: TimeZone>(time: &DateTime<T>) -> String {
-    let now = Local::now();
-    let time_local = time.with_timezone(&Local);
-    let duration = now.signed_duration_since(time_local);
+/// Format `then` relative to `now` in the current locale, e.g. "Just now",
+/// "5 minutes ago", "Yesterday" or "3 weeks ago". Times in the future are
+/// treated as "Just now".
+pub fn format_relative(then: SystemTime, now: SystemTime) -> String {
+    let secs = now
+        .duration_since(then)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
 
-    if duration.num_days() > 0 {
-        time_local.format("%m/%d").to_string()
-    } else if duration.num_hours() > 0 {
-        time_local.format("%H:%M").to_string()
+    let minutes = secs / 60;
+    let hours = minutes / 60;
+    let days = hours / 24;
+
+    if minutes < 1 {
+        t!("task_panel.time.just_now").to_string()
+    } else if minutes < 60 {
+        t!("task_panel.time.minutes_ago", minutes = minutes).to_string()
+    } else if hours < 24 {
+        t!("task_panel.time.hours_ago", hours = hours).to_string()
+    } else if days == 1 {
+        t!("task_panel.time.yesterday").to_string()
+    } else if days == 2 {
+        t!("task_panel.time.day_before_yesterday").to_string()
+    } else if days < 7 {
+        t!("task_panel.time.days_ago", days = days).to_string()
+    } else if days < 30 {
+        let weeks = days / 7;
+        if weeks == 1 {
+            t!("task_panel.time.one_week_ago").to_string()
+        } else {
+            t!("task_panel.time.weeks_ago", weeks = weeks).to_string()
+        }
+    } else if days < 365 {
+        t!("task_panel.time.months_ago", months = days / 30).to_string()
     } else {
-        time_local.format("%H:%M").to_string()
+        t!("task_panel.time.years_ago", years = days / 365).to_string()
     }
 }
 
@@ -25,3 +51,58 @@ pub fn now_millis() -> u128 {
         .expect("Time went backwards")
         .as_millis()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+
+    fn relative(secs_ago: u64) -> String {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000 * DAY);
+        format_relative(now - Duration::from_secs(secs_ago), now)
+    }
+
+    #[test]
+    fn format_relative_seconds_are_just_now() {
+        assert_eq!(relative(0), "Just now");
+        assert_eq!(relative(59), "Just now");
+    }
+
+    #[test]
+    fn format_relative_minutes_and_hours() {
+        assert_eq!(relative(MINUTE), "1 minutes ago");
+        assert_eq!(relative(59 * MINUTE + 59), "59 minutes ago");
+        assert_eq!(relative(HOUR), "1 hours ago");
+        assert_eq!(relative(DAY - 1), "23 hours ago");
+    }
+
+    #[test]
+    fn format_relative_days() {
+        assert_eq!(relative(DAY), "Yesterday");
+        assert_eq!(relative(2 * DAY), "The day before yesterday");
+        assert_eq!(relative(3 * DAY), "3 days ago");
+        assert_eq!(relative(7 * DAY - 1), "6 days ago");
+    }
+
+    #[test]
+    fn format_relative_weeks_months_and_years() {
+        assert_eq!(relative(7 * DAY), "A week ago");
+        assert_eq!(relative(29 * DAY), "4 weeks ago");
+        assert_eq!(relative(30 * DAY), "1 months ago");
+        assert_eq!(relative(364 * DAY), "12 months ago");
+        assert_eq!(relative(2 * 365 * DAY), "2 years ago");
+    }
+
+    #[test]
+    fn format_relative_future_is_just_now() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000 * DAY);
+        assert_eq!(
+            format_relative(now + Duration::from_secs(30 * DAY), now),
+            "Just now"
+        );
+    }
+}
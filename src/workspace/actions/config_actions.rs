@@ -3,8 +3,8 @@ use gpui::*;
 use crate::{
     AppState,
     app::actions::{
-        AddAgent, ChangeConfigPath, ReloadAgentConfig, RemoveAgent, RestartAgent, SetUploadDir,
-        UpdateAgent,
+        AddAgent, ChangeConfigPath, ReloadAgentConfig, RemoveAgent, RestartAgent, SetAgentIcon,
+        SetAgentsEnabled, SetUploadDir, UpdateAgent,
     },
 };
 
@@ -19,6 +19,7 @@ pub fn add_agent(action: &AddAgent, cx: &mut App) {
 
     let name = action.name.clone();
     let config = crate::core::config::AgentProcessConfig {
+        enabled: true,
         command: action.command.clone(),
         args: action.args.clone(),
         env: action.env.clone(),
@@ -50,6 +51,7 @@ pub fn update_agent(action: &UpdateAgent, cx: &mut App) {
 
     let name = action.name.clone();
     let config = crate::core::config::AgentProcessConfig {
+        enabled: true,
         command: action.command.clone(),
         args: action.args.clone(),
         env: action.env.clone(),
@@ -126,6 +128,51 @@ pub fn restart_agent(action: &RestartAgent, cx: &mut App) {
         .detach();
 }
 
+pub fn set_agents_enabled(action: &SetAgentsEnabled, cx: &mut App) {
+    let agent_config_service = match AppState::global(cx).agent_config_service() {
+        Some(service) => service.clone(),
+        None => {
+            log::error!("AgentConfigService not initialized");
+            return;
+        }
+    };
+
+    let names = action.names.clone();
+    let enabled = action.enabled;
+
+    let _ = cx
+        .spawn(async move |_cx| {
+            if let Err(e) = agent_config_service
+                .set_agents_enabled(&names, enabled)
+                .await
+            {
+                log::error!("Failed to update agents {:?}: {}", names, e);
+            }
+        })
+        .detach();
+}
+
+pub fn set_agent_icon(action: &SetAgentIcon, cx: &mut App) {
+    let agent_config_service = match AppState::global(cx).agent_config_service() {
+        Some(service) => service.clone(),
+        None => {
+            log::error!("AgentConfigService not initialized");
+            return;
+        }
+    };
+
+    let name = action.name.clone();
+    let icon = action.icon.clone();
+
+    let _ = cx
+        .spawn(async move |_cx| {
+            if let Err(e) = agent_config_service.set_agent_icon(&name, icon).await {
+                log::error!("Failed to set icon for agent '{}': {}", name, e);
+            }
+        })
+        .detach();
+}
+
 pub fn reload_agent_config(_action: &ReloadAgentConfig, cx: &mut App) {
     let agent_config_service = match AppState::global(cx).agent_config_service() {
         Some(service) => service.clone(),
@@ -184,7 +231,7 @@ pub fn change_config_path(action: &ChangeConfigPath, cx: &mut App) {
 
     let config_result = std::fs::read_to_string(&new_path);
     match config_result {
-        Ok(json) => match serde_json::from_str::<crate::core::config::Config>(&json) {
+        Ok(json) => match crate::core::config_manager::parse_config(&json) {
             Ok(_config) => {
                 log::info!("Config file validated successfully: {:?}", new_path);
 
@@ -209,7 +256,7 @@ pub fn change_config_path(action: &ChangeConfigPath, cx: &mut App) {
                 }
             }
             Err(e) => {
-                log::error!("Invalid config file format: {}", e);
+                log::error!("Invalid config file format: {:#}", e);
             }
         },
         Err(e) => {
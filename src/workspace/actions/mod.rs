@@ -3,3 +3,4 @@ mod panel_actions;
 mod session_actions;
 
 pub use config_actions::*;
+pub use panel_actions::{OpenPanel, find_session_panel};
@@ -1,14 +1,26 @@
 use gpui::*;
 use gpui_component::{
     WindowExt,
+    dialog::DialogButtonProps,
     dock::{DockItem, DockPlacement, Panel, PanelInfo, PanelState, PanelView, TabPanel},
+    input::{Input, InputState},
+    list::{List, ListState},
+    notification::Notification,
+    v_flex,
 };
-use std::sync::Arc;
+use rust_i18n::t;
+use std::{rc::Rc, sync::Arc};
 
 use crate::{
-    AppState, ConversationPanel, OpenSessionManager, PanelAction, SessionManagerPanel,
-    SettingsPanel, ToggleDockToggleButton, TogglePanelVisible, WelcomePanel,
-    app::actions::{PanelCommand, PanelKind, Submit},
+    AppState, ApplyLayoutPreset, CloseConversationPanels, ConversationPanel, ExportLayout,
+    ImportLayout, OpenSessionManager, PanelAction, ReopenStartup, SaveLayoutPreset,
+    SessionManagerPanel, SettingsPanel, ToggleCommandPalette, ToggleDockToggleButton,
+    TogglePanelVisible, WelcomePanel,
+    app::{
+        actions::{PanelCommand, PanelKind, Submit},
+        command_palette::palette_commands,
+    },
+    components::{CommandPaletteDelegate, PaletteConfirmHandler},
     panels::{
         DockPanel,
         dock_panel::{DockPanelContainer, DockPanelState},
@@ -26,6 +38,51 @@ struct SerializedDockState {
     open: bool,
 }
 
+/// A panel open in the workspace, as recorded in the dock layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenPanel {
+    /// Kind of view the container hosts, e.g. "ConversationPanel"
+    pub klass: SharedString,
+    pub session_id: Option<String>,
+}
+
+/// Index of the conversation panel showing `session_id`, if one is open
+pub fn find_session_panel(panels: &[OpenPanel], session_id: &str) -> Option<usize> {
+    panels.iter().position(|panel| {
+        panel.klass.as_ref() == ConversationPanel::klass()
+            && panel.session_id.as_deref() == Some(session_id)
+    })
+}
+
+/// Indices of the conversation panels to close. With `idle_only`, panels
+/// whose session is in the middle of a turn stay open.
+pub fn conversation_panels_to_close(
+    panels: &[OpenPanel],
+    idle_only: bool,
+    is_busy: impl Fn(&str) -> bool,
+) -> Vec<usize> {
+    panels
+        .iter()
+        .enumerate()
+        .filter(|(_, panel)| panel.klass.as_ref() == ConversationPanel::klass())
+        .filter(|(_, panel)| !idle_only || !panel.session_id.as_deref().is_some_and(&is_busy))
+        .map(|(ix, _)| ix)
+        .collect()
+}
+
+fn collect_open_panels(state: &PanelState, panels: &mut Vec<OpenPanel>) {
+    if let PanelInfo::Panel(value) = &state.info {
+        let dock_state = DockPanelState::from_value(value.clone());
+        panels.push(OpenPanel {
+            klass: dock_state.agent_studio_klass,
+            session_id: dock_state.session_id,
+        });
+    }
+    for child in &state.children {
+        collect_open_panels(child, panels);
+    }
+}
+
 impl DockWorkspace {
     pub(in crate::workspace) fn submit(
         &mut self,
@@ -55,7 +112,7 @@ impl DockWorkspace {
         cx: &mut Context<Self>,
     ) {
         if let Some(session_id) = session_id.as_deref().filter(|id| !id.is_empty()) {
-            if self.activate_existing_session_panel(session_id, window, cx) {
+            if self.focus_panel_for_session(session_id, window, cx) {
                 return;
             }
 
@@ -107,10 +164,18 @@ impl DockWorkspace {
 
     pub(in crate::workspace) fn resume_session_if_needed(session_id: &str, cx: &mut Context<Self>) {
         let agent_service = AppState::global(cx).agent_service().cloned();
+        let workspace_service = AppState::global(cx).workspace_service().cloned();
         if let Some(agent_service) = agent_service {
             let session_id_clone = session_id.to_string();
             cx.spawn(async move |_this, _cx| {
                 if let Some(agent_name) = agent_service.get_agent_for_session(&session_id_clone) {
+                    let saved_model = match &workspace_service {
+                        Some(service) => service
+                            .get_task_by_session(&session_id_clone)
+                            .await
+                            .and_then(|task| task.selected_model),
+                        None => None,
+                    };
                     log::info!(
                         "Resuming session {} for agent {}",
                         session_id_clone,
@@ -122,6 +187,9 @@ impl DockWorkspace {
                     {
                         Ok(_) => {
                             log::info!("Successfully resumed session {}", session_id_clone);
+                            agent_service
+                                .restore_session_model(&agent_name, &session_id_clone, saved_model)
+                                .await;
                         }
                         Err(e) => {
                             log::warn!("Failed to resume session {}: {}", session_id_clone, e);
@@ -175,7 +243,27 @@ impl DockWorkspace {
         }
     }
 
-    fn activate_existing_session_panel(
+    /// Panels open in the center and in every dock, in layout order
+    pub fn open_panels(&self, cx: &App) -> Vec<OpenPanel> {
+        let state = self.dock_area.read(cx).dump(cx);
+        let mut panels = Vec::new();
+        collect_open_panels(&state.center, &mut panels);
+        for dock in [state.left_dock, state.right_dock, state.bottom_dock]
+            .into_iter()
+            .flatten()
+        {
+            if let Ok(dock) =
+                serde_json::to_value(dock).and_then(serde_json::from_value::<SerializedDockState>)
+            {
+                collect_open_panels(&dock.panel, &mut panels);
+            }
+        }
+        panels
+    }
+
+    /// Activate and focus the center panel already showing `session_id`.
+    /// Returns false when there is none, so the caller can open one.
+    pub fn focus_panel_for_session(
         &mut self,
         session_id: &str,
         window: &mut Window,
@@ -224,7 +312,7 @@ impl DockWorkspace {
             .any(|child| Self::activate_session_in_state(child, session_id))
     }
 
-    fn find_active_panel_by_session(
+    pub(in crate::workspace) fn find_active_panel_by_session(
         item: &DockItem,
         session_id: &str,
         cx: &App,
@@ -245,22 +333,9 @@ impl DockWorkspace {
     }
 
     fn panel_state_contains_session(panel_state: &PanelState, session_id: &str) -> bool {
-        match &panel_state.info {
-            PanelInfo::Panel(value) => {
-                let dock_state = DockPanelState::from_value(value.clone());
-                if dock_state.agent_studio_klass.as_ref() == "ConversationPanel"
-                    && dock_state.session_id.as_deref() == Some(session_id)
-                {
-                    return true;
-                }
-            }
-            _ => {}
-        }
-
-        panel_state
-            .children
-            .iter()
-            .any(|child| Self::panel_state_contains_session(child, session_id))
+        let mut panels = Vec::new();
+        collect_open_panels(panel_state, &mut panels);
+        find_session_panel(&panels, session_id).is_some()
     }
 
     fn panel_matches_session(panel: &Arc<dyn PanelView>, session_id: &str, cx: &App) -> bool {
@@ -476,7 +551,7 @@ impl DockWorkspace {
     ) {
         let session_id = session_id.filter(|id| !id.is_empty());
         if let Some(session_id) = session_id {
-            if self.activate_existing_session_panel(&session_id, window, cx) {
+            if self.focus_panel_for_session(&session_id, window, cx) {
                 return;
             }
 
@@ -616,6 +691,95 @@ impl DockWorkspace {
         cx.notify();
     }
 
+    /// Handle CloseConversationPanels action - close conversation tabs in the
+    /// center, leaving every other panel open
+    pub(in crate::workspace) fn on_action_close_conversation_panels(
+        &mut self,
+        action: &CloseConversationPanels,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut tabs = Vec::new();
+        Self::collect_tab_items(self.dock_area.read(cx).items(), &mut tabs);
+        let panels: Vec<OpenPanel> = tabs
+            .iter()
+            .map(|(_, panel)| Self::open_panel_info(panel, cx))
+            .collect();
+
+        let agent_service = AppState::global(cx).agent_service().cloned();
+        let is_busy = |session_id: &str| {
+            agent_service
+                .as_ref()
+                .and_then(|service| service.get_session_by_id(session_id))
+                .is_some_and(|info| info.status.is_turn_in_progress())
+        };
+        let to_close = conversation_panels_to_close(&panels, action.idle_only, &is_busy);
+        log::info!("Closing {} conversation panel(s)", to_close.len());
+
+        let mut cancelled = Vec::new();
+        for ix in to_close {
+            let (tab_panel, panel) = &tabs[ix];
+            if action.cancel_sessions
+                && let Some(session_id) = panels[ix].session_id.as_deref()
+                && is_busy(session_id)
+            {
+                cancelled.push(session_id.to_string());
+            }
+            tab_panel.update(cx, |tab_panel, cx| {
+                tab_panel.remove_panel(panel.clone(), window, cx);
+            });
+        }
+
+        if cancelled.is_empty() {
+            return;
+        }
+        let Some(message_service) = AppState::global(cx).message_service().cloned() else {
+            log::warn!("MessageService not available, sessions were not cancelled");
+            return;
+        };
+        cx.spawn(async move |_this, _cx| {
+            for session_id in cancelled {
+                if let Err(e) = message_service.cancel_session(&session_id).await {
+                    log::error!("Failed to cancel session {}: {}", session_id, e);
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn collect_tab_items(item: &DockItem, tabs: &mut Vec<(Entity<TabPanel>, Arc<dyn PanelView>)>) {
+        match item {
+            DockItem::Tabs { view, items, .. } => {
+                tabs.extend(items.iter().map(|panel| (view.clone(), panel.clone())));
+            }
+            DockItem::Split { items, .. } => {
+                for item in items {
+                    Self::collect_tab_items(item, tabs);
+                }
+            }
+            DockItem::Tiles { .. } | DockItem::Panel { .. } => {}
+        }
+    }
+
+    fn open_panel_info(panel: &Arc<dyn PanelView>, cx: &App) -> OpenPanel {
+        let Ok(container) = panel.view().downcast::<DockPanelContainer>() else {
+            return OpenPanel {
+                klass: panel.panel_name(cx).into(),
+                session_id: None,
+            };
+        };
+        let container = container.read(cx);
+        let session_id = container
+            .agent_studio
+            .clone()
+            .and_then(|view| view.downcast::<ConversationPanel>().ok())
+            .and_then(|conversation| conversation.read(cx).session_id());
+        OpenPanel {
+            klass: container.agent_studio_klass.clone().unwrap_or_default(),
+            session_id,
+        }
+    }
+
     /// Handle ToggleDockToggleButton action - show/hide dock toggle buttons
     pub(in crate::workspace) fn on_action_toggle_dock_toggle_button(
         &mut self,
@@ -630,6 +794,183 @@ impl DockWorkspace {
         });
     }
 
+    /// Handle ExportLayout action - pick a file and write the current layout to it
+    pub(in crate::workspace) fn on_action_export_layout(
+        &mut self,
+        _: &ExportLayout,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, window| {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .set_title(t!("workspace.layout.export.dialog_title").to_string())
+                .add_filter("JSON", &["json"])
+                .set_file_name("layout.json")
+                .save_file()
+                .await
+            else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+
+            _ = this.update_in(window, |this, window, cx| {
+                struct ExportLayoutNote;
+                let note = match this.export_layout(&path, cx) {
+                    Ok(()) => {
+                        log::info!("Exported layout to {}", path.display());
+                        Notification::success(
+                            t!("workspace.layout.export.success", path = path.display())
+                                .to_string(),
+                        )
+                    }
+                    Err(e) => {
+                        log::error!("Failed to export layout: {:#}", e);
+                        Notification::error(format!("{:#}", e))
+                    }
+                };
+                window.push_notification(note.id::<ExportLayoutNote>(), cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Handle ImportLayout action - pick a previously exported layout and load it
+    pub(in crate::workspace) fn on_action_import_layout(
+        &mut self,
+        _: &ImportLayout,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, window| {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .set_title(t!("workspace.layout.import.dialog_title").to_string())
+                .add_filter("JSON", &["json"])
+                .pick_file()
+                .await
+            else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+
+            _ = this.update_in(window, |this, window, cx| {
+                if let Err(e) = this.import_layout(&path, window, cx) {
+                    log::error!("Failed to import layout: {:#}", e);
+                    struct ImportLayoutError;
+                    let note = Notification::error(format!("{:#}", e)).id::<ImportLayoutError>();
+                    window.push_notification(note, cx);
+                } else {
+                    log::info!("Imported layout from {}", path.display());
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Handle SaveLayoutPreset action - ask for a name and save the current layout under it
+    pub(in crate::workspace) fn on_action_save_layout_preset(
+        &mut self,
+        _: &SaveLayoutPreset,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let workspace = cx.entity().downgrade();
+        let name_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("workspace.layout.preset.name_placeholder").to_string())
+        });
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            dialog
+                .title(t!("workspace.layout.preset.dialog_title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("workspace.layout.preset.save").to_string())
+                        .cancel_text(t!("workspace.layout.preset.cancel").to_string()),
+                )
+                .on_ok({
+                    let workspace = workspace.clone();
+                    let name_input = name_input.clone();
+                    move |_, _window, cx| {
+                        let name = name_input.read(cx).text().to_string().trim().to_string();
+                        if name.is_empty() {
+                            log::warn!("Layout preset name cannot be empty");
+                            return false;
+                        }
+
+                        _ = workspace.update(cx, |this, cx| {
+                            match this.save_current_as_preset(name.clone(), cx) {
+                                Ok(()) => log::info!("Saved layout preset: {}", name),
+                                Err(e) => log::error!("Failed to save layout preset: {:#}", e),
+                            }
+                        });
+                        true
+                    }
+                })
+                .child(v_flex().w_full().p_4().child(Input::new(&name_input)))
+        });
+    }
+
+    /// Handle ApplyLayoutPreset action - switch the dock layout to a saved preset
+    pub(in crate::workspace) fn on_action_apply_layout_preset(
+        &mut self,
+        action: &ApplyLayoutPreset,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Err(e) = self.apply_preset(&action.0, window, cx) {
+            log::error!("Failed to apply layout preset: {:#}", e);
+            struct ApplyLayoutPresetError;
+            let note = Notification::error(format!("{:#}", e)).id::<ApplyLayoutPresetError>();
+            window.push_notification(note, cx);
+        }
+    }
+
+    /// Handle ReopenStartup action - show the startup wizard again
+    pub(in crate::workspace) fn on_action_reopen_startup(
+        &mut self,
+        _: &ReopenStartup,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.reset_startup(cx);
+    }
+
+    /// Handle ToggleCommandPalette action - list every registered command with
+    /// fuzzy search and dispatch the chosen one
+    pub(in crate::workspace) fn on_action_toggle_command_palette(
+        &mut self,
+        _: &ToggleCommandPalette,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if window.has_active_dialog(cx) {
+            window.close_dialog(cx);
+            return;
+        }
+
+        let on_confirm: PaletteConfirmHandler = Rc::new(|action, window, cx| {
+            window.close_dialog(cx);
+            // Dispatch once focus is back on the workspace so panel actions reach it
+            window.defer(cx, move |window, cx| window.dispatch_action(action, cx));
+        });
+        let list_state = cx.new(|cx| {
+            ListState::new(
+                CommandPaletteDelegate::new(palette_commands(), on_confirm),
+                window,
+                cx,
+            )
+            .searchable(true)
+        });
+        list_state.read(cx).focus_handle(cx).focus(window);
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            dialog
+                .title(t!("command_palette.title").to_string())
+                .child(List::new(&list_state).h(px(360.)))
+        });
+    }
+
     /// Handle Open action - open folder picker and print selected path
     pub(in crate::workspace) fn on_action_open(
         &mut self,
@@ -745,3 +1086,51 @@ impl DockWorkspace {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_panel(klass: &'static str, session_id: Option<&str>) -> OpenPanel {
+        OpenPanel {
+            klass: klass.into(),
+            session_id: session_id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn finds_conversation_panel_by_session_id() {
+        let panels = vec![
+            open_panel("WelcomePanel", None),
+            open_panel("TerminalPanel", Some("session-b")),
+            open_panel("ConversationPanel", Some("session-a")),
+            open_panel("ConversationPanel", Some("session-b")),
+        ];
+
+        assert_eq!(find_session_panel(&panels, "session-a"), Some(2));
+        // Only conversation panels count, whatever else carries the id
+        assert_eq!(find_session_panel(&panels, "session-b"), Some(3));
+        assert_eq!(find_session_panel(&panels, "session-c"), None);
+    }
+
+    #[test]
+    fn closes_only_conversation_panels_and_spares_busy_ones_when_idle_only() {
+        let panels = vec![
+            open_panel("ConversationPanel", Some("busy")),
+            open_panel("TerminalPanel", None),
+            open_panel("ConversationPanel", Some("idle")),
+            open_panel("SessionManagerPanel", Some("busy")),
+            open_panel("ConversationPanel", None),
+        ];
+        let is_busy = |session_id: &str| session_id == "busy";
+
+        assert_eq!(
+            conversation_panels_to_close(&panels, false, is_busy),
+            vec![0, 2, 4]
+        );
+        assert_eq!(
+            conversation_panels_to_close(&panels, true, is_busy),
+            vec![2, 4]
+        );
+    }
+}
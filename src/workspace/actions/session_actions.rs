@@ -5,12 +5,25 @@ use gpui_component::{
     dock::{DockItem, DockPlacement},
     notification::Notification,
 };
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use agentx_acp_ui::SLASH_COMMAND_META_KEY;
 
 use crate::{
-    AppState, ConversationPanel, CreateTaskFromWelcome, NewSessionConversationPanel,
+    AppSettings, AppState, ConversationPanel, CreateTaskFromWelcome, NewSessionConversationPanel,
     SendMessageToSession,
-    app::actions::{AddCodeSelection, CancelSession},
+    app::actions::{AddCodeSelection, CancelSession, RegenerateResponse},
+    core::{
+        config::{CommandConfig, TemplateVars, expand_slash_command},
+        services::{
+            AgentConfigService, AgentSessionInfo, AiService, MessageService, ReplyCollector,
+            SessionBranch, SessionTitler, WorkspaceService,
+        },
+    },
     panels::{DockPanel, dock_panel::DockPanelContainer},
 };
 
@@ -79,6 +92,15 @@ impl DockWorkspace {
             }
         };
 
+        // Opt-in: name the task after its first turn
+        let session_titler = if AppSettings::global(cx).auto_title_sessions {
+            AppState::global(cx).services.session_titler().ok().cloned()
+        } else {
+            None
+        };
+        let ai_service = AppState::global(cx).ai_service().cloned();
+        let event_hub = AppState::global(cx).event_hub().clone();
+
         let dock_area = self.dock_area.clone();
         let target_workspace_id = action.workspace_id.clone();
 
@@ -131,26 +153,50 @@ impl DockWorkspace {
                 workspace_cwd
             );
 
-            // Step 2: Get or reuse session
+            // Step 2: Create WorkspaceTask
+            let task = match workspace_service
+                .create_task(
+                    &workspace_id,
+                    task_input.clone(),
+                    agent_name.clone(),
+                    mode.clone(),
+                )
+                .await
+            {
+                Ok(task) => {
+                    log::info!(
+                        "Created workspace task: {} in workspace: {}",
+                        task.name,
+                        workspace_id
+                    );
+                    task
+                }
+                Err(e) => {
+                    log::error!("Failed to create workspace task: {}", e);
+                    _ = window.update(|window, cx| {
+                        struct WorkspaceTaskCreationError;
+                        let note = Notification::error(format!(
+                            "Failed to create task: {}",
+                            e
+                        ))
+                        .id::<WorkspaceTaskCreationError>();
+                        window.push_notification(note, cx);
+                    });
+                    return;
+                }
+            };
+
+            // Step 3: Get or reuse the task's session
             let session_id = if let Some(ws) = welcome_session {
                 log::info!(
                     "Reusing welcome session {} for agent {}",
                     ws.session_id,
                     ws.agent_name
                 );
+                agent_service.assign_task_session(&task.id, &ws.session_id);
                 ws.session_id
             } else {
-                let mcp_servers = if let Some(service) = agent_config_service {
-                    service
-                        .list_mcp_servers()
-                        .await
-                        .into_iter()
-                        .filter(|(_, config)| config.enabled)
-                        .map(|(name, config)| config.to_acp_mcp_server(name))
-                        .collect()
-                } else {
-                    Vec::new()
-                };
+                let mcp_servers = enabled_mcp_servers(agent_config_service.as_ref()).await;
 
                 log::info!(
                     "Creating new session for agent '{}' with cwd: {:?}",
@@ -159,14 +205,15 @@ impl DockWorkspace {
                 );
 
                 match agent_service
-                    .create_session_with_mcp_and_cwd(
+                    .create_session_for_task(
+                        &task.id,
                         &agent_name,
                         mcp_servers,
                         workspace_cwd.clone(),
                     )
                     .await
                 {
-                    Ok(session_id) => {
+                    Ok(AgentSessionInfo { session_id, .. }) => {
                         log::info!(
                             "Created new session {} for agent {}",
                             session_id,
@@ -205,6 +252,11 @@ impl DockWorkspace {
 
                         log::error!("{}", error_details);
 
+                        // The task never got a session to run in
+                        if let Err(e) = workspace_service.remove_task(&task.id).await {
+                            log::error!("Failed to remove task without session: {}", e);
+                        }
+
                         _ = window.update(|window, cx| {
                             struct TaskCreationError;
                             let note =
@@ -217,45 +269,21 @@ impl DockWorkspace {
                 }
             };
 
-            // Step 3: Create WorkspaceTask
-            let task = match workspace_service
-                .create_task(
-                    &workspace_id,
-                    task_input.clone(),
-                    agent_name.clone(),
-                    mode.clone(),
-                )
-                .await
-            {
-                Ok(task) => {
-                    log::info!(
-                        "Created workspace task: {} in workspace: {}",
-                        task.name,
-                        workspace_id
-                    );
-                    task
-                }
-                Err(e) => {
-                    log::error!("Failed to create workspace task: {}", e);
-                    _ = window.update(|window, cx| {
-                        struct WorkspaceTaskCreationError;
-                        let note = Notification::error(format!(
-                            "Failed to create task: {}",
-                            e
-                        ))
-                        .id::<WorkspaceTaskCreationError>();
-                        window.push_notification(note, cx);
-                    });
-                    return;
-                }
-            };
-
             if let Err(e) = workspace_service
                 .set_task_session(&task.id, session_id.clone())
                 .await
             {
                 log::error!("Failed to associate session with task: {}", e);
             }
+            // A model picked on the welcome panel is kept with the task
+            if let Some(model_id) = agent_service.session_model(&agent_name, &session_id) {
+                if let Err(e) = workspace_service
+                    .set_session_model(&session_id, &model_id)
+                    .await
+                {
+                    log::warn!("Failed to save model for task: {}", e);
+                }
+            }
 
             // Step 4: Clear welcome session and create ConversationPanel
             let session_id_for_send = session_id.clone();
@@ -294,15 +322,26 @@ impl DockWorkspace {
             let mut prompt_blocks: Vec<acp::ContentBlock> = Vec::new();
             // Add code selections as text context before the user message
             for selection in code_selections.iter() {
-                let code_context = format_code_selection_as_context(selection);
+                let code_context = selection.to_prompt_context();
                 prompt_blocks.push(code_context.into());
             }
-            prompt_blocks.push(task_input.into());
+            prompt_blocks.push(
+                message_block(
+                    task_input.clone(),
+                    &code_selections,
+                    Some(&workspace_cwd),
+                    agent_config_service.as_ref(),
+                )
+                .await,
+            );
             for (image_content, _filename) in images.iter() {
                 prompt_blocks.push(acp::ContentBlock::Image(image_content.clone()));
             }
             log::debug!("Built {} content blocks for prompt", prompt_blocks.len());
 
+            let reply = session_titler
+                .as_ref()
+                .map(|_| ReplyCollector::start(&event_hub, &session_id_for_send));
             match message_service
                 .send_message_to_session(&agent_name, &session_id_for_send, prompt_blocks)
                 .await
@@ -313,6 +352,18 @@ impl DockWorkspace {
                         session_id_for_send,
                         response
                     );
+
+                    // Step 6: Title the task now that its first turn is done
+                    if let (Some(titler), Some(reply)) = (session_titler, reply) {
+                        title_first_turn(
+                            &titler,
+                            &session_id_for_send,
+                            &task_input,
+                            reply,
+                            ai_service.as_deref(),
+                        )
+                        .await;
+                    }
                 }
                 Err(e) => {
                     log::error!("Failed to send message: {}", e);
@@ -365,19 +416,33 @@ impl DockWorkspace {
     pub(in crate::workspace) fn on_action_send_message_to_session(
         &mut self,
         action: &SendMessageToSession,
-        _window: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
         let session_id = action.session_id.clone();
         let message = action.message.clone();
         let images = action.images.clone();
         let code_selections = action.code_selections.clone();
+        let resources = action.resources.clone();
+        let edit_turn = action.edit_turn;
 
         log::info!("Sending message to session: {}", session_id);
 
-        cx.spawn(async move |_this, cx| {
-            let agent_service = cx.update(|cx| AppState::global(cx).agent_service().cloned());
-            let message_service = cx.update(|cx| AppState::global(cx).message_service().cloned());
+        let agent_config_service = AppState::global(cx).agent_config_service().cloned();
+        let workspace_service = AppState::global(cx).workspace_service().cloned();
+        // Opt-in: name the task after its first turn
+        let session_titler = if AppSettings::global(cx).auto_title_sessions {
+            AppState::global(cx).services.session_titler().ok().cloned()
+        } else {
+            None
+        };
+        let ai_service = AppState::global(cx).ai_service().cloned();
+        let event_hub = AppState::global(cx).event_hub().clone();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let agent_service = cx.update(|_, cx| AppState::global(cx).agent_service().cloned());
+            let message_service =
+                cx.update(|_, cx| AppState::global(cx).message_service().cloned());
 
             let (agent_service, message_service) = match (agent_service, message_service) {
                 (Ok(Some(agent_service)), Ok(Some(message_service))) => {
@@ -403,13 +468,23 @@ impl DockWorkspace {
             let mut prompt_blocks: Vec<acp::ContentBlock> = Vec::new();
             // Add code selections as text context before the user message
             for selection in code_selections.iter() {
-                let code_context = format_code_selection_as_context(selection);
+                let code_context = selection.to_prompt_context();
                 prompt_blocks.push(code_context.into());
             }
-            prompt_blocks.push(message.clone().into());
+            let cwd = session_cwd(workspace_service.as_ref(), &session_id).await;
+            prompt_blocks.push(
+                message_block(
+                    message.clone(),
+                    &code_selections,
+                    cwd.as_deref(),
+                    agent_config_service.as_ref(),
+                )
+                .await,
+            );
             for (image_content, _filename) in images.iter() {
                 prompt_blocks.push(acp::ContentBlock::Image(image_content.clone()));
             }
+            prompt_blocks.extend(resources);
 
             log::debug!(
                 "Sending prompt to agent {} for session {}",
@@ -417,21 +492,204 @@ impl DockWorkspace {
                 session_id
             );
 
-            match message_service
-                .send_message_to_session(&agent_name, &session_id, prompt_blocks)
+            // Only a session's first message names it, never an edit
+            let reply = match &session_titler {
+                Some(_) if edit_turn.is_none() => {
+                    let is_first_turn = message_service
+                        .load_history(&session_id)
+                        .await
+                        .is_ok_and(|history| history.is_empty());
+                    is_first_turn.then(|| ReplyCollector::start(&event_hub, &session_id))
+                }
+                _ => None,
+            };
+
+            // Plain messages wait in the session queue while the agent is busy
+            let result = match edit_turn {
+                Some(turn) => {
+                    let mcp_servers = enabled_mcp_servers(agent_config_service.as_ref()).await;
+                    let branch = message_service
+                        .branch_to_edit(
+                            &agent_name,
+                            &session_id,
+                            turn,
+                            prompt_blocks,
+                            mcp_servers,
+                            cwd.unwrap_or_else(|| std::env::current_dir().unwrap_or_default()),
+                        )
+                        .await;
+                    match branch {
+                        Ok(branch) => Self::continue_in_branch(
+                            &this,
+                            cx,
+                            workspace_service.as_ref(),
+                            &message_service,
+                            &session_id,
+                            branch,
+                        )
+                        .await
+                        .map(Some),
+                        Err(e) => Err(e),
+                    }
+                }
+                None => {
+                    message_service
+                        .send_or_queue(&agent_name, &session_id, prompt_blocks)
+                        .await
+                }
+            };
+
+            match result {
+                Ok(Some(_response)) => {
+                    log::info!("Prompt sent successfully to session: {}", session_id);
+
+                    if let (Some(titler), Some(reply)) = (session_titler, reply) {
+                        title_first_turn(
+                            &titler,
+                            &session_id,
+                            &message,
+                            reply,
+                            ai_service.as_deref(),
+                        )
+                        .await;
+                    }
+                }
+                Ok(None) => {
+                    log::info!("Prompt queued for busy session: {}", session_id);
+                }
+                Err(e) => {
+                    log::error!("Failed to send prompt to session {}: {}", session_id, e);
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Handle RegenerateResponse action - drop the last agent turn and resend
+    /// the user message that started it, in a new agent session
+    pub(in crate::workspace) fn on_action_regenerate_response(
+        &mut self,
+        action: &RegenerateResponse,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let session_id = action.session_id.clone();
+
+        log::info!("Regenerating last response for session: {}", session_id);
+
+        let agent_config_service = AppState::global(cx).agent_config_service().cloned();
+        let workspace_service = AppState::global(cx).workspace_service().cloned();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let agent_service = cx.update(|_, cx| AppState::global(cx).agent_service().cloned());
+            let message_service =
+                cx.update(|_, cx| AppState::global(cx).message_service().cloned());
+
+            let (agent_service, message_service) = match (agent_service, message_service) {
+                (Ok(Some(agent_service)), Ok(Some(message_service))) => {
+                    (agent_service, message_service)
+                }
+                _ => {
+                    log::error!("AgentService or MessageService not initialized");
+                    return;
+                }
+            };
+
+            let Some(agent_name) = agent_service.get_agent_for_session(&session_id) else {
+                log::error!(
+                    "Cannot regenerate response: no agent found for session {}",
+                    session_id
+                );
+                return;
+            };
+
+            let mcp_servers = enabled_mcp_servers(agent_config_service.as_ref()).await;
+            let cwd = session_cwd(workspace_service.as_ref(), &session_id)
+                .await
+                .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+            let result = match message_service
+                .branch_to_regenerate(&agent_name, &session_id, mcp_servers, cwd)
                 .await
             {
+                Ok(branch) => {
+                    Self::continue_in_branch(
+                        &this,
+                        cx,
+                        workspace_service.as_ref(),
+                        &message_service,
+                        &session_id,
+                        branch,
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
                 Ok(_response) => {
-                    log::info!("Prompt sent successfully to session: {}", session_id);
+                    log::info!("Regenerated response for session: {}", session_id);
                 }
                 Err(e) => {
-                    log::error!("Failed to send prompt to session {}: {}", session_id, e);
+                    log::error!(
+                        "Failed to regenerate response for session {}: {}",
+                        session_id,
+                        e
+                    );
                 }
             }
         })
         .detach();
     }
 
+    /// Point the task and the conversation panel of `session_id` at the new
+    /// session of `branch`, then send the prompt that continues it
+    async fn continue_in_branch(
+        this: &WeakEntity<Self>,
+        cx: &mut AsyncWindowContext,
+        workspace_service: Option<&Arc<WorkspaceService>>,
+        message_service: &MessageService,
+        session_id: &str,
+        branch: SessionBranch,
+    ) -> anyhow::Result<acp::PromptResponse> {
+        if let Some(service) = workspace_service {
+            if let Some(task) = service.get_task_by_session(session_id).await {
+                service
+                    .set_task_session(&task.id, branch.session_id.clone())
+                    .await?;
+            }
+        }
+
+        let branch_id = branch.session_id.clone();
+        _ = this.update_in(cx, |this, window, cx| {
+            this.replace_session_panel(session_id, branch_id, window, cx);
+        });
+
+        message_service.send_branch(branch).await
+    }
+
+    /// Show `new_session_id` in the panel showing `session_id`, if any
+    fn replace_session_panel(
+        &mut self,
+        session_id: &str,
+        new_session_id: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.focus_panel_for_session(session_id, window, cx) {
+            return;
+        }
+        let Some(panel) =
+            Self::find_active_panel_by_session(self.dock_area.read(cx).items(), session_id, cx)
+        else {
+            return;
+        };
+        if let Ok(container) = panel.view().downcast::<DockPanelContainer>() {
+            container.update(cx, |container, cx| {
+                container.replace_with_conversation_session(Some(new_session_id), window, cx);
+            });
+        }
+    }
+
     /// Handle CancelSession action - cancel an ongoing session operation
     pub(in crate::workspace) fn on_action_cancel_session(
         &mut self,
@@ -452,12 +710,13 @@ impl DockWorkspace {
                 session_id
             );
 
-            let agent_service = cx.update(|cx| AppState::global(cx).agent_service().cloned());
+            let message_service = cx.update(|cx| AppState::global(cx).message_service().cloned());
 
-            if let Ok(Some(agent_service)) = agent_service {
-                log::info!("DockWorkspace: Got AgentService");
+            if let Ok(Some(message_service)) = message_service {
+                log::info!("DockWorkspace: Got MessageService");
 
-                match agent_service.cancel_session_by_id(&session_id).await {
+                // Also drops any messages queued behind the cancelled turn
+                match message_service.cancel_session(&session_id).await {
                     Ok(()) => {
                         log::info!(
                             "DockWorkspace: Session {} cancelled successfully",
@@ -473,26 +732,144 @@ impl DockWorkspace {
                     }
                 }
             } else {
-                log::error!("DockWorkspace: AgentService not available");
+                log::error!("DockWorkspace: MessageService not available");
             }
         })
         .detach();
     }
 }
 
-/// Format a code selection as text context for the ACP prompt.
-///
-/// Produces a markdown-style code block with file path and line range metadata,
-/// suitable for inclusion as a `ContentBlock::Text` in the prompt.
-fn format_code_selection_as_context(selection: &AddCodeSelection) -> String {
-    let line_range = if selection.start_line == selection.end_line {
-        format!("Line {}", selection.start_line)
-    } else {
-        format!("Lines {}-{}", selection.start_line, selection.end_line)
+/// MCP servers enabled in the agent config, for a new session
+/// Title a session from its first message and the reply collected while the
+/// turn ran
+async fn title_first_turn(
+    titler: &SessionTitler,
+    session_id: &str,
+    first_message: &str,
+    reply: ReplyCollector,
+    ai_service: Option<&AiService>,
+) {
+    let titled = titler
+        .title_session(session_id, first_message, &reply.finish(), ai_service)
+        .await;
+    if let Err(e) = titled {
+        log::warn!("Failed to title session {}: {}", session_id, e);
+    }
+}
+
+async fn enabled_mcp_servers(
+    agent_config_service: Option<&Arc<AgentConfigService>>,
+) -> Vec<acp::McpServer> {
+    let Some(service) = agent_config_service else {
+        return Vec::new();
+    };
+    service
+        .list_mcp_servers()
+        .await
+        .into_iter()
+        .filter(|(_, config)| config.enabled)
+        .map(|(name, config)| config.to_acp_mcp_server(name))
+        .collect()
+}
+
+/// Directory of the workspace the session's task belongs to
+async fn session_cwd(
+    workspace_service: Option<&Arc<WorkspaceService>>,
+    session_id: &str,
+) -> Option<PathBuf> {
+    let service = workspace_service?;
+    let task = service.get_task_by_session(session_id).await?;
+    service
+        .get_workspace(&task.workspace_id)
+        .await
+        .map(|workspace| workspace.path)
+}
+
+/// Text block for a typed message. A message starting with a configured
+/// `/command` is sent as the command's expanded template; anything else is
+/// sent verbatim.
+async fn message_block(
+    message: String,
+    code_selections: &[AddCodeSelection],
+    cwd: Option<&Path>,
+    agent_config_service: Option<&Arc<AgentConfigService>>,
+) -> acp::ContentBlock {
+    let commands: HashMap<String, CommandConfig> = match agent_config_service {
+        Some(service) if message.trim_start().starts_with('/') => {
+            service.list_commands().await.into_iter().collect()
+        }
+        _ => HashMap::new(),
+    };
+    let selection = code_selections.first();
+    let vars = TemplateVars {
+        selection: selection.map(|s| s.content.clone()).unwrap_or_default(),
+        file: selection.map(|s| s.file_path.clone()).unwrap_or_default(),
+        cwd: cwd.map(|cwd| cwd.display().to_string()).unwrap_or_default(),
     };
+    expand_message(message, &commands, &vars)
+}
+
+/// Expand `message` if it names a configured command, keeping the typed text
+/// in `_meta` so the conversation shows what the user wrote
+fn expand_message(
+    message: String,
+    commands: &HashMap<String, CommandConfig>,
+    vars: &TemplateVars,
+) -> acp::ContentBlock {
+    match expand_slash_command(&message, commands, vars) {
+        Some(Ok(expanded)) => {
+            let mut text = acp::TextContent::new(expanded);
+            let mut meta = serde_json::Map::new();
+            meta.insert(SLASH_COMMAND_META_KEY.to_string(), message.into());
+            text.meta = Some(meta);
+            acp::ContentBlock::Text(text)
+        }
+        Some(Err(e)) => {
+            log::warn!(
+                "Sending '{}' verbatim, its command template is invalid: {}",
+                message,
+                e
+            );
+            message.into()
+        }
+        None => message.into(),
+    }
+}
 
-    format!(
-        "```\n// File: {} ({})\n{}\n```",
-        selection.file_path, line_range, selection.content
-    )
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentx_acp_ui::user_display_text;
+
+    #[test]
+    fn configured_slash_commands_expand_and_plain_messages_pass_through() {
+        let commands = HashMap::from([(
+            "review".to_string(),
+            CommandConfig {
+                description: "Review the code".to_string(),
+                template: "Review the code in {{cwd}}".to_string(),
+            },
+        )]);
+        let vars = TemplateVars {
+            cwd: "/work".to_string(),
+            ..TemplateVars::default()
+        };
+
+        let acp::ContentBlock::Text(command) = expand_message("/review".into(), &commands, &vars)
+        else {
+            panic!("expected a text block");
+        };
+        assert_eq!(command.text, "Review the code in /work");
+        assert_eq!(user_display_text(&command), "/review");
+
+        for message in ["Review the code", "/unknown args"] {
+            let acp::ContentBlock::Text(plain) = expand_message(message.into(), &commands, &vars)
+            else {
+                panic!("expected a text block");
+            };
+            assert_eq!(plain.text, message);
+            assert!(plain.meta.is_none());
+            assert_eq!(user_display_text(&plain), message);
+        }
+    }
 }
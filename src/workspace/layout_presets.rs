@@ -0,0 +1,122 @@
+use anyhow::Result;
+use gpui_component::dock::DockAreaState;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A dock layout saved under a user-chosen name, e.g. "Coding" or "Review"
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LayoutPreset {
+    pub name: String,
+    pub state: DockAreaState,
+}
+
+/// Named layout presets, persisted next to the docks layout file
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayoutPresets {
+    presets: Vec<LayoutPreset>,
+}
+
+impl LayoutPresets {
+    /// Load presets from `path`, treating a missing file as no presets
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LayoutPreset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    /// Preset names in the order they were first saved
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.presets.iter().map(|preset| preset.name.as_str())
+    }
+
+    /// Save `state` under `name`, replacing any preset with the same name
+    pub fn insert(&mut self, name: impl Into<String>, state: DockAreaState) {
+        let name = name.into();
+        match self.presets.iter_mut().find(|preset| preset.name == name) {
+            Some(preset) => preset.state = state,
+            None => self.presets.push(LayoutPreset { name, state }),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<LayoutPreset> {
+        let index = self.presets.iter().position(|preset| preset.name == name)?;
+        Some(self.presets.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui_component::dock::{PanelInfo, PanelState};
+
+    fn state(active_index: usize) -> DockAreaState {
+        DockAreaState {
+            version: Some(5),
+            center: PanelState {
+                panel_name: "TabPanel".into(),
+                children: vec![],
+                info: PanelInfo::Tabs { active_index },
+            },
+            left_dock: None,
+            right_dock: None,
+            bottom_dock: None,
+        }
+    }
+
+    #[test]
+    fn recalls_preset_by_name() {
+        let mut presets = LayoutPresets::default();
+        presets.insert("Coding", state(0));
+        presets.insert("Review", state(1));
+
+        assert_eq!(presets.get("Review").unwrap().state, state(1));
+        assert_eq!(presets.get("Coding").unwrap().state, state(0));
+        assert!(presets.get("Writing").is_none());
+
+        // Saving under an existing name replaces it in place
+        presets.insert("Coding", state(2));
+        assert_eq!(presets.get("Coding").unwrap().state, state(2));
+        assert_eq!(
+            presets.names().collect::<Vec<_>>(),
+            vec!["Coding", "Review"]
+        );
+
+        assert!(presets.remove("Coding").is_some());
+        assert_eq!(presets.names().collect::<Vec<_>>(), vec!["Review"]);
+    }
+
+    #[test]
+    fn presets_persist_to_disk() {
+        let path = std::env::temp_dir()
+            .join(format!("agentx_layout_presets_{}", std::process::id()))
+            .join("docks-presets.json");
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(
+            LayoutPresets::load(&path).unwrap(),
+            LayoutPresets::default()
+        );
+
+        let mut presets = LayoutPresets::default();
+        presets.insert("Review", state(1));
+        presets.save(&path).unwrap();
+        let loaded = LayoutPresets::load(&path).unwrap();
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+        assert_eq!(loaded, presets);
+    }
+}
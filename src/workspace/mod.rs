@@ -1,26 +1,41 @@
+use agentx_types::AgentRuntimeStatus;
 use anyhow::{Context as _, Result};
 use gpui::*;
-use gpui_component::Root;
 use gpui_component::dock::{
     DockArea, DockAreaState, DockEvent, DockItem, DockPlacement, PanelState,
 };
+use gpui_component::{
+    IconName, Root, Sizable as _, WindowExt as _,
+    button::{Button, ButtonVariants as _},
+    menu::DropdownMenu as _,
+    notification::Notification,
+};
+use rust_i18n::t;
 use smol::Timer;
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::BTreeSet,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 /// Panels that should be excluded from loading and saving
 const EXCLUDED_PANELS: &[&str] = &["CodeEditorPanel", "ToolCallDetailPanel"];
 
 use crate::{
-    AppSettings, AppTitleBar, CodeEditorPanel, ConversationPanel, SessionManagerPanel, TaskPanel,
-    TerminalPanel,
+    AppSettings, AppState, AppTitleBar, ApplyLayoutPreset, CloseConversationPanels,
+    CodeEditorPanel, ConversationPanel, ExportLayout, ImportLayout, PanelAction, SaveLayoutPreset,
+    SessionManagerPanel, TaskPanel, TerminalPanel, ToggleDockToggleButton,
+    core::event_bus::AgentConfigEvent,
     core::updater::{UpdateCheckResult, UpdateManager},
     panels::dock_panel::DockPanelContainer,
 };
 
-use self::startup::StartupState;
+use self::{layout_presets::LayoutPresets, startup::StartupState};
 
 // Action handlers module
 pub mod actions;
+pub mod layout_presets;
 mod startup;
 
 const MAIN_DOCK_AREA: DockAreaTab = DockAreaTab {
@@ -30,10 +45,13 @@ const MAIN_DOCK_AREA: DockAreaTab = DockAreaTab {
 
 pub struct DockWorkspace {
     title_bar: Entity<AppTitleBar>,
+    /// Scopes the persisted layout file to this window
+    window_index: usize,
     dock_area: Entity<DockArea>,
     last_layout_state: Option<DockAreaState>,
+    layout_presets: LayoutPresets,
     toggle_button_visible: bool,
-    _save_layout_task: Option<Task<()>>,
+    save_layout_task: Option<Task<()>>,
     startup_state: StartupState,
     startup_completed: bool,
     update_checked_on_startup: bool,
@@ -46,17 +64,18 @@ struct DockAreaTab {
 
 impl DockWorkspace {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let window_index = claim_window_index();
         let dock_area =
             cx.new(|cx| DockArea::new(MAIN_DOCK_AREA.id, Some(MAIN_DOCK_AREA.version), window, cx));
         let weak_dock_area = dock_area.downgrade();
 
-        match Self::load_layout(dock_area.clone(), window, cx) {
+        match Self::load_layout(window_index, dock_area.clone(), window, cx) {
             Ok(_) => {
                 println!("load layout success");
             }
             Err(err) => {
                 eprintln!("load layout error: {:?}", err);
-                Self::reset_default_layout(weak_dock_area, window, cx);
+                Self::reset_default_layout(window_index, weak_dock_area, window, cx);
             }
         };
 
@@ -70,108 +89,166 @@ impl DockWorkspace {
         )
         .detach();
 
-        cx.on_app_quit({
-            let dock_area = dock_area.clone();
-            move |_, cx| {
-                let state = dock_area.read(cx).dump(cx);
-                cx.background_executor().spawn(async move {
-                    // Save layout before quitting
-                    if let Err(e) = Self::save_state(&state) {
-                        log::warn!("Failed to save layout state: {}", e);
-                    }
-                })
-            }
+        Self::notify_agent_failures(window, cx);
+
+        cx.on_app_quit(|this, cx| {
+            // Save layout before quitting, without waiting for the debounce timer
+            this.flush_pending_layout(cx);
+            async {}
         })
         .detach();
 
         cx.on_release(|this, cx| {
-            this.flush_layout_state(cx);
+            this.flush_pending_layout(cx);
             crate::themes::save_state(cx);
+
+            // Windows share the global AppState, so only quit with the last one
+            if release_window_index(this.window_index) {
+                cx.quit();
+            }
         })
         .detach();
 
+        let layout_presets =
+            LayoutPresets::load(&crate::core::config_manager::get_docks_presets_path())
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to load layout presets: {}", e);
+                    LayoutPresets::default()
+                });
+
+        let workspace = cx.entity().downgrade();
         let title_bar = cx.new(|cx| {
-            AppTitleBar::new("Agent Studio", window, cx)
-            // .child({
-            //     move |_, cx| {
-            //         Button::new("add-panel")
-            //             .icon(IconName::LayoutDashboard)
-            //             .small()
-            //             .ghost()
-            //             .dropdown_menu({
-            //                 let invisible_panels = AppState::global(cx).invisible_panels.clone();
-
-            //                 move |menu, _, cx| {
-            //                     menu.menu(
-            //                         "Add Panel to Center",
-            //                         Box::new(PanelAction::add_conversation(DockPlacement::Center)),
-            //                     )
-            //                     .separator()
-            //                     .menu(
-            //                         "Add Panel to Left",
-            //                         Box::new(PanelAction::add_conversation(DockPlacement::Left)),
-            //                     )
-            //                     .menu(
-            //                         "Add Panel to Right",
-            //                         Box::new(PanelAction::add_conversation(DockPlacement::Right)),
-            //                     )
-            //                     .menu(
-            //                         "Add Panel to Bottom",
-            //                         Box::new(PanelAction::add_conversation(DockPlacement::Bottom)),
-            //                     )
-            //                     .separator()
-            //                     .menu(
-            //                         "Show / Hide Dock Toggle Button",
-            //                         Box::new(ToggleDockToggleButton),
-            //                     )
-            //                     .separator()
-            //                     .menu_with_check(
-            //                         "Sidebar",
-            //                         !invisible_panels
-            //                             .read(cx)
-            //                             .contains(&SharedString::from("Sidebar")),
-            //                         Box::new(TogglePanelVisible(SharedString::from("Sidebar"))),
-            //                     )
-            //                     .menu_with_check(
-            //                         "Dialog",
-            //                         !invisible_panels
-            //                             .read(cx)
-            //                             .contains(&SharedString::from("Dialog")),
-            //                         Box::new(TogglePanelVisible(SharedString::from("Dialog"))),
-            //                     )
-            //                     .menu_with_check(
-            //                         "Accordion",
-            //                         !invisible_panels
-            //                             .read(cx)
-            //                             .contains(&SharedString::from("Accordion")),
-            //                         Box::new(TogglePanelVisible(SharedString::from("Accordion"))),
-            //                     )
-            //                     .menu_with_check(
-            //                         "List",
-            //                         !invisible_panels
-            //                             .read(cx)
-            //                             .contains(&SharedString::from("List")),
-            //                         Box::new(TogglePanelVisible(SharedString::from("List"))),
-            //                     )
-            //                 }
-            //             })
-            //             .anchor(Corner::TopRight)
-            //     }
-            // })
+            AppTitleBar::new("Agent Studio", window, cx).child(move |_, _| {
+                let workspace = workspace.clone();
+                Button::new("add-panel")
+                    .icon(IconName::LayoutDashboard)
+                    .small()
+                    .ghost()
+                    .dropdown_menu(move |menu, _, cx| {
+                        let preset_names = workspace
+                            .upgrade()
+                            .map(|workspace| {
+                                workspace
+                                    .read(cx)
+                                    .layout_presets
+                                    .names()
+                                    .map(SharedString::from)
+                                    .collect::<Vec<_>>()
+                            })
+                            .unwrap_or_default();
+
+                        let menu = menu
+                            .menu(
+                                t!("title_bar.add_panel.center").to_string(),
+                                Box::new(PanelAction::add_conversation(DockPlacement::Center)),
+                            )
+                            .separator()
+                            .menu(
+                                t!("title_bar.add_panel.left").to_string(),
+                                Box::new(PanelAction::add_conversation(DockPlacement::Left)),
+                            )
+                            .menu(
+                                t!("title_bar.add_panel.right").to_string(),
+                                Box::new(PanelAction::add_conversation(DockPlacement::Right)),
+                            )
+                            .menu(
+                                t!("title_bar.add_panel.bottom").to_string(),
+                                Box::new(PanelAction::add_conversation(DockPlacement::Bottom)),
+                            )
+                            .separator()
+                            .menu(
+                                t!("title_bar.add_panel.toggle_dock_button").to_string(),
+                                Box::new(ToggleDockToggleButton),
+                            )
+                            .separator()
+                            .menu(
+                                t!("title_bar.add_panel.close_conversations").to_string(),
+                                Box::new(CloseConversationPanels {
+                                    idle_only: false,
+                                    cancel_sessions: false,
+                                }),
+                            )
+                            .menu(
+                                t!("title_bar.add_panel.close_idle_conversations").to_string(),
+                                Box::new(CloseConversationPanels {
+                                    idle_only: true,
+                                    cancel_sessions: false,
+                                }),
+                            )
+                            .separator()
+                            .menu(
+                                t!("title_bar.layout.export").to_string(),
+                                Box::new(ExportLayout),
+                            )
+                            .menu(
+                                t!("title_bar.layout.import").to_string(),
+                                Box::new(ImportLayout),
+                            )
+                            .separator()
+                            .label(t!("title_bar.layout.presets").to_string());
+
+                        preset_names
+                            .into_iter()
+                            .fold(menu, |menu, name| {
+                                menu.menu(name.clone(), Box::new(ApplyLayoutPreset(name)))
+                            })
+                            .menu(
+                                t!("title_bar.layout.save_preset").to_string(),
+                                Box::new(SaveLayoutPreset),
+                            )
+                    })
+                    .anchor(Corner::TopRight)
+            })
         });
 
         Self {
+            window_index,
             dock_area,
             title_bar,
             last_layout_state: None,
+            layout_presets,
             toggle_button_visible: true,
-            _save_layout_task: None,
+            save_layout_task: None,
             startup_state: StartupState::new(),
-            startup_completed: crate::themes::startup_completed(),
+            startup_completed: AppSettings::global(cx).setup_completed,
             update_checked_on_startup: false,
         }
     }
 
+    /// Show a notification whenever an agent fails, e.g. when its command is
+    /// not on PATH at startup
+    fn notify_agent_failures(window: &mut Window, cx: &mut Context<Self>) {
+        let event_hub = AppState::global(cx).event_hub().clone();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        event_hub.subscribe_agent_config_updates(move |event| {
+            if let AgentConfigEvent::AgentStatusChanged {
+                name,
+                status: AgentRuntimeStatus::Failed { reason },
+            } = event
+            {
+                let _ = tx.send((name.clone(), reason.clone()));
+            }
+        });
+
+        cx.spawn_in(window, async move |_this, window| {
+            while let Some((name, reason)) = rx.recv().await {
+                let updated = window.update(|window, cx| {
+                    window.push_notification(
+                        Notification::error(
+                            t!("workspace.agent_failed", name = name, reason = reason).to_string(),
+                        ),
+                        cx,
+                    );
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
     /// Check for updates on startup if auto-check is enabled
     fn maybe_check_updates_on_startup(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.update_checked_on_startup {
@@ -184,7 +261,8 @@ impl DockWorkspace {
         }
 
         log::info!("Auto-checking for updates on startup...");
-        let update_manager = UpdateManager::default();
+        let update_manager =
+            UpdateManager::default().with_channel(AppSettings::global(cx).update_channel());
 
         cx.spawn_in(window, async move |_this, _window| {
             match update_manager.check_for_updates().await {
@@ -209,58 +287,122 @@ impl DockWorkspace {
         cx: &mut Context<Self>,
     ) {
         let dock_area = dock_area.clone();
-        self._save_layout_task = Some(cx.spawn_in(window, async move |agent_studio, window| {
+        self.save_layout_task = Some(cx.spawn_in(window, async move |agent_studio, window| {
             Timer::after(Duration::from_secs(10)).await;
 
             _ = agent_studio.update_in(window, move |this, _, cx| {
-                let dock_area = dock_area.read(cx);
-                let state = dock_area.dump(cx);
-
-                let last_layout_state = this.last_layout_state.clone();
-                if Some(&state) == last_layout_state.as_ref() {
-                    return;
-                }
-
-                if let Err(e) = Self::save_state(&state) {
-                    log::warn!("Failed to save layout state: {}", e);
-                }
-                this.last_layout_state = Some(state);
+                this.save_layout_task = None;
+                let state = dock_area.read(cx).dump(cx);
+                this.write_layout_if_changed(&state);
             });
         }));
     }
 
-    fn flush_layout_state(&mut self, cx: &mut App) {
+    /// Cancel any debounced save and write the current layout immediately
+    fn flush_pending_layout(&mut self, cx: &mut App) {
+        self.save_layout_task.take();
         let state = self.dock_area.read(cx).dump(cx);
-        if Some(&state) == self.last_layout_state.as_ref() {
-            return;
-        }
-        if let Err(e) = Self::save_state(&state) {
+        self.write_layout_if_changed(&state);
+    }
+
+    fn write_layout_if_changed(&mut self, state: &DockAreaState) {
+        let window_index = self.window_index;
+        if let Err(e) = record_layout_state(&mut self.last_layout_state, state, |state| {
+            Self::save_state(window_index, state)
+        }) {
             log::warn!("Failed to save layout state: {}", e);
         }
-        self.last_layout_state = Some(state);
     }
 
-    fn save_state(state: &DockAreaState) -> Result<()> {
+    fn save_state(window_index: usize, state: &DockAreaState) -> Result<()> {
         println!("Save Docks layout...");
+        let state_file = crate::core::config_manager::get_window_docks_layout_path(window_index);
+        Self::write_state(&state_file, state)
+    }
+
+    fn write_state(path: &Path, state: &DockAreaState) -> Result<()> {
         let json = serde_json::to_string_pretty(state)?;
-        let state_file = crate::core::config_manager::get_docks_layout_path();
-        if let Some(parent) = state_file.parent() {
+        if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        std::fs::write(state_file, json)?;
+        std::fs::write(path, json)?;
         Ok(())
     }
 
+    fn read_state(path: &Path) -> Result<DockAreaState> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str::<DockAreaState>(&json)?)
+    }
+
+    /// Export the current dock layout to `path`, to share or back up
+    pub fn export_layout(&self, path: &Path, cx: &App) -> Result<()> {
+        let state = self.dock_area.read(cx).dump(cx);
+        Self::write_state(path, &state)
+            .with_context(|| format!("export layout to {}", path.display()))
+    }
+
+    /// Replace the current dock layout with one written by [`Self::export_layout`]
+    pub fn import_layout(
+        &self,
+        path: &Path,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let state = Self::read_state(path)
+            .with_context(|| format!("import layout from {}", path.display()))?;
+        Self::apply_layout(state, self.dock_area.clone(), window, cx)
+    }
+
+    /// Save the current dock layout as a named preset, replacing any preset
+    /// with the same name
+    pub fn save_current_as_preset(
+        &mut self,
+        name: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let state = self.dock_area.read(cx).dump(cx);
+        self.layout_presets.insert(name, state);
+        self.layout_presets
+            .save(&crate::core::config_manager::get_docks_presets_path())
+            .context("save layout presets")?;
+        cx.notify();
+        Ok(())
+    }
+
+    /// Switch to a preset saved by [`Self::save_current_as_preset`]
+    pub fn apply_preset(
+        &mut self,
+        name: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let state = self
+            .layout_presets
+            .get(name)
+            .with_context(|| format!("layout preset not found: {}", name))?
+            .state
+            .clone();
+        Self::apply_layout(state, self.dock_area.clone(), window, cx)
+    }
+
     fn load_layout(
+        window_index: usize,
         dock_area: Entity<DockArea>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Result<()> {
         println!("Load Docks layout...");
-        let state_file = crate::core::config_manager::get_docks_layout_path();
-        let json = std::fs::read_to_string(state_file)?;
-        let state = serde_json::from_str::<DockAreaState>(&json)?;
+        let state_file = crate::core::config_manager::get_window_docks_layout_path(window_index);
+        let state = Self::read_state(&state_file)?;
+        Self::apply_layout(state, dock_area, window, cx)
+    }
 
+    fn apply_layout(
+        state: DockAreaState,
+        dock_area: Entity<DockArea>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
         // Check if the saved layout version is different from the current version
         // Notify the user and ask if they want to reset the layout to default.
         if state.version != Some(MAIN_DOCK_AREA.version) {
@@ -275,8 +417,8 @@ impl DockWorkspace {
             let weak_dock_area = dock_area.downgrade();
             cx.spawn_in(window, async move |this, window| {
                 if answer.await == Ok(0) {
-                    _ = this.update_in(window, |_, window, cx| {
-                        Self::reset_default_layout(weak_dock_area, window, cx);
+                    _ = this.update_in(window, |this, window, cx| {
+                        Self::reset_default_layout(this.window_index, weak_dock_area, window, cx);
                     });
                 }
             })
@@ -300,7 +442,12 @@ impl DockWorkspace {
         })
     }
 
-    fn reset_default_layout(dock_area: WeakEntity<DockArea>, window: &mut Window, cx: &mut App) {
+    fn reset_default_layout(
+        window_index: usize,
+        dock_area: WeakEntity<DockArea>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
         let dock_item = Self::init_default_layout(&dock_area, window, cx);
 
         let left_panels = DockItem::split_with_sizes(
@@ -358,7 +505,7 @@ impl DockWorkspace {
             view.set_bottom_dock(bottom_panels, Some(px(200.)), true, window, cx);
             view.set_right_dock(right_panels, Some(px(480.)), true, window, cx);
 
-            if let Err(e) = Self::save_state(&view.dump(cx)) {
+            if let Err(e) = Self::save_state(window_index, &view.dump(cx)) {
                 log::warn!("Failed to save layout state: {}", e);
             }
         });
@@ -428,11 +575,6 @@ impl DockWorkspace {
                 .update(cx, |_, window, cx| {
                     window.activate_window();
                     window.set_window_title("Agent Studio");
-                    cx.on_release(|_, cx| {
-                        // exit app
-                        cx.quit();
-                    })
-                    .detach();
                 })
                 .expect("failed to update window");
 
@@ -446,7 +588,8 @@ impl Render for DockWorkspace {
         self.ensure_startup_initialized(window, cx);
 
         if self.startup_state.is_complete() && !self.startup_completed {
-            crate::themes::set_startup_completed(true);
+            AppSettings::global_mut(cx).setup_completed = true;
+            crate::themes::save_state(cx);
             self.startup_completed = true;
         }
 
@@ -470,12 +613,20 @@ impl Render for DockWorkspace {
             .on_action(cx.listener(Self::on_action_panel_action))
             .on_action(cx.listener(Self::on_action_toggle_panel_visible))
             .on_action(cx.listener(Self::on_action_toggle_dock_toggle_button))
+            .on_action(cx.listener(Self::on_action_close_conversation_panels))
+            .on_action(cx.listener(Self::on_action_toggle_command_palette))
+            .on_action(cx.listener(Self::on_action_reopen_startup))
+            .on_action(cx.listener(Self::on_action_export_layout))
+            .on_action(cx.listener(Self::on_action_import_layout))
+            .on_action(cx.listener(Self::on_action_save_layout_preset))
+            .on_action(cx.listener(Self::on_action_apply_layout_preset))
             .on_action(cx.listener(Self::on_action_open_setting_panel))
             .on_action(cx.listener(Self::on_action_open_session_manager))
             .on_action(cx.listener(Self::on_action_new_session_conversation_panel))
             .on_action(cx.listener(Self::on_action_create_task_from_welcome))
             .on_action(cx.listener(Self::on_action_send_message_to_session))
             .on_action(cx.listener(Self::on_action_cancel_session))
+            .on_action(cx.listener(Self::on_action_regenerate_response))
             .on_action(cx.listener(Self::on_action_open))
             .relative()
             .size_full()
@@ -489,6 +640,47 @@ impl Render for DockWorkspace {
     }
 }
 
+/// Indices of the open workspace windows, used to give each window its own
+/// layout file. Window ids aren't stable across launches, so the lowest free
+/// index is reused and the first window keeps the original layout file.
+static OPEN_WINDOWS: Mutex<BTreeSet<usize>> = Mutex::new(BTreeSet::new());
+
+fn claim_window_index() -> usize {
+    let mut open = OPEN_WINDOWS.lock().unwrap_or_else(|e| e.into_inner());
+    let index = lowest_free_index(&open);
+    open.insert(index);
+    index
+}
+
+/// Returns `true` when the last workspace window was released
+fn release_window_index(index: usize) -> bool {
+    let mut open = OPEN_WINDOWS.lock().unwrap_or_else(|e| e.into_inner());
+    open.remove(&index);
+    open.is_empty()
+}
+
+fn lowest_free_index(used: &BTreeSet<usize>) -> usize {
+    (0..)
+        .find(|index| !used.contains(index))
+        .unwrap_or_default()
+}
+
+/// Write `state` with `write` unless it is identical to the last written
+/// layout, so the same layout isn't written twice. `state` is remembered only
+/// once the write succeeds, so a failed save is retried next time.
+fn record_layout_state(
+    last: &mut Option<DockAreaState>,
+    state: &DockAreaState,
+    write: impl FnOnce(&DockAreaState) -> Result<()>,
+) -> Result<()> {
+    if last.as_ref() == Some(state) {
+        return Ok(());
+    }
+    write(state)?;
+    *last = Some(state.clone());
+    Ok(())
+}
+
 pub fn open_new(
     cx: &mut App,
     init: impl FnOnce(&mut Root, &mut Window, &mut Context<Root>) + 'static + Send,
@@ -502,3 +694,85 @@ pub fn open_new(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui_component::dock::PanelInfo;
+
+    fn state(active_index: usize) -> DockAreaState {
+        DockAreaState {
+            version: Some(MAIN_DOCK_AREA.version),
+            center: PanelState {
+                panel_name: "TabPanel".into(),
+                children: vec![],
+                info: PanelInfo::Tabs { active_index },
+            },
+            left_dock: None,
+            right_dock: None,
+            bottom_dock: None,
+        }
+    }
+
+    #[test]
+    fn exported_layout_round_trips_with_version() {
+        let state = state(0);
+
+        let path = std::env::temp_dir()
+            .join(format!("agentx_layout_{}", std::process::id()))
+            .join("layout.json");
+        DockWorkspace::write_state(&path, &state).unwrap();
+        let imported = DockWorkspace::read_state(&path).unwrap();
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+        assert_eq!(imported.version, Some(MAIN_DOCK_AREA.version));
+        assert_eq!(imported, state);
+    }
+
+    #[test]
+    fn unchanged_layout_is_not_written_twice() {
+        let mut last = None;
+        let mut writes = 0;
+        let mut write = |_: &DockAreaState| {
+            writes += 1;
+            Ok(())
+        };
+        // A debounced save writes the change...
+        record_layout_state(&mut last, &state(0), &mut write).unwrap();
+        // ...so flushing the same layout on quit is a no-op
+        record_layout_state(&mut last, &state(0), &mut write).unwrap();
+
+        record_layout_state(&mut last, &state(1), &mut write).unwrap();
+        record_layout_state(&mut last, &state(1), &mut write).unwrap();
+        assert_eq!(last, Some(state(1)));
+        assert_eq!(writes, 2);
+    }
+
+    #[test]
+    fn failed_layout_write_is_retried() {
+        let mut last = Some(state(0));
+        let failed =
+            record_layout_state(&mut last, &state(1), |_| Err(anyhow::anyhow!("disk full")));
+        assert!(failed.is_err());
+        assert_eq!(last, Some(state(0)));
+
+        let mut written = false;
+        record_layout_state(&mut last, &state(1), |_| {
+            written = true;
+            Ok(())
+        })
+        .unwrap();
+        assert!(written);
+        assert_eq!(last, Some(state(1)));
+    }
+
+    #[test]
+    fn windows_reuse_the_lowest_free_index() {
+        let mut used = BTreeSet::new();
+        assert_eq!(lowest_free_index(&used), 0);
+        used.extend([0, 1, 3]);
+        assert_eq!(lowest_free_index(&used), 2);
+        used.remove(&0);
+        assert_eq!(lowest_free_index(&used), 0);
+    }
+}
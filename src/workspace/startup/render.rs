@@ -19,9 +19,10 @@ use crate::{
     app::actions::{SelectLocale, SwitchTheme, SwitchThemeMode},
     assets::get_agent_icon,
     core::nodejs::NodeJsDetectionMode,
+    utils::clipboard,
 };
 
-use super::state::NodeJsStatus;
+use super::state::{InstallAction, NodeJsStatus};
 use crate::workspace::DockWorkspace;
 
 impl DockWorkspace {
@@ -297,6 +298,63 @@ impl DockWorkspace {
         content.into_any_element()
     }
 
+    fn render_install_actions(actions: Vec<InstallAction>, cx: &Context<Self>) -> AnyElement {
+        let theme = cx.theme();
+
+        v_flex()
+            .gap_2()
+            .text_size(px(13.))
+            .text_color(theme.muted_foreground)
+            .children(actions.into_iter().map(|action| {
+                match action {
+                    InstallAction::CopyCommand { manager, command } => v_flex()
+                        .gap_2()
+                        .child(t!("startup.nodejs.install.command", manager = manager).to_string())
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .px_2()
+                                        .py_1()
+                                        .rounded(theme.radius)
+                                        .bg(theme.muted)
+                                        .font_family("Monaco, 'Courier New', monospace")
+                                        .text_color(theme.foreground)
+                                        .child(command.clone()),
+                                )
+                                .child(
+                                    Button::new("startup-nodejs-copy-install")
+                                        .icon(IconName::Copy)
+                                        .label(t!("startup.nodejs.install.copy").to_string())
+                                        .ghost()
+                                        .xsmall()
+                                        .on_click(move |_, _, cx| {
+                                            clipboard::copy_text(command.clone(), cx);
+                                        }),
+                                ),
+                        )
+                        .into_any_element(),
+                    InstallAction::OpenUrl(url) => h_flex()
+                        .gap_1()
+                        .items_center()
+                        .child(t!("startup.nodejs.install.download").to_string())
+                        .child(
+                            Button::new("startup-nodejs-download")
+                                .icon(IconName::Globe)
+                                .label(url.clone())
+                                .link()
+                                .xsmall()
+                                .on_click(move |_, _, cx| cx.open_url(&url)),
+                        )
+                        .into_any_element(),
+                }
+            }))
+            .into_any_element()
+    }
+
     fn render_nodejs_step(&mut self, cx: &mut Context<Self>) -> AnyElement {
         let theme = cx.theme();
 
@@ -343,7 +401,11 @@ impl DockWorkspace {
                         ),
                 );
             }
-            NodeJsStatus::Available { version, path } => {
+            NodeJsStatus::Available {
+                version,
+                path,
+                is_wsl,
+            } => {
                 let detail = match (version, path) {
                     (Some(version), Some(path)) => t!(
                         "startup.nodejs.detail.version_path",
@@ -382,11 +444,20 @@ impl DockWorkspace {
                                 .text_size(px(13.))
                                 .text_color(theme.muted_foreground)
                                 .child(detail),
-                        ),
+                        )
+                        .when(*is_wsl, |this| {
+                            this.child(
+                                div()
+                                    .text_size(px(13.))
+                                    .text_color(theme.warning)
+                                    .child(format!("⚠ {}", t!("startup.nodejs.wsl_warning"))),
+                            )
+                        }),
                 );
             }
-            NodeJsStatus::Unavailable { message, hint } => {
+            status @ NodeJsStatus::Unavailable { message, .. } => {
                 self.startup_state.nodejs_show_custom_input = true;
+                let install_actions = status.install_actions();
 
                 content = content.child(
                     v_flex()
@@ -403,13 +474,8 @@ impl DockWorkspace {
                                 .font_weight(FontWeight::MEDIUM)
                                 .child(format!("⚠ {}", message)),
                         )
-                        .when_some(hint.as_ref(), |this, hint| {
-                            this.child(
-                                div()
-                                    .text_size(px(13.))
-                                    .text_color(theme.muted_foreground)
-                                    .child(hint.clone()),
-                            )
+                        .when(!install_actions.is_empty(), |this| {
+                            this.child(Self::render_install_actions(install_actions, cx))
                         }),
                 );
             }
@@ -512,6 +578,7 @@ impl DockWorkspace {
                     .ghost()
                     .on_click(cx.listener(|this, _ev, _, cx| {
                         this.startup_state.nodejs_skipped = true;
+                        this.remember_skipped_step(|skipped| skipped.nodejs = true, cx);
                         this.startup_state.advance_step_if_needed();
                         cx.notify();
                     })),
@@ -744,6 +811,7 @@ impl DockWorkspace {
                             .outline()
                             .on_click(cx.listener(|this, _ev, _, cx| {
                                 this.startup_state.agent_applied = true;
+                                this.remember_skipped_step(|skipped| skipped.agents = true, cx);
                                 this.startup_state.advance_step_if_needed();
                                 cx.notify();
                             })),
@@ -889,6 +957,7 @@ impl DockWorkspace {
                     .outline()
                     .on_click(cx.listener(|this, _ev, _, cx| {
                         this.startup_state.proxy_applied = true;
+                        this.remember_skipped_step(|skipped| skipped.proxy = true, cx);
                         this.startup_state.advance_step_if_needed();
                         cx.notify();
                     })),
@@ -3,7 +3,11 @@ use std::path::PathBuf;
 
 use gpui::*;
 
-use crate::core::config::{AgentProcessConfig, Config};
+use crate::core::{
+    config::{AgentProcessConfig, Config},
+    nodejs::InstallHint,
+};
+use crate::panels::SkippedSetupSteps;
 
 #[derive(Clone, Debug)]
 pub(in crate::workspace) struct AgentChoice {
@@ -18,13 +22,50 @@ pub(in crate::workspace) enum NodeJsStatus {
     Available {
         version: Option<String>,
         path: Option<PathBuf>,
+        /// Found only inside WSL, so not usable by agents started from Windows
+        is_wsl: bool,
     },
     Unavailable {
         message: String,
-        hint: Option<String>,
+        hint: Option<InstallHint>,
     },
 }
 
+impl NodeJsStatus {
+    /// Ways to install Node.js offered while it is unavailable: copying the
+    /// package manager command when one is known, then the download page
+    pub(in crate::workspace) fn install_actions(&self) -> Vec<InstallAction> {
+        let NodeJsStatus::Unavailable {
+            hint: Some(hint), ..
+        } = self
+        else {
+            return Vec::new();
+        };
+
+        let mut actions = Vec::new();
+        if !hint.command.is_empty() {
+            actions.push(InstallAction::CopyCommand {
+                manager: hint.package_manager.name(),
+                command: hint.command.clone(),
+            });
+        }
+        actions.push(InstallAction::OpenUrl(hint.url.clone()));
+        actions
+    }
+}
+
+/// An install step the Node.js setup page renders as a button
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(in crate::workspace) enum InstallAction {
+    /// Copy the install command to the clipboard
+    CopyCommand {
+        manager: &'static str,
+        command: String,
+    },
+    /// Open the manual download page
+    OpenUrl(String),
+}
+
 #[derive(Debug)]
 pub struct StartupState {
     pub(in crate::workspace) initialized: bool,
@@ -104,6 +145,19 @@ impl StartupState {
         }
     }
 
+    /// Start the wizard over. Settings applied by a previous run are read back
+    /// as the steps initialize again.
+    pub(in crate::workspace) fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Carry over steps skipped in an earlier run so they aren't asked again
+    pub(in crate::workspace) fn restore_skipped_steps(&mut self, skipped: SkippedSetupSteps) {
+        self.nodejs_skipped |= skipped.nodejs;
+        self.agent_applied |= skipped.agents;
+        self.proxy_applied |= skipped.proxy;
+    }
+
     pub(in crate::workspace) fn nodejs_ready(&self) -> bool {
         self.nodejs_skipped || matches!(self.nodejs_status, NodeJsStatus::Available { .. })
     }
@@ -191,3 +245,87 @@ impl StartupState {
         (agent_choices, default_agent_configs, None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_starts_an_uncompleted_wizard() {
+        let mut state = StartupState::new();
+        state.initialized = true;
+        state.intro_completed = true;
+        state.nodejs_skipped = true;
+        state.agent_applied = true;
+        state.proxy_applied = true;
+        state.workspace_selected = true;
+        state.workspace_path = Some(PathBuf::from("/tmp/project"));
+        state.advance_step_if_needed();
+        assert!(state.is_complete());
+
+        state.reset();
+
+        assert!(!state.is_complete());
+        assert!(!state.initialized);
+        assert!(!state.intro_completed);
+        assert_eq!(state.step, 0);
+        assert!(matches!(state.nodejs_status, NodeJsStatus::Idle));
+        assert!(!state.proxy_applied);
+        assert!(!state.workspace_selected);
+        assert!(state.workspace_path.is_none());
+    }
+
+    #[test]
+    fn restored_skips_satisfy_their_steps() {
+        let mut state = StartupState::new();
+        state.intro_completed = true;
+        state.restore_skipped_steps(SkippedSetupSteps {
+            nodejs: true,
+            agents: false,
+            proxy: true,
+        });
+
+        assert!(state.nodejs_ready());
+        assert!(state.proxy_ready());
+
+        state.restore_skipped_steps(SkippedSetupSteps {
+            agents: true,
+            ..SkippedSetupSteps::default()
+        });
+        state.workspace_selected = true;
+        state.advance_step_if_needed();
+
+        assert!(state.is_complete());
+        assert_eq!(state.step, 4);
+    }
+
+    #[test]
+    fn unavailable_nodejs_offers_copying_the_install_command() {
+        use crate::core::nodejs::{NODEJS_DOWNLOAD_URL, PackageManager};
+
+        let unavailable = |hint| NodeJsStatus::Unavailable {
+            message: "Node.js not found".to_string(),
+            hint,
+        };
+
+        let hint = InstallHint::new(PackageManager::Homebrew);
+        assert_eq!(
+            unavailable(Some(hint.clone())).install_actions(),
+            [
+                InstallAction::CopyCommand {
+                    manager: "Homebrew",
+                    command: hint.command.clone(),
+                },
+                InstallAction::OpenUrl(NODEJS_DOWNLOAD_URL.to_string()),
+            ]
+        );
+
+        // Without a known package manager only the download page is offered
+        assert_eq!(
+            unavailable(Some(InstallHint::new(PackageManager::Unknown))).install_actions(),
+            [InstallAction::OpenUrl(NODEJS_DOWNLOAD_URL.to_string())]
+        );
+        assert!(unavailable(None).install_actions().is_empty());
+        assert!(NodeJsStatus::Checking.install_actions().is_empty());
+    }
+}
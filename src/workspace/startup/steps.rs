@@ -7,6 +7,7 @@ use std::path::PathBuf;
 use crate::{
     AppSettings, AppState,
     core::nodejs::{NodeJsChecker, NodeJsDetectionMode},
+    panels::SkippedSetupSteps,
     utils,
 };
 
@@ -14,6 +15,28 @@ use super::state::NodeJsStatus;
 use crate::workspace::DockWorkspace;
 
 impl DockWorkspace {
+    /// Show the startup wizard again so Node.js, agents, proxy and workspace
+    /// can be reconfigured
+    pub fn reset_startup(&mut self, cx: &mut Context<Self>) {
+        self.startup_state.reset();
+        self.startup_completed = false;
+        let settings = AppSettings::global_mut(cx);
+        settings.setup_completed = false;
+        settings.skipped_setup_steps = SkippedSetupSteps::default();
+        crate::themes::save_state(cx);
+        cx.notify();
+    }
+
+    /// Remember a skipped step so later launches don't ask for it again
+    pub(in crate::workspace) fn remember_skipped_step(
+        &mut self,
+        update: impl FnOnce(&mut SkippedSetupSteps),
+        cx: &mut Context<Self>,
+    ) {
+        update(&mut AppSettings::global_mut(cx).skipped_setup_steps);
+        crate::themes::save_state(cx);
+    }
+
     pub(in crate::workspace) fn ensure_startup_initialized(
         &mut self,
         window: &mut Window,
@@ -21,6 +44,8 @@ impl DockWorkspace {
     ) {
         if !self.startup_state.initialized {
             self.startup_state.initialized = true;
+            self.startup_state
+                .restore_skipped_steps(AppSettings::global(cx).skipped_setup_steps);
         }
 
         if self.startup_state.intro_completed {
@@ -71,6 +96,21 @@ impl DockWorkspace {
             InputState::new(window, cx).placeholder("socks5://127.0.0.1:1080".to_string())
         });
 
+        // Start from the saved proxy, e.g. when the wizard is reopened
+        if let Some(service) = AppState::global(cx).agent_config_service() {
+            let proxy = service.proxy_config();
+            self.startup_state.proxy_enabled = proxy.enabled;
+            for (input, value) in [
+                (&http_input, proxy.http_proxy_url),
+                (&https_input, proxy.https_proxy_url),
+                (&all_input, proxy.all_proxy_url),
+            ] {
+                if !value.is_empty() {
+                    input.update(cx, |input, cx| input.set_value(value, window, cx));
+                }
+            }
+        }
+
         self.startup_state.proxy_http_input = Some(http_input);
         self.startup_state.proxy_https_input = Some(https_input);
         self.startup_state.proxy_all_input = Some(all_input);
@@ -109,7 +149,14 @@ impl DockWorkspace {
                 match result {
                     Ok(result) => {
                         if result.available {
-                            if let Some(ref path) = result.path {
+                            // A node inside WSL can't be launched by that path
+                            // from Windows, so it isn't saved for agents to use
+                            if result.is_wsl {
+                                log::warn!(
+                                    "Node.js found only inside WSL; not saving its path: {:?}",
+                                    result.path
+                                );
+                            } else if let Some(ref path) = result.path {
                                 let path_str = path.display().to_string();
                                 AppSettings::global_mut(cx).nodejs_path = path_str.into();
                                 crate::themes::save_state(cx);
@@ -122,6 +169,7 @@ impl DockWorkspace {
                             this.startup_state.nodejs_status = NodeJsStatus::Available {
                                 version: result.version,
                                 path: result.path,
+                                is_wsl: result.is_wsl,
                             };
                         } else {
                             this.startup_state.nodejs_status = NodeJsStatus::Unavailable {
@@ -140,6 +188,15 @@ impl DockWorkspace {
                     }
                 }
 
+                // A check started after an earlier skip keeps the step skipped
+                if !matches!(
+                    this.startup_state.nodejs_status,
+                    NodeJsStatus::Available { .. }
+                ) {
+                    this.startup_state.nodejs_skipped =
+                        AppSettings::global(cx).skipped_setup_steps.nodejs;
+                }
+
                 this.startup_state.advance_step_if_needed();
                 cx.notify();
             });
@@ -202,7 +259,12 @@ impl DockWorkspace {
 
                 match result {
                     Ok(result) if result.available => {
-                        if let Some(ref path) = result.path {
+                        if result.is_wsl {
+                            log::warn!(
+                                "Custom Node.js path resolved inside WSL; not saving it: {:?}",
+                                result.path
+                            );
+                        } else if let Some(ref path) = result.path {
                             let path_str = path.display().to_string();
                             AppSettings::global_mut(cx).nodejs_path = path_str.into();
                             crate::themes::save_state(cx);
@@ -212,6 +274,7 @@ impl DockWorkspace {
                         this.startup_state.nodejs_status = NodeJsStatus::Available {
                             version: result.version,
                             path: result.path,
+                            is_wsl: result.is_wsl,
                         };
                         this.startup_state.nodejs_custom_path_error = None;
                     }
@@ -518,23 +581,38 @@ impl DockWorkspace {
             .map(|input: &Entity<gpui_component::input::InputState>| input.read(cx).value())
             .unwrap_or_default();
 
+        let mut proxy_config = crate::core::config::ProxyConfig {
+            enabled,
+            http_proxy_url: http_proxy_url.trim().to_string(),
+            https_proxy_url: https_proxy_url.trim().to_string(),
+            all_proxy_url: all_proxy_url.trim().to_string(),
+            proxy_type: String::new(),
+            host: String::new(),
+            port: 0,
+            username: String::new(),
+            password: String::new(),
+        };
+
+        // A malformed URL would silently break every agent's networking
+        if let Err(err) = proxy_config.validate() {
+            self.startup_state.proxy_apply_error = Some(
+                t!(
+                    "startup.proxy.error.invalid_url",
+                    field = err.field,
+                    error = err.kind.to_string()
+                )
+                .to_string(),
+            );
+            cx.notify();
+            return;
+        }
+        proxy_config.sync_from_urls();
+
         self.startup_state.proxy_apply_in_progress = true;
         self.startup_state.proxy_apply_error = None;
         cx.notify();
 
         cx.spawn_in(window, async move |this, window| {
-            let proxy_config = crate::core::config::ProxyConfig {
-                enabled,
-                http_proxy_url: http_proxy_url.to_string(),
-                https_proxy_url: https_proxy_url.to_string(),
-                all_proxy_url: all_proxy_url.to_string(),
-                proxy_type: String::new(),
-                host: String::new(),
-                port: 0,
-                username: String::new(),
-                password: String::new(),
-            };
-
             let result = agent_config_service.update_proxy_config(proxy_config).await;
 
             _ = this.update_in(window, |this, _, cx| {